@@ -0,0 +1,278 @@
+//! Bash Debug Adapter (bash-debug-adapter + bashdb)
+//!
+//! Shell scripts are debugged with bash-debug-adapter, the bashdb-based DAP
+//! implementation behind the "Bash Debug" VS Code extension. The adapter is a
+//! Node.js script spoken to over STDIO, like debugpy.
+//!
+//! The adapter does not fill in defaults for its launch configuration (VS Code
+//! does that from the extension manifest), so every `path*` setting is sent
+//! explicitly. It also insists on `terminalKind: "debugConsole"` when there is
+//! no VS Code terminal, which makes the script's output arrive as DAP 'output'
+//! events.
+
+use super::health::{AdapterHealth, AdapterHealthCheck, HealthCheck};
+use super::logging::DebugAdapterLogger;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use tracing::error;
+
+/// Environment variable pointing at bash-debug-adapter's `out/bashDebug.js`
+pub const ADAPTER_PATH_ENV: &str = "BASH_DEBUG_ADAPTER_PATH";
+
+/// Bash bash-debug-adapter configuration
+pub struct BashAdapter;
+
+impl BashAdapter {
+    /// The adapter runs on Node.js
+    pub fn command() -> String {
+        "node".to_string()
+    }
+
+    pub fn args() -> Vec<String> {
+        vec![Self::adapter_script().to_string_lossy().to_string()]
+    }
+
+    /// Locate bash-debug-adapter's entry script
+    ///
+    /// Checks, in order:
+    /// 1. $BASH_DEBUG_ADAPTER_PATH
+    /// 2. /usr/local/lib/bash-debug/out/bashDebug.js (Docker container)
+    /// 3. The newest rogalmic.bash-debug VS Code extension in ~/.vscode/extensions
+    pub fn adapter_script() -> PathBuf {
+        if let Some(path) = std::env::var_os(ADAPTER_PATH_ENV) {
+            return PathBuf::from(path);
+        }
+
+        let container = PathBuf::from("/usr/local/lib/bash-debug/out/bashDebug.js");
+        if container.exists() {
+            return container;
+        }
+
+        std::env::var_os("HOME")
+            .and_then(|home| Self::find_extension(&PathBuf::from(home).join(".vscode/extensions")))
+            .map(|extension| extension.join("out").join("bashDebug.js"))
+            .unwrap_or(container)
+    }
+
+    fn find_extension(extensions_dir: &Path) -> Option<PathBuf> {
+        let mut candidates: Vec<PathBuf> = std::fs::read_dir(extensions_dir)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("rogalmic.bash-debug-"))
+            })
+            .collect();
+        candidates.sort();
+        candidates.pop()
+    }
+
+    /// bashdb executable and its library directory
+    ///
+    /// A bashdb in PATH is preferred; otherwise the copy bundled with the
+    /// adapter (`bashdb_dir` next to `out/`) is used.
+    pub fn bashdb_paths(adapter_script: &Path) -> (String, String) {
+        let in_path = std::env::var_os("PATH").and_then(|path| {
+            std::env::split_paths(&path)
+                .map(|dir| dir.join("bashdb"))
+                .find(|candidate| candidate.is_file())
+        });
+        if let Some(bashdb) = in_path {
+            return (
+                bashdb.to_string_lossy().to_string(),
+                "/usr/share/bashdb".to_string(),
+            );
+        }
+
+        let bundled = adapter_script
+            .parent()
+            .and_then(|out| out.parent())
+            .map(|root| root.join("bashdb_dir"))
+            .unwrap_or_else(|| PathBuf::from("bashdb_dir"));
+        (
+            bundled.join("bashdb").to_string_lossy().to_string(),
+            bundled.to_string_lossy().to_string(),
+        )
+    }
+
+    pub fn adapter_id() -> &'static str {
+        "bashdb"
+    }
+
+    pub fn launch_args_with_options(
+        program: &str,
+        args: &[String],
+        cwd: Option<&str>,
+        stop_on_entry: bool,
+    ) -> Value {
+        let (path_bashdb, path_bashdb_lib) = Self::bashdb_paths(&Self::adapter_script());
+
+        // bashdb needs a working directory; default to the script's directory
+        let cwd = cwd.map(str::to_string).unwrap_or_else(|| {
+            Path::new(program)
+                .parent()
+                .map(|dir| dir.to_string_lossy().to_string())
+                .unwrap_or_else(|| ".".to_string())
+        });
+
+        json!({
+            "type": "bashdb",
+            "request": "launch",
+            "name": "Bash debug",
+            "program": program,
+            "args": args,
+            "cwd": cwd,
+            "stopOnEntry": stop_on_entry,
+            "pathBash": "bash",
+            "pathBashdb": path_bashdb,
+            "pathBashdbLib": path_bashdb_lib,
+            "pathCat": "cat",
+            "pathMkfifo": "mkfifo",
+            "pathPkill": "pkill",
+            // Required outside VS Code: program output is sent as 'output' events
+            "terminalKind": "debugConsole",
+            "showDebugOutput": false,
+            "trace": false,
+        })
+    }
+}
+
+impl AdapterHealthCheck for BashAdapter {
+    fn health_check(&self) -> AdapterHealth {
+        let script = Self::adapter_script();
+        let (bashdb, _) = Self::bashdb_paths(&script);
+        AdapterHealth::new(
+            "bash",
+            Self::adapter_id(),
+            vec![
+                HealthCheck::file("bash-debug-adapter", &script.to_string_lossy()),
+                HealthCheck::command("node", &Self::command(), &["--version"]),
+                HealthCheck::file("bashdb", &bashdb),
+            ],
+            &format!(
+                "Install the rogalmic.bash-debug VS Code extension or set {} to its out/bashDebug.js; \
+                 install Node.js and bashdb",
+                ADAPTER_PATH_ENV
+            ),
+        )
+    }
+}
+
+// ============================================================================
+// DebugAdapterLogger Trait Implementation
+// ============================================================================
+
+impl DebugAdapterLogger for BashAdapter {
+    fn language_name(&self) -> &str {
+        "Bash"
+    }
+
+    fn language_emoji(&self) -> &str {
+        "🐚"
+    }
+
+    fn transport_type(&self) -> &str {
+        "STDIO"
+    }
+
+    fn adapter_id(&self) -> &str {
+        "bashdb"
+    }
+
+    fn command_line(&self) -> String {
+        format!("node {}", Self::adapter_script().display())
+    }
+
+    fn requires_workaround(&self) -> bool {
+        false
+    }
+
+    fn log_spawn_error(&self, error: &dyn Error) {
+        error!("❌ [BASH] Failed to spawn bash-debug-adapter: {}", error);
+        error!("   Command: {}", self.command_line());
+        error!("   ");
+        error!("   Possible causes:");
+        error!("   1. Node.js not installed → node --version");
+        error!("   2. bash-debug-adapter not found");
+        error!(
+            "      → Set {} to the adapter's out/bashDebug.js",
+            ADAPTER_PATH_ENV
+        );
+        error!("      → Or install the rogalmic.bash-debug VS Code extension");
+    }
+
+    fn log_connection_error(&self, error: &dyn Error) {
+        error!("❌ [BASH] Adapter connection failed: {}", error);
+        error!("   Transport: STDIO");
+        error!("   ");
+        error!("   Possible causes:");
+        error!("   1. Adapter process crashed on startup");
+        error!("   2. STDIO pipes broken or closed unexpectedly");
+    }
+
+    fn log_init_error(&self, error: &dyn Error) {
+        error!("❌ [BASH] DAP initialization failed: {}", error);
+        error!("   The adapter started but couldn't complete DAP handshake");
+        error!("   ");
+        error!("   Possible causes:");
+        error!("   1. bashdb not installed or pathBashdbLib wrong");
+        error!("   2. Bash older than 4.0 (bashdb requirement)");
+        error!("   3. mkfifo or pkill missing");
+        error!("   ");
+        error!("   Verify bashdb works:");
+        error!("   $ bashdb --version");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_launch_args_include_paths_and_debug_console() {
+        let args = vec!["15".to_string()];
+        let launch = BashAdapter::launch_args_with_options("/work/fizzbuzz.sh", &args, None, false);
+
+        assert_eq!(launch["type"], "bashdb");
+        assert_eq!(launch["program"], "/work/fizzbuzz.sh");
+        assert_eq!(launch["args"], json!(["15"]));
+        assert_eq!(launch["terminalKind"], "debugConsole");
+        assert_eq!(launch["pathBash"], "bash");
+        assert!(launch["pathBashdb"].as_str().unwrap().ends_with("bashdb"));
+        assert!(launch["pathBashdbLib"].is_string());
+        // Defaults to the script's directory
+        assert_eq!(launch["cwd"], "/work");
+
+        let launch =
+            BashAdapter::launch_args_with_options("/work/fizzbuzz.sh", &[], Some("/tmp"), true);
+        assert_eq!(launch["cwd"], "/tmp");
+        assert_eq!(launch["stopOnEntry"], true);
+    }
+
+    #[test]
+    fn test_find_newest_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "rogalmic.bash-debug-0.3.8",
+            "rogalmic.bash-debug-0.3.9",
+            "ms-python.python-2024.1.0",
+        ] {
+            std::fs::create_dir(dir.path().join(name)).unwrap();
+        }
+
+        let found = BashAdapter::find_extension(dir.path()).unwrap();
+        assert!(found.ends_with("rogalmic.bash-debug-0.3.9"));
+    }
+
+    #[test]
+    fn test_debug_adapter_logger_trait() {
+        let adapter = BashAdapter;
+        assert_eq!(adapter.language_name(), "Bash");
+        assert_eq!(adapter.transport_type(), "STDIO");
+        assert_eq!(adapter.adapter_id(), "bashdb");
+        assert!(!adapter.requires_workaround());
+    }
+}
@@ -0,0 +1,362 @@
+//! C/C++ Debug Adapter (CodeLLDB)
+//!
+//! # Overview
+//!
+//! C and C++ programs are debugged with the same CodeLLDB adapter as Rust:
+//! only the compilation step differs. Single source files are compiled with
+//! gcc or clang, and the resulting binary is launched through CodeLLDB.
+//!
+//! # Architecture
+//!
+//! ```text
+//! User provides: /workspace/fizzbuzz.c
+//!      ↓ Compile with gcc/clang -g -O0
+//! Binary created: /workspace/target/debug/fizzbuzz
+//!      ↓ Spawn CodeLLDB (RustAdapter::spawn, TCP)
+//! Debug session: CodeLLDB ← TCP → MCP Server
+//! ```
+
+use super::diagnostics;
+use super::health::{AdapterHealth, AdapterHealthCheck, HealthCheck};
+use super::logging::DebugAdapterLogger;
+use super::rust::{CompileOutcome, RustAdapter};
+use super::security;
+use crate::{Error, Result};
+use std::path::PathBuf;
+use std::time::Instant;
+use tokio::process::Command;
+use tracing::{error, info};
+
+/// Source language handled by [`CppAdapter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CLanguage {
+    C,
+    Cpp,
+}
+
+impl CLanguage {
+    /// Map a session language ("c", "cpp") to a C language
+    pub fn from_language(language: &str) -> Option<Self> {
+        match language {
+            "c" => Some(Self::C),
+            "cpp" => Some(Self::Cpp),
+            _ => None,
+        }
+    }
+
+    /// Compilers to try, in order of preference
+    fn compilers(self) -> &'static [&'static str] {
+        match self {
+            Self::C => &["gcc", "clang", "cc"],
+            Self::Cpp => &["g++", "clang++", "c++"],
+        }
+    }
+
+    /// Accepted source file extensions
+    fn extensions(self) -> &'static [&'static str] {
+        match self {
+            Self::C => &["c"],
+            Self::Cpp => &["cpp", "cc", "cxx", "c++"],
+        }
+    }
+}
+
+/// C/C++ adapter configuration (compilation + CodeLLDB)
+pub struct CppAdapter {
+    pub language: CLanguage,
+}
+
+impl CppAdapter {
+    pub fn new(language: CLanguage) -> Self {
+        Self { language }
+    }
+
+    /// First available compiler for `language` (gcc before clang)
+    pub fn compiler(language: CLanguage) -> Result<String> {
+        let path_dirs: Vec<PathBuf> = std::env::var_os("PATH")
+            .map(|p| std::env::split_paths(&p).collect())
+            .unwrap_or_default();
+
+        language
+            .compilers()
+            .iter()
+            .find(|compiler| path_dirs.iter().any(|dir| dir.join(compiler).is_file()))
+            .map(|compiler| compiler.to_string())
+            .ok_or_else(|| {
+                Error::Compilation(format!(
+                    "No compiler found (tried {})",
+                    language.compilers().join(", ")
+                ))
+            })
+    }
+
+    /// Compile a single C/C++ source file with debug symbols
+    ///
+    /// The binary is written to `<source_dir>/target/debug/<name>`, like
+    /// single-file Rust programs. Compiler errors are returned as
+    /// [`Error::CompilationFailed`] with the parsed diagnostics, or as
+    /// [`Error::Compilation`] with the compiler's stderr when none could be
+    /// parsed (e.g. linker errors).
+    pub async fn compile(source_path: &str, language: CLanguage) -> Result<String> {
+        Self::compile_with_outcome(source_path, language)
+            .await
+            .map(|outcome| outcome.binary)
+    }
+
+    /// Compile a single C/C++ source file, keeping the compiler's warnings
+    pub async fn compile_with_outcome(
+        source_path: &str,
+        language: CLanguage,
+    ) -> Result<CompileOutcome> {
+        let started = Instant::now();
+        // Validate and sanitize the source path (prevents path traversal)
+        let source = security::validate_source_path(source_path, None)?;
+
+        let extension = source.extension().and_then(|s| s.to_str()).unwrap_or("");
+        if !language.extensions().contains(&extension) {
+            return Err(Error::Compilation(format!(
+                "Invalid file extension. Expected one of {:?}, got: '{}'",
+                language.extensions(),
+                source.display()
+            )));
+        }
+
+        let binary_name = source
+            .file_stem()
+            .ok_or_else(|| Error::Compilation("Invalid source filename".to_string()))?
+            .to_str()
+            .ok_or_else(|| Error::Compilation("Non-UTF8 filename".to_string()))?;
+
+        let source_dir = source
+            .parent()
+            .ok_or_else(|| Error::Compilation("Cannot determine source directory".to_string()))?;
+        let output_dir = source_dir.join("target").join("debug");
+
+        tokio::fs::create_dir_all(&output_dir)
+            .await
+            .map_err(|e| Error::Compilation(format!("Failed to create output directory: {}", e)))?;
+
+        let binary_path =
+            output_dir.join(format!("{}{}", binary_name, std::env::consts::EXE_SUFFIX));
+        let compiler = Self::compiler(language)?;
+
+        info!("🔨 [C/C++] Compiling with {}: {}", compiler, source_path);
+        info!("🔨 [C/C++] Output: {}", binary_path.display());
+
+        let output = Command::new(&compiler)
+            .arg("-g")
+            .arg("-O0")
+            .arg("-o")
+            .arg(&binary_path)
+            .arg(&source)
+            .output()
+            .await
+            .map_err(|e| Error::Compilation(format!("Failed to execute {}: {}", compiler, e)))?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let diagnostics = diagnostics::parse_gcc_messages(&stderr);
+        if !output.status.success() {
+            return Err(diagnostics::compilation_error(diagnostics, &stderr));
+        }
+
+        let binary_path_str = binary_path
+            .to_str()
+            .ok_or_else(|| Error::Compilation("Non-UTF8 binary path".to_string()))?
+            .to_string();
+
+        info!("✅ [C/C++] Compilation successful: {}", binary_path_str);
+
+        Ok(CompileOutcome {
+            binary: binary_path_str,
+            fresh: false,
+            duration: started.elapsed(),
+            diagnostics,
+        })
+    }
+
+    /// Adapter ID for CodeLLDB (shared with Rust)
+    pub fn adapter_id() -> &'static str {
+        RustAdapter::adapter_id()
+    }
+
+    pub fn log_compilation_error(error: &dyn std::error::Error) {
+        error!("❌ [C/C++] Compilation failed: {}", error);
+        error!("   ");
+        error!("   Common compilation errors:");
+        error!("   1. Syntax errors in source code");
+        error!("   2. gcc/clang not installed or not in PATH");
+        error!("   3. Multi-file programs (only single source files are supported)");
+        error!("   ");
+        error!("   Troubleshooting:");
+        error!("   $ gcc -g -O0 <source_file>");
+        error!("   This should show detailed compilation errors");
+    }
+}
+
+impl AdapterHealthCheck for CppAdapter {
+    fn health_check(&self) -> AdapterHealth {
+        let compiler = match Self::compiler(self.language) {
+            Ok(compiler) => HealthCheck::command("compiler", &compiler, &["--version"]),
+            Err(e) => HealthCheck {
+                name: "compiler".to_string(),
+                command: self.language.compilers().join(" | "),
+                ok: false,
+                detail: Some(e.to_string()),
+            },
+        };
+        let (language, hint) = match self.language {
+            CLanguage::C => ("c", "Install CodeLLDB and gcc or clang"),
+            CLanguage::Cpp => ("cpp", "Install CodeLLDB and g++ or clang++"),
+        };
+        AdapterHealth::new(
+            language,
+            Self::adapter_id(),
+            vec![
+                HealthCheck::command("codelldb", &RustAdapter::command(), &["--version"]),
+                compiler,
+            ],
+            hint,
+        )
+    }
+}
+
+// ============================================================================
+// DebugAdapterLogger Trait Implementation
+// ============================================================================
+
+impl DebugAdapterLogger for CppAdapter {
+    fn language_name(&self) -> &str {
+        match self.language {
+            CLanguage::C => "C",
+            CLanguage::Cpp => "C++",
+        }
+    }
+
+    fn language_emoji(&self) -> &str {
+        "⚙️"
+    }
+
+    fn transport_type(&self) -> &str {
+        "TCP Socket"
+    }
+
+    fn adapter_id(&self) -> &str {
+        "codelldb"
+    }
+
+    fn command_line(&self) -> String {
+        format!("{} --port <PORT>", RustAdapter::command())
+    }
+
+    fn requires_workaround(&self) -> bool {
+        false // CodeLLDB supports stopOnEntry natively
+    }
+
+    fn workaround_reason(&self) -> Option<&str> {
+        None
+    }
+
+    fn log_spawn_error(&self, error: &dyn std::error::Error) {
+        RustAdapter.log_spawn_error(error);
+    }
+
+    fn log_connection_error(&self, error: &dyn std::error::Error) {
+        RustAdapter.log_connection_error(error);
+    }
+
+    fn log_init_error(&self, error: &dyn std::error::Error) {
+        error!("❌ [C/C++] DAP initialization failed: {}", error);
+        error!("   CodeLLDB started but couldn't complete DAP handshake");
+        error!("   ");
+        error!("   Possible causes:");
+        error!("   1. Binary was not compiled with debug symbols (-g)");
+        error!("   2. Binary architecture mismatch (e.g., x86_64 vs ARM64)");
+        error!("   3. Incompatible CodeLLDB version");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn copy_fixture(name: &str) -> (tempfile::TempDir, String) {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join(name);
+        let source = dir.path().join(name);
+        std::fs::copy(fixture, &source).unwrap();
+        let source = source.to_str().unwrap().to_string();
+        (dir, source)
+    }
+
+    #[test]
+    fn test_language_mapping() {
+        assert_eq!(CLanguage::from_language("c"), Some(CLanguage::C));
+        assert_eq!(CLanguage::from_language("cpp"), Some(CLanguage::Cpp));
+        assert_eq!(CLanguage::from_language("rust"), None);
+        assert_eq!(CppAdapter::adapter_id(), "codelldb");
+        assert_eq!(CppAdapter::new(CLanguage::Cpp).language_name(), "C++");
+    }
+
+    #[tokio::test]
+    async fn test_compile_c_fixture_to_target_debug() {
+        if CppAdapter::compiler(CLanguage::C).is_err() {
+            println!("⚠️  Skipping test: no C compiler installed");
+            return;
+        }
+        let (dir, source) = copy_fixture("fizzbuzz.c");
+
+        let binary = CppAdapter::compile(&source, CLanguage::C).await.unwrap();
+
+        let expected = dir
+            .path()
+            .canonicalize()
+            .unwrap()
+            .join("target/debug/fizzbuzz");
+        assert_eq!(PathBuf::from(&binary), expected);
+        assert!(expected.is_file());
+    }
+
+    #[tokio::test]
+    async fn test_compile_error_returns_diagnostics() {
+        if CppAdapter::compiler(CLanguage::C).is_err() {
+            println!("⚠️  Skipping test: no C compiler installed");
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("broken.c");
+        std::fs::write(&source, "int main(void) { return undefined_name; }\n").unwrap();
+
+        let result = CppAdapter::compile(source.to_str().unwrap(), CLanguage::C).await;
+
+        match result {
+            Err(Error::CompilationFailed {
+                message,
+                diagnostics,
+            }) => {
+                assert!(message.starts_with("Compilation failed:"));
+                assert!(
+                    message.contains("undefined_name"),
+                    "error missing: {}",
+                    message
+                );
+                let first = &diagnostics[0];
+                assert_eq!(first.level, "error");
+                assert!(first.file.as_deref().unwrap().ends_with("broken.c"));
+                assert_eq!(first.line, Some(1));
+            }
+            other => panic!("Expected CompilationFailed error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compile_rejects_wrong_extension() {
+        let (_dir, source) = copy_fixture("fizzbuzz.c");
+
+        let result = CppAdapter::compile(&source, CLanguage::Cpp).await;
+
+        assert!(matches!(result, Err(Error::Compilation(_))));
+    }
+}
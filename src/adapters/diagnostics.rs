@@ -0,0 +1,332 @@
+//! Compiler diagnostics as structured data
+//!
+//! Compiled languages (Rust, C, C++) report errors and warnings as a list of
+//! [`CompilerDiagnostic`] instead of raw compiler output, so MCP clients can
+//! jump straight to the offending `file:line`.
+//!
+//! Three formats are understood:
+//! - cargo's `--message-format=json` `compiler-message` records
+//! - rustc's `--error-format=json` diagnostics (single-file builds)
+//! - gcc/clang text output (`file:line:column: error: message`)
+
+use crate::Error;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+/// A single compiler error or warning
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CompilerDiagnostic {
+    /// "error", "warning", "note", ...
+    pub level: String,
+    pub message: String,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    /// Compiler error code, e.g. "E0308"
+    pub code: Option<String>,
+}
+
+impl CompilerDiagnostic {
+    pub fn is_error(&self) -> bool {
+        self.level == "error"
+    }
+
+    /// `file:line:column: message`, or just the message without a location
+    pub fn summary(&self) -> String {
+        let mut location = String::new();
+        if let Some(file) = &self.file {
+            location.push_str(file);
+            if let Some(line) = self.line {
+                location.push_str(&format!(":{}", line));
+                if let Some(column) = self.column {
+                    location.push_str(&format!(":{}", column));
+                }
+            }
+            location.push_str(": ");
+        }
+        match &self.code {
+            Some(code) => format!("{}{} [{}]", location, self.message, code),
+            None => format!("{}{}", location, self.message),
+        }
+    }
+}
+
+/// Parse one rustc JSON diagnostic
+///
+/// Paths are made absolute against `base_dir` when the file exists there
+/// (cargo reports paths relative to the workspace root). Returns `None` for
+/// the trailing "aborting due to ..." summary, which carries no location.
+pub fn parse_rustc_diagnostic(
+    diagnostic: &Value,
+    base_dir: Option<&Path>,
+) -> Option<CompilerDiagnostic> {
+    let level = diagnostic["level"].as_str()?;
+    let message = diagnostic["message"].as_str()?;
+    if message.starts_with("aborting due to") || level == "failure-note" {
+        return None;
+    }
+
+    let spans = diagnostic["spans"].as_array();
+    let span = spans.and_then(|spans| {
+        spans
+            .iter()
+            .find(|span| span["is_primary"].as_bool().unwrap_or(false))
+            .or_else(|| spans.first())
+    });
+
+    let file = span
+        .and_then(|span| span["file_name"].as_str())
+        .map(|file| resolve_path(file, base_dir));
+
+    Some(CompilerDiagnostic {
+        level: level.to_string(),
+        message: message.to_string(),
+        file,
+        line: span
+            .and_then(|span| span["line_start"].as_u64())
+            .map(|l| l as u32),
+        column: span
+            .and_then(|span| span["column_start"].as_u64())
+            .map(|c| c as u32),
+        code: diagnostic["code"]["code"].as_str().map(str::to_string),
+    })
+}
+
+/// Diagnostics from cargo's `--message-format=json` output
+pub fn parse_cargo_messages(json_output: &str, cargo_root: &Path) -> Vec<CompilerDiagnostic> {
+    json_output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|record| record["reason"] == "compiler-message")
+        .filter_map(|record| parse_rustc_diagnostic(&record["message"], Some(cargo_root)))
+        .collect()
+}
+
+/// Diagnostics from rustc's `--error-format=json` output (stderr)
+pub fn parse_rustc_messages(json_output: &str) -> Vec<CompilerDiagnostic> {
+    json_output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|diagnostic| diagnostic["$message_type"] == "diagnostic")
+        .filter_map(|diagnostic| parse_rustc_diagnostic(&diagnostic, None))
+        .collect()
+}
+
+/// Human-readable text of rustc's JSON output (its `rendered` fields)
+pub fn rendered_rustc_output(json_output: &str) -> String {
+    let rendered: Vec<String> = json_output
+        .lines()
+        .map(|line| match serde_json::from_str::<Value>(line) {
+            Ok(diagnostic) => diagnostic["rendered"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            // Not JSON (e.g. an ICE message): keep as is
+            Err(_) => format!("{}\n", line),
+        })
+        .collect();
+    rendered.concat()
+}
+
+/// Diagnostics from gcc/clang text output
+///
+/// Only `file:line:column: level: message` lines are recognised; context
+/// lines such as "In function 'main':" and source excerpts are skipped.
+pub fn parse_gcc_messages(output: &str) -> Vec<CompilerDiagnostic> {
+    output.lines().filter_map(parse_gcc_line).collect()
+}
+
+fn parse_gcc_line(line: &str) -> Option<CompilerDiagnostic> {
+    for level in ["fatal error", "error", "warning", "note"] {
+        let marker = format!(": {}: ", level);
+        let Some((location, message)) = line.split_once(&marker) else {
+            continue;
+        };
+
+        // location is "file:line:column" (column may be missing)
+        let mut parts = location.rsplitn(3, ':');
+        let last = parts.next()?.parse::<u32>().ok()?;
+        let (file, line_number, column) = match (parts.next(), parts.next()) {
+            (Some(line_number), Some(file)) => match line_number.parse::<u32>() {
+                Ok(line_number) => (file, line_number, Some(last)),
+                Err(_) => (location.rsplit_once(':')?.0, last, None),
+            },
+            (Some(file), None) => (file, last, None),
+            _ => return None,
+        };
+
+        let (message, code) = match message.rsplit_once(" [") {
+            Some((text, option)) if option.starts_with("-W") && option.ends_with(']') => {
+                (text, Some(option.trim_end_matches(']').to_string()))
+            }
+            _ => (message, None),
+        };
+
+        return Some(CompilerDiagnostic {
+            level: if level == "fatal error" {
+                "error"
+            } else {
+                level
+            }
+            .to_string(),
+            message: message.to_string(),
+            file: Some(file.to_string()),
+            line: Some(line_number),
+            column,
+            code,
+        });
+    }
+    None
+}
+
+/// Make a compiler-reported path absolute, trying `base_dir` and its parents
+///
+/// In a workspace, cargo runs rustc from the workspace root, which may be
+/// above the member's directory.
+fn resolve_path(file: &str, base_dir: Option<&Path>) -> String {
+    let path = Path::new(file);
+    if path.is_absolute() {
+        return file.to_string();
+    }
+    base_dir
+        .into_iter()
+        .flat_map(Path::ancestors)
+        .map(|dir| dir.join(path))
+        .find(|candidate| candidate.exists())
+        .map(|candidate| candidate.to_string_lossy().to_string())
+        .unwrap_or_else(|| file.to_string())
+}
+
+/// Build the error for a failed compilation
+///
+/// With parsed errors, the message leads with the first error's location;
+/// otherwise (e.g. linker failures) the raw compiler output is kept.
+pub fn compilation_error(diagnostics: Vec<CompilerDiagnostic>, raw_output: &str) -> Error {
+    let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+    match diagnostics.iter().find(|d| d.is_error()) {
+        Some(first) => Error::CompilationFailed {
+            message: format!(
+                "Compilation failed: {} ({} error{})",
+                first.summary(),
+                errors,
+                if errors == 1 { "" } else { "s" }
+            ),
+            diagnostics,
+        },
+        None => Error::Compilation(format!("Compilation failed:\n{}", raw_output)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_cargo_compiler_message() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "").unwrap();
+
+        let record = json!({
+            "reason": "compiler-message",
+            "message": {
+                "$message_type": "diagnostic",
+                "level": "error",
+                "message": "mismatched types",
+                "code": {"code": "E0308", "explanation": "..."},
+                "spans": [
+                    {"file_name": "src/main.rs", "line_start": 2, "column_start": 5, "is_primary": false},
+                    {"file_name": "src/main.rs", "line_start": 4, "column_start": 18, "is_primary": true}
+                ]
+            }
+        });
+        let aborting = json!({
+            "reason": "compiler-message",
+            "message": {"level": "error", "message": "aborting due to 1 previous error", "spans": [], "code": null}
+        });
+        let output = format!(
+            "{}\n{}\n{{\"reason\":\"build-finished\"}}",
+            record, aborting
+        );
+
+        let diagnostics = parse_cargo_messages(&output, dir.path());
+
+        assert_eq!(diagnostics.len(), 1);
+        let d = &diagnostics[0];
+        assert_eq!(d.level, "error");
+        assert_eq!(d.message, "mismatched types");
+        assert_eq!(
+            d.file.as_deref(),
+            Some(dir.path().join("src/main.rs").to_str().unwrap())
+        );
+        assert_eq!((d.line, d.column), (Some(4), Some(18)));
+        assert_eq!(d.code.as_deref(), Some("E0308"));
+    }
+
+    #[test]
+    fn test_parse_rustc_messages_keeps_warnings() {
+        let output = r#"{"$message_type":"diagnostic","level":"warning","message":"unused variable: `x`","code":{"code":"unused_variables"},"spans":[{"file_name":"/w/app.rs","line_start":3,"column_start":9,"is_primary":true}]}
+{"$message_type":"artifact","artifact":"/w/app.d","emit":"dep-info"}"#;
+
+        let diagnostics = parse_rustc_messages(output);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].level, "warning");
+        assert!(!diagnostics[0].is_error());
+        assert_eq!(
+            diagnostics[0].summary(),
+            "/w/app.rs:3:9: unused variable: `x` [unused_variables]"
+        );
+    }
+
+    #[test]
+    fn test_parse_gcc_messages() {
+        let output = "fizzbuzz.c: In function 'main':\n\
+                      fizzbuzz.c:5:12: error: 'missing' undeclared (first use in this function)\n\
+                      \x20   5 |     return missing;\n\
+                      fizzbuzz.c:3:9: warning: unused variable 'x' [-Wunused-variable]\n\
+                      /usr/include/stdio.h:12: note: declared here\n";
+
+        let diagnostics = parse_gcc_messages(output);
+
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(diagnostics[0].level, "error");
+        assert_eq!(diagnostics[0].file.as_deref(), Some("fizzbuzz.c"));
+        assert_eq!(
+            (diagnostics[0].line, diagnostics[0].column),
+            (Some(5), Some(12))
+        );
+        assert_eq!(diagnostics[1].message, "unused variable 'x'");
+        assert_eq!(diagnostics[1].code.as_deref(), Some("-Wunused-variable"));
+        assert_eq!(
+            (diagnostics[2].line, diagnostics[2].column),
+            (Some(12), None)
+        );
+    }
+
+    #[test]
+    fn test_compilation_error_leads_with_first_error() {
+        let diagnostics = parse_gcc_messages(
+            "a.c:1:1: warning: w\na.c:7:3: error: first\na.c:9:1: error: second\n",
+        );
+
+        match compilation_error(diagnostics, "raw") {
+            Error::CompilationFailed {
+                message,
+                diagnostics,
+            } => {
+                assert_eq!(message, "Compilation failed: a.c:7:3: first (2 errors)");
+                assert_eq!(diagnostics.len(), 3);
+            }
+            other => panic!("Expected CompilationFailed, got {:?}", other),
+        }
+
+        // Without parseable errors the raw output is kept
+        assert!(matches!(
+            compilation_error(vec![], "ld: undefined reference"),
+            Error::Compilation(msg) if msg.contains("undefined reference")
+        ));
+    }
+}
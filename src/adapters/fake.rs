@@ -0,0 +1,103 @@
+//! Scripted adapter for deterministic integration tests
+//!
+//! Only compiled with the `fake-adapter` feature (which the crate's own tests
+//! enable). The "fake" language runs `fake_dap_adapter` (tests/bin/fake_dap_adapter.rs),
+//! a small DAP server over STDIO that debugs nothing: the session's program is
+//! a scenario file listing the lines the "program" executes, with the stack
+//! and locals at each of them. Breakpoints, steps, stack traces and
+//! evaluation all play back that scenario, so the session and tool layers can
+//! be exercised without debugpy or rdbg installed.
+
+use super::logging::DebugAdapterLogger;
+use serde_json::{json, Value};
+use std::error::Error;
+use tracing::error;
+
+/// Environment variable with the path of the `fake_dap_adapter` binary
+pub const ADAPTER_PATH_ENV: &str = "FAKE_DAP_ADAPTER";
+
+/// Scenario-playing test adapter configuration
+pub struct FakeAdapter;
+
+impl FakeAdapter {
+    /// $FAKE_DAP_ADAPTER, or `fake_dap_adapter` on PATH
+    pub fn command() -> String {
+        std::env::var(ADAPTER_PATH_ENV).unwrap_or_else(|_| "fake_dap_adapter".to_string())
+    }
+
+    pub fn args() -> Vec<String> {
+        Vec::new()
+    }
+
+    pub fn adapter_id() -> &'static str {
+        "fake"
+    }
+
+    /// `program` is the scenario file the adapter plays
+    pub fn launch_args_with_options(
+        program: &str,
+        args: &[String],
+        cwd: Option<&str>,
+        stop_on_entry: bool,
+    ) -> Value {
+        json!({
+            "request": "launch",
+            "program": program,
+            "args": args,
+            "cwd": cwd,
+            "stopOnEntry": stop_on_entry,
+        })
+    }
+}
+
+impl DebugAdapterLogger for FakeAdapter {
+    fn language_name(&self) -> &str {
+        "Fake"
+    }
+
+    fn language_emoji(&self) -> &str {
+        "🧪"
+    }
+
+    fn transport_type(&self) -> &str {
+        "STDIO"
+    }
+
+    fn adapter_id(&self) -> &str {
+        "fake"
+    }
+
+    fn command_line(&self) -> String {
+        Self::command()
+    }
+
+    fn log_spawn_error(&self, error: &dyn Error) {
+        error!("❌ [FAKE] Failed to spawn fake_dap_adapter: {}", error);
+        error!("   Command: {}", self.command_line());
+        error!(
+            "   Set {} to the binary built from tests/bin/fake_dap_adapter.rs",
+            ADAPTER_PATH_ENV
+        );
+    }
+
+    fn log_connection_error(&self, error: &dyn Error) {
+        error!("❌ [FAKE] Adapter connection failed: {}", error);
+    }
+
+    fn log_init_error(&self, error: &dyn Error) {
+        error!("❌ [FAKE] DAP initialization failed: {}", error);
+        error!("   Check that the program is a valid scenario file");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_launch_args() {
+        let args = FakeAdapter::launch_args_with_options("/tmp/scenario.json", &[], None, true);
+        assert_eq!(args["program"], "/tmp/scenario.json");
+        assert_eq!(args["stopOnEntry"], true);
+    }
+}
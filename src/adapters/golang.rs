@@ -1,8 +1,10 @@
+use super::health::{AdapterHealth, AdapterHealthCheck, HealthCheck};
 use super::logging::DebugAdapterLogger;
-use crate::dap::socket_helper;
+use crate::dap::socket_helper::{self, AdapterTimeouts};
 use crate::{Error, Result};
 use serde_json::{json, Value};
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use tokio::net::TcpStream;
 use tokio::process::{Child, Command};
 use tracing::{error, info};
@@ -31,8 +33,65 @@ pub struct GoDebugSession {
 }
 
 impl GoAdapter {
+    /// Get the Delve command path
+    ///
+    /// Checks, in order:
+    /// 1. dlv in PATH
+    /// 2. $GOPATH/bin/dlv (`go install` puts it there, but it is often not in PATH)
+    /// 3. ~/go/bin/dlv (the default GOPATH)
+    ///
+    /// Falls back to plain "dlv" so the spawn error names the missing command.
     pub fn command() -> String {
-        "dlv".to_string()
+        Self::find_dlv(
+            std::env::var_os("PATH"),
+            std::env::var_os("GOPATH"),
+            std::env::var_os("HOME"),
+        )
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|| "dlv".to_string())
+    }
+
+    fn find_dlv(
+        path: Option<std::ffi::OsString>,
+        gopath: Option<std::ffi::OsString>,
+        home: Option<std::ffi::OsString>,
+    ) -> Option<PathBuf> {
+        let path_dirs = path
+            .map(|p| std::env::split_paths(&p).collect::<Vec<_>>())
+            .unwrap_or_default();
+        // GOPATH may list several workspaces, like PATH
+        let gopath_bins = gopath
+            .map(|p| {
+                std::env::split_paths(&p)
+                    .map(|dir| dir.join("bin"))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let default_gopath_bin = home.map(|h| PathBuf::from(h).join("go").join("bin"));
+
+        // dlv.exe on Windows
+        crate::process::find_in_dirs(
+            "dlv",
+            path_dirs
+                .into_iter()
+                .chain(gopath_bins)
+                .chain(default_gopath_bin),
+        )
+    }
+
+    /// Delve launch mode for `program`
+    ///
+    /// `.go` files and package/module directories are compiled by Delve
+    /// ("debug"); any other file is treated as a prebuilt binary ("exec").
+    pub fn launch_mode(program: &str) -> &'static str {
+        let path = Path::new(program);
+        if path.is_dir() || path.extension().is_some_and(|ext| ext == "go") {
+            "debug"
+        } else if path.is_file() {
+            "exec"
+        } else {
+            "debug"
+        }
     }
 
     /// Spawn Delve with DAP communication over TCP socket
@@ -49,34 +108,42 @@ impl GoAdapter {
     ///
     /// Delve determines the type automatically.
     pub async fn spawn(
+        program: &str,
+        program_args: &[String],
+        stop_on_entry: bool,
+    ) -> Result<GoDebugSession> {
+        Self::spawn_with_timeouts(
+            program,
+            program_args,
+            stop_on_entry,
+            &AdapterTimeouts::default(),
+        )
+        .await
+    }
+
+    /// [`GoAdapter::spawn`] waiting up to `timeouts.connect` for dlv to listen
+    pub async fn spawn_with_timeouts(
         _program: &str,
         _program_args: &[String],
         _stop_on_entry: bool,
+        timeouts: &AdapterTimeouts,
     ) -> Result<GoDebugSession> {
-        // 1. Find free port
-        let port = socket_helper::find_free_port()?;
-
-        // 2. Build dlv dap command args
-        let args = vec![
-            "dap".to_string(),
-            "--listen".to_string(),
-            format!("127.0.0.1:{}", port),
-        ];
-
-        info!("Spawning dlv on port {}: dlv {:?}", port, args);
-
-        // 3. Spawn dlv process
-        let child = Command::new("dlv")
-            .args(&args)
-            .spawn()
-            .map_err(|e| Error::Process(format!("Failed to spawn dlv: {}", e)))?;
-
-        // 4. Connect to socket (with 3 second timeout - dlv needs a moment to start)
-        let socket = socket_helper::connect_with_retry(port, Duration::from_secs(3))
-            .await
-            .map_err(|e| {
-                Error::Process(format!("Failed to connect to dlv on port {}: {}", port, e))
-            })?;
+        // Spawn dlv on a free port and connect (dlv needs a moment to start);
+        // on a port lost to another process, again on a new one
+        let (child, socket, port) = socket_helper::spawn_tcp_adapter("dlv", timeouts, |port| {
+            let args = vec![
+                "dap".to_string(),
+                "--listen".to_string(),
+                format!("127.0.0.1:{}", port),
+            ];
+            info!("Spawning dlv on port {}: dlv {:?}", port, args);
+            Command::new(Self::command())
+                .args(&args)
+                .stderr(Stdio::piped())
+                .spawn()
+                .map_err(|e| Error::Process(format!("Failed to spawn dlv: {}", e)))
+        })
+        .await?;
 
         Ok(GoDebugSession {
             process: child,
@@ -102,8 +169,8 @@ impl GoAdapter {
     ///
     /// ## Mode Field
     ///
-    /// Go-specific `mode` field specifies how to launch:
-    /// - `"debug"`: Debug a Go program (default)
+    /// Go-specific `mode` field specifies how to launch (see [`GoAdapter::launch_mode`]):
+    /// - `"debug"`: Compile and debug a Go program (default)
     /// - `"test"`: Debug Go tests
     /// - `"exec"`: Debug a pre-compiled binary
     pub fn launch_args_with_options(
@@ -115,7 +182,7 @@ impl GoAdapter {
         let mut launch = json!({
             "request": "launch",
             "type": "go",
-            "mode": "debug",
+            "mode": Self::launch_mode(program),
             "program": program,
             "args": args,
             "stopOnEntry": stop_on_entry,
@@ -129,6 +196,20 @@ impl GoAdapter {
     }
 }
 
+impl AdapterHealthCheck for GoAdapter {
+    fn health_check(&self) -> AdapterHealth {
+        AdapterHealth::new(
+            "go",
+            Self::adapter_id(),
+            vec![
+                HealthCheck::command("dlv", &Self::command(), &["version"]),
+                HealthCheck::command("go", "go", &["version"]),
+            ],
+            "go install github.com/go-delve/delve/cmd/dlv@latest",
+        )
+    }
+}
+
 // ============================================================================
 // DebugAdapterLogger Trait Implementation
 // ============================================================================
@@ -302,6 +383,46 @@ mod tests {
         assert_eq!(launch["mode"], "debug");
     }
 
+    #[test]
+    fn test_launch_mode_exec_for_prebuilt_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("main.go");
+        let binary = dir.path().join("fizzbuzz");
+        std::fs::write(&source, "package main\n").unwrap();
+        std::fs::write(&binary, [0x7f, b'E', b'L', b'F']).unwrap();
+
+        assert_eq!(GoAdapter::launch_mode(source.to_str().unwrap()), "debug");
+        assert_eq!(
+            GoAdapter::launch_mode(dir.path().to_str().unwrap()),
+            "debug"
+        );
+        assert_eq!(GoAdapter::launch_mode(binary.to_str().unwrap()), "exec");
+
+        let launch =
+            GoAdapter::launch_args_with_options(binary.to_str().unwrap(), &[], None, false);
+        assert_eq!(launch["mode"], "exec");
+    }
+
+    #[test]
+    fn test_find_dlv_falls_back_to_gopath_bin() {
+        let empty_path = tempfile::tempdir().unwrap();
+        let gopath = tempfile::tempdir().unwrap();
+        std::fs::create_dir(gopath.path().join("bin")).unwrap();
+        let dlv = gopath.path().join("bin").join("dlv");
+        std::fs::write(&dlv, "").unwrap();
+
+        let found = GoAdapter::find_dlv(
+            Some(empty_path.path().as_os_str().to_owned()),
+            Some(gopath.path().as_os_str().to_owned()),
+            None,
+        );
+        assert_eq!(found, Some(dlv));
+
+        let missing =
+            GoAdapter::find_dlv(Some(empty_path.path().as_os_str().to_owned()), None, None);
+        assert_eq!(missing, None);
+    }
+
     #[test]
     fn test_debug_adapter_logger_trait() {
         let adapter = GoAdapter;
@@ -0,0 +1,261 @@
+//! Adapter health checks (`debugger_doctor`, `debugger_mcp doctor`)
+//!
+//! Each adapter reports whether the tools it needs are installed, with their
+//! versions and an install hint, through [`AdapterHealthCheck`]. A missing
+//! debugger otherwise only shows up once a session fails to initialize.
+//!
+//! Example report entry:
+//!
+//! ```text
+//! {"language": "ruby", "adapter": "rdbg", "available": false,
+//!  "checks": [{"name": "rdbg", "command": "rdbg --version", "ok": false,
+//!              "detail": "No such file or directory (os error 2)"}],
+//!  "installHint": "gem install debug"}
+//! ```
+
+use super::bash::BashAdapter;
+use super::cpp::{CLanguage, CppAdapter};
+use super::golang::GoAdapter;
+use super::nodejs::NodeJsAdapter;
+use super::python::PythonAdapter;
+use super::ruby::RubyAdapter;
+use super::rust::RustAdapter;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+
+/// Result of one prerequisite check (a command run or a file looked up)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthCheck {
+    pub name: String,
+    /// Command that was run, or the path that was looked up
+    pub command: String,
+    pub ok: bool,
+    /// Version line of the command's output, or why the check failed
+    pub detail: Option<String>,
+}
+
+impl HealthCheck {
+    /// Run `program args...`; ok when it exits successfully
+    pub fn command(name: &str, program: &str, args: &[&str]) -> Self {
+        let command = std::iter::once(program)
+            .chain(args.iter().copied())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let (ok, detail) = match Command::new(program).args(args).output() {
+            Ok(output) => {
+                // Some tools print their version on stderr
+                let text = if output.stdout.trim_ascii().is_empty() {
+                    output.stderr
+                } else {
+                    output.stdout
+                };
+                // The version line: the first with a digit ("Delve Debugger\nVersion: 1.22.0")
+                let text = String::from_utf8_lossy(&text);
+                let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+                let detail = lines
+                    .clone()
+                    .find(|line| line.contains(|c: char| c.is_ascii_digit()))
+                    .or_else(|| lines.next())
+                    .map(str::to_string);
+                (output.status.success(), detail)
+            }
+            Err(e) => (false, Some(e.to_string())),
+        };
+        Self {
+            name: name.to_string(),
+            command,
+            ok,
+            detail,
+        }
+    }
+
+    /// Check that `path` exists
+    pub fn file(name: &str, path: &str) -> Self {
+        let ok = Path::new(path).exists();
+        Self {
+            name: name.to_string(),
+            command: path.to_string(),
+            ok,
+            detail: (!ok).then(|| "not found".to_string()),
+        }
+    }
+}
+
+/// Whether a language can be debugged on this machine
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdapterHealth {
+    /// Value to pass as debugger_start's `language`
+    pub language: String,
+    pub adapter: String,
+    /// All checks passed
+    pub available: bool,
+    /// Version of the debug adapter itself, when it could be determined
+    pub version: Option<String>,
+    pub checks: Vec<HealthCheck>,
+    /// How to install what is missing (only set when something is)
+    pub install_hint: Option<String>,
+}
+
+impl AdapterHealth {
+    /// Aggregate `checks`; `version` is taken from the first check's output
+    pub fn new(
+        language: &str,
+        adapter: &str,
+        checks: Vec<HealthCheck>,
+        install_hint: &str,
+    ) -> Self {
+        let available = checks.iter().all(|check| check.ok);
+        Self {
+            language: language.to_string(),
+            adapter: adapter.to_string(),
+            available,
+            version: checks
+                .first()
+                .filter(|check| check.ok)
+                .and_then(|check| check.detail.clone()),
+            checks,
+            install_hint: (!available).then(|| install_hint.to_string()),
+        }
+    }
+}
+
+/// Implemented by every adapter so [`check_all`] can report on them
+pub trait AdapterHealthCheck {
+    fn health_check(&self) -> AdapterHealth;
+}
+
+/// Health of every supported language, in a stable order
+///
+/// Runs external commands, so call it from a blocking context.
+pub fn check_all() -> Vec<AdapterHealth> {
+    let adapters: Vec<Box<dyn AdapterHealthCheck>> = vec![
+        Box::new(PythonAdapter),
+        Box::new(RubyAdapter),
+        Box::new(NodeJsAdapter),
+        Box::new(GoAdapter),
+        Box::new(RustAdapter),
+        Box::new(CppAdapter::new(CLanguage::C)),
+        Box::new(CppAdapter::new(CLanguage::Cpp)),
+        Box::new(BashAdapter),
+    ];
+    adapters
+        .iter()
+        .map(|adapter| adapter.health_check())
+        .collect()
+}
+
+/// Version of the debug adapter used for `language`, cached per process
+///
+/// Runs the language's health check the first time (see [`check_all`] for
+/// blocking); `None` for unknown languages or when no version was reported.
+pub fn adapter_version(language: &str) -> Option<String> {
+    static VERSIONS: OnceLock<Mutex<HashMap<String, Option<String>>>> = OnceLock::new();
+    let versions = VERSIONS.get_or_init(Default::default);
+
+    if let Some(version) = versions
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(language)
+    {
+        return version.clone();
+    }
+
+    let health = match language {
+        "python" => PythonAdapter.health_check(),
+        "ruby" => RubyAdapter.health_check(),
+        "nodejs" => NodeJsAdapter.health_check(),
+        "go" => GoAdapter.health_check(),
+        "rust" => RustAdapter.health_check(),
+        "c" => CppAdapter::new(CLanguage::C).health_check(),
+        "cpp" => CppAdapter::new(CLanguage::Cpp).health_check(),
+        "bash" | "sh" => BashAdapter.health_check(),
+        _ => return None,
+    };
+    versions
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(language.to_string(), health.version.clone());
+    health.version
+}
+
+/// JSON report of `health`: every entry plus the available/missing languages
+pub fn report(health: &[AdapterHealth]) -> serde_json::Value {
+    let languages = |available: bool| -> Vec<&str> {
+        health
+            .iter()
+            .filter(|h| h.available == available)
+            .map(|h| h.language.as_str())
+            .collect()
+    };
+    serde_json::json!({
+        "adapters": health,
+        "available": languages(true),
+        "missing": languages(false),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_check_reports_output_and_failures() {
+        let ok = HealthCheck::command("sh", "sh", &["-c", "echo Tool; echo 'Version: 1.2.3'"]);
+        assert!(ok.ok);
+        assert_eq!(ok.command, "sh -c echo Tool; echo 'Version: 1.2.3'");
+        assert_eq!(ok.detail.as_deref(), Some("Version: 1.2.3"));
+
+        let missing = HealthCheck::command("nope", "definitely-not-installed-tool", &["--version"]);
+        assert!(!missing.ok);
+        assert!(missing.detail.is_some());
+    }
+
+    #[test]
+    fn test_adapter_health_aggregates_checks() {
+        let present = HealthCheck::command("sh", "sh", &["-c", "echo v1"]);
+        let missing = HealthCheck::file("script", "/definitely/not/here.js");
+
+        let healthy = AdapterHealth::new("x", "x-dap", vec![present.clone()], "install x");
+        assert!(healthy.available);
+        assert_eq!(healthy.version.as_deref(), Some("v1"));
+        assert_eq!(healthy.install_hint, None);
+
+        let broken = AdapterHealth::new("x", "x-dap", vec![present, missing], "install x");
+        assert!(!broken.available);
+        assert_eq!(broken.install_hint.as_deref(), Some("install x"));
+
+        let json = serde_json::to_value(&broken).unwrap();
+        assert_eq!(json["installHint"], "install x");
+        assert_eq!(json["checks"][1]["detail"], "not found");
+    }
+
+    #[test]
+    fn test_report_lists_missing_languages() {
+        let healthy = AdapterHealth::new("python", "debugpy", vec![], "");
+        let broken = AdapterHealth::new(
+            "ruby",
+            "rdbg",
+            vec![HealthCheck::file("rdbg", "/definitely/not/rdbg")],
+            "gem install debug",
+        );
+
+        let report = report(&[healthy, broken]);
+
+        assert_eq!(report["available"], serde_json::json!(["python"]));
+        assert_eq!(report["missing"], serde_json::json!(["ruby"]));
+        assert_eq!(report["adapters"][1]["installHint"], "gem install debug");
+    }
+
+    #[test]
+    fn test_check_all_covers_every_language() {
+        let languages: Vec<String> = check_all().into_iter().map(|h| h.language).collect();
+        assert_eq!(
+            languages,
+            vec!["python", "ruby", "nodejs", "go", "rust", "c", "cpp", "bash"]
+        );
+    }
+}
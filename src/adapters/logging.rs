@@ -23,7 +23,7 @@ use tracing::{debug, info};
 /// 6. Shutdown (`log_shutdown`) - Cleanup
 ///
 /// Errors logged via: `log_spawn_error`, `log_connection_error`, `log_init_error`
-pub trait DebugAdapterLogger {
+pub trait DebugAdapterLogger: Send + Sync {
     // ========================================================================
     // Metadata (Language-Specific Constants)
     // ========================================================================
@@ -1,7 +1,14 @@
+pub mod bash;
+pub mod cpp;
+pub mod diagnostics;
+#[cfg(feature = "fake-adapter")]
+pub mod fake;
 pub mod golang;
+pub mod health;
 pub mod logging;
 pub mod nodejs;
 pub mod python;
 pub mod ruby;
 pub mod rust;
 pub mod security;
+pub mod source_analysis;
@@ -62,15 +62,140 @@
 //! - https://github.com/microsoft/vscode-js-debug - Upstream project
 //! - DAP spec: https://microsoft.github.io/debug-adapter-protocol/
 
+use super::health::{AdapterHealth, AdapterHealthCheck, HealthCheck};
 use super::logging::DebugAdapterLogger;
-use crate::dap::socket_helper;
+use crate::dap::socket_helper::{self, AdapterTimeouts};
 use crate::{Error, Result};
 use serde_json::{json, Value};
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use tokio::net::TcpStream;
 use tokio::process::{Child, Command};
 use tracing::{error, info};
 
+/// Program extensions accepted for Node.js (JavaScript and TypeScript)
+pub const PROGRAM_EXTENSIONS: &[&str] = &["js", "mjs", "cjs", "ts", "mts", "cts"];
+
+/// Package managers that run package.json scripts instead of a program
+const PACKAGE_MANAGERS: &[&str] = &["npm", "yarn", "pnpm"];
+
+/// Source map and runtime settings for TypeScript and other compiled programs
+///
+/// Breakpoints set on `.ts` files only bind when vscode-js-debug can find the
+/// compiled JavaScript (`outFiles`) and its source maps.
+///
+/// Services started with `npm run <script>` are launched through the package
+/// manager (`npmScript`, or `runtimeExecutable: "npm"` with `runtimeArgs`);
+/// vscode-js-debug then follows npm to the node process it starts.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NodeLaunchOptions {
+    /// Map compiled code back to its sources (on by default for TypeScript)
+    pub source_maps: Option<bool>,
+    /// Globs of the compiled JavaScript, e.g. "/app/dist/**/*.js"
+    pub out_files: Vec<String>,
+    /// Globs of the locations allowed to provide source maps
+    pub resolve_source_map_locations: Vec<String>,
+    /// Arguments for the Node.js runtime, e.g. ["--import", "tsx"] or
+    /// ["-r", "ts-node/register"] to run `.ts` files directly
+    pub runtime_args: Vec<String>,
+    /// Runtime to start instead of `node`, e.g. "npm"
+    pub runtime_executable: Option<String>,
+    /// package.json script to run with `npm run`
+    pub npm_script: Option<String>,
+}
+
+impl NodeLaunchOptions {
+    /// Add the options to a launch configuration from [`NodeJsAdapter::launch_config`]
+    ///
+    /// For TypeScript programs source maps are enabled and, unless given,
+    /// `outFiles` covers the JavaScript under the working directory (or the
+    /// program's directory), excluding node_modules.
+    pub fn apply(&self, launch: &mut Value) {
+        let program = launch["program"].as_str().unwrap_or_default().to_string();
+        let typescript = is_typescript(&program);
+
+        if let Some(source_maps) = self.source_maps.or(typescript.then_some(true)) {
+            launch["sourceMaps"] = json!(source_maps);
+        }
+
+        if !self.out_files.is_empty() {
+            launch["outFiles"] = json!(self.out_files);
+        } else if typescript {
+            let root = launch["cwd"]
+                .as_str()
+                .map(str::to_string)
+                .or_else(|| {
+                    Path::new(&program)
+                        .parent()
+                        .map(|dir| dir.to_string_lossy().to_string())
+                })
+                .unwrap_or_else(|| ".".to_string());
+            launch["outFiles"] = json!([
+                format!("{}/**/*.(m|c|)js", root.trim_end_matches('/')),
+                "!**/node_modules/**"
+            ]);
+        }
+
+        if !self.resolve_source_map_locations.is_empty() {
+            launch["resolveSourceMapLocations"] = json!(self.resolve_source_map_locations);
+        }
+        if let Some(script) = &self.npm_script {
+            let mut runtime_args = vec!["run".to_string(), script.clone()];
+            runtime_args.extend(self.runtime_args.iter().cloned());
+            launch["runtimeExecutable"] = json!("npm");
+            launch["runtimeArgs"] = json!(runtime_args);
+        } else {
+            if let Some(runtime) = &self.runtime_executable {
+                launch["runtimeExecutable"] = json!(runtime);
+            }
+            if !self.runtime_args.is_empty() {
+                launch["runtimeArgs"] = json!(self.runtime_args);
+            }
+        }
+
+        if self.uses_package_manager() {
+            // The script decides what runs; program arguments go to the script
+            if let Some(launch) = launch.as_object_mut() {
+                launch.remove("program");
+            }
+            let args = launch["args"].as_array().cloned().unwrap_or_default();
+            if !args.is_empty() {
+                launch["args"] =
+                    json!(std::iter::once(json!("--")).chain(args).collect::<Vec<_>>());
+            }
+        }
+    }
+
+    /// Whether a package manager runs a package.json script (no program is launched)
+    pub fn uses_package_manager(&self) -> bool {
+        self.npm_script.is_some()
+            || self.runtime_executable.as_deref().is_some_and(|runtime| {
+                let name = Path::new(runtime)
+                    .file_stem()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or(runtime);
+                PACKAGE_MANAGERS.contains(&name)
+            })
+    }
+}
+
+/// Nearest directory at or above `path` that contains a package.json
+pub fn package_root(path: &Path) -> Option<PathBuf> {
+    let start = if path.is_dir() { path } else { path.parent()? };
+    start
+        .ancestors()
+        .find(|dir| dir.join("package.json").is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Whether `program` is a TypeScript source file
+pub fn is_typescript(program: &str) -> bool {
+    Path::new(program)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| matches!(e, "ts" | "mts" | "cts"))
+}
+
 /// Node.js vscode-js-debug adapter configuration
 ///
 /// Unlike Python and Ruby which run the debugger directly, Node.js uses a
@@ -86,6 +211,8 @@ pub struct NodeJsDebugSession {
     pub process: Child,
     pub socket: TcpStream,
     pub port: u16,
+    /// dapDebugServer.js the server was started from
+    pub dap_server_path: String,
 }
 
 impl NodeJsAdapter {
@@ -123,6 +250,17 @@ impl NodeJsAdapter {
         ))
     }
 
+    /// vscode-js-debug's version from the package.json next to `src/`
+    fn js_debug_version(dap_server_path: &str) -> Option<String> {
+        let package_json = std::path::Path::new(dap_server_path)
+            .parent()?
+            .parent()?
+            .join("package.json");
+        let package: Value =
+            serde_json::from_str(&std::fs::read_to_string(package_json).ok()?).ok()?;
+        package["version"].as_str().map(str::to_string)
+    }
+
     /// Generate command for spawning vscode-js-debug DAP server
     ///
     /// Returns: ["node", "/path/to/dapDebugServer.js", "<port>", "127.0.0.1"]
@@ -147,39 +285,39 @@ impl NodeJsAdapter {
     ///
     /// Returns the DAP server process and connected TCP stream.
     pub async fn spawn_dap_server() -> Result<NodeJsDebugSession> {
-        // 1. Find free port for DAP server
-        let port = socket_helper::find_free_port()?;
+        Self::spawn_dap_server_with_timeouts(&AdapterTimeouts::default()).await
+    }
 
-        // 2. Get DAP server command
+    /// [`NodeJsAdapter::spawn_dap_server`] waiting up to `timeouts.connect`
+    /// for vscode-js-debug to listen
+    pub async fn spawn_dap_server_with_timeouts(
+        timeouts: &AdapterTimeouts,
+    ) -> Result<NodeJsDebugSession> {
+        // 1. Get DAP server command
         let dap_server_path = Self::dap_server_path()?;
-
-        info!("Spawning vscode-js-debug DAP server on port {}", port);
         info!("DAP server path: {}", dap_server_path);
 
-        // 3. Spawn vscode-js-debug DAP server
-        let child = Command::new("node")
-            .args([
-                &dap_server_path,
-                &port.to_string(),
-                "127.0.0.1", // IPv4 explicit
-            ])
-            .spawn()
-            .map_err(|e| {
-                Error::Process(format!(
-                    "Failed to spawn vscode-js-debug: {}. Is Node.js installed?",
-                    e
-                ))
-            })?;
-
-        // 4. Connect to DAP server (with 2 second timeout)
-        let socket = socket_helper::connect_with_retry(port, Duration::from_secs(2))
-            .await
-            .map_err(|e| {
-                Error::Process(format!(
-                    "Failed to connect to vscode-js-debug on port {}: {}",
-                    port, e
-                ))
-            })?;
+        // 2. Spawn vscode-js-debug DAP server on a free port and connect; on
+        // a port lost to another process, again on a new one
+        let (child, socket, port) =
+            socket_helper::spawn_tcp_adapter("vscode-js-debug", timeouts, |port| {
+                info!("Spawning vscode-js-debug DAP server on port {}", port);
+                Command::new("node")
+                    .args([
+                        &dap_server_path,
+                        &port.to_string(),
+                        "127.0.0.1", // IPv4 explicit
+                    ])
+                    .stderr(Stdio::piped())
+                    .spawn()
+                    .map_err(|e| {
+                        Error::Process(format!(
+                            "Failed to spawn vscode-js-debug: {}. Is Node.js installed?",
+                            e
+                        ))
+                    })
+            })
+            .await?;
 
         info!(
             "✅ Connected to vscode-js-debug DAP server on port {}",
@@ -190,6 +328,7 @@ impl NodeJsAdapter {
             process: child,
             socket,
             port,
+            dap_server_path,
         })
     }
 
@@ -233,6 +372,34 @@ impl NodeJsAdapter {
     }
 }
 
+impl AdapterHealthCheck for NodeJsAdapter {
+    fn health_check(&self) -> AdapterHealth {
+        let js_debug = match Self::dap_server_path() {
+            Ok(path) => {
+                let mut check = HealthCheck::file("vscode-js-debug", &path);
+                check.detail = check.detail.or_else(|| Self::js_debug_version(&path));
+                check
+            }
+            Err(e) => HealthCheck {
+                name: "vscode-js-debug".to_string(),
+                command: "dapDebugServer.js".to_string(),
+                ok: false,
+                detail: Some(e.to_string()),
+            },
+        };
+        AdapterHealth::new(
+            "nodejs",
+            Self::adapter_id(),
+            vec![
+                js_debug,
+                HealthCheck::command("node", "node", &["--version"]),
+            ],
+            "Install Node.js and unpack vscode-js-debug (js-debug-dap-*.tar.gz from \
+             https://github.com/microsoft/vscode-js-debug/releases) to /usr/local/lib/js-debug",
+        )
+    }
+}
+
 // ============================================================================
 // DebugAdapterLogger Trait Implementation
 // ============================================================================
@@ -421,6 +588,90 @@ mod tests {
         assert_eq!(config["args"][2], "result.json");
     }
 
+    #[test]
+    fn test_typescript_launch_enables_source_maps() {
+        let mut config = NodeJsAdapter::launch_config("/app/src/main.ts", &[], Some("/app"), false);
+        NodeLaunchOptions {
+            runtime_args: vec!["-r".to_string(), "ts-node/register".to_string()],
+            ..NodeLaunchOptions::default()
+        }
+        .apply(&mut config);
+
+        assert_eq!(config["sourceMaps"], true);
+        assert_eq!(
+            config["outFiles"],
+            json!(["/app/**/*.(m|c|)js", "!**/node_modules/**"])
+        );
+        assert_eq!(config["runtimeArgs"], json!(["-r", "ts-node/register"]));
+        assert!(config.get("resolveSourceMapLocations").is_none());
+    }
+
+    #[test]
+    fn test_explicit_source_map_options_win() {
+        let mut config = NodeJsAdapter::launch_config("/app/dist/main.js", &[], None, false);
+        NodeLaunchOptions::default().apply(&mut config);
+        assert!(config.get("sourceMaps").is_none());
+        assert!(config.get("outFiles").is_none());
+
+        let mut config = NodeJsAdapter::launch_config("/app/src/main.ts", &[], None, false);
+        NodeLaunchOptions {
+            source_maps: Some(false),
+            out_files: vec!["/app/build/**/*.js".to_string()],
+            resolve_source_map_locations: vec!["/app/**".to_string()],
+            ..NodeLaunchOptions::default()
+        }
+        .apply(&mut config);
+        assert_eq!(config["sourceMaps"], false);
+        assert_eq!(config["outFiles"], json!(["/app/build/**/*.js"]));
+        assert_eq!(config["resolveSourceMapLocations"], json!(["/app/**"]));
+    }
+
+    #[test]
+    fn test_npm_script_launch() {
+        let args = vec!["--port".to_string(), "3000".to_string()];
+        let mut config = NodeJsAdapter::launch_config("/app", &args, Some("/app"), false);
+        let options = NodeLaunchOptions {
+            npm_script: Some("dev".to_string()),
+            ..NodeLaunchOptions::default()
+        };
+        assert!(options.uses_package_manager());
+        options.apply(&mut config);
+
+        assert_eq!(config["runtimeExecutable"], "npm");
+        assert_eq!(config["runtimeArgs"], json!(["run", "dev"]));
+        assert_eq!(config["args"], json!(["--", "--port", "3000"]));
+        assert_eq!(config["cwd"], "/app");
+        assert!(config.get("program").is_none());
+
+        let explicit = NodeLaunchOptions {
+            runtime_executable: Some("/usr/local/bin/yarn".to_string()),
+            runtime_args: vec!["start".to_string()],
+            ..NodeLaunchOptions::default()
+        };
+        assert!(explicit.uses_package_manager());
+        let tsx = NodeLaunchOptions {
+            runtime_executable: Some("tsx".to_string()),
+            ..NodeLaunchOptions::default()
+        };
+        assert!(!tsx.uses_package_manager());
+    }
+
+    #[test]
+    fn test_package_root_walks_up_to_package_json() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("package.json"), "{}").unwrap();
+        let src = dir.path().join("src").join("lib");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("app.js"), "").unwrap();
+
+        assert_eq!(
+            package_root(&src.join("app.js")).as_deref(),
+            Some(dir.path())
+        );
+        assert_eq!(package_root(&src).as_deref(), Some(dir.path()));
+        assert_eq!(package_root(dir.path()).as_deref(), Some(dir.path()));
+    }
+
     #[test]
     fn test_launch_config_empty_args() {
         let program = "test.js";
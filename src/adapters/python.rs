@@ -1,13 +1,115 @@
+use super::health::{AdapterHealth, AdapterHealthCheck, HealthCheck};
 use super::logging::DebugAdapterLogger;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::error::Error;
 use tracing::error;
 
+/// Maps a directory on this machine to the same directory in the debuggee
+///
+/// Needed when attaching to a process that runs elsewhere (a container or a
+/// remote host): debugpy translates breakpoint and stack frame paths with it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathMapping {
+    pub local_root: String,
+    pub remote_root: String,
+}
+
+/// Web framework whose development server needs adjusted launch arguments
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebFramework {
+    Flask,
+    Django,
+}
+
+impl WebFramework {
+    pub const NAMES: &'static [&'static str] = &["flask", "django"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "flask" => Some(Self::Flask),
+            "django" => Some(Self::Django),
+            _ => None,
+        }
+    }
+}
+
+/// Subprocess and web framework settings for Python launches
+///
+/// Development servers restart themselves through an autoreloader: the
+/// process debugpy launched only watches files while a forked child serves
+/// the requests. Either the reloader is turned off (`webFramework` alone), or
+/// debugpy follows the child (`subProcess`), which it reports with a
+/// `debugpyAttach` event.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PythonLaunchOptions {
+    /// Debug subprocesses too (debugpy's `subProcess`)
+    pub sub_process: bool,
+    pub web_framework: Option<WebFramework>,
+}
+
+impl PythonLaunchOptions {
+    /// Add the options to a launch configuration from [`PythonAdapter::launch_args_with_options`]
+    ///
+    /// Flask apps are started with `flask run` (the program becomes
+    /// FLASK_APP), Django projects with `manage.py runserver` when no command
+    /// is given. The reloader is disabled unless subprocesses are followed.
+    pub fn apply(&self, launch: &mut Value) {
+        if self.sub_process {
+            launch["subProcess"] = json!(true);
+        }
+
+        let mut args: Vec<Value> = launch["args"].as_array().cloned().unwrap_or_default();
+        match self.web_framework {
+            None => return,
+            Some(WebFramework::Flask) => {
+                let program = launch
+                    .as_object_mut()
+                    .and_then(|config| config.remove("program"))
+                    .unwrap_or_default();
+                launch["module"] = json!("flask");
+                launch["env"] = json!({ "FLASK_APP": program });
+                launch["jinja"] = json!(true);
+                let reload = if self.sub_process {
+                    "--reload"
+                } else {
+                    "--no-reload"
+                };
+                args.splice(0..0, [json!("run"), json!("--no-debugger"), json!(reload)]);
+            }
+            Some(WebFramework::Django) => {
+                launch["django"] = json!(true);
+                if args.is_empty() {
+                    args.push(json!("runserver"));
+                }
+                if args[0] == "runserver"
+                    && !self.sub_process
+                    && !args.contains(&json!("--noreload"))
+                {
+                    args.push(json!("--noreload"));
+                }
+            }
+        }
+        launch["args"] = json!(args);
+    }
+}
+
 /// Python debugpy adapter configuration
 pub struct PythonAdapter;
 
 impl PythonAdapter {
+    /// Python interpreter running debugpy
+    ///
+    /// Windows installs without `python.exe` on PATH still have the `py`
+    /// launcher, which starts the newest Python 3.
     pub fn command() -> String {
+        if cfg!(windows)
+            && crate::process::which("python").is_none()
+            && crate::process::which("py").is_some()
+        {
+            return "py".to_string();
+        }
         "python".to_string()
     }
 
@@ -53,6 +155,59 @@ impl PythonAdapter {
 
         launch
     }
+
+    /// Launch configuration that runs a module (`python -m <module>`) instead
+    /// of a program file, e.g. `pytest` with `["-x", "tests/test_foo.py::test_bar"]`
+    ///
+    /// With stopOnEntry the first stop is in the module's own entry point
+    /// (pytest's main), not in the user's code: breakpoints in the test file
+    /// are the better way to stop inside a test.
+    pub fn module_launch_args(
+        module: &str,
+        args: &[String],
+        cwd: Option<&str>,
+        stop_on_entry: bool,
+    ) -> Value {
+        let mut launch = Self::launch_args_with_options("", args, cwd, stop_on_entry);
+        if let Some(config) = launch.as_object_mut() {
+            config.remove("program");
+            config.insert("module".to_string(), json!(module));
+        }
+        launch
+    }
+
+    /// Attach configuration for a process started with `python -m debugpy --listen`
+    ///
+    /// `--listen` runs the debugpy adapter inside the target process, so the
+    /// DAP client connects straight to `host:port` (no adapter is spawned).
+    /// debugpy expects the same address in the attach arguments.
+    pub fn attach_args(host: &str, port: u16, path_mappings: &[PathMapping]) -> Value {
+        json!({
+            "request": "attach",
+            "type": "python",
+            "connect": {
+                "host": host,
+                "port": port,
+            },
+            "pathMappings": path_mappings,
+            "justMyCode": true,
+        })
+    }
+}
+
+impl AdapterHealthCheck for PythonAdapter {
+    fn health_check(&self) -> AdapterHealth {
+        AdapterHealth::new(
+            "python",
+            Self::adapter_id(),
+            vec![HealthCheck::command(
+                "debugpy",
+                &Self::command(),
+                &["-c", "import debugpy; print(debugpy.__version__)"],
+            )],
+            "pip install debugpy",
+        )
+    }
 }
 
 // ============================================================================
@@ -152,6 +307,25 @@ mod tests {
         assert_eq!(PythonAdapter::adapter_id(), "debugpy");
     }
 
+    #[test]
+    fn test_attach_args_include_connect_and_path_mappings() {
+        let mappings = vec![PathMapping {
+            local_root: "/home/me/app".to_string(),
+            remote_root: "/app".to_string(),
+        }];
+        let attach = PythonAdapter::attach_args("127.0.0.1", 5678, &mappings);
+
+        assert_eq!(attach["request"], "attach");
+        assert_eq!(
+            attach["connect"],
+            json!({"host": "127.0.0.1", "port": 5678})
+        );
+        assert_eq!(
+            attach["pathMappings"],
+            json!([{"localRoot": "/home/me/app", "remoteRoot": "/app"}])
+        );
+    }
+
     #[test]
     fn test_launch_args_without_cwd() {
         let program = "/path/to/script.py";
@@ -178,6 +352,70 @@ mod tests {
         assert_eq!(launch["program"], program);
     }
 
+    #[test]
+    fn test_flask_launch_disables_reloader_unless_following_subprocesses() {
+        let mut launch =
+            PythonAdapter::launch_args("/srv/app.py", &["--port=5001".to_string()], None);
+        PythonLaunchOptions {
+            sub_process: false,
+            web_framework: Some(WebFramework::Flask),
+        }
+        .apply(&mut launch);
+
+        assert!(launch.get("program").is_none());
+        assert_eq!(launch["module"], "flask");
+        assert_eq!(launch["env"]["FLASK_APP"], "/srv/app.py");
+        assert_eq!(
+            launch["args"],
+            json!(["run", "--no-debugger", "--no-reload", "--port=5001"])
+        );
+        assert!(launch.get("subProcess").is_none());
+
+        let mut launch = PythonAdapter::launch_args("/srv/app.py", &[], None);
+        PythonLaunchOptions {
+            sub_process: true,
+            web_framework: Some(WebFramework::Flask),
+        }
+        .apply(&mut launch);
+        assert_eq!(launch["args"], json!(["run", "--no-debugger", "--reload"]));
+        assert_eq!(launch["subProcess"], true);
+    }
+
+    #[test]
+    fn test_django_launch_defaults_to_runserver_without_reloader() {
+        let options = PythonLaunchOptions {
+            sub_process: false,
+            web_framework: Some(WebFramework::Django),
+        };
+
+        let mut launch = PythonAdapter::launch_args("/srv/manage.py", &[], None);
+        options.apply(&mut launch);
+        assert_eq!(launch["program"], "/srv/manage.py");
+        assert_eq!(launch["args"], json!(["runserver", "--noreload"]));
+        assert_eq!(launch["django"], true);
+
+        // Other management commands are left alone
+        let mut launch =
+            PythonAdapter::launch_args("/srv/manage.py", &["migrate".to_string()], None);
+        options.apply(&mut launch);
+        assert_eq!(launch["args"], json!(["migrate"]));
+
+        assert_eq!(WebFramework::from_name("Flask"), Some(WebFramework::Flask));
+        assert_eq!(WebFramework::from_name("rails"), None);
+    }
+
+    #[test]
+    fn test_module_launch_args_omit_program() {
+        let args = vec!["-x".to_string(), "tests/test_calc.py::test_add".to_string()];
+        let launch = PythonAdapter::module_launch_args("pytest", &args, Some("/project"), false);
+
+        assert_eq!(launch["module"], "pytest");
+        assert!(launch.get("program").is_none());
+        assert_eq!(launch["args"], json!(args));
+        assert_eq!(launch["cwd"], "/project");
+        assert_eq!(launch["request"], "launch");
+    }
+
     #[test]
     fn test_launch_args_empty_args() {
         let program = "test.py";
@@ -1,77 +1,270 @@
+use super::health::{AdapterHealth, AdapterHealthCheck, HealthCheck};
 use super::logging::DebugAdapterLogger;
-use crate::dap::socket_helper;
+use crate::dap::socket_helper::{self, AdapterEndpoint, AdapterStream, AdapterTimeouts};
 use crate::{Error, Result};
 use serde_json::{json, Value};
-use std::time::Duration;
-use tokio::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use tokio::process::{Child, Command};
+use tokio::sync::OnceCell;
 use tracing::{error, info};
 
+/// Oldest debug gem release known to work with `rdbg --open` in socket mode
+pub const MIN_RDBG_VERSION: (u32, u32, u32) = (1, 4, 0);
+
+/// `rdbg --version` result, checked once per server process
+static RDBG_VERSION: OnceCell<std::result::Result<String, String>> = OnceCell::const_new();
+
+/// Bundler and command settings for starting rdbg
+///
+/// Programs that need their Gemfile's gems run as `bundle exec rdbg ...` in
+/// the Gemfile's directory (`use_bundler`). Tools started through a command
+/// rather than a script, such as rspec or a Rails runner, use rdbg's command
+/// mode: `rdbg -c -- bundle exec rspec spec/foo_spec.rb`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RubyLaunchOptions {
+    /// Start rdbg with `bundle exec`
+    pub use_bundler: bool,
+    /// Command that runs the program, e.g. ["bundle", "exec", "rspec"]; the
+    /// program and its args follow it
+    pub command: Vec<String>,
+}
+
+impl RubyLaunchOptions {
+    /// Executable and arguments that start rdbg listening on `endpoint`
+    ///
+    /// The arguments are passed to the process as-is, never through a shell.
+    pub fn rdbg_command(
+        &self,
+        program: &str,
+        program_args: &[String],
+        stop_on_entry: bool,
+        endpoint: &AdapterEndpoint,
+    ) -> (String, Vec<String>) {
+        let mut args = Vec::new();
+        let executable = if self.use_bundler {
+            args.extend(["exec".to_string(), RubyAdapter::command()]);
+            "bundle".to_string()
+        } else {
+            RubyAdapter::command()
+        };
+
+        args.push("--open".to_string());
+        match endpoint {
+            AdapterEndpoint::Tcp(port) => {
+                args.extend(["--port".to_string(), port.to_string()]);
+            }
+            #[cfg(unix)]
+            AdapterEndpoint::Unix(path) => {
+                args.push(format!("--sock-path={}", path.display()));
+            }
+        }
+        // Add stop behavior flag
+        if stop_on_entry {
+            args.push("--stop-at-load".to_string());
+        } else {
+            args.push("--nonstop".to_string());
+        }
+
+        if !self.command.is_empty() {
+            args.extend(["-c".to_string(), "--".to_string()]);
+            args.extend(self.command.iter().cloned());
+        }
+        args.push(program.to_string());
+        args.extend(program_args.iter().cloned());
+
+        (executable, args)
+    }
+}
+
+/// Nearest directory containing a Gemfile: `cwd` when given, otherwise
+/// `program`'s directory or one of its parents
+pub fn gemfile_root(program: &str, cwd: Option<&str>) -> Result<PathBuf> {
+    let start = match cwd {
+        Some(cwd) => PathBuf::from(cwd),
+        None => Path::new(program)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default(),
+    };
+    start
+        .ancestors()
+        .find(|dir| dir.join("Gemfile").is_file())
+        .map(Path::to_path_buf)
+        .ok_or_else(|| {
+            Error::InvalidRequest(format!(
+                "useBundler requires a Gemfile, but none was found in or above {}",
+                start.display()
+            ))
+        })
+}
+
 /// Ruby rdbg (debug gem) adapter configuration
 ///
 /// Unlike Python's debugpy which has a separate adapter server,
-/// rdbg runs the program directly and communicates via TCP socket.
+/// rdbg runs the program directly and communicates via a unix domain
+/// socket, or a TCP socket on platforms without them.
 pub struct RubyAdapter;
 
 /// Result of spawning Ruby debugger (process + connected socket)
 pub struct RubyDebugSession {
     pub process: Child,
-    pub socket: TcpStream,
-    pub port: u16,
+    pub socket: AdapterStream,
+    /// Unix socket where available, otherwise a TCP port
+    pub endpoint: AdapterEndpoint,
+    /// debug gem version reported by `rdbg --version`
+    pub version: String,
 }
 
 impl RubyAdapter {
+    /// rdbg is a gem binstub, installed as `rdbg.bat` on Windows, which
+    /// process spawning does not find by the bare name
     pub fn command() -> String {
+        if cfg!(windows) {
+            if let Some(path) = crate::process::which("rdbg") {
+                return path.to_string_lossy().to_string();
+            }
+        }
         "rdbg".to_string()
     }
 
+    /// Installed debug gem version, failing if it is older than [`MIN_RDBG_VERSION`]
+    ///
+    /// Runs `rdbg --version` the first time only; later sessions reuse the result.
+    pub async fn verify_version() -> Result<String> {
+        RDBG_VERSION
+            .get_or_init(|| async {
+                let output = Command::new(Self::command())
+                    .arg("--version")
+                    .output()
+                    .await
+                    .map_err(|e| {
+                        format!(
+                            "rdbg not found ({}). Install the debug gem: gem install debug",
+                            e
+                        )
+                    })?;
+                Self::check_version(&String::from_utf8_lossy(&output.stdout))
+            })
+            .await
+            .clone()
+            .map_err(Error::Process)
+    }
+
+    /// Validate `rdbg --version` output ("rdbg 1.9.2"), returning the version
+    fn check_version(output: &str) -> std::result::Result<String, String> {
+        let version = output
+            .split_whitespace()
+            .find(|word| word.starts_with(|c: char| c.is_ascii_digit()))
+            .ok_or_else(|| format!("Could not determine rdbg version from '{}'", output.trim()))?;
+
+        let mut parts = version
+            .split(|c: char| !c.is_ascii_digit())
+            .map(|part| part.parse::<u32>().unwrap_or(0));
+        let parsed = (
+            parts.next().unwrap_or(0),
+            parts.next().unwrap_or(0),
+            parts.next().unwrap_or(0),
+        );
+
+        if parsed < MIN_RDBG_VERSION {
+            let (major, minor, patch) = MIN_RDBG_VERSION;
+            return Err(format!(
+                "rdbg {} is too old: debug gem {}.{}.{} or newer is required for DAP over a socket. \
+                 Upgrade with: gem install debug",
+                version, major, minor, patch
+            ));
+        }
+        Ok(version.to_string())
+    }
+
     /// Spawn rdbg with socket-based DAP communication
     ///
-    /// This spawns `rdbg --open --port <PORT> program.rb` and connects to the socket.
-    /// Returns the process and connected TCP stream for DAP communication.
+    /// This spawns `rdbg --open --sock-path=<PATH> program.rb` (on platforms
+    /// without unix sockets `rdbg --open --port <PORT> program.rb`) and
+    /// connects to the socket. Returns the process and connected stream for
+    /// DAP communication.
+    /// Fails with [`Error::Process`] if the debug gem is older than [`MIN_RDBG_VERSION`].
     pub async fn spawn(
         program: &str,
         program_args: &[String],
         stop_on_entry: bool,
     ) -> Result<RubyDebugSession> {
-        // 1. Find free port
-        let port = socket_helper::find_free_port()?;
-
-        // 2. Build command args
-        let mut args = vec!["--open".to_string(), "--port".to_string(), port.to_string()];
-
-        // Add stop behavior flag
-        if stop_on_entry {
-            args.push("--stop-at-load".to_string());
-        } else {
-            args.push("--nonstop".to_string());
-        }
-
-        // Add program path
-        args.push(program.to_string());
-
-        // Add program arguments
-        args.extend(program_args.iter().cloned());
-
-        info!("Spawning rdbg on port {}: rdbg {:?}", port, args);
+        Self::spawn_with_timeouts(
+            program,
+            program_args,
+            stop_on_entry,
+            &AdapterTimeouts::default(),
+        )
+        .await
+    }
 
-        // 3. Spawn rdbg process
-        let child = Command::new("rdbg")
-            .args(&args)
-            .spawn()
-            .map_err(|e| Error::Process(format!("Failed to spawn rdbg: {}", e)))?;
+    /// [`RubyAdapter::spawn`] waiting up to `timeouts.connect` for rdbg to listen
+    pub async fn spawn_with_timeouts(
+        program: &str,
+        program_args: &[String],
+        stop_on_entry: bool,
+        timeouts: &AdapterTimeouts,
+    ) -> Result<RubyDebugSession> {
+        Self::spawn_with_options(
+            program,
+            program_args,
+            stop_on_entry,
+            None,
+            &RubyLaunchOptions::default(),
+            timeouts,
+        )
+        .await
+    }
 
-        // 4. Connect to socket (with 2 second timeout)
-        let socket = socket_helper::connect_with_retry(port, Duration::from_secs(2))
-            .await
-            .map_err(|e| {
-                Error::Process(format!("Failed to connect to rdbg on port {}: {}", port, e))
-            })?;
+    /// [`RubyAdapter::spawn_with_timeouts`] in `cwd`, through Bundler or a
+    /// command as set in `options`
+    ///
+    /// With `use_bundler` and no `cwd`, rdbg runs in the Gemfile's directory.
+    pub async fn spawn_with_options(
+        program: &str,
+        program_args: &[String],
+        stop_on_entry: bool,
+        cwd: Option<&str>,
+        options: &RubyLaunchOptions,
+        timeouts: &AdapterTimeouts,
+    ) -> Result<RubyDebugSession> {
+        let cwd = match cwd {
+            None if options.use_bundler => Some(gemfile_root(program, None)?),
+            cwd => cwd.map(PathBuf::from),
+        };
+
+        // Old debug gems fail in confusing ways in socket mode; refuse them up front
+        // (the bundle may pin another version, which rdbg then reports itself)
+        let version = Self::verify_version().await?;
+
+        // Spawn rdbg on a unix socket path (or a free port) and connect; on
+        // an endpoint it could not listen on, again on a new one
+        let spawned = socket_helper::spawn_socket_adapter(
+            "rdbg",
+            timeouts,
+            || AdapterEndpoint::prefer_unix("rdbg"),
+            |endpoint| {
+                let (executable, args) =
+                    options.rdbg_command(program, program_args, stop_on_entry, endpoint);
+                info!("Spawning rdbg on {}: {} {:?}", endpoint, executable, args);
+                let mut command = Command::new(&executable);
+                command.args(&args).stderr(Stdio::piped());
+                if let Some(cwd) = &cwd {
+                    command.current_dir(cwd);
+                }
+                command
+                    .spawn()
+                    .map_err(|e| Error::Process(format!("Failed to spawn {}: {}", executable, e)))
+            },
+        )
+        .await?;
 
         Ok(RubyDebugSession {
-            process: child,
-            socket,
-            port,
+            process: spawned.child,
+            socket: spawned.stream,
+            endpoint: spawned.endpoint,
+            version,
         })
     }
 
@@ -101,6 +294,31 @@ impl RubyAdapter {
 
         launch
     }
+
+    /// Configuration for a new connection to an rdbg that is already running
+    /// the program (rdbg keeps listening after a client goes away)
+    pub fn attach_args() -> Value {
+        json!({
+            "request": "attach",
+            "type": "ruby",
+            "localfs": true,
+        })
+    }
+}
+
+impl AdapterHealthCheck for RubyAdapter {
+    fn health_check(&self) -> AdapterHealth {
+        AdapterHealth::new(
+            "ruby",
+            Self::adapter_id(),
+            vec![HealthCheck::command(
+                "rdbg",
+                &Self::command(),
+                &["--version"],
+            )],
+            "gem install debug (Ruby 3.1+ ships it as a default gem)",
+        )
+    }
 }
 
 // ============================================================================
@@ -117,7 +335,11 @@ impl DebugAdapterLogger for RubyAdapter {
     }
 
     fn transport_type(&self) -> &str {
-        "TCP Socket"
+        if cfg!(unix) {
+            "Unix Socket"
+        } else {
+            "TCP Socket"
+        }
     }
 
     fn adapter_id(&self) -> &str {
@@ -125,8 +347,16 @@ impl DebugAdapterLogger for RubyAdapter {
     }
 
     fn command_line(&self) -> String {
-        // Port is allocated dynamically, show template
-        "rdbg --open --port <PORT> [--stop-at-load|--nonstop] <program> [args...]".to_string()
+        // Socket path or port is allocated dynamically, show template
+        let endpoint = if cfg!(unix) {
+            "--sock-path=<PATH>"
+        } else {
+            "--port <PORT>"
+        };
+        format!(
+            "rdbg --open {} [--stop-at-load|--nonstop] <program> [args...]",
+            endpoint
+        )
     }
 
     fn requires_workaround(&self) -> bool {
@@ -158,7 +388,7 @@ impl DebugAdapterLogger for RubyAdapter {
 
     fn log_connection_error(&self, error: &dyn std::error::Error) {
         error!("❌ [RUBY] Socket connection failed: {}", error);
-        error!("   Transport: TCP Socket");
+        error!("   Transport: {}", self.transport_type());
         error!("   Timeout: 2 seconds");
         error!("   ");
         error!("   Possible causes:");
@@ -196,8 +426,7 @@ impl DebugAdapterLogger for RubyAdapter {
 /// Helper to log Ruby-specific connection success with port information
 impl RubyDebugSession {
     pub fn log_connection_success_with_port(&self) {
-        info!("✅ [RUBY] Connected to rdbg on port {}", self.port);
-        info!("   Socket: localhost:{}", self.port);
+        info!("✅ [RUBY] Connected to rdbg on {}", self.endpoint);
         info!("   Process ID: {:?}", self.process.id());
     }
 }
@@ -206,6 +435,24 @@ impl RubyDebugSession {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_check_version() {
+        assert_eq!(
+            RubyAdapter::check_version("rdbg 1.9.2\n"),
+            Ok("1.9.2".to_string())
+        );
+        assert_eq!(
+            RubyAdapter::check_version("rdbg 1.10.0"),
+            Ok("1.10.0".to_string())
+        );
+
+        let too_old = RubyAdapter::check_version("rdbg 1.3.4").unwrap_err();
+        assert!(too_old.contains("rdbg 1.3.4 is too old"), "{}", too_old);
+        assert!(too_old.contains("gem install debug"));
+
+        assert!(RubyAdapter::check_version("command not found").is_err());
+    }
+
     #[test]
     fn test_command() {
         assert_eq!(RubyAdapter::command(), "rdbg");
@@ -243,6 +490,93 @@ mod tests {
         assert_eq!(launch["stopOnEntry"], false);
     }
 
+    #[test]
+    fn test_rdbg_command_with_bundler_and_command() {
+        let plain = RubyLaunchOptions::default().rdbg_command(
+            "/app/a.rb",
+            &[],
+            true,
+            &AdapterEndpoint::Tcp(1234),
+        );
+        assert_eq!(
+            plain,
+            (
+                "rdbg".to_string(),
+                vec!["--open", "--port", "1234", "--stop-at-load", "/app/a.rb"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect()
+            )
+        );
+
+        let options = RubyLaunchOptions {
+            use_bundler: true,
+            command: vec!["rspec".to_string()],
+        };
+        let (executable, args) = options.rdbg_command(
+            "spec/foo_spec.rb",
+            &["--seed".to_string(), "1; rm -rf /".to_string()],
+            false,
+            &AdapterEndpoint::Tcp(1234),
+        );
+        assert_eq!(executable, "bundle");
+        assert_eq!(
+            args,
+            vec![
+                "exec",
+                "rdbg",
+                "--open",
+                "--port",
+                "1234",
+                "--nonstop",
+                "-c",
+                "--",
+                "rspec",
+                "spec/foo_spec.rb",
+                "--seed",
+                "1; rm -rf /"
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rdbg_command_on_unix_socket() {
+        let endpoint = AdapterEndpoint::Unix(PathBuf::from("/tmp/rdbg.sock"));
+        let (_, args) =
+            RubyLaunchOptions::default().rdbg_command("/app/a.rb", &[], false, &endpoint);
+        assert_eq!(
+            args,
+            vec![
+                "--open",
+                "--sock-path=/tmp/rdbg.sock",
+                "--nonstop",
+                "/app/a.rb"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gemfile_root_walks_up() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Gemfile"),
+            "source 'https://rubygems.org'\n",
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("spec")).unwrap();
+        let spec = dir.path().join("spec").join("foo_spec.rb");
+
+        assert_eq!(
+            gemfile_root(spec.to_str().unwrap(), None).unwrap(),
+            dir.path()
+        );
+
+        let elsewhere = tempfile::tempdir().unwrap();
+        let missing = gemfile_root("/x/a.rb", elsewhere.path().to_str());
+        assert!(matches!(missing, Err(Error::InvalidRequest(_))));
+    }
+
     #[test]
     fn test_launch_args_empty_args() {
         let program = "test.rb";
@@ -30,10 +30,12 @@
 //! - Compile: `rustc -g fizzbuzz.rs -o target/debug/fizzbuzz`
 //! - Output: `/workspace/target/debug/fizzbuzz`
 //!
-//! **Phase 2: Cargo project support** (future)
+//! **Phase 2: Cargo project support**
 //! - Detect Cargo.toml
 //! - Run: `cargo build`
 //! - Parse metadata for binary path
+//! - Tests (`cargoTarget: "test"`): `cargo test --no-run`, then run the
+//!   binary containing `testFilter` with `<test> --exact --nocapture`
 //!
 //! # Key Differences from Other Languages
 //!
@@ -49,13 +51,17 @@
 //! - `docs/RUST_DEBUGGING_RESEARCH_AND_PROPOSAL.md` - Architecture and research
 //! - https://github.com/vadimcn/codelldb - CodeLLDB debugger
 
+use super::diagnostics::{self, CompilerDiagnostic};
+use super::health::{AdapterHealth, AdapterHealthCheck, HealthCheck};
 use super::logging::DebugAdapterLogger;
 use super::security;
-use crate::dap::socket_helper;
+use crate::dap::socket_helper::{self, AdapterTimeouts};
 use crate::{Error, Result};
 use serde_json::{json, Value};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
-use std::time::Duration;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
 use tokio::net::TcpStream;
 use tokio::process::{Child, Command};
 use tracing::{debug, error, info};
@@ -95,6 +101,178 @@ pub enum CargoTargetType {
     Example(String),
 }
 
+/// Result of compiling a Rust program for debugging
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompileOutcome {
+    /// Path to the executable to launch
+    pub binary: String,
+    /// True when nothing was rebuilt (binary newer than the source, or
+    /// cargo reported the artifact as fresh)
+    pub fresh: bool,
+    /// Time spent compiling, or checking that no compilation was needed
+    pub duration: Duration,
+    /// Warnings (and other non-fatal messages) reported by the compiler
+    pub diagnostics: Vec<CompilerDiagnostic>,
+}
+
+impl CompileOutcome {
+    /// JSON form used in the debugger_start result and session resource
+    pub fn to_json(&self) -> Value {
+        json!({
+            "binary": self.binary,
+            "fresh": self.fresh,
+            "durationMs": self.duration.as_millis() as u64,
+            "diagnostics": self.diagnostics,
+        })
+    }
+}
+
+/// Package and target that own a source file, resolved with `cargo metadata`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CargoPackageTarget {
+    /// Package name, passed as `-p <package>`
+    pub package: String,
+    /// Binary or example to build (`--bin <name>` / `--example <name>`)
+    pub target: CargoTargetType,
+    /// Target name
+    pub name: String,
+}
+
+impl CargoPackageTarget {
+    /// cargo build arguments selecting exactly this target
+    pub fn cargo_args(&self) -> Vec<String> {
+        let flag = match self.target {
+            CargoTargetType::Example(_) => "--example",
+            _ => "--bin",
+        };
+        vec![
+            "-p".to_string(),
+            self.package.clone(),
+            flag.to_string(),
+            self.name.clone(),
+        ]
+    }
+}
+
+/// CodeLLDB expression evaluator (`expressions` launch setting)
+///
+/// "simple" (CodeLLDB's default) and "python" show values through the Rust
+/// formatters, so a `Vec<i32>` evaluates to its elements. "native" uses
+/// LLDB's own expression parser, which can call functions but shows Rust
+/// collections as raw structs and pointers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LldbExpressions {
+    Simple,
+    Python,
+    Native,
+}
+
+impl LldbExpressions {
+    pub const NAMES: &'static [&'static str] = &["simple", "python", "native"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "simple" => Some(Self::Simple),
+            "python" => Some(Self::Python),
+            "native" => Some(Self::Native),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        Self::NAMES[self as usize]
+    }
+}
+
+/// Where CodeLLDB runs the debuggee (`terminal` launch setting)
+///
+/// "console" (the default here) gives the program no terminal, so it has no
+/// input unless stdin is redirected with `stdio`. "integrated" and "external" ask the
+/// client to start it in a terminal through a `runInTerminal` request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LldbTerminal {
+    Console,
+    Integrated,
+    External,
+}
+
+impl LldbTerminal {
+    pub const NAMES: &'static [&'static str] = &["console", "integrated", "external"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "console" => Some(Self::Console),
+            "integrated" => Some(Self::Integrated),
+            "external" => Some(Self::External),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        Self::NAMES[self as usize]
+    }
+}
+
+/// Evaluator, LLDB commands and process setup for CodeLLDB launches (Rust,
+/// C and C++)
+///
+/// `init_commands` run before the debug target is created, so they can load
+/// formatters (`command script import ...`); `pre_run_commands` run just
+/// before the program is launched. `stdio` holds the files for stdin, stdout
+/// and stderr in that order, `None` keeping the default.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LldbLaunchOptions {
+    pub expressions: Option<LldbExpressions>,
+    pub init_commands: Vec<String>,
+    pub pre_run_commands: Vec<String>,
+    pub env: BTreeMap<String, String>,
+    pub stdio: Vec<Option<String>>,
+    pub terminal: Option<LldbTerminal>,
+}
+
+impl LldbLaunchOptions {
+    /// Add the options to a launch configuration from [`RustAdapter::launch_args`]
+    pub fn apply(&self, launch: &mut Value) {
+        if let Some(expressions) = self.expressions {
+            launch["expressions"] = json!(expressions.name());
+        }
+        if !self.init_commands.is_empty() {
+            launch["initCommands"] = json!(self.init_commands);
+        }
+        if !self.pre_run_commands.is_empty() {
+            launch["preRunCommands"] = json!(self.pre_run_commands);
+        }
+        if !self.env.is_empty() {
+            launch["env"] = json!(self.env);
+        }
+        if !self.stdio.is_empty() {
+            let mut stdio = self.stdio.clone();
+            stdio.resize(3, None);
+            launch["stdio"] = json!(stdio);
+        }
+        if let Some(terminal) = self.terminal {
+            launch["terminal"] = json!(terminal.name());
+        }
+    }
+}
+
+impl CargoTargetType {
+    /// Parse a `cargoTarget` value: "binary", "test" or "example:<name>"
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "binary" => Ok(Self::Binary),
+            "test" => Ok(Self::Test),
+            _ => match value.strip_prefix("example:") {
+                Some(name) if !name.is_empty() => Ok(Self::Example(name.to_string())),
+                _ => Err(Error::InvalidRequest(format!(
+                    "Invalid cargoTarget '{}': expected \"binary\", \"test\" or \"example:<name>\"",
+                    value
+                ))),
+            },
+        }
+    }
+}
+
 impl RustAdapter {
     /// Get CodeLLDB command path
     ///
@@ -103,16 +281,29 @@ impl RustAdapter {
     /// 2. /usr/local/bin/codelldb (Docker container - old location)
     /// 3. /usr/bin/codelldb (system install)
     /// 4. codelldb (in PATH)
+    ///
+    /// On Windows the locations are those of [`RustAdapter::windows_locations`]
+    /// instead.
     pub fn command() -> String {
-        let locations = vec![
-            "/usr/local/lib/codelldb/adapter/codelldb",
-            "/usr/local/bin/codelldb",
-            "/usr/bin/codelldb",
-        ];
+        let locations: Vec<PathBuf> = if cfg!(windows) {
+            Self::windows_locations(
+                std::env::var_os("LOCALAPPDATA"),
+                std::env::var_os("USERPROFILE"),
+            )
+        } else {
+            [
+                "/usr/local/lib/codelldb/adapter/codelldb",
+                "/usr/local/bin/codelldb",
+                "/usr/bin/codelldb",
+            ]
+            .iter()
+            .map(PathBuf::from)
+            .collect()
+        };
 
         for location in locations {
-            if Path::new(location).exists() {
-                return location.to_string();
+            if location.exists() {
+                return location.to_string_lossy().to_string();
             }
         }
 
@@ -120,6 +311,41 @@ impl RustAdapter {
         "codelldb".to_string()
     }
 
+    /// Where CodeLLDB is installed on Windows
+    ///
+    /// 1. %LOCALAPPDATA%\Programs\codelldb\adapter\codelldb.exe (standalone install)
+    /// 2. %USERPROFILE%\.vscode\extensions\vadimcn.vscode-lldb-*\adapter\codelldb.exe
+    ///    (the VS Code extension, newest version first)
+    fn windows_locations(
+        local_app_data: Option<std::ffi::OsString>,
+        user_profile: Option<std::ffi::OsString>,
+    ) -> Vec<PathBuf> {
+        let adapter = |dir: PathBuf| dir.join("adapter").join("codelldb.exe");
+        let mut locations: Vec<PathBuf> = local_app_data
+            .map(|dir| adapter(PathBuf::from(dir).join("Programs").join("codelldb")))
+            .into_iter()
+            .collect();
+        if let Some(profile) = user_profile {
+            let extensions = PathBuf::from(profile).join(".vscode").join("extensions");
+            let mut installed: Vec<PathBuf> = std::fs::read_dir(&extensions)
+                .map(|entries| {
+                    entries
+                        .flatten()
+                        .map(|entry| entry.path())
+                        .filter(|path| {
+                            path.file_name()
+                                .and_then(|name| name.to_str())
+                                .is_some_and(|name| name.starts_with("vadimcn.vscode-lldb-"))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            installed.sort();
+            locations.extend(installed.into_iter().rev().map(adapter));
+        }
+        locations
+    }
+
     /// Get CodeLLDB args for STDIO mode
     ///
     /// Returns: [] (empty)
@@ -156,33 +382,40 @@ impl RustAdapter {
     ///
     /// RustDebugSession with spawned process, connected socket, and port number
     pub async fn spawn(
+        binary_path: &str,
+        args: &[String],
+        stop_on_entry: bool,
+    ) -> Result<RustDebugSession> {
+        Self::spawn_with_timeouts(
+            binary_path,
+            args,
+            stop_on_entry,
+            &AdapterTimeouts::default(),
+        )
+        .await
+    }
+
+    /// [`RustAdapter::spawn`] waiting up to `timeouts.connect` for CodeLLDB to listen
+    pub async fn spawn_with_timeouts(
         _binary_path: &str,
         _args: &[String],
         _stop_on_entry: bool,
+        timeouts: &AdapterTimeouts,
     ) -> Result<RustDebugSession> {
-        // 1. Find free port
-        let port = socket_helper::find_free_port()?;
-
-        // 2. Build codelldb command args (TCP mode as per nvim-dap)
-        let args = vec!["--port".to_string(), port.to_string()];
-
-        info!("Spawning codelldb on port {}: codelldb {:?}", port, args);
-
-        // 3. Spawn codelldb process
-        let child = Command::new(Self::command())
-            .args(&args)
-            .spawn()
-            .map_err(|e| Error::Process(format!("Failed to spawn codelldb: {}", e)))?;
-
-        // 4. Connect to socket (with 3 second timeout - CodeLLDB needs a moment to start)
-        let socket = socket_helper::connect_with_retry(port, Duration::from_secs(3))
-            .await
-            .map_err(|e| {
-                Error::Process(format!(
-                    "Failed to connect to codelldb on port {}: {}",
-                    port, e
-                ))
-            })?;
+        // Spawn codelldb on a free port (TCP mode as per nvim-dap) and connect
+        // (CodeLLDB needs a moment to start); on a port lost to another
+        // process, again on a new one
+        let (child, socket, port) =
+            socket_helper::spawn_tcp_adapter("codelldb", timeouts, |port| {
+                let args = vec!["--port".to_string(), port.to_string()];
+                info!("Spawning codelldb on port {}: codelldb {:?}", port, args);
+                Command::new(Self::command())
+                    .args(&args)
+                    .stderr(Stdio::piped())
+                    .spawn()
+                    .map_err(|e| Error::Process(format!("Failed to spawn codelldb: {}", e)))
+            })
+            .await?;
 
         Ok(RustDebugSession {
             process: child,
@@ -350,6 +583,128 @@ impl RustAdapter {
         )))
     }
 
+    /// Parse Cargo JSON output to find every test executable
+    ///
+    /// `cargo test --no-run` builds one test binary per target (lib unit
+    /// tests, each bin's unit tests, each file in tests/), in build order.
+    pub fn parse_cargo_test_executables(json_output: &str) -> Vec<String> {
+        json_output
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+            .filter(|artifact| {
+                artifact["reason"] == "compiler-artifact"
+                    && artifact["profile"]["test"].as_bool().unwrap_or(false)
+            })
+            .filter_map(|artifact| artifact["executable"].as_str().map(str::to_string))
+            .collect()
+    }
+
+    /// Parse the output of `<test binary> --list --format terse`
+    ///
+    /// Each test is listed as `<path>: test`; benchmarks are skipped.
+    pub fn parse_test_list(output: &str) -> Vec<String> {
+        output
+            .lines()
+            .filter_map(|line| line.strip_suffix(": test"))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Resolve a test filter to a full test path
+    ///
+    /// The filter matches a test by its full path (`tests::test_serialization`)
+    /// or by its last segments (`test_serialization`). Returns `None` when no
+    /// test, or more than one, matches.
+    pub fn select_test(tests: &[String], filter: &str) -> Option<String> {
+        if let Some(exact) = tests.iter().find(|name| *name == filter) {
+            return Some(exact.clone());
+        }
+
+        let suffix = format!("::{}", filter);
+        let mut matches = tests.iter().filter(|name| name.ends_with(&suffix));
+        match (matches.next(), matches.next()) {
+            (Some(name), None) => Some(name.clone()),
+            _ => None,
+        }
+    }
+
+    /// Program arguments that run exactly one test, with output shown
+    pub fn test_args(test_name: &str) -> Vec<String> {
+        vec![
+            test_name.to_string(),
+            "--exact".to_string(),
+            "--nocapture".to_string(),
+        ]
+    }
+
+    /// Cargo project root for a source file, for `cargoTarget`
+    pub fn cargo_root(source_path: &str) -> Result<String> {
+        match Self::detect_project_type(source_path)? {
+            RustProjectType::CargoProject { root, .. } => root
+                .to_str()
+                .map(str::to_string)
+                .ok_or_else(|| Error::Compilation("Non-UTF8 Cargo root path".to_string())),
+            RustProjectType::SingleFile(_) => Err(Error::InvalidRequest(format!(
+                "cargoTarget requires a source file inside a Cargo project (src/, tests/, ...): {}",
+                source_path
+            ))),
+        }
+    }
+
+    /// Compile a Cargo project's tests and pick the binary to debug
+    ///
+    /// Without a filter the first test binary is returned with no arguments.
+    /// With a filter, each test binary is asked for its test list and the one
+    /// containing the test is returned, along with the arguments that run
+    /// only that test (`<full name> --exact --nocapture`).
+    pub async fn compile_cargo_test(
+        cargo_root: &str,
+        test_filter: Option<&str>,
+    ) -> Result<(CompileOutcome, Vec<String>)> {
+        let started = Instant::now();
+        let stdout = Self::run_cargo(cargo_root, &CargoTargetType::Test, false, &[]).await?;
+        let executables = Self::parse_cargo_test_executables(&stdout);
+        let outcome = |binary: String| CompileOutcome {
+            fresh: Self::is_artifact_fresh(&stdout, &binary),
+            binary,
+            duration: started.elapsed(),
+            diagnostics: diagnostics::parse_cargo_messages(&stdout, Path::new(cargo_root)),
+        };
+
+        let Some(filter) = test_filter else {
+            let executable = executables.into_iter().next().ok_or_else(|| {
+                Error::Compilation("No test executable found in cargo output".to_string())
+            })?;
+            info!("✅ [RUST] Test binary: {}", executable);
+            return Ok((outcome(executable), vec![]));
+        };
+
+        let mut available = Vec::new();
+        for executable in executables {
+            let output = Command::new(&executable)
+                .args(["--list", "--format", "terse"])
+                .current_dir(cargo_root)
+                .output()
+                .await
+                .map_err(|e| {
+                    Error::Compilation(format!("Failed to list tests in {}: {}", executable, e))
+                })?;
+            let tests = Self::parse_test_list(&String::from_utf8_lossy(&output.stdout));
+
+            if let Some(test_name) = Self::select_test(&tests, filter) {
+                info!("✅ [RUST] Test '{}' is in {}", test_name, executable);
+                return Ok((outcome(executable), Self::test_args(&test_name)));
+            }
+            available.extend(tests);
+        }
+
+        Err(Error::InvalidRequest(format!(
+            "No single test matches testFilter '{}'. Available tests: {}",
+            filter,
+            available.join(", ")
+        )))
+    }
+
     /// Compile Cargo project
     ///
     /// Runs `cargo build` with JSON output and parses the executable path.
@@ -393,6 +748,53 @@ impl RustAdapter {
         cargo_root: &str,
         target_type: &CargoTargetType,
         release: bool,
+    ) -> Result<String> {
+        Self::compile_cargo_target(cargo_root, target_type, release)
+            .await
+            .map(|outcome| outcome.binary)
+    }
+
+    /// Compile Cargo project, reporting whether cargo rebuilt anything
+    pub async fn compile_cargo_target(
+        cargo_root: &str,
+        target_type: &CargoTargetType,
+        release: bool,
+    ) -> Result<CompileOutcome> {
+        let started = Instant::now();
+        let stdout = Self::run_cargo(cargo_root, target_type, release, &[]).await?;
+        let executable = Self::parse_cargo_executable(&stdout, target_type)?;
+
+        info!("✅ [RUST] Cargo build successful: {}", executable);
+
+        Ok(CompileOutcome {
+            fresh: Self::is_artifact_fresh(&stdout, &executable),
+            binary: executable,
+            duration: started.elapsed(),
+            diagnostics: diagnostics::parse_cargo_messages(&stdout, Path::new(cargo_root)),
+        })
+    }
+
+    /// Whether cargo reported the artifact producing `executable` as fresh
+    /// (`"fresh": true`: up to date, nothing recompiled)
+    pub fn is_artifact_fresh(json_output: &str, executable: &str) -> bool {
+        json_output
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+            .find(|artifact| {
+                artifact["reason"] == "compiler-artifact" && artifact["executable"] == executable
+            })
+            .and_then(|artifact| artifact["fresh"].as_bool())
+            .unwrap_or(false)
+    }
+
+    /// Run the cargo build for a target and return its JSON output
+    ///
+    /// `extra_args` narrows the build, e.g. `-p <package> --bin <name>`.
+    async fn run_cargo(
+        cargo_root: &str,
+        target_type: &CargoTargetType,
+        release: bool,
+        extra_args: &[String],
     ) -> Result<String> {
         // Validate and sanitize the cargo root directory (prevents path traversal)
         let cargo_root_path = security::validate_directory_path(cargo_root)?;
@@ -437,6 +839,8 @@ impl RustAdapter {
             }
         }
 
+        cmd.args(extra_args);
+
         if release {
             cmd.arg("--release");
         }
@@ -456,19 +860,212 @@ impl RustAdapter {
             let stderr = String::from_utf8_lossy(&output.stderr);
             error!("❌ [RUST] Cargo build failed");
             error!("❌ [RUST] stderr:\n{}", stderr);
+
+            // compiler-message records are on stdout even when the build fails
+            let diagnostics = diagnostics::parse_cargo_messages(
+                &String::from_utf8_lossy(&output.stdout),
+                Path::new(cargo_root),
+            );
+            if diagnostics.iter().any(CompilerDiagnostic::is_error) {
+                return Err(diagnostics::compilation_error(diagnostics, &stderr));
+            }
             return Err(Error::Compilation(format!(
                 "Cargo build failed:\n{}",
                 stderr
             )));
         }
 
-        // Parse JSON output to find executable
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let executable = Self::parse_cargo_executable(&stdout, target_type)?;
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
 
-        info!("✅ [RUST] Cargo build successful: {}", executable);
+    /// Find the package and target that own `source` in `cargo metadata` output
+    ///
+    /// A source file that is a target's root (src/main.rs, src/bin/tool.rs,
+    /// examples/demo.rs) selects that target. Any other file selects the
+    /// binary of the package whose directory contains it, which must be
+    /// unambiguous. In a workspace this keeps each member's main.rs building
+    /// and debugging its own binary.
+    pub fn select_cargo_target(metadata: &Value, source: &Path) -> Result<CargoPackageTarget> {
+        let packages = metadata["packages"].as_array().cloned().unwrap_or_default();
+        let canonical = |path: &str| {
+            let path = PathBuf::from(path);
+            path.canonicalize().unwrap_or(path)
+        };
+        let is_kind = |target: &Value, kind: &str| {
+            target["kind"]
+                .as_array()
+                .is_some_and(|kinds| kinds.iter().any(|k| k == kind))
+        };
+
+        // 1. The source file is the root of a binary or example target
+        for package in &packages {
+            for target in package["targets"].as_array().into_iter().flatten() {
+                let Some(src_path) = target["src_path"].as_str() else {
+                    continue;
+                };
+                if canonical(src_path) != source {
+                    continue;
+                }
+                let name = target["name"].as_str().unwrap_or_default().to_string();
+                let target_type = if is_kind(target, "bin") {
+                    CargoTargetType::Binary
+                } else if is_kind(target, "example") {
+                    CargoTargetType::Example(name.clone())
+                } else {
+                    // e.g. src/lib.rs: fall through to the package's binary
+                    break;
+                };
+                return Ok(CargoPackageTarget {
+                    package: package["name"].as_str().unwrap_or_default().to_string(),
+                    target: target_type,
+                    name,
+                });
+            }
+        }
+
+        // 2. Otherwise, the binary of the innermost package containing the file
+        let owner = packages
+            .iter()
+            .filter_map(|package| {
+                let manifest = canonical(package["manifest_path"].as_str()?);
+                let dir = manifest.parent()?.to_path_buf();
+                source.starts_with(&dir).then_some((dir, package))
+            })
+            .max_by_key(|(dir, _)| dir.components().count())
+            .map(|(_, package)| package)
+            .ok_or_else(|| {
+                Error::InvalidRequest(format!(
+                    "{} is not part of any package in this Cargo workspace",
+                    source.display()
+                ))
+            })?;
+
+        let package_name = owner["name"].as_str().unwrap_or_default().to_string();
+        let bins: Vec<&str> = owner["targets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|target| is_kind(target, "bin"))
+            .filter_map(|target| target["name"].as_str())
+            .collect();
+
+        match bins.as_slice() {
+            [name] => Ok(CargoPackageTarget {
+                package: package_name,
+                target: CargoTargetType::Binary,
+                name: name.to_string(),
+            }),
+            [] => Err(Error::InvalidRequest(format!(
+                "{} belongs to package '{}', which has no binary target. Use cargoTarget \"test\" or \"example:<name>\" instead",
+                source.display(),
+                package_name
+            ))),
+            _ => Err(Error::InvalidRequest(format!(
+                "{} belongs to package '{}', which has several binaries ({}). Pass the main file of the binary to debug as 'program'",
+                source.display(),
+                package_name,
+                bins.join(", ")
+            ))),
+        }
+    }
+
+    /// Resolve the package and target owning a source file via `cargo metadata`
+    pub async fn resolve_cargo_target(
+        cargo_root: &str,
+        source_path: &str,
+    ) -> Result<CargoPackageTarget> {
+        let source = security::validate_source_path(source_path, Some("rs"))?;
+
+        let output = Command::new("cargo")
+            .args(["metadata", "--format-version", "1", "--no-deps"])
+            .current_dir(cargo_root)
+            .output()
+            .await
+            .map_err(|e| {
+                Error::Compilation(format!(
+                    "Failed to execute cargo metadata: {}. Is cargo installed?",
+                    e
+                ))
+            })?;
+        if !output.status.success() {
+            return Err(Error::Compilation(format!(
+                "cargo metadata failed:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        let metadata: Value = serde_json::from_slice(&output.stdout)
+            .map_err(|e| Error::Compilation(format!("Invalid cargo metadata output: {}", e)))?;
+        let target = Self::select_cargo_target(&metadata, &source)?;
+        info!(
+            "📦 [RUST] {} → package '{}', target '{}'",
+            source_path, target.package, target.name
+        );
+        Ok(target)
+    }
+
+    /// Build one package target and return its executable
+    ///
+    /// With `force_rebuild`, the package is cleaned first so cargo cannot
+    /// reuse its cached build.
+    pub async fn compile_package_target(
+        cargo_root: &str,
+        target: &CargoPackageTarget,
+        release: bool,
+        force_rebuild: bool,
+    ) -> Result<CompileOutcome> {
+        let started = Instant::now();
+        if force_rebuild {
+            info!(
+                "🧹 [RUST] Cleaning package '{}' before rebuild",
+                target.package
+            );
+            let output = Command::new("cargo")
+                .args(["clean", "-p", &target.package])
+                .current_dir(cargo_root)
+                .output()
+                .await
+                .map_err(|e| Error::Compilation(format!("Failed to execute cargo clean: {}", e)))?;
+            if !output.status.success() {
+                return Err(Error::Compilation(format!(
+                    "cargo clean failed:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                )));
+            }
+        }
+
+        let stdout =
+            Self::run_cargo(cargo_root, &target.target, release, &target.cargo_args()).await?;
+
+        // Only the selected target is built, but match its name to be sure
+        let executable = stdout
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+            .filter(|artifact| {
+                artifact["reason"] == "compiler-artifact"
+                    && artifact["target"]["name"] == target.name.as_str()
+                    && !artifact["profile"]["test"].as_bool().unwrap_or(false)
+            })
+            .find_map(|artifact| artifact["executable"].as_str().map(str::to_string))
+            .ok_or_else(|| {
+                Error::Compilation(format!(
+                    "No executable found for target '{}' of package '{}'",
+                    target.name, target.package
+                ))
+            })?;
 
-        Ok(executable)
+        let fresh = Self::is_artifact_fresh(&stdout, &executable);
+        info!(
+            "✅ [RUST] Cargo build successful ({}): {}",
+            if fresh { "fresh" } else { "rebuilt" },
+            executable
+        );
+        Ok(CompileOutcome {
+            binary: executable,
+            fresh,
+            duration: started.elapsed(),
+            diagnostics: diagnostics::parse_cargo_messages(&stdout, Path::new(cargo_root)),
+        })
     }
 
     /// Compile Rust source (auto-detects single-file vs Cargo project)
@@ -496,25 +1093,86 @@ impl RustAdapter {
     /// let binary = RustAdapter::compile("/workspace/cargo-simple/src/main.rs", false).await?;
     /// ```
     pub async fn compile(source_path: &str, release: bool) -> Result<String> {
+        Self::compile_with_options(source_path, release, false)
+            .await
+            .map(|outcome| outcome.binary)
+    }
+
+    /// Compile Rust source, skipping the build when it is up to date
+    ///
+    /// Single files are not recompiled while the binary in target/ is newer
+    /// than the source; Cargo projects rely on cargo's own caching. Pass
+    /// `force_rebuild` to compile regardless.
+    pub async fn compile_with_options(
+        source_path: &str,
+        release: bool,
+        force_rebuild: bool,
+    ) -> Result<CompileOutcome> {
         // Detect project type
         let project_type = Self::detect_project_type(source_path)?;
 
         match project_type {
-            RustProjectType::SingleFile(_) => {
+            RustProjectType::SingleFile(source) => {
+                let started = Instant::now();
+                let binary_path = Self::single_file_binary_path(&source, release)?;
+                if !force_rebuild && Self::is_binary_fresh(&source, &binary_path) {
+                    info!(
+                        "♻️  [RUST] Binary is newer than source, skipping rustc: {}",
+                        binary_path.display()
+                    );
+                    return Ok(CompileOutcome {
+                        binary: binary_path.to_string_lossy().to_string(),
+                        fresh: true,
+                        duration: started.elapsed(),
+                        diagnostics: vec![],
+                    });
+                }
+
                 info!("📄 [RUST] Compiling single file with rustc");
-                Self::compile_single_file(source_path, release).await
+                let (binary, diagnostics) = Self::rustc(source_path, release).await?;
+                Ok(CompileOutcome {
+                    binary,
+                    fresh: false,
+                    duration: started.elapsed(),
+                    diagnostics,
+                })
             }
             RustProjectType::CargoProject { root, .. } => {
                 info!("📦 [RUST] Compiling Cargo project");
                 let root_str = root
                     .to_str()
                     .ok_or_else(|| Error::Compilation("Non-UTF8 Cargo root path".to_string()))?;
-                // Default to building binary target
-                Self::compile_cargo_project(root_str, &CargoTargetType::Binary, release).await
+                // Build only the binary (or example) this source file belongs to
+                let target = Self::resolve_cargo_target(root_str, source_path).await?;
+                Self::compile_package_target(root_str, &target, release, force_rebuild).await
             }
         }
     }
 
+    /// Output path for a single-file build: `<source_dir>/target/<debug|release>/<name>`
+    /// (`<name>.exe` on Windows)
+    fn single_file_binary_path(source: &Path, release: bool) -> Result<PathBuf> {
+        let mut binary_name = source
+            .file_stem()
+            .ok_or_else(|| Error::Compilation("Invalid source filename".to_string()))?
+            .to_os_string();
+        binary_name.push(std::env::consts::EXE_SUFFIX);
+        let source_dir = source
+            .parent()
+            .ok_or_else(|| Error::Compilation("Cannot determine source directory".to_string()))?;
+        let build_type = if release { "release" } else { "debug" };
+        Ok(source_dir.join("target").join(build_type).join(binary_name))
+    }
+
+    /// Whether `binary` exists and was modified after `source`
+    pub fn is_binary_fresh(source: &Path, binary: &Path) -> bool {
+        let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        match (modified(source), modified(binary)) {
+            (Some(source_time), Some(binary_time)) => binary_time > source_time,
+            _ => false,
+        }
+    }
+
     /// Compile Rust source file to binary
     ///
     /// This compiles a single Rust source file using rustc.
@@ -536,6 +1194,13 @@ impl RustAdapter {
     /// // binary = "/workspace/target/debug/fizzbuzz"
     /// ```
     pub async fn compile_single_file(source_path: &str, release: bool) -> Result<String> {
+        Self::rustc(source_path, release)
+            .await
+            .map(|(binary, _)| binary)
+    }
+
+    /// Run rustc on a single file, returning the binary and its warnings
+    async fn rustc(source_path: &str, release: bool) -> Result<(String, Vec<CompilerDiagnostic>)> {
         // Validate and sanitize the source path (prevents path traversal)
         let source = security::validate_source_path(source_path, Some("rs"))?;
 
@@ -559,7 +1224,8 @@ impl RustAdapter {
             .await
             .map_err(|e| Error::Compilation(format!("Failed to create output directory: {}", e)))?;
 
-        let binary_path = output_dir.join(binary_name);
+        let binary_path =
+            output_dir.join(format!("{}{}", binary_name, std::env::consts::EXE_SUFFIX));
 
         info!("🔨 [RUST] Compiling: {}", source_path);
         info!("🔨 [RUST] Output: {}", binary_path.display());
@@ -569,6 +1235,8 @@ impl RustAdapter {
         let mut cmd = Command::new("rustc");
         cmd.arg(source_path);
         cmd.arg("-o").arg(&binary_path);
+        // Diagnostics as JSON lines on stderr
+        cmd.arg("--error-format=json");
 
         if release {
             // Release build: optimizations + debug symbols
@@ -588,12 +1256,13 @@ impl RustAdapter {
         })?;
 
         // Check compilation result
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let diagnostics = diagnostics::parse_rustc_messages(&stderr);
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(Error::Compilation(format!(
-                "Compilation failed:\n{}",
-                stderr
-            )));
+            return Err(diagnostics::compilation_error(
+                diagnostics,
+                &diagnostics::rendered_rustc_output(&stderr),
+            ));
         }
 
         let binary_path_str = binary_path
@@ -603,7 +1272,7 @@ impl RustAdapter {
 
         info!("✅ [RUST] Compilation successful: {}", binary_path_str);
 
-        Ok(binary_path_str)
+        Ok((binary_path_str, diagnostics))
     }
 
     /// Generate launch configuration for Rust debugging
@@ -617,6 +1286,10 @@ impl RustAdapter {
     /// * `cwd` - Working directory (optional)
     /// * `stop_on_entry` - Whether to stop at program entry point
     ///
+    /// The program runs without a terminal and with empty stdin;
+    /// [`LldbLaunchOptions::apply`] adds the environment and overrides
+    /// `stdio` and `terminal`.
+    ///
     /// # Note
     ///
     /// `binary_path` must be the compiled binary, not the source file!
@@ -640,6 +1313,8 @@ impl RustAdapter {
             "stdio": [null, null, null],
             // Explicitly set source path to help with breakpoint resolution
             "sourceMap": {".": "."},
+            // Enables the Rust formatters (String, Vec, HashMap summaries)
+            "sourceLanguages": ["rust"],
         });
 
         if let Some(cwd_path) = cwd {
@@ -648,6 +1323,58 @@ impl RustAdapter {
 
         launch
     }
+
+    /// Configuration that attaches CodeLLDB to the running `binary_path`,
+    /// found by its name
+    pub fn attach_args(binary_path: &str) -> Value {
+        json!({
+            "type": "lldb",
+            "request": "attach",
+            "program": binary_path,
+            "sourceLanguages": ["rust"],
+        })
+    }
+
+    /// Launch configuration that loads a core dump instead of running the program
+    ///
+    /// Uses CodeLLDB's custom launch: the target is created from the binary
+    /// and the core, and no process is started. The adapter reports the
+    /// crashed thread as stopped once configuration is done.
+    pub fn core_dump_args(binary_path: &str, core_dump: &str) -> Value {
+        json!({
+            "type": "lldb",
+            "request": "launch",
+            "custom": true,
+            "targetCreateCommands": [format!(
+                "target create {} --core {}",
+                quote_lldb_arg(binary_path),
+                quote_lldb_arg(core_dump)
+            )],
+            "processCreateCommands": [],
+            "sourceLanguages": ["rust"],
+        })
+    }
+}
+
+/// Quote a path for an LLDB command line
+fn quote_lldb_arg(arg: &str) -> String {
+    format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl AdapterHealthCheck for RustAdapter {
+    fn health_check(&self) -> AdapterHealth {
+        AdapterHealth::new(
+            "rust",
+            Self::adapter_id(),
+            vec![
+                HealthCheck::command("codelldb", &Self::command(), &["--version"]),
+                HealthCheck::command("rustc", "rustc", &["--version"]),
+                HealthCheck::command("cargo", "cargo", &["--version"]),
+            ],
+            "Install CodeLLDB from https://github.com/vadimcn/codelldb/releases \
+             (adapter/codelldb) and Rust via https://rustup.rs",
+        )
+    }
 }
 
 // ============================================================================
@@ -778,6 +1505,37 @@ mod tests {
         assert!(cmd.contains("codelldb"));
     }
 
+    #[test]
+    fn test_windows_locations() {
+        let profile = tempfile::tempdir().unwrap();
+        let extensions = profile.path().join(".vscode").join("extensions");
+        for name in [
+            "vadimcn.vscode-lldb-1.10.0",
+            "vadimcn.vscode-lldb-1.11.0",
+            "ms-python.python-2024.1.0",
+        ] {
+            std::fs::create_dir_all(extensions.join(name)).unwrap();
+        }
+
+        let locations = RustAdapter::windows_locations(
+            Some("C:\\Users\\me\\AppData\\Local".into()),
+            Some(profile.path().as_os_str().to_owned()),
+        );
+        let adapter = |dir: PathBuf| dir.join("adapter").join("codelldb.exe");
+        assert_eq!(
+            locations,
+            vec![
+                adapter(
+                    PathBuf::from("C:\\Users\\me\\AppData\\Local")
+                        .join("Programs")
+                        .join("codelldb")
+                ),
+                adapter(extensions.join("vadimcn.vscode-lldb-1.11.0")),
+                adapter(extensions.join("vadimcn.vscode-lldb-1.10.0")),
+            ]
+        );
+    }
+
     #[test]
     fn test_args() {
         let args = RustAdapter::args();
@@ -800,7 +1558,69 @@ mod tests {
         assert_eq!(config["program"], binary);
         assert_eq!(config["args"], json!([]));
         assert_eq!(config["stopOnEntry"], false);
+        assert_eq!(config["sourceLanguages"], json!(["rust"]));
         assert!(config["cwd"].is_null());
+        assert!(config["expressions"].is_null());
+    }
+
+    #[test]
+    fn test_core_dump_args() {
+        let config = RustAdapter::core_dump_args("/work/target/debug/app", "/tmp/my \"core\"");
+
+        assert_eq!(config["request"], "launch");
+        assert_eq!(config["custom"], true);
+        assert_eq!(
+            config["targetCreateCommands"][0],
+            r#"target create "/work/target/debug/app" --core "/tmp/my \"core\"""#
+        );
+        assert_eq!(config["processCreateCommands"], json!([]));
+        assert!(config.get("program").is_none());
+    }
+
+    #[test]
+    fn test_lldb_launch_options() {
+        let mut config = RustAdapter::launch_args("/workspace/target/debug/app", &[], None, false);
+        LldbLaunchOptions {
+            expressions: LldbExpressions::from_name("Native"),
+            init_commands: vec!["command script import /opt/formatters.py".to_string()],
+            pre_run_commands: vec![],
+            ..Default::default()
+        }
+        .apply(&mut config);
+
+        assert_eq!(config["expressions"], "native");
+        assert_eq!(
+            config["initCommands"],
+            json!(["command script import /opt/formatters.py"])
+        );
+        assert!(config["preRunCommands"].is_null());
+        assert_eq!(LldbExpressions::from_name("lua"), None);
+        assert_eq!(LldbExpressions::Python.name(), "python");
+        // Without process options the console defaults stay
+        assert_eq!(config["terminal"], "console");
+        assert_eq!(config["stdio"], json!([null, null, null]));
+        assert!(config["env"].is_null());
+    }
+
+    #[test]
+    fn test_lldb_process_options() {
+        let args = vec!["--name".to_string(), "a b".to_string()];
+        let mut config =
+            RustAdapter::launch_args("/work/target/debug/app", &args, Some("/work"), false);
+        LldbLaunchOptions {
+            env: BTreeMap::from([("RUST_LOG".to_string(), "debug".to_string())]),
+            stdio: vec![Some("/work/input.txt".to_string())],
+            terminal: LldbTerminal::from_name("Integrated"),
+            ..Default::default()
+        }
+        .apply(&mut config);
+
+        assert_eq!(config["args"], json!(["--name", "a b"]));
+        assert_eq!(config["cwd"], "/work");
+        assert_eq!(config["env"], json!({"RUST_LOG": "debug"}));
+        assert_eq!(config["stdio"], json!(["/work/input.txt", null, null]));
+        assert_eq!(config["terminal"], "integrated");
+        assert_eq!(LldbTerminal::from_name("tty"), None);
     }
 
     #[test]
@@ -828,6 +1648,177 @@ mod tests {
         assert_eq!(config["args"], json!(args));
     }
 
+    #[test]
+    fn test_cargo_target_parse() {
+        assert_eq!(
+            CargoTargetType::parse("binary").unwrap(),
+            CargoTargetType::Binary
+        );
+        assert_eq!(
+            CargoTargetType::parse("test").unwrap(),
+            CargoTargetType::Test
+        );
+        assert_eq!(
+            CargoTargetType::parse("example:demo").unwrap(),
+            CargoTargetType::Example("demo".to_string())
+        );
+        assert!(matches!(
+            CargoTargetType::parse("example:"),
+            Err(Error::InvalidRequest(_))
+        ));
+        assert!(matches!(
+            CargoTargetType::parse("bench"),
+            Err(Error::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_cargo_test_executables() {
+        let json_output = r#"{"reason":"compiler-artifact","target":{"kind":["lib"],"name":"app"},"profile":{"test":false},"executable":null}
+{"reason":"compiler-artifact","target":{"kind":["lib"],"name":"app"},"profile":{"test":true},"executable":"/p/target/debug/deps/app-111"}
+{"reason":"compiler-artifact","target":{"kind":["bin"],"name":"app"},"profile":{"test":false},"executable":"/p/target/debug/app"}
+{"reason":"compiler-artifact","target":{"kind":["test"],"name":"describe"},"profile":{"test":true},"executable":"/p/target/debug/deps/describe-222"}
+{"reason":"build-finished","success":true}"#;
+
+        assert_eq!(
+            RustAdapter::parse_cargo_test_executables(json_output),
+            vec![
+                "/p/target/debug/deps/app-111".to_string(),
+                "/p/target/debug/deps/describe-222".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_select_test_by_full_path_or_suffix() {
+        let tests = RustAdapter::parse_test_list(
+            "tests::test_serialization: test\nparser::tests::test_parse: test\nlexer::tests::test_parse: test\nbench_encode: bench\n",
+        );
+        assert_eq!(tests.len(), 3);
+
+        assert_eq!(
+            RustAdapter::select_test(&tests, "test_serialization").as_deref(),
+            Some("tests::test_serialization")
+        );
+        assert_eq!(
+            RustAdapter::select_test(&tests, "parser::tests::test_parse").as_deref(),
+            Some("parser::tests::test_parse")
+        );
+        // Ambiguous and unknown filters select nothing
+        assert_eq!(RustAdapter::select_test(&tests, "test_parse"), None);
+        assert_eq!(RustAdapter::select_test(&tests, "serialization"), None);
+
+        assert_eq!(
+            RustAdapter::test_args("tests::test_serialization"),
+            vec!["tests::test_serialization", "--exact", "--nocapture"]
+        );
+    }
+
+    #[test]
+    fn test_select_cargo_target_in_workspace() {
+        let metadata = json!({
+            "packages": [
+                {
+                    "name": "alpha",
+                    "manifest_path": "/ws/alpha/Cargo.toml",
+                    "targets": [
+                        {"name": "alpha", "kind": ["bin"], "src_path": "/ws/alpha/src/main.rs"}
+                    ]
+                },
+                {
+                    "name": "beta",
+                    "manifest_path": "/ws/beta/Cargo.toml",
+                    "targets": [
+                        {"name": "beta", "kind": ["bin"], "src_path": "/ws/beta/src/main.rs"},
+                        {"name": "beta-report", "kind": ["bin"], "src_path": "/ws/beta/src/bin/beta-report.rs"},
+                        {"name": "demo", "kind": ["example"], "src_path": "/ws/beta/examples/demo.rs"}
+                    ]
+                }
+            ]
+        });
+        let select = |path: &str| RustAdapter::select_cargo_target(&metadata, Path::new(path));
+
+        let alpha = select("/ws/alpha/src/main.rs").unwrap();
+        assert_eq!(alpha.package, "alpha");
+        assert_eq!(alpha.name, "alpha");
+        assert_eq!(alpha.cargo_args(), vec!["-p", "alpha", "--bin", "alpha"]);
+
+        // Any module of a single-binary package selects that binary
+        assert_eq!(select("/ws/alpha/src/util.rs").unwrap().name, "alpha");
+
+        assert_eq!(
+            select("/ws/beta/src/bin/beta-report.rs").unwrap().name,
+            "beta-report"
+        );
+        let example = select("/ws/beta/examples/demo.rs").unwrap();
+        assert_eq!(example.target, CargoTargetType::Example("demo".to_string()));
+        assert_eq!(
+            example.cargo_args(),
+            vec!["-p", "beta", "--example", "demo"]
+        );
+
+        // A module of a package with several binaries is ambiguous
+        match select("/ws/beta/src/format.rs") {
+            Err(Error::InvalidRequest(msg)) => {
+                assert!(msg.contains("beta, beta-report"), "{}", msg)
+            }
+            other => panic!("Expected InvalidRequest, got {:?}", other),
+        }
+        assert!(matches!(
+            select("/elsewhere/main.rs"),
+            Err(Error::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_is_artifact_fresh() {
+        let json_output = r#"{"reason":"compiler-artifact","target":{"kind":["bin"],"name":"app"},"executable":"/p/target/debug/app","fresh":true}
+{"reason":"compiler-artifact","target":{"kind":["bin"],"name":"tool"},"executable":"/p/target/debug/tool","fresh":false}"#;
+
+        assert!(RustAdapter::is_artifact_fresh(
+            json_output,
+            "/p/target/debug/app"
+        ));
+        assert!(!RustAdapter::is_artifact_fresh(
+            json_output,
+            "/p/target/debug/tool"
+        ));
+        assert!(!RustAdapter::is_artifact_fresh(
+            json_output,
+            "/p/target/debug/missing"
+        ));
+    }
+
+    #[test]
+    fn test_is_binary_fresh_compares_mtimes() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("main.rs");
+        let binary = dir.path().join("main");
+        std::fs::write(&source, "fn main() {}").unwrap();
+
+        // No binary yet
+        assert!(!RustAdapter::is_binary_fresh(&source, &binary));
+
+        std::fs::write(&binary, "").unwrap();
+        let earlier = std::time::SystemTime::now() - Duration::from_secs(60);
+        std::fs::File::options()
+            .write(true)
+            .open(&source)
+            .unwrap()
+            .set_modified(earlier)
+            .unwrap();
+        assert!(RustAdapter::is_binary_fresh(&source, &binary));
+
+        // Editing the source makes the binary stale
+        std::fs::File::options()
+            .write(true)
+            .open(&binary)
+            .unwrap()
+            .set_modified(earlier - Duration::from_secs(60))
+            .unwrap();
+        assert!(!RustAdapter::is_binary_fresh(&source, &binary));
+    }
+
     // Compilation tests require rustc installed
     #[tokio::test]
     #[ignore] // Only run when rustc is available
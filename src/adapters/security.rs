@@ -149,6 +149,122 @@ pub fn validate_directory_path(path_str: &str) -> Result<PathBuf> {
     Ok(canonical)
 }
 
+/// Directories the server may debug in (`--allowed-paths`)
+///
+/// Paths are resolved, symlinks included, before they are compared with the
+/// roots, so neither `..` nor a symlink pointing elsewhere leads outside.
+/// Without roots every path is allowed.
+#[derive(Debug, Clone, Default)]
+pub struct AllowedPaths {
+    roots: Vec<PathBuf>,
+}
+
+impl AllowedPaths {
+    /// Allow the directories `roots`, which must exist
+    pub fn new(roots: impl IntoIterator<Item = PathBuf>) -> Result<Self> {
+        let roots = roots
+            .into_iter()
+            .map(|root| {
+                let canonical = root.canonicalize().map_err(|e| {
+                    Error::InvalidRequest(format!(
+                        "Invalid allowed path '{}': {}",
+                        root.display(),
+                        e
+                    ))
+                })?;
+                if !canonical.is_dir() {
+                    return Err(Error::InvalidRequest(format!(
+                        "Allowed path is not a directory: '{}'",
+                        canonical.display()
+                    )));
+                }
+                Ok(canonical)
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { roots })
+    }
+
+    /// Whether only paths within the roots are allowed
+    pub fn is_restricted(&self) -> bool {
+        !self.roots.is_empty()
+    }
+
+    /// The allowed directories, canonicalized
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// Check that `path` lies within one of the roots
+    ///
+    /// Paths that do not exist (yet) are resolved through their nearest
+    /// existing ancestor. Returns the resolved path.
+    pub fn check(&self, path: impl AsRef<Path>) -> Result<PathBuf> {
+        let path = path.as_ref();
+        if !self.is_restricted() {
+            return Ok(path.to_path_buf());
+        }
+        if path.components().any(|c| c == Component::ParentDir) {
+            return Err(Error::PathNotAllowed(format!(
+                "'{}' contains a '..' component",
+                path.display()
+            )));
+        }
+        let resolved = resolve_path(path)?;
+        if self.roots.iter().any(|root| resolved.starts_with(root)) {
+            return Ok(resolved);
+        }
+        let roots: Vec<String> = self
+            .roots
+            .iter()
+            .map(|root| root.display().to_string())
+            .collect();
+        Err(Error::PathNotAllowed(format!(
+            "'{}' is outside the allowed paths ({})",
+            resolved.display(),
+            roots.join(", ")
+        )))
+    }
+}
+
+/// `path` made absolute with symlinks resolved; the part that does not exist
+/// is appended to its nearest existing ancestor
+fn resolve_path(path: &Path) -> Result<PathBuf> {
+    let absolute = if path.is_relative() {
+        std::env::current_dir()?.join(path)
+    } else {
+        path.to_path_buf()
+    };
+    let mut missing = Vec::new();
+    let mut current = absolute.as_path();
+    loop {
+        if let Ok(canonical) = current.canonicalize() {
+            return Ok(missing
+                .iter()
+                .rev()
+                .fold(canonical, |resolved, name| resolved.join(name)));
+        }
+        // A dangling symlink could be pointed anywhere later
+        if current.symlink_metadata().is_ok() {
+            return Err(Error::PathNotAllowed(format!(
+                "'{}' is a dangling symlink",
+                current.display()
+            )));
+        }
+        match (current.parent(), current.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                current = parent;
+            }
+            _ => {
+                return Err(Error::PathNotAllowed(format!(
+                    "'{}' cannot be resolved",
+                    path.display()
+                )))
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -209,4 +325,83 @@ mod tests {
 
         fs::remove_file(test_file).ok();
     }
+
+    #[test]
+    fn test_allowed_paths_accepts_paths_within_roots() {
+        let root = tempfile::tempdir().unwrap();
+        let program = root.path().join("app.py");
+        fs::write(&program, "print(1)").unwrap();
+        let allowed = AllowedPaths::new([root.path().to_path_buf()]).unwrap();
+
+        let canonical = program.canonicalize().unwrap();
+        assert_eq!(allowed.check(&program).unwrap(), canonical);
+        assert!(allowed.check(root.path()).is_ok());
+        // Files that do not exist yet resolve through their directory
+        assert_eq!(
+            allowed.check(root.path().join("build/out.bin")).unwrap(),
+            root.path().canonicalize().unwrap().join("build/out.bin")
+        );
+        // Without roots nothing is restricted
+        assert!(AllowedPaths::default().check("/etc/passwd").is_ok());
+    }
+
+    #[test]
+    fn test_allowed_paths_rejects_traversal_and_outside_paths() {
+        let parent = tempfile::tempdir().unwrap();
+        let root = parent.path().join("project");
+        fs::create_dir(&root).unwrap();
+        let secret = parent.path().join("secret.py");
+        fs::write(&secret, "").unwrap();
+        let allowed = AllowedPaths::new([root.clone()]).unwrap();
+
+        let traversal = root.join("..").join("secret.py");
+        assert!(matches!(
+            allowed.check(&traversal),
+            Err(Error::PathNotAllowed(_))
+        ));
+        assert!(matches!(
+            allowed.check(&secret),
+            Err(Error::PathNotAllowed(_))
+        ));
+        // A sibling sharing the root's name as a prefix is not inside it
+        let sibling = parent.path().join("project-other");
+        fs::create_dir(&sibling).unwrap();
+        assert!(matches!(
+            allowed.check(&sibling),
+            Err(Error::PathNotAllowed(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_allowed_paths_resolves_symlinks() {
+        let parent = tempfile::tempdir().unwrap();
+        let root = parent.path().join("project");
+        fs::create_dir(&root).unwrap();
+        let secret = parent.path().join("secret.py");
+        fs::write(&secret, "").unwrap();
+        let allowed = AllowedPaths::new([root.clone()]).unwrap();
+
+        // A link inside the root pointing out of it
+        std::os::unix::fs::symlink(&secret, root.join("escape.py")).unwrap();
+        assert!(matches!(
+            allowed.check(root.join("escape.py")),
+            Err(Error::PathNotAllowed(_))
+        ));
+        std::os::unix::fs::symlink(parent.path(), root.join("up")).unwrap();
+        assert!(matches!(
+            allowed.check(root.join("up/secret.py")),
+            Err(Error::PathNotAllowed(_))
+        ));
+        std::os::unix::fs::symlink(parent.path().join("missing"), root.join("dangling")).unwrap();
+        assert!(matches!(
+            allowed.check(root.join("dangling")),
+            Err(Error::PathNotAllowed(_))
+        ));
+
+        // A link from outside into the root is fine
+        let link = parent.path().join("link");
+        std::os::unix::fs::symlink(&root, &link).unwrap();
+        assert!(allowed.check(link.join("new.py")).is_ok());
+    }
 }
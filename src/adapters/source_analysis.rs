@@ -0,0 +1,851 @@
+//! Source analysis for entry breakpoints
+//!
+//! Some adapters don't honor `stopOnEntry` (rdbg in socket mode, vscode-js-debug's
+//! parent session), so we emulate it with a breakpoint on the first line that
+//! actually executes when the program starts. This module finds that line using
+//! per-language heuristics over the source text.
+//!
+//! The heuristics are intentionally conservative: they skip comments, imports and
+//! declarations (including whole function/class bodies, which only run when
+//! called) and report how confident they are, so callers can warn when they had
+//! to fall back to line 1.
+
+use serde::Serialize;
+use std::path::Path;
+use tracing::{info, warn};
+
+/// Languages with an entry line heuristic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceLanguage {
+    Ruby,
+    JavaScript,
+    Python,
+    Rust,
+    Go,
+}
+
+impl SourceLanguage {
+    /// Map an adapter type / session language to a source language
+    pub fn from_language(language: &str) -> Option<Self> {
+        match language {
+            "ruby" => Some(Self::Ruby),
+            "nodejs" | "javascript" => Some(Self::JavaScript),
+            "python" => Some(Self::Python),
+            "rust" => Some(Self::Rust),
+            "go" => Some(Self::Go),
+            _ => None,
+        }
+    }
+}
+
+/// How the entry line was determined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EntryLineConfidence {
+    /// Provided explicitly by the user (`entryLine`)
+    Override,
+    /// Found by the language heuristic
+    Detected,
+    /// Nothing executable was found (or the file was unreadable); line 1 is a guess
+    Fallback,
+}
+
+/// First executable line of a program, with how it was found
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryLine {
+    /// 1-indexed line number (DAP convention)
+    pub line: usize,
+    pub confidence: EntryLineConfidence,
+}
+
+impl EntryLine {
+    fn detected(index: usize) -> Self {
+        Self {
+            line: index + 1,
+            confidence: EntryLineConfidence::Detected,
+        }
+    }
+
+    fn fallback() -> Self {
+        Self {
+            line: 1,
+            confidence: EntryLineConfidence::Fallback,
+        }
+    }
+
+    /// A user-provided entry line
+    pub fn override_line(line: usize) -> Self {
+        Self {
+            line,
+            confidence: EntryLineConfidence::Override,
+        }
+    }
+
+    pub fn is_fallback(&self) -> bool {
+        self.confidence == EntryLineConfidence::Fallback
+    }
+}
+
+/// Resolve the entry line for a program, honoring a user override
+///
+/// Logs a warning when the heuristic had to fall back to line 1.
+pub fn resolve_entry_line(
+    language: SourceLanguage,
+    program_path: &str,
+    override_line: Option<usize>,
+) -> EntryLine {
+    if let Some(line) = override_line {
+        info!("  Using entry line override: {}", line);
+        return EntryLine::override_line(line);
+    }
+
+    let entry = find_first_executable_line(language, program_path);
+    if entry.is_fallback() {
+        warn!(
+            "⚠️  Could not detect first executable line in {}, using line 1",
+            program_path
+        );
+        warn!("   Pass entryLine to debugger_start if the program doesn't stop at entry");
+    } else {
+        info!("  First executable line detected: {}", entry.line);
+    }
+    entry
+}
+
+/// Find the first executable line of a source file
+pub fn find_first_executable_line(language: SourceLanguage, program_path: &str) -> EntryLine {
+    match std::fs::read_to_string(Path::new(program_path)) {
+        Ok(content) => first_executable_line(language, &content),
+        Err(e) => {
+            warn!("Could not read {} for line detection: {}", program_path, e);
+            EntryLine::fallback()
+        }
+    }
+}
+
+/// Find the first executable line in source text
+pub fn first_executable_line(language: SourceLanguage, content: &str) -> EntryLine {
+    let found = match language {
+        SourceLanguage::Ruby => ruby(content),
+        SourceLanguage::JavaScript => javascript(content),
+        SourceLanguage::Python => python(content),
+        SourceLanguage::Rust => rust(content),
+        SourceLanguage::Go => go(content),
+    };
+    found.map_or_else(EntryLine::fallback, EntryLine::detected)
+}
+
+/// Ruby: first top-level statement outside `def`/`class`/`module` bodies
+fn ruby(content: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_block_comment = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+
+        // =begin/=end block comments must start at column 0
+        if in_block_comment {
+            if line.starts_with("=end") {
+                in_block_comment = false;
+            }
+            continue;
+        }
+        if line.starts_with("=begin") {
+            in_block_comment = true;
+            continue;
+        }
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if trimmed == "__END__" {
+            break;
+        }
+
+        if depth > 0 {
+            depth += ruby_opens_block(trimmed) as usize;
+            if ruby_closes_block(trimmed) {
+                depth = depth.saturating_sub(1);
+            }
+            continue;
+        }
+
+        // Requires/loads only pull in definitions
+        if ["require ", "require(", "require_relative", "load ", "load("]
+            .iter()
+            .any(|prefix| trimmed.starts_with(prefix))
+        {
+            continue;
+        }
+
+        // Definitions only run their bodies when called
+        if ["def ", "class ", "module "]
+            .iter()
+            .any(|prefix| trimmed.starts_with(prefix))
+        {
+            depth += ruby_opens_block(trimmed) as usize;
+            if ruby_closes_block(trimmed) {
+                depth = depth.saturating_sub(1);
+            }
+            continue;
+        }
+
+        return Some(index);
+    }
+
+    None
+}
+
+/// Does this (trimmed) Ruby line open a block that needs a matching `end`?
+///
+/// One-liners like `class Foo; end` open and close on the same line; callers
+/// account for both.
+fn ruby_opens_block(trimmed: &str) -> bool {
+    let code = trimmed.split(" #").next().unwrap_or(trimmed).trim_end();
+    let first_word = code.split_whitespace().next().unwrap_or("");
+
+    match first_word {
+        // Endless method definitions (`def square(x) = x * x`) have no `end`
+        "def" => {
+            let rest = match code.find(')') {
+                Some(paren) => &code[paren + 1..],
+                None => code.splitn(3, ' ').nth(2).unwrap_or(""),
+            };
+            !rest.trim_start().starts_with('=')
+        }
+        "class" | "module" | "if" | "unless" | "while" | "until" | "case" | "for" | "begin" => true,
+        _ => code.ends_with(" do") || code == "do" || code.contains(" do |"),
+    }
+}
+
+/// Does this (trimmed) Ruby line close a block?
+fn ruby_closes_block(trimmed: &str) -> bool {
+    trimmed == "end"
+        || trimmed.starts_with("end ")
+        || trimmed.starts_with("end.")
+        || trimmed.ends_with("; end")
+}
+
+/// JavaScript/TypeScript: first module-level statement that isn't an import,
+/// directive, or declaration
+fn javascript(content: &str) -> Option<usize> {
+    let mut depth = 0i32;
+    let mut in_block_comment = false;
+    let mut in_import = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let mut trimmed = line.trim();
+
+        if in_block_comment {
+            match trimmed.find("*/") {
+                Some(end) => {
+                    in_block_comment = false;
+                    trimmed = trimmed[end + 2..].trim();
+                }
+                None => continue,
+            }
+        }
+        if trimmed.starts_with("/*") {
+            match trimmed.find("*/") {
+                Some(end) => trimmed = trimmed[end + 2..].trim(),
+                None => {
+                    in_block_comment = true;
+                    continue;
+                }
+            }
+        }
+
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+        if index == 0 && trimmed.starts_with("#!") {
+            continue;
+        }
+
+        // Multi-line `import { a,\n b } from 'x';`
+        if in_import {
+            if trimmed.contains(" from ") || trimmed.starts_with("from ") || trimmed.ends_with(';')
+            {
+                in_import = false;
+            }
+            continue;
+        }
+
+        if depth > 0 {
+            depth += brace_delta(trimmed);
+            continue;
+        }
+
+        // Directives
+        if trimmed.starts_with("\"use strict\"") || trimmed.starts_with("'use strict'") {
+            continue;
+        }
+
+        if trimmed.starts_with("import ") || trimmed.starts_with("import{") {
+            if !(trimmed.contains(" from ") || trimmed.ends_with(';') || trimmed.contains('\''))
+                || trimmed.ends_with('{')
+            {
+                in_import = true;
+            }
+            continue;
+        }
+
+        // TypeScript: decorators belong to the class that follows, and
+        // `| 'a'` / `& B` continue a multi-line type alias
+        if ["@", "|", "&"]
+            .iter()
+            .any(|prefix| trimmed.starts_with(prefix))
+        {
+            depth += brace_delta(trimmed);
+            continue;
+        }
+
+        if trimmed.contains("require(")
+            && ["const ", "let ", "var ", "require("]
+                .iter()
+                .any(|prefix| trimmed.starts_with(prefix))
+        {
+            continue;
+        }
+
+        let declaration = trimmed.trim_start_matches("export default ");
+        let declaration = declaration.trim_start_matches("export ");
+        let is_declaration = declaration.starts_with("function ")
+            || declaration.starts_with("function*")
+            || declaration.starts_with("async function")
+            || declaration.starts_with("class ")
+            || declaration.starts_with("abstract class ")
+            || declaration.starts_with("declare ")
+            || declaration.starts_with("interface ")
+            || declaration.starts_with("type ")
+            || declaration.starts_with("enum ")
+            || ["const ", "let ", "var "]
+                .iter()
+                .any(|prefix| declaration.starts_with(prefix))
+                && (declaration.contains("=>") || declaration.contains("function"))
+            || ["let ", "var "]
+                .iter()
+                .any(|prefix| declaration.starts_with(prefix))
+                && !declaration.contains('=');
+
+        if is_declaration {
+            depth += brace_delta(trimmed);
+            continue;
+        }
+
+        // Re-exports and other export lists carry no runtime code
+        if trimmed.starts_with("export ") {
+            continue;
+        }
+
+        return Some(index);
+    }
+
+    None
+}
+
+/// Python: first top-level statement that isn't an import, docstring, or definition
+fn python(content: &str) -> Option<usize> {
+    let mut in_docstring: Option<&str> = None;
+    let mut open_parens = 0i32;
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+
+        if let Some(quote) = in_docstring {
+            if trimmed.contains(quote) {
+                in_docstring = None;
+            }
+            continue;
+        }
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        // Continuation of a parenthesized import or decorator
+        if open_parens > 0 {
+            open_parens += paren_delta(trimmed);
+            continue;
+        }
+
+        // Bodies of functions/classes only run when called
+        if line.starts_with(' ') || line.starts_with('\t') {
+            continue;
+        }
+
+        // Docstrings and other bare string literals are no-ops
+        if let Some(quote) = ["\"\"\"", "'''"]
+            .into_iter()
+            .find(|quote| trimmed.starts_with(quote))
+        {
+            if !trimmed[3..].contains(quote) {
+                in_docstring = Some(quote);
+            }
+            continue;
+        }
+
+        if trimmed.starts_with("import ")
+            || trimmed.starts_with("from ")
+            || trimmed.starts_with('@')
+        {
+            open_parens += paren_delta(trimmed);
+            continue;
+        }
+
+        if trimmed.starts_with("def ")
+            || trimmed.starts_with("async def ")
+            || trimmed.starts_with("class ")
+        {
+            continue;
+        }
+
+        return Some(index);
+    }
+
+    None
+}
+
+/// Rust: first statement in the body of `fn main`
+fn rust(content: &str) -> Option<usize> {
+    let mut in_main = false;
+    let mut in_body = false;
+    let mut in_block_comment = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+
+        if in_block_comment {
+            if trimmed.contains("*/") {
+                in_block_comment = false;
+            }
+            continue;
+        }
+        if trimmed.starts_with("/*") {
+            in_block_comment = !trimmed.contains("*/");
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+
+        if !in_main {
+            let signature = trimmed
+                .trim_start_matches("pub ")
+                .trim_start_matches("async ");
+            if signature.starts_with("fn main(") {
+                in_main = true;
+                if let Some(brace) = trimmed.find('{') {
+                    in_body = true;
+                    // Body on the same line as the signature: `fn main() { run(); }`
+                    if !trimmed[brace + 1..]
+                        .trim()
+                        .trim_end_matches('}')
+                        .trim()
+                        .is_empty()
+                    {
+                        return Some(index);
+                    }
+                }
+            }
+            continue;
+        }
+
+        if !in_body {
+            if let Some(rest) = trimmed.strip_prefix('{') {
+                in_body = true;
+                if !rest.trim().is_empty() {
+                    return Some(index);
+                }
+            }
+            continue;
+        }
+
+        if trimmed.starts_with("#[") || trimmed == "}" {
+            continue;
+        }
+
+        return Some(index);
+    }
+
+    None
+}
+
+/// Go: first statement in the body of `func main`
+fn go(content: &str) -> Option<usize> {
+    let mut in_main = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with("//") || trimmed.starts_with("/*") {
+            continue;
+        }
+
+        if trimmed.starts_with("func main()") {
+            in_main = true;
+            continue;
+        }
+
+        // Declarations without initialization don't produce a stoppable instruction
+        if in_main {
+            if trimmed == "{" || trimmed.starts_with("var ") && !trimmed.contains('=') {
+                continue;
+            }
+            return Some(index);
+        }
+    }
+
+    None
+}
+
+/// Net change in `{}` nesting on a line, ignoring braces in strings and comments
+fn brace_delta(line: &str) -> i32 {
+    delimiter_delta(line, '{', '}')
+}
+
+/// Net change in `()` nesting on a line, ignoring parens in strings and comments
+fn paren_delta(line: &str) -> i32 {
+    delimiter_delta(line, '(', ')')
+}
+
+fn delimiter_delta(line: &str, open: char, close: char) -> i32 {
+    let mut delta = 0;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) => {
+                if c == '\\' {
+                    chars.next();
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None => match c {
+                '"' | '\'' | '`' => quote = Some(c),
+                '/' if chars.peek() == Some(&'/') => break,
+                '#' if open == '(' => break,
+                c if c == open => delta += 1,
+                c if c == close => delta -= 1,
+                _ => {}
+            },
+        }
+    }
+
+    delta
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn line(language: SourceLanguage, content: &str) -> EntryLine {
+        first_executable_line(language, content)
+    }
+
+    fn detected(line: usize) -> EntryLine {
+        EntryLine {
+            line,
+            confidence: EntryLineConfidence::Detected,
+        }
+    }
+
+    #[test]
+    fn test_from_language() {
+        assert_eq!(
+            SourceLanguage::from_language("nodejs"),
+            Some(SourceLanguage::JavaScript)
+        );
+        assert_eq!(
+            SourceLanguage::from_language("ruby"),
+            Some(SourceLanguage::Ruby)
+        );
+        assert_eq!(SourceLanguage::from_language("cobol"), None);
+    }
+
+    #[test]
+    fn test_ruby_skips_top_level_def_to_call_at_bottom() {
+        let source = "\
+# comment
+require 'json'
+
+def fizzbuzz(n)
+  if n % 15 == 0
+    'FizzBuzz'
+  else
+    n.to_s
+  end
+end
+
+def main
+  (1..3).each do |i|
+    puts fizzbuzz(i)
+  end
+end
+
+main if __FILE__ == $0
+";
+        assert_eq!(line(SourceLanguage::Ruby, source), detected(18));
+    }
+
+    #[test]
+    fn test_ruby_skips_class_and_module_bodies() {
+        let source = "\
+#!/usr/bin/env ruby
+# frozen_string_literal: true
+module Greeting
+  class Greeter
+    def greet(name) = \"hi #{name}\"
+
+    def loud(name)
+      greet(name).upcase
+    end
+  end
+end
+
+=begin
+block comment
+=end
+puts Greeting::Greeter.new.loud('x')
+";
+        assert_eq!(line(SourceLanguage::Ruby, source), detected(16));
+    }
+
+    #[test]
+    fn test_ruby_first_statement_when_no_definitions() {
+        let source = "# setup\n\nx = 1\nputs x\n";
+        assert_eq!(line(SourceLanguage::Ruby, source), detected(3));
+    }
+
+    #[test]
+    fn test_ruby_only_definitions_falls_back() {
+        let source = "def a\n  1\nend\n";
+        let entry = line(SourceLanguage::Ruby, source);
+        assert_eq!(entry.line, 1);
+        assert!(entry.is_fallback());
+    }
+
+    #[test]
+    fn test_javascript_fixture_style() {
+        let source = "\
+// comment
+function fizzbuzz(n) {
+    if (n % 15 === 0) {
+        return 'FizzBuzz';
+    }
+    return n.toString();
+}
+
+// Main execution
+for (let i = 1; i <= 3; i++) {
+    console.log(fizzbuzz(i));
+}
+";
+        assert_eq!(line(SourceLanguage::JavaScript, source), detected(10));
+    }
+
+    #[test]
+    fn test_javascript_use_strict_and_imports() {
+        let source = "\
+#!/usr/bin/env node
+'use strict';
+/*
+ * License header
+ */
+import fs from 'fs';
+import {
+  readFile,
+  writeFile,
+} from 'fs/promises';
+const path = require('path');
+
+const main = async () => {
+  await readFile('x');
+};
+
+main();
+";
+        assert_eq!(line(SourceLanguage::JavaScript, source), detected(17));
+    }
+
+    #[test]
+    fn test_javascript_skips_classes_and_typescript_declarations() {
+        let source = "\
+interface Point {
+  x: number;
+}
+type Id = string;
+export class Service {
+  run() {
+    return 1;
+  }
+}
+let counter;
+const service = new Service();
+";
+        assert_eq!(line(SourceLanguage::JavaScript, source), detected(11));
+    }
+
+    #[test]
+    fn test_typescript_type_only_lines_and_imports() {
+        let source = "\
+import type { Config } from './config';
+import fs = require('fs');
+declare const VERSION: string;
+declare global {
+  interface Window { app: unknown }
+}
+type Mode =
+  | 'fast'
+  | 'safe';
+@Injectable({
+  scope: 'singleton',
+})
+export abstract class Base {
+  abstract run(): void;
+}
+let mode: Mode;
+console.log(VERSION);
+";
+        assert_eq!(line(SourceLanguage::JavaScript, source), detected(17));
+    }
+
+    #[test]
+    fn test_javascript_braces_in_strings_do_not_confuse_depth() {
+        let source = "\
+function f() {
+  return '}';
+}
+f();
+";
+        assert_eq!(line(SourceLanguage::JavaScript, source), detected(4));
+    }
+
+    #[test]
+    fn test_python_skips_docstring_imports_and_definitions() {
+        let source = "\
+#!/usr/bin/env python3
+\"\"\"
+Module docstring.
+\"\"\"
+from typing import (
+    List,
+    Dict,
+)
+import os
+
+@decorator
+def fizzbuzz(n):
+    return n
+
+
+class Runner:
+    pass
+
+
+if __name__ == \"__main__\":
+    fizzbuzz(1)
+";
+        assert_eq!(line(SourceLanguage::Python, source), detected(20));
+    }
+
+    #[test]
+    fn test_python_single_line_docstring() {
+        let source = "\"\"\"Doc.\"\"\"\nx = 1\n";
+        assert_eq!(line(SourceLanguage::Python, source), detected(2));
+    }
+
+    #[test]
+    fn test_rust_first_statement_in_main() {
+        let source = "\
+// comment
+fn helper() -> i32 {
+    1
+}
+
+fn main() {
+    // start
+    let x = helper();
+    println!(\"{}\", x);
+}
+";
+        assert_eq!(line(SourceLanguage::Rust, source), detected(8));
+    }
+
+    #[test]
+    fn test_rust_main_brace_on_next_line_and_attributes() {
+        let source = "\
+#[tokio::main]
+async fn main()
+{
+    #[allow(unused)]
+    run().await;
+}
+";
+        assert_eq!(line(SourceLanguage::Rust, source), detected(5));
+    }
+
+    #[test]
+    fn test_rust_without_main_falls_back() {
+        assert!(line(SourceLanguage::Rust, "pub fn lib() {}\n").is_fallback());
+    }
+
+    #[test]
+    fn test_go_first_statement_in_main() {
+        let source = "\
+package main
+
+import (
+\t\"fmt\"
+)
+
+func main() {
+\t// comment
+\tvar results []string
+\tresults = append(results, \"x\")
+\tfmt.Println(results)
+}
+";
+        assert_eq!(line(SourceLanguage::Go, source), detected(10));
+    }
+
+    #[test]
+    fn test_repository_fixtures() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        let cases = [
+            (SourceLanguage::Ruby, "fizzbuzz.rb", 22),
+            (SourceLanguage::JavaScript, "fizzbuzz.js", 17),
+            (SourceLanguage::Rust, "fizzbuzz.rs", 17),
+            (SourceLanguage::Go, "fizzbuzz.go", 27),
+        ];
+
+        for (language, file, expected) in cases {
+            let path = fixtures.join(file);
+            let entry = find_first_executable_line(language, path.to_str().unwrap());
+            assert_eq!(entry, detected(expected), "{}", file);
+        }
+    }
+
+    #[test]
+    fn test_unreadable_file_falls_back() {
+        let entry = find_first_executable_line(SourceLanguage::Ruby, "/nonexistent/file.rb");
+        assert!(entry.is_fallback());
+    }
+
+    #[test]
+    fn test_resolve_entry_line_prefers_override() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "x = 1").unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let entry = resolve_entry_line(SourceLanguage::Ruby, path, Some(7));
+        assert_eq!(entry, EntryLine::override_line(7));
+
+        let entry = resolve_entry_line(SourceLanguage::Ruby, path, None);
+        assert_eq!(entry, detected(1));
+    }
+}
@@ -1,38 +1,219 @@
+use super::metrics::{ClientMetrics, ClientMetricsSlot};
+use super::socket_helper::{AdapterStream, AdapterTimeouts};
 use super::transport::DapTransport;
-use super::transport_trait::DapTransportTrait;
+use super::transport_trait::{DapReader, DapTransportTrait, DapWriter};
 use super::types::*;
+use super::wire_log::{DapLog, DapLogSlot, LoggedReader, LoggedWriter};
+use crate::adapters::source_analysis::{self, EntryLine, SourceLanguage};
 use crate::{Error, Result};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicI32, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::process::{Child, Command};
-use tokio::sync::{mpsc, oneshot, Mutex, Notify, RwLock};
-use tracing::{debug, error, info, warn};
+use tokio::sync::{mpsc, oneshot, Notify, RwLock};
+use tracing::{debug, error, info, warn, Instrument};
 
 type ResponseSender = oneshot::Sender<Response>;
 type EventNotifier = Arc<Notify>;
 type EventCallback = Arc<dyn Fn(Event) + Send + Sync>;
-type ChildSessionSpawnCallback = Arc<
-    dyn Fn(String) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>> + Send + Sync,
+type EventHandlers = HashMap<String, Vec<EventHandler>>;
+/// Capabilities of the adapter, shared with the Node.js child clients
+pub(crate) type CapabilitiesSlot = Arc<std::sync::RwLock<Option<Capabilities>>>;
+/// Breakpoint of the stopOnEntry workaround, shared with the session's
+/// 'stopped' handler
+pub(crate) type EntryBreakpointSlot = Arc<std::sync::Mutex<Option<EntryBreakpoint>>>;
+/// Processes spawned for runInTerminal, shared with the reverse request handler
+type TerminalProcesses = Arc<std::sync::Mutex<Vec<Child>>>;
+type ReverseRequestFuture =
+    std::pin::Pin<Box<dyn std::future::Future<Output = Result<Option<Value>>> + Send>>;
+type ReverseRequestHandler = Arc<dyn Fn(Request) -> ReverseRequestFuture + Send + Sync>;
+type BreakpointsAppliedFuture = std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>;
+type BreakpointsAppliedHook = Arc<
+    dyn Fn(String, Vec<SourceBreakpoint>, Vec<Breakpoint>) -> BreakpointsAppliedFuture
+        + Send
+        + Sync,
 >;
 
+/// Requests whose success resumes the debuggee
+///
+/// Adapters are not expected to send a 'continued' event for these (DAP
+/// spec), so the client reports one itself when the response arrives.
+pub const RESUME_COMMANDS: &[&str] = &[
+    "continue",
+    "next",
+    "stepIn",
+    "stepOut",
+    "stepBack",
+    "reverseContinue",
+];
+
+/// An event callback, tagged with its owner when registered through
+/// [`DapClient::replace_event_handler`]
+struct EventHandler {
+    owner: Option<String>,
+    callback: EventCallback,
+}
+
+/// A request whose response is awaited later, with [`DapClient::wait_for_response`]
+pub struct PendingResponse {
+    seq: i32,
+    command: String,
+    sent: std::time::Instant,
+    rx: oneshot::Receiver<Response>,
+}
+
+impl PendingResponse {
+    pub fn seq(&self) -> i32 {
+        self.seq
+    }
+}
+
+/// Timeout applied to DAP requests that have no per-command override
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Timeout for 'disconnect' until a request timeout is configured
+///
+/// A hung adapter should not hold up ending its session; the session ends
+/// whether or not the adapter answers.
+pub const DEFAULT_DISCONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Messages queued for the writer task before senders have to wait
+pub const WRITE_QUEUE_DEPTH: usize = 64;
+
+/// How long a sender waits for room in a full write queue before giving up
+/// on the adapter
+pub const DEFAULT_WRITE_QUEUE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Queue `message` for the writer task, waiting up to `timeout` for room
+///
+/// The queue only stays full when the adapter stops reading its input, so
+/// running out of time means the adapter is stuck.
+async fn queue_for_writing(
+    write_tx: &mpsc::Sender<Message>,
+    message: Message,
+    what: &str,
+    timeout: Duration,
+) -> Result<()> {
+    match tokio::time::timeout(timeout, write_tx.send(message)).await {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(_)) => Err(Error::Dap("Write channel closed".to_string())),
+        Err(_) => Err(Error::AdapterStalled(format!(
+            "{} could not be sent: {} messages have been waiting for the adapter to read \
+             its input for {:?}",
+            what, WRITE_QUEUE_DEPTH, timeout
+        ))),
+    }
+}
+
+/// Per-request timeout policy
+///
+/// Every request sent through [`DapClient::send_request`] is bounded by the
+/// timeout for its command. The built-in allowances for slow commands only
+/// ever extend the default, so raising the default for a slow environment
+/// raises it for every command. Overrides set with
+/// [`RequestTimeouts::with_override`] apply exactly as given.
+#[derive(Debug, Clone)]
+pub struct RequestTimeouts {
+    default: Duration,
+    /// Built-in minimum timeouts for commands known to be slow
+    slow_commands: HashMap<String, Duration>,
+    overrides: HashMap<String, Duration>,
+}
+
+impl Default for RequestTimeouts {
+    fn default() -> Self {
+        Self::new(DEFAULT_REQUEST_TIMEOUT)
+    }
+}
+
+impl RequestTimeouts {
+    /// Create a policy with the given default and the built-in allowances
+    pub fn new(default: Duration) -> Self {
+        // launch/attach only answer once the adapter has started the debuggee,
+        // which can involve loading symbols or a whole runtime
+        let slow_commands = [
+            ("launch", Duration::from_secs(30)),
+            ("attach", Duration::from_secs(30)),
+        ]
+        .into_iter()
+        .map(|(command, timeout)| (command.to_string(), timeout))
+        .collect();
+
+        Self {
+            default,
+            slow_commands,
+            overrides: HashMap::new(),
+        }
+    }
+
+    /// Set the timeout used for a specific command
+    ///
+    /// The override replaces both the default and any built-in allowance, so
+    /// it may be shorter than either.
+    pub fn with_override(mut self, command: &str, timeout: Duration) -> Self {
+        self.overrides.insert(command.to_string(), timeout);
+        self
+    }
+
+    /// Timeout for commands without an override
+    pub fn default_timeout(&self) -> Duration {
+        self.default
+    }
+
+    /// Timeout that applies to the given command
+    pub fn for_command(&self, command: &str) -> Duration {
+        if let Some(timeout) = self.overrides.get(command) {
+            return *timeout;
+        }
+        self.slow_commands
+            .get(command)
+            .map_or(self.default, |timeout| (*timeout).max(self.default))
+    }
+}
+
 /// DAP Client with event-driven architecture
 pub struct DapClient {
-    transport: Arc<Mutex<Box<dyn DapTransportTrait>>>,
     seq_counter: Arc<AtomicI32>,
     pending_requests: Arc<RwLock<HashMap<i32, ResponseSender>>>,
-    #[allow(dead_code)] // Reserved for future event handling
-    event_tx: mpsc::UnboundedSender<Event>,
     // For backward compatibility with wait_for_event
     event_notifiers: Arc<RwLock<HashMap<String, EventNotifier>>>,
     // New: Event callbacks (can have multiple callbacks per event)
-    event_callbacks: Arc<RwLock<HashMap<String, Vec<EventCallback>>>>,
-    // Callback for child session spawning (Node.js multi-session)
-    child_session_spawn_callback: Arc<RwLock<Option<ChildSessionSpawnCallback>>>,
-    // Channel for sending write requests to avoid lock contention
-    write_tx: mpsc::UnboundedSender<Message>,
-    _child: Option<Child>,
+    event_callbacks: Arc<RwLock<EventHandlers>>,
+    // Handlers for reverse requests sent by the adapter (runInTerminal, startDebugging)
+    reverse_request_handlers: Arc<RwLock<HashMap<String, ReverseRequestHandler>>>,
+    // Channel for sending write requests to avoid lock contention; bounded,
+    // so a stuck adapter surfaces as an error instead of unbounded memory
+    write_tx: mpsc::Sender<Message>,
+    // How long senders wait for room in the write queue
+    write_queue_timeout: Arc<std::sync::RwLock<Duration>>,
+    // Timeout policy applied to every request
+    request_timeouts: Arc<std::sync::RwLock<RequestTimeouts>>,
+    // Limit for the whole initialize + launch handshake
+    initialize_timeout: Arc<std::sync::RwLock<Duration>>,
+    // Invoked with the results of pending breakpoints applied before configurationDone
+    breakpoints_applied_hook: Arc<RwLock<Option<BreakpointsAppliedHook>>>,
+    // Breakpoint set by the stopOnEntry workaround, until it is removed
+    entry_breakpoint: EntryBreakpointSlot,
+    // Entry line the stopOnEntry workaround resolved, with how it was found
+    entry_line: Arc<std::sync::Mutex<Option<EntryLine>>>,
+    // Capabilities from the initialize response, once received, updated by
+    // 'capabilities' events
+    capabilities: CapabilitiesSlot,
+    // Protocol log shared with the reader/writer tasks, when capture is enabled
+    dap_log: DapLogSlot,
+    // Request latencies and event counts
+    metrics: ClientMetricsSlot,
+    // Span of the session using this client; the reader/writer tasks run in it
+    span: tracing::Span,
+    // Adapter process, for adapters spawned by the server; killed on shutdown
+    adapter_process: std::sync::Mutex<Option<Child>>,
+    // Debuggees started for the adapter's runInTerminal requests; killed with
+    // the adapter, on disconnect, and when the last clone of the client drops
+    terminal_processes: TerminalProcesses,
+    // How the client talks to the adapter ("stdio", "tcp:<addr>", "unix:<path>")
+    transport: String,
 }
 
 impl DapClient {
@@ -59,15 +240,91 @@ impl DapClient {
             .ok_or_else(|| Error::Process("Failed to get stdout".to_string()))?;
 
         let transport: Box<dyn DapTransportTrait> = Box::new(DapTransport::new(stdin, stdout));
-        Self::new_with_transport(transport, Some(child)).await
+        let mut client = Self::new_with_transport(transport, Some(child)).await?;
+        client.transport = "stdio".to_string();
+        Ok(client)
     }
 
     /// Create DAP client from TCP socket (for Ruby/rdbg)
     pub async fn from_socket(socket: tokio::net::TcpStream) -> Result<Self> {
         info!("Creating DAP client from socket: {:?}", socket.peer_addr());
 
+        let description = match socket.peer_addr() {
+            Ok(addr) => format!("tcp:{}", addr),
+            Err(_) => "tcp".to_string(),
+        };
         let transport: Box<dyn DapTransportTrait> = Box::new(DapTransport::new_socket(socket));
-        Self::new_with_transport(transport, None).await
+        let mut client = Self::new_with_transport(transport, None).await?;
+        client.transport = description;
+        Ok(client)
+    }
+
+    /// Create DAP client from a unix domain socket
+    #[cfg(unix)]
+    pub async fn from_unix_socket(socket: tokio::net::UnixStream) -> Result<Self> {
+        info!(
+            "Creating DAP client from unix socket: {:?}",
+            socket.peer_addr()
+        );
+
+        let description = match socket.peer_addr().ok().and_then(|addr| {
+            addr.as_pathname()
+                .map(|path| format!("unix:{}", path.display()))
+        }) {
+            Some(description) => description,
+            None => "unix".to_string(),
+        };
+        let transport: Box<dyn DapTransportTrait> = Box::new(DapTransport::new_unix_socket(socket));
+        let mut client = Self::new_with_transport(transport, None).await?;
+        client.transport = description;
+        Ok(client)
+    }
+
+    /// Create DAP client from a connection to a socket-mode adapter
+    pub async fn from_stream(stream: AdapterStream) -> Result<Self> {
+        match stream {
+            AdapterStream::Tcp(socket) => Self::from_socket(socket).await,
+            #[cfg(unix)]
+            AdapterStream::Unix(socket) => Self::from_unix_socket(socket).await,
+        }
+    }
+
+    /// Take ownership of the adapter process a socket client is connected to,
+    /// so [`DapClient::kill_adapter`] can end it
+    pub fn with_adapter_process(self, child: Child) -> Self {
+        *self
+            .adapter_process
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = Some(child);
+        self
+    }
+
+    /// How the client talks to the adapter: "stdio", "tcp:<address>",
+    /// "unix:<path>", or "custom" for a transport given to
+    /// [`DapClient::new_with_transport`]
+    pub fn transport(&self) -> &str {
+        &self.transport
+    }
+
+    /// Kill the adapter process if it is still running
+    ///
+    /// Used on server shutdown, after the session was disconnected, so
+    /// adapters that ignore the disconnect are not left orphaned. Debuggees
+    /// started through runInTerminal are killed as well.
+    pub fn kill_adapter(&self) {
+        self.kill_terminal_processes();
+        let mut process = self
+            .adapter_process
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(child) = process.as_mut() {
+            if let Ok(None) = child.try_wait() {
+                info!("🔪 Killing adapter process {:?}", child.id());
+                if let Err(e) = crate::process::kill_tree(child) {
+                    warn!("⚠️  Failed to kill adapter process: {}", e);
+                }
+            }
+        }
     }
 
     /// Create a new DAP client with a custom transport (for testing)
@@ -75,92 +332,118 @@ impl DapClient {
         transport: Box<dyn DapTransportTrait>,
         child: Option<Child>,
     ) -> Result<Self> {
-        let transport = Arc::new(Mutex::new(transport));
+        let (reader, writer) = transport.split();
+        // Both halves record into the protocol log once capture is enabled
+        let dap_log = Arc::new(std::sync::OnceLock::new());
+        let metrics: ClientMetricsSlot = Default::default();
+        let reader = LoggedReader::boxed(reader, dap_log.clone(), metrics.clone());
+        let writer = LoggedWriter::boxed(writer, dap_log.clone());
         let seq_counter = Arc::new(AtomicI32::new(1));
         let pending_requests = Arc::new(RwLock::new(HashMap::new()));
-        let (event_tx, event_rx) = mpsc::unbounded_channel();
-        let (write_tx, write_rx) = mpsc::unbounded_channel();
+        let (write_tx, write_rx) = mpsc::channel(WRITE_QUEUE_DEPTH);
 
         let event_notifiers = Arc::new(RwLock::new(HashMap::new()));
         let event_callbacks = Arc::new(RwLock::new(HashMap::new()));
-        let child_session_spawn_callback = Arc::new(RwLock::new(None));
+        let reverse_request_handlers = Arc::new(RwLock::new(HashMap::new()));
 
         let client = Self {
-            transport: transport.clone(),
             seq_counter: seq_counter.clone(),
             pending_requests: pending_requests.clone(),
-            event_tx,
             event_notifiers: event_notifiers.clone(),
             event_callbacks: event_callbacks.clone(),
-            child_session_spawn_callback: child_session_spawn_callback.clone(),
+            reverse_request_handlers: reverse_request_handlers.clone(),
             write_tx: write_tx.clone(),
-            _child: child,
+            write_queue_timeout: Arc::new(std::sync::RwLock::new(DEFAULT_WRITE_QUEUE_TIMEOUT)),
+            request_timeouts: Arc::new(std::sync::RwLock::new(
+                RequestTimeouts::default().with_override("disconnect", DEFAULT_DISCONNECT_TIMEOUT),
+            )),
+            initialize_timeout: Arc::new(std::sync::RwLock::new(
+                AdapterTimeouts::default().initialize,
+            )),
+            breakpoints_applied_hook: Arc::new(RwLock::new(None)),
+            entry_breakpoint: Arc::new(std::sync::Mutex::new(None)),
+            entry_line: Arc::new(std::sync::Mutex::new(None)),
+            capabilities: Arc::new(std::sync::RwLock::new(None)),
+            dap_log: dap_log.clone(),
+            metrics,
+            // The session id is recorded once a session takes the client
+            span: tracing::info_span!("session", session_id = tracing::field::Empty),
+            adapter_process: std::sync::Mutex::new(child),
+            terminal_processes: Arc::new(std::sync::Mutex::new(Vec::new())),
+            transport: "custom".to_string(),
         };
 
-        // Spawn message reader handler
-        tokio::spawn(Self::message_reader(
-            transport.clone(),
-            pending_requests.clone(),
-            event_notifiers.clone(),
-            event_callbacks.clone(),
-            child_session_spawn_callback.clone(),
-            event_rx,
-        ));
+        // Built-in reverse request handlers
+        let terminal_processes = client.terminal_processes.clone();
+        client
+            .on_reverse_request("runInTerminal", move |req| {
+                Box::pin(Self::run_in_terminal(
+                    req.arguments,
+                    terminal_processes.clone(),
+                ))
+            })
+            .await;
+
+        // Spawn message reader handler (owns the read half)
+        tokio::spawn(
+            Self::message_reader(
+                reader,
+                write_tx,
+                seq_counter.clone(),
+                pending_requests.clone(),
+                event_notifiers.clone(),
+                event_callbacks.clone(),
+                reverse_request_handlers.clone(),
+            )
+            .instrument(client.span.clone()),
+        );
 
-        // Spawn message writer handler
-        tokio::spawn(Self::message_writer(transport.clone(), write_rx));
+        // Spawn message writer handler (owns the write half)
+        tokio::spawn(Self::message_writer(writer, write_rx).instrument(client.span.clone()));
 
         Ok(client)
     }
 
     /// Message reader task - reads messages from transport and dispatches them
+    ///
+    /// The reader exclusively owns the read half, so it simply awaits the next
+    /// frame; a read is never cancelled part-way through a message.
     async fn message_reader(
-        transport: Arc<Mutex<Box<dyn DapTransportTrait>>>,
+        mut reader: Box<dyn DapReader>,
+        write_tx: mpsc::Sender<Message>,
+        seq_counter: Arc<AtomicI32>,
         pending_requests: Arc<RwLock<HashMap<i32, ResponseSender>>>,
         event_notifiers: Arc<RwLock<HashMap<String, EventNotifier>>>,
-        event_callbacks: Arc<RwLock<HashMap<String, Vec<EventCallback>>>>,
-        child_session_spawn_callback: Arc<RwLock<Option<ChildSessionSpawnCallback>>>,
-        mut _event_rx: mpsc::UnboundedReceiver<Event>,
+        event_callbacks: Arc<RwLock<EventHandlers>>,
+        reverse_request_handlers: Arc<RwLock<HashMap<String, ReverseRequestHandler>>>,
     ) {
         loop {
-            debug!("📖 message_reader: Attempting to acquire transport lock");
-
-            // Try to read a message with the lock, but release it if no message is ready
-            let msg_result = {
-                let mut transport = transport.lock().await;
-                debug!("📖 message_reader: Lock acquired, checking for message");
-
-                // Use select with timeout to avoid holding lock indefinitely
-                let read_future = transport.read_message();
-                tokio::select! {
-                    result = read_future => {
-                        debug!("📖 message_reader: Message read attempt completed");
-                        Some(result)
-                    }
-                    _ = tokio::time::sleep(tokio::time::Duration::from_millis(50)) => {
-                        debug!("📖 message_reader: Read timeout, releasing lock");
-                        None
-                    }
-                }
-            };
-            debug!("📖 message_reader: Lock released");
-
-            // If we didn't get a message, continue loop (will retry)
-            let msg = match msg_result {
-                None => {
-                    tokio::time::sleep(tokio::time::Duration::from_micros(100)).await;
-                    continue;
-                }
-                Some(Ok(msg)) => msg,
-                Some(Err(e)) => {
+            let msg = match reader.read_message().await {
+                Ok(msg) => msg,
+                Err(e) => {
                     error!("📖 message_reader: Failed to read DAP message: {}", e);
                     break;
                 }
             };
-
             match msg {
                 Message::Response(resp) => {
                     debug!("Received response for seq {}", resp.request_seq);
+                    // Dispatched before the requester is woken up and in wire
+                    // order, so a 'stopped' event read later always wins
+                    if resp.success && RESUME_COMMANDS.contains(&resp.command.as_str()) {
+                        let all_threads = resp
+                            .body
+                            .as_ref()
+                            .and_then(|body| body.get("allThreadsContinued"))
+                            .and_then(Value::as_bool)
+                            .unwrap_or(resp.command == "continue");
+                        let continued = Event {
+                            seq: resp.seq,
+                            event: "continued".to_string(),
+                            body: Some(serde_json::json!({ "allThreadsContinued": all_threads })),
+                        };
+                        Self::dispatch_event(&event_notifiers, &event_callbacks, continued).await;
+                    }
                     let mut pending = pending_requests.write().await;
                     if let Some(sender) = pending.remove(&resp.request_seq) {
                         if sender.send(resp).is_err() {
@@ -178,32 +461,7 @@ impl DapClient {
                         "🎯 EVENT RECEIVED: '{}' with body: {:?}",
                         event.event, event.body
                     );
-
-                    // 1. Notify anyone waiting for this specific event (legacy wait_for_event)
-                    let notifiers = event_notifiers.read().await;
-                    if let Some(notifier) = notifiers.get(&event.event) {
-                        info!("  Notifying waiters for event '{}'", event.event);
-                        notifier.notify_waiters();
-                    }
-                    drop(notifiers);
-
-                    // 2. Invoke registered event callbacks
-                    let callbacks = event_callbacks.read().await;
-                    if let Some(handlers) = callbacks.get(&event.event) {
-                        info!(
-                            "  Found {} callback(s) for event '{}'",
-                            handlers.len(),
-                            event.event
-                        );
-                        for (idx, callback) in handlers.iter().enumerate() {
-                            info!("  Invoking callback {} for event '{}'", idx, event.event);
-                            // Invoke callback with cloned event
-                            callback(event.clone());
-                            info!("  Callback {} completed for event '{}'", idx, event.event);
-                        }
-                    } else {
-                        info!("  No callbacks registered for event '{}'", event.event);
-                    }
+                    Self::dispatch_event(&event_notifiers, &event_callbacks, event).await;
                 }
                 Message::Request(req) => {
                     info!(
@@ -212,111 +470,139 @@ impl DapClient {
                     );
                     info!("   Arguments: {:?}", req.arguments);
 
-                    // vscode-js-debug sends reverse requests like 'startDebugging' or 'attachedChildSession'
-                    // We need to respond to these requests or the adapter will hang
-
-                    // Handle startDebugging - extract __pendingTargetId and spawn child
-                    if req.command == "startDebugging" {
-                        info!(
-                            "   🎯 startDebugging request detected - extracting __pendingTargetId"
-                        );
-                        if let Some(args) = &req.arguments {
-                            if let Some(config) = args.get("configuration") {
-                                if let Some(target_id) = config.get("__pendingTargetId") {
-                                    if let Some(target_id_str) = target_id.as_str() {
-                                        info!("   ✅ Found __pendingTargetId: {}", target_id_str);
-
-                                        // Invoke callback to spawn child session
-                                        let callback_guard =
-                                            child_session_spawn_callback.read().await;
-                                        if let Some(callback) = callback_guard.as_ref() {
-                                            info!("   📞 Invoking child session spawn callback with target_id: {}", target_id_str);
-                                            let fut = callback(target_id_str.to_string());
-                                            drop(callback_guard);
-                                            tokio::spawn(fut);
-                                        } else {
-                                            warn!(
-                                                "   ⚠️  No child session spawn callback registered"
-                                            );
-                                        }
-                                    } else {
-                                        warn!(
-                                            "   ⚠️  __pendingTargetId is not a string: {:?}",
-                                            target_id
-                                        );
-                                    }
-                                } else {
-                                    warn!("   ⚠️  No __pendingTargetId in configuration");
+                    // The adapter blocks until it gets a response, so every
+                    // reverse request is answered, even unsupported ones
+                    let handler = reverse_request_handlers
+                        .read()
+                        .await
+                        .get(&req.command)
+                        .cloned();
+                    let write_tx = write_tx.clone();
+                    let seq_counter = seq_counter.clone();
+
+                    // Handlers may take a while (e.g. spawning a process), so
+                    // they run off the reader task
+                    tokio::spawn(async move {
+                        let request_seq = req.seq;
+                        let command = req.command.clone();
+
+                        let result = match handler {
+                            Some(handler) => handler(req).await,
+                            None => Err(Error::Dap(format!(
+                                "Reverse request '{}' is not supported",
+                                command
+                            ))),
+                        };
+
+                        let response = match result {
+                            Ok(body) => Response {
+                                seq: seq_counter.fetch_add(1, Ordering::SeqCst),
+                                request_seq,
+                                success: true,
+                                command: command.clone(),
+                                message: None,
+                                body,
+                            },
+                            Err(e) => {
+                                warn!("   ⚠️  Reverse request '{}' failed: {}", command, e);
+                                Response {
+                                    seq: seq_counter.fetch_add(1, Ordering::SeqCst),
+                                    request_seq,
+                                    success: false,
+                                    command: command.clone(),
+                                    message: Some(e.to_string()),
+                                    body: None,
                                 }
-                            } else {
-                                warn!("   ⚠️  No configuration in startDebugging arguments");
                             }
-                        }
-                    }
+                        };
 
-                    // Send success response
-                    let response = Response {
-                        seq: 0, // Response seq (incremental, but not critical for reverse requests)
-                        request_seq: req.seq,
-                        success: true,
-                        command: req.command.clone(),
-                        message: None,
-                        body: None,
-                    };
-
-                    info!(
-                        "   Sending success response to reverse request '{}'",
-                        req.command
-                    );
+                        info!(
+                            "   Sending {} response to reverse request '{}'",
+                            if response.success {
+                                "success"
+                            } else {
+                                "failure"
+                            },
+                            command
+                        );
 
-                    // Send response back via transport (don't use write channel to avoid deadlock)
-                    let transport_clone = transport.clone();
-                    tokio::spawn(async move {
-                        let mut transport = transport_clone.lock().await;
-                        if let Err(e) = transport.write_message(&Message::Response(response)).await
+                        // The writer task owns the write half, so queueing cannot deadlock
+                        if let Err(e) = queue_for_writing(
+                            &write_tx,
+                            Message::Response(response),
+                            "Reverse request response",
+                            DEFAULT_WRITE_QUEUE_TIMEOUT,
+                        )
+                        .await
                         {
                             error!("Failed to send reverse request response: {}", e);
                         }
                     });
                 }
             }
+        }
+
+        // Fail any requests still waiting: their responses can no longer arrive
+        pending_requests.write().await.clear();
+        debug!("📖 message_reader: Task exiting");
+    }
 
-            // Small sleep to let other tasks run (e.g., configurationDone sender)
-            // This is necessary because read_message() blocks holding the lock
-            tokio::time::sleep(tokio::time::Duration::from_micros(100)).await;
+    /// Wake up waiters and invoke the callbacks registered for `event`
+    async fn dispatch_event(
+        event_notifiers: &RwLock<HashMap<String, EventNotifier>>,
+        event_callbacks: &RwLock<EventHandlers>,
+        event: Event,
+    ) {
+        // 1. Notify anyone waiting for this specific event (legacy wait_for_event)
+        let notifiers = event_notifiers.read().await;
+        if let Some(notifier) = notifiers.get(&event.event) {
+            info!("  Notifying waiters for event '{}'", event.event);
+            notifier.notify_waiters();
+        }
+        drop(notifiers);
+
+        // 2. Invoke registered event callbacks
+        let callbacks = event_callbacks.read().await;
+        if let Some(handlers) = callbacks.get(&event.event) {
+            info!(
+                "  Found {} callback(s) for event '{}'",
+                handlers.len(),
+                event.event
+            );
+            for (idx, handler) in handlers.iter().enumerate() {
+                info!("  Invoking callback {} for event '{}'", idx, event.event);
+                // Invoke callback with cloned event
+                (handler.callback)(event.clone());
+                info!("  Callback {} completed for event '{}'", idx, event.event);
+            }
+        } else {
+            info!("  No callbacks registered for event '{}'", event.event);
         }
     }
 
     /// Message writer task - writes messages to transport from a channel
-    /// This avoids lock contention between reader and writers
-    async fn message_writer(
-        transport: Arc<Mutex<Box<dyn DapTransportTrait>>>,
-        mut write_rx: mpsc::UnboundedReceiver<Message>,
-    ) {
-        info!("📝 message_writer: Task started");
+    /// The writer exclusively owns the write half, so there is no lock to contend on
+    async fn message_writer(mut writer: Box<dyn DapWriter>, mut write_rx: mpsc::Receiver<Message>) {
+        debug!("📝 message_writer: Task started");
         while let Some(message) = write_rx.recv().await {
-            let msg_type = match &message {
-                Message::Request(req) => format!("Request({})", req.command),
-                Message::Response(resp) => format!("Response(seq {})", resp.seq),
-                Message::Event(evt) => format!("Event({})", evt.event),
-            };
-            info!("📝 message_writer: Received {} from channel", msg_type);
-            info!("📝 message_writer: Attempting to acquire transport lock");
-            let mut transport = transport.lock().await;
-            info!("📝 message_writer: Lock acquired, writing message");
-            if let Err(e) = transport.write_message(&message).await {
+            if let Err(e) = writer.write_message(&message).await {
                 error!("📝 message_writer: Failed to write DAP message: {}", e);
                 break;
             }
-            info!("📝 message_writer: Message written successfully, releasing lock");
-            drop(transport);
-            info!("📝 message_writer: Lock released");
         }
-        info!("📝 message_writer: Task exiting");
+        debug!("📝 message_writer: Task exiting");
     }
 
     /// Register a callback for a specific DAP event
     /// The callback will be invoked every time the event is received
+    ///
+    /// ORDERING: callbacks run on the reader task, one event at a time and in
+    /// the order the adapter sent the events, so they must not block. Work
+    /// that has to await (such as taking the session state lock) must keep
+    /// that order itself by handing the event to a single consumer, as
+    /// `DebugSession` does with its state-update queue. Spawning a task per
+    /// event does not: a 'stopped' event's task can then run after the task
+    /// of a later 'continued' event.
     pub async fn on_event<F>(&self, event_name: &str, callback: F)
     where
         F: Fn(Event) + Send + Sync + 'static,
@@ -325,7 +611,44 @@ impl DapClient {
         callbacks
             .entry(event_name.to_string())
             .or_insert_with(Vec::new)
-            .push(Arc::new(callback));
+            .push(EventHandler {
+                owner: None,
+                callback: Arc::new(callback),
+            });
+    }
+
+    /// Register `owner`'s callback for an event, replacing the one it
+    /// registered before
+    ///
+    /// Registering again (e.g. when a session relaunches) therefore never
+    /// results in the event being handled twice. The ordering rules of
+    /// [`DapClient::on_event`] apply.
+    pub async fn replace_event_handler<F>(&self, event_name: &str, owner: &str, callback: F)
+    where
+        F: Fn(Event) + Send + Sync + 'static,
+    {
+        let mut callbacks = self.event_callbacks.write().await;
+        let handlers = callbacks.entry(event_name.to_string()).or_default();
+        handlers.retain(|handler| handler.owner.as_deref() != Some(owner));
+        handlers.push(EventHandler {
+            owner: Some(owner.to_string()),
+            callback: Arc::new(callback),
+        });
+    }
+
+    /// Remove every callback `owner` registered with [`DapClient::replace_event_handler`]
+    pub async fn remove_owned_event_handlers(&self, owner: &str) {
+        let mut callbacks = self.event_callbacks.write().await;
+        for handlers in callbacks.values_mut() {
+            handlers.retain(|handler| handler.owner.as_deref() != Some(owner));
+        }
+        callbacks.retain(|_, handlers| !handlers.is_empty());
+    }
+
+    /// Number of callbacks registered for an event
+    pub async fn event_handler_count(&self, event_name: &str) -> usize {
+        let callbacks = self.event_callbacks.read().await;
+        callbacks.get(event_name).map_or(0, Vec::len)
     }
 
     /// Remove all callbacks for a specific event
@@ -334,15 +657,51 @@ impl DapClient {
         callbacks.remove(event_name);
     }
 
+    /// Register a handler for a reverse request sent by the adapter
+    ///
+    /// The handler's `Ok` value becomes the body of a success response and an
+    /// `Err` becomes a failure response carrying the error message. Registering
+    /// a handler for a command replaces any previous handler.
+    pub async fn on_reverse_request<F>(&self, command: &str, handler: F)
+    where
+        F: Fn(Request) -> ReverseRequestFuture + Send + Sync + 'static,
+    {
+        let mut handlers = self.reverse_request_handlers.write().await;
+        handlers.insert(command.to_string(), Arc::new(handler));
+        info!("✅ Reverse request handler registered for '{}'", command);
+    }
+
+    /// Register a hook that receives the adapter's verdict on pending breakpoints
+    ///
+    /// During [`DapClient::initialize_and_launch_with_pending`] the hook is
+    /// awaited once per source file with the requested breakpoints and the
+    /// `setBreakpoints` results, after the 'initialized' event and before
+    /// configurationDone. Registering a hook replaces any previous one.
+    pub async fn on_breakpoints_applied<F>(&self, hook: F)
+    where
+        F: Fn(String, Vec<SourceBreakpoint>, Vec<Breakpoint>) -> BreakpointsAppliedFuture
+            + Send
+            + Sync
+            + 'static,
+    {
+        *self.breakpoints_applied_hook.write().await = Some(Arc::new(hook));
+    }
+
+    /// Drop the hook registered with [`DapClient::on_breakpoints_applied`]
+    pub async fn remove_breakpoints_applied_hook(&self) {
+        *self.breakpoints_applied_hook.write().await = None;
+    }
+
     /// Register a callback for child session spawning (multi-session debugging)
     ///
-    /// This callback will be invoked when a reverse request (like `startDebugging`)
-    /// contains a child session port. The callback should spawn the child session
-    /// and connect to it.
+    /// This installs the `startDebugging` reverse request handler. The callback
+    /// is invoked with the `__pendingTargetId` from the request's configuration
+    /// and should spawn the child session and connect to it. It runs in the
+    /// background so the adapter gets its response straight away.
     ///
     /// # Arguments
     ///
-    /// * `callback` - Async function that takes a port number and spawns a child session
+    /// * `callback` - Async function that takes a target ID and spawns a child session
     ///
     /// # Example
     ///
@@ -365,11 +724,127 @@ impl DapClient {
             + Sync
             + 'static,
     {
-        let mut callback_guard = self.child_session_spawn_callback.write().await;
-        *callback_guard = Some(Arc::new(callback));
+        let callback = Arc::new(callback);
+        self.on_reverse_request("startDebugging", move |req| {
+            let callback = callback.clone();
+            Box::pin(async move {
+                let target_id = req
+                    .arguments
+                    .as_ref()
+                    .and_then(|args| args.get("configuration"))
+                    .and_then(|config| config.get("__pendingTargetId"))
+                    .and_then(|id| id.as_str())
+                    .ok_or_else(|| {
+                        Error::Dap(
+                            "startDebugging request has no configuration.__pendingTargetId"
+                                .to_string(),
+                        )
+                    })?;
+
+                info!(
+                    "   📞 Invoking child session spawn callback with target_id: {}",
+                    target_id
+                );
+                tokio::spawn(callback(target_id.to_string()));
+                Ok(None)
+            })
+        })
+        .await;
         info!("✅ Child session spawn callback registered");
     }
 
+    /// Built-in `runInTerminal` handler
+    ///
+    /// We have no terminal to offer, so the command is spawned directly. Its
+    /// stdout goes to our stderr because stdout carries the MCP protocol.
+    /// The process is kept in `processes`, so it does not outlive the client.
+    async fn run_in_terminal(
+        arguments: Option<Value>,
+        processes: TerminalProcesses,
+    ) -> Result<Option<Value>> {
+        let args: RunInTerminalRequestArguments = serde_json::from_value(
+            arguments.ok_or_else(|| Error::Dap("runInTerminal without arguments".to_string()))?,
+        )
+        .map_err(|e| Error::Dap(format!("Invalid runInTerminal arguments: {}", e)))?;
+
+        let (program, program_args) = args
+            .args
+            .split_first()
+            .ok_or_else(|| Error::Dap("runInTerminal with empty args".to_string()))?;
+
+        info!(
+            "🖥️  runInTerminal: {} {:?} (cwd: {})",
+            program, program_args, args.cwd
+        );
+
+        let mut command = Command::new(program);
+        command
+            .args(program_args)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::io::stderr())
+            .stderr(std::process::Stdio::inherit())
+            .kill_on_drop(true);
+        if !args.cwd.is_empty() {
+            command.current_dir(&args.cwd);
+        }
+        for (key, value) in args.env.unwrap_or_default() {
+            match value {
+                Some(value) => command.env(key, value),
+                None => command.env_remove(key),
+            };
+        }
+
+        let child = command
+            .spawn()
+            .map_err(|e| Error::Process(format!("runInTerminal failed to spawn: {}", e)))?;
+        let process_id = child.id();
+
+        let mut processes = processes.lock().unwrap_or_else(|e| e.into_inner());
+        // Reap the ones that exited since; the adapter tracks them by PID
+        processes.retain_mut(|child| match child.try_wait() {
+            Ok(None) => true,
+            Ok(Some(status)) => {
+                info!("🖥️  runInTerminal process exited: {}", status);
+                false
+            }
+            Err(e) => {
+                warn!("🖥️  runInTerminal process wait failed: {}", e);
+                false
+            }
+        });
+        processes.push(child);
+
+        Ok(Some(serde_json::json!({ "processId": process_id })))
+    }
+
+    /// Kill the processes started for runInTerminal that are still running
+    fn kill_terminal_processes(&self) {
+        let processes = std::mem::take(
+            &mut *self
+                .terminal_processes
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()),
+        );
+        for mut child in processes {
+            if let Ok(None) = child.try_wait() {
+                info!("🔪 Killing runInTerminal process {:?}", child.id());
+                if let Err(e) = crate::process::kill_tree(&mut child) {
+                    warn!("⚠️  Failed to kill runInTerminal process: {}", e);
+                }
+            }
+        }
+    }
+
+    /// IDs of the runInTerminal processes that have not been reaped
+    pub fn terminal_process_ids(&self) -> Vec<u32> {
+        self.terminal_processes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter_map(Child::id)
+            .collect()
+    }
+
     /// Wait for a specific DAP event with a timeout (legacy method)
     pub async fn wait_for_event(
         &self,
@@ -414,29 +889,152 @@ impl DapClient {
         };
 
         debug!(
-            "send_request_nowait: Acquiring transport lock for command '{}'",
-            command
-        );
-        let mut transport = self.transport.lock().await;
-        debug!(
-            "send_request_nowait: Writing {} request (seq {})",
+            "send_request_nowait: Queueing {} request (seq {})",
             command, seq
         );
-        transport.write_message(&Message::Request(request)).await?;
-        debug!("send_request_nowait: Message written successfully");
-        drop(transport);
+        self.queue_request(request).await?;
 
         Ok(seq)
     }
 
+    /// Queue `request` for the writer task
+    ///
+    /// A queue that stays full counts as a timeout of the request, so an
+    /// adapter that stopped reading is reported unhealthy like one that
+    /// stopped answering.
+    async fn queue_request(&self, request: Request) -> Result<()> {
+        let command = request.command.clone();
+        let timeout = self.write_queue_timeout();
+        let what = format!("'{}' (seq {})", command, request.seq);
+        let result =
+            queue_for_writing(&self.write_tx, Message::Request(request), &what, timeout).await;
+        if let Err(Error::AdapterStalled(message)) = &result {
+            warn!("⏱️  {}", message);
+            self.metrics().record_timeout(&command, timeout);
+        }
+        result
+    }
+
+    /// Messages waiting for the writer task
+    pub fn write_queue_depth(&self) -> usize {
+        self.write_tx.max_capacity() - self.write_tx.capacity()
+    }
+
+    /// Set how long senders wait for room in a full write queue
+    pub fn set_write_queue_timeout(&self, timeout: Duration) {
+        *self
+            .write_queue_timeout
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = timeout;
+    }
+
+    /// How long senders wait for room in a full write queue
+    pub fn write_queue_timeout(&self) -> Duration {
+        *self
+            .write_queue_timeout
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Replace the request timeout policy
+    pub fn set_request_timeouts(&self, timeouts: RequestTimeouts) {
+        *self
+            .request_timeouts
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = timeouts;
+    }
+
+    /// Current request timeout policy
+    pub fn request_timeouts(&self) -> RequestTimeouts {
+        self.request_timeouts
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Record every message exchanged with the adapter into `log`
+    ///
+    /// Call before the first request so the handshake is captured. Capture
+    /// can only be enabled once per client; later calls are ignored.
+    pub fn capture_dap_log(&self, log: Arc<DapLog>) {
+        let _ = self.dap_log.set(log);
+    }
+
+    /// The protocol log, when capture is enabled
+    pub fn dap_log(&self) -> Option<Arc<DapLog>> {
+        self.dap_log.get().cloned()
+    }
+
+    /// Request latencies and event counts of this client
+    pub fn metrics(&self) -> Arc<ClientMetrics> {
+        self.metrics
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Record into `metrics` instead (Node.js child clients count toward
+    /// their parent session)
+    pub fn share_metrics(&self, metrics: Arc<ClientMetrics>) {
+        *self.metrics.write().unwrap_or_else(|e| e.into_inner()) = metrics;
+    }
+
+    /// Span of the session using this client (`session{session_id=...}`)
+    pub fn span(&self) -> &tracing::Span {
+        &self.span
+    }
+
+    /// Set the limit for the initialize + launch handshake
+    pub fn set_initialize_timeout(&self, timeout: Duration) {
+        *self
+            .initialize_timeout
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = timeout;
+    }
+
+    /// Limit for the initialize + launch handshake
+    /// ([`DapClient::initialize_and_launch_with_timeout`])
+    pub fn initialize_timeout(&self) -> Duration {
+        *self
+            .initialize_timeout
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
     /// Send a request and wait for response (blocking)
+    ///
+    /// The wait is bounded by the client's [`RequestTimeouts`] for `command`.
     pub async fn send_request(&self, command: &str, arguments: Option<Value>) -> Result<Response> {
-        let seq = self.seq_counter.fetch_add(1, Ordering::SeqCst);
+        let timeout = self.request_timeouts().for_command(command);
+        self.send_request_with_timeout(command, arguments, timeout)
+            .await
+    }
 
+    /// Send a request with an explicit timeout, bypassing the timeout policy
+    pub async fn send_request_with_timeout(
+        &self,
+        command: &str,
+        arguments: Option<Value>,
+        timeout: Duration,
+    ) -> Result<Response> {
         info!(
-            "✉️  send_request: Sending '{}' request (seq {})",
-            command, seq
+            "✉️  send_request: Sending '{}' request (timeout {:?})",
+            command, timeout
         );
+        let mut pending = self.send_request_deferred(command, arguments).await?;
+        self.wait_for_response(&mut pending, timeout).await
+    }
+
+    /// Send a request now and wait for its response later
+    ///
+    /// Unlike [`DapClient::send_request_nowait`] the response is not dropped:
+    /// it is kept for [`DapClient::wait_for_response`].
+    pub async fn send_request_deferred(
+        &self,
+        command: &str,
+        arguments: Option<Value>,
+    ) -> Result<PendingResponse> {
+        let seq = self.seq_counter.fetch_add(1, Ordering::SeqCst);
 
         let request = Request {
             seq,
@@ -456,15 +1054,58 @@ impl DapClient {
         }
 
         info!("✉️  send_request: Sending message to write channel");
-        self.write_tx
-            .send(Message::Request(request))
-            .map_err(|_| Error::Dap("Write channel closed".to_string()))?;
+        if let Err(e) = self.queue_request(request).await {
+            self.pending_requests.write().await.remove(&seq);
+            return Err(e);
+        }
+
+        Ok(PendingResponse {
+            seq,
+            command: command.to_string(),
+            sent: std::time::Instant::now(),
+            rx,
+        })
+    }
+
+    /// Wait up to `timeout` for the response to a deferred request
+    ///
+    /// After a timeout the request is no longer tracked; its response will
+    /// be ignored.
+    pub async fn wait_for_response(
+        &self,
+        pending: &mut PendingResponse,
+        timeout: Duration,
+    ) -> Result<Response> {
+        let PendingResponse {
+            seq,
+            command,
+            sent,
+            rx,
+        } = pending;
+        let (seq, command, sent) = (*seq, command.as_str(), *sent);
 
         info!("✉️  send_request: Waiting for response to seq {}", seq);
-        let response = rx
-            .await
-            .map_err(|_| Error::Dap("Request cancelled or connection closed".to_string()))?;
+        let metrics = self.metrics();
+        let response = match tokio::time::timeout(timeout, rx).await {
+            Ok(result) => result.map_err(|_| {
+                metrics.record_request(command, sent.elapsed(), false);
+                Error::Dap("Request cancelled or connection closed".to_string())
+            })?,
+            Err(_) => {
+                metrics.record_timeout(command, sent.elapsed());
+                self.pending_requests.write().await.remove(&seq);
+                warn!(
+                    "⏱️  send_request: '{}' (seq {}) timed out after {:?}",
+                    command, seq, timeout
+                );
+                return Err(Error::Dap(format!(
+                    "'{}' timed out after {:?}",
+                    command, timeout
+                )));
+            }
+        };
 
+        metrics.record_request(command, sent.elapsed(), response.success);
         info!(
             "✅ send_request: Received response for '{}' (seq {}), success: {}",
             command, seq, response.success
@@ -472,28 +1113,6 @@ impl DapClient {
         Ok(response)
     }
 
-    /// Send a request with a timeout (aggressive timeout wrapper)
-    pub async fn send_request_with_timeout(
-        &self,
-        command: &str,
-        arguments: Option<Value>,
-        timeout: std::time::Duration,
-    ) -> Result<Response> {
-        info!(
-            "⏱️  send_request_with_timeout: '{}' with timeout {:?}",
-            command, timeout
-        );
-
-        tokio::time::timeout(timeout, self.send_request(command, arguments))
-            .await
-            .map_err(|_| {
-                Error::Dap(format!(
-                    "Request '{}' timed out after {:?}",
-                    command, timeout
-                ))
-            })?
-    }
-
     /// Send a request with a callback for the response
     pub async fn send_request_async<F>(
         &self,
@@ -525,18 +1144,40 @@ impl DapClient {
             "send_request_async: Sending {} request (seq {}) to write channel",
             command, seq
         );
-        self.write_tx
-            .send(Message::Request(request))
-            .map_err(|_| Error::Dap("Write channel closed".to_string()))?;
+        if let Err(e) = self.queue_request(request).await {
+            self.pending_requests.write().await.remove(&seq);
+            return Err(e);
+        }
         debug!("send_request_async: Request queued");
 
         // Spawn task to wait for response and invoke callback
+        let timeout = self.request_timeouts().for_command(command);
+        let command = command.to_string();
+        let pending_requests = self.pending_requests.clone();
+        let metrics = self.metrics();
+        let sent = std::time::Instant::now();
         tokio::spawn(async move {
             debug!(
                 "send_request_async callback task: Waiting for response seq {}",
                 seq
             );
-            match rx.await {
+            let result = match tokio::time::timeout(timeout, rx).await {
+                Ok(result) => result,
+                Err(_) => {
+                    metrics.record_timeout(&command, sent.elapsed());
+                    pending_requests.write().await.remove(&seq);
+                    return callback(Err(Error::Dap(format!(
+                        "'{}' timed out after {:?}",
+                        command, timeout
+                    ))));
+                }
+            };
+            metrics.record_request(
+                &command,
+                sent.elapsed(),
+                result.as_ref().is_ok_and(|response| response.success),
+            );
+            match result {
                 Ok(response) => {
                     debug!(
                         "send_request_async callback task: Got response for seq {}",
@@ -563,6 +1204,8 @@ impl DapClient {
             lines_start_at_1: Some(true),
             columns_start_at_1: Some(true),
             path_format: Some("path".to_string()),
+            supports_memory_references: Some(true),
+            supports_variable_paging: Some(true),
         };
 
         let response = self
@@ -584,11 +1227,63 @@ impl DapClient {
                     .map_err(|e| Error::Dap(format!("Failed to parse capabilities: {}", e)))
             })?;
 
+        *self.capabilities.write().unwrap_or_else(|e| e.into_inner()) = Some(caps.clone());
+
         Ok(caps)
     }
 
+    /// Capabilities reported by the adapter, or `None` before initialize completes
+    pub fn capabilities(&self) -> Option<Capabilities> {
+        self.capabilities
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// The stored capabilities, for a 'capabilities' event handler to update
+    /// (see [`Capabilities::merge`])
+    pub(crate) fn capabilities_slot(&self) -> CapabilitiesSlot {
+        self.capabilities.clone()
+    }
+
+    /// The breakpoint the stopOnEntry workaround set, while it is set
+    pub fn entry_breakpoint(&self) -> Option<EntryBreakpoint> {
+        self.entry_breakpoint
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// The entry line the stopOnEntry workaround resolved, once launched
+    ///
+    /// A [`EntryLine::is_fallback`] line is a guess: the session reports it
+    /// as a diagnostic.
+    pub fn entry_line(&self) -> Option<EntryLine> {
+        self.entry_line
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// The entry breakpoint, for the 'stopped' handler to recognize the stop
+    /// at it (see [`EntryBreakpoint::record_stop`])
+    pub(crate) fn entry_breakpoint_slot(&self) -> EntryBreakpointSlot {
+        self.entry_breakpoint.clone()
+    }
+
+    /// Forget the entry breakpoint once the program stopped at it; the caller
+    /// removes it from the adapter
+    pub fn take_hit_entry_breakpoint(&self) -> Option<EntryBreakpoint> {
+        let mut slot = self
+            .entry_breakpoint
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        slot.take_if(|bp| bp.hit)
+    }
+
+    /// Send the launch request, or attach when `args["request"]` is "attach"
     pub async fn launch(&self, args: Value) -> Result<()> {
-        let response = self.send_request("launch", Some(args)).await?;
+        let response = self.send_request(launch_command(&args), Some(args)).await?;
 
         if !response.success {
             return Err(Error::Dap(format!("Launch failed: {:?}", response.message)));
@@ -615,20 +1310,59 @@ impl DapClient {
             launch_args,
             adapter_type,
             HashMap::new(),
+            None,
         )
         .await
     }
 
+    /// Initialize and launch, applying pending breakpoints before configurationDone
+    ///
+    /// `entry_line` overrides the detected first executable line used by the
+    /// stopOnEntry workaround.
     pub async fn initialize_and_launch_with_pending(
         &self,
         adapter_id: &str,
         launch_args: Value,
         adapter_type: Option<&str>,
         pending_breakpoints: HashMap<String, Vec<SourceBreakpoint>>,
+        entry_line: Option<usize>,
+    ) -> Result<()> {
+        self.launch_sequence(
+            adapter_id,
+            launch_args,
+            adapter_type,
+            pending_breakpoints,
+            entry_line,
+            None,
+        )
+        .await
+    }
+
+    /// The initialize + launch handshake; with a `deadline`, every phase is
+    /// bounded by it and a timeout names the phase that was running
+    async fn launch_sequence(
+        &self,
+        adapter_id: &str,
+        launch_args: Value,
+        adapter_type: Option<&str>,
+        pending_breakpoints: HashMap<String, Vec<SourceBreakpoint>>,
+        entry_line: Option<usize>,
+        deadline: Option<LaunchDeadline>,
     ) -> Result<()> {
+        // An entry breakpoint of an earlier launch on this client is gone
+        *self
+            .entry_breakpoint
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = None;
+
         // Step 1: Send initialize request and get capabilities
         info!("Sending initialize request to adapter");
-        let capabilities = self.initialize(adapter_id).await?;
+        let capabilities = LaunchDeadline::bound(
+            deadline,
+            LaunchPhase::Initialize,
+            self.initialize(adapter_id),
+        )
+        .await?;
         debug!(
             "Adapter capabilities: supportsConfigurationDoneRequest={:?}",
             capabilities.supports_configuration_done_request
@@ -699,7 +1433,8 @@ impl DapClient {
         let (init_tx, init_rx) = oneshot::channel();
         let init_tx = Arc::new(tokio::sync::Mutex::new(Some(init_tx)));
 
-        self.on_event("initialized", move |_event| {
+        // Replaces the handler of an earlier launch on this client
+        self.replace_event_handler("initialized", "initialize_and_launch", move |_event| {
             info!("Received 'initialized' event - signaling");
             let tx = init_tx.clone();
             // Just signal - don't call any async methods from here
@@ -712,157 +1447,233 @@ impl DapClient {
         })
         .await;
 
-        // Step 3: Send launch request (doesn't wait for response yet)
-        info!("Sending launch request with args: {:?}", launch_args);
-        let launch_seq = self
-            .send_request_nowait("launch", Some(launch_args))
-            .await?;
-        info!("Launch request sent with seq {}", launch_seq);
+        // Step 3: Send launch (or attach) request (doesn't wait for response yet)
+        let command = launch_command(&launch_args);
+        info!("Sending {} request with args: {:?}", command, launch_args);
+        let mut launch = LaunchDeadline::bound(
+            deadline,
+            LaunchPhase::Launch,
+            self.send_request_deferred(command, Some(launch_args)),
+        )
+        .await?;
+        info!("Launch request sent with seq {}", launch.seq());
+        // Some adapters answer launch before 'initialized', most only after configurationDone
+        let mut launch_response = None;
 
         // Step 4: Wait for 'initialized' event signal
         if config_done_supported {
-            info!("Waiting for 'initialized' event (timeout: 5s)...");
-            match tokio::time::timeout(tokio::time::Duration::from_secs(5), init_rx).await {
-                Ok(Ok(())) => {
-                    info!("✅ Received 'initialized' event signal");
+            // Without a handshake deadline, leave the initialize request its
+            // share of the limit; under one, the deadline alone bounds the wait
+            let initialized_timeout = self
+                .initialize_timeout()
+                .saturating_sub(Duration::from_secs(2))
+                .max(self.initialize_timeout() / 2);
+            let initialized_deadline = match deadline {
+                Some(deadline) => deadline.at,
+                None => tokio::time::Instant::now() + initialized_timeout,
+            };
+            let launch_timeout = self.request_timeouts().for_command(command);
+            info!(
+                "Waiting for 'initialized' event (timeout: {:?})...",
+                initialized_deadline.saturating_duration_since(tokio::time::Instant::now())
+            );
+            let mut init_rx = init_rx;
+            let initialized = async {
+                loop {
+                    tokio::select! {
+                        signal = &mut init_rx => {
+                            return signal.map_err(|_| {
+                                Error::Dap("'initialized' event signal was cancelled".to_string())
+                            });
+                        }
+                        // A failed launch never sends 'initialized': report it right away
+                        response = self.wait_for_response(&mut launch, launch_timeout),
+                            if launch_response.is_none() =>
+                        {
+                            launch_response = Some(launch_result(command, response)?);
+                        }
+                    }
+                }
+            };
+            match tokio::time::timeout_at(initialized_deadline, initialized).await {
+                Ok(Ok(())) => info!("✅ Received 'initialized' event signal"),
+                Ok(Err(e)) => {
+                    error!("❌ {}", e);
+                    return Err(e);
+                }
+                Err(_) => {
+                    error!(
+                        "❌ Timeout waiting for 'initialized' event ({:?})",
+                        initialized_timeout
+                    );
+                    error!("   This usually means:");
+                    error!("   1. The program path is invalid or not found");
+                    error!("   2. The Python environment doesn't have the target program");
+                    error!("   3. The program has a syntax error preventing launch");
+                    error!("   4. debugpy couldn't start the target program");
+                    error!("   Check that the program path exists and is executable");
+                    return Err(match deadline {
+                        Some(deadline) => deadline.expired(LaunchPhase::WaitingForInitialized),
+                        None => Error::Dap(format!("Timeout waiting for 'initialized' event ({:?}). Program may not exist or has errors.", initialized_timeout)),
+                    });
+                }
+            }
 
-                    // Apply pending breakpoints BEFORE configurationDone (correct DAP sequence)
-                    if !pending_breakpoints.is_empty() {
+            // Step 5: Now send the breakpoints and configurationDone from main
+            // context (not from event handler)
+            let configure = async {
+                // Apply pending breakpoints BEFORE configurationDone (correct DAP sequence)
+                if !pending_breakpoints.is_empty() {
+                    info!(
+                        "🔧 Applying {} pending breakpoints before configurationDone",
+                        pending_breakpoints.len()
+                    );
+                    for (source_path, breakpoints) in &pending_breakpoints {
                         info!(
-                            "🔧 Applying {} pending breakpoints before configurationDone",
-                            pending_breakpoints.len()
+                            "  Setting {} breakpoints for {}",
+                            breakpoints.len(),
+                            source_path
                         );
-                        for (source_path, breakpoints) in &pending_breakpoints {
-                            info!(
-                                "  Setting {} breakpoints for {}",
-                                breakpoints.len(),
-                                source_path
-                            );
+                        let source = Source {
+                            path: Some(source_path.clone()),
+                            name: None,
+                            source_reference: None,
+                            origin: None,
+                        };
+                        match self.set_breakpoints(source, breakpoints.clone()).await {
+                            Ok(bps) => {
+                                info!("  ✅ Set {} breakpoints for {}", bps.len(), source_path);
+                                for bp in &bps {
+                                    if bp.verified {
+                                        info!("    Line {}: verified", bp.line.unwrap_or(0));
+                                    } else {
+                                        warn!("    Line {}: NOT verified", bp.line.unwrap_or(0));
+                                    }
+                                }
+                                let hook = self.breakpoints_applied_hook.read().await.clone();
+                                if let Some(hook) = hook {
+                                    hook(source_path.clone(), breakpoints.clone(), bps).await;
+                                }
+                            }
+                            Err(e) => {
+                                warn!("  ⚠️  Failed to set breakpoints for {}: {}", source_path, e);
+                            }
+                        }
+                    }
+                }
+
+                // Entry breakpoint workaround: Set breakpoint BEFORE configurationDone
+                // This follows the correct DAP sequence (setBreakpoints must be before configurationDone)
+                if needs_workaround {
+                    info!(
+                        "🔧 Applying {} stopOnEntry workaround: setting entry breakpoint",
+                        adapter_type_str
+                    );
+                    info!("   (Per DAP spec: breakpoints must be set BEFORE configurationDone)");
+
+                    match program_path_for_breakpoint.as_deref() {
+                        Some(path) => {
+                            // Find first executable line based on language
+                            let language = SourceLanguage::from_language(adapter_type_str)
+                                .unwrap_or(SourceLanguage::JavaScript);
+                            let resolved =
+                                source_analysis::resolve_entry_line(language, path, entry_line);
+                            let entry_line = resolved.line;
+                            *self.entry_line.lock().unwrap_or_else(|e| e.into_inner()) =
+                                Some(resolved);
+                            info!("  Entry breakpoint will be set at line {}", entry_line);
+
+                            // Create breakpoint at entry line
                             let source = Source {
-                                path: Some(source_path.clone()),
+                                path: Some(path.to_string()),
                                 name: None,
                                 source_reference: None,
+                                origin: None,
+                            };
+
+                            let breakpoint = SourceBreakpoint {
+                                line: entry_line as i32,
+                                column: None,
+                                condition: None,
+                                hit_condition: None,
+                                log_message: None,
+                            };
+
+                            // setBreakpoints replaces every breakpoint in the file, so
+                            // resend any pending ones alongside the entry breakpoint
+                            let mut breakpoints =
+                                pending_breakpoints.get(path).cloned().unwrap_or_default();
+                            let user_breakpoint = breakpoints
+                                .iter()
+                                .any(|bp| bp.line == breakpoint.line && bp.column.is_none());
+                            breakpoints.push(breakpoint);
+
+                            // Set breakpoint BEFORE configurationDone (per DAP spec)
+                            let result = if user_breakpoint {
+                                info!(
+                                    "  Line {} already has a breakpoint: it stops the program at entry",
+                                    entry_line
+                                );
+                                Ok(Vec::new())
+                            } else {
+                                self.set_breakpoints(source, breakpoints).await
                             };
-                            match self.set_breakpoints(source, breakpoints.clone()).await {
+                            match result {
                                 Ok(bps) => {
-                                    info!("  ✅ Set {} breakpoints for {}", bps.len(), source_path);
-                                    for bp in bps {
+                                    if let Some(bp) = bps.last() {
+                                        *self
+                                            .entry_breakpoint
+                                            .lock()
+                                            .unwrap_or_else(|e| e.into_inner()) = Some(
+                                            EntryBreakpoint::new(path, entry_line as i32, bp.id),
+                                        );
                                         if bp.verified {
-                                            info!("    Line {}: verified", bp.line.unwrap_or(0));
+                                            info!(
+                                                "✅ Entry breakpoint set at line {} (verified)",
+                                                entry_line
+                                            );
                                         } else {
                                             warn!(
-                                                "    Line {}: NOT verified",
-                                                bp.line.unwrap_or(0)
+                                                "⚠️  Entry breakpoint not verified at line {}",
+                                                entry_line
                                             );
+                                            warn!("   Program may not stop - check if line is executable");
                                         }
                                     }
                                 }
                                 Err(e) => {
-                                    warn!(
-                                        "  ⚠️  Failed to set breakpoints for {}: {}",
-                                        source_path, e
-                                    );
+                                    warn!("⚠️  Failed to set entry breakpoint: {}", e);
+                                    warn!("   Continuing anyway - program might not stop at entry");
                                 }
                             }
                         }
-                    }
-
-                    // Entry breakpoint workaround: Set breakpoint BEFORE configurationDone
-                    // This follows the correct DAP sequence (setBreakpoints must be before configurationDone)
-                    if needs_workaround {
-                        info!(
-                            "🔧 Applying {} stopOnEntry workaround: setting entry breakpoint",
-                            adapter_type_str
-                        );
-                        info!(
-                            "   (Per DAP spec: breakpoints must be set BEFORE configurationDone)"
-                        );
-
-                        match program_path_for_breakpoint.as_deref() {
-                            Some(path) => {
-                                // Find first executable line based on language
-                                let entry_line = if adapter_type_str == "ruby" {
-                                    Self::find_first_executable_line_ruby(path)
-                                } else if adapter_type_str == "go" {
-                                    Self::find_first_executable_line_go(path)
-                                } else {
-                                    Self::find_first_executable_line_javascript(path)
-                                };
-                                info!("  Entry breakpoint will be set at line {}", entry_line);
-
-                                // Create breakpoint at entry line
-                                let source = Source {
-                                    path: Some(path.to_string()),
-                                    name: None,
-                                    source_reference: None,
-                                };
-
-                                let breakpoint = SourceBreakpoint {
-                                    line: entry_line as i32,
-                                    column: None,
-                                    condition: None,
-                                    hit_condition: None,
-                                };
-
-                                // Set breakpoint BEFORE configurationDone (per DAP spec)
-                                match self.set_breakpoints(source, vec![breakpoint]).await {
-                                    Ok(bps) => {
-                                        if let Some(bp) = bps.first() {
-                                            if bp.verified {
-                                                info!(
-                                                    "✅ Entry breakpoint set at line {} (verified)",
-                                                    entry_line
-                                                );
-                                            } else {
-                                                warn!(
-                                                    "⚠️  Entry breakpoint not verified at line {}",
-                                                    entry_line
-                                                );
-                                                warn!("   Program may not stop - check if line is executable");
-                                            }
-                                        }
-                                    }
-                                    Err(e) => {
-                                        warn!("⚠️  Failed to set entry breakpoint: {}", e);
-                                        warn!("   Continuing anyway - program might not stop at entry");
-                                    }
-                                }
-                            }
-                            None => {
-                                warn!("⚠️  No program path in launch args - cannot set entry breakpoint");
-                                warn!("   {} stopOnEntry may not work", adapter_type_str);
-                            }
+                        None => {
+                            warn!(
+                                "⚠️  No program path in launch args - cannot set entry breakpoint"
+                            );
+                            warn!("   {} stopOnEntry may not work", adapter_type_str);
                         }
                     }
                 }
-                Ok(Err(_)) => {
-                    error!("❌ 'initialized' event signal was cancelled");
-                    return Err(Error::Dap(
-                        "'initialized' event signal was cancelled".to_string(),
-                    ));
-                }
-                Err(_) => {
-                    error!("❌ Timeout waiting for 'initialized' event (5s)");
-                    error!("   This usually means:");
-                    error!("   1. The program path is invalid or not found");
-                    error!("   2. The Python environment doesn't have the target program");
-                    error!("   3. The program has a syntax error preventing launch");
-                    error!("   4. debugpy couldn't start the target program");
-                    error!("   Check that the program path exists and is executable");
-                    return Err(Error::Dap("Timeout waiting for 'initialized' event (5s). Program may not exist or has errors.".to_string()));
-                }
-            }
 
-            // Step 5: Now send configurationDone from main context (not from event handler)
-            info!("Sending configurationDone");
-            self.configuration_done().await?;
+                info!("Sending configurationDone");
+                self.configuration_done().await
+            };
+            LaunchDeadline::bound(deadline, LaunchPhase::ConfigurationDone, configure).await?;
             info!("configurationDone completed");
         }
 
-        // Step 6: Wait for launch response (using wait_for_event on the response)
-        // The launch response should arrive shortly after configurationDone
-        info!("Waiting for launch to complete");
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        // Step 6: Wait for the launch response, which most adapters only send
+        // after configurationDone
+        if launch_response.is_none() {
+            info!("Waiting for {} response", command);
+            let response =
+                self.wait_for_response(&mut launch, self.request_timeouts().for_command(command));
+            let response =
+                LaunchDeadline::bound(deadline, LaunchPhase::Launch, async { Ok(response.await) })
+                    .await?;
+            launch_result(command, response)?;
+        }
 
         info!("Launch sequence completed successfully");
         Ok(())
@@ -873,15 +1684,25 @@ impl DapClient {
     #[allow(dead_code)]
     fn clone_for_callback(&self) -> Self {
         Self {
-            transport: self.transport.clone(),
             seq_counter: self.seq_counter.clone(),
             pending_requests: self.pending_requests.clone(),
-            event_tx: self.event_tx.clone(),
             event_notifiers: self.event_notifiers.clone(),
             event_callbacks: self.event_callbacks.clone(),
-            child_session_spawn_callback: self.child_session_spawn_callback.clone(),
+            reverse_request_handlers: self.reverse_request_handlers.clone(),
             write_tx: self.write_tx.clone(),
-            _child: None, // Don't clone the child process
+            write_queue_timeout: self.write_queue_timeout.clone(),
+            request_timeouts: self.request_timeouts.clone(),
+            initialize_timeout: self.initialize_timeout.clone(),
+            breakpoints_applied_hook: self.breakpoints_applied_hook.clone(),
+            entry_breakpoint: self.entry_breakpoint.clone(),
+            entry_line: self.entry_line.clone(),
+            capabilities: self.capabilities.clone(),
+            dap_log: self.dap_log.clone(),
+            metrics: self.metrics.clone(),
+            span: self.span.clone(),
+            adapter_process: std::sync::Mutex::new(None), // Don't clone the child process
+            terminal_processes: self.terminal_processes.clone(),
+            transport: self.transport.clone(),
         }
     }
 
@@ -964,261 +1785,201 @@ impl DapClient {
         Ok(body.breakpoints)
     }
 
-    pub async fn continue_execution(&self, thread_id: i32) -> Result<()> {
-        let args = ContinueArguments { thread_id };
+    /// Replace all function breakpoints with `breakpoints`
+    ///
+    /// Fails without contacting the adapter if it has reported that it lacks
+    /// `supportsFunctionBreakpoints`.
+    pub async fn set_function_breakpoints(
+        &self,
+        breakpoints: Vec<FunctionBreakpoint>,
+    ) -> Result<Vec<Breakpoint>> {
+        let supported = self
+            .capabilities()
+            .map(|caps| caps.supports_function_breakpoints.unwrap_or(false));
+        if supported == Some(false) {
+            return Err(Error::InvalidRequest(
+                "The debug adapter does not support function breakpoints \
+                 (supportsFunctionBreakpoints). Set a line breakpoint instead"
+                    .to_string(),
+            ));
+        }
 
+        info!(
+            "🔧 set_function_breakpoints: {:?}",
+            breakpoints.iter().map(|bp| &bp.name).collect::<Vec<_>>()
+        );
+
+        let args = SetFunctionBreakpointsArguments { breakpoints };
         let response = self
-            .send_request("continue", Some(serde_json::to_value(args)?))
+            .send_request("setFunctionBreakpoints", Some(serde_json::to_value(args)?))
             .await?;
 
         if !response.success {
             return Err(Error::Dap(format!(
-                "Continue failed: {:?}",
+                "SetFunctionBreakpoints failed: {:?}",
                 response.message
             )));
         }
 
-        Ok(())
+        #[derive(serde::Deserialize)]
+        struct SetFunctionBreakpointsResponse {
+            breakpoints: Vec<Breakpoint>,
+        }
+
+        let body: SetFunctionBreakpointsResponse = response
+            .body
+            .ok_or_else(|| Error::Dap("No breakpoints in response".to_string()))
+            .and_then(|v| {
+                serde_json::from_value(v)
+                    .map_err(|e| Error::Dap(format!("Failed to parse breakpoints: {}", e)))
+            })?;
+
+        Ok(body.breakpoints)
     }
 
-    /// Find the first executable line in a Ruby source file
+    /// Whether a data breakpoint can be set on `name` (a child of
+    /// `variables_reference`, or an expression in `frame_id`), and its dataId
     ///
-    /// Skips comments, empty lines, requires, and class/module definitions
-    /// to find the first actual executable line.
-    ///
-    /// Returns line number (1-indexed) or 1 as fallback.
-    fn find_first_executable_line_ruby(program_path: &str) -> usize {
-        use std::fs;
-
-        let content = match fs::read_to_string(program_path) {
-            Ok(c) => c,
-            Err(e) => {
-                warn!(
-                    "Could not read {} for line detection: {}, using line 1",
-                    program_path, e
-                );
-                return 1;
-            }
+    /// Fails without contacting the adapter if it has reported that it lacks
+    /// `supportsDataBreakpoints`.
+    pub async fn data_breakpoint_info(
+        &self,
+        variables_reference: Option<i32>,
+        name: &str,
+        frame_id: Option<i32>,
+    ) -> Result<DataBreakpointInfoResponse> {
+        self.require_data_breakpoints()?;
+
+        let args = DataBreakpointInfoArguments {
+            variables_reference,
+            name: name.to_string(),
+            frame_id,
         };
+        let response = self
+            .send_request("dataBreakpointInfo", Some(serde_json::to_value(args)?))
+            .await?;
 
-        for (line_num, line) in content.lines().enumerate() {
-            let trimmed = line.trim();
-
-            // Skip empty lines
-            if trimmed.is_empty() {
-                continue;
-            }
-
-            // Skip shebang
-            if line_num == 0 && trimmed.starts_with("#!") {
-                continue;
-            }
-
-            // Skip comments
-            if trimmed.starts_with('#') {
-                continue;
-            }
-
-            // Skip requires/loads (not executable, just declarations)
-            if trimmed.starts_with("require") || trimmed.starts_with("load") {
-                continue;
-            }
-
-            // Skip class/module/def declarations (not entry point)
-            if trimmed.starts_with("class ") || trimmed.starts_with("module ") {
-                // Continue looking inside the class for executable code
-                continue;
-            }
-
-            // Found first executable line!
-            info!("  First executable line detected: {}", line_num + 1);
-            return line_num + 1; // DAP uses 1-indexed lines
+        if !response.success {
+            return Err(Error::Dap(format!(
+                "DataBreakpointInfo failed: {:?}",
+                response.message
+            )));
         }
 
-        // Fallback: No executable line found, use line 1
-        warn!("No executable line found in {}, using line 1", program_path);
-        1
+        response
+            .body
+            .ok_or_else(|| Error::Dap("No body in dataBreakpointInfo response".to_string()))
+            .and_then(|v| {
+                serde_json::from_value(v).map_err(|e| {
+                    Error::Dap(format!(
+                        "Failed to parse dataBreakpointInfo response: {}",
+                        e
+                    ))
+                })
+            })
     }
 
-    /// Find the first executable line in a Go source file
-    ///
-    /// Skips package declarations, imports, comments, and function signatures
-    /// to find the first actual executable line (typically inside main function).
+    /// Replace all data breakpoints with `breakpoints`
     ///
-    /// Returns line number (1-indexed) or 1 as fallback.
-    fn find_first_executable_line_go(program_path: &str) -> usize {
-        use std::fs;
+    /// Fails without contacting the adapter if it has reported that it lacks
+    /// `supportsDataBreakpoints`.
+    pub async fn set_data_breakpoints(
+        &self,
+        breakpoints: Vec<DataBreakpoint>,
+    ) -> Result<Vec<Breakpoint>> {
+        self.require_data_breakpoints()?;
 
-        let content = match fs::read_to_string(program_path) {
-            Ok(c) => c,
-            Err(e) => {
-                warn!(
-                    "Could not read {} for line detection: {}, using line 1",
-                    program_path, e
-                );
-                return 1;
-            }
-        };
+        info!(
+            "🔧 set_data_breakpoints: {:?}",
+            breakpoints.iter().map(|bp| &bp.data_id).collect::<Vec<_>>()
+        );
 
-        let mut in_func_main = false;
+        let args = SetDataBreakpointsArguments { breakpoints };
+        let response = self
+            .send_request("setDataBreakpoints", Some(serde_json::to_value(args)?))
+            .await?;
 
-        for (line_num, line) in content.lines().enumerate() {
-            let trimmed = line.trim();
+        if !response.success {
+            return Err(Error::Dap(format!(
+                "SetDataBreakpoints failed: {:?}",
+                response.message
+            )));
+        }
 
-            // Skip empty lines
-            if trimmed.is_empty() {
-                continue;
-            }
+        #[derive(serde::Deserialize)]
+        struct SetDataBreakpointsResponse {
+            breakpoints: Vec<Breakpoint>,
+        }
 
-            // Skip package declaration
-            if trimmed.starts_with("package ") {
-                continue;
-            }
+        let body: SetDataBreakpointsResponse = response
+            .body
+            .ok_or_else(|| Error::Dap("No breakpoints in response".to_string()))
+            .and_then(|v| {
+                serde_json::from_value(v)
+                    .map_err(|e| Error::Dap(format!("Failed to parse breakpoints: {}", e)))
+            })?;
 
-            // Skip import statements (single line and multi-line)
-            if trimmed.starts_with("import ") || trimmed.starts_with("import(") {
-                continue;
-            }
+        Ok(body.breakpoints)
+    }
 
-            // Skip comments (// and /*)
-            if trimmed.starts_with("//") || trimmed.starts_with("/*") {
-                continue;
-            }
+    fn require_data_breakpoints(&self) -> Result<()> {
+        let supported = self
+            .capabilities()
+            .map(|caps| caps.supports_data_breakpoints.unwrap_or(false));
+        if supported == Some(false) {
+            return Err(Error::InvalidRequest(
+                "The debug adapter does not support data breakpoints \
+                 (supportsDataBreakpoints). Data breakpoints are available for \
+                 Rust, C and C++ (CodeLLDB)"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
 
-            // Detect main function
-            if trimmed.starts_with("func main()") {
-                in_func_main = true;
-                continue;
-            }
+    pub async fn continue_execution(&self, thread_id: i32) -> Result<()> {
+        let args = ContinueArguments { thread_id };
 
-            // If we're inside main function, find first executable line
-            if in_func_main {
-                // Skip opening brace, comments, and variable declarations without initialization
-                if trimmed == "{" || trimmed.starts_with("//") || trimmed.starts_with("var ") {
-                    continue;
-                }
+        let response = self
+            .send_request("continue", Some(serde_json::to_value(args)?))
+            .await?;
 
-                // Found first executable line inside main!
-                info!(
-                    "  First executable line detected (Go main): {}",
-                    line_num + 1
-                );
-                return line_num + 1; // DAP uses 1-indexed lines
-            }
+        if !response.success {
+            return Err(Error::Dap(format!(
+                "Continue failed: {:?}",
+                response.message
+            )));
         }
 
-        // Fallback: No executable line found, use line 1
-        warn!("No executable line found in {}, using line 1", program_path);
-        1
+        Ok(())
     }
 
-    /// Find the first executable line in a JavaScript/TypeScript source file
-    ///
-    /// Skips comments, empty lines, imports, and type declarations
-    /// to find the first actual executable line.
-    ///
-    /// Returns line number (1-indexed) or 1 as fallback.
-    pub fn find_first_executable_line_javascript(program_path: &str) -> usize {
-        use std::fs;
-
-        let content = match fs::read_to_string(program_path) {
-            Ok(c) => c,
-            Err(e) => {
-                warn!(
-                    "Could not read {} for line detection: {}, using line 1",
-                    program_path, e
-                );
-                return 1;
-            }
+    /// Fail early when `granularity` is requested from an adapter that
+    /// would silently step by statement instead
+    fn require_stepping_granularity(&self, granularity: Option<&str>) -> Result<()> {
+        let Some(granularity) = granularity else {
+            return Ok(());
         };
-
-        let mut in_multiline_comment = false;
-
-        for (line_num, line) in content.lines().enumerate() {
-            let trimmed = line.trim();
-
-            // Handle multiline comments
-            if in_multiline_comment {
-                if trimmed.contains("*/") {
-                    in_multiline_comment = false;
-                }
-                continue;
-            }
-
-            if trimmed.contains("/*") {
-                in_multiline_comment = true;
-                continue;
-            }
-
-            // Skip empty lines
-            if trimmed.is_empty() {
-                continue;
-            }
-
-            // Skip shebang
-            if line_num == 0 && trimmed.starts_with("#!") {
-                continue;
-            }
-
-            // Skip single-line comments
-            if trimmed.starts_with("//") {
-                continue;
-            }
-
-            // Skip import/require statements (not executable, just declarations)
-            if trimmed.starts_with("import ")
-                || trimmed.starts_with("export ")
-                || trimmed.starts_with("require(")
-                || trimmed.starts_with("const ") && trimmed.contains("require(")
-            {
-                continue;
-            }
-
-            // Skip type declarations (TypeScript)
-            if trimmed.starts_with("type ") || trimmed.starts_with("interface ") {
-                continue;
-            }
-
-            // Skip function declarations (look for first line OUTSIDE function)
-            if trimmed.starts_with("function ")
-                || trimmed.starts_with("const ") && trimmed.contains("=>")
-            {
-                // Skip function declarations - we want module-level code
-                continue;
-            }
-
-            // Skip class declarations
-            if trimmed.starts_with("class ") {
-                continue;
-            }
-
-            // Skip variable declarations without initialization
-            if (trimmed.starts_with("let ")
-                || trimmed.starts_with("var ")
-                || trimmed.starts_with("const "))
-                && !trimmed.contains('=')
-            {
-                continue;
-            }
-
-            // Skip indented lines (likely inside function/class bodies)
-            // Module-level executable code should start at column 0
-            if line.starts_with(' ') || line.starts_with('\t') {
-                continue;
-            }
-
-            // Found first executable line at module level!
-            info!("  First executable line detected: {}", line_num + 1);
-            return line_num + 1; // DAP uses 1-indexed lines
+        let supported = self
+            .capabilities()
+            .map(|caps| caps.supports_stepping_granularity.unwrap_or(false));
+        if supported == Some(false) {
+            return Err(Error::InvalidRequest(format!(
+                "The debug adapter does not support stepping by {} \
+                 (supportsSteppingGranularity). Instruction stepping is available for \
+                 Rust, C and C++ (CodeLLDB)",
+                granularity
+            )));
         }
-
-        // Fallback: No executable line found, use line 1
-        warn!("No executable line found in {}, using line 1", program_path);
-        1
+        Ok(())
     }
 
-    pub async fn next(&self, thread_id: i32) -> Result<()> {
-        let args = NextArguments { thread_id };
+    pub async fn next(&self, thread_id: i32, granularity: Option<&str>) -> Result<()> {
+        self.require_stepping_granularity(granularity)?;
+        let args = NextArguments {
+            thread_id,
+            granularity: granularity.map(str::to_string),
+        };
 
         let response = self
             .send_request("next", Some(serde_json::to_value(args)?))
@@ -1234,8 +1995,23 @@ impl DapClient {
         Ok(())
     }
 
-    pub async fn step_in(&self, thread_id: i32) -> Result<()> {
-        let args = StepInArguments { thread_id };
+    /// Step into the call `target_id` (from [`Self::step_in_targets`]) or,
+    /// without one, the first call on the line
+    pub async fn step_in(
+        &self,
+        thread_id: i32,
+        granularity: Option<&str>,
+        target_id: Option<i32>,
+    ) -> Result<()> {
+        self.require_stepping_granularity(granularity)?;
+        if target_id.is_some() {
+            self.require_step_in_targets()?;
+        }
+        let args = StepInArguments {
+            thread_id,
+            target_id,
+            granularity: granularity.map(str::to_string),
+        };
 
         let response = self
             .send_request("stepIn", Some(serde_json::to_value(args)?))
@@ -1248,16 +2024,28 @@ impl DapClient {
         Ok(())
     }
 
-    pub async fn step_out(&self, thread_id: i32) -> Result<()> {
-        let args = StepOutArguments { thread_id };
+    /// Re-run the function of `frame_id` from its first line
+    pub async fn restart_frame(&self, frame_id: i32) -> Result<()> {
+        let supported = self
+            .capabilities()
+            .map(|caps| caps.supports_restart_frame.unwrap_or(false));
+        if supported == Some(false) {
+            return Err(Error::InvalidRequest(
+                "The debug adapter does not support restarting a frame \
+                 (supportsRestartFrame). Restarting frames is available for \
+                 Python (debugpy) and Rust, C and C++ (CodeLLDB)"
+                    .to_string(),
+            ));
+        }
 
+        let args = RestartFrameArguments { frame_id };
         let response = self
-            .send_request("stepOut", Some(serde_json::to_value(args)?))
+            .send_request("restartFrame", Some(serde_json::to_value(args)?))
             .await?;
 
         if !response.success {
             return Err(Error::Dap(format!(
-                "StepOut failed: {:?}",
+                "RestartFrame failed: {:?}",
                 response.message
             )));
         }
@@ -1265,31 +2053,162 @@ impl DapClient {
         Ok(())
     }
 
-    pub async fn stack_trace(&self, thread_id: i32) -> Result<Vec<StackFrame>> {
-        let args = StackTraceArguments {
-            thread_id,
-            start_frame: None,
-            levels: None,
+    /// Fails with [`Error::InvalidRequest`] when the adapter does not
+    /// report supportsGotoTargetsRequest
+    fn require_goto(&self) -> Result<()> {
+        let supported = self
+            .capabilities()
+            .map(|caps| caps.supports_goto_targets_request.unwrap_or(false));
+        if supported == Some(false) {
+            return Err(Error::InvalidRequest(
+                "The debug adapter does not support jumping to a line \
+                 (supportsGotoTargetsRequest). Jumping is available for Python (debugpy)"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Locations at `line` of `source` the program counter can be moved to
+    pub async fn goto_targets(&self, source: Source, line: i32) -> Result<Vec<GotoTarget>> {
+        self.require_goto()?;
+        let args = GotoTargetsArguments {
+            source,
+            line,
+            column: None,
         };
+        let response = self
+            .send_request("gotoTargets", Some(serde_json::to_value(args)?))
+            .await?;
+
+        if !response.success {
+            return Err(Error::Dap(format!(
+                "GotoTargets failed: {:?}",
+                response.message
+            )));
+        }
+
+        let targets = response
+            .body
+            .and_then(|body| body.get("targets").cloned())
+            .ok_or_else(|| Error::Dap("No targets in gotoTargets response".to_string()))?;
+        serde_json::from_value(targets)
+            .map_err(|e| Error::Dap(format!("Failed to parse goto targets: {}", e)))
+    }
 
+    /// Move `thread_id` to `target_id` (from [`Self::goto_targets`]) without
+    /// executing the code in between; the adapter then reports a 'stopped'
+    /// event with reason "goto"
+    pub async fn goto(&self, thread_id: i32, target_id: i32) -> Result<()> {
+        self.require_goto()?;
+        let args = GotoArguments {
+            thread_id,
+            target_id,
+        };
         let response = self
-            .send_request("stackTrace", Some(serde_json::to_value(args)?))
+            .send_request("goto", Some(serde_json::to_value(args)?))
+            .await?;
+
+        if !response.success {
+            return Err(Error::Dap(format!("Goto failed: {:?}", response.message)));
+        }
+
+        Ok(())
+    }
+
+    /// Fails with [`Error::InvalidRequest`] when the adapter does not
+    /// report supportsStepInTargetsRequest
+    fn require_step_in_targets(&self) -> Result<()> {
+        let supported = self
+            .capabilities()
+            .map(|caps| caps.supports_step_in_targets_request.unwrap_or(false));
+        if supported == Some(false) {
+            return Err(Error::InvalidRequest(
+                "The debug adapter does not support choosing a call to step into \
+                 (supportsStepInTargetsRequest). Step into targets are available for \
+                 Python (debugpy) and JavaScript (vscode-js-debug)"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The calls on the current line of `frame_id` that stepIn can enter
+    pub async fn step_in_targets(&self, frame_id: i32) -> Result<Vec<StepInTarget>> {
+        self.require_step_in_targets()?;
+        let args = StepInTargetsArguments { frame_id };
+        let response = self
+            .send_request("stepInTargets", Some(serde_json::to_value(args)?))
             .await?;
 
         if !response.success {
             return Err(Error::Dap(format!(
-                "StackTrace failed: {:?}",
+                "StepInTargets failed: {:?}",
                 response.message
             )));
         }
 
-        #[derive(serde::Deserialize)]
-        struct StackTraceResponse {
-            #[serde(rename = "stackFrames")]
-            stack_frames: Vec<StackFrame>,
+        let targets = response
+            .body
+            .and_then(|body| body.get("targets").cloned())
+            .ok_or_else(|| Error::Dap("No targets in stepInTargets response".to_string()))?;
+        serde_json::from_value(targets)
+            .map_err(|e| Error::Dap(format!("Failed to parse step in targets: {}", e)))
+    }
+
+    pub async fn step_out(&self, thread_id: i32, granularity: Option<&str>) -> Result<()> {
+        self.require_stepping_granularity(granularity)?;
+        let args = StepOutArguments {
+            thread_id,
+            granularity: granularity.map(str::to_string),
+        };
+
+        let response = self
+            .send_request("stepOut", Some(serde_json::to_value(args)?))
+            .await?;
+
+        if !response.success {
+            return Err(Error::Dap(format!(
+                "StepOut failed: {:?}",
+                response.message
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub async fn stack_trace(&self, thread_id: i32) -> Result<Vec<StackFrame>> {
+        self.stack_trace_page(thread_id, None, None)
+            .await
+            .map(|page| page.stack_frames)
+    }
+
+    /// Frames `start_frame..start_frame + levels` of a thread's stack (the
+    /// whole stack when `levels` is `None`)
+    pub async fn stack_trace_page(
+        &self,
+        thread_id: i32,
+        start_frame: Option<i32>,
+        levels: Option<i32>,
+    ) -> Result<StackTracePage> {
+        let args = StackTraceArguments {
+            thread_id,
+            start_frame,
+            levels,
+        };
+
+        let response = self
+            .send_request("stackTrace", Some(serde_json::to_value(args)?))
+            .await?;
+
+        if !response.success {
+            return Err(Error::Dap(format!(
+                "StackTrace failed: {:?}",
+                response.message
+            )));
         }
 
-        let body: StackTraceResponse = response
+        let mut page: StackTracePage = response
             .body
             .ok_or_else(|| Error::Dap("No stack frames in response".to_string()))
             .and_then(|v| {
@@ -1297,16 +2216,59 @@ impl DapClient {
                     .map_err(|e| Error::Dap(format!("Failed to parse stack frames: {}", e)))
             })?;
 
-        Ok(body.stack_frames)
+        // Adapters without supportsDelayedStackTraceLoading may ignore
+        // startFrame and send the whole stack (recognizable when it is as
+        // deep as totalFrames)
+        let start = start_frame.unwrap_or(0);
+        let returned = page.stack_frames.len() as i32;
+        if start > 0 && page.total_frames == Some(returned) {
+            page.stack_frames.drain(..(start.min(returned) as usize));
+        }
+
+        // Not every adapter honors 'levels'
+        if let Some(levels) = levels.filter(|&levels| levels > 0) {
+            let returned = page.stack_frames.len();
+            if returned > levels as usize {
+                page.stack_frames.truncate(levels as usize);
+                page.total_frames.get_or_insert(start + returned as i32);
+            }
+        }
+
+        Ok(page)
+    }
+
+    pub async fn evaluate(
+        &self,
+        expression: &str,
+        frame_id: Option<i32>,
+    ) -> Result<EvaluateResult> {
+        // Use "watch" for code expression evaluation, not "repl" (LLDB commands)
+        self.evaluate_in_context(expression, frame_id, "watch")
+            .await
     }
 
-    pub async fn evaluate(&self, expression: &str, frame_id: Option<i32>) -> Result<String> {
+    /// Evaluate `expression` in an explicit DAP evaluate context
+    ///
+    /// `context` is one of [`EVALUATE_CONTEXTS`]. Adapters treat "repl" as
+    /// console input (debugpy executes statements, CodeLLDB runs LLDB
+    /// commands), while "watch" and "hover" are meant for side-effect free
+    /// inspection.
+    pub async fn evaluate_in_context(
+        &self,
+        expression: &str,
+        frame_id: Option<i32>,
+        context: &str,
+    ) -> Result<EvaluateResult> {
         // If frame_id is None, get the top frame from stack trace
         let frame_id = if let Some(id) = frame_id {
             Some(id)
         } else {
             // Get current thread (assume thread 0 for simplicity)
-            match self.stack_trace(0).await {
+            match self
+                .stack_trace_page(0, None, Some(1))
+                .await
+                .map(|page| page.stack_frames)
+            {
                 Ok(frames) if !frames.is_empty() => {
                     info!("📍 Auto-fetched frame_id {} for evaluate", frames[0].id);
                     Some(frames[0].id)
@@ -1325,7 +2287,7 @@ impl DapClient {
         let args = EvaluateArguments {
             expression: expression.to_string(),
             frame_id,
-            context: Some("watch".to_string()), // Use "watch" for code expression evaluation, not "repl" (LLDB commands)
+            context: Some(context.to_string()),
         };
 
         let response = self
@@ -1339,75 +2301,363 @@ impl DapClient {
             )));
         }
 
+        response
+            .body
+            .ok_or_else(|| Error::Dap("No result in evaluate response".to_string()))
+            .and_then(|v| {
+                serde_json::from_value(v)
+                    .map_err(|e| Error::Dap(format!("Failed to parse evaluate result: {}", e)))
+            })
+    }
+
+    /// Positions in `source` between `line` and `end_line` (inclusive) where
+    /// breakpoints can bind
+    pub async fn breakpoint_locations(
+        &self,
+        source: Source,
+        line: i32,
+        end_line: Option<i32>,
+    ) -> Result<Vec<BreakpointLocation>> {
+        let args = BreakpointLocationsArguments {
+            source,
+            line,
+            column: None,
+            end_line,
+            end_column: None,
+        };
+
+        let response = self
+            .send_request("breakpointLocations", Some(serde_json::to_value(args)?))
+            .await?;
+
+        if !response.success {
+            return Err(Error::Dap(format!(
+                "BreakpointLocations failed: {:?}",
+                response.message
+            )));
+        }
+
         #[derive(serde::Deserialize)]
-        struct EvaluateResponse {
-            result: String,
+        struct BreakpointLocationsResponse {
+            breakpoints: Vec<BreakpointLocation>,
         }
 
-        let body: EvaluateResponse = response
+        let body: BreakpointLocationsResponse = response
             .body
-            .ok_or_else(|| Error::Dap("No result in evaluate response".to_string()))
+            .ok_or_else(|| Error::Dap("No breakpoint locations in response".to_string()))
             .and_then(|v| {
                 serde_json::from_value(v)
-                    .map_err(|e| Error::Dap(format!("Failed to parse evaluate result: {}", e)))
+                    .map_err(|e| Error::Dap(format!("Failed to parse breakpoint locations: {}", e)))
             })?;
 
-        Ok(body.result)
+        Ok(body.breakpoints)
     }
 
-    pub async fn disconnect(&self) -> Result<()> {
-        let response = self.send_request("disconnect", None).await?;
+    /// Every source the adapter has loaded so far
+    pub async fn loaded_sources(&self) -> Result<Vec<Source>> {
+        let supported = self
+            .capabilities()
+            .map(|caps| caps.supports_loaded_sources_request.unwrap_or(false));
+        if supported == Some(false) {
+            return Err(Error::InvalidRequest(
+                "The debug adapter does not support listing loaded sources \
+                 (supportsLoadedSourcesRequest)"
+                    .to_string(),
+            ));
+        }
+
+        let response = self
+            .send_request("loadedSources", Some(serde_json::json!({})))
+            .await?;
 
         if !response.success {
-            warn!("Disconnect failed: {:?}", response.message);
+            return Err(Error::Dap(format!(
+                "LoadedSources failed: {:?}",
+                response.message
+            )));
         }
 
-        Ok(())
+        let sources = response
+            .body
+            .and_then(|body| body.get("sources").cloned())
+            .ok_or_else(|| Error::Dap("No sources in loadedSources response".to_string()))?;
+        serde_json::from_value(sources)
+            .map_err(|e| Error::Dap(format!("Failed to parse loaded sources: {}", e)))
     }
 
-    // === Timeout Wrappers (Aggressive Timeouts) ===
+    /// Every module the debuggee has loaded so far
+    pub async fn modules(&self) -> Result<Vec<Module>> {
+        let supported = self
+            .capabilities()
+            .map(|caps| caps.supports_modules_request.unwrap_or(false));
+        if supported == Some(false) {
+            return Err(Error::InvalidRequest(
+                "The debug adapter does not support listing modules (supportsModulesRequest)"
+                    .to_string(),
+            ));
+        }
 
-    /// Initialize with 2 second timeout
-    /// DAP init takes ~100ms normally, 2s = 20x safety margin
-    pub async fn initialize_with_timeout(&self, adapter_id: &str) -> Result<Capabilities> {
-        let timeout = std::time::Duration::from_secs(2);
-        info!("⏱️  initialize_with_timeout: Starting with 2s timeout");
+        let response = self
+            .send_request("modules", Some(serde_json::json!({})))
+            .await?;
 
-        tokio::time::timeout(timeout, self.initialize(adapter_id))
-            .await
-            .map_err(|_| Error::Dap(format!("Initialize timed out after {:?}", timeout)))?
+        if !response.success {
+            return Err(Error::Dap(format!(
+                "Modules failed: {:?}",
+                response.message
+            )));
+        }
+
+        let modules = response
+            .body
+            .and_then(|body| body.get("modules").cloned())
+            .ok_or_else(|| Error::Dap("No modules in modules response".to_string()))?;
+        serde_json::from_value(modules)
+            .map_err(|e| Error::Dap(format!("Failed to parse modules: {}", e)))
     }
 
-    /// Launch with 5 second timeout
-    /// Launch is more complex and may involve file loading
-    pub async fn launch_with_timeout(&self, args: Value) -> Result<()> {
-        let timeout = std::time::Duration::from_secs(5);
-        info!("⏱️  launch_with_timeout: Starting with 5s timeout");
+    /// Complete the partial expression `text` with the cursor at `column`
+    /// (1-based), in the scope of `frame_id` (global scope when `None`)
+    pub async fn completions(
+        &self,
+        text: &str,
+        column: i32,
+        frame_id: Option<i32>,
+    ) -> Result<Vec<CompletionItem>> {
+        let supported = self
+            .capabilities()
+            .map(|caps| caps.supports_completions_request.unwrap_or(false));
+        if supported == Some(false) {
+            return Err(Error::InvalidRequest(
+                "The debug adapter does not support completions \
+                 (supportsCompletionsRequest)"
+                    .to_string(),
+            ));
+        }
 
-        tokio::time::timeout(timeout, self.launch(args))
-            .await
-            .map_err(|_| Error::Dap(format!("Launch timed out after {:?}", timeout)))?
+        let args = CompletionsArguments {
+            frame_id,
+            text: text.to_string(),
+            column,
+            line: None,
+        };
+        let response = self
+            .send_request("completions", Some(serde_json::to_value(args)?))
+            .await?;
+
+        if !response.success {
+            return Err(Error::Dap(format!(
+                "Completions failed: {:?}",
+                response.message
+            )));
+        }
+
+        let targets = response
+            .body
+            .and_then(|body| body.get("targets").cloned())
+            .ok_or_else(|| Error::Dap("No targets in completions response".to_string()))?;
+        serde_json::from_value(targets)
+            .map_err(|e| Error::Dap(format!("Failed to parse completions: {}", e)))
     }
 
-    /// Disconnect with 2 second timeout (force cleanup)
-    /// If disconnect hangs, we want to fail fast and let process cleanup handle it
-    pub async fn disconnect_with_timeout(&self) -> Result<()> {
-        let timeout = std::time::Duration::from_secs(2);
-        info!("⏱️  disconnect_with_timeout: Starting with 2s timeout");
+    /// Read `count` bytes at `memory_reference` + `offset`
+    ///
+    /// The reference comes from an evaluate result or a variable. Fails with
+    /// [`Error::InvalidRequest`] when the adapter does not support readMemory.
+    pub async fn read_memory(
+        &self,
+        memory_reference: &str,
+        offset: Option<i64>,
+        count: i64,
+    ) -> Result<ReadMemoryResult> {
+        let supported = self
+            .capabilities()
+            .map(|caps| caps.supports_read_memory_request.unwrap_or(false));
+        if supported == Some(false) {
+            return Err(Error::InvalidRequest(
+                "The debug adapter does not support reading memory \
+                 (supportsReadMemoryRequest)"
+                    .to_string(),
+            ));
+        }
 
-        tokio::time::timeout(timeout, self.disconnect())
-            .await
-            .map_err(|_| {
-                warn!(
-                    "Disconnect timed out after {:?}, proceeding anyway",
-                    timeout
-                );
-                Error::Dap(format!("Disconnect timed out after {:?}", timeout))
-            })?
+        let args = ReadMemoryArguments {
+            memory_reference: memory_reference.to_string(),
+            offset,
+            count,
+        };
+        let response = self
+            .send_request("readMemory", Some(serde_json::to_value(args)?))
+            .await?;
+
+        if !response.success {
+            return Err(Error::Dap(format!(
+                "ReadMemory failed: {:?}",
+                response.message
+            )));
+        }
+
+        response
+            .body
+            .ok_or_else(|| Error::Dap("No memory in response".to_string()))
+            .and_then(|v| {
+                serde_json::from_value(v)
+                    .map_err(|e| Error::Dap(format!("Failed to parse memory: {}", e)))
+            })
+    }
+
+    /// Disassemble `instruction_count` instructions starting at `memory_reference`
+    ///
+    /// Fails with [`Error::InvalidRequest`] when the adapter does not support
+    /// disassemble.
+    pub async fn disassemble(
+        &self,
+        memory_reference: &str,
+        instruction_count: i64,
+    ) -> Result<Vec<DisassembledInstruction>> {
+        let supported = self
+            .capabilities()
+            .map(|caps| caps.supports_disassemble_request.unwrap_or(false));
+        if supported == Some(false) {
+            return Err(Error::InvalidRequest(
+                "The debug adapter does not support disassembly \
+                 (supportsDisassembleRequest)"
+                    .to_string(),
+            ));
+        }
+
+        let args = DisassembleArguments {
+            memory_reference: memory_reference.to_string(),
+            offset: None,
+            instruction_offset: None,
+            instruction_count,
+            resolve_symbols: Some(true),
+        };
+        let response = self
+            .send_request("disassemble", Some(serde_json::to_value(args)?))
+            .await?;
+
+        if !response.success {
+            return Err(Error::Dap(format!(
+                "Disassemble failed: {:?}",
+                response.message
+            )));
+        }
+
+        #[derive(serde::Deserialize)]
+        struct DisassembleResponse {
+            instructions: Vec<DisassembledInstruction>,
+        }
+
+        let body: DisassembleResponse = response
+            .body
+            .ok_or_else(|| Error::Dap("No instructions in response".to_string()))
+            .and_then(|v| {
+                serde_json::from_value(v)
+                    .map_err(|e| Error::Dap(format!("Failed to parse instructions: {}", e)))
+            })?;
+
+        Ok(body.instructions)
+    }
+
+    /// Fetch the children of a structured value (`variablesReference > 0`)
+    pub async fn variables(&self, variables_reference: i32) -> Result<Vec<Variable>> {
+        self.variables_page(variables_reference, None, None).await
     }
 
-    /// Initialize and launch with combined timeout (2s + 5s = 7s total)
-    /// This wraps the entire sequence with aggressive timeouts
+    /// Fetch `count` children of a structured value starting at `start`, so
+    /// a list with 100000 items can be browsed without transferring it all
+    pub async fn variables_page(
+        &self,
+        variables_reference: i32,
+        start: Option<i32>,
+        count: Option<i32>,
+    ) -> Result<Vec<Variable>> {
+        let args = VariablesArguments {
+            variables_reference,
+            start,
+            count,
+        };
+
+        let response = self
+            .send_request("variables", Some(serde_json::to_value(args)?))
+            .await?;
+
+        if !response.success {
+            return Err(Error::Dap(format!(
+                "Variables failed: {:?}",
+                response.message
+            )));
+        }
+
+        #[derive(serde::Deserialize)]
+        struct VariablesResponse {
+            variables: Vec<Variable>,
+        }
+
+        let body: VariablesResponse = response
+            .body
+            .ok_or_else(|| Error::Dap("No variables in response".to_string()))
+            .and_then(|v| {
+                serde_json::from_value(v)
+                    .map_err(|e| Error::Dap(format!("Failed to parse variables: {}", e)))
+            })?;
+
+        let mut variables = body.variables;
+        // Adapters without paging support send every child
+        if let Some(count) = count.filter(|&count| count >= 0) {
+            variables.truncate(count as usize);
+        }
+        Ok(variables)
+    }
+
+    /// Variable scopes (locals, globals, registers...) of a stack frame
+    pub async fn scopes(&self, frame_id: i32) -> Result<Vec<Scope>> {
+        let args = ScopesArguments { frame_id };
+
+        let response = self
+            .send_request("scopes", Some(serde_json::to_value(args)?))
+            .await?;
+
+        if !response.success {
+            return Err(Error::Dap(format!("Scopes failed: {:?}", response.message)));
+        }
+
+        #[derive(serde::Deserialize)]
+        struct ScopesResponse {
+            scopes: Vec<Scope>,
+        }
+
+        let body: ScopesResponse = response
+            .body
+            .ok_or_else(|| Error::Dap("No scopes in response".to_string()))
+            .and_then(|v| {
+                serde_json::from_value(v)
+                    .map_err(|e| Error::Dap(format!("Failed to parse scopes: {}", e)))
+            })?;
+
+        Ok(body.scopes)
+    }
+
+    /// Disconnect from the adapter, then kill any runInTerminal debuggee it
+    /// left running
+    pub async fn disconnect(&self) -> Result<()> {
+        let response = self.send_request("disconnect", None).await;
+        self.kill_terminal_processes();
+
+        let response = response?;
+        if !response.success {
+            warn!("Disconnect failed: {:?}", response.message);
+        }
+
+        Ok(())
+    }
+
+    /// Initialize and launch, bounded by [`DapClient::initialize_timeout`]
+    /// (7s unless configured)
+    ///
+    /// Every phase of the handshake counts against the same deadline; a
+    /// timeout error names the phase that was still running.
     pub async fn initialize_and_launch_with_timeout(
         &self,
         adapter_id: &str,
@@ -1419,6 +2669,7 @@ impl DapClient {
             launch_args,
             adapter_type,
             HashMap::new(),
+            None,
         )
         .await
     }
@@ -1429,63 +2680,195 @@ impl DapClient {
         launch_args: Value,
         adapter_type: Option<&str>,
         pending_breakpoints: HashMap<String, Vec<SourceBreakpoint>>,
+        entry_line: Option<usize>,
     ) -> Result<()> {
-        let timeout = std::time::Duration::from_secs(7);
-        info!("⏱️  initialize_and_launch_with_timeout: Starting with 7s timeout");
+        let timeout = self.initialize_timeout();
+        info!(
+            "⏱️  initialize_and_launch_with_timeout: Starting with {:?} timeout",
+            timeout
+        );
         if let Some(atype) = adapter_type {
             info!("   Adapter type: {}", atype);
         }
 
-        tokio::time::timeout(
-            timeout,
-            self.initialize_and_launch_with_pending(
-                adapter_id,
-                launch_args,
-                adapter_type,
-                pending_breakpoints,
-            ),
+        self.launch_sequence(
+            adapter_id,
+            launch_args,
+            adapter_type,
+            pending_breakpoints,
+            entry_line,
+            Some(LaunchDeadline::new(timeout)),
         )
         .await
-        .map_err(|_| {
-            Error::Dap(format!(
-                "Initialize and launch timed out after {:?}",
-                timeout
-            ))
-        })?
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::super::transport_trait::DapTransportTrait;
-    use super::*;
-    use mockall::mock;
-    use serde_json::json;
-
-    mock! {
-        pub TestTransport {}
+/// Breakpoint the stopOnEntry workaround sets on the program's first
+/// executable line (Ruby and Node.js)
+///
+/// It is not one of the user's breakpoints: the stop at it is reported with
+/// reason "entry", and the session removes it before the program resumes so
+/// a loop through the first line does not stop there again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryBreakpoint {
+    /// Program path as sent to the adapter
+    pub source_path: String,
+    pub line: i32,
+    /// ID the adapter gave the breakpoint, if any
+    pub id: Option<i32>,
+    /// The program stopped at it
+    pub hit: bool,
+    /// A stop was recorded before (see [`EntryBreakpoint::record_stop`])
+    stopped_before: bool,
+}
 
-        #[async_trait::async_trait]
-        impl DapTransportTrait for TestTransport {
-            async fn read_message(&mut self) -> Result<Message>;
-            async fn write_message(&mut self, msg: &Message) -> Result<()>;
+impl EntryBreakpoint {
+    pub fn new(source_path: &str, line: i32, id: Option<i32>) -> Self {
+        Self {
+            source_path: source_path.to_string(),
+            line,
+            id,
+            hit: false,
+            stopped_before: false,
         }
     }
 
-    // Helper to create a mock that responds once then errors
-    fn create_mock_with_response(response: Response) -> MockTestTransport {
-        let mut mock = MockTestTransport::new();
+    /// Record a stop of the program; true when it is the stop at this
+    /// breakpoint
+    ///
+    /// The stop is matched by `hitBreakpointIds`. rdbg sends none, so then
+    /// the first breakpoint stop counts: nothing runs before the first
+    /// executable line, and a user breakpoint on that line replaces this one.
+    pub fn record_stop(&mut self, reason: &str, hit_breakpoint_ids: &[i32]) -> bool {
+        let first = !std::mem::replace(&mut self.stopped_before, true);
+        if self.hit || reason != "breakpoint" {
+            return false;
+        }
+        self.hit = match self.id {
+            Some(id) if !hit_breakpoint_ids.is_empty() => hit_breakpoint_ids.contains(&id),
+            _ => first,
+        };
+        self.hit
+    }
+}
+
+/// Phase of the initialize + launch handshake, named when it times out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LaunchPhase {
+    /// The initialize request
+    Initialize,
+    /// Between sending launch and the 'initialized' event
+    WaitingForInitialized,
+    /// The initial breakpoints and configurationDone
+    ConfigurationDone,
+    /// The launch (or attach) response
+    Launch,
+}
+
+impl std::fmt::Display for LaunchPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LaunchPhase::Initialize => "during the initialize request",
+            LaunchPhase::WaitingForInitialized => "waiting for the 'initialized' event",
+            LaunchPhase::ConfigurationDone => "during configurationDone",
+            LaunchPhase::Launch => "waiting for the launch response",
+        })
+    }
+}
+
+/// Limit of the whole initialize + launch handshake
+#[derive(Debug, Clone, Copy)]
+struct LaunchDeadline {
+    at: tokio::time::Instant,
+    limit: Duration,
+}
+
+impl LaunchDeadline {
+    fn new(limit: Duration) -> Self {
+        Self {
+            at: tokio::time::Instant::now() + limit,
+            limit,
+        }
+    }
+
+    fn expired(&self, phase: LaunchPhase) -> Error {
+        error!("❌ Initialize and launch timed out {}", phase);
+        Error::Dap(format!(
+            "Initialize and launch timed out after {:?} {}",
+            self.limit, phase
+        ))
+    }
+
+    /// Run `phase` until the deadline, if there is one
+    async fn bound<T>(
+        deadline: Option<Self>,
+        phase: LaunchPhase,
+        future: impl std::future::Future<Output = Result<T>>,
+    ) -> Result<T> {
+        match deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline.at, future)
+                .await
+                .map_err(|_| deadline.expired(phase))?,
+            None => future.await,
+        }
+    }
+}
+
+/// The launch (or attach) response, or the failure the adapter reported
+fn launch_result(command: &str, response: Result<Response>) -> Result<Response> {
+    let response = response.map_err(|e| Error::Dap(format!("No {} response: {}", command, e)))?;
+    if !response.success {
+        // The detailed error, when the adapter sent one, is in body.error.format
+        let reason = response
+            .body
+            .as_ref()
+            .and_then(|body| body.pointer("/error/format"))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .or_else(|| response.message.clone())
+            .unwrap_or_else(|| "no reason given".to_string());
+        error!("❌ {} failed: {}", command, reason);
+        return Err(Error::Dap(format!("{} failed: {}", command, reason)));
+    }
+    Ok(response)
+}
+
+/// DAP command for a launch configuration: "attach" for attach configurations
+/// (`"request": "attach"`), "launch" otherwise
+fn launch_command(args: &Value) -> &'static str {
+    match args.get("request").and_then(Value::as_str) {
+        Some("attach") => "attach",
+        _ => "launch",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::ScriptedAdapter;
+    use super::super::transport_trait::{DapReader, DapTransportTrait, DapWriter};
+    use super::*;
+    use crate::dap::testing::MockTransport;
+    use serde_json::json;
+
+    // Helper to create a mock that responds once then errors
+    fn create_mock_with_response(response: Response) -> MockTransport {
+        let mut mock = MockTransport::new();
 
         // Expect write
-        mock.expect_write_message().times(1).returning(|_| Ok(()));
+        mock.writer
+            .expect_write_message()
+            .times(1)
+            .returning(|_| Ok(()));
 
         // Return response once
-        mock.expect_read_message()
+        mock.reader
+            .expect_read_message()
             .times(1)
             .return_once(move || Ok(Message::Response(response)));
 
         // Then error to stop message loop
-        mock.expect_read_message()
+        mock.reader
+            .expect_read_message()
             .returning(|| Err(Error::Dap("Connection closed".to_string())));
 
         mock
@@ -1493,32 +2876,38 @@ mod tests {
 
     #[tokio::test]
     async fn test_dap_client_initialize() {
-        let mut mock_transport = MockTestTransport::new();
+        let mut mock_transport = MockTransport::new();
 
         // Expect write of initialize request
         mock_transport
+            .writer
             .expect_write_message()
             .times(1)
             .returning(|_| Ok(()));
 
         // Return initialize response, then error to stop message loop
-        mock_transport.expect_read_message().times(1).returning(|| {
-            Ok(Message::Response(Response {
-                seq: 1,
-                request_seq: 1,
-                command: "initialize".to_string(),
-                success: true,
-                message: None,
-                body: Some(json!({
-                    "supportsConfigurationDoneRequest": true,
-                    "supportsFunctionBreakpoints": false,
-                    "supportsConditionalBreakpoints": true,
-                })),
-            }))
-        });
+        mock_transport
+            .reader
+            .expect_read_message()
+            .times(1)
+            .returning(|| {
+                Ok(Message::Response(Response {
+                    seq: 1,
+                    request_seq: 1,
+                    command: "initialize".to_string(),
+                    success: true,
+                    message: None,
+                    body: Some(json!({
+                        "supportsConfigurationDoneRequest": true,
+                        "supportsFunctionBreakpoints": false,
+                        "supportsConditionalBreakpoints": true,
+                    })),
+                }))
+            });
 
         // Second read returns error to stop background task
         mock_transport
+            .reader
             .expect_read_message()
             .returning(|| Err(Error::Dap("Connection closed".to_string())));
 
@@ -1526,9 +2915,11 @@ mod tests {
             .await
             .unwrap();
 
+        assert!(client.capabilities().is_none());
         let caps = client.initialize("test-adapter").await.unwrap();
 
         assert!(caps.supports_configuration_done_request.unwrap_or(false));
+        assert!(client.capabilities().is_some());
         assert!(!caps.supports_function_breakpoints.unwrap_or(true));
         assert!(caps.supports_conditional_breakpoints.unwrap_or(false));
     }
@@ -1604,6 +2995,7 @@ mod tests {
             name: Some("test.py".to_string()),
             path: Some("/path/to/test.py".to_string()),
             source_reference: None,
+            origin: None,
         };
 
         let breakpoints = vec![SourceBreakpoint {
@@ -1611,6 +3003,7 @@ mod tests {
             column: None,
             condition: None,
             hit_condition: None,
+            log_message: None,
         }];
 
         let result = client.set_breakpoints(source, breakpoints).await.unwrap();
@@ -1620,6 +3013,315 @@ mod tests {
         assert!(result[0].verified);
     }
 
+    #[tokio::test]
+    async fn test_dap_client_set_function_breakpoints() {
+        let mut mock_transport = MockTransport::new();
+        mock_transport
+            .writer
+            .expect_write_message()
+            .times(1)
+            .withf(|msg| match msg {
+                Message::Request(req) => {
+                    req.command == "setFunctionBreakpoints"
+                        && req.arguments.as_ref().unwrap()["breakpoints"][0]["name"] == "fizzbuzz"
+                }
+                _ => false,
+            })
+            .returning(|_| Ok(()));
+        mock_transport
+            .reader
+            .expect_read_message()
+            .times(1)
+            .returning(|| {
+                Ok(Message::Response(Response {
+                    seq: 1,
+                    request_seq: 1,
+                    command: "setFunctionBreakpoints".to_string(),
+                    success: true,
+                    message: None,
+                    body: Some(json!({"breakpoints": [{"id": 4, "verified": true}]})),
+                }))
+            });
+        mock_transport
+            .reader
+            .expect_read_message()
+            .returning(|| Err(Error::Dap("Connection closed".to_string())));
+
+        let client = DapClient::new_with_transport(Box::new(mock_transport), None)
+            .await
+            .unwrap();
+
+        let result = client
+            .set_function_breakpoints(vec![FunctionBreakpoint {
+                name: "fizzbuzz".to_string(),
+                condition: None,
+                hit_condition: None,
+            }])
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].id, Some(4));
+        assert!(result[0].verified);
+    }
+
+    #[tokio::test]
+    async fn test_set_function_breakpoints_requires_capability() {
+        let adapter = ScriptedAdapter::new();
+        let trace = adapter.trace();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        client.initialize("test-adapter").await.unwrap();
+
+        let result = client
+            .set_function_breakpoints(vec![FunctionBreakpoint {
+                name: "main".to_string(),
+                condition: None,
+                hit_condition: None,
+            }])
+            .await;
+
+        match result {
+            Err(Error::InvalidRequest(msg)) => assert!(msg.contains("supportsFunctionBreakpoints")),
+            other => panic!("Expected InvalidRequest, got {:?}", other),
+        }
+        assert_eq!(*trace.lock().unwrap(), vec!["initialize"]);
+    }
+
+    #[tokio::test]
+    async fn test_data_breakpoints_require_capability() {
+        let adapter = ScriptedAdapter::new().with_capability("supportsDataBreakpoints");
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        client.initialize("test-adapter").await.unwrap();
+
+        let info = client
+            .data_breakpoint_info(Some(100), "n", None)
+            .await
+            .unwrap();
+        assert_eq!(info.data_id.as_deref(), Some("100/n"));
+        let result = client
+            .set_data_breakpoints(vec![DataBreakpoint {
+                data_id: "100/n".to_string(),
+                access_type: Some("write".to_string()),
+                condition: None,
+                hit_condition: None,
+            }])
+            .await
+            .unwrap();
+        assert_eq!(result[0].id, Some(1000));
+        assert!(result[0].verified);
+
+        let adapter = ScriptedAdapter::new();
+        let trace = adapter.trace();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        client.initialize("test-adapter").await.unwrap();
+        match client.data_breakpoint_info(Some(100), "n", None).await {
+            Err(Error::InvalidRequest(msg)) => assert!(msg.contains("supportsDataBreakpoints")),
+            other => panic!("Expected InvalidRequest, got {:?}", other),
+        }
+        assert!(client.set_data_breakpoints(vec![]).await.is_err());
+        assert_eq!(*trace.lock().unwrap(), vec!["initialize"]);
+    }
+
+    #[tokio::test]
+    async fn test_read_memory_and_disassemble() {
+        let mut mock_transport = MockTransport::new();
+        mock_transport
+            .writer
+            .expect_write_message()
+            .times(1)
+            .withf(|msg| match msg {
+                Message::Request(req) => {
+                    let args = req.arguments.as_ref().unwrap();
+                    req.command == "readMemory"
+                        && args["memoryReference"] == "0x1000"
+                        && args["offset"] == 4
+                        && args["count"] == 3
+                }
+                _ => false,
+            })
+            .returning(|_| Ok(()));
+        mock_transport
+            .reader
+            .expect_read_message()
+            .times(1)
+            .returning(|| {
+                Ok(Message::Response(Response {
+                    seq: 1,
+                    request_seq: 1,
+                    command: "readMemory".to_string(),
+                    success: true,
+                    message: None,
+                    body: Some(json!({"address": "0x1004", "data": "AQID"})),
+                }))
+            });
+        mock_transport
+            .reader
+            .expect_read_message()
+            .returning(|| Err(Error::Dap("Connection closed".to_string())));
+
+        let client = DapClient::new_with_transport(Box::new(mock_transport), None)
+            .await
+            .unwrap();
+
+        let memory = client.read_memory("0x1000", Some(4), 3).await.unwrap();
+        assert_eq!(memory.address, "0x1004");
+        assert_eq!(memory.data.as_deref(), Some("AQID"));
+
+        let client = DapClient::new_with_transport(
+            Box::new(create_mock_with_response(Response {
+                seq: 1,
+                request_seq: 1,
+                command: "disassemble".to_string(),
+                success: true,
+                message: None,
+                body: Some(json!({"instructions": [
+                    {"address": "0x2000", "instruction": "push rbp", "instructionBytes": "55"},
+                    {"address": "0x2001", "instruction": "mov rbp, rsp"}
+                ]})),
+            })),
+            None,
+        )
+        .await
+        .unwrap();
+        let instructions = client.disassemble("0x2000", 2).await.unwrap();
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(instructions[0].instruction_bytes.as_deref(), Some("55"));
+        assert_eq!(instructions[1].instruction, "mov rbp, rsp");
+    }
+
+    #[tokio::test]
+    async fn test_dap_client_completions() {
+        let mut mock_transport = MockTransport::new();
+        mock_transport
+            .writer
+            .expect_write_message()
+            .times(1)
+            .withf(|msg| match msg {
+                Message::Request(req) => {
+                    let args = req.arguments.as_ref().unwrap();
+                    req.command == "completions"
+                        && args["text"] == "fi"
+                        && args["column"] == 3
+                        && args["frameId"] == 2
+                }
+                _ => false,
+            })
+            .returning(|_| Ok(()));
+        mock_transport
+            .reader
+            .expect_read_message()
+            .times(1)
+            .returning(|| {
+                Ok(Message::Response(Response {
+                    seq: 1,
+                    request_seq: 1,
+                    command: "completions".to_string(),
+                    success: true,
+                    message: None,
+                    body: Some(json!({"targets": [
+                        {"label": "fizzbuzz", "type": "function"},
+                        {"label": "filter", "text": "filter(", "type": "class"}
+                    ]})),
+                }))
+            });
+        mock_transport
+            .reader
+            .expect_read_message()
+            .returning(|| Err(Error::Dap("Connection closed".to_string())));
+
+        let client = DapClient::new_with_transport(Box::new(mock_transport), None)
+            .await
+            .unwrap();
+
+        let targets = client.completions("fi", 3, Some(2)).await.unwrap();
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[0].label, "fizzbuzz");
+        assert_eq!(targets[1].text.as_deref(), Some("filter("));
+    }
+
+    #[tokio::test]
+    async fn test_dap_client_loaded_sources_and_modules() {
+        let client = DapClient::new_with_transport(
+            Box::new(create_mock_with_response(Response {
+                seq: 1,
+                request_seq: 1,
+                command: "loadedSources".to_string(),
+                success: true,
+                message: None,
+                body: Some(json!({"sources": [
+                    {"name": "app.py", "path": "/srv/app/app.py"},
+                    {"name": "<string>", "sourceReference": 7}
+                ]})),
+            })),
+            None,
+        )
+        .await
+        .unwrap();
+        let sources = client.loaded_sources().await.unwrap();
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].path.as_deref(), Some("/srv/app/app.py"));
+        assert_eq!(sources[1].source_reference, Some(7));
+
+        let client = DapClient::new_with_transport(
+            Box::new(create_mock_with_response(Response {
+                seq: 1,
+                request_seq: 1,
+                command: "modules".to_string(),
+                success: true,
+                message: None,
+                body: Some(json!({"modules": [
+                    {"id": 1, "name": "app", "path": "/srv/app/target/debug/app"}
+                ], "totalModules": 1})),
+            })),
+            None,
+        )
+        .await
+        .unwrap();
+        let modules = client.modules().await.unwrap();
+        assert_eq!(modules[0].name, "app");
+        assert_eq!(modules[0].id, json!(1));
+    }
+
+    #[tokio::test]
+    async fn test_memory_requests_require_capabilities() {
+        let adapter = ScriptedAdapter::new();
+        let trace = adapter.trace();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        client.initialize("test-adapter").await.unwrap();
+
+        match client.read_memory("0x1000", None, 16).await {
+            Err(Error::InvalidRequest(msg)) => assert!(msg.contains("supportsReadMemoryRequest")),
+            other => panic!("Expected InvalidRequest, got {:?}", other),
+        }
+        match client.disassemble("0x1000", 4).await {
+            Err(Error::InvalidRequest(msg)) => assert!(msg.contains("supportsDisassembleRequest")),
+            other => panic!("Expected InvalidRequest, got {:?}", other),
+        }
+        match client.completions("fi", 3, None).await {
+            Err(Error::InvalidRequest(msg)) => assert!(msg.contains("supportsCompletionsRequest")),
+            other => panic!("Expected InvalidRequest, got {:?}", other),
+        }
+        match client.loaded_sources().await {
+            Err(Error::InvalidRequest(msg)) => {
+                assert!(msg.contains("supportsLoadedSourcesRequest"))
+            }
+            other => panic!("Expected InvalidRequest, got {:?}", other),
+        }
+        match client.modules().await {
+            Err(Error::InvalidRequest(msg)) => assert!(msg.contains("supportsModulesRequest")),
+            other => panic!("Expected InvalidRequest, got {:?}", other),
+        }
+        assert_eq!(*trace.lock().unwrap(), vec!["initialize"]);
+    }
+
     #[tokio::test]
     async fn test_dap_client_continue_execution() {
         let mock_transport = create_mock_with_response(Response {
@@ -1638,6 +3340,59 @@ mod tests {
         client.continue_execution(1).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_resume_responses_report_continued_event() {
+        let client = DapClient::new_with_transport(
+            Box::new(crate::dap::testing::ScriptedAdapter::new()),
+            None,
+        )
+        .await
+        .unwrap();
+        let continued = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = continued.clone();
+        client
+            .on_event("continued", move |event| {
+                seen.lock().unwrap().push(event.body.unwrap());
+            })
+            .await;
+
+        client.continue_execution(1).await.unwrap();
+        // Already dispatched when the request returns
+        assert_eq!(continued.lock().unwrap().len(), 1);
+        client.next(1, None).await.unwrap();
+        client.send_request("threads", None).await.unwrap();
+
+        let continued = continued.lock().unwrap();
+        assert_eq!(continued.len(), 2);
+        assert_eq!(continued[0]["allThreadsContinued"], true);
+        assert_eq!(continued[1]["allThreadsContinued"], false);
+    }
+
+    #[tokio::test]
+    async fn test_replace_event_handler_keeps_one_per_owner() {
+        let client = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+            .await
+            .unwrap();
+        let invocations = Arc::new(std::sync::Mutex::new(Vec::new()));
+        for (owner, label) in [("a", "a1"), ("a", "a2"), ("b", "b1")] {
+            let invocations = invocations.clone();
+            client
+                .replace_event_handler("continued", owner, move |_| {
+                    invocations.lock().unwrap().push(label);
+                })
+                .await;
+        }
+        assert_eq!(client.event_handler_count("continued").await, 2);
+
+        client.continue_execution(1).await.unwrap();
+        assert_eq!(*invocations.lock().unwrap(), vec!["a2", "b1"]);
+
+        client.remove_owned_event_handlers("a").await;
+        assert_eq!(client.event_handler_count("continued").await, 1);
+        client.remove_owned_event_handlers("b").await;
+        assert_eq!(client.event_handler_count("continued").await, 0);
+    }
+
     #[tokio::test]
     async fn test_dap_client_stack_trace() {
         let mock_transport = create_mock_with_response(Response {
@@ -1669,6 +3424,96 @@ mod tests {
         assert_eq!(frames[0].line, 42);
     }
 
+    #[tokio::test]
+    async fn test_dap_client_stack_trace_page() {
+        let mut mock_transport = MockTransport::new();
+        mock_transport
+            .writer
+            .expect_write_message()
+            .times(1)
+            .withf(|msg| match msg {
+                Message::Request(req) => {
+                    let args = req.arguments.as_ref().unwrap();
+                    req.command == "stackTrace" && args["startFrame"] == 10 && args["levels"] == 2
+                }
+                _ => false,
+            })
+            .returning(|_| Ok(()));
+        mock_transport
+            .reader
+            .expect_read_message()
+            .times(1)
+            .returning(|| {
+                // An adapter ignoring 'levels' sends the rest of the stack
+                let frames: Vec<Value> = (10..15)
+                    .map(|id| json!({"id": id, "name": "recurse", "line": 3, "column": 0}))
+                    .collect();
+                Ok(Message::Response(Response {
+                    seq: 1,
+                    request_seq: 1,
+                    command: "stackTrace".to_string(),
+                    success: true,
+                    message: None,
+                    body: Some(json!({ "stackFrames": frames })),
+                }))
+            });
+        mock_transport
+            .reader
+            .expect_read_message()
+            .returning(|| Err(Error::Dap("Connection closed".to_string())));
+
+        let client = DapClient::new_with_transport(Box::new(mock_transport), None)
+            .await
+            .unwrap();
+
+        let page = client.stack_trace_page(1, Some(10), Some(2)).await.unwrap();
+
+        assert_eq!(page.stack_frames.len(), 2);
+        assert_eq!(page.stack_frames[0].id, 10);
+        assert_eq!(page.total_frames, Some(15));
+    }
+
+    #[tokio::test]
+    async fn test_stack_trace_page_without_delayed_loading() {
+        let mut mock_transport = MockTransport::new();
+        mock_transport
+            .writer
+            .expect_write_message()
+            .returning(|_| Ok(()));
+        mock_transport
+            .reader
+            .expect_read_message()
+            .times(1)
+            .returning(|| {
+                // startFrame ignored: the whole stack, from the top
+                let frames: Vec<Value> = (0..6)
+                    .map(|id| json!({"id": id, "name": "recurse", "line": 3, "column": 0}))
+                    .collect();
+                Ok(Message::Response(Response {
+                    seq: 1,
+                    request_seq: 1,
+                    command: "stackTrace".to_string(),
+                    success: true,
+                    message: None,
+                    body: Some(json!({ "stackFrames": frames, "totalFrames": 6 })),
+                }))
+            });
+        mock_transport
+            .reader
+            .expect_read_message()
+            .returning(|| Err(Error::Dap("Connection closed".to_string())));
+
+        let client = DapClient::new_with_transport(Box::new(mock_transport), None)
+            .await
+            .unwrap();
+
+        let page = client.stack_trace_page(1, Some(2), Some(3)).await.unwrap();
+
+        let ids: Vec<i32> = page.stack_frames.iter().map(|frame| frame.id).collect();
+        assert_eq!(ids, vec![2, 3, 4]);
+        assert_eq!(page.total_frames, Some(6));
+    }
+
     #[tokio::test]
     async fn test_dap_client_evaluate() {
         let mock_transport = create_mock_with_response(Response {
@@ -1688,34 +3533,180 @@ mod tests {
 
         let result = client.evaluate("x + y", Some(1)).await.unwrap();
 
-        assert_eq!(result, "42");
+        assert_eq!(result.result, "42");
+        assert_eq!(result.variables_reference, 0);
+        assert!(result.type_.is_none());
     }
 
     #[tokio::test]
-    async fn test_dap_client_configuration_done() {
+    async fn test_dap_client_breakpoint_locations() {
         let mock_transport = create_mock_with_response(Response {
             seq: 1,
             request_seq: 1,
-            command: "configurationDone".to_string(),
+            command: "breakpointLocations".to_string(),
             success: true,
             message: None,
-            body: None,
+            body: Some(json!({
+                "breakpoints": [
+                    {"line": 3, "column": 1},
+                    {"line": 3, "column": 24, "endColumn": 30}
+                ]
+            })),
         });
 
         let client = DapClient::new_with_transport(Box::new(mock_transport), None)
             .await
             .unwrap();
 
-        client.configuration_done().await.unwrap();
+        let source = Source {
+            name: None,
+            path: Some("/app/bundle.min.js".to_string()),
+            source_reference: None,
+            origin: None,
+        };
+        let locations = client.breakpoint_locations(source, 3, None).await.unwrap();
+
+        assert_eq!(locations.len(), 2);
+        assert_eq!(locations[1].column, Some(24));
+        assert_eq!(locations[1].end_column, Some(30));
     }
 
     #[tokio::test]
-    async fn test_dap_client_disconnect() {
-        let mock_transport = create_mock_with_response(Response {
-            seq: 1,
-            request_seq: 1,
-            command: "disconnect".to_string(),
-            success: true,
+    async fn test_evaluate_sends_requested_context() {
+        let adapter = ScriptedAdapter::new();
+        let requests = adapter.requests();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+
+        client.evaluate("x", Some(1)).await.unwrap();
+        client
+            .evaluate_in_context("x = 5", Some(1), "repl")
+            .await
+            .unwrap();
+
+        let requests = requests.lock().unwrap();
+        let contexts: Vec<_> = requests
+            .iter()
+            .filter(|r| r.command == "evaluate")
+            .map(|r| r.arguments.as_ref().unwrap()["context"].clone())
+            .collect();
+        assert_eq!(contexts, vec![json!("watch"), json!("repl")]);
+    }
+
+    #[tokio::test]
+    async fn test_dap_client_evaluate_structured_result() {
+        let mock_transport = create_mock_with_response(Response {
+            seq: 1,
+            request_seq: 1,
+            command: "evaluate".to_string(),
+            success: true,
+            message: None,
+            body: Some(json!({
+                "result": "{'a': 1, 'b': 2}",
+                "type": "dict",
+                "variablesReference": 7,
+                "namedVariables": 2,
+                "presentationHint": {"kind": "data"}
+            })),
+        });
+
+        let client = DapClient::new_with_transport(Box::new(mock_transport), None)
+            .await
+            .unwrap();
+
+        let result = client.evaluate("d", Some(1)).await.unwrap();
+
+        assert_eq!(result.result, "{'a': 1, 'b': 2}");
+        assert_eq!(result.type_.as_deref(), Some("dict"));
+        assert_eq!(result.variables_reference, 7);
+        assert_eq!(result.named_variables, Some(2));
+        assert_eq!(result.indexed_variables, None);
+        assert_eq!(result.presentation_hint, Some(json!({"kind": "data"})));
+    }
+
+    #[tokio::test]
+    async fn test_dap_client_variables() {
+        let mock_transport = create_mock_with_response(Response {
+            seq: 1,
+            request_seq: 1,
+            command: "variables".to_string(),
+            success: true,
+            message: None,
+            body: Some(json!({
+                "variables": [
+                    {"name": "'a'", "value": "1", "type": "int", "variablesReference": 0},
+                    {"name": "'b'", "value": "[1, 2]", "type": "list", "variablesReference": 8, "indexedVariables": 2}
+                ]
+            })),
+        });
+
+        let client = DapClient::new_with_transport(Box::new(mock_transport), None)
+            .await
+            .unwrap();
+
+        let variables = client.variables(7).await.unwrap();
+
+        assert_eq!(variables.len(), 2);
+        assert_eq!(variables[0].name, "'a'");
+        assert_eq!(variables[0].variables_reference, 0);
+        assert_eq!(variables[1].variables_reference, 8);
+        assert_eq!(variables[1].indexed_variables, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_dap_client_scopes() {
+        let mock_transport = create_mock_with_response(Response {
+            seq: 1,
+            request_seq: 1,
+            command: "scopes".to_string(),
+            success: true,
+            message: None,
+            body: Some(json!({
+                "scopes": [
+                    {"name": "Locals", "variablesReference": 3, "expensive": false},
+                    {"name": "Globals", "variablesReference": 4, "expensive": true}
+                ]
+            })),
+        });
+
+        let client = DapClient::new_with_transport(Box::new(mock_transport), None)
+            .await
+            .unwrap();
+
+        let scopes = client.scopes(1).await.unwrap();
+
+        assert_eq!(scopes.len(), 2);
+        assert_eq!(scopes[0].name, "Locals");
+        assert_eq!(scopes[0].variables_reference, 3);
+        assert!(scopes[1].expensive);
+    }
+
+    #[tokio::test]
+    async fn test_dap_client_configuration_done() {
+        let mock_transport = create_mock_with_response(Response {
+            seq: 1,
+            request_seq: 1,
+            command: "configurationDone".to_string(),
+            success: true,
+            message: None,
+            body: None,
+        });
+
+        let client = DapClient::new_with_transport(Box::new(mock_transport), None)
+            .await
+            .unwrap();
+
+        client.configuration_done().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_dap_client_disconnect() {
+        let mock_transport = create_mock_with_response(Response {
+            seq: 1,
+            request_seq: 1,
+            command: "disconnect".to_string(),
+            success: true,
             message: None,
             body: None,
         });
@@ -1726,4 +3717,732 @@ mod tests {
 
         client.disconnect().await.unwrap();
     }
+
+    /// Transport whose adapter accepts requests but never answers them
+    struct SilentTransport;
+
+    #[async_trait::async_trait]
+    impl DapTransportTrait for SilentTransport {
+        async fn read_message(&mut self) -> Result<Message> {
+            std::future::pending().await
+        }
+
+        async fn write_message(&mut self, _msg: &Message) -> Result<()> {
+            Ok(())
+        }
+
+        fn split(self: Box<Self>) -> (Box<dyn DapReader>, Box<dyn DapWriter>) {
+            (Box::new(SilentReader), Box::new(SilentWriter))
+        }
+    }
+
+    struct SilentReader;
+
+    #[async_trait::async_trait]
+    impl DapReader for SilentReader {
+        async fn read_message(&mut self) -> Result<Message> {
+            std::future::pending().await
+        }
+    }
+
+    struct SilentWriter;
+
+    #[async_trait::async_trait]
+    impl DapWriter for SilentWriter {
+        async fn write_message(&mut self, _msg: &Message) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Transport whose adapter never reads its input: writes never complete
+    struct StuckTransport;
+
+    #[async_trait::async_trait]
+    impl DapTransportTrait for StuckTransport {
+        async fn read_message(&mut self) -> Result<Message> {
+            std::future::pending().await
+        }
+
+        async fn write_message(&mut self, _msg: &Message) -> Result<()> {
+            std::future::pending().await
+        }
+
+        fn split(self: Box<Self>) -> (Box<dyn DapReader>, Box<dyn DapWriter>) {
+            (Box::new(SilentReader), Box::new(StuckWriter))
+        }
+    }
+
+    struct StuckWriter;
+
+    #[async_trait::async_trait]
+    impl DapWriter for StuckWriter {
+        async fn write_message(&mut self, _msg: &Message) -> Result<()> {
+            std::future::pending().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_full_write_queue_reports_stalled_adapter() {
+        let client = DapClient::new_with_transport(Box::new(StuckTransport), None)
+            .await
+            .unwrap();
+        client.set_write_queue_timeout(Duration::from_millis(50));
+
+        // The writer takes the first message and blocks on it; the rest queue up
+        for _ in 0..=WRITE_QUEUE_DEPTH {
+            client.send_request_nowait("threads", None).await.unwrap();
+        }
+        tokio::task::yield_now().await;
+        assert_eq!(client.write_queue_depth(), WRITE_QUEUE_DEPTH);
+
+        match client.send_request_nowait("pause", None).await {
+            Err(Error::AdapterStalled(msg)) => assert!(msg.starts_with("'pause'"), "{}", msg),
+            other => panic!("Expected AdapterStalled, got {:?}", other),
+        }
+        match client.send_request("evaluate", None).await {
+            Err(Error::AdapterStalled(msg)) => assert!(msg.contains("'evaluate'"), "{}", msg),
+            other => panic!("Expected AdapterStalled, got {:?}", other),
+        }
+        assert!(client.pending_requests.read().await.is_empty());
+        assert_eq!(client.metrics().consecutive_timeouts(), 2);
+    }
+
+    #[test]
+    fn test_request_timeouts_overrides_extend_default() {
+        let timeouts = RequestTimeouts::default();
+        assert_eq!(timeouts.for_command("stackTrace"), DEFAULT_REQUEST_TIMEOUT);
+        assert_eq!(timeouts.for_command("launch"), Duration::from_secs(30));
+
+        // A raised default applies to slow commands too
+        let timeouts = RequestTimeouts::new(Duration::from_secs(60));
+        assert_eq!(timeouts.for_command("launch"), Duration::from_secs(60));
+
+        let timeouts =
+            RequestTimeouts::default().with_override("evaluate", Duration::from_secs(20));
+        assert_eq!(timeouts.for_command("evaluate"), Duration::from_secs(20));
+        assert_eq!(timeouts.default_timeout(), DEFAULT_REQUEST_TIMEOUT);
+    }
+
+    #[test]
+    fn test_request_timeouts_override_shorter_than_default() {
+        let timeouts = RequestTimeouts::new(Duration::from_secs(10))
+            .with_override("evaluate", Duration::from_secs(2))
+            .with_override("launch", Duration::from_secs(5));
+        assert_eq!(timeouts.for_command("evaluate"), Duration::from_secs(2));
+        assert_eq!(timeouts.for_command("launch"), Duration::from_secs(5));
+        assert_eq!(timeouts.for_command("attach"), Duration::from_secs(30));
+        assert_eq!(timeouts.for_command("stackTrace"), Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn test_send_request_times_out_naming_command() {
+        let client = DapClient::new_with_transport(Box::new(SilentTransport), None)
+            .await
+            .unwrap();
+        client.set_request_timeouts(RequestTimeouts::new(Duration::from_millis(50)));
+
+        let result = client.stack_trace(1).await;
+
+        match result {
+            Err(Error::Dap(msg)) => assert_eq!(msg, "'stackTrace' timed out after 50ms"),
+            other => panic!("Expected timeout error, got {:?}", other),
+        }
+        assert!(client.pending_requests.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_bounded_by_request_timeouts() {
+        let client = DapClient::new_with_transport(Box::new(SilentTransport), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            client.request_timeouts().for_command("disconnect"),
+            DEFAULT_DISCONNECT_TIMEOUT
+        );
+
+        // A configured request timeout replaces the built-in one
+        client.set_request_timeouts(RequestTimeouts::new(Duration::from_millis(50)));
+        match client.disconnect().await {
+            Err(Error::Dap(msg)) => assert_eq!(msg, "'disconnect' timed out after 50ms"),
+            other => panic!("Expected timeout error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_request_async_times_out() {
+        let client = DapClient::new_with_transport(Box::new(SilentTransport), None)
+            .await
+            .unwrap();
+        client.set_request_timeouts(RequestTimeouts::new(Duration::from_millis(50)));
+
+        let (tx, rx) = oneshot::channel();
+        client
+            .send_request_async("evaluate", None, move |result| {
+                let _ = tx.send(result);
+            })
+            .await
+            .unwrap();
+
+        match rx.await.unwrap() {
+            Err(Error::Dap(msg)) => assert!(msg.contains("'evaluate' timed out")),
+            other => panic!("Expected timeout error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_initialize_and_launch_uses_configured_timeout() {
+        let client = DapClient::new_with_transport(Box::new(SilentTransport), None)
+            .await
+            .unwrap();
+        assert_eq!(client.initialize_timeout(), Duration::from_secs(7));
+        client.set_initialize_timeout(Duration::from_millis(100));
+
+        let started = std::time::Instant::now();
+        let result = client
+            .initialize_and_launch_with_timeout("debugpy", json!({}), None)
+            .await;
+
+        match result {
+            Err(Error::Dap(msg)) => assert_eq!(
+                msg,
+                "Initialize and launch timed out after 100ms during the initialize request"
+            ),
+            other => panic!("Expected timeout error, got {:?}", other),
+        }
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_entry_breakpoint_matches_its_stop() {
+        // Matched by hitBreakpointIds when the adapter sends them
+        let mut entry = EntryBreakpoint::new("/app/main.rb", 3, Some(7));
+        assert!(!entry.record_stop("breakpoint", &[8]));
+        assert!(!entry.record_stop("breakpoint", &[]));
+        assert!(entry.record_stop("breakpoint", &[7]));
+        assert!(!entry.record_stop("breakpoint", &[7]));
+
+        // Otherwise only the first stop, if it is a breakpoint stop
+        let mut entry = EntryBreakpoint::new("/app/main.rb", 3, Some(7));
+        assert!(entry.record_stop("breakpoint", &[]));
+        let mut entry = EntryBreakpoint::new("/app/main.rb", 3, None);
+        assert!(!entry.record_stop("pause", &[]));
+        assert!(!entry.record_stop("breakpoint", &[]));
+        assert!(!entry.hit);
+    }
+
+    #[tokio::test]
+    async fn test_launch_timeout_names_the_stalled_phase() {
+        let cases = [
+            (
+                ScriptedAdapter::new().ignoring("initialize"),
+                "during the initialize request",
+            ),
+            (
+                ScriptedAdapter::new().ignoring("launch"),
+                "waiting for the 'initialized' event",
+            ),
+            (
+                ScriptedAdapter::new().ignoring("setBreakpoints"),
+                "during configurationDone",
+            ),
+            (
+                ScriptedAdapter::new().ignoring("configurationDone"),
+                "during configurationDone",
+            ),
+            (
+                ScriptedAdapter::new().withholding_launch_response(),
+                "waiting for the launch response",
+            ),
+        ];
+        for (adapter, phase) in cases {
+            let client = DapClient::new_with_transport(Box::new(adapter), None)
+                .await
+                .unwrap();
+            client.set_initialize_timeout(Duration::from_millis(200));
+
+            let result = client
+                .initialize_and_launch_with_timeout_and_pending(
+                    "debugpy",
+                    json!({"program": "/app/main.py"}),
+                    Some("python"),
+                    pending("/app/main.py", &[3]),
+                    None,
+                )
+                .await;
+            match result {
+                Err(Error::Dap(msg)) => assert_eq!(
+                    msg,
+                    format!("Initialize and launch timed out after 200ms {}", phase)
+                ),
+                other => panic!("Expected timeout {}, got {:?}", phase, other),
+            }
+        }
+    }
+
+    fn pending(path: &str, lines: &[i32]) -> HashMap<String, Vec<SourceBreakpoint>> {
+        let breakpoints = lines
+            .iter()
+            .map(|&line| SourceBreakpoint {
+                line,
+                column: None,
+                condition: None,
+                hit_condition: None,
+                log_message: None,
+            })
+            .collect();
+        HashMap::from([(path.to_string(), breakpoints)])
+    }
+
+    async fn launch_with(adapter: ScriptedAdapter) -> (Result<()>, Vec<String>) {
+        let trace = adapter.trace();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let result = client
+            .initialize_and_launch_with_timeout(
+                "debugpy",
+                json!({"program": "/app/main.py"}),
+                Some("python"),
+            )
+            .await;
+        let trace = trace.lock().unwrap().clone();
+        (result, trace)
+    }
+
+    #[tokio::test]
+    async fn test_launch_waits_for_response_after_configuration_done() {
+        let (result, trace) = launch_with(ScriptedAdapter::new()).await;
+        result.unwrap();
+        assert_eq!(trace, vec!["initialize", "launch", "configurationDone"]);
+
+        let (result, _) = launch_with(ScriptedAdapter::new().failing_launch("No such file")).await;
+        match result {
+            Err(Error::Dap(message)) => assert_eq!(message, "launch failed: No such file"),
+            other => panic!("Expected launch failure, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_launch_response_before_initialized() {
+        let (result, trace) = launch_with(ScriptedAdapter::new().answering_launch_early()).await;
+        result.unwrap();
+        assert_eq!(trace, vec!["initialize", "launch", "configurationDone"]);
+
+        // Reported at once, not after the 'initialized' timeout
+        let started = std::time::Instant::now();
+        let (result, trace) = launch_with(
+            ScriptedAdapter::new()
+                .answering_launch_early()
+                .failing_launch("Program does not exist"),
+        )
+        .await;
+        match result {
+            Err(Error::Dap(message)) => {
+                assert_eq!(message, "launch failed: Program does not exist")
+            }
+            other => panic!("Expected launch failure, got {:?}", other),
+        }
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(trace, vec!["initialize", "launch"]);
+    }
+
+    #[tokio::test]
+    async fn test_pending_breakpoints_applied_before_configuration_done() {
+        let adapter = ScriptedAdapter::new().rejecting_line(99);
+        let trace = adapter.trace();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+
+        let results = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let hook_trace = trace.clone();
+        let hook_results = results.clone();
+        client
+            .on_breakpoints_applied(move |path, requested, applied| {
+                hook_trace.lock().unwrap().push("hook".to_string());
+                hook_results
+                    .lock()
+                    .unwrap()
+                    .push((path, requested.len(), applied));
+                Box::pin(async {})
+            })
+            .await;
+
+        client
+            .initialize_and_launch_with_pending(
+                "debugpy",
+                json!({"program": "/app/main.py", "stopOnEntry": false}),
+                Some("python"),
+                pending("/app/main.py", &[10, 99]),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *trace.lock().unwrap(),
+            vec![
+                "initialize",
+                "launch",
+                "setBreakpoints",
+                "hook",
+                "configurationDone"
+            ]
+        );
+
+        let results = results.lock().unwrap();
+        let (path, requested, applied) = &results[0];
+        assert_eq!(path, "/app/main.py");
+        assert_eq!(*requested, 2);
+        assert!(applied[0].verified);
+        assert!(!applied[1].verified);
+    }
+
+    #[tokio::test]
+    async fn test_attach_configuration_sends_attach_request() {
+        let adapter = ScriptedAdapter::new();
+        let trace = adapter.trace();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+
+        client
+            .initialize_and_launch_with_pending(
+                "debugpy",
+                json!({"request": "attach", "connect": {"host": "127.0.0.1", "port": 5678}}),
+                Some("python"),
+                pending("/app/main.py", &[10]),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *trace.lock().unwrap(),
+            vec![
+                "initialize",
+                "attach",
+                "setBreakpoints",
+                "configurationDone"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ruby_entry_breakpoint_keeps_pending_breakpoints() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let program = dir.path().join("app.rb");
+        std::fs::write(&program, "x = 1\nputs x\nputs x + 1\n").unwrap();
+        let program = program.to_string_lossy().to_string();
+
+        let adapter = ScriptedAdapter::new();
+        let requests = adapter.requests();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+
+        client
+            .initialize_and_launch_with_pending(
+                "rdbg",
+                json!({"program": program, "stopOnEntry": true}),
+                Some("ruby"),
+                pending(&program, &[3]),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let requests = requests.lock().unwrap();
+        let commands: Vec<&str> = requests.iter().map(|r| r.command.as_str()).collect();
+        assert_eq!(commands.last(), Some(&"configurationDone"));
+
+        // setBreakpoints replaces the file's breakpoints, so the entry
+        // breakpoint request must carry the pending breakpoint as well
+        let last_set = requests
+            .iter()
+            .rev()
+            .find(|r| r.command == "setBreakpoints")
+            .unwrap();
+        let lines: Vec<i64> = last_set.arguments.as_ref().unwrap()["breakpoints"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|bp| bp["line"].as_i64().unwrap())
+            .collect();
+        assert_eq!(lines, vec![3, 1]);
+    }
+
+    // Helper to create a mock that delivers one reverse request and records writes
+    fn create_mock_with_reverse_request(
+        request: Request,
+    ) -> (MockTransport, Arc<std::sync::Mutex<Vec<Message>>>) {
+        let written = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut mock = MockTransport::new();
+
+        let sink = written.clone();
+        mock.writer.expect_write_message().returning(move |msg| {
+            sink.lock().unwrap().push(msg.clone());
+            Ok(())
+        });
+
+        mock.reader
+            .expect_read_message()
+            .times(1)
+            .return_once(move || Ok(Message::Request(request)));
+        mock.reader
+            .expect_read_message()
+            .returning(|| Err(Error::Dap("Connection closed".to_string())));
+
+        (mock, written)
+    }
+
+    async fn wait_for_reverse_response(written: &std::sync::Mutex<Vec<Message>>) -> Response {
+        for _ in 0..100 {
+            if let Some(Message::Response(resp)) = written.lock().unwrap().first() {
+                return resp.clone();
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("No response written for reverse request");
+    }
+
+    #[tokio::test]
+    async fn test_reverse_request_run_in_terminal() {
+        let (mock_transport, written) = create_mock_with_reverse_request(Request {
+            seq: 7,
+            command: "runInTerminal".to_string(),
+            arguments: Some(json!({
+                "kind": "integrated",
+                "cwd": "",
+                "args": ["true"],
+                "env": {"DEBUGGER_MCP_TEST": "1", "DEBUGGER_MCP_UNSET": null}
+            })),
+        });
+
+        let _client = DapClient::new_with_transport(Box::new(mock_transport), None)
+            .await
+            .unwrap();
+
+        let response = wait_for_reverse_response(&written).await;
+        assert_eq!(response.request_seq, 7);
+        assert_eq!(response.command, "runInTerminal");
+        assert!(response.success, "failed: {:?}", response.message);
+        assert!(response.body.unwrap()["processId"].is_u64());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_run_in_terminal_process_killed_with_adapter() {
+        let (mock_transport, written) = create_mock_with_reverse_request(Request {
+            seq: 8,
+            command: "runInTerminal".to_string(),
+            arguments: Some(json!({"cwd": "", "args": ["sleep", "30"]})),
+        });
+        let client = DapClient::new_with_transport(Box::new(mock_transport), None)
+            .await
+            .unwrap();
+
+        let response = wait_for_reverse_response(&written).await;
+        let pid = response.body.unwrap()["processId"].as_u64().unwrap() as u32;
+        assert_eq!(client.terminal_process_ids(), vec![pid]);
+
+        client.kill_adapter();
+        assert!(client.terminal_process_ids().is_empty());
+
+        // Gone, or a zombie waiting to be reaped
+        let killed = || match std::fs::read_to_string(format!("/proc/{}/stat", pid)) {
+            Err(_) => true,
+            Ok(stat) => stat
+                .rsplit(')')
+                .next()
+                .unwrap()
+                .trim_start()
+                .starts_with('Z'),
+        };
+        for _ in 0..100 {
+            if killed() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("runInTerminal process {} still running", pid);
+    }
+
+    #[tokio::test]
+    async fn test_reverse_request_run_in_terminal_spawn_failure() {
+        let (mock_transport, written) = create_mock_with_reverse_request(Request {
+            seq: 3,
+            command: "runInTerminal".to_string(),
+            arguments: Some(json!({
+                "cwd": "",
+                "args": ["/nonexistent/debugger-mcp-test-binary"]
+            })),
+        });
+
+        let _client = DapClient::new_with_transport(Box::new(mock_transport), None)
+            .await
+            .unwrap();
+
+        let response = wait_for_reverse_response(&written).await;
+        assert_eq!(response.request_seq, 3);
+        assert!(!response.success);
+        assert!(response.message.unwrap().contains("runInTerminal"));
+    }
+
+    #[tokio::test]
+    async fn test_reverse_request_unsupported_gets_failure_response() {
+        let (mock_transport, written) = create_mock_with_reverse_request(Request {
+            seq: 4,
+            command: "somethingCustom".to_string(),
+            arguments: None,
+        });
+
+        let _client = DapClient::new_with_transport(Box::new(mock_transport), None)
+            .await
+            .unwrap();
+
+        let response = wait_for_reverse_response(&written).await;
+        assert_eq!(response.request_seq, 4);
+        assert!(!response.success);
+        assert!(response.message.unwrap().contains("not supported"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_child_session_flow_after_unsupported_reverse_request() {
+        // vscode-js-debug keeps going when a reverse request it sends is
+        // refused: the following startDebugging still spawns the child
+        let written = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut mock = MockTransport::new();
+        let sink = written.clone();
+        mock.writer.expect_write_message().returning(move |msg| {
+            sink.lock().unwrap().push(msg.clone());
+            Ok(())
+        });
+        let mut requests = vec![
+            Request {
+                seq: 2,
+                command: "startDebugging".to_string(),
+                arguments: Some(json!({
+                    "request": "launch",
+                    "configuration": {"__pendingTargetId": "target-2"}
+                })),
+            },
+            Request {
+                seq: 1,
+                command: "somethingCustom".to_string(),
+                arguments: None,
+            },
+        ];
+        // Nothing arrives before the child session callback is registered
+        let (go_tx, go_rx) = std::sync::mpsc::channel::<()>();
+        let mut go_rx = Some(go_rx);
+        mock.reader.expect_read_message().returning(move || {
+            if let Some(go_rx) = go_rx.take() {
+                let _ = go_rx.recv();
+            }
+            match requests.pop() {
+                Some(request) => Ok(Message::Request(request)),
+                None => Err(Error::Dap("Connection closed".to_string())),
+            }
+        });
+
+        let client = DapClient::new_with_transport(Box::new(mock), None)
+            .await
+            .unwrap();
+        let (tx, rx) = oneshot::channel();
+        let tx = Arc::new(std::sync::Mutex::new(Some(tx)));
+        client
+            .on_child_session_spawn(move |target_id| {
+                let tx = tx.clone();
+                Box::pin(async move {
+                    if let Some(tx) = tx.lock().unwrap().take() {
+                        let _ = tx.send(target_id);
+                    }
+                })
+            })
+            .await;
+        go_tx.send(()).unwrap();
+
+        let target = tokio::time::timeout(Duration::from_secs(2), rx)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(target, "target-2");
+        for _ in 0..100 {
+            if written.lock().unwrap().len() == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let responses: Vec<(i32, bool)> = written
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|msg| match msg {
+                Message::Response(resp) => Some((resp.request_seq, resp.success)),
+                _ => None,
+            })
+            .collect();
+        assert!(responses.contains(&(1, false)), "{:?}", responses);
+        assert!(responses.contains(&(2, true)), "{:?}", responses);
+    }
+
+    #[tokio::test]
+    async fn test_reverse_request_custom_handler_body() {
+        let (mock_transport, written) = create_mock_with_reverse_request(Request {
+            seq: 5,
+            command: "somethingCustom".to_string(),
+            arguments: Some(json!({"value": 21})),
+        });
+
+        let client = DapClient::new_with_transport(Box::new(mock_transport), None)
+            .await
+            .unwrap();
+        client
+            .on_reverse_request("somethingCustom", |req| {
+                Box::pin(async move {
+                    let value = req.arguments.unwrap()["value"].as_i64().unwrap();
+                    Ok(Some(json!({ "doubled": value * 2 })))
+                })
+            })
+            .await;
+
+        let response = wait_for_reverse_response(&written).await;
+        assert!(response.success);
+        assert_eq!(response.body.unwrap()["doubled"], 42);
+    }
+
+    #[tokio::test]
+    async fn test_reverse_request_start_debugging_invokes_child_callback() {
+        let (mock_transport, written) = create_mock_with_reverse_request(Request {
+            seq: 6,
+            command: "startDebugging".to_string(),
+            arguments: Some(json!({
+                "request": "launch",
+                "configuration": {"__pendingTargetId": "target-1"}
+            })),
+        });
+
+        let client = DapClient::new_with_transport(Box::new(mock_transport), None)
+            .await
+            .unwrap();
+
+        let (tx, rx) = oneshot::channel();
+        let tx = Arc::new(std::sync::Mutex::new(Some(tx)));
+        client
+            .on_child_session_spawn(move |target_id| {
+                let tx = tx.clone();
+                Box::pin(async move {
+                    if let Some(tx) = tx.lock().unwrap().take() {
+                        let _ = tx.send(target_id);
+                    }
+                })
+            })
+            .await;
+
+        let response = wait_for_reverse_response(&written).await;
+        assert!(response.success);
+        assert_eq!(response.command, "startDebugging");
+        assert_eq!(rx.await.unwrap(), "target-1");
+    }
 }
@@ -0,0 +1,168 @@
+//! Request latency and event counts of a DAP client
+//!
+//! Every request sent through [`DapClient`](super::client::DapClient) is
+//! timed per command, and every event read from the adapter is counted. The
+//! session turns these into its [`SessionMetrics`](crate::debug::SessionMetrics).
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+/// Latencies kept per command for the p95
+const LATENCY_SAMPLES: usize = 256;
+
+/// Metrics slot shared by a client, its reader and the Node.js child clients
+pub(crate) type ClientMetricsSlot = Arc<RwLock<Arc<ClientMetrics>>>;
+
+/// Latency summary of one DAP command
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandMetrics {
+    pub command: String,
+    pub count: usize,
+    /// Error responses and timeouts
+    pub failures: usize,
+    pub mean_ms: f64,
+    /// Over the last 256 requests
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Debug, Default)]
+struct CommandSamples {
+    count: usize,
+    failures: usize,
+    total: Duration,
+    max: Duration,
+    recent: VecDeque<Duration>,
+}
+
+/// Counters of one DAP client (shared with child clients)
+#[derive(Debug, Default)]
+pub struct ClientMetrics {
+    commands: Mutex<HashMap<String, CommandSamples>>,
+    events_received: AtomicUsize,
+    /// Requests in a row that timed out; any answer resets it
+    consecutive_timeouts: AtomicUsize,
+}
+
+impl ClientMetrics {
+    /// Record a request that completed (or failed) after `elapsed`
+    pub fn record_request(&self, command: &str, elapsed: Duration, success: bool) {
+        self.consecutive_timeouts.store(0, Ordering::Relaxed);
+        self.record_sample(command, elapsed, success);
+    }
+
+    /// Record a request that got no answer within its timeout
+    pub fn record_timeout(&self, command: &str, elapsed: Duration) {
+        self.consecutive_timeouts.fetch_add(1, Ordering::Relaxed);
+        self.record_sample(command, elapsed, false);
+    }
+
+    fn record_sample(&self, command: &str, elapsed: Duration, success: bool) {
+        let mut commands = self.commands.lock().unwrap_or_else(|e| e.into_inner());
+        let samples = commands.entry(command.to_string()).or_default();
+        samples.count += 1;
+        if !success {
+            samples.failures += 1;
+        }
+        samples.total += elapsed;
+        samples.max = samples.max.max(elapsed);
+        if samples.recent.len() == LATENCY_SAMPLES {
+            samples.recent.pop_front();
+        }
+        samples.recent.push_back(elapsed);
+    }
+
+    pub fn record_event(&self) {
+        self.events_received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn requests_sent(&self) -> usize {
+        let commands = self.commands.lock().unwrap_or_else(|e| e.into_inner());
+        commands.values().map(|samples| samples.count).sum()
+    }
+
+    pub fn events_received(&self) -> usize {
+        self.events_received.load(Ordering::Relaxed)
+    }
+
+    /// Requests that timed out since the adapter last answered one
+    pub fn consecutive_timeouts(&self) -> usize {
+        self.consecutive_timeouts.load(Ordering::Relaxed)
+    }
+
+    /// Per-command summaries, sorted by command name
+    pub fn commands(&self) -> Vec<CommandMetrics> {
+        let commands = self.commands.lock().unwrap_or_else(|e| e.into_inner());
+        let mut summaries: Vec<CommandMetrics> = commands
+            .iter()
+            .map(|(command, samples)| {
+                let mut recent: Vec<Duration> = samples.recent.iter().copied().collect();
+                recent.sort();
+                // Nearest-rank percentile
+                let p95 = recent
+                    .get((recent.len() * 95).div_ceil(100).saturating_sub(1))
+                    .copied()
+                    .unwrap_or_default();
+                CommandMetrics {
+                    command: command.clone(),
+                    count: samples.count,
+                    failures: samples.failures,
+                    mean_ms: millis(samples.total) / samples.count.max(1) as f64,
+                    p95_ms: millis(p95),
+                    max_ms: millis(samples.max),
+                }
+            })
+            .collect();
+        summaries.sort_by(|a, b| a.command.cmp(&b.command));
+        summaries
+    }
+}
+
+/// Milliseconds, rounded to microseconds
+fn millis(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 1_000_000.0).round() / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_summaries() {
+        let metrics = ClientMetrics::default();
+        for ms in 1..=20 {
+            metrics.record_request("stackTrace", Duration::from_millis(ms), true);
+        }
+        metrics.record_request("evaluate", Duration::from_millis(4), false);
+        metrics.record_event();
+
+        assert_eq!(metrics.requests_sent(), 21);
+        assert_eq!(metrics.events_received(), 1);
+
+        let commands = metrics.commands();
+        assert_eq!(commands[0].command, "evaluate");
+        assert_eq!(commands[0].failures, 1);
+        let stack_trace = &commands[1];
+        assert_eq!(stack_trace.count, 20);
+        assert_eq!(stack_trace.mean_ms, 10.5);
+        assert_eq!(stack_trace.p95_ms, 19.0);
+        assert_eq!(stack_trace.max_ms, 20.0);
+    }
+
+    #[test]
+    fn test_consecutive_timeouts_reset_on_answer() {
+        let metrics = ClientMetrics::default();
+        metrics.record_timeout("threads", Duration::from_millis(50));
+        metrics.record_timeout("stackTrace", Duration::from_millis(50));
+        assert_eq!(metrics.consecutive_timeouts(), 2);
+        assert_eq!(metrics.commands()[1].failures, 1);
+
+        // An error response still means the adapter is alive
+        metrics.record_request("evaluate", Duration::from_millis(1), false);
+        assert_eq!(metrics.consecutive_timeouts(), 0);
+    }
+}
@@ -1,8 +1,12 @@
 pub mod client;
+pub mod metrics;
 pub mod multi_connection_listener;
 pub mod socket_helper;
+#[cfg(test)]
+pub(crate) mod testing;
 pub mod transport;
 pub mod transport_trait;
 pub mod types;
+pub mod wire_log;
 
-pub use multi_connection_listener::MultiConnectionListener;
+pub use multi_connection_listener::{ListenerConfig, ListenerStats, MultiConnectionListener};
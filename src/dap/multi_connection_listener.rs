@@ -25,19 +25,90 @@
 /// 4. We match the child connection to the pending target ID
 ///
 /// This module manages accepting multiple connections on the same port.
+use super::types::Message;
 use crate::{Error, Result};
+use serde::Serialize;
+use serde_json::Value;
 use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::{mpsc, Mutex, RwLock};
-use tokio::time::{timeout, Duration};
+use tokio::time::{timeout, Duration, Instant};
 use tracing::{error, info, warn};
 
+/// Most bytes of a new connection inspected before it is handed out; a
+/// first message larger than this is rejected
+const MAX_FIRST_MESSAGE_BYTES: usize = 64 * 1024;
+
 /// Represents a pending child session waiting for a connection
 #[derive(Debug, Clone)]
 pub struct PendingChild {
     pub target_id: String,
     pub created_at: std::time::Instant,
+    /// How long the child has to connect before it is dropped
+    pub accept_timeout: Duration,
+}
+
+/// Limits of a [`MultiConnectionListener`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListenerConfig {
+    /// Port on 127.0.0.1 to listen on; 0 picks a free one
+    pub port: u16,
+    /// How long to wait for the parent connection
+    pub parent_timeout: Duration,
+    /// How long a registered child has to connect, unless registered with
+    /// its own timeout
+    pub child_accept_timeout: Duration,
+    /// Most connections held at once (being checked or waiting to be
+    /// claimed); more are closed right away
+    pub max_connections: usize,
+    /// How long a new connection has to send its first DAP message
+    pub first_message_timeout: Duration,
+}
+
+impl Default for ListenerConfig {
+    fn default() -> Self {
+        Self {
+            port: 0,
+            parent_timeout: Duration::from_secs(5),
+            child_accept_timeout: Duration::from_secs(30),
+            max_connections: 8,
+            first_message_timeout: Duration::from_secs(2),
+        }
+    }
+}
+
+/// What the listener did with incoming connections
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListenerStats {
+    /// Child connections accepted (the parent is not counted)
+    pub accepted: u64,
+    /// Handed to a waiting child session
+    pub matched: u64,
+    /// Closed because the first message was not a DAP frame
+    pub rejected_invalid: u64,
+    /// Closed because `max_connections` were already held
+    pub rejected_over_limit: u64,
+    /// Closed because no child was expected
+    pub rejected_unexpected: u64,
+    /// Pending children that never connected in time
+    pub expired: u64,
+    /// Connections held right now
+    pub active: usize,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    accepted: AtomicU64,
+    matched: AtomicU64,
+    rejected_invalid: AtomicU64,
+    rejected_over_limit: AtomicU64,
+    rejected_unexpected: AtomicU64,
+    expired: AtomicU64,
+    active: AtomicUsize,
 }
 
 /// Multi-connection listener state
@@ -46,12 +117,14 @@ pub struct MultiConnectionListener {
     listener: Arc<Mutex<TcpListener>>,
     /// Port the listener is bound to
     pub port: u16,
+    config: ListenerConfig,
     /// Map of pending child sessions (target_id -> PendingChild)
     pending_children: Arc<RwLock<HashMap<String, PendingChild>>>,
     /// Channel to send accepted child connections
     child_tx: mpsc::UnboundedSender<(String, TcpStream)>,
     /// Channel to receive accepted child connections
     child_rx: Arc<Mutex<mpsc::UnboundedReceiver<(String, TcpStream)>>>,
+    counters: Arc<Counters>,
 }
 
 impl MultiConnectionListener {
@@ -62,8 +135,18 @@ impl MultiConnectionListener {
     ///
     /// Returns: (parent_socket, listener)
     pub async fn create_and_accept_parent() -> Result<(TcpStream, Self)> {
-        // Bind to a free port
-        let listener = TcpListener::bind("127.0.0.1:0")
+        Self::create_and_accept_parent_with_config(ListenerConfig::default()).await
+    }
+
+    /// [`MultiConnectionListener::create_and_accept_parent`] with the limits
+    /// in `config`
+    ///
+    /// Only 127.0.0.1 is listened on, and connections from elsewhere are
+    /// refused.
+    pub async fn create_and_accept_parent_with_config(
+        config: ListenerConfig,
+    ) -> Result<(TcpStream, Self)> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, config.port))
             .await
             .map_err(|e| Error::Process(format!("Failed to bind TCP listener: {}", e)))?;
 
@@ -75,16 +158,24 @@ impl MultiConnectionListener {
         info!("📡 Multi-connection listener bound to port {}", port);
 
         // Wait for parent connection with timeout
-        let parent_socket = timeout(Duration::from_secs(5), listener.accept())
-            .await
-            .map_err(|_| {
-                Error::Process(format!(
-                    "Timeout waiting for parent connection on port {}",
-                    port
-                ))
-            })?
-            .map_err(|e| Error::Process(format!("Failed to accept parent connection: {}", e)))?
-            .0;
+        let deadline = Instant::now() + config.parent_timeout;
+        let parent_socket = loop {
+            let (socket, addr) = tokio::time::timeout_at(deadline, listener.accept())
+                .await
+                .map_err(|_| {
+                    Error::Process(format!(
+                        "Timeout waiting for parent connection on port {}",
+                        port
+                    ))
+                })?
+                .map_err(|e| {
+                    Error::Process(format!("Failed to accept parent connection: {}", e))
+                })?;
+            if addr.ip().is_loopback() {
+                break socket;
+            }
+            warn!("🚫 Refusing parent connection from {}", addr);
+        };
 
         info!("✅ Parent connection accepted on port {}", port);
 
@@ -94,9 +185,11 @@ impl MultiConnectionListener {
         let multi_listener = Self {
             listener: Arc::new(Mutex::new(listener)),
             port,
+            config,
             pending_children: Arc::new(RwLock::new(HashMap::new())),
             child_tx,
             child_rx: Arc::new(Mutex::new(child_rx)),
+            counters: Arc::new(Counters::default()),
         };
 
         // Start accepting child connections in background
@@ -110,13 +203,28 @@ impl MultiConnectionListener {
     /// When parent sends `startDebugging` with `__pendingTargetId`, we register
     /// it here so we can match incoming connections.
     pub async fn register_pending_child(&self, target_id: String) {
-        info!("📝 Registering pending child: {}", target_id);
+        self.register_pending_child_with_timeout(target_id, self.config.child_accept_timeout)
+            .await
+    }
+
+    /// [`MultiConnectionListener::register_pending_child`] giving the child
+    /// `accept_timeout` to connect
+    pub async fn register_pending_child_with_timeout(
+        &self,
+        target_id: String,
+        accept_timeout: Duration,
+    ) {
+        info!(
+            "📝 Registering pending child: {} (accept timeout {:?})",
+            target_id, accept_timeout
+        );
         let mut pending = self.pending_children.write().await;
         pending.insert(
             target_id.clone(),
             PendingChild {
                 target_id,
                 created_at: std::time::Instant::now(),
+                accept_timeout,
             },
         );
     }
@@ -139,8 +247,10 @@ impl MultiConnectionListener {
             let mut rx = self.child_rx.lock().await;
             loop {
                 if let Some((id, socket)) = rx.recv().await {
+                    self.counters.active.fetch_sub(1, Ordering::Relaxed);
                     if id == target_id {
                         info!("✅ Child connection matched: {}", target_id);
+                        self.counters.matched.fetch_add(1, Ordering::Relaxed);
                         return Ok(socket);
                     } else {
                         warn!(
@@ -172,9 +282,13 @@ impl MultiConnectionListener {
     /// Start the background task that accepts child connections
     fn start_accept_loop(&self) {
         let listener = self.listener.clone();
-        let pending_children = self.pending_children.clone();
-        let child_tx = self.child_tx.clone();
         let port = self.port;
+        let accepted = AcceptedConnections {
+            pending_children: self.pending_children.clone(),
+            child_tx: self.child_tx.clone(),
+            counters: self.counters.clone(),
+            config: self.config.clone(),
+        };
 
         tokio::spawn(async move {
             info!("🔄 Starting child connection accept loop on port {}", port);
@@ -186,27 +300,7 @@ impl MultiConnectionListener {
                         drop(listener_guard); // Release lock before processing
 
                         info!("📥 New connection received from {} on port {}", addr, port);
-
-                        // We don't have target_id yet - it will come in the DAP initialize message
-                        // For now, we need to peek at the first DAP message to extract __pendingTargetId
-                        // TODO: Implement DAP message peeking
-
-                        // Temporary workaround: Try to match with any pending child
-                        let pending = pending_children.read().await;
-                        if let Some((target_id, _)) = pending.iter().next() {
-                            let target_id = target_id.clone();
-                            drop(pending);
-
-                            info!("🎯 Matching connection to pending child: {}", target_id);
-                            if child_tx.send((target_id.clone(), socket)).is_err() {
-                                error!("❌ Failed to send child connection to channel");
-                            }
-
-                            // Remove from pending list
-                            pending_children.write().await.remove(&target_id);
-                        } else {
-                            warn!("⚠️  Received connection but no pending children registered");
-                        }
+                        accepted.admit(socket, addr);
                     }
                     Err(e) => {
                         error!("❌ Error accepting connection on port {}: {}", port, e);
@@ -223,22 +317,397 @@ impl MultiConnectionListener {
         self.port
     }
 
-    /// Clean up expired pending children (older than 30 seconds)
+    /// Counts of accepted, matched and rejected connections
+    pub fn stats(&self) -> ListenerStats {
+        let counters = &self.counters;
+        ListenerStats {
+            accepted: counters.accepted.load(Ordering::Relaxed),
+            matched: counters.matched.load(Ordering::Relaxed),
+            rejected_invalid: counters.rejected_invalid.load(Ordering::Relaxed),
+            rejected_over_limit: counters.rejected_over_limit.load(Ordering::Relaxed),
+            rejected_unexpected: counters.rejected_unexpected.load(Ordering::Relaxed),
+            expired: counters.expired.load(Ordering::Relaxed),
+            active: counters.active.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Drop pending children whose accept timeout has passed
     pub async fn cleanup_expired_pending(&self) {
         let mut pending = self.pending_children.write().await;
-        let now = std::time::Instant::now();
-        pending.retain(|id, child| {
-            let age = now.duration_since(child.created_at);
-            if age.as_secs() > 30 {
-                warn!(
-                    "🧹 Cleaning up expired pending child: {} (age: {}s)",
-                    id,
-                    age.as_secs()
-                );
-                false
-            } else {
-                true
+        let expired = expire_pending(&mut pending);
+        self.counters
+            .expired
+            .fetch_add(expired as u64, Ordering::Relaxed);
+    }
+}
+
+/// Remove pending children older than their accept timeout; returns how many
+fn expire_pending(pending: &mut HashMap<String, PendingChild>) -> usize {
+    let now = std::time::Instant::now();
+    let before = pending.len();
+    pending.retain(|id, child| {
+        let age = now.duration_since(child.created_at);
+        if age > child.accept_timeout {
+            warn!(
+                "🧹 Cleaning up expired pending child: {} (age: {}s)",
+                id,
+                age.as_secs()
+            );
+            false
+        } else {
+            true
+        }
+    });
+    before - pending.len()
+}
+
+/// What the accept loop needs to vet and route a new connection
+#[derive(Clone)]
+struct AcceptedConnections {
+    pending_children: Arc<RwLock<HashMap<String, PendingChild>>>,
+    child_tx: mpsc::UnboundedSender<(String, TcpStream)>,
+    counters: Arc<Counters>,
+    config: ListenerConfig,
+}
+
+impl AcceptedConnections {
+    /// Check `socket` in the background so a slow or silent peer does not
+    /// hold up the accept loop
+    fn admit(&self, socket: TcpStream, addr: SocketAddr) {
+        let counters = &self.counters;
+        counters.accepted.fetch_add(1, Ordering::Relaxed);
+        if !addr.ip().is_loopback() {
+            warn!("🚫 Refusing connection from {}", addr);
+            counters.rejected_unexpected.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        if counters.active.fetch_add(1, Ordering::Relaxed) >= self.config.max_connections {
+            counters.active.fetch_sub(1, Ordering::Relaxed);
+            counters.rejected_over_limit.fetch_add(1, Ordering::Relaxed);
+            warn!(
+                "🚫 Refusing connection from {}: {} connections already held",
+                addr, self.config.max_connections
+            );
+            return;
+        }
+
+        let accepted = self.clone();
+        tokio::spawn(async move {
+            if !accepted.route(socket, addr).await {
+                accepted.counters.active.fetch_sub(1, Ordering::Relaxed);
             }
         });
     }
+
+    /// Hand `socket` to the pending child it belongs to; false when it was
+    /// dropped instead
+    async fn route(&self, socket: TcpStream, addr: SocketAddr) -> bool {
+        let first = match timeout(
+            self.config.first_message_timeout,
+            peek_first_message(&socket),
+        )
+        .await
+        {
+            Ok(first) => first,
+            Err(_) => FirstMessage::Invalid(format!(
+                "no DAP message within {:?}",
+                self.config.first_message_timeout
+            )),
+        };
+        let target_id = match first {
+            FirstMessage::Valid { target_id } => target_id,
+            FirstMessage::Invalid(reason) | FirstMessage::Incomplete(reason) => {
+                warn!("🚫 Dropping connection from {}: {}", addr, reason);
+                self.counters
+                    .rejected_invalid
+                    .fetch_add(1, Ordering::Relaxed);
+                return false;
+            }
+        };
+
+        let mut pending = self.pending_children.write().await;
+        let expired = expire_pending(&mut pending);
+        self.counters
+            .expired
+            .fetch_add(expired as u64, Ordering::Relaxed);
+        // Children that do not name their target get the oldest pending one
+        let target_id = match target_id {
+            Some(id) if pending.contains_key(&id) => Some(id),
+            Some(id) => {
+                warn!("⚠️  Connection from {} names unknown target {}", addr, id);
+                None
+            }
+            None => pending
+                .values()
+                .min_by_key(|child| child.created_at)
+                .map(|child| child.target_id.clone()),
+        };
+        let Some(target_id) = target_id else {
+            warn!("⚠️  Received connection but no pending children registered");
+            self.counters
+                .rejected_unexpected
+                .fetch_add(1, Ordering::Relaxed);
+            return false;
+        };
+        pending.remove(&target_id);
+        drop(pending);
+
+        info!("🎯 Matching connection to pending child: {}", target_id);
+        if self.child_tx.send((target_id, socket)).is_err() {
+            error!("❌ Failed to send child connection to channel");
+            return false;
+        }
+        true
+    }
+}
+
+/// The first message of a new connection, as far as it has arrived
+#[derive(Debug, PartialEq)]
+enum FirstMessage {
+    /// A DAP message; `target_id` is the `__pendingTargetId` it carries
+    Valid { target_id: Option<String> },
+    /// Not (the start of) a DAP message
+    Invalid(String),
+    /// Could still become a DAP message once more bytes arrive
+    Incomplete(String),
+}
+
+/// Wait until the first message of `socket` has arrived and check it,
+/// without consuming anything the session will read
+async fn peek_first_message(socket: &TcpStream) -> FirstMessage {
+    let mut buffer = vec![0u8; MAX_FIRST_MESSAGE_BYTES];
+    let mut available = 0;
+    loop {
+        let read = match socket.peek(&mut buffer).await {
+            Ok(read) => read,
+            Err(e) => return FirstMessage::Invalid(e.to_string()),
+        };
+        if read == 0 {
+            return FirstMessage::Invalid("closed before sending a message".to_string());
+        }
+        let first = parse_first_message(&buffer[..read]);
+        if !matches!(first, FirstMessage::Incomplete(_)) || read == buffer.len() {
+            return first;
+        }
+        // peek returns at once while unread data is waiting; poll for more
+        if read == available {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        available = read;
+    }
+}
+
+/// Check that `bytes` start with a Content-Length framed DAP message
+fn parse_first_message(bytes: &[u8]) -> FirstMessage {
+    let Some(header_end) = bytes.windows(4).position(|w| w == b"\r\n\r\n") else {
+        let prefix = &bytes[..bytes.len().min(16)];
+        return if b"Content-Length:".starts_with(prefix) || prefix.starts_with(b"Content-Length:") {
+            FirstMessage::Incomplete("headers not complete".to_string())
+        } else {
+            FirstMessage::Invalid("does not start with a Content-Length header".to_string())
+        };
+    };
+    let Ok(headers) = std::str::from_utf8(&bytes[..header_end]) else {
+        return FirstMessage::Invalid("headers are not UTF-8".to_string());
+    };
+    let Some(length) = headers
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("Content-Length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+    else {
+        return FirstMessage::Invalid("missing or invalid Content-Length".to_string());
+    };
+    let body_start = header_end + 4;
+    if body_start + length > MAX_FIRST_MESSAGE_BYTES {
+        return FirstMessage::Invalid(format!(
+            "first message of {} bytes is larger than {} bytes",
+            length, MAX_FIRST_MESSAGE_BYTES
+        ));
+    }
+    let Some(body) = bytes.get(body_start..body_start + length) else {
+        return FirstMessage::Incomplete("body not complete".to_string());
+    };
+    let Ok(value) = serde_json::from_slice::<Value>(body) else {
+        return FirstMessage::Invalid("body is not JSON".to_string());
+    };
+    if serde_json::from_value::<Message>(value.clone()).is_err() {
+        return FirstMessage::Invalid("body is not a DAP message".to_string());
+    }
+    let target_id = value
+        .pointer("/arguments/__pendingTargetId")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    FirstMessage::Valid { target_id }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dap::socket_helper;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn frame(body: &str) -> Vec<u8> {
+        format!("Content-Length: {}\r\n\r\n{}", body.len(), body).into_bytes()
+    }
+
+    const LAUNCH: &str =
+        r#"{"seq":1,"type":"request","command":"launch","arguments":{"__pendingTargetId":"t1"}}"#;
+
+    #[test]
+    fn test_parse_first_message() {
+        assert_eq!(
+            parse_first_message(&frame(LAUNCH)),
+            FirstMessage::Valid {
+                target_id: Some("t1".to_string())
+            }
+        );
+        let initialize = r#"{"seq":1,"type":"request","command":"initialize"}"#;
+        assert_eq!(
+            parse_first_message(&frame(initialize)),
+            FirstMessage::Valid { target_id: None }
+        );
+
+        let whole = frame(LAUNCH);
+        for partial in [&b"Content-Le"[..], &whole[..30], &whole[..whole.len() - 1]] {
+            assert!(matches!(
+                parse_first_message(partial),
+                FirstMessage::Incomplete(_)
+            ));
+        }
+
+        for garbage in [
+            b"GET / HTTP/1.1\r\n\r\n".to_vec(),
+            b"SSH-2.0-OpenSSH_9.6\r\n".to_vec(),
+            b"Content-Length: x\r\n\r\n{}".to_vec(),
+            frame("not json"),
+            frame(r#"{"hello":"world"}"#),
+        ] {
+            assert!(
+                matches!(parse_first_message(&garbage), FirstMessage::Invalid(_)),
+                "{:?}",
+                String::from_utf8_lossy(&garbage)
+            );
+        }
+    }
+
+    /// A listener on a known port with its parent connected
+    async fn listener(config: ListenerConfig) -> (TcpStream, MultiConnectionListener) {
+        let port = socket_helper::find_free_port().unwrap();
+        let parent = tokio::spawn(async move {
+            socket_helper::connect_with_retry(port, Duration::from_secs(5))
+                .await
+                .unwrap()
+        });
+        let (_, listener) =
+            MultiConnectionListener::create_and_accept_parent_with_config(ListenerConfig {
+                port,
+                ..config
+            })
+            .await
+            .unwrap();
+        (parent.await.unwrap(), listener)
+    }
+
+    async fn wait_for_stats(
+        listener: &MultiConnectionListener,
+        done: impl Fn(&ListenerStats) -> bool,
+    ) -> ListenerStats {
+        for _ in 0..100 {
+            let stats = listener.stats();
+            if done(&stats) {
+                return stats;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("Listener stats never settled: {:?}", listener.stats());
+    }
+
+    #[tokio::test]
+    async fn test_garbage_connection_is_dropped_and_child_still_connects() {
+        let (_parent, listener) = listener(ListenerConfig::default()).await;
+        listener.register_pending_child("t1".to_string()).await;
+
+        // A stray client sends something that is not DAP
+        let mut stray = TcpStream::connect(("127.0.0.1", listener.port()))
+            .await
+            .unwrap();
+        stray.write_all(b"GET / HTTP/1.1\r\n\r\n").await.unwrap();
+        let stats = wait_for_stats(&listener, |s| s.rejected_invalid == 1).await;
+        assert_eq!(stats.matched, 0);
+        // Closed (reset, as its request was never read)
+        let mut rest = [0u8; 16];
+        assert!(matches!(stray.read(&mut rest).await, Ok(0) | Err(_)));
+
+        // The real child is still matched, with its first message unread
+        let mut child = TcpStream::connect(("127.0.0.1", listener.port()))
+            .await
+            .unwrap();
+        child.write_all(&frame(LAUNCH)).await.unwrap();
+        let mut socket = listener
+            .wait_for_child_connection("t1", Duration::from_secs(5))
+            .await
+            .unwrap();
+        let mut received = vec![0u8; frame(LAUNCH).len()];
+        socket.read_exact(&mut received).await.unwrap();
+        assert_eq!(received, frame(LAUNCH));
+
+        let stats = listener.stats();
+        assert_eq!(
+            (
+                stats.accepted,
+                stats.matched,
+                stats.rejected_invalid,
+                stats.active
+            ),
+            (2, 1, 1, 0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connection_limit_and_first_message_timeout() {
+        let (_parent, listener) = listener(ListenerConfig {
+            max_connections: 1,
+            first_message_timeout: Duration::from_millis(500),
+            ..ListenerConfig::default()
+        })
+        .await;
+        listener.register_pending_child("t1".to_string()).await;
+
+        // A silent connection holds the only slot until it times out
+        let _silent = TcpStream::connect(("127.0.0.1", listener.port()))
+            .await
+            .unwrap();
+        wait_for_stats(&listener, |s| s.active == 1).await;
+        let _extra = TcpStream::connect(("127.0.0.1", listener.port()))
+            .await
+            .unwrap();
+        wait_for_stats(&listener, |s| s.rejected_over_limit == 1).await;
+        let stats = wait_for_stats(&listener, |s| s.rejected_invalid == 1).await;
+        assert_eq!(stats.active, 0);
+
+        // With the slot free again the child gets through
+        let mut child = TcpStream::connect(("127.0.0.1", listener.port()))
+            .await
+            .unwrap();
+        child.write_all(&frame(LAUNCH)).await.unwrap();
+        assert!(listener
+            .wait_for_child_connection("t1", Duration::from_secs(5))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_pending_child_expires_after_its_accept_timeout() {
+        let (_parent, listener) = listener(ListenerConfig::default()).await;
+        listener
+            .register_pending_child_with_timeout("t1".to_string(), Duration::from_millis(50))
+            .await;
+        listener.register_pending_child("t2".to_string()).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        listener.cleanup_expired_pending().await;
+        assert_eq!(listener.stats().expired, 1);
+        assert!(listener.pending_children.read().await.contains_key("t2"));
+    }
 }
@@ -1,11 +1,271 @@
 /// Helper functions for socket-based DAP adapters (e.g., Ruby/rdbg)
 use crate::{Error, Result};
-use std::time::Duration;
+use std::collections::VecDeque;
+use std::future::Future;
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::net::TcpStream;
-use tracing::{debug, info};
+#[cfg(unix)]
+use tokio::net::UnixStream;
+use tokio::process::Child;
+use tracing::{debug, info, warn};
 
-/// Find an available TCP port on localhost
+/// Lines of adapter stderr kept for connection error messages
+const STDERR_TAIL_LINES: usize = 20;
+
+/// How many endpoints [`spawn_socket_adapter`] tries before giving up
+pub const ENDPOINT_ATTEMPTS: usize = 3;
+
+/// How long to wait for a debug adapter to come up
+///
+/// The defaults depend on the language ([`AdapterTimeouts::for_language`]).
+/// Slow CI machines or cold Docker volumes can need far more; set per session
+/// with `adapterTimeoutMs` or server-wide with `serve --adapter-timeout-ms`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdapterTimeouts {
+    /// How long to keep retrying the connection to a socket-based adapter
+    pub connect: Duration,
+    /// Delay between connection attempts
+    pub retry_interval: Duration,
+    /// Limit for the whole initialize + launch handshake
+    pub initialize: Duration,
+}
+
+impl Default for AdapterTimeouts {
+    fn default() -> Self {
+        Self {
+            connect: Duration::from_secs(3),
+            retry_interval: Duration::from_millis(100),
+            initialize: Duration::from_secs(7),
+        }
+    }
+}
+
+impl AdapterTimeouts {
+    /// Use `timeout` for both connecting and the initialize handshake
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            connect: timeout,
+            initialize: timeout,
+            ..Self::default()
+        }
+    }
+
+    /// Defaults for sessions of `language`
+    ///
+    /// vscode-js-debug starts a child session before the program runs, and
+    /// CodeLLDB loads the debug info of the whole binary during launch, so
+    /// Node.js and Rust get 15s for the handshake instead of 7s. Compiling a
+    /// Rust program happens before the adapter is spawned and does not count.
+    pub fn for_language(language: &str) -> Self {
+        match language {
+            "nodejs" | "rust" => Self {
+                initialize: Duration::from_secs(15),
+                ..Self::default()
+            },
+            _ => Self::default(),
+        }
+    }
+}
+
+/// The last lines an adapter wrote to stderr
+///
+/// Draining the pipe in the background also keeps a chatty adapter from
+/// blocking on a full pipe once it is connected.
+#[derive(Debug, Clone, Default)]
+pub struct StderrTail {
+    lines: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl StderrTail {
+    /// Start collecting the stderr of `child` (spawned with `Stdio::piped()`)
+    pub fn capture(adapter: &str, child: &mut Child) -> Self {
+        let tail = Self::default();
+        if let Some(stderr) = child.stderr.take() {
+            let lines = Arc::clone(&tail.lines);
+            let adapter = adapter.to_string();
+            tokio::spawn(async move {
+                let mut reader = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = reader.next_line().await {
+                    debug!("[{} stderr] {}", adapter, line);
+                    let mut lines = lines.lock().unwrap_or_else(|e| e.into_inner());
+                    if lines.len() == STDERR_TAIL_LINES {
+                        lines.pop_front();
+                    }
+                    lines.push_back(line);
+                }
+            });
+        }
+        tail
+    }
+
+    /// Collected lines, oldest first
+    pub fn text(&self) -> String {
+        let lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        lines.iter().cloned().collect::<Vec<_>>().join("\n")
+    }
+}
+
+/// Where a socket-mode adapter listens
+///
+/// A unix domain socket cannot collide with another service's port and is
+/// not reachable from outside the machine (or container), so adapters that
+/// support it prefer it; TCP on localhost is the fallback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdapterEndpoint {
+    Tcp(u16),
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl AdapterEndpoint {
+    /// A unix socket path for `adapter` if the platform has them, otherwise
+    /// a free TCP port
+    pub fn prefer_unix(adapter: &str) -> Result<Self> {
+        #[cfg(unix)]
+        match create_unix_socket_path(adapter) {
+            Ok(path) => return Ok(Self::Unix(path)),
+            Err(e) => debug!("Falling back to TCP for {}: {}", adapter, e),
+        }
+        Ok(Self::Tcp(find_free_port()?))
+    }
+
+    /// TCP port, for TCP endpoints
+    pub fn port(&self) -> Option<u16> {
+        match self {
+            Self::Tcp(port) => Some(*port),
+            #[cfg(unix)]
+            Self::Unix(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for AdapterEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tcp(port) => write!(f, "tcp:127.0.0.1:{}", port),
+            #[cfg(unix)]
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// A connection to a socket-mode adapter
+#[derive(Debug)]
+pub enum AdapterStream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+/// Longest unix socket path the platform accepts (`sun_path` minus the NUL)
+#[cfg(unix)]
+const MAX_UNIX_SOCKET_PATH: usize = if cfg!(target_os = "linux") { 107 } else { 103 };
+
+/// A fresh path for `adapter` to listen on, in the temp directory
+///
+/// Fails when the path would be too long for a socket address (a deep
+/// `TMPDIR`), in which case the caller uses TCP.
+#[cfg(unix)]
+pub fn create_unix_socket_path(adapter: &str) -> Result<PathBuf> {
+    static NEXT: AtomicU32 = AtomicU32::new(0);
+    let name = format!(
+        "debugger-mcp-{}-{}-{}.sock",
+        adapter,
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    );
+    let path = std::env::temp_dir().join(name);
+    if path.as_os_str().len() > MAX_UNIX_SOCKET_PATH {
+        return Err(Error::Process(format!(
+            "Unix socket path {} is longer than {} bytes",
+            path.display(),
+            MAX_UNIX_SOCKET_PATH
+        )));
+    }
+    // A leftover from an earlier server with the same PID would make the
+    // adapter fail to bind
+    let _ = std::fs::remove_file(&path);
+    Ok(path)
+}
+
+/// Inclusive range of ports socket-mode adapters may listen on
+///
+/// Set server-wide with `serve --dap-port-range 40000-40100` when only some
+/// ports are open (firewalls, published container ports).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortRange {
+    pub first: u16,
+    pub last: u16,
+}
+
+impl PortRange {
+    /// Number of ports in the range
+    pub fn len(&self) -> u32 {
+        u32::from(self.last) - u32::from(self.first) + 1
+    }
+
+    /// Always false: a range holds at least one port
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    pub fn contains(&self, port: u16) -> bool {
+        (self.first..=self.last).contains(&port)
+    }
+}
+
+impl std::str::FromStr for PortRange {
+    type Err = String;
+
+    /// `FIRST-LAST`, or a single port
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let parse = |port: &str| {
+            port.trim()
+                .parse::<u16>()
+                .ok()
+                .filter(|port| *port > 0)
+                .ok_or_else(|| format!("'{}' is not a port number (1-65535)", port.trim()))
+        };
+        let (first, last) = match s.split_once('-') {
+            Some((first, last)) => (parse(first)?, parse(last)?),
+            None => (parse(s)?, parse(s)?),
+        };
+        if first > last {
+            return Err(format!("Port range {}-{} is empty", first, last));
+        }
+        Ok(Self { first, last })
+    }
+}
+
+impl std::fmt::Display for PortRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.first, self.last)
+    }
+}
+
+/// Range [`find_free_port`] picks from; any port when unset
+static PORT_RANGE: RwLock<Option<PortRange>> = RwLock::new(None);
+
+/// Restrict the ports socket-mode adapters listen on, for the whole process
+pub fn set_port_range(range: Option<PortRange>) {
+    *PORT_RANGE.write().unwrap_or_else(|e| e.into_inner()) = range;
+}
+
+/// The range set with [`set_port_range`]
+pub fn port_range() -> Option<PortRange> {
+    *PORT_RANGE.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Find an available TCP port on localhost, within [`port_range`] if set
 pub fn find_free_port() -> Result<u16> {
+    if let Some(range) = port_range() {
+        return find_free_port_in(range);
+    }
     // Use port 0 to let OS assign a free port
     let listener = std::net::TcpListener::bind("127.0.0.1:0")
         .map_err(|e| Error::Process(format!("Failed to bind to port: {}", e)))?;
@@ -19,45 +279,409 @@ pub fn find_free_port() -> Result<u16> {
     Ok(port)
 }
 
+/// Find an available TCP port on localhost within `range`
+///
+/// The search starts after the port handed out last, so a retry after an
+/// adapter lost its port to another process gets a different one.
+pub fn find_free_port_in(range: PortRange) -> Result<u16> {
+    static NEXT: AtomicU32 = AtomicU32::new(0);
+    let start = NEXT.load(Ordering::Relaxed);
+    for offset in 0..range.len() {
+        let index = (start + offset) % range.len();
+        // Fits: index < len <= 65535 - first + 1
+        let port = range.first + index as u16;
+        if std::net::TcpListener::bind(("127.0.0.1", port)).is_ok() {
+            NEXT.store(index + 1, Ordering::Relaxed);
+            debug!("Found free port {} in {}", port, range);
+            return Ok(port);
+        }
+    }
+    Err(Error::Process(format!(
+        "No free port in range {} (see --dap-port-range)",
+        range
+    )))
+}
+
+/// Whether a failed connect means nothing listens yet, as opposed to a
+/// broken connection or a network problem that retrying will not fix
+///
+/// A unix socket file only appears once the adapter listens.
+fn adapter_not_listening_yet(error: &std::io::Error) -> bool {
+    matches!(
+        error.kind(),
+        std::io::ErrorKind::ConnectionRefused
+            | std::io::ErrorKind::NotFound
+            | std::io::ErrorKind::TimedOut
+    )
+}
+
 /// Connect to TCP socket with retry and timeout
 ///
 /// Retries connecting to the specified port for up to `timeout` duration,
 /// with 100ms between attempts.
 pub async fn connect_with_retry(port: u16, timeout: Duration) -> Result<TcpStream> {
-    let start = std::time::Instant::now();
-    let retry_interval = Duration::from_millis(100);
+    connect_host_with_retry("127.0.0.1", port, timeout).await
+}
+
+/// Connect to `host:port` with retry and timeout (e.g. a remote debug server)
+pub async fn connect_host_with_retry(
+    host: &str,
+    port: u16,
+    timeout: Duration,
+) -> Result<TcpStream> {
+    let timeouts = AdapterTimeouts {
+        connect: timeout,
+        ..AdapterTimeouts::default()
+    };
+    connect_host_with_timeouts(host, port, &timeouts).await
+}
 
-    info!("Connecting to 127.0.0.1:{} (timeout: {:?})", port, timeout);
+/// Connect to `host:port`, retrying every `timeouts.retry_interval` for up
+/// to `timeouts.connect`
+pub async fn connect_host_with_timeouts(
+    host: &str,
+    port: u16,
+    timeouts: &AdapterTimeouts,
+) -> Result<TcpStream> {
+    let start = Instant::now();
+
+    info!(
+        "Connecting to {}:{} (timeout: {:?})",
+        host, port, timeouts.connect
+    );
 
     loop {
-        match TcpStream::connect(("127.0.0.1", port)).await {
+        match TcpStream::connect((host, port)).await {
             Ok(stream) => {
-                info!(
-                    "Connected to 127.0.0.1:{} after {:?}",
-                    port,
-                    start.elapsed()
-                );
+                info!("Connected to {}:{} after {:?}", host, port, start.elapsed());
                 return Ok(stream);
             }
             Err(e) => {
-                if start.elapsed() >= timeout {
+                if !adapter_not_listening_yet(&e) || start.elapsed() >= timeouts.connect {
                     return Err(Error::Process(format!(
-                        "Failed to connect to port {} after {:?}: {}",
-                        port, timeout, e
+                        "Failed to connect to {}:{} after {:?}: {}",
+                        host,
+                        port,
+                        start.elapsed(),
+                        e
                     )));
                 }
                 // Wait before retrying
-                tokio::time::sleep(retry_interval).await;
+                tokio::time::sleep(timeouts.retry_interval).await;
+            }
+        }
+    }
+}
+
+/// Connect to `endpoint`, retrying every `timeouts.retry_interval` for up
+/// to `timeouts.connect`
+pub async fn connect_endpoint_with_timeouts(
+    endpoint: &AdapterEndpoint,
+    timeouts: &AdapterTimeouts,
+) -> Result<AdapterStream> {
+    match endpoint {
+        AdapterEndpoint::Tcp(port) => connect_host_with_timeouts("127.0.0.1", *port, timeouts)
+            .await
+            .map(AdapterStream::Tcp),
+        #[cfg(unix)]
+        AdapterEndpoint::Unix(path) => {
+            let start = Instant::now();
+            loop {
+                match UnixStream::connect(path).await {
+                    Ok(stream) => return Ok(AdapterStream::Unix(stream)),
+                    Err(e)
+                        if !adapter_not_listening_yet(&e)
+                            || start.elapsed() >= timeouts.connect =>
+                    {
+                        return Err(Error::Process(format!(
+                            "Failed to connect to {} after {:?}: {}",
+                            path.display(),
+                            start.elapsed(),
+                            e
+                        )));
+                    }
+                    Err(_) => tokio::time::sleep(timeouts.retry_interval).await,
+                }
+            }
+        }
+    }
+}
+
+/// Connect to an adapter process listening on `port` on localhost
+///
+/// Stops retrying as soon as the adapter exits. The error says how long we
+/// waited, whether the adapter is still running or how it exited, and the
+/// tail of its stderr.
+pub async fn connect_to_adapter(
+    adapter: &str,
+    port: u16,
+    child: &mut Child,
+    stderr: &StderrTail,
+    timeouts: &AdapterTimeouts,
+) -> Result<TcpStream> {
+    let place = format!("port {}", port);
+    wait_for_adapter(adapter, &place, child, stderr, timeouts, || {
+        TcpStream::connect(("127.0.0.1", port))
+    })
+    .await
+    .map_err(Error::from)
+}
+
+/// [`connect_to_adapter`] for an adapter listening on a unix socket
+#[cfg(unix)]
+pub async fn connect_unix_to_adapter(
+    adapter: &str,
+    path: &std::path::Path,
+    child: &mut Child,
+    stderr: &StderrTail,
+    timeouts: &AdapterTimeouts,
+) -> Result<UnixStream> {
+    let place = format!("unix socket {}", path.display());
+    wait_for_adapter(adapter, &place, child, stderr, timeouts, || {
+        UnixStream::connect(path)
+    })
+    .await
+    .map_err(Error::from)
+}
+
+/// [`connect_to_adapter`] for either kind of endpoint
+pub async fn connect_to_adapter_endpoint(
+    adapter: &str,
+    endpoint: &AdapterEndpoint,
+    child: &mut Child,
+    stderr: &StderrTail,
+    timeouts: &AdapterTimeouts,
+) -> Result<AdapterStream> {
+    wait_for_endpoint(adapter, endpoint, child, stderr, timeouts)
+        .await
+        .map_err(Error::from)
+}
+
+/// A socket-mode adapter process and the connection to it
+#[derive(Debug)]
+pub struct SpawnedAdapter {
+    pub child: Child,
+    pub stream: AdapterStream,
+    pub endpoint: AdapterEndpoint,
+}
+
+/// Spawn a socket-mode adapter and connect to it, moving to a new endpoint
+/// when it cannot be reached on the one it was given
+///
+/// `next_endpoint` picks where the adapter listens and `spawn` starts it
+/// there, with stderr piped. Between picking a free port and the adapter
+/// binding it another process can take the port; the adapter then exits or
+/// never accepts. That attempt's adapter is killed and, up to
+/// [`ENDPOINT_ATTEMPTS`] times, a new one is spawned on a new endpoint.
+/// Failing to spawn, or a connection error other than "refused", ends the
+/// attempts at once.
+pub async fn spawn_socket_adapter<N, S>(
+    adapter: &str,
+    timeouts: &AdapterTimeouts,
+    mut next_endpoint: N,
+    mut spawn: S,
+) -> Result<SpawnedAdapter>
+where
+    N: FnMut() -> Result<AdapterEndpoint>,
+    S: FnMut(&AdapterEndpoint) -> Result<Child>,
+{
+    let mut attempt = 1;
+    loop {
+        let endpoint = next_endpoint()?;
+        let mut child = spawn(&endpoint)?;
+        let stderr = StderrTail::capture(adapter, &mut child);
+        match wait_for_endpoint(adapter, &endpoint, &mut child, &stderr, timeouts).await {
+            Ok(stream) => {
+                return Ok(SpawnedAdapter {
+                    child,
+                    stream,
+                    endpoint,
+                })
+            }
+            Err(failure) if failure.try_another_endpoint && attempt < ENDPOINT_ATTEMPTS => {
+                warn!(
+                    "🔁 {} not reachable on {} (attempt {}/{}), retrying on a new endpoint",
+                    adapter, endpoint, attempt, ENDPOINT_ATTEMPTS
+                );
+                debug!("{}", failure.message);
+                let _ = child.kill().await;
+                attempt += 1;
+            }
+            Err(mut failure) => {
+                let _ = child.start_kill();
+                if attempt > 1 {
+                    failure.message.push_str(&format!(
+                        "\nGave up after {} attempts, each on a new endpoint",
+                        attempt
+                    ));
+                }
+                return Err(failure.into());
             }
         }
     }
 }
 
+/// [`spawn_socket_adapter`] for adapters that only listen on TCP: `spawn`
+/// gets a free port; returns the adapter, the connection and the port
+pub async fn spawn_tcp_adapter<S>(
+    adapter: &str,
+    timeouts: &AdapterTimeouts,
+    mut spawn: S,
+) -> Result<(Child, TcpStream, u16)>
+where
+    S: FnMut(u16) -> Result<Child>,
+{
+    let spawned = spawn_socket_adapter(
+        adapter,
+        timeouts,
+        || Ok(AdapterEndpoint::Tcp(find_free_port()?)),
+        |endpoint| spawn(endpoint.port().unwrap_or_default()),
+    )
+    .await?;
+    match (spawned.stream, spawned.endpoint) {
+        (AdapterStream::Tcp(stream), AdapterEndpoint::Tcp(port)) => {
+            Ok((spawned.child, stream, port))
+        }
+        #[cfg(unix)]
+        _ => unreachable!("only TCP endpoints were handed out"),
+    }
+}
+
+/// Why [`wait_for_adapter`] gave up
+#[derive(Debug)]
+struct ConnectFailure {
+    message: String,
+    /// The adapter exited or never started listening, so it may have lost
+    /// its port; another endpoint can help
+    try_another_endpoint: bool,
+}
+
+impl From<ConnectFailure> for Error {
+    fn from(failure: ConnectFailure) -> Self {
+        Error::Process(failure.message)
+    }
+}
+
+async fn wait_for_endpoint(
+    adapter: &str,
+    endpoint: &AdapterEndpoint,
+    child: &mut Child,
+    stderr: &StderrTail,
+    timeouts: &AdapterTimeouts,
+) -> std::result::Result<AdapterStream, ConnectFailure> {
+    match endpoint {
+        AdapterEndpoint::Tcp(port) => {
+            let place = format!("port {}", port);
+            wait_for_adapter(adapter, &place, child, stderr, timeouts, || {
+                TcpStream::connect(("127.0.0.1", *port))
+            })
+            .await
+            .map(AdapterStream::Tcp)
+        }
+        #[cfg(unix)]
+        AdapterEndpoint::Unix(path) => {
+            let place = format!("unix socket {}", path.display());
+            wait_for_adapter(adapter, &place, child, stderr, timeouts, || {
+                UnixStream::connect(path)
+            })
+            .await
+            .map(AdapterStream::Unix)
+        }
+    }
+}
+
+/// Retry `connect` while nothing listens yet, until it succeeds, the adapter
+/// exits or the connect timeout passes; `place` names where the adapter
+/// listens
+async fn wait_for_adapter<S, F, Fut>(
+    adapter: &str,
+    place: &str,
+    child: &mut Child,
+    stderr: &StderrTail,
+    timeouts: &AdapterTimeouts,
+    connect: F,
+) -> std::result::Result<S, ConnectFailure>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = std::io::Result<S>>,
+{
+    let start = Instant::now();
+
+    info!(
+        "Connecting to {} on {} (timeout: {:?})",
+        adapter, place, timeouts.connect
+    );
+
+    let (error, exit_status) = loop {
+        match connect().await {
+            Ok(stream) => {
+                info!(
+                    "Connected to {} on {} after {:?}",
+                    adapter,
+                    place,
+                    start.elapsed()
+                );
+                return Ok(stream);
+            }
+            Err(e) => {
+                let exit_status = child.try_wait().ok().flatten();
+                if exit_status.is_some()
+                    || !adapter_not_listening_yet(&e)
+                    || start.elapsed() >= timeouts.connect
+                {
+                    break (e, exit_status);
+                }
+                tokio::time::sleep(timeouts.retry_interval).await;
+            }
+        }
+    };
+
+    let try_another_endpoint = exit_status.is_some() || adapter_not_listening_yet(&error);
+    let state = match exit_status {
+        Some(status) => format!("{} exited with {}", adapter, status),
+        None => format!("{} is still running", adapter),
+    };
+    let mut message = format!(
+        "Failed to connect to {} on {} after waiting {:?} (timeout {:?}): {}; {}",
+        adapter,
+        place,
+        start.elapsed(),
+        timeouts.connect,
+        error,
+        state
+    );
+    // Give the reader a moment to collect what the adapter printed before exiting
+    if exit_status.is_some() {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    let tail = stderr.text();
+    if !tail.is_empty() {
+        message.push_str(&format!("\n{} stderr:\n{}", adapter, tail));
+    }
+    Err(ConnectFailure {
+        message,
+        try_another_endpoint,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tokio::net::TcpListener;
 
+    #[test]
+    fn test_language_timeouts() {
+        assert_eq!(
+            AdapterTimeouts::for_language("python"),
+            AdapterTimeouts::default()
+        );
+        for language in ["nodejs", "rust"] {
+            let timeouts = AdapterTimeouts::for_language(language);
+            assert_eq!(timeouts.initialize, Duration::from_secs(15));
+            assert_eq!(timeouts.connect, AdapterTimeouts::default().connect);
+        }
+    }
+
     #[test]
     fn test_find_free_port() {
         let port = find_free_port().unwrap();
@@ -72,6 +696,155 @@ mod tests {
         assert_ne!(port1, port2);
     }
 
+    #[test]
+    fn test_port_range() {
+        let range: PortRange = "40000-40100".parse().unwrap();
+        assert_eq!((range.first, range.last, range.len()), (40000, 40100, 101));
+        assert_eq!(range.to_string(), "40000-40100");
+        assert!(range.contains(40100) && !range.contains(40101));
+        assert_eq!("40000".parse::<PortRange>().unwrap().len(), 1);
+        for bad in ["40100-40000", "0-10", "a-b", "40000-70000", ""] {
+            assert!(bad.parse::<PortRange>().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_find_free_port_in_skips_occupied_ports() {
+        let occupied = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = occupied.local_addr().unwrap().port();
+
+        // The only port in the range is taken
+        let only = PortRange {
+            first: port,
+            last: port,
+        };
+        let err = find_free_port_in(only).unwrap_err();
+        assert!(err.to_string().contains("No free port"), "{}", err);
+
+        // Next to the taken port there is room
+        let range = PortRange {
+            first: port.saturating_sub(5).max(1),
+            last: port.saturating_add(5),
+        };
+        for _ in 0..3 {
+            let found = find_free_port_in(range).unwrap();
+            assert!(range.contains(found) && found != port, "{}", found);
+        }
+    }
+
+    #[test]
+    fn test_only_refused_connections_are_retried() {
+        use std::io::{Error as IoError, ErrorKind};
+        assert!(adapter_not_listening_yet(&IoError::from(
+            ErrorKind::ConnectionRefused
+        )));
+        assert!(adapter_not_listening_yet(&IoError::from(
+            ErrorKind::NotFound
+        )));
+        assert!(!adapter_not_listening_yet(&IoError::from(
+            ErrorKind::ConnectionReset
+        )));
+        assert!(!adapter_not_listening_yet(&IoError::from(
+            ErrorKind::PermissionDenied
+        )));
+    }
+
+    /// Stand-in for an adapter told to listen on `port`: exits like an
+    /// adapter that cannot bind when the port is taken
+    fn spawn_fake_adapter(port: u16) -> Result<Child> {
+        match std::net::TcpListener::bind(("127.0.0.1", port)) {
+            Ok(listener) => {
+                std::thread::spawn(move || {
+                    let _ = listener.accept();
+                });
+                Ok(tokio::process::Command::new("sleep")
+                    .arg("5")
+                    .kill_on_drop(true)
+                    .spawn()?)
+            }
+            Err(_) => Ok(tokio::process::Command::new("sh")
+                .args(["-c", "echo 'address already in use' >&2; exit 1"])
+                .stderr(std::process::Stdio::piped())
+                .spawn()?),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spawn_socket_adapter_retries_on_a_taken_port() {
+        // Another process grabs the port between picking it and the bind;
+        // a bound socket that never listens refuses connections
+        let taken = tokio::net::TcpSocket::new_v4().unwrap();
+        taken.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let taken_port = taken.local_addr().unwrap().port();
+
+        let mut ports = vec![taken_port];
+        let mut spawned_on = Vec::new();
+        let spawned = spawn_socket_adapter(
+            "fake",
+            &AdapterTimeouts::new(Duration::from_secs(5)),
+            || match ports.pop() {
+                Some(port) => Ok(AdapterEndpoint::Tcp(port)),
+                None => Ok(AdapterEndpoint::Tcp(find_free_port()?)),
+            },
+            |endpoint| {
+                let port = endpoint.port().unwrap();
+                spawned_on.push(port);
+                spawn_fake_adapter(port)
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(spawned_on.len(), 2);
+        assert_eq!(spawned_on[0], taken_port);
+        assert_eq!(spawned.endpoint, AdapterEndpoint::Tcp(spawned_on[1]));
+        assert!(matches!(spawned.stream, AdapterStream::Tcp(_)));
+    }
+
+    #[tokio::test]
+    async fn test_spawn_socket_adapter_gives_up_after_all_attempts() {
+        let taken = tokio::net::TcpSocket::new_v4().unwrap();
+        taken.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let taken_port = taken.local_addr().unwrap().port();
+
+        let mut attempts = 0;
+        let result = spawn_socket_adapter(
+            "fake",
+            &AdapterTimeouts::new(Duration::from_secs(5)),
+            || Ok(AdapterEndpoint::Tcp(taken_port)),
+            |endpoint| {
+                attempts += 1;
+                spawn_fake_adapter(endpoint.port().unwrap())
+            },
+        )
+        .await;
+
+        assert_eq!(attempts, ENDPOINT_ATTEMPTS);
+        match result {
+            Err(Error::Process(msg)) => {
+                assert!(msg.contains("fake exited with"), "{}", msg);
+                assert!(msg.contains("address already in use"), "{}", msg);
+                assert!(msg.contains("Gave up after 3 attempts"), "{}", msg);
+            }
+            other => panic!("Expected Process error, got {:?}", other),
+        }
+
+        // Failing to spawn is not worth another endpoint
+        let mut attempts = 0;
+        let result = spawn_socket_adapter(
+            "missing",
+            &AdapterTimeouts::default(),
+            || Ok(AdapterEndpoint::Tcp(taken_port)),
+            |_| {
+                attempts += 1;
+                Err(Error::Process("Failed to spawn missing".to_string()))
+            },
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+
     #[tokio::test]
     async fn test_connect_with_retry_success() {
         // Start a test server
@@ -107,6 +880,157 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_connect_to_adapter_waits_for_slow_listener() {
+        let port = find_free_port().unwrap();
+        let mut child = tokio::process::Command::new("sleep")
+            .arg("5")
+            .kill_on_drop(true)
+            .spawn()
+            .unwrap();
+
+        // Listener that only comes up after the default 3s connect timeout
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(3500)).await;
+            let listener = TcpListener::bind(("127.0.0.1", port)).await.unwrap();
+            let _ = listener.accept().await;
+        });
+
+        let timeouts = AdapterTimeouts {
+            connect: Duration::from_secs(6),
+            retry_interval: Duration::from_millis(250),
+            ..AdapterTimeouts::default()
+        };
+        let result =
+            connect_to_adapter("slow", port, &mut child, &StderrTail::default(), &timeouts).await;
+        assert!(result.is_ok(), "{:?}", result.err());
+    }
+
+    #[tokio::test]
+    async fn test_connect_to_adapter_reports_exited_child() {
+        let port = find_free_port().unwrap();
+        let mut child = tokio::process::Command::new("sh")
+            .args(["-c", "echo 'gem not found' >&2; exit 3"])
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stderr = StderrTail::capture("fake", &mut child);
+
+        let started = Instant::now();
+        let result = connect_to_adapter(
+            "fake",
+            port,
+            &mut child,
+            &stderr,
+            &AdapterTimeouts::new(Duration::from_secs(10)),
+        )
+        .await;
+
+        // Gives up as soon as the adapter exits instead of waiting out the timeout
+        assert!(started.elapsed() < Duration::from_secs(5));
+        match result {
+            Err(Error::Process(msg)) => {
+                assert!(msg.contains("after waiting"), "{}", msg);
+                assert!(msg.contains("fake exited with"), "{}", msg);
+                assert!(msg.contains("gem not found"), "{}", msg);
+            }
+            other => panic!("Expected Process error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connect_to_adapter_reports_running_child() {
+        let port = find_free_port().unwrap();
+        let mut child = tokio::process::Command::new("sleep")
+            .arg("5")
+            .kill_on_drop(true)
+            .spawn()
+            .unwrap();
+
+        let result = connect_to_adapter(
+            "stuck",
+            port,
+            &mut child,
+            &StderrTail::default(),
+            &AdapterTimeouts::new(Duration::from_millis(300)),
+        )
+        .await;
+
+        match result {
+            Err(Error::Process(msg)) => {
+                assert!(msg.contains("timeout 300ms"), "{}", msg);
+                assert!(msg.contains("stuck is still running"), "{}", msg);
+            }
+            other => panic!("Expected Process error, got {:?}", other),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_create_unix_socket_path() {
+        let first = create_unix_socket_path("rdbg").unwrap();
+        let second = create_unix_socket_path("rdbg").unwrap();
+        assert_ne!(first, second);
+        assert!(first.as_os_str().len() <= MAX_UNIX_SOCKET_PATH);
+        assert!(first.to_string_lossy().ends_with(".sock"));
+        assert!(matches!(
+            AdapterEndpoint::prefer_unix("rdbg").unwrap(),
+            AdapterEndpoint::Unix(_)
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_connect_to_adapter_endpoint_over_unix_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("adapter.sock");
+        let endpoint = AdapterEndpoint::Unix(path.clone());
+        assert_eq!(endpoint.to_string(), format!("unix:{}", path.display()));
+        assert_eq!(endpoint.port(), None);
+        let mut child = tokio::process::Command::new("sleep")
+            .arg("5")
+            .kill_on_drop(true)
+            .spawn()
+            .unwrap();
+
+        // The adapter creates its socket a moment after starting
+        let listening = path.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            let listener = tokio::net::UnixListener::bind(listening).unwrap();
+            let _ = listener.accept().await;
+        });
+
+        let stream = connect_to_adapter_endpoint(
+            "mock",
+            &endpoint,
+            &mut child,
+            &StderrTail::default(),
+            &AdapterTimeouts::new(Duration::from_secs(5)),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(stream, AdapterStream::Unix(_)));
+
+        // Nobody listening: the error names the socket
+        let missing = AdapterEndpoint::Unix(dir.path().join("missing.sock"));
+        match connect_to_adapter_endpoint(
+            "mock",
+            &missing,
+            &mut child,
+            &StderrTail::default(),
+            &AdapterTimeouts::new(Duration::from_millis(200)),
+        )
+        .await
+        {
+            Err(Error::Process(msg)) => {
+                assert!(msg.contains("unix socket"), "{}", msg);
+                assert!(msg.contains("missing.sock"), "{}", msg);
+            }
+            other => panic!("Expected Process error, got {:?}", other),
+        }
+    }
+
     #[tokio::test]
     async fn test_connect_with_retry_eventual_success() {
         let port = find_free_port().unwrap();
@@ -0,0 +1,737 @@
+//! In-process DAP adapter double for unit tests
+//!
+//! [`ScriptedAdapter`] answers the launch handshake the way real adapters do:
+//! `launch` triggers the 'initialized' event, and the launch response only
+//! arrives after configurationDone (unless [`ScriptedAdapter::answering_launch_early`]). A stopped program has a single `main`
+//! frame, and `evaluate` echoes the expression back as its result unless the
+//! expression was marked as failing, and `breakpointLocations` reports columns
+//! 1 and 10 of every requested line (deeper stacks with
+//! [`ScriptedAdapter::stack_depth`]). The frame has a cheap "Locals" scope
+//! holding `n = 15` and an expensive "Globals" scope. With [`ScriptedAdapter::stopping_on_resume`]
+//! every continue or step is followed by a 'stopped' event (a "data breakpoint" stop on continue
+//! once setDataBreakpoints was sent). [`ScriptedAdapter::with_registers`] adds a
+//! CodeLLDB-like "Registers" scope whose `rip` advances with every step. Every request command is appended to a
+//! shared trace so tests can assert on the order of the DAP sequence.
+//!
+//! [`MockTransport`] is the expectation-based double: its read and write
+//! halves are separate mocks, so a split client never serializes the two.
+
+use super::transport_trait::{DapReader, DapTransportTrait, DapWriter};
+use super::types::*;
+use crate::{Error, Result};
+use mockall::mock;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Shared, ordered record of what happened during a test
+pub(crate) type Trace = Arc<Mutex<Vec<String>>>;
+
+pub(crate) struct ScriptedAdapter {
+    trace: Trace,
+    requests: Arc<Mutex<Vec<Request>>>,
+    unverified_lines: HashSet<i32>,
+    late_lines: HashSet<i32>,
+    moved_lines: HashMap<i32, i32>,
+    failing_expressions: HashSet<String>,
+    failing_commands: HashSet<String>,
+    ignored_commands: HashSet<String>,
+    capabilities: Value,
+    frame_source: Option<String>,
+    loaded_sources: Vec<String>,
+    stack_depth: usize,
+    frame_hints: HashMap<usize, String>,
+    stop_on_resume: bool,
+    registers: bool,
+    early_launch_response: bool,
+    withheld_launch_response: bool,
+    launch_failure: Option<String>,
+    launch_events: Vec<(String, Value)>,
+    delayed_launch_events: Vec<(String, Value, Duration)>,
+    silent: Arc<AtomicBool>,
+}
+
+impl ScriptedAdapter {
+    pub(crate) fn new() -> Self {
+        Self {
+            trace: Arc::new(Mutex::new(Vec::new())),
+            requests: Arc::new(Mutex::new(Vec::new())),
+            unverified_lines: HashSet::new(),
+            late_lines: HashSet::new(),
+            moved_lines: HashMap::new(),
+            failing_expressions: HashSet::new(),
+            failing_commands: HashSet::new(),
+            ignored_commands: HashSet::new(),
+            capabilities: json!({"supportsConfigurationDoneRequest": true}),
+            frame_source: None,
+            loaded_sources: Vec::new(),
+            stack_depth: 1,
+            frame_hints: HashMap::new(),
+            stop_on_resume: false,
+            registers: false,
+            early_launch_response: false,
+            withheld_launch_response: false,
+            launch_failure: None,
+            launch_events: Vec::new(),
+            delayed_launch_events: Vec::new(),
+            silent: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Answer launch/attach right away, before 'initialized'
+    pub(crate) fn answering_launch_early(mut self) -> Self {
+        self.early_launch_response = true;
+        self
+    }
+
+    /// Never answer launch/attach, not even after configurationDone
+    pub(crate) fn withholding_launch_response(mut self) -> Self {
+        self.withheld_launch_response = true;
+        self
+    }
+
+    /// Record `command` requests without reacting to them (an ignored
+    /// launch sends no 'initialized' either)
+    pub(crate) fn ignoring(mut self, command: &str) -> Self {
+        self.ignored_commands.insert(command.to_string());
+        self
+    }
+
+    /// Answer launch/attach with a failure (an early failure sends no 'initialized')
+    pub(crate) fn failing_launch(mut self, message: &str) -> Self {
+        self.launch_failure = Some(message.to_string());
+        self
+    }
+
+    /// Send `event` with `body` right before the deferred launch/attach
+    /// response (where debugpy sends 'process')
+    pub(crate) fn emitting_on_launch(mut self, event: &str, body: Value) -> Self {
+        self.launch_events.push((event.to_string(), body));
+        self
+    }
+
+    /// Send `event` with `body` `delay` after the deferred launch/attach
+    /// response (a stop that trails the adapter's other launch events)
+    pub(crate) fn emitting_after_launch(
+        mut self,
+        event: &str,
+        body: Value,
+        delay: Duration,
+    ) -> Self {
+        self.delayed_launch_events
+            .push((event.to_string(), body, delay));
+        self
+    }
+
+    /// Stop again right after every continue/step response ("breakpoint"
+    /// after continue, "step" after a step)
+    pub(crate) fn stopping_on_resume(mut self) -> Self {
+        self.stop_on_resume = true;
+        self
+    }
+
+    /// Add a "Registers" scope (reference 102) with a general purpose group
+    /// (103) holding `rip` and `rsp`, and a floating point group (104);
+    /// `rip` moves 4 bytes with every step
+    pub(crate) fn with_registers(mut self) -> Self {
+        self.registers = true;
+        self
+    }
+
+    /// Advertise `capability` (e.g. "supportsLogPoints") in the initialize response
+    pub(crate) fn with_capability(mut self, capability: &str) -> Self {
+        self.capabilities[capability] = json!(true);
+        self
+    }
+
+    /// Answer `evaluate` for `expression` with a failure response
+    pub(crate) fn failing_expression(mut self, expression: &str) -> Self {
+        self.failing_expressions.insert(expression.to_string());
+        self
+    }
+
+    /// Answer every `command` request (e.g. "scopes") with a failure
+    pub(crate) fn failing_command(mut self, command: &str) -> Self {
+        self.failing_commands.insert(command.to_string());
+        self
+    }
+
+    /// Report breakpoints on `line` as unverified
+    pub(crate) fn rejecting_line(mut self, line: i32) -> Self {
+        self.unverified_lines.insert(line);
+        self
+    }
+
+    /// Report breakpoints on `line` as unverified, then bind them to the
+    /// next line with a 'breakpoint' event (like a lazily loaded module)
+    pub(crate) fn binding_late(mut self, line: i32) -> Self {
+        self.late_lines.insert(line);
+        self
+    }
+
+    /// Bind breakpoints requested on `line` to `to` instead, like an adapter
+    /// moving them off a blank line
+    pub(crate) fn moving_line(mut self, line: i32, to: i32) -> Self {
+        self.moved_lines.insert(line, to);
+        self
+    }
+
+    /// Report `path` as the source of the `main` frame
+    pub(crate) fn frame_source(mut self, path: &str) -> Self {
+        self.frame_source = Some(path.to_string());
+        self
+    }
+
+    /// Announce `path` in a 'loadedSource' event after configurationDone,
+    /// and list it in `loadedSources` responses
+    pub(crate) fn loading_source(mut self, path: &str) -> Self {
+        self.loaded_sources.push(path.to_string());
+        self
+    }
+
+    /// Report a stack of `depth` frames: `main` on top, then `caller_1`,
+    /// `caller_2`, ... honoring startFrame and levels
+    pub(crate) fn stack_depth(mut self, depth: usize) -> Self {
+        self.stack_depth = depth;
+        self
+    }
+
+    /// Switch that, once set, makes the adapter hang: requests are still
+    /// recorded but never answered
+    pub(crate) fn silence_switch(&self) -> Arc<AtomicBool> {
+        self.silent.clone()
+    }
+
+    /// Mark the frame at `index` (0 is `main`) with a presentationHint
+    /// ("label" or "subtle"), from source origin "library"
+    pub(crate) fn frame_hint(mut self, index: usize, hint: &str) -> Self {
+        self.frame_hints.insert(index, hint.to_string());
+        self
+    }
+
+    /// Command names of every request received, in order
+    pub(crate) fn trace(&self) -> Trace {
+        self.trace.clone()
+    }
+
+    /// Every request received, in order
+    pub(crate) fn requests(&self) -> Arc<Mutex<Vec<Request>>> {
+        self.requests.clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl DapTransportTrait for ScriptedAdapter {
+    async fn read_message(&mut self) -> Result<Message> {
+        Err(Error::Dap("ScriptedAdapter must be split".to_string()))
+    }
+
+    async fn write_message(&mut self, _msg: &Message) -> Result<()> {
+        Err(Error::Dap("ScriptedAdapter must be split".to_string()))
+    }
+
+    fn split(self: Box<Self>) -> (Box<dyn DapReader>, Box<dyn DapWriter>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (
+            Box::new(ScriptedReader { rx }),
+            Box::new(ScriptedWriter {
+                tx,
+                seq: 1,
+                launch_seq: None,
+                data_breakpoints: Vec::new(),
+                steps: 0,
+                line: 1,
+                adapter: *self,
+            }),
+        )
+    }
+}
+
+mock! {
+    pub(crate) Reader {}
+
+    #[async_trait::async_trait]
+    impl DapReader for Reader {
+        async fn read_message(&mut self) -> Result<Message>;
+    }
+}
+
+mock! {
+    pub(crate) Writer {}
+
+    #[async_trait::async_trait]
+    impl DapWriter for Writer {
+        async fn write_message(&mut self, msg: &Message) -> Result<()>;
+    }
+}
+
+/// Transport with independently mocked read and write halves
+pub(crate) struct MockTransport {
+    pub(crate) reader: MockReader,
+    pub(crate) writer: MockWriter,
+}
+
+impl MockTransport {
+    pub(crate) fn new() -> Self {
+        Self {
+            reader: MockReader::new(),
+            writer: MockWriter::new(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl DapTransportTrait for MockTransport {
+    async fn read_message(&mut self) -> Result<Message> {
+        self.reader.read_message().await
+    }
+
+    async fn write_message(&mut self, msg: &Message) -> Result<()> {
+        self.writer.write_message(msg).await
+    }
+
+    fn split(self: Box<Self>) -> (Box<dyn DapReader>, Box<dyn DapWriter>) {
+        (Box::new(self.reader), Box::new(self.writer))
+    }
+}
+
+struct ScriptedReader {
+    rx: mpsc::UnboundedReceiver<Message>,
+}
+
+#[async_trait::async_trait]
+impl DapReader for ScriptedReader {
+    async fn read_message(&mut self) -> Result<Message> {
+        self.rx
+            .recv()
+            .await
+            .ok_or_else(|| Error::Dap("Connection closed".to_string()))
+    }
+}
+
+struct ScriptedWriter {
+    tx: mpsc::UnboundedSender<Message>,
+    seq: i32,
+    /// Pending launch or attach request, answered after configurationDone
+    launch_seq: Option<(i32, String)>,
+    /// dataIds of the data breakpoints last set, in order (IDs 1000, 1001, ...)
+    data_breakpoints: Vec<String>,
+    /// next/stepIn/stepOut requests received
+    steps: u64,
+    /// Line of the top frame, moved by goto
+    line: i64,
+    adapter: ScriptedAdapter,
+}
+
+impl ScriptedWriter {
+    fn respond(&mut self, request_seq: i32, command: &str, body: Option<Value>) {
+        let seq = self.next_seq();
+        let _ = self.tx.send(Message::Response(Response {
+            seq,
+            request_seq,
+            command: command.to_string(),
+            success: true,
+            message: None,
+            body,
+        }));
+    }
+
+    fn fail(&mut self, request_seq: i32, command: &str, message: &str) {
+        let seq = self.next_seq();
+        let _ = self.tx.send(Message::Response(Response {
+            seq,
+            request_seq,
+            command: command.to_string(),
+            success: false,
+            message: Some(message.to_string()),
+            body: None,
+        }));
+    }
+
+    fn emit(&mut self, event: &str, body: Option<Value>) {
+        let seq = self.next_seq();
+        let _ = self.tx.send(Message::Event(Event {
+            seq,
+            event: event.to_string(),
+            body,
+        }));
+    }
+
+    fn next_seq(&mut self) -> i32 {
+        self.seq += 1;
+        self.seq - 1
+    }
+
+    fn requested_lines(arguments: &Option<Value>) -> Vec<i32> {
+        arguments
+            .as_ref()
+            .and_then(|args| args.get("breakpoints"))
+            .and_then(|bps| bps.as_array())
+            .map(|bps| {
+                bps.iter()
+                    .filter_map(|bp| bp.get("line").and_then(|l| l.as_i64()))
+                    .map(|l| l as i32)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn set_breakpoints_body(&self, arguments: &Option<Value>) -> Value {
+        let breakpoints: Vec<Value> = Self::requested_lines(arguments)
+            .iter()
+            .map(|line| {
+                let message = if self.adapter.unverified_lines.contains(line) {
+                    "Breakpoint in file that does not exist"
+                } else if self.adapter.late_lines.contains(line) {
+                    "Unbound breakpoint"
+                } else {
+                    let bound = self.adapter.moved_lines.get(line).unwrap_or(line);
+                    return json!({"id": line, "verified": true, "line": bound});
+                };
+                json!({"id": line, "verified": false, "line": line, "message": message})
+            })
+            .collect();
+        json!({ "breakpoints": breakpoints })
+    }
+}
+
+#[async_trait::async_trait]
+impl DapWriter for ScriptedWriter {
+    async fn write_message(&mut self, msg: &Message) -> Result<()> {
+        let Message::Request(req) = msg else {
+            return Ok(());
+        };
+        self.adapter.trace.lock().unwrap().push(req.command.clone());
+        self.adapter.requests.lock().unwrap().push(req.clone());
+        if self.adapter.silent.load(Ordering::SeqCst)
+            || self.adapter.ignored_commands.contains(&req.command)
+        {
+            return Ok(());
+        }
+
+        if self.adapter.failing_commands.contains(&req.command) {
+            let message = format!("{} is not available", req.command);
+            self.fail(req.seq, &req.command, &message);
+            return Ok(());
+        }
+
+        match req.command.as_str() {
+            "initialize" => {
+                let capabilities = self.adapter.capabilities.clone();
+                self.respond(req.seq, "initialize", Some(capabilities));
+            }
+            "launch" | "attach" if self.adapter.early_launch_response => {
+                match self.adapter.launch_failure.clone() {
+                    Some(message) => self.fail(req.seq, &req.command, &message),
+                    None => {
+                        self.respond(req.seq, &req.command, None);
+                        self.emit("initialized", None);
+                    }
+                }
+            }
+            "launch" | "attach" => {
+                self.launch_seq = Some((req.seq, req.command.clone()));
+                self.emit("initialized", None);
+            }
+            "setBreakpoints" => {
+                let body = self.set_breakpoints_body(&req.arguments);
+                self.respond(req.seq, "setBreakpoints", Some(body));
+                for line in Self::requested_lines(&req.arguments) {
+                    if self.adapter.late_lines.contains(&line) {
+                        self.emit(
+                            "breakpoint",
+                            Some(json!({
+                                "reason": "changed",
+                                "breakpoint": {"id": line, "verified": true, "line": line + 1}
+                            })),
+                        );
+                    }
+                }
+            }
+            "breakpointLocations" => {
+                let args = req.arguments.clone().unwrap_or_default();
+                let line = args["line"].as_i64().unwrap_or(1);
+                let end_line = args["endLine"].as_i64().unwrap_or(line);
+                let locations: Vec<Value> = (line..=end_line)
+                    .flat_map(|l| {
+                        [
+                            json!({"line": l, "column": 1}),
+                            json!({"line": l, "column": 10}),
+                        ]
+                    })
+                    .collect();
+                self.respond(
+                    req.seq,
+                    "breakpointLocations",
+                    Some(json!({ "breakpoints": locations })),
+                );
+            }
+            "stackTrace" => {
+                let args = req.arguments.clone().unwrap_or_default();
+                let depth = self.adapter.stack_depth;
+                let start = (args["startFrame"].as_u64().unwrap_or(0) as usize).min(depth);
+                let levels = match args["levels"].as_u64() {
+                    Some(levels) if levels > 0 => levels as usize,
+                    _ => depth,
+                };
+                let frames: Vec<Value> = (start..depth.min(start + levels))
+                    .map(|index| {
+                        let name = match index {
+                            0 => "main".to_string(),
+                            index => format!("caller_{}", index),
+                        };
+                        let line = if index == 0 { self.line } else { 1 };
+                        let mut frame =
+                            json!({"id": index + 1, "name": name, "line": line, "column": 0});
+                        if let Some(path) = &self.adapter.frame_source {
+                            frame["source"] = json!({ "path": path });
+                        }
+                        if let Some(hint) = self.adapter.frame_hints.get(&index) {
+                            frame["presentationHint"] = json!(hint);
+                            frame["source"] = json!({"name": "runpy.py", "origin": "library"});
+                        }
+                        frame
+                    })
+                    .collect();
+                self.respond(
+                    req.seq,
+                    "stackTrace",
+                    Some(json!({ "stackFrames": frames, "totalFrames": depth })),
+                );
+            }
+            "scopes" => {
+                let mut scopes = json!([
+                    {"name": "Locals", "variablesReference": 100, "expensive": false},
+                    {"name": "Globals", "variablesReference": 101, "expensive": true}
+                ]);
+                if self.adapter.registers {
+                    scopes.as_array_mut().unwrap().push(json!({
+                        "name": "Registers",
+                        "presentationHint": "registers",
+                        "variablesReference": 102,
+                        "expensive": false
+                    }));
+                }
+                self.respond(req.seq, "scopes", Some(json!({ "scopes": scopes })));
+            }
+            "variables" => {
+                let reference = req
+                    .arguments
+                    .as_ref()
+                    .and_then(|args| args["variablesReference"].as_i64());
+                let rip = format!("0x{:016x}", 0x401000 + 4 * self.steps);
+                let variables = match reference {
+                    Some(102) if self.adapter.registers => json!([
+                        {"name": "General Purpose Registers", "value": "", "variablesReference": 103},
+                        {"name": "Floating Point Registers", "value": "", "variablesReference": 104}
+                    ]),
+                    Some(103) if self.adapter.registers => json!([
+                        {"name": "rip", "value": rip, "variablesReference": 0},
+                        {"name": "rsp", "value": "0x00007fffffffe000", "variablesReference": 0}
+                    ]),
+                    Some(104) if self.adapter.registers => json!([
+                        {"name": "xmm0", "value": "{0x00 x 16}", "variablesReference": 0}
+                    ]),
+                    _ => json!([
+                        {"name": "n", "value": "15", "type": "int", "variablesReference": 0}
+                    ]),
+                };
+                self.respond(
+                    req.seq,
+                    "variables",
+                    Some(json!({ "variables": variables })),
+                );
+            }
+            "evaluate" => {
+                let expression = req
+                    .arguments
+                    .as_ref()
+                    .and_then(|args| args.get("expression"))
+                    .and_then(|e| e.as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                if self.adapter.failing_expressions.contains(&expression) {
+                    self.fail(
+                        req.seq,
+                        "evaluate",
+                        &format!("name '{}' is not defined", expression),
+                    );
+                } else {
+                    self.respond(req.seq, "evaluate", Some(json!({"result": expression})));
+                }
+            }
+            "completions" => {
+                // Names in scope that start with the text
+                let text = req
+                    .arguments
+                    .as_ref()
+                    .and_then(|args| args.get("text"))
+                    .and_then(|t| t.as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                let targets: Vec<Value> = [("fizzbuzz", "function"), ("filter", "class")]
+                    .into_iter()
+                    .filter(|(label, _)| label.starts_with(&text))
+                    .map(|(label, type_)| json!({"label": label, "type": type_}))
+                    .collect();
+                self.respond(req.seq, "completions", Some(json!({ "targets": targets })));
+            }
+            "gotoTargets" => {
+                // One target per line, identified by the line number
+                let line = req
+                    .arguments
+                    .as_ref()
+                    .and_then(|args| args["line"].as_i64())
+                    .unwrap_or(1);
+                self.respond(
+                    req.seq,
+                    "gotoTargets",
+                    Some(json!({"targets": [
+                        {"id": line, "label": format!("line {}", line), "line": line}
+                    ]})),
+                );
+            }
+            "goto" => {
+                self.line = req
+                    .arguments
+                    .as_ref()
+                    .and_then(|args| args["targetId"].as_i64())
+                    .unwrap_or(1);
+                self.respond(req.seq, "goto", None);
+                self.emit("stopped", Some(json!({"reason": "goto", "threadId": 1})));
+            }
+            "stepInTargets" => {
+                // As for a line like `foo(bar(), baz())`
+                self.respond(
+                    req.seq,
+                    "stepInTargets",
+                    Some(json!({"targets": [
+                        {"id": 1, "label": "bar", "line": 1, "column": 5},
+                        {"id": 2, "label": "baz", "line": 1, "column": 12}
+                    ]})),
+                );
+            }
+            "loadedSources" => {
+                let sources: Vec<Value> = self
+                    .adapter
+                    .loaded_sources
+                    .iter()
+                    .map(|path| json!({ "path": path }))
+                    .collect();
+                self.respond(
+                    req.seq,
+                    "loadedSources",
+                    Some(json!({ "sources": sources })),
+                );
+            }
+            "dataBreakpointInfo" => {
+                // dataId "<variablesReference>/<name>", or "<name>" for expressions
+                let args = req.arguments.clone().unwrap_or_default();
+                let name = args["name"].as_str().unwrap_or_default().to_string();
+                let data_id = match args["variablesReference"].as_i64() {
+                    Some(reference) => format!("{}/{}", reference, name),
+                    None => name.clone(),
+                };
+                self.respond(
+                    req.seq,
+                    "dataBreakpointInfo",
+                    Some(json!({
+                        "dataId": data_id,
+                        "description": format!("4 bytes of {}", name),
+                        "accessTypes": ["read", "write", "readWrite"]
+                    })),
+                );
+            }
+            "setDataBreakpoints" => {
+                let args = req.arguments.clone().unwrap_or_default();
+                self.data_breakpoints = args["breakpoints"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|bp| bp["dataId"].as_str().map(str::to_string))
+                    .collect();
+                let breakpoints: Vec<Value> = (0..self.data_breakpoints.len())
+                    .map(|i| json!({"id": 1000 + i, "verified": true}))
+                    .collect();
+                self.respond(
+                    req.seq,
+                    "setDataBreakpoints",
+                    Some(json!({ "breakpoints": breakpoints })),
+                );
+            }
+            "continue" if self.adapter.stop_on_resume && !self.data_breakpoints.is_empty() => {
+                self.respond(req.seq, "continue", None);
+                let description = format!("Value of {} changed", self.data_breakpoints[0]);
+                self.emit(
+                    "stopped",
+                    Some(json!({
+                        "reason": "data breakpoint",
+                        "description": description,
+                        "threadId": 1,
+                        "hitBreakpointIds": [1000]
+                    })),
+                );
+            }
+            "configurationDone" => {
+                self.respond(req.seq, "configurationDone", None);
+                for path in self.adapter.loaded_sources.clone() {
+                    self.emit(
+                        "loadedSource",
+                        Some(json!({"reason": "new", "source": { "path": path }})),
+                    );
+                }
+                let launch = self.launch_seq.take();
+                if let Some((launch_seq, command)) =
+                    launch.filter(|_| !self.adapter.withheld_launch_response)
+                {
+                    for (event, body) in self.adapter.launch_events.clone() {
+                        self.emit(&event, Some(body));
+                    }
+                    match self.adapter.launch_failure.clone() {
+                        Some(message) => self.fail(launch_seq, &command, &message),
+                        None => self.respond(launch_seq, &command, None),
+                    }
+                    for (event, body, delay) in self.adapter.delayed_launch_events.clone() {
+                        let seq = self.next_seq();
+                        let tx = self.tx.clone();
+                        tokio::spawn(async move {
+                            tokio::time::sleep(delay).await;
+                            let _ = tx.send(Message::Event(Event {
+                                seq,
+                                event,
+                                body: Some(body),
+                            }));
+                        });
+                    }
+                }
+            }
+            "continue" | "next" | "stepIn" | "stepOut" | "restartFrame"
+                if self.adapter.stop_on_resume =>
+            {
+                if req.command != "continue" {
+                    self.steps += 1;
+                }
+                let reason = match req.command.as_str() {
+                    "continue" => "breakpoint",
+                    "restartFrame" => "restart",
+                    _ => "step",
+                };
+                self.respond(req.seq, &req.command, None);
+                self.emit("stopped", Some(json!({"reason": reason, "threadId": 1})));
+            }
+            "terminate" => {
+                self.respond(req.seq, "terminate", None);
+                self.emit("exited", Some(json!({"exitCode": 0})));
+                self.emit("terminated", None);
+            }
+            command => {
+                let command = command.to_string();
+                self.respond(req.seq, &command, None);
+            }
+        }
+        Ok(())
+    }
+}
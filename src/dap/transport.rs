@@ -1,13 +1,24 @@
-use super::transport_trait::DapTransportTrait;
+use super::transport_trait::{DapReader, DapTransportTrait, DapWriter};
 use super::types::Message;
 use crate::{Error, Result};
 use async_trait::async_trait;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader,
+};
 use tokio::net::TcpStream;
+#[cfg(unix)]
+use tokio::net::UnixStream;
 use tokio::process::{ChildStdin, ChildStdout};
 use tracing::{debug, trace};
 
-/// DAP Transport - supports both STDIO and TCP socket
+/// Largest message body accepted, so a corrupt Content-Length cannot make
+/// the reader allocate unbounded memory
+pub const MAX_CONTENT_LENGTH: usize = 16 * 1024 * 1024;
+
+/// Largest header block accepted (DAP only sends Content-Length)
+const MAX_HEADER_BYTES: usize = 8 * 1024;
+
+/// DAP Transport - supports STDIO, TCP and unix domain sockets
 pub enum DapTransport {
     /// STDIO transport (used by Python/debugpy)
     Stdio {
@@ -16,6 +27,9 @@ pub enum DapTransport {
     },
     /// TCP socket transport (used by Ruby/rdbg)
     Socket { stream: BufReader<TcpStream> },
+    /// Unix domain socket transport (rdbg, where available)
+    #[cfg(unix)]
+    UnixSocket { stream: BufReader<UnixStream> },
 }
 
 impl DapTransport {
@@ -34,86 +48,153 @@ impl DapTransport {
         }
     }
 
+    /// Create a new unix domain socket transport
+    #[cfg(unix)]
+    pub fn new_unix_socket(stream: UnixStream) -> Self {
+        Self::UnixSocket {
+            stream: BufReader::new(stream),
+        }
+    }
+
     pub async fn read_message(&mut self) -> Result<Message> {
         // Read from either stdio or socket
-        let (_headers, content) = match self {
-            Self::Stdio { stdout, .. } => Self::read_from_stream(stdout).await?,
-            Self::Socket { stream } => Self::read_from_stream(stream).await?,
-        };
+        match self {
+            Self::Stdio { stdout, .. } => read_framed(stdout).await,
+            Self::Socket { stream } => read_framed(stream).await,
+            #[cfg(unix)]
+            Self::UnixSocket { stream } => read_framed(stream).await,
+        }
+    }
+
+    pub async fn write_message(&mut self, msg: &Message) -> Result<()> {
+        // Write to either stdio or socket
+        match self {
+            Self::Stdio { stdin, .. } => write_framed(stdin, msg).await,
+            Self::Socket { stream } => write_framed(stream.get_mut(), msg).await,
+            #[cfg(unix)]
+            Self::UnixSocket { stream } => write_framed(stream.get_mut(), msg).await,
+        }
+    }
+}
 
-        debug!("DAP received: {}", content);
+/// Read half of a split [`DapTransport`]
+pub struct DapReadHalf<R> {
+    reader: R,
+}
 
-        let msg: Message = serde_json::from_str(&content)
-            .map_err(|e| Error::Dap(format!("Failed to parse DAP message: {}", e)))?;
+/// Write half of a split [`DapTransport`]
+pub struct DapWriteHalf<W> {
+    writer: W,
+}
 
-        Ok(msg)
+#[async_trait]
+impl<R: AsyncBufRead + Unpin + Send> DapReader for DapReadHalf<R> {
+    async fn read_message(&mut self) -> Result<Message> {
+        read_framed(&mut self.reader).await
     }
+}
 
-    /// Helper to read DAP message from any async reader
-    async fn read_from_stream<R: AsyncBufReadExt + tokio::io::AsyncRead + Unpin>(
-        reader: &mut R,
-    ) -> Result<(String, String)> {
-        // Read Content-Length header
-        let mut headers = String::new();
-        loop {
-            let mut line = String::new();
-            reader.read_line(&mut line).await?;
+#[async_trait]
+impl<W: AsyncWrite + Unpin + Send> DapWriter for DapWriteHalf<W> {
+    async fn write_message(&mut self, msg: &Message) -> Result<()> {
+        write_framed(&mut self.writer, msg).await
+    }
+}
+
+/// Read and parse one Content-Length framed DAP message
+async fn read_framed<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Message> {
+    let (_headers, content) = read_from_stream(reader).await?;
 
-            if line == "\r\n" || line == "\n" {
-                break;
+    debug!("DAP received: {}", content);
+
+    let msg: Message = serde_json::from_str(&content)
+        .map_err(|e| Error::Dap(format!("Failed to parse DAP message: {}", e)))?;
+
+    Ok(msg)
+}
+
+/// Helper to read DAP message from any async reader
+///
+/// Headers end with an empty line; both CRLF and bare LF line endings are
+/// accepted, as are blank lines between messages and headers other than
+/// Content-Length (ignored).
+async fn read_from_stream<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<(String, String)> {
+    // Read Content-Length header
+    let mut headers = String::new();
+    loop {
+        let mut line = String::new();
+        let limit = (MAX_HEADER_BYTES - headers.len()) as u64;
+        let read = (&mut *reader).take(limit).read_line(&mut line).await?;
+
+        if !line.ends_with('\n') {
+            if read as u64 == limit {
+                return Err(Error::Dap(format!(
+                    "DAP headers exceed {} bytes",
+                    MAX_HEADER_BYTES
+                )));
             }
+            return Err(Error::Io(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "DAP stream closed",
+            )));
+        }
 
-            headers.push_str(&line);
+        if line == "\r\n" || line == "\n" {
+            if headers.is_empty() {
+                continue;
+            }
+            break;
         }
 
-        // Parse Content-Length
-        let content_length = headers
-            .lines()
-            .find(|line| line.starts_with("Content-Length:"))
-            .and_then(|line| line.split(':').nth(1))
-            .and_then(|s| s.trim().parse::<usize>().ok())
-            .ok_or_else(|| Error::Dap("Missing Content-Length header".to_string()))?;
-
-        trace!(
-            "DAP: Reading message with Content-Length: {}",
-            content_length
-        );
+        headers.push_str(&line);
+    }
 
-        // Read content
-        let mut buffer = vec![0u8; content_length];
-        tokio::io::AsyncReadExt::read_exact(reader, &mut buffer).await?;
+    // Parse Content-Length
+    let value = headers
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("Content-Length"))
+        .map(|(_, value)| value.trim())
+        .ok_or_else(|| Error::Dap("Missing Content-Length header".to_string()))?;
+    let content_length = value
+        .parse::<usize>()
+        .map_err(|_| Error::Dap(format!("Invalid Content-Length header: {:?}", value)))?;
+    if content_length > MAX_CONTENT_LENGTH {
+        return Err(Error::Dap(format!(
+            "DAP message too large: Content-Length {} exceeds the {} byte limit",
+            content_length, MAX_CONTENT_LENGTH
+        )));
+    }
 
-        let content =
-            String::from_utf8(buffer).map_err(|e| Error::Dap(format!("Invalid UTF-8: {}", e)))?;
+    trace!(
+        "DAP: Reading message with Content-Length: {}",
+        content_length
+    );
 
-        Ok((headers, content))
-    }
+    // Read content
+    let mut buffer = vec![0u8; content_length];
+    reader.read_exact(&mut buffer).await?;
 
-    pub async fn write_message(&mut self, msg: &Message) -> Result<()> {
-        let content = serde_json::to_string(msg)
-            .map_err(|e| Error::Dap(format!("Failed to serialize DAP message: {}", e)))?;
+    let content =
+        String::from_utf8(buffer).map_err(|e| Error::Dap(format!("Invalid UTF-8: {}", e)))?;
 
-        debug!("DAP sending: {}", content);
+    Ok((headers, content))
+}
 
-        let headers = format!("Content-Length: {}\r\n\r\n", content.len());
+/// Serialize and write one Content-Length framed DAP message
+async fn write_framed<W: AsyncWrite + Unpin>(writer: &mut W, msg: &Message) -> Result<()> {
+    let content = serde_json::to_string(msg)
+        .map_err(|e| Error::Dap(format!("Failed to serialize DAP message: {}", e)))?;
 
-        // Write to either stdio or socket
-        match self {
-            Self::Stdio { stdin, .. } => {
-                stdin.write_all(headers.as_bytes()).await?;
-                stdin.write_all(content.as_bytes()).await?;
-                stdin.flush().await?;
-            }
-            Self::Socket { stream } => {
-                let inner_stream = stream.get_mut();
-                inner_stream.write_all(headers.as_bytes()).await?;
-                inner_stream.write_all(content.as_bytes()).await?;
-                inner_stream.flush().await?;
-            }
-        }
+    debug!("DAP sending: {}", content);
 
-        Ok(())
-    }
+    let headers = format!("Content-Length: {}\r\n\r\n", content.len());
+
+    writer.write_all(headers.as_bytes()).await?;
+    writer.write_all(content.as_bytes()).await?;
+    writer.flush().await?;
+
+    Ok(())
 }
 
 // Implement the trait for the concrete transport
@@ -128,43 +209,66 @@ impl DapTransportTrait for DapTransport {
         // Delegate to existing implementation
         self.write_message(msg).await
     }
+
+    fn split(self: Box<Self>) -> (Box<dyn DapReader>, Box<dyn DapWriter>) {
+        match *self {
+            Self::Stdio { stdin, stdout } => (
+                Box::new(DapReadHalf { reader: stdout }),
+                Box::new(DapWriteHalf { writer: stdin }),
+            ),
+            Self::Socket { stream } => {
+                // Nothing has been read yet when the client splits the
+                // transport, so the BufReader holds no buffered bytes.
+                let (read_half, write_half) = stream.into_inner().into_split();
+                (
+                    Box::new(DapReadHalf {
+                        reader: BufReader::new(read_half),
+                    }),
+                    Box::new(DapWriteHalf { writer: write_half }),
+                )
+            }
+            #[cfg(unix)]
+            Self::UnixSocket { stream } => {
+                let (read_half, write_half) = stream.into_inner().into_split();
+                (
+                    Box::new(DapReadHalf {
+                        reader: BufReader::new(read_half),
+                    }),
+                    Box::new(DapWriteHalf { writer: write_half }),
+                )
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::super::types::{Event, Request, Response};
     use super::*;
-    use mockall::mock;
+    use crate::dap::testing::MockTransport;
     use serde_json::json;
 
-    // Generate mock using mockall
-    mock! {
-        pub DapTransport {}
-
-        #[async_trait]
-        impl DapTransportTrait for DapTransport {
-            async fn read_message(&mut self) -> Result<Message>;
-            async fn write_message(&mut self, msg: &Message) -> Result<()>;
-        }
-    }
-
     #[tokio::test]
     async fn test_mock_read_initialize_response() {
-        let mut mock_transport = MockDapTransport::new();
-
-        mock_transport.expect_read_message().times(1).returning(|| {
-            Ok(Message::Response(Response {
-                seq: 1,
-                request_seq: 1,
-                command: "initialize".to_string(),
-                success: true,
-                message: None,
-                body: Some(json!({
-                    "supportsConfigurationDoneRequest": true,
-                    "supportsFunctionBreakpoints": false,
-                })),
-            }))
-        });
+        let mut mock_transport = MockTransport::new();
+
+        mock_transport
+            .reader
+            .expect_read_message()
+            .times(1)
+            .returning(|| {
+                Ok(Message::Response(Response {
+                    seq: 1,
+                    request_seq: 1,
+                    command: "initialize".to_string(),
+                    success: true,
+                    message: None,
+                    body: Some(json!({
+                        "supportsConfigurationDoneRequest": true,
+                        "supportsFunctionBreakpoints": false,
+                    })),
+                }))
+            });
 
         let msg = mock_transport.read_message().await.unwrap();
 
@@ -180,9 +284,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_mock_write_launch_request() {
-        let mut mock_transport = MockDapTransport::new();
+        let mut mock_transport = MockTransport::new();
 
         mock_transport
+            .writer
             .expect_write_message()
             .times(1)
             .withf(|msg| {
@@ -205,9 +310,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_mock_read_error() {
-        let mut mock_transport = MockDapTransport::new();
+        let mut mock_transport = MockTransport::new();
 
         mock_transport
+            .reader
             .expect_read_message()
             .times(1)
             .returning(|| Err(Error::Dap("Connection closed".to_string())));
@@ -223,18 +329,22 @@ mod tests {
 
     #[tokio::test]
     async fn test_mock_read_event() {
-        let mut mock_transport = MockDapTransport::new();
-
-        mock_transport.expect_read_message().times(1).returning(|| {
-            Ok(Message::Event(Event {
-                seq: 1,
-                event: "stopped".to_string(),
-                body: Some(json!({
-                    "reason": "breakpoint",
-                    "threadId": 1,
-                })),
-            }))
-        });
+        let mut mock_transport = MockTransport::new();
+
+        mock_transport
+            .reader
+            .expect_read_message()
+            .times(1)
+            .returning(|| {
+                Ok(Message::Event(Event {
+                    seq: 1,
+                    event: "stopped".to_string(),
+                    body: Some(json!({
+                        "reason": "breakpoint",
+                        "threadId": 1,
+                    })),
+                }))
+            });
 
         let msg = mock_transport.read_message().await.unwrap();
 
@@ -249,9 +359,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_mock_write_multiple_requests() {
-        let mut mock_transport = MockDapTransport::new();
+        let mut mock_transport = MockTransport::new();
 
         mock_transport
+            .writer
             .expect_write_message()
             .times(3)
             .returning(|_| Ok(()));
@@ -295,4 +406,322 @@ mod tests {
             _ => panic!("Expected Socket variant"),
         }
     }
+
+    #[tokio::test]
+    async fn test_split_socket_halves_are_independent() {
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let handle = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            socket
+        });
+
+        let client = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let server = handle.await.unwrap();
+
+        let transport: Box<dyn DapTransportTrait> = Box::new(DapTransport::new_socket(client));
+        let (mut reader, mut writer) = transport.split();
+        let (mut server_reader, mut server_writer) =
+            Box::new(DapTransport::new_socket(server)).split();
+
+        // A read pending on one half must not block writes on the other
+        let pending_read = tokio::spawn(async move { reader.read_message().await });
+
+        let request = Message::Request(Request {
+            seq: 1,
+            command: "threads".to_string(),
+            arguments: None,
+        });
+        writer.write_message(&request).await.unwrap();
+
+        match server_reader.read_message().await.unwrap() {
+            Message::Request(req) => assert_eq!(req.command, "threads"),
+            _ => panic!("Expected Request"),
+        }
+
+        let response = Message::Response(Response {
+            seq: 1,
+            request_seq: 1,
+            command: "threads".to_string(),
+            success: true,
+            message: None,
+            body: Some(json!({"threads": []})),
+        });
+        server_writer.write_message(&response).await.unwrap();
+
+        match pending_read.await.unwrap().unwrap() {
+            Message::Response(resp) => assert_eq!(resp.request_seq, 1),
+            _ => panic!("Expected Response"),
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_split_unix_socket_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("adapter.sock");
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+
+        let handle = tokio::spawn(async move { listener.accept().await.unwrap().0 });
+        let client = UnixStream::connect(&path).await.unwrap();
+        let server = handle.await.unwrap();
+
+        let (_, mut writer) = Box::new(DapTransport::new_unix_socket(client)).split();
+        let mut server_transport = DapTransport::new_unix_socket(server);
+
+        let request = Message::Request(Request {
+            seq: 1,
+            command: "initialize".to_string(),
+            arguments: None,
+        });
+        writer.write_message(&request).await.unwrap();
+        match server_transport.read_message().await.unwrap() {
+            Message::Request(req) => assert_eq!(req.command, "initialize"),
+            _ => panic!("Expected Request"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_half_parses_consecutive_frames() {
+        let (client, mut server) = tokio::io::duplex(1024);
+        let mut reader = DapReadHalf {
+            reader: BufReader::new(client),
+        };
+
+        let first = r#"{"type":"event","seq":1,"event":"initialized"}"#;
+        let second = r#"{"type":"event","seq":2,"event":"stopped","body":{"reason":"entry"}}"#;
+        let mut frames = String::new();
+        for body in [first, second] {
+            frames.push_str(&format!("Content-Length: {}\r\n\r\n{}", body.len(), body));
+        }
+        server.write_all(frames.as_bytes()).await.unwrap();
+
+        for expected in ["initialized", "stopped"] {
+            match reader.read_message().await.unwrap() {
+                Message::Event(evt) => assert_eq!(evt.event, expected),
+                _ => panic!("Expected Event"),
+            }
+        }
+    }
+
+    fn frame(body: &str) -> Vec<u8> {
+        format!("Content-Length: {}\r\n\r\n{}", body.len(), body).into_bytes()
+    }
+
+    fn event(seq: i32, name: &str) -> String {
+        json!({"type": "event", "seq": seq, "event": name}).to_string()
+    }
+
+    /// Reader returning at most one chunk per read, then EOF
+    struct ChunkedReader(std::collections::VecDeque<Vec<u8>>);
+
+    impl tokio::io::AsyncRead for ChunkedReader {
+        fn poll_read(
+            mut self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            buf: &mut tokio::io::ReadBuf<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            if let Some(mut chunk) = self.0.pop_front() {
+                let n = chunk.len().min(buf.remaining());
+                buf.put_slice(&chunk[..n]);
+                if n < chunk.len() {
+                    self.0.push_front(chunk.split_off(n));
+                }
+            }
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    fn chunked(chunks: &[Vec<u8>]) -> BufReader<ChunkedReader> {
+        BufReader::new(ChunkedReader(chunks.iter().cloned().collect()))
+    }
+
+    /// Read messages from `chunks`, delivered one read at a time, until the
+    /// first error
+    async fn read_chunks(chunks: &[Vec<u8>]) -> (Vec<Message>, Error) {
+        let mut reader = chunked(chunks);
+        let mut messages = Vec::new();
+        loop {
+            match read_framed(&mut reader).await {
+                Ok(msg) => messages.push(msg),
+                Err(e) => return (messages, e),
+            }
+        }
+    }
+
+    fn event_names(messages: &[Message]) -> Vec<String> {
+        messages
+            .iter()
+            .map(|msg| match msg {
+                Message::Event(evt) => evt.event.clone(),
+                other => panic!("Expected Event, got {:?}", other),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_framing_accepts_lf_extra_headers_and_blank_lines() {
+        let body = event(1, "initialized");
+        let lf = format!("content-length: {}\n\n{}", body.len(), body);
+        let extra = format!(
+            "Content-Type: application/vscode-jsonrpc\r\nContent-Length:{}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let chunks = vec![lf.into_bytes(), b"\r\n".to_vec(), extra.into_bytes()];
+
+        let (messages, error) = read_chunks(&chunks).await;
+        assert_eq!(event_names(&messages), ["initialized", "initialized"]);
+        assert!(matches!(error, Error::Io(ref e) if e.kind() == std::io::ErrorKind::UnexpectedEof));
+    }
+
+    #[tokio::test]
+    async fn test_framing_body_split_across_reads() {
+        let bytes = [frame(&event(1, "stopped")), frame(&event(2, "continued"))].concat();
+        let chunks: Vec<Vec<u8>> = bytes.chunks(3).map(<[u8]>::to_vec).collect();
+
+        let (messages, _) = read_chunks(&chunks).await;
+        assert_eq!(event_names(&messages), ["stopped", "continued"]);
+    }
+
+    #[tokio::test]
+    async fn test_framing_errors() {
+        let body = event(1, "stopped");
+        let cases = [
+            (
+                format!("X-Other: 1\r\n\r\n{}", body),
+                "Missing Content-Length",
+            ),
+            (
+                "Content-Length: ten\r\n\r\n".to_string(),
+                "Invalid Content-Length",
+            ),
+            (
+                "Content-Length: -1\r\n\r\n".to_string(),
+                "Invalid Content-Length",
+            ),
+            (
+                format!("Content-Length: {}\r\n\r\n", MAX_CONTENT_LENGTH + 1),
+                "too large",
+            ),
+            (
+                format!("X-Padding: {}\r\n", "x".repeat(MAX_HEADER_BYTES)),
+                "headers exceed",
+            ),
+        ];
+        let invalid_utf8 = b"Content-Length: 4\r\n\r\n\xff\xfe{}".to_vec();
+        let cases = cases
+            .into_iter()
+            .map(|(input, expected)| (input.into_bytes(), expected))
+            .chain([(invalid_utf8, "Invalid UTF-8")]);
+        for (input, expected) in cases {
+            let (messages, error) = read_chunks(std::slice::from_ref(&input)).await;
+            assert!(messages.is_empty(), "{:?}", input);
+            assert!(
+                error.to_string().contains(expected),
+                "{:?}: {}",
+                input,
+                error
+            );
+        }
+
+        // Stream ends inside the headers or the body
+        for input in ["Content-Len", "Content-Length: 50\r\n\r\n{\"type\""] {
+            let (_, error) = read_chunks(&[input.as_bytes().to_vec()]).await;
+            assert!(matches!(error, Error::Io(_)), "{:?}: {}", input, error);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_framing_bad_body_does_not_desync() {
+        let mut reader = chunked(&[frame("{not json}"), frame(&event(2, "stopped"))]);
+
+        assert!(read_framed(&mut reader).await.is_err());
+        match read_framed(&mut reader).await.unwrap() {
+            Message::Event(evt) => assert_eq!(evt.event, "stopped"),
+            other => panic!("Expected Event, got {:?}", other),
+        }
+    }
+
+    mod fuzz {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn block_on<F: std::future::Future>(future: F) -> F::Output {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(future)
+        }
+
+        /// Split `bytes` at the given cut points (modulo its length)
+        fn chunk(bytes: &[u8], cuts: &[usize]) -> Vec<Vec<u8>> {
+            let mut cuts: Vec<usize> = cuts
+                .iter()
+                .map(|cut| cut % (bytes.len() + 1))
+                .chain([0, bytes.len()])
+                .collect();
+            cuts.sort_unstable();
+            cuts.dedup();
+            cuts.windows(2)
+                .map(|range| bytes[range[0]..range[1]].to_vec())
+                .collect()
+        }
+
+        proptest! {
+            #[test]
+            fn valid_frames_survive_any_chunking(
+                names in prop::collection::vec("\\PC{0,40}", 1..6),
+                lf_only in any::<bool>(),
+                cuts in prop::collection::vec(any::<usize>(), 0..20),
+            ) {
+                let separator = if lf_only { "\n" } else { "\r\n" };
+                let bytes: Vec<u8> = names
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(seq, name)| {
+                        let body = event(seq as i32, name);
+                        format!(
+                            "Content-Length: {}{sep}{sep}{}",
+                            body.len(),
+                            body,
+                            sep = separator
+                        )
+                        .into_bytes()
+                    })
+                    .collect();
+
+                let (messages, error) = block_on(read_chunks(&chunk(&bytes, &cuts)));
+                prop_assert_eq!(event_names(&messages), names);
+                prop_assert!(matches!(error, Error::Io(_)), "{}", error);
+            }
+
+            #[test]
+            fn arbitrary_bytes_end_in_a_clean_error(
+                bytes in prop::collection::vec(any::<u8>(), 0..512),
+                cuts in prop::collection::vec(any::<usize>(), 0..8),
+            ) {
+                // Terminates (every read consumes input) without panicking
+                let (messages, _) = block_on(read_chunks(&chunk(&bytes, &cuts)));
+                prop_assert!(messages.len() <= bytes.len());
+            }
+
+            #[test]
+            fn corrupted_length_never_reads_past_the_stream(
+                length in any::<usize>(),
+                body in "\\PC{0,64}",
+            ) {
+                let input = format!("Content-Length: {}\r\n\r\n{}", length, body);
+                let (messages, error) = block_on(read_chunks(&[input.into_bytes()]));
+                prop_assert!(messages.len() <= 1);
+                if length > MAX_CONTENT_LENGTH {
+                    prop_assert!(error.to_string().contains("too large"), "{}", error);
+                }
+            }
+        }
+    }
 }
@@ -2,6 +2,25 @@ use super::types::Message;
 use crate::Result;
 use async_trait::async_trait;
 
+/// Read half of a DAP transport
+///
+/// Owned exclusively by the client's reader task, so reads never compete
+/// with writes for a lock.
+#[async_trait]
+pub trait DapReader: Send {
+    /// Read the next DAP protocol message
+    async fn read_message(&mut self) -> Result<Message>;
+}
+
+/// Write half of a DAP transport
+///
+/// Owned exclusively by the client's writer task.
+#[async_trait]
+pub trait DapWriter: Send {
+    /// Write a DAP protocol message
+    async fn write_message(&mut self, msg: &Message) -> Result<()>;
+}
+
 /// Trait for DAP transport layer to enable testing with mocks
 #[async_trait]
 pub trait DapTransportTrait: Send + Sync {
@@ -10,4 +29,10 @@ pub trait DapTransportTrait: Send + Sync {
 
     /// Write a DAP protocol message to the transport
     async fn write_message(&mut self, msg: &Message) -> Result<()>;
+
+    /// Split the transport into independently owned read and write halves
+    ///
+    /// The halves must not share a lock: the reader task keeps a read pending
+    /// at all times, and a write waiting for that read would never be sent.
+    fn split(self: Box<Self>) -> (Box<dyn DapReader>, Box<dyn DapWriter>);
 }
@@ -57,6 +57,10 @@ pub struct InitializeRequestArguments {
     pub lines_start_at_1: Option<bool>,
     pub columns_start_at_1: Option<bool>,
     pub path_format: Option<String>,
+    /// Lets adapters return `memoryReference` on evaluate results and variables
+    pub supports_memory_references: Option<bool>,
+    /// Lets adapters honor `start`/`count` in variables requests
+    pub supports_variable_paging: Option<bool>,
 }
 
 /// Capabilities returned by initialize
@@ -71,6 +75,62 @@ pub struct Capabilities {
     pub supports_set_variable: Option<bool>,
     pub supports_restart_frame: Option<bool>,
     pub supports_step_in_targets_request: Option<bool>,
+    pub supports_goto_targets_request: Option<bool>,
+    pub supports_log_points: Option<bool>,
+    pub supports_breakpoint_locations_request: Option<bool>,
+    pub supports_read_memory_request: Option<bool>,
+    pub supports_disassemble_request: Option<bool>,
+    pub supports_completions_request: Option<bool>,
+    pub supports_loaded_sources_request: Option<bool>,
+    pub supports_modules_request: Option<bool>,
+    pub supports_data_breakpoints: Option<bool>,
+    pub supports_stepping_granularity: Option<bool>,
+    /// Whether startFrame and levels of stackTrace requests are honored
+    pub supports_delayed_stack_trace_loading: Option<bool>,
+}
+
+impl Capabilities {
+    /// Apply the body of a 'capabilities' event, which only lists the
+    /// capabilities that changed; the others keep their value
+    pub fn merge(&mut self, changed: &Value) {
+        let (Ok(mut merged), Some(changed)) = (serde_json::to_value(&*self), changed.as_object())
+        else {
+            return;
+        };
+        if let Some(merged) = merged.as_object_mut() {
+            for (name, value) in changed.iter().filter(|(_, value)| !value.is_null()) {
+                merged.insert(name.clone(), value.clone());
+            }
+        }
+        if let Ok(merged) = serde_json::from_value(merged) {
+            *self = merged;
+        }
+    }
+}
+
+/// The program being debugged, from the adapter's 'process' event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebuggeeProcess {
+    /// Usually the program path
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_process_id: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_local_process: Option<bool>,
+    /// "launch", "attach" or "attachForSuspendedLaunch"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_method: Option<String>,
+}
+
+impl DebuggeeProcess {
+    /// PID of the process if the adapter started it on this machine, so
+    /// ending the session may kill it (attached processes are left alone)
+    pub fn launched_pid(&self) -> Option<u32> {
+        let launched = matches!(self.start_method.as_deref(), None | Some("launch"));
+        let local = self.is_local_process != Some(false);
+        self.system_process_id.filter(|_| launched && local)
+    }
 }
 
 /// Launch Request Arguments
@@ -98,6 +158,9 @@ pub struct Source {
     pub name: Option<String>,
     pub path: Option<String>,
     pub source_reference: Option<i32>,
+    /// Where the source comes from, e.g. "internal module" or "library"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
 }
 
 /// Source breakpoint
@@ -105,9 +168,78 @@ pub struct Source {
 #[serde(rename_all = "camelCase")]
 pub struct SourceBreakpoint {
     pub line: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub column: Option<i32>,
     pub condition: Option<String>,
     pub hit_condition: Option<String>,
+    /// Logpoint message: log this (with `{expr}` interpolation) instead of stopping
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_message: Option<String>,
+}
+
+/// Function breakpoint (break on entry to a function by name)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionBreakpoint {
+    pub name: String,
+    pub condition: Option<String>,
+    pub hit_condition: Option<String>,
+}
+
+/// SetFunctionBreakpoints Request Arguments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetFunctionBreakpointsArguments {
+    pub breakpoints: Vec<FunctionBreakpoint>,
+}
+
+/// Values the DAP specification allows for [`DataBreakpoint::access_type`]
+pub const DATA_BREAKPOINT_ACCESS_TYPES: &[&str] = &["read", "write", "readWrite"];
+
+/// DataBreakpointInfo Request Arguments
+///
+/// With `variables_reference`, `name` is a child of that container; without
+/// it, `name` is an expression evaluated in `frame_id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataBreakpointInfoArguments {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variables_reference: Option<i32>,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frame_id: Option<i32>,
+}
+
+/// Body of the dataBreakpointInfo response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataBreakpointInfoResponse {
+    /// `None` when no data breakpoint can be set on the value;
+    /// `description` then says why
+    pub data_id: Option<String>,
+    pub description: String,
+    pub access_types: Option<Vec<String>>,
+    pub can_persist: Option<bool>,
+}
+
+/// Data breakpoint (break when a value is read or written)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataBreakpoint {
+    pub data_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hit_condition: Option<String>,
+}
+
+/// SetDataBreakpoints Request Arguments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetDataBreakpointsArguments {
+    pub breakpoints: Vec<DataBreakpoint>,
 }
 
 /// Breakpoint response
@@ -131,6 +263,16 @@ pub struct StackTraceArguments {
     pub levels: Option<i32>,
 }
 
+/// Frames returned by one stackTrace request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StackTracePage {
+    pub stack_frames: Vec<StackFrame>,
+    /// Depth of the whole stack, when the adapter reports it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_frames: Option<i32>,
+}
+
 /// Stack Frame
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -142,6 +284,21 @@ pub struct StackFrame {
     pub column: i32,
     pub end_line: Option<i32>,
     pub end_column: Option<i32>,
+    /// Address of the frame's current instruction, for debugger_disassemble
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instruction_pointer_reference: Option<String>,
+    /// "normal", "label" (a separator such as "Async call from ...") or
+    /// "subtle" (library code the user rarely cares about)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presentation_hint: Option<String>,
+}
+
+impl StackFrame {
+    /// Whether the adapter marks this frame as a label or as subtle, i.e.
+    /// not the user's code
+    pub fn is_subtle(&self) -> bool {
+        matches!(self.presentation_hint.as_deref(), Some("label" | "subtle"))
+    }
 }
 
 /// Thread info
@@ -151,6 +308,9 @@ pub struct Thread {
     pub name: String,
 }
 
+/// Values the DAP specification allows for [`EvaluateArguments::context`]
+pub const EVALUATE_CONTEXTS: &[&str] = &["watch", "repl", "hover", "clipboard"];
+
 /// Evaluate Request Arguments
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -160,6 +320,28 @@ pub struct EvaluateArguments {
     pub context: Option<String>,
 }
 
+/// Evaluate Response body
+///
+/// `variables_reference > 0` means the result is structured and its children
+/// can be fetched with a `variables` request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvaluateResult {
+    pub result: String,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub type_: Option<String>,
+    #[serde(default)]
+    pub variables_reference: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub named_variables: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub indexed_variables: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presentation_hint: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_reference: Option<String>,
+}
+
 /// Variable
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -169,6 +351,161 @@ pub struct Variable {
     #[serde(rename = "type")]
     pub type_: Option<String>,
     pub variables_reference: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub named_variables: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub indexed_variables: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_reference: Option<String>,
+}
+
+/// ReadMemory Request Arguments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadMemoryArguments {
+    pub memory_reference: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<i64>,
+    pub count: i64,
+}
+
+/// ReadMemory Response body
+///
+/// `data` is base64 encoded and starts at `address`; it is absent or shorter
+/// than requested when part of the range could not be read.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadMemoryResult {
+    pub address: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unreadable_bytes: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+}
+
+/// Disassemble Request Arguments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisassembleArguments {
+    pub memory_reference: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instruction_offset: Option<i64>,
+    pub instruction_count: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolve_symbols: Option<bool>,
+}
+
+/// One instruction of a disassemble response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisassembledInstruction {
+    pub address: String,
+    pub instruction: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instruction_bytes: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<Source>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<i32>,
+}
+
+/// Completions Request Arguments
+///
+/// `column` is the cursor position in `text` (1-based, in UTF-16 code units).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletionsArguments {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frame_id: Option<i32>,
+    pub text: String,
+    pub column: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<i32>,
+}
+
+/// One completion of a completions response
+///
+/// Inserting `text` (or `label` when absent) replaces `length` characters of
+/// the request's text from `start` (0-based); both default to the word
+/// before the cursor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletionItem {
+    pub label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// "function", "variable", "module", "keyword", ...
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub type_: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length: Option<i32>,
+}
+
+/// A module (shared library, Python module, ...) loaded by the debuggee
+///
+/// Reported by the modules request and 'module' events.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Module {
+    /// Number or string, depending on the adapter
+    pub id: Value,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// e.g. "Symbols loaded." or "Symbols not found."
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol_status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol_file_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address_range: Option<String>,
+}
+
+/// BreakpointLocations Request Arguments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakpointLocationsArguments {
+    pub source: Source,
+    pub line: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_column: Option<i32>,
+}
+
+/// A position where the adapter can bind a breakpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakpointLocation {
+    pub line: i32,
+    pub column: Option<i32>,
+    pub end_line: Option<i32>,
+    pub end_column: Option<i32>,
+}
+
+/// Variables Request Arguments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariablesArguments {
+    pub variables_reference: i32,
+    /// Index of the first child to return (with `supportsVariablePaging`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<i32>,
+    /// Children to return; all when omitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<i32>,
 }
 
 /// Scopes Request Arguments
@@ -183,6 +520,9 @@ pub struct ScopesArguments {
 #[serde(rename_all = "camelCase")]
 pub struct Scope {
     pub name: String,
+    /// "arguments", "locals", "registers" or an adapter specific hint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presentation_hint: Option<String>,
     pub variables_reference: i32,
     pub expensive: bool,
 }
@@ -194,11 +534,18 @@ pub struct ContinueArguments {
     pub thread_id: i32,
 }
 
+/// Values the DAP specification allows for the `granularity` of a step
+pub const STEPPING_GRANULARITIES: &[&str] = &["statement", "line", "instruction"];
+
 /// Next (Step Over) Request Arguments
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NextArguments {
     pub thread_id: i32,
+    /// One of [`STEPPING_GRANULARITIES`]; the adapter's default (usually a
+    /// statement) when omitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub granularity: Option<String>,
 }
 
 /// StepIn (Step Into) Request Arguments
@@ -206,6 +553,76 @@ pub struct NextArguments {
 #[serde(rename_all = "camelCase")]
 pub struct StepInArguments {
     pub thread_id: i32,
+    /// Call to step into, from a stepInTargets response; the first call on
+    /// the line when omitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_id: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub granularity: Option<String>,
+}
+
+/// RestartFrame Request Arguments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestartFrameArguments {
+    pub frame_id: i32,
+}
+
+/// GotoTargets Request Arguments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GotoTargetsArguments {
+    pub source: Source,
+    pub line: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<i32>,
+}
+
+/// A location the program counter can be moved to with a goto request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GotoTarget {
+    pub id: i32,
+    pub label: String,
+    pub line: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_column: Option<i32>,
+}
+
+/// Goto Request Arguments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GotoArguments {
+    pub thread_id: i32,
+    pub target_id: i32,
+}
+
+/// StepInTargets Request Arguments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepInTargetsArguments {
+    pub frame_id: i32,
+}
+
+/// A call on the current line that stepIn can enter, e.g. `bar()` or
+/// `baz()` in `foo(bar(), baz())`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepInTarget {
+    pub id: i32,
+    pub label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_column: Option<i32>,
 }
 
 /// StepOut (Step Out) Request Arguments
@@ -213,6 +630,20 @@ pub struct StepInArguments {
 #[serde(rename_all = "camelCase")]
 pub struct StepOutArguments {
     pub thread_id: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub granularity: Option<String>,
+}
+
+/// RunInTerminal Reverse Request Arguments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunInTerminalRequestArguments {
+    pub kind: Option<String>,
+    pub title: Option<String>,
+    pub cwd: String,
+    pub args: Vec<String>,
+    /// `None` values ask for the variable to be removed from the environment
+    pub env: Option<std::collections::HashMap<String, Option<String>>>,
 }
 
 #[cfg(test)]
@@ -255,12 +686,281 @@ mod tests {
             column: Some(5),
             condition: Some("x > 0".to_string()),
             hit_condition: None,
+            log_message: None,
         };
 
         assert_eq!(bp.line, 10);
         assert_eq!(bp.column, Some(5));
     }
 
+    #[test]
+    fn test_source_breakpoint_log_message_serialization() {
+        let mut bp = SourceBreakpoint {
+            line: 10,
+            column: None,
+            condition: None,
+            hit_condition: None,
+            log_message: None,
+        };
+        let value = serde_json::to_value(&bp).unwrap();
+        assert!(value.get("logMessage").is_none());
+
+        bp.log_message = Some("n = {n}".to_string());
+        let value = serde_json::to_value(&bp).unwrap();
+        assert_eq!(value["logMessage"], "n = {n}");
+    }
+
+    #[test]
+    fn test_source_breakpoint_column_serialization() {
+        let mut bp = SourceBreakpoint {
+            line: 1,
+            column: None,
+            condition: None,
+            hit_condition: None,
+            log_message: None,
+        };
+        assert!(serde_json::to_value(&bp).unwrap().get("column").is_none());
+
+        bp.column = Some(42);
+        assert_eq!(serde_json::to_value(&bp).unwrap()["column"], 42);
+    }
+
+    #[test]
+    fn test_breakpoint_locations_serialization() {
+        let args = BreakpointLocationsArguments {
+            source: Source {
+                name: None,
+                path: Some("/app/bundle.min.js".to_string()),
+                source_reference: None,
+                origin: None,
+            },
+            line: 3,
+            column: None,
+            end_line: Some(5),
+            end_column: None,
+        };
+        let value = serde_json::to_value(&args).unwrap();
+        assert_eq!(value["line"], 3);
+        assert_eq!(value["endLine"], 5);
+        assert!(value.get("column").is_none());
+        assert!(value.get("endColumn").is_none());
+
+        let location: BreakpointLocation =
+            serde_json::from_value(json!({"line": 3, "column": 17})).unwrap();
+        assert_eq!(location.column, Some(17));
+        assert_eq!(location.end_line, None);
+    }
+
+    #[test]
+    fn test_completions_serialization() {
+        let args = CompletionsArguments {
+            frame_id: Some(4),
+            text: "fi".to_string(),
+            column: 3,
+            line: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&args).unwrap(),
+            json!({"frameId": 4, "text": "fi", "column": 3})
+        );
+
+        let item: CompletionItem = serde_json::from_value(
+            json!({"label": "fizzbuzz", "type": "function", "start": 0, "length": 2}),
+        )
+        .unwrap();
+        assert_eq!(item.type_.as_deref(), Some("function"));
+        assert_eq!(item.text, None);
+        assert_eq!(item.length, Some(2));
+        assert_eq!(
+            serde_json::to_value(&item).unwrap(),
+            json!({"label": "fizzbuzz", "type": "function", "start": 0, "length": 2})
+        );
+    }
+
+    #[test]
+    fn test_step_in_targets_serialization() {
+        let args = StepInArguments {
+            thread_id: 1,
+            target_id: Some(2),
+            granularity: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&args).unwrap(),
+            json!({"threadId": 1, "targetId": 2})
+        );
+        assert_eq!(
+            serde_json::to_value(StepInTargetsArguments { frame_id: 4 }).unwrap(),
+            json!({"frameId": 4})
+        );
+
+        let target: StepInTarget = serde_json::from_value(
+            json!({"id": 2, "label": "baz", "line": 7, "column": 19, "endColumn": 24}),
+        )
+        .unwrap();
+        assert_eq!(target.label, "baz");
+        assert_eq!(target.end_line, None);
+        assert_eq!(target.end_column, Some(24));
+        assert_eq!(
+            serde_json::to_value(&target).unwrap(),
+            json!({"id": 2, "label": "baz", "line": 7, "column": 19, "endColumn": 24})
+        );
+    }
+
+    #[test]
+    fn test_goto_serialization() {
+        let args = GotoTargetsArguments {
+            source: Source {
+                name: None,
+                path: Some("/app/main.py".to_string()),
+                source_reference: None,
+                origin: None,
+            },
+            line: 12,
+            column: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&args).unwrap(),
+            json!({"source": {"name": null, "path": "/app/main.py", "sourceReference": null}, "line": 12})
+        );
+        assert_eq!(
+            serde_json::to_value(GotoArguments {
+                thread_id: 1,
+                target_id: 3
+            })
+            .unwrap(),
+            json!({"threadId": 1, "targetId": 3})
+        );
+
+        let target: GotoTarget =
+            serde_json::from_value(json!({"id": 3, "label": "main.py:12", "line": 12})).unwrap();
+        assert_eq!(target.line, 12);
+        assert_eq!(target.column, None);
+    }
+
+    #[test]
+    fn test_data_breakpoint_serialization() {
+        let args = DataBreakpointInfoArguments {
+            variables_reference: Some(1001),
+            name: "count".to_string(),
+            frame_id: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&args).unwrap(),
+            json!({"variablesReference": 1001, "name": "count"})
+        );
+
+        let info: DataBreakpointInfoResponse = serde_json::from_value(json!({
+            "dataId": "0x7ffe0010/4",
+            "description": "4 bytes at 0x7ffe0010",
+            "accessTypes": ["read", "write", "readWrite"]
+        }))
+        .unwrap();
+        assert_eq!(info.data_id.as_deref(), Some("0x7ffe0010/4"));
+        assert_eq!(info.access_types.unwrap().len(), 3);
+
+        let bp = DataBreakpoint {
+            data_id: "0x7ffe0010/4".to_string(),
+            access_type: Some("write".to_string()),
+            condition: None,
+            hit_condition: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&bp).unwrap(),
+            json!({"dataId": "0x7ffe0010/4", "accessType": "write"})
+        );
+    }
+
+    #[test]
+    fn test_step_granularity_and_scope_hint_serialization() {
+        let args = NextArguments {
+            thread_id: 1,
+            granularity: None,
+        };
+        assert_eq!(serde_json::to_value(&args).unwrap(), json!({"threadId": 1}));
+        let args = StepInArguments {
+            thread_id: 1,
+            target_id: None,
+            granularity: Some("instruction".to_string()),
+        };
+        assert_eq!(
+            serde_json::to_value(&args).unwrap(),
+            json!({"threadId": 1, "granularity": "instruction"})
+        );
+
+        let scope: Scope = serde_json::from_value(json!({
+            "name": "Registers",
+            "presentationHint": "registers",
+            "variablesReference": 1002,
+            "expensive": false
+        }))
+        .unwrap();
+        assert_eq!(scope.presentation_hint.as_deref(), Some("registers"));
+        let locals: Scope = serde_json::from_value(
+            json!({"name": "Locals", "variablesReference": 1001, "expensive": false}),
+        )
+        .unwrap();
+        assert_eq!(locals.presentation_hint, None);
+    }
+
+    #[test]
+    fn test_module_serialization() {
+        let module: Module = serde_json::from_value(json!({
+            "id": "libc.so.6",
+            "name": "libc.so.6",
+            "path": "/usr/lib/libc.so.6",
+            "symbolStatus": "Symbols loaded."
+        }))
+        .unwrap();
+        assert_eq!(module.id, json!("libc.so.6"));
+        assert_eq!(module.symbol_status.as_deref(), Some("Symbols loaded."));
+        assert_eq!(module.address_range, None);
+
+        let numbered: Module = serde_json::from_value(json!({"id": 3, "name": "json"})).unwrap();
+        assert_eq!(
+            serde_json::to_value(&numbered).unwrap(),
+            json!({"id": 3, "name": "json"})
+        );
+    }
+
+    #[test]
+    fn test_capabilities_merge() {
+        let mut capabilities: Capabilities = serde_json::from_value(json!({
+            "supportsConfigurationDoneRequest": true,
+            "supportsRestartFrame": false
+        }))
+        .unwrap();
+        capabilities
+            .merge(&json!({"supportsRestartFrame": true, "supportsGotoTargetsRequest": true}));
+
+        assert_eq!(capabilities.supports_configuration_done_request, Some(true));
+        assert_eq!(capabilities.supports_restart_frame, Some(true));
+        assert_eq!(capabilities.supports_goto_targets_request, Some(true));
+        assert_eq!(capabilities.supports_log_points, None);
+    }
+
+    #[test]
+    fn test_debuggee_process() {
+        let launched: DebuggeeProcess = serde_json::from_value(json!({
+            "name": "/app/main.py",
+            "systemProcessId": 4242,
+            "isLocalProcess": true,
+            "startMethod": "launch"
+        }))
+        .unwrap();
+        assert_eq!(launched.launched_pid(), Some(4242));
+
+        let attached = DebuggeeProcess {
+            start_method: Some("attach".to_string()),
+            ..launched.clone()
+        };
+        assert_eq!(attached.launched_pid(), None);
+        let remote = DebuggeeProcess {
+            is_local_process: Some(false),
+            ..launched
+        };
+        assert_eq!(remote.launched_pid(), None);
+    }
+
     #[test]
     fn test_stack_frame() {
         let frame = StackFrame {
@@ -270,14 +970,33 @@ mod tests {
                 name: Some("test.py".to_string()),
                 path: Some("/path/to/test.py".to_string()),
                 source_reference: None,
+                origin: None,
             }),
             line: 42,
             column: 10,
             end_line: None,
             end_column: None,
+            instruction_pointer_reference: None,
+            presentation_hint: None,
         };
 
         assert_eq!(frame.name, "main");
         assert_eq!(frame.line, 42);
+        assert!(!frame.is_subtle());
+
+        let library: StackFrame = serde_json::from_value(json!({
+            "id": 2,
+            "name": "run",
+            "source": {"path": "/usr/lib/python3/runpy.py", "origin": "library"},
+            "line": 1,
+            "column": 1,
+            "presentationHint": "subtle"
+        }))
+        .unwrap();
+        assert!(library.is_subtle());
+        assert_eq!(
+            library.source.and_then(|source| source.origin).as_deref(),
+            Some("library")
+        );
     }
 }
@@ -0,0 +1,345 @@
+//! Per-session DAP protocol log (`captureDapLog`)
+//!
+//! With capture enabled, the client's transport halves record every
+//! message exchanged with the adapter into a [`DapLog`] ring buffer. The log
+//! is read through the `debugger_dap_log` tool and the
+//! `debugger://sessions/{id}/dapLog` resource, so one session's traffic can
+//! be inspected without digging through the interleaved tracing output.
+//!
+//! Recording never waits on the I/O paths: bodies are serialized before the
+//! buffer's lock is taken, and the lock is only held to push an entry.
+
+use super::metrics::ClientMetricsSlot;
+use super::transport_trait::{DapReader, DapWriter};
+use super::types::Message;
+use crate::Result;
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Entries kept per session; older ones are dropped
+pub const DAP_LOG_CAPACITY: usize = 1000;
+
+/// Bodies longer than this (serialized, in bytes) are truncated
+pub const DAP_LOG_BODY_LIMIT: usize = 2048;
+
+/// Whether a message was sent to or received from the adapter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Sent,
+    Received,
+}
+
+/// One logged DAP message
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DapLogEntry {
+    pub direction: Direction,
+    /// Wall clock time, milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+    /// Milliseconds since capture started
+    pub elapsed_ms: u64,
+    /// "request", "response" or "event"
+    #[serde(rename = "type")]
+    pub message_type: &'static str,
+    pub seq: i32,
+    /// Request/response command or event name
+    pub name: String,
+    /// Responses only: seq of the request being answered
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_seq: Option<i32>,
+    /// Responses only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+    /// Arguments (requests) or body (responses, events); a string ending in
+    /// a truncation note when the body exceeded [`DAP_LOG_BODY_LIMIT`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+    pub truncated: bool,
+}
+
+impl DapLogEntry {
+    fn new(direction: Direction, message: &Message, started: Instant) -> Self {
+        let (message_type, seq, name, request_seq, success, body) = match message {
+            Message::Request(req) => (
+                "request",
+                req.seq,
+                req.command.clone(),
+                None,
+                None,
+                req.arguments.as_ref(),
+            ),
+            Message::Response(resp) => (
+                "response",
+                resp.seq,
+                resp.command.clone(),
+                Some(resp.request_seq),
+                Some(resp.success),
+                resp.body.as_ref(),
+            ),
+            Message::Event(event) => (
+                "event",
+                event.seq,
+                event.event.clone(),
+                None,
+                None,
+                event.body.as_ref(),
+            ),
+        };
+        let (body, truncated) = match body.map(truncate_body) {
+            Some((body, truncated)) => (Some(body), truncated),
+            None => (None, false),
+        };
+
+        Self {
+            direction,
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_millis() as u64),
+            elapsed_ms: started.elapsed().as_millis() as u64,
+            message_type,
+            seq,
+            name,
+            request_seq,
+            success,
+            body,
+            truncated,
+        }
+    }
+}
+
+/// The body itself when small enough, otherwise its serialized prefix with a note
+fn truncate_body(body: &Value) -> (Value, bool) {
+    let text = body.to_string();
+    if text.len() <= DAP_LOG_BODY_LIMIT {
+        return (body.clone(), false);
+    }
+    let mut end = DAP_LOG_BODY_LIMIT;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    let note = format!(
+        "{}… [truncated: {} of {} bytes shown]",
+        &text[..end],
+        end,
+        text.len()
+    );
+    (Value::String(note), true)
+}
+
+/// Ring buffer of the DAP messages of one session
+#[derive(Debug)]
+pub struct DapLog {
+    entries: Mutex<VecDeque<DapLogEntry>>,
+    capacity: usize,
+    started: Instant,
+    /// Messages recorded since capture started, including dropped ones
+    recorded: AtomicUsize,
+}
+
+impl Default for DapLog {
+    fn default() -> Self {
+        Self::with_capacity(DAP_LOG_CAPACITY)
+    }
+}
+
+impl DapLog {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::with_capacity(capacity.min(DAP_LOG_CAPACITY))),
+            capacity,
+            started: Instant::now(),
+            recorded: AtomicUsize::new(0),
+        }
+    }
+
+    /// Record a message (called from the client's reader and writer tasks)
+    pub fn record(&self, direction: Direction, message: &Message) {
+        let entry = DapLogEntry::new(direction, message, self.started);
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+        self.recorded.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The last `tail` entries (all kept entries when `None`), oldest first
+    pub fn entries(&self, tail: Option<usize>) -> Vec<DapLogEntry> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let skip = tail.map_or(0, |tail| entries.len().saturating_sub(tail));
+        entries.iter().skip(skip).cloned().collect()
+    }
+
+    /// Messages recorded so far, including those no longer kept
+    pub fn recorded(&self) -> usize {
+        self.recorded.load(Ordering::Relaxed)
+    }
+
+    /// JSON view used by the tool and the resource
+    pub fn to_json(&self, tail: Option<usize>) -> Value {
+        let entries = self.entries(tail);
+        let recorded = self.recorded();
+        serde_json::json!({
+            "recorded": recorded,
+            "capacity": self.capacity,
+            "returned": entries.len(),
+            "entries": entries,
+        })
+    }
+}
+
+/// Log slot shared by a client and its transport halves; empty until
+/// capture is enabled
+pub(crate) type DapLogSlot = Arc<OnceLock<Arc<DapLog>>>;
+
+/// Read half that records every message it reads, and counts events
+pub(crate) struct LoggedReader {
+    inner: Box<dyn DapReader>,
+    log: DapLogSlot,
+    metrics: ClientMetricsSlot,
+}
+
+impl LoggedReader {
+    pub(crate) fn boxed(
+        inner: Box<dyn DapReader>,
+        log: DapLogSlot,
+        metrics: ClientMetricsSlot,
+    ) -> Box<dyn DapReader> {
+        Box::new(Self {
+            inner,
+            log,
+            metrics,
+        })
+    }
+}
+
+#[async_trait]
+impl DapReader for LoggedReader {
+    async fn read_message(&mut self) -> Result<Message> {
+        let message = self.inner.read_message().await?;
+        if let Some(log) = self.log.get() {
+            log.record(Direction::Received, &message);
+        }
+        if matches!(message, Message::Event(_)) {
+            self.metrics
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .record_event();
+        }
+        Ok(message)
+    }
+}
+
+/// Write half that records every message before writing it
+pub(crate) struct LoggedWriter {
+    inner: Box<dyn DapWriter>,
+    log: DapLogSlot,
+}
+
+impl LoggedWriter {
+    pub(crate) fn boxed(inner: Box<dyn DapWriter>, log: DapLogSlot) -> Box<dyn DapWriter> {
+        Box::new(Self { inner, log })
+    }
+}
+
+#[async_trait]
+impl DapWriter for LoggedWriter {
+    async fn write_message(&mut self, msg: &Message) -> Result<()> {
+        if let Some(log) = self.log.get() {
+            log.record(Direction::Sent, msg);
+        }
+        self.inner.write_message(msg).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dap::types::{Event, Request, Response};
+    use serde_json::json;
+
+    fn request(seq: i32, command: &str, arguments: Option<Value>) -> Message {
+        Message::Request(Request {
+            seq,
+            command: command.to_string(),
+            arguments,
+        })
+    }
+
+    #[test]
+    fn test_records_messages_in_both_directions() {
+        let log = DapLog::default();
+        log.record(Direction::Sent, &request(1, "threads", None));
+        log.record(
+            Direction::Received,
+            &Message::Response(Response {
+                seq: 10,
+                request_seq: 1,
+                success: true,
+                command: "threads".to_string(),
+                message: None,
+                body: Some(json!({"threads": []})),
+            }),
+        );
+        log.record(
+            Direction::Received,
+            &Message::Event(Event {
+                seq: 11,
+                event: "stopped".to_string(),
+                body: Some(json!({"reason": "breakpoint"})),
+            }),
+        );
+
+        let json = log.to_json(None);
+        assert_eq!(json["recorded"], 3);
+        let entries = json["entries"].as_array().unwrap();
+        assert_eq!(entries[0]["direction"], "sent");
+        assert_eq!(entries[0]["type"], "request");
+        assert_eq!(entries[0]["name"], "threads");
+        assert!(entries[0].get("body").is_none());
+        assert_eq!(entries[1]["requestSeq"], 1);
+        assert_eq!(entries[1]["success"], true);
+        assert_eq!(entries[2]["name"], "stopped");
+        assert_eq!(entries[2]["body"]["reason"], "breakpoint");
+    }
+
+    #[test]
+    fn test_ring_buffer_keeps_latest_entries() {
+        let log = DapLog::with_capacity(3);
+        for seq in 1..=5 {
+            log.record(Direction::Sent, &request(seq, "next", None));
+        }
+
+        let seqs: Vec<i32> = log.entries(None).iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, vec![3, 4, 5]);
+        let seqs: Vec<i32> = log.entries(Some(2)).iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, vec![4, 5]);
+        assert_eq!(log.recorded(), 5);
+    }
+
+    #[test]
+    fn test_large_bodies_are_truncated() {
+        let log = DapLog::default();
+        let big = "x".repeat(DAP_LOG_BODY_LIMIT * 2);
+        log.record(
+            Direction::Sent,
+            &request(1, "evaluate", Some(json!({"expression": big}))),
+        );
+
+        let entry = &log.entries(None)[0];
+        assert!(entry.truncated);
+        let body = entry.body.as_ref().unwrap().as_str().unwrap();
+        assert!(body.starts_with("{\"expression\":\"xxx"));
+        assert!(body.ends_with(&format!(
+            "[truncated: {} of {} bytes shown]",
+            DAP_LOG_BODY_LIMIT,
+            DAP_LOG_BODY_LIMIT * 2 + 17
+        )));
+    }
+}
@@ -0,0 +1,221 @@
+//! Breakpoints remembered per program across sessions
+//!
+//! When a session is disconnected its line breakpoints are saved under the
+//! program's absolute path, so the next `debugger_start` for the same program
+//! can restore them with `restoreBreakpoints: true`. The store lives in
+//! memory and is optionally mirrored to a JSON file (`--breakpoint-store`) so
+//! it survives server restarts.
+
+use super::manager::InitialBreakpoint;
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// A line breakpoint as saved in the store
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedBreakpoint {
+    pub source_path: String,
+    pub line: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_message: Option<String>,
+}
+
+impl From<SavedBreakpoint> for InitialBreakpoint {
+    fn from(bp: SavedBreakpoint) -> Self {
+        Self {
+            source_path: bp.source_path,
+            line: bp.line,
+            condition: bp.condition,
+            log_message: bp.log_message,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BreakpointStore {
+    /// JSON file mirroring the store, if persistence is enabled
+    path: Option<PathBuf>,
+    programs: BTreeMap<String, Vec<SavedBreakpoint>>,
+}
+
+impl BreakpointStore {
+    /// A store that only lives as long as the server
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// A store persisted to `path`, loading what a previous run saved there
+    pub fn open(path: PathBuf) -> Result<Self> {
+        let programs = if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            serde_json::from_str(&content).map_err(|e| {
+                Error::InvalidRequest(format!(
+                    "Invalid breakpoint store {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?
+        } else {
+            BTreeMap::new()
+        };
+
+        info!(
+            "📌 Breakpoint store {} ({} programs)",
+            path.display(),
+            programs.len()
+        );
+        Ok(Self {
+            path: Some(path),
+            programs,
+        })
+    }
+
+    /// Absolute path identifying `program` in the store
+    pub fn program_key(program: &str) -> String {
+        std::fs::canonicalize(program)
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|_| program.to_string())
+    }
+
+    /// Breakpoints saved for `program` (empty if none)
+    pub fn get(&self, program: &str) -> Vec<SavedBreakpoint> {
+        self.programs
+            .get(&Self::program_key(program))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Every program with saved breakpoints
+    pub fn programs(&self) -> &BTreeMap<String, Vec<SavedBreakpoint>> {
+        &self.programs
+    }
+
+    /// Replace the breakpoints saved for `program`
+    pub fn save(&mut self, program: &str, breakpoints: Vec<SavedBreakpoint>) -> Result<()> {
+        let key = Self::program_key(program);
+        if breakpoints.is_empty() {
+            self.programs.remove(&key);
+        } else {
+            self.programs.insert(key, breakpoints);
+        }
+        self.persist()
+    }
+
+    /// Forget the breakpoints of `program`, or of every program if `None`;
+    /// returns how many breakpoints were removed
+    pub fn clear(&mut self, program: Option<&str>) -> Result<usize> {
+        let removed = match program {
+            Some(program) => self
+                .programs
+                .remove(&Self::program_key(program))
+                .map_or(0, |bps| bps.len()),
+            None => {
+                let count = self.programs.values().map(Vec::len).sum();
+                self.programs.clear();
+                count
+            }
+        };
+        self.persist()?;
+        Ok(removed)
+    }
+
+    fn persist(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        // Write then rename so a crash never leaves a truncated store
+        let tmp = temp_path(path);
+        std::fs::write(&tmp, serde_json::to_string_pretty(&self.programs)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+pub(super) fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn bp(source: &str, line: i32) -> SavedBreakpoint {
+        SavedBreakpoint {
+            source_path: source.to_string(),
+            line,
+            condition: None,
+            log_message: None,
+        }
+    }
+
+    #[test]
+    fn test_save_get_and_clear() {
+        let mut store = BreakpointStore::in_memory();
+        store
+            .save(
+                "/app/main.py",
+                vec![bp("/app/main.py", 3), bp("/app/lib.py", 9)],
+            )
+            .unwrap();
+        store
+            .save("/app/other.py", vec![bp("/app/other.py", 1)])
+            .unwrap();
+
+        assert_eq!(store.get("/app/main.py").len(), 2);
+        assert!(store.get("/app/missing.py").is_empty());
+
+        assert_eq!(store.clear(Some("/app/main.py")).unwrap(), 2);
+        assert!(store.get("/app/main.py").is_empty());
+        assert_eq!(store.clear(None).unwrap(), 1);
+        assert!(store.programs().is_empty());
+    }
+
+    #[test]
+    fn test_saving_nothing_removes_the_program() {
+        let mut store = BreakpointStore::in_memory();
+        store
+            .save("/app/main.py", vec![bp("/app/main.py", 3)])
+            .unwrap();
+        store.save("/app/main.py", Vec::new()).unwrap();
+        assert!(store.programs().is_empty());
+    }
+
+    #[test]
+    fn test_persisted_store_survives_reopen() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("breakpoints.json");
+        let program = dir.path().join("main.py");
+        std::fs::write(&program, "print('hi')\n").unwrap();
+        let program = program.to_string_lossy().to_string();
+
+        let mut saved = bp(&program, 1);
+        saved.condition = Some("x > 1".to_string());
+        BreakpointStore::open(path.clone())
+            .unwrap()
+            .save(&program, vec![saved.clone()])
+            .unwrap();
+
+        let reopened = BreakpointStore::open(path).unwrap();
+        assert_eq!(reopened.get(&program), vec![saved]);
+    }
+
+    #[test]
+    fn test_open_rejects_corrupt_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("breakpoints.json");
+        std::fs::write(&path, "not json").unwrap();
+
+        assert!(matches!(
+            BreakpointStore::open(path),
+            Err(Error::InvalidRequest(_))
+        ));
+    }
+}
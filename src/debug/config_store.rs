@@ -0,0 +1,247 @@
+//! Named debugger_start configurations
+//!
+//! Agents tend to re-derive the same start arguments on every run. A
+//! configuration is a saved `debugger_start` payload that a later call
+//! reuses with `config: "<name>"`, overriding single fields as needed.
+//!
+//! Configurations come from three places:
+//! - `debugger_save_config`
+//! - the last successful `debugger_start`, kept as [`LAST_CONFIG`]
+//! - a `.debugger-mcp.json` file checked into a project, read from the
+//!   server's working directory at startup and from a program's directory
+//!   the first time it is started:
+//!
+//! ```text
+//! {"configurations": {"tests": {"language": "python", "module": "pytest",
+//!                               "args": ["-x", "tests"]}}}
+//! ```
+//!
+//! Relative `program` and `cwd` paths in a project file are resolved against
+//! its directory. Saved configurations win over project files with the same
+//! name.
+
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Project file holding checked-in configurations
+pub const PROJECT_CONFIG_FILE: &str = ".debugger-mcp.json";
+
+/// Name under which the last successful debugger_start is kept
+pub const LAST_CONFIG: &str = "last";
+
+/// Where a configuration came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "kind", content = "path")]
+pub enum ConfigSource {
+    /// debugger_save_config
+    Saved,
+    /// The last successful debugger_start
+    LastStart,
+    /// A project's `.debugger-mcp.json`
+    ProjectFile(String),
+}
+
+/// A named debugger_start payload
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NamedConfig {
+    pub name: String,
+    pub source: ConfigSource,
+    pub config: Map<String, Value>,
+}
+
+/// Contents of a `.debugger-mcp.json` file
+#[derive(Debug, Deserialize)]
+struct ProjectFile {
+    #[serde(default)]
+    configurations: BTreeMap<String, Map<String, Value>>,
+}
+
+#[derive(Debug, Default)]
+pub struct ConfigStore {
+    configs: BTreeMap<String, NamedConfig>,
+    /// Project files already read, so each is only loaded once
+    loaded_files: HashSet<PathBuf>,
+}
+
+impl ConfigStore {
+    /// Store `config` under `name`; returns the configuration it replaced
+    pub fn save(
+        &mut self,
+        name: &str,
+        config: Map<String, Value>,
+        source: ConfigSource,
+    ) -> Option<NamedConfig> {
+        self.configs.insert(
+            name.to_string(),
+            NamedConfig {
+                name: name.to_string(),
+                source,
+                config,
+            },
+        )
+    }
+
+    pub fn get(&self, name: &str) -> Option<&NamedConfig> {
+        self.configs.get(name)
+    }
+
+    /// Every configuration, sorted by name
+    pub fn list(&self) -> Vec<NamedConfig> {
+        self.configs.values().cloned().collect()
+    }
+
+    /// Add the configurations of `dir`'s project file, unless it was read
+    /// before; returns how many were added
+    ///
+    /// Names that are already taken keep their current configuration.
+    pub fn load_project_file(&mut self, dir: &Path) -> Result<usize> {
+        let path = dir.join(PROJECT_CONFIG_FILE);
+        if !path.is_file() || !self.loaded_files.insert(path.clone()) {
+            return Ok(0);
+        }
+
+        let content = std::fs::read_to_string(&path)?;
+        let file: ProjectFile = serde_json::from_str(&content)
+            .map_err(|e| Error::InvalidRequest(format!("Invalid {}: {}", path.display(), e)))?;
+
+        let source = ConfigSource::ProjectFile(path.to_string_lossy().to_string());
+        let mut added = 0;
+        for (name, mut config) in file.configurations {
+            // Checked-in paths are relative to the project file
+            for field in ["program", "cwd"] {
+                if let Some(Value::String(path)) = config.get_mut(field) {
+                    if Path::new(path.as_str()).is_relative() {
+                        *path = dir.join(path.as_str()).to_string_lossy().to_string();
+                    }
+                }
+            }
+            if !self.configs.contains_key(&name) {
+                self.save(&name, config, source.clone());
+                added += 1;
+            }
+        }
+        info!(
+            "📋 Loaded {} debug configurations from {}",
+            added,
+            path.display()
+        );
+        Ok(added)
+    }
+}
+
+/// `config` with every field of `overrides` replacing the configured value
+pub fn merge(config: &Map<String, Value>, overrides: &Map<String, Value>) -> Map<String, Value> {
+    let mut merged = config.clone();
+    for (field, value) in overrides {
+        merged.insert(field.clone(), value.clone());
+    }
+    merged
+}
+
+/// Configuration names: letters, digits, '-', '_' and '.'
+pub fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(Error::InvalidRequest(format!(
+            "Invalid configuration name \"{}\": use letters, digits, '-', '_' and '.'",
+            name
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_merge_prefers_overrides() {
+        let config = object(json!({"language": "python", "program": "a.py", "stopOnEntry": true}));
+        let merged = merge(
+            &config,
+            &object(json!({"stopOnEntry": false, "args": ["-v"]})),
+        );
+
+        assert_eq!(
+            Value::Object(merged),
+            json!({"language": "python", "program": "a.py", "stopOnEntry": false, "args": ["-v"]})
+        );
+    }
+
+    #[test]
+    fn test_project_file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let configs = json!({"configurations": {
+            "tests": {"language": "python", "module": "pytest", "args": ["-x"]},
+            "app": {"language": "python", "program": "app.py"},
+            "web": {"language": "python", "program": "web/app.py", "cwd": "/srv"}
+        }});
+        std::fs::write(
+            dir.path().join(PROJECT_CONFIG_FILE),
+            serde_json::to_string_pretty(&configs).unwrap(),
+        )
+        .unwrap();
+
+        let mut store = ConfigStore::default();
+        store.save(
+            "app",
+            object(json!({"language": "ruby", "program": "app.rb"})),
+            ConfigSource::Saved,
+        );
+
+        assert_eq!(store.load_project_file(dir.path()).unwrap(), 2);
+        // Each file is read once
+        assert_eq!(store.load_project_file(dir.path()).unwrap(), 0);
+
+        let tests = store.get("tests").unwrap();
+        assert_eq!(
+            Value::Object(tests.config.clone()),
+            configs["configurations"]["tests"]
+        );
+        assert!(matches!(tests.source, ConfigSource::ProjectFile(_)));
+        let web = &store.get("web").unwrap().config;
+        assert_eq!(
+            web["program"],
+            dir.path().join("web/app.py").to_string_lossy().as_ref()
+        );
+        assert_eq!(web["cwd"], "/srv");
+        // Saved configurations are not replaced by the project file
+        assert_eq!(store.get("app").unwrap().config["language"], "ruby");
+        assert_eq!(
+            serde_json::to_value(store.get("app").unwrap()).unwrap()["source"],
+            json!({"kind": "saved"})
+        );
+    }
+
+    #[test]
+    fn test_invalid_project_file_and_names() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(PROJECT_CONFIG_FILE),
+            "{\"configurations\": []}",
+        )
+        .unwrap();
+        let mut store = ConfigStore::default();
+        assert!(matches!(
+            store.load_project_file(dir.path()),
+            Err(Error::InvalidRequest(_))
+        ));
+
+        assert!(validate_name("py-tests_2.x").is_ok());
+        for invalid in ["", "my tests", "../x"] {
+            assert!(validate_name(invalid).is_err(), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! Watch mode: restart a session when its program changes (`watchMode`)
+//!
+//! debugger_start with `watchMode: true` polls the modification time and size
+//! of the program file, plus any `watchPaths`. Once a change has settled for
+//! [`WATCH_DEBOUNCE`], the [`SessionManager`](super::SessionManager) restarts
+//! the session under the same ID: the debuggee is terminated, Rust programs
+//! are rebuilt, and the program is launched again with the session's current
+//! breakpoints. Subscribers see a state change with reason [`RESTART_REASON`].
+//!
+//! Polling keeps the watcher free of platform notification APIs, and the few
+//! files of a debugging session are cheap to stat.
+
+use serde::Serialize;
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often watched files are checked
+pub const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How long a change must stay untouched before the session restarts, so an
+/// editor writing a file in several steps causes a single restart
+pub const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Reason of the state change published when a watched file restarts a session
+pub const RESTART_REASON: &str = "restarted: file changed";
+
+/// Modification time and size of each watched path; `None` while missing
+type Stamps = Vec<Option<(Option<SystemTime>, u64)>>;
+
+fn stamps(paths: &[String]) -> Stamps {
+    paths
+        .iter()
+        .map(|path| {
+            std::fs::metadata(path)
+                .ok()
+                .map(|metadata| (metadata.modified().ok(), metadata.len()))
+        })
+        .collect()
+}
+
+/// Detects changes to a set of files once they have settled
+#[derive(Debug)]
+pub struct ChangeDetector {
+    paths: Vec<String>,
+    debounce: Duration,
+    baseline: Stamps,
+    /// Latest changed stamps and when they were first seen
+    pending: Option<(Stamps, Instant)>,
+}
+
+impl ChangeDetector {
+    pub fn new(paths: Vec<String>, debounce: Duration) -> Self {
+        let baseline = stamps(&paths);
+        Self {
+            paths,
+            debounce,
+            baseline,
+            pending: None,
+        }
+    }
+
+    pub fn paths(&self) -> &[String] {
+        &self.paths
+    }
+
+    /// Check the files again; true while a change has been left untouched for
+    /// the debounce period, until [`ChangeDetector::reset`] is called
+    pub fn poll(&mut self) -> bool {
+        let current = stamps(&self.paths);
+        if current == self.baseline {
+            self.pending = None;
+            return false;
+        }
+        match &self.pending {
+            Some((pending, since)) if *pending == current => since.elapsed() >= self.debounce,
+            // Changed again: wait for the new change to settle
+            _ => {
+                self.pending = Some((current, Instant::now()));
+                false
+            }
+        }
+    }
+
+    /// Take the files as they are now as the unchanged state
+    pub fn reset(&mut self) {
+        self.baseline = stamps(&self.paths);
+        self.pending = None;
+    }
+}
+
+/// Watch mode of a session, as reported by debugger_watch_status
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileWatchStatus {
+    pub enabled: bool,
+    pub paths: Vec<String>,
+    /// Restarts completed because of file changes
+    pub restarts: usize,
+    /// A restart is in progress
+    pub restarting: bool,
+    /// When the last restart completed, milliseconds since the Unix epoch
+    pub last_restart_ms: Option<u64>,
+    /// Why the last restart failed; cleared by the next successful one
+    pub last_error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_detector_debounces_rewrites() {
+        let dir = tempfile::tempdir().unwrap();
+        let program = dir.path().join("app.py");
+        std::fs::write(&program, "print(1)\n").unwrap();
+        let debounce = Duration::from_millis(50);
+        let mut detector =
+            ChangeDetector::new(vec![program.to_string_lossy().to_string()], debounce);
+        assert!(!detector.poll());
+
+        // A change is only reported once it stops changing
+        std::fs::write(&program, "print(22)\n").unwrap();
+        assert!(!detector.poll());
+        std::thread::sleep(debounce / 2);
+        std::fs::write(&program, "print(333)\n").unwrap();
+        assert!(!detector.poll());
+        std::thread::sleep(debounce * 2);
+        assert!(detector.poll());
+        // Still pending until the restart takes it
+        assert!(detector.poll());
+
+        detector.reset();
+        assert!(!detector.poll());
+
+        std::fs::remove_file(&program).unwrap();
+        assert!(!detector.poll());
+        std::thread::sleep(debounce * 2);
+        assert!(detector.poll());
+    }
+}
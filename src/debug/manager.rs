@@ -1,20 +1,402 @@
-use super::session::DebugSession;
+use super::breakpoint_store::{BreakpointStore, SavedBreakpoint};
+use super::config_store::{ConfigSource, ConfigStore, NamedConfig};
+use super::file_watch::{
+    ChangeDetector, FileWatchStatus, RESTART_REASON, WATCH_DEBOUNCE, WATCH_POLL_INTERVAL,
+};
+use super::multi_session::MultiSessionManager;
+use super::path_mapping::{same_source_path, PathMapper};
+use super::recorder::{RecordingConfig, SessionRecorder};
+use super::session::{DebugSession, SessionMode};
+use super::session_state::{PersistedSession, SessionStateFile, UnrecoverableSession};
+use super::state::{DebugState, ResourceChange, StateChange, StateNotifier};
+use crate::adapters::bash::BashAdapter;
+use crate::adapters::cpp::{CLanguage, CppAdapter};
 use crate::adapters::golang::GoAdapter;
+use crate::adapters::health;
 use crate::adapters::logging::DebugAdapterLogger;
-use crate::adapters::nodejs::NodeJsAdapter;
-use crate::adapters::python::PythonAdapter;
-use crate::adapters::ruby::RubyAdapter;
-use crate::adapters::rust::RustAdapter;
-use crate::dap::client::DapClient;
+use crate::adapters::nodejs::{NodeJsAdapter, NodeLaunchOptions};
+use crate::adapters::python::{PathMapping, PythonAdapter, PythonLaunchOptions};
+use crate::adapters::ruby::{RubyAdapter, RubyLaunchOptions};
+use crate::adapters::rust::{
+    CargoTargetType, CompileOutcome, LldbLaunchOptions, RustAdapter, RustProjectType,
+};
+use crate::adapters::security::AllowedPaths;
+use crate::dap::client::{DapClient, RequestTimeouts};
+use crate::dap::socket_helper::{
+    connect_endpoint_with_timeouts, connect_host_with_timeouts, AdapterEndpoint, AdapterTimeouts,
+};
+use crate::dap::types::SourceBreakpoint;
+use crate::dap::wire_log::DapLog;
 use crate::{Error, Result};
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{error, info};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::{broadcast, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// Optional per-session settings beyond the basic launch parameters
+#[derive(Debug, Clone, Default)]
+pub struct SessionOptions {
+    /// Default DAP request timeout (falls back to the client default)
+    pub request_timeout: Option<Duration>,
+    /// Line for the entry breakpoint used to emulate stopOnEntry (1-indexed),
+    /// overriding the detected first executable line
+    pub entry_line: Option<usize>,
+    /// Breakpoints sent before configurationDone, so the program stops at
+    /// them without needing stopOnEntry
+    pub breakpoints: Vec<InitialBreakpoint>,
+    /// Cargo target to build for Rust sources (defaults to the binary)
+    pub cargo_target: Option<CargoTargetType>,
+    /// Single test to run when `cargo_target` is a test build
+    pub test_filter: Option<String>,
+    /// Compile even when the previous build is up to date
+    pub force_rebuild: bool,
+    /// Translates source paths when the program sees different paths than
+    /// the MCP client (e.g. inside a container)
+    pub path_mapper: PathMapper,
+    /// Adapter connect/initialize timeouts (falls back to the manager's, then
+    /// to the language's defaults)
+    pub adapter_timeouts: Option<AdapterTimeouts>,
+    /// Record the DAP messages of the session (see [`DapLog`])
+    pub capture_dap_log: bool,
+    /// Write a transcript of the session's tool calls and state changes
+    /// (always on when the manager records every session)
+    pub record: bool,
+    /// Node.js only: source maps and runtime arguments (TypeScript)
+    pub node_launch: NodeLaunchOptions,
+    /// Python only: run this module (`python -m`) instead of the program
+    pub python_module: Option<String>,
+    /// Python only: subprocesses and web framework dev servers
+    pub python_launch: PythonLaunchOptions,
+    /// Ruby only: Bundler and command mode
+    pub ruby_launch: RubyLaunchOptions,
+    /// Rust, C and C++ only: CodeLLDB evaluator and LLDB commands
+    pub lldb_launch: LldbLaunchOptions,
+    /// Rust, C and C++ only: core dump of the program to inspect post-mortem
+    pub core_dump: Option<String>,
+    /// Register the session under this ID instead of a new one (a restarted
+    /// session keeps its ID)
+    pub session_id: Option<String>,
+    /// What [`SessionManager::start_session`] does when the program is
+    /// already being debugged
+    pub existing_session: ExistingSession,
+}
+
+/// What starting a session does when a session that has not terminated is
+/// already debugging the same program
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExistingSession {
+    /// Start another session anyway
+    #[default]
+    Start,
+    /// Fail with an error naming the existing session (`exclusive`)
+    Fail,
+    /// Return the existing session instead (`reuseExisting`)
+    Reuse,
+}
+
+/// Session returned by [`SessionManager::start_session`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StartedSession {
+    pub session_id: String,
+    /// An existing session was returned instead of starting one
+    pub reused: bool,
+    /// How the new session was started (`None` when reused)
+    pub info: Option<StartInfo>,
+}
+
+/// What starting a session resolved: adapter, paths and build
+///
+/// Serialized into debugger_start's response, e.g.
+///
+/// ```text
+/// {"language": "rust", "program": "/work/app/src/main.rs", "cwd": "/work/app",
+///  "stopOnEntry": false, "adapter": "codelldb",
+///  "adapterCommand": "/usr/local/bin/codelldb", "adapterVersion": "codelldb 1.11.0",
+///  "compiled": true, "rustProject": {"kind": "cargo", "target": "bin:app"}}
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartInfo {
+    /// Canonical language name ("golang" is reported as "go")
+    pub language: String,
+    /// Canonical program path (a Python module's name)
+    pub program: String,
+    /// Working directory the program runs in, after defaults
+    pub cwd: Option<String>,
+    pub stop_on_entry: bool,
+    /// Adapter ID sent in the initialize request
+    pub adapter: String,
+    /// Adapter executable, or the script node runs (Node.js, Bash)
+    pub adapter_command: String,
+    /// Adapter version, when it could be determined
+    pub adapter_version: Option<String>,
+    /// The program was built from source before launching (Rust, C and
+    /// C++); the build finishes before the session is returned
+    pub compiled: bool,
+    /// Rust only: how the program was built
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rust_project: Option<RustProject>,
+}
+
+impl StartInfo {
+    fn adapter(&mut self, adapter_id: &str, command: &str) {
+        self.adapter = adapter_id.to_string();
+        self.adapter_command = command_path(command);
+    }
+
+    /// Fill in the adapter a `language` session is started with, and for C
+    /// and C++ whether the program is built (Rust decides once it knows what
+    /// `program` is; Node.js sessions report the server script they spawned)
+    fn language_adapter(&mut self, language: &str, options: &SessionOptions) {
+        match language {
+            "python" => self.adapter(PythonAdapter::adapter_id(), &PythonAdapter::command()),
+            #[cfg(feature = "fake-adapter")]
+            "fake" => {
+                use crate::adapters::fake::FakeAdapter;
+                self.adapter(FakeAdapter::adapter_id(), &FakeAdapter::command())
+            }
+            "bash" | "sh" => self.adapter(
+                BashAdapter::adapter_id(),
+                &BashAdapter::adapter_script().to_string_lossy(),
+            ),
+            "ruby" => self.adapter(RubyAdapter::adapter_id(), &RubyAdapter::command()),
+            // vscode-js-debug runs on node; the script is known once spawned
+            "nodejs" => self.adapter(NodeJsAdapter::adapter_id(), "node"),
+            "go" => self.adapter(GoAdapter::adapter_id(), &GoAdapter::command()),
+            "rust" => self.adapter(RustAdapter::adapter_id(), &RustAdapter::command()),
+            "c" | "cpp" => {
+                // Same CodeLLDB as Rust
+                self.adapter(CppAdapter::adapter_id(), &RustAdapter::command());
+                self.compiled = options.core_dump.is_none();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Rust program layout reported in [`StartInfo`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RustProject {
+    pub kind: RustProjectKind,
+    /// Cargo target that was built: `bin:<name>`, `example:<name>` or `test`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RustProjectKind {
+    /// A lone .rs file compiled with rustc
+    SingleFile,
+    /// A source file of a Cargo project, built with cargo
+    Cargo,
+    /// A binary built beforehand (or the binary of a core dump)
+    Binary,
+}
+
+/// `command` as an absolute path when it is found on PATH
+fn command_path(command: &str) -> String {
+    crate::process::which(command)
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|| command.to_string())
+}
+
+/// How a Rust session's program was built: `compiled` is `None` for a
+/// binary built beforehand
+fn rust_project(
+    compiled: Option<&CompileOutcome>,
+    single_file: bool,
+    cargo_target: Option<&CargoTargetType>,
+) -> RustProject {
+    match compiled {
+        None => RustProject {
+            kind: RustProjectKind::Binary,
+            target: None,
+        },
+        Some(_) if single_file => RustProject {
+            kind: RustProjectKind::SingleFile,
+            target: None,
+        },
+        Some(compiled) => RustProject {
+            kind: RustProjectKind::Cargo,
+            target: Some(cargo_target_label(cargo_target, &compiled.binary)),
+        },
+    }
+}
+
+/// [`RustProject::target`] of a Cargo build of `binary`
+fn cargo_target_label(target: Option<&CargoTargetType>, binary: &str) -> String {
+    let binary = std::path::Path::new(binary);
+    let name = binary
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    match target {
+        Some(CargoTargetType::Test) => "test".to_string(),
+        Some(CargoTargetType::Example(name)) => format!("example:{}", name),
+        // cargo puts examples in target/<profile>/examples/
+        _ if binary
+            .parent()
+            .and_then(|dir| dir.file_name())
+            .is_some_and(|dir| dir == "examples") =>
+        {
+            format!("example:{}", name)
+        }
+        _ => format!("bin:{}", name),
+    }
+}
+
+/// A breakpoint requested as part of starting a session
+#[derive(Debug, Clone, PartialEq)]
+pub struct InitialBreakpoint {
+    pub source_path: String,
+    pub line: i32,
+    pub condition: Option<String>,
+    pub log_message: Option<String>,
+}
+
+impl SessionOptions {
+    /// Add saved breakpoints that are not already requested; returns how many
+    /// were added
+    pub fn restore_breakpoints(&mut self, saved: Vec<SavedBreakpoint>) -> usize {
+        let mut restored = 0;
+        for bp in saved {
+            let duplicate = self
+                .breakpoints
+                .iter()
+                .any(|b| same_source_path(&b.source_path, &bp.source_path) && b.line == bp.line);
+            if !duplicate {
+                self.breakpoints.push(bp.into());
+                restored += 1;
+            }
+        }
+        restored
+    }
+
+    /// Adapter timeouts in effect for this session
+    fn timeouts(&self) -> AdapterTimeouts {
+        self.adapter_timeouts.unwrap_or_default()
+    }
+
+    /// Apply the options that live on the DAP client
+    fn configure_client(&self, client: &DapClient) {
+        if let Some(timeout) = self.request_timeout {
+            info!("⏱️  Using DAP request timeout of {:?}", timeout);
+            client.set_request_timeouts(RequestTimeouts::new(timeout));
+        }
+        client.set_initialize_timeout(self.timeouts().initialize);
+        if self.capture_dap_log {
+            info!("📼 Capturing DAP protocol log");
+            client.capture_dap_log(Arc::new(DapLog::default()));
+        }
+    }
+
+    /// Queue the initial breakpoints as pending on a freshly created session
+    async fn queue_initial_breakpoints(&self, session: &DebugSession) {
+        if !self.breakpoints.is_empty() {
+            info!(
+                "📌 Queueing {} initial breakpoint(s) for configuration",
+                self.breakpoints.len()
+            );
+        }
+        for bp in &self.breakpoints {
+            session
+                .add_pending_breakpoint(
+                    bp.source_path.clone(),
+                    SourceBreakpoint {
+                        line: bp.line,
+                        column: None,
+                        condition: bp.condition.clone(),
+                        hit_condition: None,
+                        log_message: bp.log_message.clone(),
+                    },
+                )
+                .await;
+        }
+    }
+}
+
+/// Working directory of a session started without `cwd`
+///
+/// Programs run from their own directory (Go package directories from
+/// themselves), so relative paths in the program and in breakpoint requests
+/// do not depend on where the server was started. Cargo targets run from the
+/// package root instead, and Python modules have no directory.
+fn default_cwd(language: &str, program: &str, options: &SessionOptions) -> Option<String> {
+    if options.python_module.is_some() || (language == "rust" && options.cargo_target.is_some()) {
+        return None;
+    }
+    let path = std::path::Path::new(program).canonicalize().ok()?;
+    let dir = if path.is_dir() {
+        path.as_path()
+    } else {
+        path.parent()?
+    };
+    dir.to_str().map(str::to_string)
+}
+
+/// How a session was started, so it can be started again
+#[derive(Debug, Clone)]
+struct SessionLaunch {
+    language: String,
+    program: String,
+    args: Vec<String>,
+    cwd: Option<String>,
+    stop_on_entry: bool,
+    options: SessionOptions,
+}
+
+/// Watch mode of one session (see [`super::file_watch`])
+struct FileWatch {
+    status: Arc<Mutex<FileWatchStatus>>,
+    task: JoinHandle<()>,
+}
+
+/// Capacity of the state change channel; slow subscribers skip older events
+const STATE_CHANGE_CAPACITY: usize = 256;
 
 /// Session Manager - manages multiple debug sessions
+///
+/// Clones share the same sessions, so background tasks (watch mode) can hold
+/// one.
+#[derive(Clone)]
 pub struct SessionManager {
     sessions: Arc<RwLock<HashMap<String, Arc<DebugSession>>>>,
+    state_changes: broadcast::Sender<StateChange>,
+    /// Sessions created or removed and breakpoint changes, for resource
+    /// subscriptions
+    resource_changes: broadcast::Sender<ResourceChange>,
+    /// Breakpoints saved when sessions are disconnected, per program
+    breakpoint_store: Arc<RwLock<BreakpointStore>>,
+    /// Named debugger_start configurations
+    config_store: Arc<RwLock<ConfigStore>>,
+    /// Adapter timeouts for sessions that do not set their own (otherwise the
+    /// language's defaults)
+    adapter_timeouts: Option<AdapterTimeouts>,
+    /// How each session was started, for restarts
+    launches: Arc<RwLock<HashMap<String, SessionLaunch>>>,
+    /// Sessions in watch mode
+    file_watches: Arc<RwLock<HashMap<String, FileWatch>>>,
+    /// Sessions being restarted
+    restarting: Arc<Mutex<HashSet<String>>>,
+    /// Held while an exclusive start looks for an existing session and
+    /// registers its own, so two such starts cannot both miss each other
+    exclusive_start: Arc<tokio::sync::Mutex<()>>,
+    /// Where transcripts of recorded sessions go
+    recording: RecordingConfig,
+    /// Record every session, not only those started with `record: true`
+    record_all: bool,
+    /// State file sessions are written to for adoption after a restart
+    /// (`--resume`)
+    session_state: Option<Arc<tokio::sync::Mutex<SessionStateFile>>>,
+    /// Sessions of the previous server that could not be adopted
+    unrecoverable: Arc<RwLock<Vec<UnrecoverableSession>>>,
+    /// Directories programs, working directories and sources must lie in
+    allowed_paths: AllowedPaths,
 }
 
 impl Default for SessionManager {
@@ -25,8 +407,253 @@ impl Default for SessionManager {
 
 impl SessionManager {
     pub fn new() -> Self {
+        Self::with_breakpoint_store(BreakpointStore::in_memory())
+    }
+
+    pub fn with_breakpoint_store(breakpoint_store: BreakpointStore) -> Self {
+        let (state_changes, _) = broadcast::channel(STATE_CHANGE_CAPACITY);
+        let (resource_changes, _) = broadcast::channel(STATE_CHANGE_CAPACITY);
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            state_changes,
+            resource_changes,
+            breakpoint_store: Arc::new(RwLock::new(breakpoint_store)),
+            config_store: Arc::new(RwLock::new(ConfigStore::default())),
+            adapter_timeouts: None,
+            launches: Arc::new(RwLock::new(HashMap::new())),
+            file_watches: Arc::new(RwLock::new(HashMap::new())),
+            restarting: Arc::new(Mutex::new(HashSet::new())),
+            exclusive_start: Arc::new(tokio::sync::Mutex::new(())),
+            recording: RecordingConfig::new(RecordingConfig::default_dir()),
+            record_all: false,
+            session_state: None,
+            unrecoverable: Arc::new(RwLock::new(Vec::new())),
+            allowed_paths: AllowedPaths::default(),
+        }
+    }
+
+    /// Use `timeouts` for sessions started without `adapterTimeoutMs`
+    pub fn with_adapter_timeouts(mut self, timeouts: AdapterTimeouts) -> Self {
+        self.adapter_timeouts = Some(timeouts);
+        self
+    }
+
+    /// Write transcripts as configured by `recording`: of every session with
+    /// `record_all` (`--record-dir`), otherwise of sessions started with
+    /// `record: true`
+    pub fn with_recording(mut self, recording: RecordingConfig, record_all: bool) -> Self {
+        self.recording = recording;
+        self.record_all = record_all;
+        self
+    }
+
+    /// Keep `file` up to date with the sessions, so that a restarted server
+    /// can adopt them with [`SessionManager::resume_sessions`]
+    ///
+    /// Adapters of sessions that can be adopted are left running on
+    /// [`SessionManager::shutdown`].
+    pub fn with_session_state(mut self, file: SessionStateFile) -> Self {
+        self.session_state = Some(Arc::new(tokio::sync::Mutex::new(file)));
+        self
+    }
+
+    /// Only debug programs, and set breakpoints in sources, within `allowed`
+    pub fn with_allowed_paths(mut self, allowed: AllowedPaths) -> Self {
+        self.allowed_paths = allowed;
+        self
+    }
+
+    /// Directories sessions are restricted to (unrestricted by default)
+    pub fn allowed_paths(&self) -> &AllowedPaths {
+        &self.allowed_paths
+    }
+
+    /// Reject a launch whose program, working directory, core dump or
+    /// initial breakpoints lie outside the allowed paths
+    fn check_allowed_paths(
+        &self,
+        program: Option<&str>,
+        cwd: Option<&str>,
+        options: &SessionOptions,
+    ) -> Result<()> {
+        if !self.allowed_paths.is_restricted() {
+            return Ok(());
+        }
+        let paths = program
+            .into_iter()
+            .chain(cwd)
+            .chain(options.core_dump.as_deref())
+            .chain(options.breakpoints.iter().map(|bp| bp.source_path.as_str()));
+        for path in paths {
+            self.allowed_paths.check(path)?;
+        }
+        Ok(())
+    }
+
+    /// Breakpoints saved for `program` by earlier sessions
+    pub async fn saved_breakpoints(&self, program: &str) -> Vec<SavedBreakpoint> {
+        self.breakpoint_store.read().await.get(program)
+    }
+
+    /// Saved breakpoints of every program, keyed by absolute program path
+    pub async fn all_saved_breakpoints(
+        &self,
+    ) -> std::collections::BTreeMap<String, Vec<SavedBreakpoint>> {
+        self.breakpoint_store.read().await.programs().clone()
+    }
+
+    /// Forget saved breakpoints of `program` (or all programs); returns how
+    /// many were removed
+    pub async fn clear_saved_breakpoints(&self, program: Option<&str>) -> Result<usize> {
+        self.breakpoint_store.write().await.clear(program)
+    }
+
+    /// Store a named debugger_start configuration; returns the one it replaced
+    pub async fn save_config(
+        &self,
+        name: &str,
+        config: serde_json::Map<String, serde_json::Value>,
+        source: ConfigSource,
+    ) -> Option<NamedConfig> {
+        self.config_store.write().await.save(name, config, source)
+    }
+
+    /// The configuration saved as `name`
+    pub async fn config(&self, name: &str) -> Option<NamedConfig> {
+        self.config_store.read().await.get(name).cloned()
+    }
+
+    /// Every named configuration, sorted by name
+    pub async fn list_configs(&self) -> Vec<NamedConfig> {
+        self.config_store.read().await.list()
+    }
+
+    /// Read the `.debugger-mcp.json` of `dir` (once); returns how many
+    /// configurations were added
+    pub async fn load_project_configs(&self, dir: &std::path::Path) -> Result<usize> {
+        self.config_store.write().await.load_project_file(dir)
+    }
+
+    /// The session's line breakpoints, sorted by file and line
+    async fn line_breakpoints(session: &DebugSession) -> Vec<SavedBreakpoint> {
+        let state = session.get_full_state().await;
+        let mut saved: Vec<SavedBreakpoint> = state
+            .breakpoints
+            .values()
+            .flatten()
+            .map(|bp| SavedBreakpoint {
+                source_path: bp.source_path.clone(),
+                line: bp.line,
+                condition: bp.condition.clone(),
+                log_message: bp.log_message.clone(),
+            })
+            .collect();
+        saved.sort_by(|a, b| (&a.source_path, a.line).cmp(&(&b.source_path, b.line)));
+        saved
+    }
+
+    /// Remember the session's line breakpoints for its program
+    ///
+    /// Sessions without breakpoints leave earlier saved ones untouched.
+    async fn save_breakpoints(&self, session: &DebugSession) {
+        let saved = Self::line_breakpoints(session).await;
+        if saved.is_empty() {
+            return;
+        }
+
+        info!(
+            "📌 Saving {} breakpoints for {}",
+            saved.len(),
+            session.program
+        );
+        if let Err(e) = self
+            .breakpoint_store
+            .write()
+            .await
+            .save(&session.program, saved)
+        {
+            warn!(
+                "⚠️  Failed to save breakpoints for {}: {}",
+                session.program, e
+            );
+        }
+    }
+
+    /// Receive a [`StateChange`] for every state transition of every session
+    pub fn subscribe_state_changes(&self) -> broadcast::Receiver<StateChange> {
+        self.state_changes.subscribe()
+    }
+
+    /// Receive a [`ResourceChange`] whenever a session is created or removed
+    /// or its breakpoints change
+    pub fn subscribe_resource_changes(&self) -> broadcast::Receiver<ResourceChange> {
+        self.resource_changes.subscribe()
+    }
+
+    /// Store a session under `session_id` and start publishing (and, when
+    /// recorded, writing) its state changes
+    async fn register_session(
+        &self,
+        session_id: &str,
+        session: &Arc<DebugSession>,
+        options: &SessionOptions,
+    ) {
+        let recorder = self.recorder(session_id, options.record);
+        let mut state = session.state.write().await;
+        state.attach_notifier(
+            StateNotifier::new(session_id.to_string(), self.state_changes.clone())
+                .with_resource_changes(self.resource_changes.clone()),
+        );
+        if let Some(recorder) = recorder {
+            state.attach_recorder(recorder);
+        }
+        state.touch();
+        drop(state);
+        self.sessions
+            .write()
+            .await
+            .insert(session_id.to_string(), Arc::clone(session));
+        self.persist_sessions().await;
+        // No receivers just means nobody is listening
+        let _ = self.resource_changes.send(ResourceChange::Sessions);
+
+        // Record the adapter version for bug reports (looked up once per language)
+        if session.state.read().await.adapter_version.is_none() {
+            let session = Arc::clone(session);
+            tokio::spawn(async move {
+                let language = session.language.clone();
+                let version =
+                    tokio::task::spawn_blocking(move || health::adapter_version(&language)).await;
+                if let Ok(Some(version)) = version {
+                    session.state.write().await.adapter_version = Some(version);
+                }
+            });
+        }
+    }
+
+    /// Transcript recorder for a new session, when it is recorded
+    ///
+    /// Failing to open the transcript does not fail the session.
+    fn recorder(&self, session_id: &str, record: bool) -> Option<Arc<SessionRecorder>> {
+        if !record && !self.record_all {
+            return None;
+        }
+        match SessionRecorder::create(&self.recording, session_id) {
+            Ok(recorder) => {
+                info!(
+                    "🎬 Recording session {} to {}",
+                    session_id,
+                    recorder.path().display()
+                );
+                Some(Arc::new(recorder))
+            }
+            Err(e) => {
+                warn!(
+                    "⚠️  Failed to open transcript for session {}: {}",
+                    session_id, e
+                );
+                None
+            }
         }
     }
 
@@ -38,6 +665,173 @@ impl SessionManager {
         cwd: Option<String>,
         stop_on_entry: bool,
     ) -> Result<String> {
+        self.create_session_with_options(
+            language,
+            program,
+            args,
+            cwd,
+            stop_on_entry,
+            SessionOptions::default(),
+        )
+        .await
+    }
+
+    pub async fn create_session_with_options(
+        &self,
+        language: &str,
+        program: String,
+        args: Vec<String>,
+        cwd: Option<String>,
+        stop_on_entry: bool,
+        options: SessionOptions,
+    ) -> Result<String> {
+        self.create_started_session(language, program, args, cwd, stop_on_entry, options)
+            .await
+            .map(|(session_id, _)| session_id)
+    }
+
+    /// [`SessionManager::create_session_with_options`], also returning how
+    /// the session was started
+    async fn create_started_session(
+        &self,
+        language: &str,
+        program: String,
+        args: Vec<String>,
+        cwd: Option<String>,
+        stop_on_entry: bool,
+        options: SessionOptions,
+    ) -> Result<(String, StartInfo)> {
+        let launch = SessionLaunch {
+            language: language.to_string(),
+            program: program.clone(),
+            args: args.clone(),
+            cwd: cwd.clone(),
+            stop_on_entry,
+            options: options.clone(),
+        };
+        let (session_id, mut info) = self
+            .spawn_session(language, program, args, cwd, stop_on_entry, options)
+            .await?;
+        self.launches
+            .write()
+            .await
+            .insert(session_id.clone(), launch);
+        if info.adapter_version.is_none() {
+            // Cached per language after the first lookup
+            let language = info.language.clone();
+            info.adapter_version =
+                tokio::task::spawn_blocking(move || health::adapter_version(&language))
+                    .await
+                    .ok()
+                    .flatten();
+        }
+        Ok((session_id, info))
+    }
+
+    /// Start a session unless `options.existing_session` says to fail on, or
+    /// reuse, a session already debugging `program`
+    pub async fn start_session(
+        &self,
+        language: &str,
+        program: String,
+        args: Vec<String>,
+        cwd: Option<String>,
+        stop_on_entry: bool,
+        options: SessionOptions,
+    ) -> Result<StartedSession> {
+        let _exclusive = match options.existing_session {
+            ExistingSession::Start => None,
+            policy => {
+                let guard = self.exclusive_start.lock().await;
+                if let Some(existing) = self.active_session_for(&program).await {
+                    if policy == ExistingSession::Reuse {
+                        info!("♻️  Reusing session {} for {}", existing, program);
+                        return Ok(StartedSession {
+                            session_id: existing,
+                            reused: true,
+                            info: None,
+                        });
+                    }
+                    return Err(Error::InvalidRequest(format!(
+                        "{} is already being debugged by session {}: use that session, start with reuseExisting, or disconnect it first",
+                        program, existing
+                    )));
+                }
+                Some(guard)
+            }
+        };
+        let (session_id, info) = self
+            .create_started_session(language, program, args, cwd, stop_on_entry, options)
+            .await?;
+        Ok(StartedSession {
+            session_id,
+            reused: false,
+            info: Some(info),
+        })
+    }
+
+    /// ID of a session debugging `program` (compared as canonical paths)
+    /// that has not terminated or failed
+    pub async fn active_session_for(&self, program: &str) -> Option<String> {
+        let program = BreakpointStore::program_key(program);
+        let sessions: Vec<_> = self
+            .sessions
+            .read()
+            .await
+            .iter()
+            .map(|(id, session)| (id.clone(), Arc::clone(session)))
+            .collect();
+        for (id, session) in sessions {
+            if !same_source_path(&BreakpointStore::program_key(&session.program), &program) {
+                continue;
+            }
+            match session.state.read().await.state {
+                DebugState::Terminated | DebugState::Failed { .. } => continue,
+                _ => return Some(id),
+            }
+        }
+        None
+    }
+
+    /// Adapter timeouts of a session of `language` that sets none itself
+    fn default_timeouts(&self, language: &str) -> AdapterTimeouts {
+        self.adapter_timeouts
+            .unwrap_or_else(|| AdapterTimeouts::for_language(language))
+    }
+
+    async fn spawn_session(
+        &self,
+        language: &str,
+        program: String,
+        args: Vec<String>,
+        cwd: Option<String>,
+        stop_on_entry: bool,
+        mut options: SessionOptions,
+    ) -> Result<(String, StartInfo)> {
+        // "golang" is accepted as an alias; sessions always report "go"
+        let language = match language {
+            "golang" => "go",
+            other => other,
+        };
+        options
+            .adapter_timeouts
+            .get_or_insert(self.default_timeouts(language));
+        let cwd = cwd.or_else(|| default_cwd(language, &program, &options));
+        // A Python module is looked up by the interpreter, not a path
+        let program_path = options.python_module.is_none().then_some(program.as_str());
+        self.check_allowed_paths(program_path, cwd.as_deref(), &options)?;
+        let mut info = StartInfo {
+            language: language.to_string(),
+            program: match &options.python_module {
+                Some(module) => module.clone(),
+                None => BreakpointStore::program_key(&program),
+            },
+            cwd: cwd.clone(),
+            stop_on_entry,
+            ..Default::default()
+        };
+        info.language_adapter(language, &options);
+
         // Type alias for STDIO adapter tuple: (command, args, adapter_id, launch_args, adapter_for_logging)
         type StdioAdapterTuple<'a> = (
             String,
@@ -59,20 +853,73 @@ impl SessionManager {
                     let cmd = PythonAdapter::command();
                     let adapter_args = PythonAdapter::args();
                     let adapter_id = PythonAdapter::adapter_id();
-                    let launch_args = PythonAdapter::launch_args_with_options(
+                    let mut launch_args = match &options.python_module {
+                        Some(module) => PythonAdapter::module_launch_args(
+                            module,
+                            &args,
+                            cwd.as_deref(),
+                            stop_on_entry,
+                        ),
+                        None => PythonAdapter::launch_args_with_options(
+                            &program,
+                            &args,
+                            cwd.as_deref(),
+                            stop_on_entry,
+                        ),
+                    };
+                    options.python_launch.apply(&mut launch_args);
+
+                    // Log transport initialization
+                    adapter.log_transport_init();
+
+                    (
+                        cmd,
+                        adapter_args,
+                        adapter_id,
+                        launch_args,
+                        Box::new(adapter),
+                    )
+                }
+                #[cfg(feature = "fake-adapter")]
+                "fake" => {
+                    use crate::adapters::fake::FakeAdapter;
+                    let adapter = FakeAdapter;
+                    adapter.log_selection();
+
+                    let launch_args = FakeAdapter::launch_args_with_options(
                         &program,
                         &args,
                         cwd.as_deref(),
                         stop_on_entry,
                     );
+                    adapter.log_transport_init();
 
-                    // Log transport initialization
+                    (
+                        FakeAdapter::command(),
+                        FakeAdapter::args(),
+                        FakeAdapter::adapter_id(),
+                        launch_args,
+                        Box::new(adapter),
+                    )
+                }
+                "bash" | "sh" => {
+                    let adapter = BashAdapter;
+                    adapter.log_selection();
+
+                    // STDIO like debugpy; the adapter's output events carry the
+                    // script's output (terminalKind: debugConsole)
+                    let launch_args = BashAdapter::launch_args_with_options(
+                        &program,
+                        &args,
+                        cwd.as_deref(),
+                        stop_on_entry,
+                    );
                     adapter.log_transport_init();
 
                     (
-                        cmd,
-                        adapter_args,
-                        adapter_id,
+                        BashAdapter::command(),
+                        BashAdapter::args(),
+                        BashAdapter::adapter_id(),
                         launch_args,
                         Box::new(adapter),
                     )
@@ -90,11 +937,19 @@ impl SessionManager {
                     // Ruby uses socket-based communication, not stdio
                     // Spawn rdbg and connect to socket
                     adapter.log_spawn_attempt();
-                    let ruby_session = RubyAdapter::spawn(&program, &args, stop_on_entry)
-                        .await
-                        .inspect_err(|e| {
-                            adapter.log_spawn_error(e);
-                        })?;
+                    let spawn_started = Instant::now();
+                    let ruby_session = RubyAdapter::spawn_with_options(
+                        &program,
+                        &args,
+                        stop_on_entry,
+                        cwd.as_deref(),
+                        &options.ruby_launch,
+                        &options.timeouts(),
+                    )
+                    .await
+                    .inspect_err(|e| {
+                        adapter.log_spawn_error(e);
+                    })?;
 
                     // Log successful connection with Ruby-specific details
                     ruby_session.log_connection_success_with_port();
@@ -108,23 +963,32 @@ impl SessionManager {
                     );
 
                     // Create DAP client from socket
-                    let client = DapClient::from_socket(ruby_session.socket)
+                    let client = DapClient::from_stream(ruby_session.socket)
                         .await
+                        .map(|client| client.with_adapter_process(ruby_session.process))
                         .inspect_err(|e| {
                             adapter.log_connection_error(e);
                         })?;
+                    options.configure_client(&client);
 
                     // Create session
-                    let session =
-                        DebugSession::new(language.to_string(), program.clone(), client).await?;
+                    let session = DebugSession::new(language.to_string(), program.clone(), client)
+                        .await?
+                        .with_entry_line(options.entry_line)
+                        .with_path_mapper(options.path_mapper.clone())
+                        .with_cwd(cwd.clone())
+                        .with_adapter_endpoint(Some(ruby_session.endpoint))
+                        .with_spawn_time(spawn_started.elapsed())
+                        .with_id(options.session_id.clone());
+                    options.queue_initial_breakpoints(&session).await;
+                    info.adapter_version = Some(ruby_session.version.clone());
+                    session.state.write().await.adapter_version = Some(ruby_session.version);
                     let session_id = session.id.clone();
 
                     // Store session immediately
                     let session_arc = Arc::new(session);
-                    {
-                        let mut sessions = self.sessions.write().await;
-                        sessions.insert(session_id.clone(), session_arc.clone());
-                    }
+                    self.register_session(&session_id, &session_arc, &options)
+                        .await;
 
                     // Log workaround application (Ruby requires entry breakpoint workaround)
                     adapter.log_workaround_applied();
@@ -135,7 +999,7 @@ impl SessionManager {
                             .initialize_and_launch_async(adapter_id.to_string(), launch_args),
                     );
 
-                    return Ok(session_id);
+                    return Ok((session_id, info));
                 }
                 "nodejs" => {
                     // Create adapter instance for logging
@@ -150,42 +1014,49 @@ impl SessionManager {
                     // Node.js uses socket-based communication with vscode-js-debug DAP server
                     // Spawn vscode-js-debug and connect to socket
                     adapter.log_spawn_attempt();
+                    let spawn_started = Instant::now();
                     let nodejs_session =
-                        NodeJsAdapter::spawn_dap_server().await.inspect_err(|e| {
-                            adapter.log_spawn_error(e);
-                        })?;
+                        NodeJsAdapter::spawn_dap_server_with_timeouts(&options.timeouts())
+                            .await
+                            .inspect_err(|e| {
+                                adapter.log_spawn_error(e);
+                            })?;
 
                     // Log successful connection with Node.js-specific details
                     nodejs_session.log_connection_success_with_details();
 
                     let adapter_id = NodeJsAdapter::adapter_id();
-                    let launch_args = NodeJsAdapter::launch_config(
+                    info.adapter_command = nodejs_session.dap_server_path.clone();
+                    let mut launch_args = NodeJsAdapter::launch_config(
                         &program,
                         &args,
                         cwd.as_deref(),
                         stop_on_entry,
                     );
+                    options.node_launch.apply(&mut launch_args);
 
                     // Create DAP client from socket (parent session)
                     let parent_client = DapClient::from_socket(nodejs_session.socket)
                         .await
+                        .map(|client| client.with_adapter_process(nodejs_session.process))
                         .inspect_err(|e| {
                             adapter.log_connection_error(e);
                         })?;
+                    options.configure_client(&parent_client);
 
                     info!("🔄 [NODEJS] Creating multi-session manager for parent session");
 
                     // Create session with multi-session mode
-                    use super::multi_session::MultiSessionManager;
-                    use super::session::SessionMode;
-
-                    let session_id = uuid::Uuid::new_v4().to_string();
+                    let session_id = options
+                        .session_id
+                        .clone()
+                        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
                     let multi_session_manager = MultiSessionManager::new(session_id.clone());
 
                     let session_mode = SessionMode::MultiSession {
                         parent_client: Arc::new(RwLock::new(parent_client)),
                         multi_session_manager: multi_session_manager.clone(),
-                        vscode_js_debug_port: nodejs_session.port,
+                        adapter_port: nodejs_session.port,
                     };
 
                     let session = DebugSession::new_with_mode(
@@ -193,35 +1064,27 @@ impl SessionManager {
                         program.clone(),
                         session_mode,
                     )
-                    .await?;
+                    .await?
+                    .with_entry_line(options.entry_line)
+                    .with_path_mapper(options.path_mapper.clone())
+                    .with_cwd(cwd.clone())
+                    .with_adapter_port(Some(nodejs_session.port))
+                    .with_spawn_time(spawn_started.elapsed())
+                    .with_id(Some(session_id.clone()));
+                    options.queue_initial_breakpoints(&session).await;
 
                     // Store session immediately
                     let session_arc = Arc::new(session);
-                    {
-                        let mut sessions = self.sessions.write().await;
-                        sessions.insert(session_id.clone(), session_arc.clone());
-                    }
+                    self.register_session(&session_id, &session_arc, &options)
+                        .await;
 
                     // Register child session spawn callback on parent client
                     info!("🔄 [NODEJS] Registering child session spawn callback");
-                    let session_clone = session_arc.clone();
                     if let SessionMode::MultiSession { parent_client, .. } =
                         &session_arc.session_mode
                     {
                         let parent = parent_client.read().await;
-                        parent
-                        .on_child_session_spawn(move |target_id| {
-                            let session = session_clone.clone();
-                            Box::pin(async move {
-                                info!("🎯 [NODEJS] Child session spawn callback invoked for target_id: {}", target_id);
-                                if let Err(e) = session.spawn_child_session(target_id).await {
-                                    error!("❌ [NODEJS] Failed to spawn child session: {}", e);
-                                } else {
-                                    info!("✅ [NODEJS] Child session spawned successfully");
-                                }
-                            })
-                        })
-                        .await;
+                        session_arc.register_child_spawner(&parent).await;
                     }
 
                     // Log workaround application (Node.js uses multi-session for stopOnEntry)
@@ -234,7 +1097,7 @@ impl SessionManager {
                             .initialize_and_launch_async(adapter_id.to_string(), launch_args),
                     );
 
-                    return Ok(session_id);
+                    return Ok((session_id, info));
                 }
                 "go" => {
                     // Create adapter instance for logging
@@ -249,11 +1112,17 @@ impl SessionManager {
                     // Go uses socket-based communication with Delve DAP server
                     // Spawn dlv dap and connect to socket
                     adapter.log_spawn_attempt();
-                    let go_session = GoAdapter::spawn(&program, &args, stop_on_entry)
-                        .await
-                        .inspect_err(|e| {
-                            adapter.log_spawn_error(e);
-                        })?;
+                    let spawn_started = Instant::now();
+                    let go_session = GoAdapter::spawn_with_timeouts(
+                        &program,
+                        &args,
+                        stop_on_entry,
+                        &options.timeouts(),
+                    )
+                    .await
+                    .inspect_err(|e| {
+                        adapter.log_spawn_error(e);
+                    })?;
 
                     // Log successful connection with Go-specific details
                     go_session.log_connection_success_with_port();
@@ -269,21 +1138,28 @@ impl SessionManager {
                     // Create DAP client from socket
                     let client = DapClient::from_socket(go_session.socket)
                         .await
+                        .map(|client| client.with_adapter_process(go_session.process))
                         .inspect_err(|e| {
                             adapter.log_connection_error(e);
                         })?;
+                    options.configure_client(&client);
 
                     // Create session
-                    let session =
-                        DebugSession::new(language.to_string(), program.clone(), client).await?;
+                    let session = DebugSession::new(language.to_string(), program.clone(), client)
+                        .await?
+                        .with_entry_line(options.entry_line)
+                        .with_path_mapper(options.path_mapper.clone())
+                        .with_cwd(cwd.clone())
+                        .with_adapter_port(Some(go_session.port))
+                        .with_spawn_time(spawn_started.elapsed())
+                        .with_id(options.session_id.clone());
+                    options.queue_initial_breakpoints(&session).await;
                     let session_id = session.id.clone();
 
                     // Store session immediately
                     let session_arc = Arc::new(session);
-                    {
-                        let mut sessions = self.sessions.write().await;
-                        sessions.insert(session_id.clone(), session_arc.clone());
-                    }
+                    self.register_session(&session_id, &session_arc, &options)
+                        .await;
 
                     // Log workaround application (if any Go-specific workarounds needed)
                     adapter.log_workaround_applied();
@@ -294,7 +1170,7 @@ impl SessionManager {
                             .initialize_and_launch_async(adapter_id.to_string(), launch_args),
                     );
 
-                    return Ok(session_id);
+                    return Ok((session_id, info));
                 }
                 "rust" => {
                     // Create adapter instance for logging
@@ -303,26 +1179,84 @@ impl SessionManager {
                     // Log adapter selection
                     adapter.log_selection();
 
-                    // Determine if program is a source file or already-compiled binary
-                    let binary_path = if program.ends_with(".rs") {
-                        // Source file - need to compile
-                        info!("🔨 [RUST] Compiling Rust source before debugging");
+                    let mut program_args = args.clone();
+                    let mut launch_cwd = cwd.clone();
 
-                        RustAdapter::log_compilation_start(&program, false); // false = debug build
-                        let binary_path =
-                            RustAdapter::compile(&program, false)
+                    // Determine if program is a source file or already-compiled binary
+                    let mut rust_single_file = false;
+                    let compiled = if options.core_dump.is_some() {
+                        // The core belongs to an existing binary: never rebuild it
+                        info!("💥 [RUST] Loading core dump of binary: {}", program);
+                        None
+                    } else if let Some(target) = &options.cargo_target {
+                        // Explicit Cargo target: build it from the project root
+                        let cargo_root = RustAdapter::cargo_root(&program)?;
+                        RustAdapter::log_compilation_start(&program, false);
+                        let compiled = match target {
+                            CargoTargetType::Test => {
+                                let (compiled, test_args) = RustAdapter::compile_cargo_test(
+                                    &cargo_root,
+                                    options.test_filter.as_deref(),
+                                )
                                 .await
                                 .inspect_err(|e| {
                                     RustAdapter::log_compilation_error(e);
                                 })?;
+                                // The test name goes before any user-provided arguments
+                                program_args = test_args.into_iter().chain(args.clone()).collect();
+                                compiled
+                            }
+                            // The binary owning the source file, not the workspace's first
+                            CargoTargetType::Binary => RustAdapter::compile_with_options(
+                                &program,
+                                false,
+                                options.force_rebuild,
+                            )
+                            .await
+                            .inspect_err(|e| {
+                                RustAdapter::log_compilation_error(e);
+                            })?,
+                            target => RustAdapter::compile_cargo_target(&cargo_root, target, false)
+                                .await
+                                .inspect_err(|e| {
+                                    RustAdapter::log_compilation_error(e);
+                                })?,
+                        };
+                        RustAdapter::log_compilation_success(&compiled.binary);
+
+                        // cargo runs tests and binaries from the package root
+                        launch_cwd.get_or_insert(cargo_root);
+                        Some(compiled)
+                    } else if program.ends_with(".rs") {
+                        // Source file - need to compile (skipped when up to date)
+                        info!("🔨 [RUST] Compiling Rust source before debugging");
+                        rust_single_file = matches!(
+                            RustAdapter::detect_project_type(&program),
+                            Ok(RustProjectType::SingleFile(_))
+                        );
 
-                        RustAdapter::log_compilation_success(&binary_path);
-                        binary_path
+                        RustAdapter::log_compilation_start(&program, false); // false = debug build
+                        let compiled = RustAdapter::compile_with_options(
+                            &program,
+                            false,
+                            options.force_rebuild,
+                        )
+                        .await
+                        .inspect_err(|e| {
+                            RustAdapter::log_compilation_error(e);
+                        })?;
+
+                        RustAdapter::log_compilation_success(&compiled.binary);
+                        Some(compiled)
                     } else {
                         // Assume it's already a compiled binary
                         info!("🎯 [RUST] Using pre-compiled binary: {}", program);
-                        program.clone()
+                        None
                     };
+                    let binary_path = compiled
+                        .as_ref()
+                        .map(|compiled| compiled.binary.clone())
+                        .unwrap_or_else(|| program.clone());
 
                     // Log transport initialization
                     adapter.log_transport_init();
@@ -330,41 +1264,67 @@ impl SessionManager {
                     // Step 2: Spawn CodeLLDB in TCP mode (like Ruby/Node.js/Go)
                     // Based on nvim-dap: CodeLLDB uses TCP mode with --port argument
                     adapter.log_spawn_attempt();
-                    let rust_session = RustAdapter::spawn(&binary_path, &args, stop_on_entry)
-                        .await
-                        .inspect_err(|e| {
-                            adapter.log_spawn_error(e);
-                        })?;
+                    let spawn_started = Instant::now();
+                    let rust_session = RustAdapter::spawn_with_timeouts(
+                        &binary_path,
+                        &program_args,
+                        stop_on_entry,
+                        &options.timeouts(),
+                    )
+                    .await
+                    .inspect_err(|e| {
+                        adapter.log_spawn_error(e);
+                    })?;
 
                     // Log successful connection with Rust-specific details
                     rust_session.log_connection_success_with_port();
 
                     let adapter_id = RustAdapter::adapter_id();
-                    let launch_args = RustAdapter::launch_args(
+                    info.cwd = launch_cwd.clone();
+                    info.compiled = compiled.is_some();
+                    info.rust_project = Some(rust_project(
+                        compiled.as_ref(),
+                        rust_single_file,
+                        options.cargo_target.as_ref(),
+                    ));
+                    let mut launch_args = RustAdapter::launch_args(
                         &binary_path, // Use compiled binary path, not source
-                        &args,
-                        cwd.as_deref(),
+                        &program_args,
+                        launch_cwd.as_deref(),
                         stop_on_entry,
                     );
+                    if let Some(core_dump) = &options.core_dump {
+                        launch_args = RustAdapter::core_dump_args(&binary_path, core_dump);
+                    }
+                    options.lldb_launch.apply(&mut launch_args);
 
                     // Create DAP client from socket (like Ruby/Go)
                     let client = DapClient::from_socket(rust_session.socket)
                         .await
+                        .map(|client| client.with_adapter_process(rust_session.process))
                         .inspect_err(|e| {
                             adapter.log_connection_error(e);
                         })?;
+                    options.configure_client(&client);
 
                     // Create session
-                    let session =
-                        DebugSession::new(language.to_string(), program.clone(), client).await?;
+                    let session = DebugSession::new(language.to_string(), program.clone(), client)
+                        .await?
+                        .with_entry_line(options.entry_line)
+                        .with_path_mapper(options.path_mapper.clone())
+                        .with_cwd(launch_cwd.clone())
+                        .with_adapter_port(Some(rust_session.port))
+                        .with_spawn_time(spawn_started.elapsed())
+                        .with_id(options.session_id.clone())
+                        .with_build(compiled)
+                        .with_core_dump(options.core_dump.clone());
+                    options.queue_initial_breakpoints(&session).await;
                     let session_id = session.id.clone();
 
                     // Store session immediately
                     let session_arc = Arc::new(session);
-                    {
-                        let mut sessions = self.sessions.write().await;
-                        sessions.insert(session_id.clone(), session_arc.clone());
-                    }
+                    self.register_session(&session_id, &session_arc, &options)
+                        .await;
 
                     // Log workaround application (Rust doesn't require workarounds)
                     adapter.log_workaround_applied();
@@ -375,7 +1335,94 @@ impl SessionManager {
                             .initialize_and_launch_async(adapter_id.to_string(), launch_args),
                     );
 
-                    return Ok(session_id);
+                    return Ok((session_id, info));
+                }
+                "c" | "cpp" => {
+                    let c_language = CLanguage::from_language(language)
+                        .ok_or_else(|| Error::AdapterNotFound(language.to_string()))?;
+                    let adapter = CppAdapter::new(c_language);
+                    adapter.log_selection();
+
+                    // Step 1: Compile with gcc/clang, like rustc for Rust (a core
+                    // dump is loaded with the binary that produced it instead)
+                    let compiled = match &options.core_dump {
+                        Some(_) => None,
+                        None => Some(
+                            CppAdapter::compile_with_outcome(&program, c_language)
+                                .await
+                                .inspect_err(|e| {
+                                    CppAdapter::log_compilation_error(e);
+                                })?,
+                        ),
+                    };
+                    let binary_path = compiled
+                        .as_ref()
+                        .map(|compiled| compiled.binary.clone())
+                        .unwrap_or_else(|| program.clone());
+
+                    // Step 2: Same CodeLLDB TCP setup as Rust
+                    adapter.log_transport_init();
+                    adapter.log_spawn_attempt();
+                    let spawn_started = Instant::now();
+                    let lldb_session = RustAdapter::spawn_with_timeouts(
+                        &binary_path,
+                        &args,
+                        stop_on_entry,
+                        &options.timeouts(),
+                    )
+                    .await
+                    .inspect_err(|e| {
+                        adapter.log_spawn_error(e);
+                    })?;
+                    lldb_session.log_connection_success_with_port();
+
+                    let adapter_id = CppAdapter::adapter_id();
+                    let mut launch_args = RustAdapter::launch_args(
+                        &binary_path,
+                        &args,
+                        cwd.as_deref(),
+                        stop_on_entry,
+                    );
+                    if let Some(core_dump) = &options.core_dump {
+                        launch_args = RustAdapter::core_dump_args(&binary_path, core_dump);
+                    }
+                    // C and C++ share CodeLLDB's C++ language support
+                    launch_args["sourceLanguages"] = serde_json::json!(["cpp"]);
+                    options.lldb_launch.apply(&mut launch_args);
+
+                    let client = DapClient::from_socket(lldb_session.socket)
+                        .await
+                        .map(|client| client.with_adapter_process(lldb_session.process))
+                        .inspect_err(|e| {
+                            adapter.log_connection_error(e);
+                        })?;
+                    options.configure_client(&client);
+
+                    let session = DebugSession::new(language.to_string(), program.clone(), client)
+                        .await?
+                        .with_entry_line(options.entry_line)
+                        .with_path_mapper(options.path_mapper.clone())
+                        .with_cwd(cwd.clone())
+                        .with_adapter_port(Some(lldb_session.port))
+                        .with_spawn_time(spawn_started.elapsed())
+                        .with_id(options.session_id.clone())
+                        .with_build(compiled)
+                        .with_core_dump(options.core_dump.clone());
+                    options.queue_initial_breakpoints(&session).await;
+                    let session_id = session.id.clone();
+
+                    let session_arc = Arc::new(session);
+                    self.register_session(&session_id, &session_arc, &options)
+                        .await;
+
+                    adapter.log_workaround_applied();
+
+                    tokio::spawn(
+                        session_arc
+                            .initialize_and_launch_async(adapter_id.to_string(), launch_args),
+                    );
+
+                    return Ok((session_id, info));
                 }
                 _ => return Err(Error::AdapterNotFound(language.to_string())),
             };
@@ -383,24 +1430,42 @@ impl SessionManager {
         // Spawn DAP client (Python path - uses STDIO transport)
         // Adapter instance is passed from match arm above for language-specific logging
         adapter.log_spawn_attempt();
+        let spawn_started = Instant::now();
         let client = DapClient::spawn(&command, &adapter_args)
             .await
             .inspect_err(|e| {
                 adapter.log_spawn_error(e);
             })?;
+        options.configure_client(&client);
 
         // Log successful connection
         adapter.log_connection_success();
 
         // Create session
-        let session = DebugSession::new(language.to_string(), program, client).await?;
+        let session = if language == "python" && options.python_launch.sub_process {
+            DebugSession::new_following_subprocesses(program, client).await?
+        } else {
+            DebugSession::new(language.to_string(), program, client).await?
+        };
+        let session = session
+            .with_entry_line(options.entry_line)
+            .with_path_mapper(options.path_mapper.clone())
+            .with_cwd(cwd.clone())
+            .with_spawn_time(spawn_started.elapsed())
+            .with_id(options.session_id.clone());
+        options.queue_initial_breakpoints(&session).await;
         let session_id = session.id.clone();
 
         // Store session immediately
         let session_arc = Arc::new(session);
-        {
-            let mut sessions = self.sessions.write().await;
-            sessions.insert(session_id.clone(), session_arc.clone());
+        self.register_session(&session_id, &session_arc, &options)
+            .await;
+
+        if let SessionMode::MultiSession { parent_client, .. } = &session_arc.session_mode {
+            info!("🔄 [PYTHON] Following subprocesses (debugpyAttach)");
+            session_arc
+                .register_child_spawner(&*parent_client.read().await)
+                .await;
         }
 
         // Log workaround if needed (Python doesn't require workarounds)
@@ -409,6 +1474,65 @@ impl SessionManager {
         // Initialize and launch in the background
         tokio::spawn(session_arc.initialize_and_launch_async(adapter_id.to_string(), launch_args));
 
+        Ok((session_id, info))
+    }
+
+    /// Attach to a program that is already running under a debug server
+    ///
+    /// Only Python is supported: the program is started with
+    /// `python -m debugpy --listen host:port`, which makes debugpy accept DAP
+    /// connections itself. The session's program is reported as "host:port".
+    pub async fn attach_session(
+        &self,
+        language: &str,
+        host: &str,
+        port: u16,
+        path_mappings: Vec<PathMapping>,
+        mut options: SessionOptions,
+    ) -> Result<String> {
+        if language != "python" {
+            return Err(Error::InvalidRequest(format!(
+                "Attach is only supported for Python (debugpy), not '{}'",
+                language
+            )));
+        }
+
+        self.check_allowed_paths(None, None, &options)?;
+
+        let adapter = PythonAdapter;
+        info!("🐍 [PYTHON] Attaching to debugpy at {}:{}", host, port);
+
+        // A remote debug server gets at least 5s to accept the connection
+        let timeouts = *options
+            .adapter_timeouts
+            .get_or_insert(self.default_timeouts(language));
+        let timeouts = AdapterTimeouts {
+            connect: timeouts.connect.max(Duration::from_secs(5)),
+            ..timeouts
+        };
+        let socket = connect_host_with_timeouts(host, port, &timeouts)
+            .await
+            .inspect_err(|e| adapter.log_connection_error(e))?;
+        let client = DapClient::from_socket(socket)
+            .await
+            .inspect_err(|e| adapter.log_connection_error(e))?;
+        options.configure_client(&client);
+
+        let session =
+            DebugSession::new(language.to_string(), format!("{}:{}", host, port), client).await?;
+        options.queue_initial_breakpoints(&session).await;
+        let session_id = session.id.clone();
+
+        let session_arc = Arc::new(session);
+        self.register_session(&session_id, &session_arc, &options)
+            .await;
+
+        let attach_args = PythonAdapter::attach_args(host, port, &path_mappings);
+        tokio::spawn(
+            session_arc
+                .initialize_and_launch_async(PythonAdapter::adapter_id().to_string(), attach_args),
+        );
+
         Ok(session_id)
     }
 
@@ -420,6 +1544,13 @@ impl SessionManager {
             .ok_or_else(|| Error::SessionNotFound(session_id.to_string()))
     }
 
+    /// Record activity on a session (a tool call), if it still exists
+    pub async fn touch_session(&self, session_id: &str) {
+        if let Ok(session) = self.get_session(session_id).await {
+            session.state.write().await.touch();
+        }
+    }
+
     pub async fn get_session_state(
         &self,
         session_id: &str,
@@ -433,25 +1564,697 @@ impl SessionManager {
         sessions.keys().cloned().collect()
     }
 
+    /// Register an already constructed session (used by tests)
+    #[cfg(test)]
+    pub(crate) async fn insert_session(&self, session: Arc<DebugSession>) -> String {
+        let session_id = session.id.clone();
+        self.register_session(&session_id, &session, &SessionOptions::default())
+            .await;
+        session_id
+    }
+
+    /// Restart a session started by [`SessionManager::create_session_with_options`]
+    /// under the same ID
+    ///
+    /// The debuggee is terminated, Rust and C/C++ programs are rebuilt when
+    /// their sources changed, and the program is launched again with the
+    /// session's current line breakpoints. Subscribers get a state change
+    /// carrying `reason`.
+    pub async fn restart_session(&self, session_id: &str, reason: &str) -> Result<()> {
+        let launch = self
+            .launches
+            .read()
+            .await
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| {
+                Error::InvalidState(format!(
+                    "Session {} cannot be restarted: it was not started by debugger_start",
+                    session_id
+                ))
+            })?;
+        if !self
+            .restarting
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(session_id.to_string())
+        {
+            return Err(Error::InvalidState(format!(
+                "A restart of session {} is already in progress",
+                session_id
+            )));
+        }
+
+        let result = self.relaunch(session_id, launch, reason).await;
+        self.restarting
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(session_id);
+        result
+    }
+
+    async fn relaunch(&self, session_id: &str, launch: SessionLaunch, reason: &str) -> Result<()> {
+        info!("🔄 Restarting session {} ({})", session_id, reason);
+        let mut options = launch.options;
+        options.session_id = Some(session_id.to_string());
+
+        // After a failed restart there is no session left to take over from
+        let old_state = match self.get_session(session_id).await {
+            Ok(old) => {
+                options.breakpoints = Self::line_breakpoints(&old)
+                    .await
+                    .into_iter()
+                    .map(Into::into)
+                    .collect();
+                let old_state = old.get_state().await;
+                if let Err(e) = old.disconnect().await {
+                    warn!("⚠️  Failed to disconnect session {}: {}", session_id, e);
+                }
+                self.sessions.write().await.remove(session_id);
+                old_state
+            }
+            Err(_) => DebugState::Terminated,
+        };
+
+        self.spawn_session(
+            &launch.language,
+            launch.program,
+            launch.args,
+            launch.cwd,
+            launch.stop_on_entry,
+            options,
+        )
+        .await?;
+
+        let new_state = self.get_session(session_id).await?.get_state().await;
+        // No receivers just means nobody is listening
+        let _ = self.state_changes.send(StateChange {
+            session_id: session_id.to_string(),
+            old_state,
+            new_state,
+            stop: None,
+            reason: Some(reason.to_string()),
+        });
+        info!("✅ Session {} restarted", session_id);
+        Ok(())
+    }
+
+    /// Restart the session whenever `paths` change (watch mode)
+    ///
+    /// Changes are only acted on while the program is running or paused; a
+    /// session whose last restart failed is restarted again on the next
+    /// change, so fixing a build error is enough to get it back.
+    pub async fn watch_session(&self, session_id: &str, paths: Vec<String>) -> Result<()> {
+        self.get_session(session_id).await?;
+        for path in &paths {
+            self.allowed_paths.check(path)?;
+        }
+        self.unwatch_session(session_id).await;
+
+        info!(
+            "👀 Watching {} file(s) to restart session {}",
+            paths.len(),
+            session_id
+        );
+        let status = Arc::new(Mutex::new(FileWatchStatus {
+            enabled: true,
+            paths: paths.clone(),
+            ..Default::default()
+        }));
+        let manager = self.clone();
+        let id = session_id.to_string();
+        let task_status = Arc::clone(&status);
+        // Changes count from now, not from when the task first runs
+        let mut detector = ChangeDetector::new(paths, WATCH_DEBOUNCE);
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(WATCH_POLL_INTERVAL).await;
+                if !detector.poll() {
+                    continue;
+                }
+                let restart = match manager.get_session(&id).await {
+                    Ok(session) => match session.get_state().await {
+                        DebugState::Running | DebugState::Stopped { .. } => true,
+                        // Still starting: act on the change once it runs
+                        DebugState::NotStarted
+                        | DebugState::Initializing
+                        | DebugState::Initialized
+                        | DebugState::Launching => continue,
+                        DebugState::Terminated | DebugState::Failed { .. } => false,
+                    },
+                    Err(_) => true,
+                };
+                if restart {
+                    let result = manager.restart_session(&id, RESTART_REASON).await;
+                    let mut status = task_status.lock().unwrap_or_else(|e| e.into_inner());
+                    match result {
+                        Ok(()) => {
+                            status.restarts += 1;
+                            status.last_restart_ms = SystemTime::now()
+                                .duration_since(UNIX_EPOCH)
+                                .ok()
+                                .map(|d| d.as_millis() as u64);
+                            status.last_error = None;
+                        }
+                        Err(e) => {
+                            warn!("⚠️  Watch mode failed to restart session {}: {}", id, e);
+                            status.last_error = Some(e.to_string());
+                        }
+                    }
+                }
+                detector.reset();
+            }
+        });
+        self.file_watches
+            .write()
+            .await
+            .insert(session_id.to_string(), FileWatch { status, task });
+        Ok(())
+    }
+
+    /// Stop watching the session's files; returns whether it was watched
+    pub async fn unwatch_session(&self, session_id: &str) -> bool {
+        match self.file_watches.write().await.remove(session_id) {
+            Some(watch) => {
+                watch.task.abort();
+                info!("👀 Stopped watching the files of session {}", session_id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Watch mode of a session (`enabled: false` when it is not watched)
+    pub async fn watch_status(&self, session_id: &str) -> Result<FileWatchStatus> {
+        let restarting = self
+            .restarting
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(session_id);
+        match self.file_watches.read().await.get(session_id) {
+            Some(watch) => {
+                let mut status = watch
+                    .status
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .clone();
+                status.restarting = restarting;
+                Ok(status)
+            }
+            None => {
+                self.get_session(session_id).await?;
+                Ok(FileWatchStatus::default())
+            }
+        }
+    }
+
     pub async fn remove_session(&self, session_id: &str) -> Result<()> {
-        // Disconnect the session first
+        self.unwatch_session(session_id).await;
+        self.launches.write().await.remove(session_id);
+
+        // Save breakpoints, then disconnect the session
         if let Ok(session) = self.get_session(session_id).await {
+            self.save_breakpoints(&session).await;
             let _ = session.disconnect().await;
         }
 
-        let mut sessions = self.sessions.write().await;
-        sessions
+        self.sessions
+            .write()
+            .await
             .remove(session_id)
             .ok_or_else(|| Error::SessionNotFound(session_id.to_string()))?;
+        self.persist_sessions().await;
+        let _ = self.resource_changes.send(ResourceChange::Sessions);
+
+        Ok(())
+    }
+
+    /// End every session, on server shutdown
+    ///
+    /// Breakpoints are saved and the sessions disconnected concurrently (each
+    /// with the usual 2s timeout); adapter processes that are still running
+    /// afterwards are killed so none are left orphaned. With a session state
+    /// file, sessions that a restarted server can adopt are left running
+    /// instead.
+    pub async fn shutdown(&self) {
+        let session_ids = self.list_sessions().await;
+        if session_ids.is_empty() {
+            return;
+        }
+        info!("🛑 Shutting down {} session(s)", session_ids.len());
+
+        let mut sessions = Vec::new();
+        let mut detached = Vec::new();
+        for session_id in &session_ids {
+            self.unwatch_session(session_id).await;
+            self.launches.write().await.remove(session_id);
+            if let Ok(session) = self.get_session(session_id).await {
+                if self.session_state.is_some() && Self::adoptable(&session) {
+                    detached.push(session_id.clone());
+                    continue;
+                }
+                self.save_breakpoints(&session).await;
+                sessions.push(session);
+            }
+        }
 
+        let mut disconnects = tokio::task::JoinSet::new();
+        for session in &sessions {
+            let session = Arc::clone(session);
+            disconnects.spawn(async move { session.disconnect().await });
+        }
+        while let Some(result) = disconnects.join_next().await {
+            if let Ok(Err(e)) = result {
+                warn!("⚠️  Disconnect during shutdown failed: {}", e);
+            }
+        }
+
+        for session in &sessions {
+            session.kill_adapter().await;
+        }
+        let mut registered = self.sessions.write().await;
+        for session in &sessions {
+            registered.remove(&session.id);
+        }
+        drop(registered);
+
+        // The state file keeps listing the sessions left running
+        self.persist_sessions().await;
+        if !detached.is_empty() {
+            info!(
+                "💾 Leaving {} session(s) running for --resume",
+                detached.len()
+            );
+        }
+        let mut registered = self.sessions.write().await;
+        for session_id in &detached {
+            registered.remove(session_id);
+        }
+    }
+
+    /// Configuration that attaches a new connection to the session's running
+    /// program, for adapters that keep running it without a client
+    fn reattach_args(session: &DebugSession) -> Option<serde_json::Value> {
+        match session.language.as_str() {
+            "ruby" => Some(RubyAdapter::attach_args()),
+            "rust" | "c" | "cpp" if session.core_dump().is_none() => {
+                let binary = session
+                    .build()
+                    .map(|build| build.binary.clone())
+                    .unwrap_or_else(|| session.program.clone());
+                let mut attach = RustAdapter::attach_args(&binary);
+                if session.language != "rust" {
+                    attach["sourceLanguages"] = serde_json::json!(["cpp"]);
+                }
+                Some(attach)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether a restarted server can adopt the session: its adapter listens
+    /// on a socket and can attach to the running program again
+    fn adoptable(session: &DebugSession) -> bool {
+        session.adapter_endpoint().is_some() && Self::reattach_args(session).is_some()
+    }
+
+    /// Rewrite the session state file, if there is one
+    pub async fn persist_sessions(&self) {
+        let Some(file) = &self.session_state else {
+            return;
+        };
+        let sessions: Vec<Arc<DebugSession>> =
+            self.sessions.read().await.values().cloned().collect();
+        let mut persisted = Vec::new();
+        for session in sessions {
+            persisted.push(PersistedSession {
+                id: session.id.clone(),
+                language: session.language.clone(),
+                program: session.program.clone(),
+                adapter_port: session.adapter_port(),
+                adapter_socket: match session.adapter_endpoint() {
+                    #[cfg(unix)]
+                    Some(AdapterEndpoint::Unix(path)) => Some(path.clone()),
+                    _ => None,
+                },
+                attach: Self::reattach_args(&session),
+                breakpoints: Self::line_breakpoints(&session).await,
+            });
+        }
+        persisted.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let file = file.lock().await;
+        if let Err(e) = file.write(persisted) {
+            warn!(
+                "⚠️  Failed to write session state {}: {}",
+                file.path().display(),
+                e
+            );
+        }
+    }
+
+    /// Adopt the sessions of a previous server listed in the state file
+    ///
+    /// Socket-mode adapters that still accept connections are reconnected,
+    /// attached to the running program and given the session's breakpoints
+    /// again, under the same session ID. The other sessions are reported by
+    /// [`SessionManager::unrecoverable_sessions`]. Returns the IDs of the
+    /// adopted sessions.
+    pub async fn resume_sessions(&self) -> Result<Vec<String>> {
+        let Some(file) = &self.session_state else {
+            return Ok(Vec::new());
+        };
+        let persisted = file.lock().await.load()?;
+
+        let mut adopted = Vec::new();
+        let mut unrecoverable = Vec::new();
+        for session in persisted {
+            match self.adopt_session(&session).await {
+                Ok(()) => {
+                    info!(
+                        "♻️  Adopted {} session {} ({})",
+                        session.language, session.id, session.program
+                    );
+                    adopted.push(session.id);
+                }
+                Err(reason) => {
+                    warn!(
+                        "⚠️  Cannot adopt {} session {}: {}",
+                        session.language, session.id, reason
+                    );
+                    unrecoverable.push(UnrecoverableSession {
+                        id: session.id,
+                        language: session.language,
+                        program: session.program,
+                        reason,
+                    });
+                }
+            }
+        }
+        *self.unrecoverable.write().await = unrecoverable;
+        self.persist_sessions().await;
+        Ok(adopted)
+    }
+
+    /// Reconnect to the adapter of a persisted session; the error is the
+    /// reason it cannot be adopted
+    async fn adopt_session(&self, persisted: &PersistedSession) -> std::result::Result<(), String> {
+        let Some(endpoint) = persisted.endpoint() else {
+            return Err(
+                "its adapter ran over STDIO and ended with the previous server".to_string(),
+            );
+        };
+        let (Some(attach), Some(adapter_id)) = (
+            persisted.attach.clone(),
+            Self::reattach_adapter_id(&persisted.language),
+        ) else {
+            return Err(format!(
+                "the {} adapter cannot attach to the running program again",
+                persisted.language
+            ));
+        };
+
+        let timeouts = self.default_timeouts(&persisted.language);
+        let socket = connect_endpoint_with_timeouts(&endpoint, &timeouts)
+            .await
+            .map_err(|e| format!("its adapter on {} is gone: {}", endpoint, e))?;
+        let client = DapClient::from_stream(socket)
+            .await
+            .map_err(|e| e.to_string())?;
+        let options = SessionOptions {
+            breakpoints: persisted
+                .breakpoints
+                .iter()
+                .cloned()
+                .map(InitialBreakpoint::from)
+                .collect(),
+            adapter_timeouts: Some(timeouts),
+            ..Default::default()
+        };
+        options.configure_client(&client);
+
+        let session = DebugSession::new(
+            persisted.language.clone(),
+            persisted.program.clone(),
+            client,
+        )
+        .await
+        .map_err(|e| e.to_string())?
+        .with_id(Some(persisted.id.clone()))
+        .with_adapter_endpoint(Some(endpoint));
+        options.queue_initial_breakpoints(&session).await;
+
+        let session = Arc::new(session);
+        self.register_session(&persisted.id, &session, &options)
+            .await;
+        tokio::spawn(session.initialize_and_launch_async(adapter_id.to_string(), attach));
         Ok(())
     }
+
+    fn reattach_adapter_id(language: &str) -> Option<&'static str> {
+        match language {
+            "ruby" => Some(RubyAdapter::adapter_id()),
+            "rust" => Some(RustAdapter::adapter_id()),
+            "c" | "cpp" => Some(CppAdapter::adapter_id()),
+            _ => None,
+        }
+    }
+
+    /// Sessions of the previous server that [`SessionManager::resume_sessions`]
+    /// could not adopt
+    pub async fn unrecoverable_sessions(&self) -> Vec<UnrecoverableSession> {
+        self.unrecoverable.read().await.clone()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_default_timeouts() {
+        let manager = SessionManager::new();
+        assert_eq!(
+            manager.default_timeouts("rust").initialize,
+            Duration::from_secs(15)
+        );
+        assert_eq!(
+            manager.default_timeouts("python").initialize,
+            Duration::from_secs(7)
+        );
+
+        // --adapter-timeout-ms replaces the language defaults
+        let manager = manager.with_adapter_timeouts(AdapterTimeouts::new(Duration::from_secs(20)));
+        assert_eq!(
+            manager.default_timeouts("rust").initialize,
+            Duration::from_secs(20)
+        );
+    }
+
+    #[test]
+    fn test_default_cwd() {
+        let dir = tempfile::tempdir().unwrap();
+        let program = dir.path().join("app.py");
+        std::fs::write(&program, "print(1)\n").unwrap();
+        let canonical = dir.path().canonicalize().unwrap();
+        let canonical = canonical.to_str();
+        let options = SessionOptions::default();
+
+        // Programs run from their directory, Go packages from themselves
+        let program = program.to_str().unwrap();
+        assert_eq!(
+            default_cwd("python", program, &options).as_deref(),
+            canonical
+        );
+        let package = dir.path().to_str().unwrap();
+        assert_eq!(default_cwd("go", package, &options).as_deref(), canonical);
+
+        // Modules and missing programs have no directory
+        let module = SessionOptions {
+            python_module: Some("http.server".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(default_cwd("python", "http.server", &module), None);
+        assert_eq!(default_cwd("python", "/nonexistent/app.py", &options), None);
+
+        // Cargo targets run from the package root, chosen when building
+        let cargo = SessionOptions {
+            cargo_target: Some(CargoTargetType::Test),
+            ..Default::default()
+        };
+        assert_eq!(default_cwd("rust", program, &cargo), None);
+    }
+
+    #[test]
+    fn test_start_info_json() {
+        let python = StartInfo {
+            language: "python".to_string(),
+            program: "/work/app.py".to_string(),
+            cwd: Some("/work".to_string()),
+            stop_on_entry: true,
+            adapter: "debugpy".to_string(),
+            adapter_command: "/usr/bin/python".to_string(),
+            adapter_version: Some("1.8.0".to_string()),
+            compiled: false,
+            rust_project: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&python).unwrap(),
+            serde_json::json!({
+                "language": "python",
+                "program": "/work/app.py",
+                "cwd": "/work",
+                "stopOnEntry": true,
+                "adapter": "debugpy",
+                "adapterCommand": "/usr/bin/python",
+                "adapterVersion": "1.8.0",
+                "compiled": false
+            })
+        );
+
+        let rust = StartInfo {
+            language: "rust".to_string(),
+            compiled: true,
+            rust_project: Some(RustProject {
+                kind: RustProjectKind::Cargo,
+                target: Some("bin:app".to_string()),
+            }),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&rust).unwrap();
+        assert_eq!(json["compiled"], true);
+        assert_eq!(
+            json["rustProject"],
+            serde_json::json!({"kind": "cargo", "target": "bin:app"})
+        );
+        assert_eq!(json["adapterVersion"], serde_json::Value::Null);
+
+        let single = RustProject {
+            kind: RustProjectKind::SingleFile,
+            target: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&single).unwrap(),
+            serde_json::json!({"kind": "singleFile"})
+        );
+    }
+
+    #[test]
+    fn test_start_info_adapter_per_language() {
+        let info = |language: &str, options: &SessionOptions| {
+            let mut info = StartInfo {
+                language: language.to_string(),
+                ..Default::default()
+            };
+            info.language_adapter(language, options);
+            info
+        };
+        let defaults = SessionOptions::default();
+
+        for (language, adapter) in [
+            ("python", "debugpy"),
+            ("ruby", "rdbg"),
+            ("nodejs", "nodejs"),
+            ("go", "delve"),
+            ("rust", "codelldb"),
+            ("c", "codelldb"),
+            ("cpp", "codelldb"),
+            ("bash", "bashdb"),
+        ] {
+            let info = info(language, &defaults);
+            assert_eq!(info.adapter, adapter, "{}", language);
+            assert!(!info.adapter_command.is_empty(), "{}", language);
+            // Rust decides once it knows whether the program is a source file
+            assert_eq!(
+                info.compiled,
+                matches!(language, "c" | "cpp"),
+                "{}",
+                language
+            );
+            assert_eq!(info.rust_project, None);
+        }
+
+        // C and C++ reuse Rust's CodeLLDB, and skip the build for a core dump
+        let c = info("c", &defaults);
+        assert_eq!(c.adapter_command, command_path(&RustAdapter::command()));
+        let json = serde_json::to_value(&c).unwrap();
+        assert_eq!(json["compiled"], true);
+        assert!(json.get("rustProject").is_none());
+        let core_dump = SessionOptions {
+            core_dump: Some("/work/core".to_string()),
+            ..Default::default()
+        };
+        assert!(!info("cpp", &core_dump).compiled);
+
+        // Before the server is spawned Node.js reports node itself
+        let node = info("nodejs", &defaults);
+        assert_eq!(node.adapter_command, command_path("node"));
+    }
+
+    #[test]
+    fn test_rust_project_per_build() {
+        let build = |binary: &str| CompileOutcome {
+            binary: binary.to_string(),
+            fresh: true,
+            duration: Duration::ZERO,
+            diagnostics: Vec::new(),
+        };
+        assert_eq!(
+            rust_project(None, false, None),
+            RustProject {
+                kind: RustProjectKind::Binary,
+                target: None
+            }
+        );
+        assert_eq!(
+            rust_project(Some(&build("/work/hello")), true, None),
+            RustProject {
+                kind: RustProjectKind::SingleFile,
+                target: None
+            }
+        );
+        assert_eq!(
+            rust_project(
+                Some(&build("/work/app/target/debug/deps/app-1a2b")),
+                false,
+                Some(&CargoTargetType::Test)
+            ),
+            RustProject {
+                kind: RustProjectKind::Cargo,
+                target: Some("test".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_cargo_target_label() {
+        assert_eq!(
+            cargo_target_label(None, "/work/app/target/debug/app"),
+            "bin:app"
+        );
+        assert_eq!(
+            cargo_target_label(
+                Some(&CargoTargetType::Binary),
+                "/work/app/target/debug/examples/demo"
+            ),
+            "example:demo"
+        );
+        assert_eq!(
+            cargo_target_label(
+                Some(&CargoTargetType::Example("demo".to_string())),
+                "/work/app/target/debug/examples/demo-1a2b"
+            ),
+            "example:demo"
+        );
+        assert_eq!(
+            cargo_target_label(
+                Some(&CargoTargetType::Test),
+                "/work/app/target/debug/deps/app-1a2b"
+            ),
+            "test"
+        );
+    }
+
     #[tokio::test]
     async fn test_session_manager_new() {
         let manager = SessionManager::new();
@@ -494,6 +2297,254 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_shutdown_ends_all_sessions() {
+        use crate::dap::testing::ScriptedAdapter;
+
+        let manager = SessionManager::new();
+        let mut sessions = Vec::new();
+        for program in ["/app/a.py", "/app/b.py"] {
+            let client = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+                .await
+                .unwrap();
+            let session = Arc::new(
+                DebugSession::new("python".to_string(), program.to_string(), client)
+                    .await
+                    .unwrap(),
+            );
+            manager.insert_session(Arc::clone(&session)).await;
+            sessions.push(session);
+        }
+
+        manager.shutdown().await;
+
+        assert!(manager.list_sessions().await.is_empty());
+        for session in sessions {
+            assert!(matches!(
+                session.state.read().await.state,
+                DebugState::Terminated
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_leaves_adoptable_sessions_for_resume() {
+        use crate::dap::testing::ScriptedAdapter;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions.json");
+        // Stands in for an rdbg that keeps listening
+        let adapter = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = adapter.local_addr().unwrap().port();
+
+        let manager = SessionManager::new().with_session_state(SessionStateFile::new(path.clone()));
+        let mut sessions = Vec::new();
+        for (language, program, adapter_port) in [
+            ("ruby", "/app/main.rb", Some(port)),
+            ("python", "/app/main.py", None),
+        ] {
+            let client = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+                .await
+                .unwrap();
+            let session = DebugSession::new(language.to_string(), program.to_string(), client)
+                .await
+                .unwrap()
+                .with_adapter_port(adapter_port);
+            session
+                .state
+                .write()
+                .await
+                .add_breakpoint(program.to_string(), 3);
+            let session = Arc::new(session);
+            manager.insert_session(Arc::clone(&session)).await;
+            sessions.push(session);
+        }
+        let ruby_id = sessions[0].id.clone();
+
+        manager.shutdown().await;
+
+        // The ruby session is left running and stays in the state file
+        assert!(manager.list_sessions().await.is_empty());
+        assert!(!matches!(
+            sessions[0].state.read().await.state,
+            DebugState::Terminated
+        ));
+        assert!(matches!(
+            sessions[1].state.read().await.state,
+            DebugState::Terminated
+        ));
+        let mut persisted = SessionStateFile::new(path.clone()).load().unwrap();
+        assert_eq!(persisted.len(), 1);
+        assert_eq!(persisted[0].id, ruby_id);
+        assert_eq!(persisted[0].adapter_port, Some(port));
+        assert_eq!(persisted[0].breakpoints.len(), 1);
+        assert_eq!(persisted[0].attach.as_ref().unwrap()["request"], "attach");
+
+        // Add a session over STDIO and one whose adapter is gone
+        let closed_port = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        let mut stdio = persisted[0].clone();
+        stdio.id = "stdio".to_string();
+        stdio.adapter_port = None;
+        let mut gone = persisted[0].clone();
+        gone.id = "gone".to_string();
+        gone.adapter_port = Some(closed_port);
+        persisted.extend([stdio, gone]);
+        SessionStateFile::new(path.clone())
+            .write(persisted)
+            .unwrap();
+
+        let resumed = SessionManager::new()
+            .with_adapter_timeouts(AdapterTimeouts::new(Duration::from_millis(300)))
+            .with_session_state(SessionStateFile::new(path.clone()));
+        assert_eq!(
+            resumed.resume_sessions().await.unwrap(),
+            vec![ruby_id.clone()]
+        );
+        let adopted = resumed.get_session(&ruby_id).await.unwrap();
+        assert_eq!(adopted.adapter_port(), Some(port));
+
+        let unrecoverable = resumed.unrecoverable_sessions().await;
+        assert_eq!(unrecoverable.len(), 2);
+        assert!(
+            unrecoverable[0].reason.contains("STDIO"),
+            "{:?}",
+            unrecoverable
+        );
+        assert!(
+            unrecoverable[1].reason.contains("gone"),
+            "{:?}",
+            unrecoverable
+        );
+        let persisted = SessionStateFile::new(path).load().unwrap();
+        assert_eq!(persisted.len(), 1);
+        drop(adapter);
+    }
+
+    #[tokio::test]
+    async fn test_breakpoints_saved_on_disconnect_are_restored() {
+        use crate::dap::testing::ScriptedAdapter;
+
+        let client = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/main.py".to_string(), client)
+            .await
+            .unwrap();
+        {
+            let mut state = session.state.write().await;
+            state.add_source_breakpoint(
+                "/app/main.py".to_string(),
+                12,
+                Some("n > 3".to_string()),
+                None,
+            );
+            state.add_breakpoint("/app/lib.py".to_string(), 4);
+        }
+
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        manager.remove_session(&session_id).await.unwrap();
+
+        let saved = manager.saved_breakpoints("/app/main.py").await;
+        assert_eq!(saved.len(), 2);
+        assert_eq!(saved[0].source_path, "/app/lib.py");
+        assert_eq!(saved[1].condition.as_deref(), Some("n > 3"));
+
+        // The next start restores them, skipping ones requested explicitly
+        let mut options = SessionOptions {
+            breakpoints: vec![InitialBreakpoint {
+                source_path: "/app/lib.py".to_string(),
+                line: 4,
+                condition: None,
+                log_message: None,
+            }],
+            ..Default::default()
+        };
+        assert_eq!(options.restore_breakpoints(saved), 1);
+        assert_eq!(options.breakpoints.len(), 2);
+        assert_eq!(options.breakpoints[1].line, 12);
+
+        assert_eq!(manager.clear_saved_breakpoints(None).await.unwrap(), 2);
+        assert!(manager.all_saved_breakpoints().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_watch_mode_restarts_on_file_change() {
+        use crate::dap::testing::ScriptedAdapter;
+
+        let dir = tempfile::tempdir().unwrap();
+        let program = dir.path().join("app.py");
+        std::fs::write(&program, "print('v1')\n").unwrap();
+        let program = program.to_string_lossy().to_string();
+
+        let client = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), program.clone(), client)
+            .await
+            .unwrap();
+        session.state.write().await.set_state(DebugState::Running);
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+
+        // Only sessions started through the manager can be started again
+        assert!(matches!(
+            manager.restart_session(&session_id, RESTART_REASON).await,
+            Err(Error::InvalidState(_))
+        ));
+        manager.launches.write().await.insert(
+            session_id.clone(),
+            SessionLaunch {
+                language: "cobol".to_string(),
+                program: program.clone(),
+                args: vec![],
+                cwd: None,
+                stop_on_entry: false,
+                options: SessionOptions::default(),
+            },
+        );
+        manager
+            .restarting
+            .lock()
+            .unwrap()
+            .insert(session_id.clone());
+        let err = manager
+            .restart_session(&session_id, RESTART_REASON)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("already in progress"));
+        manager.restarting.lock().unwrap().clear();
+
+        manager
+            .watch_session(&session_id, vec![program.clone()])
+            .await
+            .unwrap();
+        let status = manager.watch_status(&session_id).await.unwrap();
+        assert!(status.enabled);
+        assert_eq!(status.paths, vec![program.clone()]);
+
+        // The rewrite triggers a restart, which fails: no such adapter
+        std::fs::write(&program, "print('version 2')\n").unwrap();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let status = loop {
+            let status = manager.watch_status(&session_id).await.unwrap();
+            if status.last_error.is_some() || Instant::now() > deadline {
+                break status;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        };
+        assert!(status.last_error.unwrap().contains("cobol"));
+        assert_eq!(status.restarts, 0);
+        // The old session was stopped to make room for the new one
+        assert!(manager.get_session(&session_id).await.is_err());
+
+        assert!(manager.unwatch_session(&session_id).await);
+        assert!(!manager.unwatch_session(&session_id).await);
+    }
+
     #[tokio::test]
     async fn test_create_session_unknown_language() {
         let manager = SessionManager::new();
@@ -510,4 +2561,35 @@ mod tests {
             _ => panic!("Expected AdapterNotFound error"),
         }
     }
+
+    #[tokio::test]
+    async fn test_create_session_outside_allowed_paths() {
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let program = outside.path().join("app.py");
+        std::fs::write(&program, "print(1)").unwrap();
+        let manager = SessionManager::new()
+            .with_allowed_paths(AllowedPaths::new([root.path().to_path_buf()]).unwrap());
+
+        let program = program.to_string_lossy().to_string();
+        let result = manager
+            .create_session("python", program.clone(), vec![], None, false)
+            .await;
+        assert!(matches!(result, Err(Error::PathNotAllowed(_))));
+
+        // An allowed program with a working directory outside the roots
+        let allowed_program = root.path().join("app.py");
+        std::fs::write(&allowed_program, "print(1)").unwrap();
+        let result = manager
+            .create_session(
+                "python",
+                allowed_program.to_string_lossy().to_string(),
+                vec![],
+                Some(outside.path().to_string_lossy().to_string()),
+                false,
+            )
+            .await;
+        assert!(matches!(result, Err(Error::PathNotAllowed(_))));
+        assert!(manager.list_sessions().await.is_empty());
+    }
 }
@@ -0,0 +1,114 @@
+//! Memory dumps returned by `debugger_read_memory`
+//!
+//! Adapters answer readMemory with base64 data starting at an address. The
+//! data is returned as is, together with an address map: one row per 16
+//! bytes with the row's address, the bytes in hex and their printable ASCII.
+//!
+//! ```text
+//! {"address": "0x5555555a1040", "data": "aGVsbG8=", "bytesRead": 5,
+//!  "unreadableBytes": 0,
+//!  "rows": [{"address": "0x5555555a1040", "hex": "68 65 6c 6c 6f", "ascii": "hello"}]}
+//! ```
+
+use crate::dap::types::ReadMemoryResult;
+use crate::{Error, Result};
+use base64::Engine;
+use serde_json::{json, Value};
+
+/// Bytes shown per row of the address map
+pub const BYTES_PER_ROW: usize = 16;
+
+/// Largest readMemory count accepted by `debugger_read_memory`
+pub const MAX_READ_BYTES: i64 = 64 * 1024;
+
+/// JSON view of a readMemory result: the base64 data plus the address map
+pub fn memory_view(result: &ReadMemoryResult) -> Result<Value> {
+    let data = result.data.as_deref().unwrap_or("");
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|e| Error::Dap(format!("Invalid base64 memory data: {}", e)))?;
+    let base = parse_address(&result.address);
+
+    let rows: Vec<Value> = bytes
+        .chunks(BYTES_PER_ROW)
+        .enumerate()
+        .map(|(i, row)| {
+            let offset = i * BYTES_PER_ROW;
+            let address = match base {
+                Some(base) => format!("{:#x}", base + offset as u64),
+                None => format!("{}+{:#x}", result.address, offset),
+            };
+            let hex: Vec<String> = row.iter().map(|b| format!("{:02x}", b)).collect();
+            let ascii: String = row
+                .iter()
+                .map(|&b| {
+                    if b.is_ascii_graphic() || b == b' ' {
+                        b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            json!({"address": address, "hex": hex.join(" "), "ascii": ascii})
+        })
+        .collect();
+
+    Ok(json!({
+        "address": result.address,
+        "data": data,
+        "bytesRead": bytes.len(),
+        "unreadableBytes": result.unreadable_bytes.unwrap_or(0),
+        "rows": rows,
+    }))
+}
+
+/// Numeric value of a "0x..." or decimal address
+fn parse_address(address: &str) -> Option<u64> {
+    match address
+        .strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => address.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(address: &str, data: Option<&str>) -> ReadMemoryResult {
+        ReadMemoryResult {
+            address: address.to_string(),
+            unreadable_bytes: None,
+            data: data.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_address_map_rows() {
+        // "Hello, debugger!" followed by 0x00 0xff
+        let view = memory_view(&result("0x1000", Some("SGVsbG8sIGRlYnVnZ2VyIQD/"))).unwrap();
+
+        assert_eq!(view["bytesRead"], 18);
+        let rows = view["rows"].as_array().unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["address"], "0x1000");
+        assert_eq!(rows[0]["ascii"], "Hello, debugger!");
+        assert_eq!(rows[1]["address"], "0x1010");
+        assert_eq!(rows[1]["hex"], "00 ff");
+        assert_eq!(rows[1]["ascii"], "..");
+    }
+
+    #[test]
+    fn test_unparsed_addresses_and_missing_data() {
+        let view = memory_view(&result("main+4", Some("AQID"))).unwrap();
+        assert_eq!(view["rows"][0]["address"], "main+4+0x0");
+
+        let empty = memory_view(&result("0x10", None)).unwrap();
+        assert_eq!(empty["bytesRead"], 0);
+        assert_eq!(empty["rows"], json!([]));
+
+        assert!(memory_view(&result("0x10", Some("not base64!"))).is_err());
+    }
+}
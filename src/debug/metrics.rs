@@ -0,0 +1,33 @@
+//! Per-session performance metrics (`debugger_session_metrics`)
+//!
+//! Collected from the session's DAP client (request latencies per command,
+//! events received) and its state history (stops, time stopped vs running),
+//! plus how long the adapter took to start and the program to compile.
+
+use crate::dap::metrics::CommandMetrics;
+use serde::Serialize;
+use std::time::Duration;
+
+/// Snapshot of a session's metrics
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionMetrics {
+    pub requests_sent: usize,
+    pub events_received: usize,
+    pub stops: usize,
+    pub time_stopped_ms: u64,
+    pub time_running_ms: u64,
+    /// Spawning the adapter and connecting to it (None when attached)
+    pub adapter_spawn_ms: Option<u64>,
+    /// Compiled languages only
+    pub compile_ms: Option<u64>,
+    /// Latency per DAP command, sorted by command
+    pub commands: Vec<CommandMetrics>,
+    /// Messages waiting to be written to the adapter; stays high when the
+    /// adapter stops reading its input
+    pub write_queue_depth: usize,
+}
+
+pub(crate) fn millis(duration: Duration) -> u64 {
+    duration.as_millis() as u64
+}
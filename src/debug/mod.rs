@@ -1,9 +1,31 @@
+pub mod breakpoint_store;
+pub mod config_store;
+pub mod file_watch;
 pub mod manager;
+pub mod memory;
+pub mod metrics;
 pub mod multi_session;
+pub mod path_mapping;
+pub mod recorder;
 pub mod session;
+pub mod session_state;
 pub mod state;
+pub mod truncate;
 
-pub use manager::SessionManager;
+pub use breakpoint_store::{BreakpointStore, SavedBreakpoint};
+pub use config_store::{ConfigSource, ConfigStore, NamedConfig};
+pub use file_watch::FileWatchStatus;
+pub use manager::{
+    ExistingSession, InitialBreakpoint, RustProject, RustProjectKind, SessionManager,
+    SessionOptions, StartInfo, StartedSession,
+};
+pub use metrics::SessionMetrics;
 pub use multi_session::{ChildSession, MultiSessionManager};
+pub use path_mapping::{PathMapper, PathMapping};
+pub use recorder::{RecordingConfig, SessionRecorder};
 pub use session::{DebugSession, SessionMode};
-pub use state::{DebugState, SessionState};
+pub use session_state::{PersistedSession, SessionStateFile, UnrecoverableSession};
+pub use state::{
+    DataBreakpointInfo, DebugState, FrameCheck, FunctionBreakpointInfo, OutputEntry,
+    ResourceChange, SessionState, StateChange, StopContext, Watch, WatchValue,
+};
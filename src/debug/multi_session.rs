@@ -1,7 +1,10 @@
+use super::state::StopContext;
 use crate::dap::client::DapClient;
 use crate::{Error, Result};
-use std::collections::HashMap;
-use std::sync::Arc;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 use tracing::{info, warn};
 
@@ -21,10 +24,44 @@ pub struct ChildSession {
     pub session_type: String,
 }
 
+/// What a child session's events have reported so far
+#[derive(Debug, Clone, Default)]
+struct ChildStatus {
+    /// Threads announced by 'thread' events or seen in 'stopped' events
+    threads: BTreeSet<i32>,
+    /// Set while the child is stopped
+    stop: Option<StopContext>,
+    /// Order of the child's latest stop, to find the most recent one
+    stopped_at: u64,
+}
+
+/// A child session as reported in the session details resource
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChildInfo {
+    pub id: String,
+    pub session_type: String,
+    /// Operations without a thread go to the active child
+    pub active: bool,
+    pub threads: Vec<i32>,
+    /// "Stopped" or "Running"
+    pub state: &'static str,
+    /// Stopped children only
+    pub thread_id: Option<i32>,
+    pub reason: Option<String>,
+}
+
 /// Manager for parent-child session relationships in multi-session debugging
 ///
 /// Tracks child sessions spawned from a parent session and routes operations
-/// to the appropriate child. Used primarily for Node.js debugging with vscode-js-debug.
+/// to the appropriate child. Used primarily for Node.js debugging with vscode-js-debug,
+/// where a program that forks or starts workers gets one child per process.
+///
+/// Thread-specific operations go to the child that owns the thread (see
+/// [`MultiSessionManager::client_for_thread`]), breakpoints go to every child,
+/// and the child that stopped last becomes the active child. A child that
+/// ends is removed; the debugging session is over once the parent and every
+/// child have ended ([`MultiSessionManager::is_finished`]).
 ///
 /// # Architecture
 ///
@@ -55,7 +92,15 @@ pub struct MultiSessionManager {
     /// Map of child session ID to child session
     children: Arc<RwLock<HashMap<String, ChildSession>>>,
     /// Currently active child session ID (operations routed here)
-    active_child: Arc<RwLock<Option<String>>>,
+    ///
+    /// A std lock: it is updated from event callbacks, which cannot await.
+    active_child: Arc<std::sync::RwLock<Option<String>>>,
+    /// Threads and stops of each child, updated from its event callbacks
+    statuses: Arc<Mutex<HashMap<String, ChildStatus>>>,
+    /// Counter ordering the children's stops
+    stop_counter: Arc<std::sync::atomic::AtomicU64>,
+    /// Set once the parent session reported 'terminated' or 'exited'
+    parent_finished: Arc<AtomicBool>,
 }
 
 impl MultiSessionManager {
@@ -68,7 +113,10 @@ impl MultiSessionManager {
         Self {
             parent_session_id,
             children: Arc::new(RwLock::new(HashMap::new())),
-            active_child: Arc::new(RwLock::new(None)),
+            active_child: Arc::new(std::sync::RwLock::new(None)),
+            statuses: Arc::new(Mutex::new(HashMap::new())),
+            stop_counter: Arc::new(std::sync::atomic::AtomicU64::new(0)),
+            parent_finished: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -87,9 +135,11 @@ impl MultiSessionManager {
         );
 
         self.children.write().await.insert(child_id.clone(), child);
+        self.statuses()
+            .insert(child_id.clone(), ChildStatus::default());
 
         // Set as active if first child
-        let mut active = self.active_child.write().await;
+        let mut active = self.active_child();
         if active.is_none() {
             info!("   ✅ Set as active child (first child)");
             *active = Some(child_id);
@@ -108,8 +158,10 @@ impl MultiSessionManager {
             return Err(Error::SessionNotFound(child_id.to_string()));
         }
 
+        self.statuses().remove(child_id);
+
         // If this was the active child, clear active or pick another
-        let mut active = self.active_child.write().await;
+        let mut active = self.active_child();
         if active.as_ref() == Some(&child_id.to_string()) {
             info!("   Active child removed");
             *active = children.keys().next().cloned();
@@ -128,7 +180,7 @@ impl MultiSessionManager {
     /// Returns None if no child sessions exist yet.
     /// Operations should fall back to parent client if None.
     pub async fn get_active_child(&self) -> Option<Arc<RwLock<DapClient>>> {
-        let active_id = self.active_child.read().await;
+        let active_id = self.active_child().clone();
         if let Some(id) = active_id.as_ref() {
             let children = self.children.read().await;
             children.get(id).map(|child| child.client.clone())
@@ -139,7 +191,7 @@ impl MultiSessionManager {
 
     /// Get the active child session ID
     pub async fn get_active_child_id(&self) -> Option<String> {
-        self.active_child.read().await.clone()
+        self.active_child().clone()
     }
 
     /// Set the active child session
@@ -156,10 +208,148 @@ impl MultiSessionManager {
         drop(children);
 
         info!("🎯 Setting active child to: {}", child_id);
-        *self.active_child.write().await = Some(child_id);
+        *self.active_child() = Some(child_id);
         Ok(())
     }
 
+    /// Client of the child owning `thread_id`
+    ///
+    /// Child processes number their threads independently, so a child
+    /// stopped on the thread wins over one that merely reported it (the most
+    /// recent stop first). Falls back to the active child.
+    pub async fn client_for_thread(&self, thread_id: i32) -> Option<Arc<RwLock<DapClient>>> {
+        let owner = {
+            let statuses = self.statuses();
+            statuses
+                .iter()
+                .filter(|(_, status)| {
+                    status
+                        .stop
+                        .as_ref()
+                        .is_some_and(|stop| stop.thread_id == thread_id)
+                })
+                .max_by_key(|(_, status)| status.stopped_at)
+                .or_else(|| {
+                    statuses
+                        .iter()
+                        .find(|(_, status)| status.threads.contains(&thread_id))
+                })
+                .map(|(id, _)| id.clone())
+        };
+        match owner {
+            Some(id) => {
+                let children = self.children.read().await;
+                children.get(&id).map(|child| child.client.clone())
+            }
+            None => self.get_active_child().await,
+        }
+    }
+
+    /// Clients of every child, the active one first
+    pub async fn child_clients(&self) -> Vec<Arc<RwLock<DapClient>>> {
+        let active = self.active_child().clone();
+        let children = self.children.read().await;
+        let mut clients: Vec<(bool, &ChildSession)> = children
+            .values()
+            .map(|child| (Some(&child.id) == active.as_ref(), child))
+            .collect();
+        clients.sort_by(|(a_active, a), (b_active, b)| {
+            b_active.cmp(a_active).then_with(|| a.id.cmp(&b.id))
+        });
+        clients
+            .into_iter()
+            .map(|(_, child)| child.client.clone())
+            .collect()
+    }
+
+    /// Record a thread reported by a child's 'thread' event
+    pub fn record_thread(&self, child_id: &str, thread_id: i32, exited: bool) {
+        if let Some(status) = self.statuses().get_mut(child_id) {
+            if exited {
+                status.threads.remove(&thread_id);
+            } else {
+                status.threads.insert(thread_id);
+            }
+        }
+    }
+
+    /// Record a child's stop; the child becomes the active child
+    pub fn record_stop(&self, child_id: &str, stop: &StopContext) {
+        let order = self.stop_counter.fetch_add(1, Ordering::SeqCst);
+        if let Some(status) = self.statuses().get_mut(child_id) {
+            status.threads.insert(stop.thread_id);
+            status.stop = Some(stop.clone());
+            status.stopped_at = order;
+        }
+        *self.active_child() = Some(child_id.to_string());
+    }
+
+    /// Record that a child resumed
+    ///
+    /// Returns the stop of another child that is still stopped (the most
+    /// recent one), which then becomes the active child: the session as a
+    /// whole stays stopped.
+    pub fn record_running(&self, child_id: &str) -> Option<StopContext> {
+        let mut statuses = self.statuses();
+        if let Some(status) = statuses.get_mut(child_id) {
+            status.stop = None;
+        }
+        let (other_id, stop) = statuses
+            .iter()
+            .filter_map(|(id, status)| Some((id, status.stop.as_ref()?, status.stopped_at)))
+            .max_by_key(|(_, _, stopped_at)| *stopped_at)
+            .map(|(id, stop, _)| (id.clone(), stop.clone()))?;
+        drop(statuses);
+        *self.active_child() = Some(other_id);
+        Some(stop)
+    }
+
+    /// Every child with its threads and state, sorted by ID
+    pub async fn child_infos(&self) -> Vec<ChildInfo> {
+        let active = self.active_child().clone();
+        let children = self.children.read().await;
+        let statuses = self.statuses();
+        let mut infos: Vec<ChildInfo> = children
+            .values()
+            .map(|child| {
+                let status = statuses.get(&child.id).cloned().unwrap_or_default();
+                ChildInfo {
+                    id: child.id.clone(),
+                    session_type: child.session_type.clone(),
+                    active: active.as_deref() == Some(child.id.as_str()),
+                    threads: status.threads.into_iter().collect(),
+                    state: if status.stop.is_some() {
+                        "Stopped"
+                    } else {
+                        "Running"
+                    },
+                    thread_id: status.stop.as_ref().map(|stop| stop.thread_id),
+                    reason: status.stop.map(|stop| stop.reason),
+                }
+            })
+            .collect();
+        infos.sort_by(|a, b| a.id.cmp(&b.id));
+        infos
+    }
+
+    fn statuses(&self) -> std::sync::MutexGuard<'_, HashMap<String, ChildStatus>> {
+        self.statuses.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn active_child(&self) -> std::sync::RwLockWriteGuard<'_, Option<String>> {
+        self.active_child.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Record that the parent session ended
+    pub fn record_parent_finished(&self) {
+        self.parent_finished.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether the parent and every child have ended
+    pub async fn is_finished(&self) -> bool {
+        self.parent_finished.load(Ordering::SeqCst) && self.children.read().await.is_empty()
+    }
+
     /// Get all child session IDs
     pub async fn get_children(&self) -> Vec<String> {
         self.children.read().await.keys().cloned().collect()
@@ -184,23 +374,12 @@ impl MultiSessionManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::dap::transport_trait::DapTransportTrait;
-    use crate::dap::types::*;
-    use mockall::mock;
+    use crate::dap::testing::MockTransport;
 
-    mock! {
-        pub TestTransport {}
-
-        #[async_trait::async_trait]
-        impl DapTransportTrait for TestTransport {
-            async fn read_message(&mut self) -> Result<Message>;
-            async fn write_message(&mut self, msg: &Message) -> Result<()>;
-        }
-    }
-
-    fn create_empty_mock() -> MockTestTransport {
-        let mut mock = MockTestTransport::new();
-        mock.expect_read_message()
+    fn create_empty_mock() -> MockTransport {
+        let mut mock = MockTransport::new();
+        mock.reader
+            .expect_read_message()
             .returning(|| Err(Error::Dap("Connection closed".to_string())));
         mock
     }
@@ -354,4 +533,112 @@ mod tests {
         let manager = MultiSessionManager::new("parent".to_string());
         assert!(manager.get_child("nonexistent").await.is_none());
     }
+
+    fn stop_on(thread_id: i32, reason: &str) -> StopContext {
+        StopContext::from_event_body(&serde_json::json!({
+            "threadId": thread_id,
+            "reason": reason,
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_client_for_thread_prefers_the_stopped_child() {
+        let manager = MultiSessionManager::new("parent".to_string());
+        let child1 = create_mock_child_session("child-1", 9000).await;
+        let child2 = create_mock_child_session("child-2", 9000).await;
+        let child2_client = child2.client.clone();
+        manager.add_child(child1).await;
+        manager.add_child(child2).await;
+
+        // Both processes number their main thread 1; only child-2 is stopped
+        manager.record_thread("child-1", 1, false);
+        manager.record_thread("child-2", 1, false);
+        manager.record_thread("child-2", 7, false);
+        manager.record_stop("child-2", &stop_on(1, "breakpoint"));
+        manager
+            .set_active_child("child-1".to_string())
+            .await
+            .unwrap();
+
+        let client = manager.client_for_thread(1).await.unwrap();
+        assert!(Arc::ptr_eq(&client, &child2_client));
+        let client = manager.client_for_thread(7).await.unwrap();
+        assert!(Arc::ptr_eq(&client, &child2_client));
+
+        // Exited threads are forgotten: fall back to the active child
+        manager.record_thread("child-2", 7, true);
+        let client = manager.client_for_thread(7).await.unwrap();
+        assert!(!Arc::ptr_eq(&client, &child2_client));
+    }
+
+    #[tokio::test]
+    async fn test_resuming_one_child_refocuses_another_stopped_child() {
+        let manager = MultiSessionManager::new("parent".to_string());
+        manager
+            .add_child(create_mock_child_session("child-1", 9000).await)
+            .await;
+        manager
+            .add_child(create_mock_child_session("child-2", 9000).await)
+            .await;
+
+        manager.record_stop("child-1", &stop_on(1, "breakpoint"));
+        manager.record_stop("child-2", &stop_on(3, "step"));
+        assert_eq!(
+            manager.get_active_child_id().await,
+            Some("child-2".to_string())
+        );
+
+        let still_stopped = manager.record_running("child-2").unwrap();
+        assert_eq!(still_stopped.thread_id, 1);
+        assert_eq!(still_stopped.reason, "breakpoint");
+        assert_eq!(
+            manager.get_active_child_id().await,
+            Some("child-1".to_string())
+        );
+
+        assert!(manager.record_running("child-1").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_child_infos_report_threads_and_state() {
+        let manager = MultiSessionManager::new("parent".to_string());
+        manager
+            .add_child(create_mock_child_session("child-b", 9000).await)
+            .await;
+        manager
+            .add_child(create_mock_child_session("child-a", 9000).await)
+            .await;
+        manager.record_thread("child-a", 2, false);
+        manager.record_stop("child-b", &stop_on(1, "breakpoint"));
+
+        let infos = manager.child_infos().await;
+
+        assert_eq!(infos.len(), 2);
+        assert_eq!(infos[0].id, "child-a");
+        assert_eq!(infos[0].state, "Running");
+        assert_eq!(infos[0].threads, vec![2]);
+        assert!(!infos[0].active);
+        assert_eq!(infos[1].state, "Stopped");
+        assert_eq!(infos[1].thread_id, Some(1));
+        assert!(infos[1].active);
+
+        let json = serde_json::to_value(&infos[1]).unwrap();
+        assert_eq!(json["sessionType"], "pwa-node");
+        assert_eq!(json["reason"], "breakpoint");
+    }
+
+    #[tokio::test]
+    async fn test_finished_once_parent_and_all_children_ended() {
+        let manager = MultiSessionManager::new("parent".to_string());
+        manager
+            .add_child(create_mock_child_session("child-1", 9000).await)
+            .await;
+        assert!(!manager.is_finished().await);
+
+        manager.record_parent_finished();
+        assert!(!manager.is_finished().await);
+
+        manager.remove_child("child-1").await.unwrap();
+        assert!(manager.is_finished().await);
+    }
 }
@@ -0,0 +1,223 @@
+//! Path mapping between the MCP client's files and the debuggee's files
+//!
+//! A program running in a Docker container (or on another machine) sees its
+//! sources under different absolute paths than the MCP client. Breakpoints
+//! are sent with the client's paths translated to the debuggee's
+//! ([`PathMapper::to_remote`]), and paths reported by the adapter, such as
+//! stack frame sources, are translated back ([`PathMapper::to_local`]).
+//!
+//! The longest matching prefix wins, and prefixes only match whole path
+//! components: `/app` maps `/app/main.py` but not `/application/main.py`.
+//! Either slash separates components, so Windows paths (`C:\\app`) map too;
+//! the rest of a translated path takes the separator of the side it maps to.
+
+use serde::Deserialize;
+
+/// One directory as seen by the MCP client (`local`) and by the debuggee (`remote`)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PathMapping {
+    pub local: String,
+    pub remote: String,
+}
+
+/// Translates paths in both directions using a set of [`PathMapping`]s
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathMapper {
+    mappings: Vec<PathMapping>,
+    case_sensitive: bool,
+}
+
+impl Default for PathMapper {
+    fn default() -> Self {
+        Self::new(Vec::new(), true)
+    }
+}
+
+/// Whether two source paths name the same file, compared the way the
+/// platform's file system does: ignoring case and slash direction on
+/// Windows, exactly elsewhere
+pub fn same_source_path(a: &str, b: &str) -> bool {
+    paths_equal(a, b, !cfg!(windows))
+}
+
+/// Compare paths exactly, or ignoring ASCII case and slash direction
+pub fn paths_equal(a: &str, b: &str, case_sensitive: bool) -> bool {
+    if case_sensitive {
+        return a == b;
+    }
+    let normalize = |c: char| match c {
+        '\\' => '/',
+        c => c.to_ascii_lowercase(),
+    };
+    a.chars().map(normalize).eq(b.chars().map(normalize))
+}
+
+impl PathMapper {
+    pub fn new(mappings: Vec<PathMapping>, case_sensitive: bool) -> Self {
+        Self {
+            mappings,
+            case_sensitive,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mappings.is_empty()
+    }
+
+    /// Client path → debuggee path (unchanged when no mapping applies)
+    pub fn to_remote(&self, path: &str) -> String {
+        self.translate(path, |m| (&m.local, &m.remote))
+    }
+
+    /// Debuggee path → client path (unchanged when no mapping applies)
+    pub fn to_local(&self, path: &str) -> String {
+        self.translate(path, |m| (&m.remote, &m.local))
+    }
+
+    fn translate<'a>(
+        &'a self,
+        path: &str,
+        direction: impl Fn(&'a PathMapping) -> (&'a String, &'a String),
+    ) -> String {
+        self.mappings
+            .iter()
+            .map(direction)
+            .filter_map(|(from, to)| {
+                let rest = self.strip_prefix(path, from)?;
+                Some((from.len(), to, rest))
+            })
+            .max_by_key(|(prefix_len, _, _)| *prefix_len)
+            .map(|(_, to, rest)| {
+                let to = to.trim_end_matches(['/', '\\']);
+                let separator = if to.contains('\\') && !to.contains('/') {
+                    '\\'
+                } else {
+                    '/'
+                };
+                if rest.is_empty() {
+                    to.to_string()
+                } else {
+                    let rest: String = rest
+                        .chars()
+                        .map(|c| if c == '/' || c == '\\' { separator } else { c })
+                        .collect();
+                    format!("{}{}{}", to, separator, rest)
+                }
+            })
+            .unwrap_or_else(|| path.to_string())
+    }
+
+    /// Remainder of `path` after `prefix` (without the separator), when
+    /// `prefix` matches whole path components
+    fn strip_prefix<'p>(&self, path: &'p str, prefix: &str) -> Option<&'p str> {
+        let prefix = prefix.trim_end_matches(['/', '\\']);
+        let head = path.get(..prefix.len())?;
+        if !paths_equal(head, prefix, self.case_sensitive) {
+            return None;
+        }
+        let rest = &path[prefix.len()..];
+        if rest.is_empty() {
+            Some(rest)
+        } else {
+            rest.strip_prefix(['/', '\\'])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(local: &str, remote: &str) -> PathMapping {
+        PathMapping {
+            local: local.to_string(),
+            remote: remote.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_maps_both_directions() {
+        let mapper = PathMapper::new(vec![mapping("/home/me/project", "/app")], true);
+
+        assert_eq!(
+            mapper.to_remote("/home/me/project/src/main.py"),
+            "/app/src/main.py"
+        );
+        assert_eq!(
+            mapper.to_local("/app/src/main.py"),
+            "/home/me/project/src/main.py"
+        );
+        // Outside every mapping: unchanged
+        assert_eq!(mapper.to_remote("/usr/lib/x.py"), "/usr/lib/x.py");
+        assert_eq!(mapper.to_local("/usr/lib/x.py"), "/usr/lib/x.py");
+    }
+
+    #[test]
+    fn test_longest_prefix_wins() {
+        let mapper = PathMapper::new(
+            vec![
+                mapping("/work", "/app"),
+                mapping("/work/vendor/", "/opt/vendor"),
+            ],
+            true,
+        );
+
+        assert_eq!(mapper.to_remote("/work/main.py"), "/app/main.py");
+        assert_eq!(
+            mapper.to_remote("/work/vendor/lib.py"),
+            "/opt/vendor/lib.py"
+        );
+        assert_eq!(mapper.to_local("/opt/vendor/lib.py"), "/work/vendor/lib.py");
+    }
+
+    #[test]
+    fn test_prefix_matches_whole_components() {
+        let mapper = PathMapper::new(vec![mapping("/app", "/srv/app")], true);
+
+        assert_eq!(
+            mapper.to_remote("/application/main.py"),
+            "/application/main.py"
+        );
+        assert_eq!(mapper.to_remote("/app"), "/srv/app");
+    }
+
+    #[test]
+    fn test_case_sensitivity_is_configurable() {
+        let mappings = vec![mapping("C:/Users/me/Project", "/app")];
+
+        let sensitive = PathMapper::new(mappings.clone(), true);
+        assert_eq!(
+            sensitive.to_remote("c:/users/me/project/main.py"),
+            "c:/users/me/project/main.py"
+        );
+
+        let insensitive = PathMapper::new(mappings, false);
+        assert_eq!(
+            insensitive.to_remote("c:/users/me/project/main.py"),
+            "/app/main.py"
+        );
+    }
+
+    #[test]
+    fn test_maps_windows_paths() {
+        let mapper = PathMapper::new(vec![mapping("/home/me/project", "C:\\app\\")], false);
+
+        assert_eq!(
+            mapper.to_remote("/home/me/project/src/main.py"),
+            "C:\\app\\src\\main.py"
+        );
+        // Either slash, any case
+        assert_eq!(
+            mapper.to_local("c:/APP\\src\\main.py"),
+            "/home/me/project/src/main.py"
+        );
+    }
+
+    #[test]
+    fn test_paths_equal() {
+        assert!(paths_equal("/app/main.py", "/app/main.py", true));
+        assert!(!paths_equal("/app/main.py", "/App/main.py", true));
+        assert!(paths_equal("C:\\App\\main.py", "c:/app/MAIN.py", false));
+        assert!(!paths_equal("C:\\App\\main.py", "c:/app/other.py", false));
+    }
+}
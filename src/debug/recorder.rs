@@ -0,0 +1,280 @@
+//! Session transcripts (`--record-dir`, `record: true`)
+//!
+//! A recorded session appends one JSON line per event to
+//! `<dir>/<session id>.jsonl`: every tool call on the session with its
+//! arguments, result (or error) and duration, and every state transition.
+//! Transcripts reproduce bugs in the server itself and show what an agent
+//! did; `debugger_mcp replay <file>` issues the recorded tool calls again.
+//!
+//! Values under keys containing one of the redact keys (case-insensitive,
+//! e.g. "password" matches `dbPassword`) are replaced by [`REDACTED`] before
+//! they are written.
+
+use super::state::{DebugState, StopContext};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// Keys redacted in every transcript
+pub const DEFAULT_REDACT_KEYS: &[&str] =
+    &["password", "secret", "token", "apikey", "authorization"];
+
+/// Replacement for redacted values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Where transcripts go and what they leave out
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordingConfig {
+    pub dir: PathBuf,
+    /// Lowercase key fragments whose values are redacted
+    pub redact_keys: Vec<String>,
+}
+
+impl RecordingConfig {
+    /// Record into `dir`, redacting [`DEFAULT_REDACT_KEYS`]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            redact_keys: DEFAULT_REDACT_KEYS.iter().map(|k| k.to_string()).collect(),
+        }
+    }
+
+    /// Also redact values under keys containing one of `keys`
+    pub fn with_redact_keys(mut self, keys: impl IntoIterator<Item = String>) -> Self {
+        for key in keys {
+            let key = key.to_lowercase();
+            if !self.redact_keys.contains(&key) {
+                self.redact_keys.push(key);
+            }
+        }
+        self
+    }
+
+    /// Directory for sessions started with `record: true` when the server
+    /// was not given `--record-dir`
+    pub fn default_dir() -> PathBuf {
+        std::env::temp_dir().join("debugger-mcp-recordings")
+    }
+}
+
+/// One line of a transcript
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TranscriptEntry {
+    #[serde(rename_all = "camelCase")]
+    ToolCall {
+        /// Milliseconds since the Unix epoch
+        timestamp_ms: u64,
+        tool: String,
+        arguments: Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        result: Option<Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+        duration_ms: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    StateChange {
+        timestamp_ms: u64,
+        from: Value,
+        to: Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        stop: Option<Value>,
+    },
+}
+
+/// Appends the transcript of one session
+#[derive(Debug)]
+pub struct SessionRecorder {
+    path: PathBuf,
+    file: Mutex<std::fs::File>,
+    redact_keys: Vec<String>,
+}
+
+impl SessionRecorder {
+    /// Open (or continue, for a restarted session) the transcript of `session_id`
+    pub fn create(config: &RecordingConfig, session_id: &str) -> Result<Self> {
+        std::fs::create_dir_all(&config.dir)?;
+        let path = config.dir.join(format!("{}.jsonl", session_id));
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+            redact_keys: config.redact_keys.clone(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn record_tool_call(
+        &self,
+        tool: &str,
+        arguments: &Value,
+        result: &Result<Value>,
+        elapsed: Duration,
+    ) {
+        let (result, error) = match result {
+            Ok(value) => (Some(self.redacted(value)), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        self.append(&TranscriptEntry::ToolCall {
+            timestamp_ms: now_ms(),
+            tool: tool.to_string(),
+            arguments: self.redacted(arguments),
+            result,
+            error,
+            duration_ms: elapsed.as_millis() as u64,
+        });
+    }
+
+    pub fn record_state_change(
+        &self,
+        from: &DebugState,
+        to: &DebugState,
+        stop: Option<&StopContext>,
+    ) {
+        self.append(&TranscriptEntry::StateChange {
+            timestamp_ms: now_ms(),
+            from: serde_json::to_value(from).unwrap_or(Value::Null),
+            to: serde_json::to_value(to).unwrap_or(Value::Null),
+            stop: stop.and_then(|stop| serde_json::to_value(stop).ok()),
+        });
+    }
+
+    fn redacted(&self, value: &Value) -> Value {
+        let mut value = value.clone();
+        redact(&mut value, &self.redact_keys);
+        value
+    }
+
+    /// Write one line; a failing write is logged, never returned to the tool call
+    fn append(&self, entry: &TranscriptEntry) {
+        let line = match serde_json::to_string(entry) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("⚠️  Failed to serialize transcript entry: {}", e);
+                return;
+            }
+        };
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = writeln!(file, "{}", line) {
+            warn!(
+                "⚠️  Failed to write transcript {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+}
+
+/// Replace the values of object keys containing one of `keys` (lowercase)
+pub fn redact(value: &mut Value, keys: &[String]) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                let key = key.to_lowercase();
+                if keys.iter().any(|redacted| key.contains(redacted.as_str())) {
+                    *child = Value::String(REDACTED.to_string());
+                } else {
+                    redact(child, keys);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| redact(item, keys)),
+        _ => {}
+    }
+}
+
+/// Read a transcript written by [`SessionRecorder`]
+pub fn read_transcript(path: &Path) -> Result<Vec<TranscriptEntry>> {
+    let content = std::fs::read_to_string(path)?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line).map_err(|e| {
+                Error::InvalidRequest(format!(
+                    "Invalid transcript line {} of {}: {}",
+                    index + 1,
+                    path.display(),
+                    e
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Milliseconds since the Unix epoch
+pub(crate) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_transcript_round_trip_with_redaction() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = RecordingConfig::new(dir.path()).with_redact_keys(["ENV".to_string()]);
+        let recorder = SessionRecorder::create(&config, "abc").unwrap();
+        assert_eq!(recorder.path(), dir.path().join("abc.jsonl"));
+
+        recorder.record_tool_call(
+            "debugger_start",
+            &json!({"program": "app.py", "env": {"HOME": "/root"},
+                    "launch": [{"dbPassword": "hunter2", "user": "me"}]}),
+            &Ok(json!({"sessionId": "abc", "apiToken": "t0k3n"})),
+            Duration::from_millis(12),
+        );
+        recorder.record_state_change(&DebugState::NotStarted, &DebugState::Running, None);
+        recorder.record_tool_call(
+            "debugger_continue",
+            &json!({"sessionId": "abc"}),
+            &Err(Error::InvalidState("not stopped".to_string())),
+            Duration::ZERO,
+        );
+
+        let entries = read_transcript(recorder.path()).unwrap();
+        assert_eq!(entries.len(), 3);
+        match &entries[0] {
+            TranscriptEntry::ToolCall {
+                tool,
+                arguments,
+                result,
+                duration_ms,
+                ..
+            } => {
+                assert_eq!(tool, "debugger_start");
+                assert_eq!(arguments["env"], REDACTED);
+                assert_eq!(arguments["launch"][0]["dbPassword"], REDACTED);
+                assert_eq!(arguments["launch"][0]["user"], "me");
+                assert_eq!(result.as_ref().unwrap()["apiToken"], REDACTED);
+                assert_eq!(*duration_ms, 12);
+            }
+            other => panic!("Expected a tool call, got {:?}", other),
+        }
+        assert!(matches!(
+            &entries[1],
+            TranscriptEntry::StateChange { to, .. } if to == &json!("Running")
+        ));
+        assert!(matches!(
+            &entries[2],
+            TranscriptEntry::ToolCall { error: Some(e), result: None, .. } if e.contains("not stopped")
+        ));
+    }
+}
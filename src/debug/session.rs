@@ -67,17 +67,288 @@
 //! - `src/dap/client.rs` - DapClient with reverse request handling
 //! - `docs/NODEJS_ALL_TESTS_PASSING.md` - Multi-session architecture details
 
-use super::multi_session::MultiSessionManager;
-use super::state::{DebugState, SessionState};
+use super::metrics::{millis, SessionMetrics};
+use super::multi_session::{ChildInfo, ChildSession, MultiSessionManager};
+use super::path_mapping::{same_source_path, PathMapper};
+use super::recorder::SessionRecorder;
+use super::state::{
+    Breakpoint, DataBreakpointInfo, DebugState, FrameCheck, FunctionBreakpointInfo, SessionState,
+    StopContext, Watch, WatchValue,
+};
+use crate::adapters::nodejs;
+use crate::adapters::python::PythonAdapter;
+use crate::adapters::rust::CompileOutcome;
+use crate::adapters::source_analysis::{self, SourceLanguage};
 use crate::dap::client::DapClient;
-use crate::dap::types::{Source, SourceBreakpoint};
+use crate::dap::metrics::ClientMetrics;
+use crate::dap::socket_helper::AdapterEndpoint;
+use crate::dap::types::{
+    BreakpointLocation, CompletionItem, DataBreakpoint, DataBreakpointInfoResponse,
+    DebuggeeProcess, DisassembledInstruction, EvaluateResult, FunctionBreakpoint, GotoTarget,
+    Module, ReadMemoryResult, Scope, Source, SourceBreakpoint, StackTracePage, StepInTarget,
+    Variable,
+};
+use crate::dap::wire_log::DapLog;
 use crate::Result;
 use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{error, info, warn};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use tracing::{debug, error, info, warn, Instrument};
 use uuid::Uuid;
 
+/// How long to wait after configurationDone for a stopOnEntry launch to stop
+const ENTRY_STOP_WINDOW: Duration = Duration::from_secs(2);
+
+/// Upper bound on evaluating a single watch expression
+const WATCH_EVAL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Requests in a row that must time out before a session counts as unhealthy
+pub const UNHEALTHY_AFTER_TIMEOUTS: usize = 3;
+
+/// Owner tag of the event handlers a session registers on its clients
+const SESSION_HANDLER_OWNER: &str = "debug-session";
+
+/// Orders the DAP requests of a session
+///
+/// Some adapters (rdbg notably) misbehave when requests interleave with a
+/// state transition. Read-only requests (stackTrace, evaluate, variables)
+/// run concurrently; configuration changes (breakpoints) wait until every
+/// other request is done; and a resume (continue, step) is rejected while
+/// another resume is in flight rather than queued behind it, since it would
+/// start from a different place than the caller saw.
+#[derive(Debug, Default)]
+struct RequestGate {
+    requests: RwLock<()>,
+    /// The resume in flight ("continue", "step")
+    resuming: std::sync::Mutex<Option<&'static str>>,
+}
+
+impl RequestGate {
+    async fn read(&self) -> RwLockReadGuard<'_, ()> {
+        self.requests.read().await
+    }
+
+    async fn write(&self) -> RwLockWriteGuard<'_, ()> {
+        self.requests.write().await
+    }
+
+    async fn resume(&self, operation: &'static str) -> Result<ResumeGuard<'_>> {
+        {
+            let mut resuming = self.resuming.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(current) = *resuming {
+                return Err(crate::Error::InvalidState(format!(
+                    "Cannot {}: a {} is already in progress",
+                    operation, current
+                )));
+            }
+            *resuming = Some(operation);
+        }
+        // Built before waiting, so a cancelled resume still clears the flag
+        let mut guard = ResumeGuard {
+            gate: self,
+            _requests: None,
+        };
+        guard._requests = Some(self.requests.write().await);
+        Ok(guard)
+    }
+}
+
+/// Held while a resume is in flight
+struct ResumeGuard<'a> {
+    gate: &'a RequestGate,
+    _requests: Option<RwLockWriteGuard<'a, ()>>,
+}
+
+impl Drop for ResumeGuard<'_> {
+    fn drop(&mut self) {
+        *self.gate.resuming.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+/// Kind of step request sent by [`DebugSession::step`]
+enum Step {
+    Over,
+    /// Into the given step-in target, or the first call on the line
+    Into(Option<i32>),
+    Out,
+}
+
+/// State change reported by the adapter
+enum StateUpdate {
+    Stopped(StopContext),
+    /// Another child resumed; this earlier stop is current again
+    Refocus(StopContext),
+    Running,
+    Terminated {
+        exited: bool,
+    },
+    /// A Node.js child session ended ('terminated' or 'exited')
+    ChildFinished(String),
+    /// The vscode-js-debug parent session ended
+    ParentFinished {
+        exited: bool,
+    },
+    Thread(i32),
+    /// A 'breakpoint' event: the adapter bound, moved or removed a breakpoint
+    Breakpoint {
+        reason: String,
+        breakpoint: crate::dap::types::Breakpoint,
+    },
+    /// A 'loadedSource' event for a source with a (local) path
+    LoadedSource {
+        reason: String,
+        path: String,
+    },
+    Module {
+        reason: String,
+        module: Module,
+    },
+    /// A 'process' event: the debuggee's name and PID
+    Process(DebuggeeProcess),
+    /// An 'exited' event's exit code, queued before the update that ends
+    /// the session or child
+    ExitCode(i64),
+    /// Answered once every update queued before it has been applied
+    Sync(oneshot::Sender<()>),
+}
+
+/// Apply state updates in the order they were queued
+///
+/// Event callbacks run on the client's reader task and cannot take the state
+/// lock, so they queue updates here instead of spawning a task per event:
+/// separately spawned tasks could take the lock out of order and let an
+/// older event overwrite a newer one.
+///
+/// In multi-session mode a finished child is removed from `children`; the
+/// session only becomes Terminated once the parent and every child are done.
+fn spawn_state_updater(
+    state: Arc<RwLock<SessionState>>,
+    children: Option<MultiSessionManager>,
+) -> mpsc::UnboundedSender<StateUpdate> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while let Some(update) = rx.recv().await {
+            match update {
+                StateUpdate::Stopped(stop) => {
+                    let reason = stop.reason.clone();
+                    state.write().await.record_stop(stop);
+                    info!("✅ Session state updated to Stopped (reason: {})", reason);
+                }
+                StateUpdate::Refocus(stop) => {
+                    let child = stop.child_session.clone().unwrap_or_default();
+                    state.write().await.restore_stop(stop);
+                    info!("✅ Session state still Stopped ({} is stopped)", child);
+                }
+                StateUpdate::Running => {
+                    state.write().await.set_state(DebugState::Running);
+                    info!("✅ Session state updated to Running");
+                }
+                StateUpdate::Terminated { exited } => {
+                    state.write().await.set_state(DebugState::Terminated);
+                    if exited {
+                        info!("✅ Session state updated to Terminated (exited)");
+                    } else {
+                        info!("✅ Session state updated to Terminated");
+                    }
+                }
+                StateUpdate::ChildFinished(child_id) => {
+                    let Some(manager) = &children else {
+                        continue;
+                    };
+                    // 'exited' and 'terminated' both report the end of a child
+                    if manager.get_child(&child_id).await.is_none() {
+                        continue;
+                    }
+                    let still_stopped = manager.record_running(&child_id);
+                    let _ = manager.remove_child(&child_id).await;
+
+                    let mut state = state.write().await;
+                    if manager.is_finished().await {
+                        state.set_state(DebugState::Terminated);
+                        info!(
+                            "✅ Session state updated to Terminated (last child {} ended)",
+                            child_id
+                        );
+                        continue;
+                    }
+                    let stopped_here = matches!(state.state, DebugState::Stopped { .. })
+                        && state
+                            .last_stop
+                            .as_ref()
+                            .and_then(|stop| stop.child_session.as_deref())
+                            == Some(child_id.as_str());
+                    if stopped_here {
+                        match still_stopped {
+                            Some(stop) => state.restore_stop(stop),
+                            None => state.set_state(DebugState::Running),
+                        }
+                    }
+                    info!(
+                        "✅ Child session {} ended, {} still running",
+                        child_id,
+                        manager.child_count().await
+                    );
+                }
+                StateUpdate::ParentFinished { exited } => {
+                    let Some(manager) = &children else {
+                        continue;
+                    };
+                    manager.record_parent_finished();
+                    if manager.is_finished().await {
+                        state.write().await.set_state(DebugState::Terminated);
+                        if exited {
+                            info!("✅ Session state updated to Terminated (exited)");
+                        } else {
+                            info!("✅ Session state updated to Terminated");
+                        }
+                    } else {
+                        info!(
+                            "⏳ Parent session ended, waiting for {} child session(s)",
+                            manager.child_count().await
+                        );
+                    }
+                }
+                StateUpdate::Thread(thread_id) => state.write().await.add_thread(thread_id),
+                StateUpdate::Breakpoint { reason, breakpoint } => {
+                    if state
+                        .write()
+                        .await
+                        .apply_breakpoint_event(&reason, &breakpoint)
+                    {
+                        info!(
+                            "✅ Breakpoint {:?} {} (verified: {})",
+                            breakpoint.id, reason, breakpoint.verified
+                        );
+                    }
+                }
+                StateUpdate::LoadedSource { reason, path } => {
+                    state.write().await.record_loaded_source(&reason, path)
+                }
+                StateUpdate::Module { reason, module } => {
+                    state.write().await.record_module(&reason, module)
+                }
+                StateUpdate::Process(process) => {
+                    let (name, pid) = (process.name.clone(), process.system_process_id);
+                    if state.write().await.record_process(process) {
+                        info!("✅ Debuggee process {} (PID {:?})", name, pid);
+                    }
+                }
+                StateUpdate::ExitCode(exit_code) => {
+                    state.write().await.record_exit_code(exit_code);
+                    info!("✅ Program exited with code {}", exit_code);
+                }
+                StateUpdate::Sync(done) => {
+                    let _ = done.send(());
+                }
+            }
+        }
+    });
+    tx
+}
+
 /// Session mode - determines how debugging operations are routed
 ///
 /// Single mode is used for languages like Python and Ruby where the debugger
@@ -85,27 +356,58 @@ use uuid::Uuid;
 ///
 /// MultiSession mode is used for adapters like vscode-js-debug that use a
 /// parent-child session architecture, where the parent coordinates and children
-/// do actual debugging.
+/// do actual debugging. Python sessions launched with `subProcess` use it too:
+/// debugpy has no coordinating parent, so the main process is tracked as the
+/// child [`PYTHON_MAIN_PROCESS`] next to the subprocesses it starts.
 pub enum SessionMode {
     /// Single session mode (Python, Ruby)
     Single { client: Arc<RwLock<DapClient>> },
-    /// Multi-session mode (Node.js with vscode-js-debug)
+    /// Multi-session mode (Node.js with vscode-js-debug, Python subprocesses)
     MultiSession {
         parent_client: Arc<RwLock<DapClient>>,
         multi_session_manager: MultiSessionManager,
-        /// Port that vscode-js-debug is listening on (for spawning child connections)
-        vscode_js_debug_port: u16,
+        /// Port that vscode-js-debug is listening on (for spawning child
+        /// connections); 0 for debugpy, whose events carry each address
+        adapter_port: u16,
     },
 }
 
+/// Child ID of a Python multi-session's main process
+pub const PYTHON_MAIN_PROCESS: &str = "main";
+
 pub struct DebugSession {
     pub id: String,
     pub language: String,
     pub program: String,
     pub session_mode: SessionMode,
     pub(crate) state: Arc<RwLock<SessionState>>,
+    /// Ordered queue of the state changes reported by the adapter
+    state_updates: mpsc::UnboundedSender<StateUpdate>,
     /// Pending breakpoints that will be applied after initialization completes
     pending_breakpoints: Arc<RwLock<HashMap<String, Vec<SourceBreakpoint>>>>,
+    /// User override for the stopOnEntry workaround's entry breakpoint line
+    entry_line: Option<usize>,
+    /// How the debugged binary was built, for compiled languages
+    build: Option<CompileOutcome>,
+    /// Translates source paths between the MCP client and the debuggee
+    path_mapper: PathMapper,
+    /// DAP protocol log, when the session was started with `captureDapLog`
+    dap_log: Option<Arc<DapLog>>,
+    /// Request latencies and event counts of the session's clients
+    client_metrics: Arc<ClientMetrics>,
+    /// How long spawning and connecting to the adapter took
+    spawn_time: Option<Duration>,
+    /// Core dump loaded instead of running the program (post-mortem session)
+    core_dump: Option<String>,
+    /// Working directory of the program, when known
+    cwd: Option<String>,
+    /// Where a socket-mode adapter listens; such adapters can outlive the
+    /// server
+    adapter_endpoint: Option<AdapterEndpoint>,
+    /// Keeps resumes, configuration changes and reads from interleaving
+    gate: RequestGate,
+    /// `session{session_id=...}` span shared with the DAP client's tasks
+    span: tracing::Span,
 }
 
 impl DebugSession {
@@ -115,6 +417,11 @@ impl DebugSession {
     /// For multi-session debugging (Node.js), use `new_with_mode()`.
     pub async fn new(language: String, program: String, client: DapClient) -> Result<Self> {
         let id = Uuid::new_v4().to_string();
+        let dap_log = client.dap_log();
+        let client_metrics = client.metrics();
+        let span = client.span().clone();
+        span.record("session_id", id.as_str());
+        let state = Arc::new(RwLock::new(SessionState::new()));
 
         Ok(Self {
             id,
@@ -123,8 +430,20 @@ impl DebugSession {
             session_mode: SessionMode::Single {
                 client: Arc::new(RwLock::new(client)),
             },
-            state: Arc::new(RwLock::new(SessionState::new())),
+            state_updates: spawn_state_updater(state.clone(), None),
+            state,
             pending_breakpoints: Arc::new(RwLock::new(HashMap::new())),
+            entry_line: None,
+            build: None,
+            path_mapper: PathMapper::default(),
+            dap_log,
+            client_metrics,
+            spawn_time: None,
+            core_dump: None,
+            cwd: None,
+            adapter_endpoint: None,
+            gate: RequestGate::default(),
+            span,
         })
     }
 
@@ -137,17 +456,216 @@ impl DebugSession {
         session_mode: SessionMode,
     ) -> Result<Self> {
         let id = Uuid::new_v4().to_string();
+        let client = match &session_mode {
+            SessionMode::Single { client } => client.read().await,
+            SessionMode::MultiSession { parent_client, .. } => parent_client.read().await,
+        };
+        let dap_log = client.dap_log();
+        let client_metrics = client.metrics();
+        let span = client.span().clone();
+        span.record("session_id", id.as_str());
+        drop(client);
+        let state = Arc::new(RwLock::new(SessionState::new()));
+        let children = match &session_mode {
+            SessionMode::Single { .. } => None,
+            SessionMode::MultiSession {
+                multi_session_manager,
+                ..
+            } => Some(multi_session_manager.clone()),
+        };
 
         Ok(Self {
             id,
             language,
             program,
             session_mode,
-            state: Arc::new(RwLock::new(SessionState::new())),
+            state_updates: spawn_state_updater(state.clone(), children),
+            state,
             pending_breakpoints: Arc::new(RwLock::new(HashMap::new())),
+            entry_line: None,
+            build: None,
+            path_mapper: PathMapper::default(),
+            dap_log,
+            client_metrics,
+            spawn_time: None,
+            core_dump: None,
+            cwd: None,
+            adapter_endpoint: None,
+            gate: RequestGate::default(),
+            span,
         })
     }
 
+    /// Override the line used for the entry breakpoint (stopOnEntry workaround)
+    pub fn with_entry_line(mut self, entry_line: Option<usize>) -> Self {
+        self.entry_line = entry_line;
+        self
+    }
+
+    /// Record how the debugged binary was built
+    pub fn with_build(mut self, build: Option<CompileOutcome>) -> Self {
+        self.build = build;
+        self
+    }
+
+    /// How the debugged binary was built (None for interpreted languages)
+    pub fn build(&self) -> Option<&CompileOutcome> {
+        self.build.as_ref()
+    }
+
+    /// Record how long spawning and connecting to the adapter took
+    pub fn with_spawn_time(mut self, spawn_time: Duration) -> Self {
+        self.spawn_time = Some(spawn_time);
+        self
+    }
+
+    /// Record the working directory the program runs in
+    pub fn with_cwd(mut self, cwd: Option<String>) -> Self {
+        self.cwd = cwd;
+        self
+    }
+
+    /// Working directory of the program, against which relative source
+    /// paths from the client are resolved
+    pub fn cwd(&self) -> Option<&str> {
+        self.cwd.as_deref()
+    }
+
+    /// Record where the adapter listens (socket-mode adapters)
+    pub fn with_adapter_endpoint(mut self, endpoint: Option<AdapterEndpoint>) -> Self {
+        self.adapter_endpoint = endpoint;
+        self
+    }
+
+    /// Record the TCP port the adapter listens on
+    pub fn with_adapter_port(self, port: Option<u16>) -> Self {
+        self.with_adapter_endpoint(port.map(AdapterEndpoint::Tcp))
+    }
+
+    pub fn adapter_endpoint(&self) -> Option<&AdapterEndpoint> {
+        self.adapter_endpoint.as_ref()
+    }
+
+    pub fn adapter_port(&self) -> Option<u16> {
+        self.adapter_endpoint
+            .as_ref()
+            .and_then(AdapterEndpoint::port)
+    }
+
+    /// Mark the session as a post-mortem session of `core_dump`
+    pub fn with_core_dump(mut self, core_dump: Option<String>) -> Self {
+        self.core_dump = core_dump;
+        self
+    }
+
+    /// Core dump being inspected, for post-mortem sessions
+    pub fn core_dump(&self) -> Option<&str> {
+        self.core_dump.as_deref()
+    }
+
+    /// Refuse to resume a post-mortem session: the crashed process cannot run
+    fn ensure_live(&self, action: &str) -> Result<()> {
+        match &self.core_dump {
+            Some(core_dump) => Err(crate::Error::InvalidState(format!(
+                "Cannot {} session {}: it is a post-mortem session of core dump {}. \
+                 Inspect the crash with debugger_stack_trace and debugger_evaluate, \
+                 then disconnect",
+                action, self.id, core_dump
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// `session{session_id=...}` span for work done on behalf of this session
+    pub fn span(&self) -> &tracing::Span {
+        &self.span
+    }
+
+    /// Request, event, stop and timing metrics of this session
+    pub async fn metrics(&self) -> SessionMetrics {
+        let write_queue_depth = self
+            .get_debug_client()
+            .await
+            .read()
+            .await
+            .write_queue_depth();
+        let state = self.state.read().await;
+        SessionMetrics {
+            requests_sent: self.client_metrics.requests_sent(),
+            events_received: self.client_metrics.events_received(),
+            stops: state.timing.stops(),
+            time_stopped_ms: millis(state.timing.stopped(&state.state)),
+            time_running_ms: millis(state.timing.running(&state.state)),
+            adapter_spawn_ms: self.spawn_time.map(millis),
+            compile_ms: self.build.as_ref().map(|build| millis(build.duration)),
+            commands: self.client_metrics.commands(),
+            write_queue_depth,
+        }
+    }
+
+    /// DAP requests in a row that timed out without an answer
+    pub fn consecutive_timeouts(&self) -> usize {
+        self.client_metrics.consecutive_timeouts()
+    }
+
+    /// Whether the adapter seems hung: the last
+    /// [`UNHEALTHY_AFTER_TIMEOUTS`] requests all timed out
+    ///
+    /// rdbg in particular can stop answering while its process keeps
+    /// running; such a session can only be ended with [`Self::force_kill`].
+    pub fn is_unhealthy(&self) -> bool {
+        self.consecutive_timeouts() >= UNHEALTHY_AFTER_TIMEOUTS
+    }
+
+    /// DAP protocol log, when capture was enabled for this session
+    pub fn dap_log(&self) -> Option<&Arc<DapLog>> {
+        self.dap_log.as_ref()
+    }
+
+    /// Transcript recorder, when the session is recorded
+    pub async fn recorder(&self) -> Option<Arc<SessionRecorder>> {
+        self.state.read().await.recorder().cloned()
+    }
+
+    /// The last `tail` protocol log entries as JSON (all when `None`)
+    pub fn dap_log_json(&self, tail: Option<usize>) -> Result<serde_json::Value> {
+        let log = self.dap_log.as_ref().ok_or_else(|| {
+            crate::Error::InvalidRequest(format!(
+                "DAP log capture is not enabled for session {}; start it with captureDapLog: true",
+                self.id
+            ))
+        })?;
+        let mut json = log.to_json(tail);
+        json["sessionId"] = serde_json::json!(self.id);
+        Ok(json)
+    }
+
+    /// Use `id` instead of the generated session ID (a restarted session
+    /// keeps the ID its client knows)
+    pub fn with_id(mut self, id: Option<String>) -> Self {
+        if let Some(id) = id {
+            self.span.record("session_id", id.as_str());
+            self.id = id;
+        }
+        self
+    }
+
+    /// Translate breakpoint and stack frame paths with `path_mapper`
+    pub fn with_path_mapper(mut self, path_mapper: PathMapper) -> Self {
+        self.path_mapper = path_mapper;
+        self
+    }
+
+    /// Source sent to the adapter for a path given by the MCP client
+    fn adapter_source(&self, source_path: &str) -> Source {
+        Source {
+            name: None,
+            path: Some(self.path_mapper.to_remote(source_path)),
+            source_reference: None,
+            origin: None,
+        }
+    }
+
     /// Get the client to use for debugging operations
     ///
     /// # Parent vs Child Responsibilities (Multi-Session Mode)
@@ -198,6 +716,128 @@ impl DebugSession {
         }
     }
 
+    /// Get the client that owns `thread_id`
+    ///
+    /// With several Node.js children, thread-specific requests (continue,
+    /// step, stackTrace) must go to the child the thread belongs to, which is
+    /// not necessarily the active one. Falls back to [`Self::get_debug_client`].
+    async fn get_thread_client(&self, thread_id: i32) -> Arc<RwLock<DapClient>> {
+        if let SessionMode::MultiSession {
+            multi_session_manager,
+            ..
+        } = &self.session_mode
+        {
+            if let Some(client) = multi_session_manager.client_for_thread(thread_id).await {
+                return client;
+            }
+        }
+        self.get_debug_client().await
+    }
+
+    /// Get every client breakpoints must be sent to
+    ///
+    /// Each Node.js child runs its own process, so breakpoints go to all of
+    /// them (the parent only while no child exists yet).
+    async fn get_breakpoint_clients(&self) -> Vec<Arc<RwLock<DapClient>>> {
+        match &self.session_mode {
+            SessionMode::Single { client } => vec![client.clone()],
+            SessionMode::MultiSession {
+                parent_client,
+                multi_session_manager,
+                ..
+            } => {
+                let children = multi_session_manager.child_clients().await;
+                if children.is_empty() {
+                    vec![parent_client.clone()]
+                } else {
+                    children
+                }
+            }
+        }
+    }
+
+    /// Send setBreakpoints for `source` to every breakpoint client
+    ///
+    /// A breakpoint counts as verified when any child verified it. Fails only
+    /// when every client failed.
+    async fn set_breakpoints_everywhere(
+        &self,
+        source: Source,
+        breakpoints: Vec<SourceBreakpoint>,
+    ) -> Result<Vec<crate::dap::types::Breakpoint>> {
+        let clients = self.get_breakpoint_clients().await;
+        let mut results = Vec::with_capacity(clients.len());
+        for client in &clients {
+            results.push(
+                client
+                    .read()
+                    .await
+                    .set_breakpoints(source.clone(), breakpoints.clone())
+                    .await,
+            );
+        }
+        merge_breakpoint_results(results)
+    }
+
+    /// Send setFunctionBreakpoints to every breakpoint client (see
+    /// [`Self::set_breakpoints_everywhere`])
+    async fn set_function_breakpoints_everywhere(
+        &self,
+        breakpoints: Vec<FunctionBreakpoint>,
+    ) -> Result<Vec<crate::dap::types::Breakpoint>> {
+        let clients = self.get_breakpoint_clients().await;
+        let mut results = Vec::with_capacity(clients.len());
+        for client in &clients {
+            results.push(
+                client
+                    .read()
+                    .await
+                    .set_function_breakpoints(breakpoints.clone())
+                    .await,
+            );
+        }
+        merge_breakpoint_results(results)
+    }
+
+    /// Node.js child sessions with their threads and state (empty outside
+    /// multi-session mode)
+    pub async fn children(&self) -> Vec<ChildInfo> {
+        match &self.session_mode {
+            SessionMode::Single { .. } => Vec::new(),
+            SessionMode::MultiSession {
+                multi_session_manager,
+                ..
+            } => multi_session_manager.child_infos().await,
+        }
+    }
+
+    /// Create a Python session that follows subprocesses (`subProcess`)
+    ///
+    /// Each subprocess debugpy reports gets its own client; the launched
+    /// process is tracked as the child [`PYTHON_MAIN_PROCESS`], so the
+    /// session ends once it and every subprocess have ended.
+    pub async fn new_following_subprocesses(program: String, client: DapClient) -> Result<Self> {
+        let multi_session_manager = MultiSessionManager::new(Uuid::new_v4().to_string());
+        let client = Arc::new(RwLock::new(client));
+        multi_session_manager
+            .add_child(ChildSession {
+                id: PYTHON_MAIN_PROCESS.to_string(),
+                client: client.clone(),
+                port: 0,
+                session_type: "python".to_string(),
+            })
+            .await;
+        // No coordinating parent that could end separately
+        multi_session_manager.record_parent_finished();
+
+        let session_mode = SessionMode::MultiSession {
+            parent_client: client,
+            multi_session_manager,
+            adapter_port: 0,
+        };
+        Self::new_with_mode("python".to_string(), program, session_mode).await
+    }
+
     /// Spawn a child session for multi-session debugging (Node.js vscode-js-debug)
     ///
     /// This method is called when vscode-js-debug sends a `startDebugging` reverse request
@@ -215,19 +855,23 @@ impl DebugSession {
     /// # Returns
     ///
     /// Ok(()) if child session spawned successfully, Err otherwise
-    pub async fn spawn_child_session(&self, target_id: String) -> Result<()> {
+    pub async fn spawn_child_session(self: &Arc<Self>, target_id: String) -> Result<()> {
         info!(
             "🔄 [MULTI-SESSION] Spawning child session for target_id: {}",
             target_id
         );
 
         // Only works in multi-session mode
-        let (multi_session_manager, vscode_port) = match &self.session_mode {
+        let (multi_session_manager, vscode_port, parent_client) = match &self.session_mode {
             SessionMode::MultiSession {
                 multi_session_manager,
-                vscode_js_debug_port,
-                ..
-            } => (multi_session_manager.clone(), *vscode_js_debug_port),
+                adapter_port,
+                parent_client,
+            } => (
+                multi_session_manager.clone(),
+                *adapter_port,
+                parent_client.clone(),
+            ),
             _ => {
                 return Err(crate::Error::InvalidState(
                     "spawn_child_session called on non-multi-session session".to_string(),
@@ -235,6 +879,15 @@ impl DebugSession {
             }
         };
 
+        let child_id = format!("child-{}", &target_id);
+        // Only a JavaScript program gets the entry breakpoint: with a package
+        // manager runtime (npm run ...) the program is the package itself
+        let entry_program = std::path::Path::new(&self.program)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| nodejs::PROGRAM_EXTENSIONS.contains(&ext));
+        let first_child = multi_session_manager.child_count().await == 0;
+
         // 1. Connect to vscode-js-debug port (SAME as parent)
         info!(
             "   Connecting to vscode-js-debug on localhost:{}",
@@ -253,6 +906,12 @@ impl DebugSession {
 
         // 2. Create DAP client for child
         let child_client = DapClient::from_socket(socket).await?;
+        child_client.set_request_timeouts(parent_client.read().await.request_timeouts());
+        if let Some(log) = &self.dap_log {
+            child_client.capture_dap_log(Arc::clone(log));
+        }
+        child_client.share_metrics(Arc::clone(&self.client_metrics));
+        child_client.span().record("session_id", self.id.as_str());
         info!("   Created DAP client for child session");
 
         // 3. Initialize child session
@@ -287,175 +946,86 @@ impl DebugSession {
         // 5. Register event handlers for child (forward to parent state)
         info!("   Registering event handlers for child session");
 
-        // Handler for 'stopped' events from child
-        let session_state = self.state.clone();
-        child_client
-            .on_event("stopped", move |event| {
-                info!("📍 [CHILD] Received 'stopped' event: {:?}", event);
-                // Update parent session state
-                let state_clone = session_state.clone();
-                tokio::spawn(async move {
-                    if let Some(body) = &event.body {
-                        let thread_id = body
-                            .get("threadId")
-                            .and_then(|v| v.as_i64())
-                            .map(|v| v as i32)
-                            .unwrap_or(1);
-                        let reason = body
-                            .get("reason")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("unknown")
-                            .to_string();
-
-                        info!(
-                            "   [CHILD] Updating parent state to Stopped (thread: {}, reason: {})",
-                            thread_id, reason
-                        );
-
-                        let mut state = state_clone.write().await;
-                        state.set_state(DebugState::Stopped {
-                            thread_id,
-                            reason: reason.clone(),
-                        });
-
-                        info!("   ✅ Parent state updated to Stopped (reason: {})", reason);
-                    }
-                });
-            })
-            .await;
-
-        // Handler for 'continued' events from child
-        let session_state = self.state.clone();
-        child_client
-            .on_event("continued", move |event| {
-                info!("▶️  [CHILD] Received 'continued' event: {:?}", event);
-                let state_clone = session_state.clone();
-                tokio::spawn(async move {
-                    let mut state = state_clone.write().await;
-                    state.set_state(DebugState::Running);
-                    info!("   ✅ Parent state updated to Running");
-                });
-            })
-            .await;
-
-        // Handler for 'terminated' events from child
-        let session_state = self.state.clone();
-        child_client
-            .on_event("terminated", move |event| {
-                info!("🛑 [CHILD] Received 'terminated' event: {:?}", event);
-                let state_clone = session_state.clone();
-                tokio::spawn(async move {
-                    let mut state = state_clone.write().await;
-                    state.set_state(DebugState::Terminated);
-                    info!("   ✅ Parent state updated to Terminated");
-                });
-            })
-            .await;
-
-        // Handler for 'exited' events from child
-        let session_state = self.state.clone();
-        child_client
-            .on_event("exited", move |event| {
-                info!("🚪 [CHILD] Received 'exited' event: {:?}", event);
-                let state_clone = session_state.clone();
-                tokio::spawn(async move {
-                    let mut state = state_clone.write().await;
-                    state.set_state(DebugState::Terminated);
-                    info!("   ✅ Parent state updated to Terminated (exited)");
-                });
-            })
+        self.register_state_handlers(&child_client, Some(&child_id))
             .await;
+        self.register_output_handler(&child_client).await;
+        // Processes started by the child (npm → node, node → worker) are
+        // announced on the child's connection
+        self.register_child_spawner(&child_client).await;
 
         info!("   Event handlers registered for child session");
 
         // 5. Set entry breakpoint on child (stopOnEntry workaround for Node.js)
         //    The child session is what actually runs the user's code, so it needs
-        //    the entry breakpoint, not the parent.
+        //    the entry breakpoint, not the parent. Processes spawned later by the
+        //    program (child_process, workers) only get the user's breakpoints.
         //    Use intelligent line detection to skip comments/imports.
-        let entry_line =
-            crate::dap::client::DapClient::find_first_executable_line_javascript(&self.program);
-        info!(
-            "   Setting entry breakpoint on child at line {} of {}",
-            entry_line, self.program
-        );
-        let source = crate::dap::types::Source {
-            path: Some(self.program.clone()),
-            name: None,
-            source_reference: None,
-        };
-        let entry_bp = crate::dap::types::SourceBreakpoint {
-            line: entry_line as i32,
-            column: None,
-            condition: None,
-            hit_condition: None,
-        };
-        match child_client
-            .set_breakpoints(source.clone(), vec![entry_bp])
-            .await
-        {
-            Ok(verified_bps) => {
-                if !verified_bps.is_empty() && verified_bps[0].verified {
-                    info!(
-                        "   ✅ Entry breakpoint set and verified on child at line {}",
-                        entry_line
-                    );
-                } else {
-                    error!("   ❌ Entry breakpoint could not be verified on child");
-                }
-            }
-            Err(e) => {
-                error!("   ❌ Failed to set entry breakpoint on child: {}", e);
-            }
+        if first_child && entry_program {
+            self.set_child_entry_breakpoint(&child_client).await;
         }
 
-        // 6. Copy pending breakpoints from parent to child
-        info!("   Checking for pending breakpoints to copy to child...");
-        let breakpoints = self.pending_breakpoints.read().await;
-        if !breakpoints.is_empty() {
-            info!(
-                "   Found {} files with pending breakpoints",
-                breakpoints.len()
-            );
-            for (file, bp_list) in breakpoints.iter() {
-                info!("     File: {} has {} breakpoints", file, bp_list.len());
-                // Set breakpoints on child session
-                let source = crate::dap::types::Source {
-                    path: Some(file.clone()),
-                    name: None,
-                    source_reference: None,
-                };
+        // 6. Copy the session's breakpoints to the child
+        info!("   Copying breakpoints to child...");
+        let tracked = self.state.read().await.breakpoints.clone();
+        if tracked.is_empty() {
+            info!("   No breakpoints to copy");
+        }
+        for (file, bp_list) in tracked {
+            info!("     File: {} has {} breakpoints", file, bp_list.len());
+            let requested: Vec<SourceBreakpoint> = bp_list
+                .iter()
+                .map(|bp| SourceBreakpoint {
+                    line: bp.line,
+                    column: bp.column,
+                    condition: bp.condition.clone(),
+                    hit_condition: bp.hit_condition.clone(),
+                    log_message: bp.log_message.clone(),
+                })
+                .collect();
 
-                match child_client.set_breakpoints(source, bp_list.clone()).await {
-                    Ok(verified_bps) => {
-                        info!(
-                            "     ✅ {} breakpoints set on child for {}",
-                            verified_bps.len(),
-                            file
-                        );
-                    }
-                    Err(e) => {
-                        error!(
-                            "     ❌ Failed to set breakpoints on child for {}: {}",
-                            file, e
-                        );
+            match child_client
+                .set_breakpoints(self.adapter_source(&file), requested)
+                .await
+            {
+                Ok(verified_bps) => {
+                    info!(
+                        "     ✅ {} breakpoints set on child for {}",
+                        verified_bps.len(),
+                        file
+                    );
+                    // Another child may already have verified them
+                    let mut state = self.state.write().await;
+                    for (requested, bp) in bp_list.iter().zip(&verified_bps) {
+                        if bp.verified {
+                            state.record_breakpoint_result(
+                                &file,
+                                requested.line,
+                                bp.id,
+                                true,
+                                None,
+                            );
+                        }
                     }
                 }
+                Err(e) => {
+                    error!(
+                        "     ❌ Failed to set breakpoints on child for {}: {}",
+                        file, e
+                    );
+                }
             }
-        } else {
-            info!("   No pending breakpoints to copy");
         }
 
-        // 6. Send configurationDone to child so it starts running
+        // 7. Send configurationDone to child so it starts running
         info!("   Sending configurationDone to child session");
         match child_client.configuration_done().await {
             Ok(_) => info!("   ✅ Child session configuration complete"),
             Err(e) => error!("   ❌ Failed to send configurationDone to child: {}", e),
         }
 
-        // 7. Add to multi-session manager
-        use super::multi_session::ChildSession;
+        // 8. Add to multi-session manager
         let child = ChildSession {
-            id: format!("child-{}", &target_id),
+            id: child_id,
             client: Arc::new(RwLock::new(child_client)),
             port: vscode_port, // Store vscode-js-debug port, not a child-specific port
             session_type: "pwa-node".to_string(),
@@ -472,123 +1042,521 @@ impl DebugSession {
         Ok(())
     }
 
-    /// Initialize and launch using the proper DAP sequence
-    /// This combines initialize and launch into one atomic operation
-    pub async fn initialize_and_launch(
-        &self,
-        adapter_id: &str,
-        launch_args: serde_json::Value,
+    /// Spawn a child session for every `startDebugging` request (Node.js) or
+    /// `debugpyAttach` event (Python) `client` receives: the parent, and
+    /// children that start processes of their own
+    pub async fn register_child_spawner(self: &Arc<Self>, client: &DapClient) {
+        let session = Arc::clone(self);
+        if self.language == "python" {
+            client
+                .replace_event_handler("debugpyAttach", SESSION_HANDLER_OWNER, move |event| {
+                    let config = event.body.unwrap_or_default();
+                    tokio::spawn(Self::attach_subprocess(session.clone(), config));
+                })
+                .await;
+        } else {
+            client
+                .on_child_session_spawn(move |target_id| {
+                    Self::spawn_child(session.clone(), target_id)
+                })
+                .await;
+        }
+    }
+
+    fn attach_subprocess(
+        session: Arc<Self>,
+        config: serde_json::Value,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(async move {
+            if let Err(e) = session.attach_python_subprocess(config).await {
+                error!("❌ [PYTHON] Failed to attach to subprocess: {}", e);
+            }
+        })
+    }
+
+    /// Attach to a subprocess announced by debugpy's `debugpyAttach` event
+    ///
+    /// The event body is the attach configuration to send: it names the
+    /// address the adapter accepts the subprocess's connection on and the
+    /// `subProcessId`. The subprocess waits for configurationDone, so the
+    /// session's breakpoints are in place before it runs.
+    pub async fn attach_python_subprocess(
+        self: &Arc<Self>,
+        config: serde_json::Value,
     ) -> Result<()> {
+        let SessionMode::MultiSession {
+            multi_session_manager,
+            parent_client,
+            ..
+        } = &self.session_mode
+        else {
+            return Err(crate::Error::InvalidState(
+                "debugpyAttach received outside a multi-session".to_string(),
+            ));
+        };
+        let (host, port) = subprocess_address(&config)?;
+        let child_id = match config.get("subProcessId") {
+            Some(pid) => format!("subprocess-{}", pid),
+            None => format!("subprocess-{}", port),
+        };
+        info!(
+            "🔄 [MULTI-SESSION] Attaching to Python subprocess {} at {}:{}",
+            child_id, host, port
+        );
+
+        let socket = tokio::net::TcpStream::connect((host.as_str(), port))
+            .await
+            .map_err(|e| {
+                crate::Error::Process(format!(
+                    "Failed to connect to debugpy subprocess at {}:{}: {}",
+                    host, port, e
+                ))
+            })?;
+        let child_client = DapClient::from_socket(socket).await?;
+        child_client.set_request_timeouts(parent_client.read().await.request_timeouts());
+        if let Some(log) = &self.dap_log {
+            child_client.capture_dap_log(Arc::clone(log));
+        }
+        child_client.share_metrics(Arc::clone(&self.client_metrics));
+        child_client.span().record("session_id", self.id.as_str());
+
+        self.register_state_handlers(&child_client, Some(&child_id))
+            .await;
+        self.register_output_handler(&child_client).await;
+        self.register_breakpoint_results_handler(&child_client)
+            .await;
+        self.register_child_spawner(&child_client).await;
+
+        // Tracked before the attach: the subprocess may stop as soon as it runs
+        let client = Arc::new(RwLock::new(child_client));
+        multi_session_manager
+            .add_child(ChildSession {
+                id: child_id.clone(),
+                client: client.clone(),
+                port,
+                session_type: "python".to_string(),
+            })
+            .await;
+
+        let breakpoints: HashMap<String, Vec<SourceBreakpoint>> = self
+            .state
+            .read()
+            .await
+            .breakpoints
+            .iter()
+            .map(|(file, bps)| {
+                let requested = bps
+                    .iter()
+                    .map(|bp| SourceBreakpoint {
+                        line: bp.line,
+                        column: bp.column,
+                        condition: bp.condition.clone(),
+                        hit_condition: bp.hit_condition.clone(),
+                        log_message: bp.log_message.clone(),
+                    })
+                    .collect();
+                (self.path_mapper.to_remote(file), requested)
+            })
+            .collect();
+
+        let attached = client
+            .read()
+            .await
+            .initialize_and_launch_with_timeout_and_pending(
+                PythonAdapter::adapter_id(),
+                config,
+                Some("python"),
+                breakpoints,
+                None,
+            )
+            .await;
+        if let Err(e) = attached {
+            let _ = multi_session_manager.remove_child(&child_id).await;
+            return Err(e);
+        }
+
+        info!("🎉 [MULTI-SESSION] Python subprocess {} attached", child_id);
+        Ok(())
+    }
+
+    fn spawn_child(
+        session: Arc<Self>,
+        target_id: String,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(async move {
+            info!(
+                "🎯 [NODEJS] Child session spawn callback invoked for target_id: {}",
+                target_id
+            );
+            if let Err(e) = session.spawn_child_session(target_id).await {
+                error!("❌ [NODEJS] Failed to spawn child session: {}", e);
+            } else {
+                info!("✅ [NODEJS] Child session spawned successfully");
+            }
+        })
+    }
+
+    /// Set the stopOnEntry breakpoint on the first Node.js child
+    async fn set_child_entry_breakpoint(&self, child_client: &DapClient) {
+        let resolved = source_analysis::resolve_entry_line(
+            SourceLanguage::JavaScript,
+            &self.program,
+            self.entry_line,
+        );
+        let entry_line = resolved.line;
+        self.state.write().await.entry_line = Some(resolved);
+        info!(
+            "   Setting entry breakpoint on child at line {} of {}",
+            entry_line, self.program
+        );
+        let source = crate::dap::types::Source {
+            path: Some(self.program.clone()),
+            name: None,
+            source_reference: None,
+            origin: None,
+        };
+        let entry_bp = crate::dap::types::SourceBreakpoint {
+            line: entry_line as i32,
+            column: None,
+            condition: None,
+            hit_condition: None,
+            log_message: None,
+        };
+        match child_client
+            .set_breakpoints(source.clone(), vec![entry_bp])
+            .await
         {
-            let mut state = self.state.write().await;
-            state.set_state(DebugState::Initializing);
+            Ok(verified_bps) => {
+                if !verified_bps.is_empty() && verified_bps[0].verified {
+                    info!(
+                        "   ✅ Entry breakpoint set and verified on child at line {}",
+                        entry_line
+                    );
+                } else {
+                    error!("   ❌ Entry breakpoint could not be verified on child");
+                }
+            }
+            Err(e) => {
+                error!("   ❌ Failed to set entry breakpoint on child: {}", e);
+            }
         }
+    }
 
-        let client_arc = self.get_debug_client().await;
-        let client = client_arc.read().await;
+    /// Track the client's 'stopped', 'continued', 'terminated', 'exited',
+    /// 'thread', 'breakpoint', 'loadedSource', 'module' and 'process' events
+    /// in the session state, and apply 'capabilities' events to the client
+    ///
+    /// Updates go through the session's ordered queue, so they are applied in
+    /// the order the adapter sent them. Registering again replaces the
+    /// previous handlers.
+    ///
+    /// For a Node.js child session (`child_id`), stops are tagged with the
+    /// child and tracked in the [`MultiSessionManager`]: the session stays
+    /// Stopped while any child is stopped.
+    async fn register_state_handlers(&self, client: &DapClient, child_id: Option<&str>) {
+        let multi_session = matches!(self.session_mode, SessionMode::MultiSession { .. });
+        let child = match (&self.session_mode, child_id) {
+            (
+                SessionMode::MultiSession {
+                    multi_session_manager,
+                    ..
+                },
+                Some(child_id),
+            ) => Some((child_id.to_string(), multi_session_manager.clone())),
+            _ => None,
+        };
+        let origin = match child_id {
+            Some(child_id) => format!("[CHILD {}] ", child_id),
+            None => String::new(),
+        };
 
-        // Register event handlers BEFORE launching to capture all state changes
-        info!("📡 Registering DAP event handlers for session state tracking");
+        let updates = self.state_updates.clone();
+        let stopped_child = child.clone();
+        let stopped_origin = origin.clone();
+        let entry_breakpoint = client.entry_breakpoint_slot();
+        client
+            .replace_event_handler("stopped", SESSION_HANDLER_OWNER, move |event| {
+                let origin = &stopped_origin;
+                info!("📍 {}Received 'stopped' event: {:?}", origin, event);
+                if let Some(body) = &event.body {
+                    let mut stop = StopContext::from_event_body(body);
+                    // The stopOnEntry workaround's breakpoint is not the user's
+                    if let Some(entry) = entry_breakpoint
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .as_mut()
+                    {
+                        if entry.record_stop(&stop.reason, &stop.hit_breakpoint_ids) {
+                            info!("🚪 {}Stopped at the entry breakpoint", origin);
+                            stop.reason = "entry".to_string();
+                            stop.hit_breakpoint_ids.retain(|id| Some(*id) != entry.id);
+                        }
+                    }
+                    if let Some((child_id, manager)) = &stopped_child {
+                        stop.child_session = Some(child_id.clone());
+                        manager.record_stop(child_id, &stop);
+                    }
+                    info!(
+                        "   {}Thread: {}, Reason: {}, Hit breakpoints: {:?}",
+                        origin, stop.thread_id, stop.reason, stop.hit_breakpoint_ids
+                    );
+                    let _ = updates.send(StateUpdate::Stopped(stop));
+                }
+            })
+            .await;
 
-        // Handler for 'stopped' events (breakpoints, steps, entry)
-        let session_state = self.state.clone();
+        let updates = self.state_updates.clone();
+        let continued_child = child.clone();
+        let continued_origin = origin.clone();
         client
-            .on_event("stopped", move |event| {
-                info!("📍 Received 'stopped' event: {:?}", event);
+            .replace_event_handler("continued", SESSION_HANDLER_OWNER, move |event| {
+                let origin = &continued_origin;
+                info!("▶️  {}Received 'continued' event: {:?}", origin, event);
+                let still_stopped = continued_child
+                    .as_ref()
+                    .and_then(|(child_id, manager)| manager.record_running(child_id));
+                let _ = match still_stopped {
+                    Some(stop) => updates.send(StateUpdate::Refocus(stop)),
+                    None => updates.send(StateUpdate::Running),
+                };
+            })
+            .await;
 
-                if let Some(body) = &event.body {
-                    let thread_id = body
-                        .get("threadId")
-                        .and_then(|v| v.as_i64())
-                        .map(|v| v as i32)
-                        .unwrap_or(1);
-
-                    let reason = body
-                        .get("reason")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("unknown")
-                        .to_string();
-
-                    info!("   Thread: {}, Reason: {}", thread_id, reason);
-
-                    // Update session state
-                    let state_clone = session_state.clone();
-                    tokio::spawn(async move {
-                        let mut state = state_clone.write().await;
-                        state.set_state(DebugState::Stopped {
-                            thread_id,
-                            reason: reason.clone(),
-                        });
-                        info!("✅ Session state updated to Stopped (reason: {})", reason);
-                    });
+        // A child ending only removes that child (see spawn_state_updater)
+        let ended_child = child_id.map(str::to_string);
+        let finished = move |exited: bool| match &ended_child {
+            Some(child_id) => StateUpdate::ChildFinished(child_id.clone()),
+            None if multi_session => StateUpdate::ParentFinished { exited },
+            None => StateUpdate::Terminated { exited },
+        };
+
+        let updates = self.state_updates.clone();
+        let terminated_origin = origin.clone();
+        let terminated = finished.clone();
+        client
+            .replace_event_handler("terminated", SESSION_HANDLER_OWNER, move |event| {
+                info!(
+                    "🛑 {}Received 'terminated' event: {:?}",
+                    terminated_origin, event
+                );
+                let _ = updates.send(terminated(false));
+            })
+            .await;
+
+        let updates = self.state_updates.clone();
+        let exited_origin = origin.clone();
+        client
+            .replace_event_handler("exited", SESSION_HANDLER_OWNER, move |event| {
+                info!("🚪 {}Received 'exited' event: {:?}", exited_origin, event);
+                if let Some(exit_code) = event
+                    .body
+                    .as_ref()
+                    .and_then(|body| body.get("exitCode"))
+                    .and_then(|code| code.as_i64())
+                {
+                    let _ = updates.send(StateUpdate::ExitCode(exit_code));
                 }
+                let _ = updates.send(finished(true));
             })
             .await;
 
-        // Handler for 'continued' events
-        let session_state = self.state.clone();
+        let updates = self.state_updates.clone();
         client
-            .on_event("continued", move |event| {
-                info!("▶️  Received 'continued' event: {:?}", event);
+            .replace_event_handler("thread", SESSION_HANDLER_OWNER, move |event| {
+                let Some(body) = &event.body else {
+                    return;
+                };
+                if let Some(thread_id) = body.get("threadId").and_then(|v| v.as_i64()) {
+                    if let Some((child_id, manager)) = &child {
+                        let exited = body.get("reason").and_then(|v| v.as_str()) == Some("exited");
+                        manager.record_thread(child_id, thread_id as i32, exited);
+                    }
+                    let _ = updates.send(StateUpdate::Thread(thread_id as i32));
+                }
+            })
+            .await;
 
-                let state_clone = session_state.clone();
-                tokio::spawn(async move {
-                    let mut state = state_clone.write().await;
-                    state.set_state(DebugState::Running);
-                    info!("✅ Session state updated to Running");
-                });
+        // Breakpoints that bind late (lazily loaded modules) or move
+        let updates = self.state_updates.clone();
+        client
+            .replace_event_handler("breakpoint", SESSION_HANDLER_OWNER, move |event| {
+                let Some(body) = &event.body else {
+                    return;
+                };
+                let Some(breakpoint) = body.get("breakpoint").and_then(|bp| {
+                    serde_json::from_value::<crate::dap::types::Breakpoint>(bp.clone()).ok()
+                }) else {
+                    return;
+                };
+                let reason = body
+                    .get("reason")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("changed")
+                    .to_string();
+                let _ = updates.send(StateUpdate::Breakpoint { reason, breakpoint });
             })
             .await;
 
-        // Handler for 'terminated' events
-        let session_state = self.state.clone();
+        let updates = self.state_updates.clone();
+        let path_mapper = self.path_mapper.clone();
         client
-            .on_event("terminated", move |event| {
-                info!("🛑 Received 'terminated' event: {:?}", event);
-
-                let state_clone = session_state.clone();
-                tokio::spawn(async move {
-                    let mut state = state_clone.write().await;
-                    state.set_state(DebugState::Terminated);
-                    info!("✅ Session state updated to Terminated");
+            .replace_event_handler("loadedSource", SESSION_HANDLER_OWNER, move |event| {
+                let Some(body) = &event.body else {
+                    return;
+                };
+                // Sources without a path (eval'd code, <string>) cannot hold
+                // file breakpoints
+                let Some(path) = body
+                    .get("source")
+                    .and_then(|source| source.get("path"))
+                    .and_then(|path| path.as_str())
+                else {
+                    return;
+                };
+                let reason = body
+                    .get("reason")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("new")
+                    .to_string();
+                let _ = updates.send(StateUpdate::LoadedSource {
+                    reason,
+                    path: path_mapper.to_local(path),
                 });
             })
             .await;
 
-        // Handler for 'exited' events
-        let session_state = self.state.clone();
+        let updates = self.state_updates.clone();
         client
-            .on_event("exited", move |event| {
-                info!("🚪 Received 'exited' event: {:?}", event);
-
-                let state_clone = session_state.clone();
-                tokio::spawn(async move {
-                    let mut state = state_clone.write().await;
-                    state.set_state(DebugState::Terminated);
-                    info!("✅ Session state updated to Terminated (exited)");
-                });
+            .replace_event_handler("module", SESSION_HANDLER_OWNER, move |event| {
+                let Some(body) = &event.body else {
+                    return;
+                };
+                let Some(module) = body
+                    .get("module")
+                    .and_then(|module| serde_json::from_value::<Module>(module.clone()).ok())
+                else {
+                    return;
+                };
+                let reason = body
+                    .get("reason")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("new")
+                    .to_string();
+                let _ = updates.send(StateUpdate::Module { reason, module });
             })
             .await;
 
-        // Handler for 'thread' events (track threads)
-        let session_state = self.state.clone();
+        let updates = self.state_updates.clone();
         client
-            .on_event("thread", move |event| {
-                if let Some(body) = &event.body {
-                    if let Some(thread_id) = body.get("threadId").and_then(|v| v.as_i64()) {
-                        let state_clone = session_state.clone();
-                        tokio::spawn(async move {
-                            let mut state = state_clone.write().await;
-                            state.add_thread(thread_id as i32);
-                        });
-                    }
+            .replace_event_handler("process", SESSION_HANDLER_OWNER, move |event| {
+                let Some(process) = event
+                    .body
+                    .and_then(|body| serde_json::from_value::<DebuggeeProcess>(body).ok())
+                else {
+                    return;
+                };
+                let _ = updates.send(StateUpdate::Process(process));
+            })
+            .await;
+
+        // Adapters announce capabilities they only gain mid-session (e.g.
+        // once the runtime is loaded); later feature checks must see them
+        let capabilities = client.capabilities_slot();
+        client
+            .replace_event_handler("capabilities", SESSION_HANDLER_OWNER, move |event| {
+                let Some(changed) = event
+                    .body
+                    .as_ref()
+                    .and_then(|body| body.get("capabilities"))
+                else {
+                    return;
+                };
+                info!("🧩 Adapter capabilities changed: {}", changed);
+                let mut capabilities = capabilities.write().unwrap_or_else(|e| e.into_inner());
+                if let Some(capabilities) = capabilities.as_mut() {
+                    capabilities.merge(changed);
                 }
             })
             .await;
+    }
+
+    /// Wait until every state change reported so far has been applied
+    async fn sync_state(&self) {
+        let (done_tx, done_rx) = oneshot::channel();
+        if self.state_updates.send(StateUpdate::Sync(done_tx)).is_ok() {
+            let _ = done_rx.await;
+        }
+    }
+
+    /// Buffer the client's 'output' events (stdout, stderr, logpoints) in the session state
+    ///
+    /// Events are funnelled through a channel so they are stored in the order
+    /// the adapter sent them. Registering again replaces the previous handler,
+    /// whose channel task then ends.
+    async fn register_output_handler(&self, client: &DapClient) {
+        let (output_tx, mut output_rx) = tokio::sync::mpsc::unbounded_channel();
+        let session_state = self.state.clone();
+        tokio::spawn(async move {
+            while let Some((category, output)) = output_rx.recv().await {
+                session_state.write().await.push_output(category, output);
+            }
+        });
+
+        client
+            .replace_event_handler("output", SESSION_HANDLER_OWNER, move |event| {
+                let Some(body) = &event.body else {
+                    return;
+                };
+                let output = body
+                    .get("output")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                // Per the DAP spec a missing category means 'console'
+                let category = body
+                    .get("category")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("console")
+                    .to_string();
+                let _ = output_tx.send((category, output));
+            })
+            .await;
+    }
+
+    /// Initialize and launch using the proper DAP sequence
+    /// This combines initialize and launch into one atomic operation
+    pub async fn initialize_and_launch(
+        &self,
+        adapter_id: &str,
+        launch_args: serde_json::Value,
+    ) -> Result<()> {
+        {
+            let mut state = self.state.write().await;
+            state.set_state(DebugState::Initializing);
+            // The code the program runs from now on
+            state.track_source(&self.program);
+        }
+
+        let client_arc = self.get_debug_client().await;
+        let client = client_arc.read().await;
+
+        // Register event handlers BEFORE launching to capture all state changes
+        info!("📡 Registering DAP event handlers for session state tracking");
+
+        // 'stopped', 'continued', 'terminated', 'exited', 'thread',
+        // 'breakpoint', 'loadedSource', 'module', 'process' and
+        // 'capabilities' events
+        self.register_state_handlers(&client, self.main_process_child())
+            .await;
+
+        // Handler for 'output' events (program output and logpoint messages)
+        self.register_output_handler(&client).await;
+
+        self.register_breakpoint_results_handler(&client).await;
 
         // Use the DapClient's event-driven initialize_and_launch method with timeout
         // This properly handles the 'initialized' event and configurationDone sequence
-        // Timeout: 7s (2s for init + 5s for launch, as per TIMEOUT_IMPLEMENTATION.md)
+        // Timeout: the session's initialize timeout (7s, 15s for Rust and Node.js)
         // Pass adapter type for language-specific workarounds (e.g., Ruby stopOnEntry fix)
         let adapter_type = match self.language.as_str() {
             "python" => Some("python"),
@@ -597,6 +1565,11 @@ impl DebugSession {
             _ => None,
         };
 
+        let stop_on_entry = launch_args
+            .get("stopOnEntry")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
         // Collect pending breakpoints to pass to initialization
         // They will be applied AFTER 'initialized' event, BEFORE configurationDone (correct DAP sequence)
         let pending_breakpoints_map = {
@@ -606,7 +1579,10 @@ impl DebugSession {
                 "🔧 Passing {} pending breakpoint(s) to initialization (will be applied before configurationDone)",
                 pending_count
             );
-            pending.clone()
+            pending
+                .iter()
+                .map(|(path, bps)| (self.path_mapper.to_remote(path), bps.clone()))
+                .collect::<HashMap<_, _>>()
         };
 
         // Initialize and launch with pending breakpoints
@@ -617,9 +1593,15 @@ impl DebugSession {
                 launch_args,
                 adapter_type,
                 pending_breakpoints_map.clone(),
+                self.entry_line,
             )
             .await?;
 
+        // A guessed entry line surfaces as a session diagnostic
+        if let Some(entry) = client.entry_line() {
+            self.state.write().await.entry_line = Some(entry);
+        }
+
         // Clear pending breakpoints since they've been applied
         {
             let mut pending = self.pending_breakpoints.write().await;
@@ -632,6 +1614,15 @@ impl DebugSession {
             }
         }
 
+        // debugpy honours stopOnEntry natively, but a fast-exiting script can still
+        // run to completion before the entry stop is observed. Verify it actually
+        // happened so callers get an actionable diagnostic instead of a timeout.
+        // (Ruby and Node.js reach their entry stop through workarounds instead.)
+        if stop_on_entry && self.language == "python" {
+            drop(client);
+            self.verify_entry_stop(ENTRY_STOP_WINDOW).await;
+        }
+
         // Pending breakpoints have been applied during initialization
         // (after 'initialized' event, before configurationDone - the correct DAP sequence)
         // This fixes the Go debugging issue where breakpoints were being applied too late
@@ -650,12 +1641,100 @@ impl DebugSession {
         Ok(())
     }
 
+    /// Record the adapter's verdict on pending breakpoints as they are applied
+    /// (the adapter saw mapped paths; state is keyed by the client's paths)
+    async fn register_breakpoint_results_handler(&self, client: &DapClient) {
+        let session_state = self.state.clone();
+        let path_mapper = self.path_mapper.clone();
+        client
+            .on_breakpoints_applied(move |source_path, requested, results| {
+                let session_state = session_state.clone();
+                let source_path = path_mapper.to_local(&source_path);
+                Box::pin(async move {
+                    let mut state = session_state.write().await;
+                    for (requested, bp) in requested.iter().zip(&results) {
+                        state.record_breakpoint_result(
+                            &source_path,
+                            requested.line,
+                            bp.id,
+                            bp.verified,
+                            bp.message.clone(),
+                        );
+                        if let (true, Some(actual)) = (bp.verified, bp.line) {
+                            state.record_breakpoint_line(&source_path, requested.line, actual);
+                        }
+                    }
+                })
+            })
+            .await;
+    }
+
+    /// Child ID the launched client's events are tracked under: the main
+    /// process of a Python multi-session, none otherwise
+    fn main_process_child(&self) -> Option<&'static str> {
+        match &self.session_mode {
+            SessionMode::MultiSession { .. } if self.language == "python" => {
+                Some(PYTHON_MAIN_PROCESS)
+            }
+            _ => None,
+        }
+    }
+
+    /// Wait up to `window` for the session to stop after a stopOnEntry launch
+    ///
+    /// Returns `true` once the entry stop is observed. Terminating, failing
+    /// or stopping for another reason is a definite miss; a running program
+    /// may still stop at entry (some adapters send 'continued' or 'process'
+    /// first), so that waits out the window. On a miss the session is
+    /// flagged with [`SessionState::entry_stop_missed`] and `false` is returned.
+    pub async fn verify_entry_stop(&self, window: Duration) -> bool {
+        let deadline = tokio::time::Instant::now() + window;
+        loop {
+            match self.get_state().await {
+                DebugState::Stopped { reason, .. } if reason == "entry" => {
+                    info!("✅ stopOnEntry verified (reason: {})", reason);
+                    return true;
+                }
+                DebugState::Stopped { .. } | DebugState::Terminated | DebugState::Failed { .. } => {
+                    break
+                }
+                _ if tokio::time::Instant::now() >= deadline => break,
+                _ => tokio::time::sleep(Duration::from_millis(50)).await,
+            }
+        }
+
+        let mut state = self.state.write().await;
+        // The stopped handler may have landed between the last poll and the lock
+        if matches!(&state.state, DebugState::Stopped { reason, .. } if reason == "entry") {
+            return true;
+        }
+        warn!(
+            "⚠️  {} (session {}, state: {:?})",
+            super::state::STOP_ON_ENTRY_MISSED,
+            self.id,
+            state.state
+        );
+        state.entry_stop_missed = true;
+        false
+    }
+
     /// Initialize and launch in the background, returning immediately
     /// Updates state to indicate initialization status
     pub async fn initialize_and_launch_async(
         self: Arc<Self>,
         adapter_id: String,
         launch_args: serde_json::Value,
+    ) {
+        let span = self.span.clone();
+        self.run_initialize_and_launch(adapter_id, launch_args)
+            .instrument(span)
+            .await
+    }
+
+    async fn run_initialize_and_launch(
+        self: Arc<Self>,
+        adapter_id: String,
+        launch_args: serde_json::Value,
     ) {
         let session_id = self.id.clone();
         info!(
@@ -726,7 +1805,51 @@ impl DebugSession {
         Ok(())
     }
 
+    /// Queue a breakpoint to be sent after 'initialized' and before configurationDone
+    ///
+    /// The breakpoint is tracked in the session state straight away; its
+    /// verification is recorded once the adapter has answered.
+    pub async fn add_pending_breakpoint(&self, source_path: String, breakpoint: SourceBreakpoint) {
+        let tracked = tracked_breakpoint(&source_path, &breakpoint);
+        self.pending_breakpoints
+            .write()
+            .await
+            .entry(source_path.clone())
+            .or_default()
+            .push(breakpoint);
+
+        let mut state = self.state.write().await;
+        state.add_line_breakpoint(tracked);
+    }
+
     pub async fn set_breakpoint(&self, source_path: String, line: i32) -> Result<bool> {
+        self.set_source_breakpoint(
+            source_path,
+            SourceBreakpoint {
+                line,
+                column: None,
+                condition: None,
+                hit_condition: None,
+                log_message: None,
+            },
+        )
+        .await
+    }
+
+    /// Set a breakpoint (or logpoint, if `log_message` is set) at a source line
+    ///
+    /// Logpoints are rejected when the adapter has reported that it lacks
+    /// `supportsLogPoints`. While the session is still initializing the
+    /// capabilities are unknown, so the logpoint is queued and the adapter decides.
+    pub async fn set_source_breakpoint(
+        &self,
+        source_path: String,
+        breakpoint: SourceBreakpoint,
+    ) -> Result<bool> {
+        check_breakpoint_line(&source_path, breakpoint.line).await?;
+        let _requests = self.gate.write().await;
+        let line = breakpoint.line;
+        self.state.write().await.track_source(&source_path);
         // Check current state
         let current_state = {
             let state = self.state.read().await;
@@ -745,20 +1868,7 @@ impl DebugSession {
                     "📌 Session initializing, storing breakpoint as pending: {}:{}",
                     source_path, line
                 );
-                let mut pending = self.pending_breakpoints.write().await;
-                pending
-                    .entry(source_path.clone())
-                    .or_insert_with(Vec::new)
-                    .push(SourceBreakpoint {
-                        line,
-                        column: None,
-                        condition: None,
-                        hit_condition: None,
-                    });
-
-                // Add to state for tracking
-                let mut state = self.state.write().await;
-                state.add_breakpoint(source_path, line);
+                self.add_pending_breakpoint(source_path, breakpoint).await;
 
                 info!("✅ Breakpoint stored as pending, will be applied during initialization");
                 // Return true to indicate it will be set
@@ -768,35 +1878,48 @@ impl DebugSession {
             | DebugState::Stopped { .. }
             | DebugState::Initialized
             | DebugState::Launching => {
+                if breakpoint.log_message.is_some() {
+                    let client_arc = self.get_debug_client().await;
+                    let supports_log_points = client_arc
+                        .read()
+                        .await
+                        .capabilities()
+                        .and_then(|caps| caps.supports_log_points)
+                        .unwrap_or(false);
+                    if !supports_log_points {
+                        return Err(crate::Error::InvalidRequest(format!(
+                            "The {} debug adapter does not support logpoints (supportsLogPoints). \
+                             Set a regular breakpoint and use debugger_evaluate instead",
+                            self.language
+                        )));
+                    }
+                }
+
                 // Add to state
                 {
                     let mut state = self.state.write().await;
-                    state.add_breakpoint(source_path.clone(), line);
+                    state.add_line_breakpoint(tracked_breakpoint(&source_path, &breakpoint));
                 }
 
                 // Set via DAP immediately
-                let source = Source {
-                    name: None,
-                    path: Some(source_path.clone()),
-                    source_reference: None,
-                };
-
-                let breakpoints = vec![SourceBreakpoint {
-                    line,
-                    column: None,
-                    condition: None,
-                    hit_condition: None,
-                }];
+                let source = self.adapter_source(&source_path);
 
-                let client_arc = self.get_debug_client().await;
-                let client = client_arc.read().await;
-                let result = client.set_breakpoints(source, breakpoints).await?;
+                let result = self
+                    .set_breakpoints_everywhere(source, vec![breakpoint])
+                    .await?;
 
                 // Update state with results
                 if let Some(bp) = result.first() {
                     let mut state = self.state.write().await;
-                    if let Some(id) = bp.id {
-                        state.update_breakpoint(&source_path, line, id, bp.verified);
+                    state.record_breakpoint_result(
+                        &source_path,
+                        line,
+                        bp.id,
+                        bp.verified,
+                        bp.message.clone(),
+                    );
+                    if let (true, Some(actual)) = (bp.verified, bp.line) {
+                        state.record_breakpoint_line(&source_path, line, actual);
                     }
                     Ok(bp.verified)
                 } else {
@@ -809,75 +1932,667 @@ impl DebugSession {
         }
     }
 
-    pub async fn continue_execution(&self) -> Result<()> {
-        let state = self.state.read().await;
-        let thread_id = state.threads.first().copied().unwrap_or(1);
-        drop(state);
-
-        let client_arc = self.get_debug_client().await;
-        let client = client_arc.read().await;
-        client.continue_execution(thread_id).await?;
+    /// Set several line breakpoints with one setBreakpoints request per file
+    ///
+    /// setBreakpoints replaces everything the adapter knows about a file, so
+    /// each file's request also carries the breakpoints already set there; a
+    /// new breakpoint on an existing line replaces the old one. While the
+    /// session is initializing the merged lists are queued as pending instead.
+    /// Results are returned in the order the breakpoints were requested.
+    pub async fn set_breakpoints_bulk(
+        &self,
+        requested: Vec<(String, SourceBreakpoint)>,
+    ) -> Result<Vec<Breakpoint>> {
+        for (source_path, bp) in &requested {
+            check_breakpoint_line(source_path, bp.line).await?;
+        }
+        let _requests = self.gate.write().await;
+        {
+            let mut state = self.state.write().await;
+            for (source_path, _) in &requested {
+                state.track_source(source_path);
+            }
+        }
+        let current_state = self.get_state().await;
+        let pending = match current_state {
+            DebugState::NotStarted | DebugState::Initializing => true,
+            DebugState::Running
+            | DebugState::Stopped { .. }
+            | DebugState::Initialized
+            | DebugState::Launching => false,
+            DebugState::Terminated | DebugState::Failed { .. } => {
+                return Err(crate::Error::InvalidState(format!(
+                    "Cannot set breakpoints in state: {:?}",
+                    current_state
+                )))
+            }
+        };
+
+        // Group by file, keeping the last breakpoint requested for a position
+        let mut files: Vec<(String, Vec<SourceBreakpoint>)> = Vec::new();
+        for (source_path, bp) in &requested {
+            match files
+                .iter_mut()
+                .find(|(path, _)| same_source_path(path, source_path))
+            {
+                Some((_, bps)) => {
+                    bps.retain(|b| (b.line, b.column) != (bp.line, bp.column));
+                    bps.push(bp.clone());
+                }
+                None => files.push((source_path.clone(), vec![bp.clone()])),
+            }
+        }
+
+        info!(
+            "🔧 set_breakpoints_bulk: {} breakpoint(s) in {} file(s), pending: {}",
+            requested.len(),
+            files.len(),
+            pending
+        );
+
+        if !pending && requested.iter().any(|(_, bp)| bp.log_message.is_some()) {
+            let client_arc = self.get_debug_client().await;
+            let supports_log_points = client_arc
+                .read()
+                .await
+                .capabilities()
+                .and_then(|caps| caps.supports_log_points)
+                .unwrap_or(false);
+            if !supports_log_points {
+                return Err(crate::Error::InvalidRequest(format!(
+                    "The {} debug adapter does not support logpoints (supportsLogPoints). \
+                     Set regular breakpoints and use debugger_evaluate instead",
+                    self.language
+                )));
+            }
+        }
+
+        for (source_path, new_bps) in files {
+            let existing = self.state.read().await.get_breakpoints(&source_path);
+            let mut tracked: Vec<Breakpoint> = existing
+                .into_iter()
+                .filter(|bp| {
+                    // A moved breakpoint still answers to its requested line
+                    let lines = [Some(bp.line), bp.requested_line];
+                    new_bps
+                        .iter()
+                        .all(|new| new.column != bp.column || !lines.contains(&Some(new.line)))
+                })
+                .collect();
+            tracked.extend(
+                new_bps
+                    .iter()
+                    .map(|bp| tracked_breakpoint(&source_path, bp)),
+            );
+            let merged: Vec<SourceBreakpoint> = tracked
+                .iter()
+                .map(|bp| SourceBreakpoint {
+                    line: bp.line,
+                    column: bp.column,
+                    condition: bp.condition.clone(),
+                    hit_condition: bp.hit_condition.clone(),
+                    log_message: bp.log_message.clone(),
+                })
+                .collect();
+
+            if pending {
+                self.pending_breakpoints
+                    .write()
+                    .await
+                    .insert(source_path.clone(), merged);
+            } else {
+                let source = self.adapter_source(&source_path);
+                // The adapter answers in the order the breakpoints were sent
+                let results = self.set_breakpoints_everywhere(source, merged).await?;
+                for (bp, result) in tracked.iter_mut().zip(results) {
+                    apply_breakpoint_result(bp, result);
+                }
+            }
+
+            self.state
+                .write()
+                .await
+                .set_line_breakpoints(source_path, tracked);
+        }
+
+        let state = self.state.read().await;
+        Ok(requested
+            .iter()
+            .filter_map(|(source_path, bp)| {
+                state
+                    .get_breakpoints(source_path)
+                    .into_iter()
+                    .find(|tracked| {
+                        (
+                            tracked.requested_line.unwrap_or(tracked.line),
+                            tracked.column,
+                        ) == (bp.line, bp.column)
+                    })
+            })
+            .collect())
+    }
+
+    /// Positions between `line` and `end_line` where the adapter can bind breakpoints
+    ///
+    /// Needs the adapter's capabilities, so the session must have finished
+    /// initializing; adapters without `supportsBreakpointLocationsRequest`
+    /// are rejected up front.
+    pub async fn breakpoint_locations(
+        &self,
+        source_path: String,
+        line: i32,
+        end_line: Option<i32>,
+    ) -> Result<Vec<BreakpointLocation>> {
+        let _requests = self.gate.read().await;
+        let current_state = self.get_state().await;
+        if matches!(
+            current_state,
+            DebugState::NotStarted
+                | DebugState::Initializing
+                | DebugState::Terminated
+                | DebugState::Failed { .. }
+        ) {
+            return Err(crate::Error::InvalidState(format!(
+                "Cannot query breakpoint locations in state: {:?}",
+                current_state
+            )));
+        }
+
+        let client_arc = self.get_debug_client().await;
+        let client = client_arc.read().await;
+        let supported = client
+            .capabilities()
+            .and_then(|caps| caps.supports_breakpoint_locations_request)
+            .unwrap_or(false);
+        if !supported {
+            return Err(crate::Error::InvalidRequest(format!(
+                "The {} debug adapter does not support breakpoint locations \
+                 (supportsBreakpointLocationsRequest)",
+                self.language
+            )));
+        }
+
+        let source = self.adapter_source(&source_path);
+        client.breakpoint_locations(source, line, end_line).await
+    }
+
+    /// Replace the session's function breakpoints
+    ///
+    /// Unlike line breakpoints these are not queued during initialization, so
+    /// the adapter's capabilities are known when they are sent.
+    pub async fn set_function_breakpoints(
+        &self,
+        breakpoints: Vec<FunctionBreakpoint>,
+    ) -> Result<Vec<FunctionBreakpointInfo>> {
+        let _requests = self.gate.write().await;
+        let current_state = self.get_state().await;
+        match current_state {
+            DebugState::Running
+            | DebugState::Stopped { .. }
+            | DebugState::Initialized
+            | DebugState::Launching => {}
+            _ => {
+                return Err(crate::Error::InvalidState(format!(
+                    "Cannot set function breakpoints in state: {:?}. \
+                     Start with stopOnEntry: true and wait for the entry stop first",
+                    current_state
+                )))
+            }
+        }
+
+        let results = self
+            .set_function_breakpoints_everywhere(breakpoints.clone())
+            .await?;
+
+        let tracked: Vec<FunctionBreakpointInfo> = breakpoints
+            .into_iter()
+            .enumerate()
+            .map(|(i, bp)| {
+                let result = results.get(i);
+                FunctionBreakpointInfo {
+                    name: bp.name,
+                    condition: bp.condition,
+                    id: result.and_then(|r| r.id),
+                    verified: result.is_some_and(|r| r.verified),
+                }
+            })
+            .collect();
 
         let mut state = self.state.write().await;
-        state.set_state(DebugState::Running);
+        state.set_function_breakpoints(tracked.clone());
+        Ok(tracked)
+    }
 
-        Ok(())
+    /// What a data breakpoint on `name` would watch: a child of the container
+    /// `variables_reference`, or without one the expression `name` in
+    /// `frame_id` (default: the top frame of the stopped thread)
+    pub async fn data_breakpoint_info(
+        &self,
+        variables_reference: Option<i32>,
+        name: &str,
+        frame_id: Option<i32>,
+    ) -> Result<DataBreakpointInfoResponse> {
+        let _requests = self.gate.read().await;
+        let stopped_thread = match &self.state.read().await.state {
+            DebugState::Stopped { thread_id, .. } => *thread_id,
+            _ => {
+                return Err(crate::Error::InvalidState(
+                    "Cannot look up data breakpoint targets while program is running: \
+                     variable references are only valid while paused"
+                        .to_string(),
+                ))
+            }
+        };
+        let client_arc = self.get_thread_client(stopped_thread).await;
+        let client = client_arc.read().await;
+
+        let frame_id = match (variables_reference, frame_id) {
+            (Some(_), frame_id) | (None, frame_id @ Some(_)) => frame_id,
+            (None, None) => client
+                .stack_trace_page(stopped_thread, None, Some(1))
+                .await?
+                .stack_frames
+                .first()
+                .map(|frame| frame.id),
+        };
+        client
+            .data_breakpoint_info(variables_reference, name, frame_id)
+            .await
+    }
+
+    /// Add a data breakpoint, replacing one on the same dataId, and send all
+    /// of the session's data breakpoints (setDataBreakpoints replaces them)
+    ///
+    /// `description` is what [`Self::data_breakpoint_info`] reported for the
+    /// dataId.
+    pub async fn add_data_breakpoint(
+        &self,
+        breakpoint: DataBreakpoint,
+        description: String,
+    ) -> Result<DataBreakpointInfo> {
+        let _requests = self.gate.write().await;
+        let mut tracked = self.state.read().await.data_breakpoints.clone();
+        tracked.retain(|bp| bp.data_id != breakpoint.data_id);
+        tracked.push(DataBreakpointInfo {
+            data_id: breakpoint.data_id,
+            description,
+            access_type: breakpoint.access_type,
+            condition: breakpoint.condition,
+            id: None,
+            verified: false,
+        });
+
+        let mut tracked = self.send_data_breakpoints(tracked).await?;
+        tracked.pop().ok_or_else(|| {
+            crate::Error::Internal("Data breakpoint missing after setDataBreakpoints".to_string())
+        })
+    }
+
+    /// Remove every data breakpoint; returns how many there were
+    pub async fn clear_data_breakpoints(&self) -> Result<usize> {
+        let _requests = self.gate.write().await;
+        let cleared = self.state.read().await.data_breakpoints.len();
+        self.send_data_breakpoints(Vec::new()).await?;
+        Ok(cleared)
+    }
+
+    /// Send `tracked` to the adapter and record the results in the state
+    async fn send_data_breakpoints(
+        &self,
+        mut tracked: Vec<DataBreakpointInfo>,
+    ) -> Result<Vec<DataBreakpointInfo>> {
+        let breakpoints = tracked
+            .iter()
+            .map(|bp| DataBreakpoint {
+                data_id: bp.data_id.clone(),
+                access_type: bp.access_type.clone(),
+                condition: bp.condition.clone(),
+                hit_condition: None,
+            })
+            .collect();
+        let results = {
+            let client_arc = self.get_debug_client().await;
+            let client = client_arc.read().await;
+            client.set_data_breakpoints(breakpoints).await?
+        };
+        for (bp, result) in tracked.iter_mut().zip(&results) {
+            bp.id = result.id;
+            bp.verified = result.verified;
+        }
+
+        self.state
+            .write()
+            .await
+            .set_data_breakpoints(tracked.clone());
+        Ok(tracked)
     }
 
-    pub async fn step_over(&self, thread_id: i32) -> Result<()> {
+    /// Remove the stopOnEntry workaround's breakpoint once the program
+    /// stopped there, so resuming through the first line again (a loop, a
+    /// re-entered method) does not stop
+    ///
+    /// setBreakpoints replaces every breakpoint of the file, so the file's own
+    /// breakpoints are sent again without it.
+    async fn remove_hit_entry_breakpoint(&self) {
         let client_arc = self.get_debug_client().await;
         let client = client_arc.read().await;
-        client.next(thread_id).await?;
+        let Some(entry) = client.take_hit_entry_breakpoint() else {
+            return;
+        };
+        let local_path = self.path_mapper.to_local(&entry.source_path);
+        let mut tracked = self.state.read().await.get_breakpoints(&local_path);
+        info!(
+            "🧹 Removing entry breakpoint at {}:{} ({} breakpoint(s) kept in the file)",
+            local_path,
+            entry.line,
+            tracked.len()
+        );
+        let breakpoints = tracked
+            .iter()
+            .map(|bp| SourceBreakpoint {
+                line: bp.line,
+                column: bp.column,
+                condition: bp.condition.clone(),
+                hit_condition: bp.hit_condition.clone(),
+                log_message: bp.log_message.clone(),
+            })
+            .collect();
+        let source = Source {
+            path: Some(entry.source_path.clone()),
+            name: None,
+            source_reference: None,
+            origin: None,
+        };
+        match client.set_breakpoints(source, breakpoints).await {
+            Ok(results) => {
+                // The adapter may give the resent breakpoints new IDs
+                for (bp, result) in tracked.iter_mut().zip(results) {
+                    apply_breakpoint_result(bp, result);
+                }
+                self.state
+                    .write()
+                    .await
+                    .set_line_breakpoints(local_path, tracked);
+            }
+            Err(e) => warn!("⚠️  Failed to remove the entry breakpoint: {}", e),
+        }
+    }
+
+    pub async fn continue_execution(&self) -> Result<()> {
+        self.ensure_live("continue")?;
+        let _resume = self.gate.resume("continue").await?;
+        self.remove_hit_entry_breakpoint().await;
+
+        let state = self.state.read().await;
+        let thread_id = match &state.state {
+            DebugState::Stopped { thread_id, .. } => *thread_id,
+            _ => state.threads.first().copied().unwrap_or(1),
+        };
+        drop(state);
+
+        let client_arc = self.get_thread_client(thread_id).await;
+        let client = client_arc.read().await;
+        client.continue_execution(thread_id).await?;
+        drop(client);
 
-        // State will be updated by 'stopped' event handler when step completes
+        // The client reports the resume as a 'continued' event; once it is
+        // applied the state is Running, or already Stopped again
+        self.sync_state().await;
         Ok(())
     }
 
-    pub async fn step_into(&self, thread_id: i32) -> Result<()> {
-        let client_arc = self.get_debug_client().await;
+    /// Step over the current line, or by `granularity` ("statement", "line"
+    /// or "instruction") when the adapter supports it
+    pub async fn step_over(&self, thread_id: i32, granularity: Option<&str>) -> Result<()> {
+        self.step(thread_id, granularity, Step::Over).await
+    }
+
+    /// Re-run the function of `frame_id` from its start, dropping the frames
+    /// above it; the adapter reports a 'stopped' event (reason "restart")
+    /// once it is back at the first line
+    pub async fn restart_frame(&self, thread_id: i32, frame_id: i32) -> Result<()> {
+        self.ensure_live("restart frame")?;
+        Self::validate_frame(&*self.state.read().await, frame_id, Some(thread_id))?;
+        let _resume = self.gate.resume("restart frame").await?;
+        self.remove_hit_entry_breakpoint().await;
+
+        let client_arc = self.get_thread_client(thread_id).await;
         let client = client_arc.read().await;
-        client.step_in(thread_id).await?;
+        client.restart_frame(frame_id).await?;
+        drop(client);
 
-        // State will be updated by 'stopped' event handler when step completes
+        self.sync_state().await;
         Ok(())
     }
 
-    pub async fn step_out(&self, thread_id: i32) -> Result<()> {
-        let client_arc = self.get_debug_client().await;
+    /// Move the stopped `thread_id` to `line` of `source_path` without
+    /// executing the code in between, returning the goto target used
+    pub async fn jump_to_line(
+        &self,
+        thread_id: i32,
+        source_path: &str,
+        line: i32,
+    ) -> Result<GotoTarget> {
+        self.ensure_live("jump")?;
+        let _resume = self.gate.resume("jump").await?;
+        self.remove_hit_entry_breakpoint().await;
+
+        let client_arc = self.get_thread_client(thread_id).await;
+        let client = client_arc.read().await;
+        let target = client
+            .goto_targets(self.adapter_source(source_path), line)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                crate::Error::InvalidRequest(format!(
+                    "The debug adapter cannot jump to {}:{} (no goto target there; \
+                     pick a line with code in the current function)",
+                    source_path, line
+                ))
+            })?;
+        client.goto(thread_id, target.id).await?;
+        drop(client);
+
+        // The 'stopped' event (reason "goto") may come later, but the old
+        // frame ids already point to the wrong line
+        self.state.write().await.invalidate_frames();
+        Ok(target)
+    }
+
+    /// Step into the call `target_id` (see [`Self::step_in_targets`]), or
+    /// the first call on the line
+    pub async fn step_into(
+        &self,
+        thread_id: i32,
+        granularity: Option<&str>,
+        target_id: Option<i32>,
+    ) -> Result<()> {
+        self.step(thread_id, granularity, Step::Into(target_id))
+            .await
+    }
+
+    pub async fn step_out(&self, thread_id: i32, granularity: Option<&str>) -> Result<()> {
+        self.step(thread_id, granularity, Step::Out).await
+    }
+
+    /// Send the step request for `thread_id`
+    ///
+    /// Afterwards the queued state updates are flushed, so the step's own
+    /// transitions are visible; this does not wait for the 'stopped' event
+    /// that ends the step.
+    async fn step(&self, thread_id: i32, granularity: Option<&str>, step: Step) -> Result<()> {
+        self.ensure_live("step")?;
+        let _resume = self.gate.resume("step").await?;
+        self.remove_hit_entry_breakpoint().await;
+
+        let client_arc = self.get_thread_client(thread_id).await;
         let client = client_arc.read().await;
-        client.step_out(thread_id).await?;
+        match step {
+            Step::Over => client.next(thread_id, granularity).await?,
+            Step::Into(target_id) => client.step_in(thread_id, granularity, target_id).await?,
+            Step::Out => client.step_out(thread_id, granularity).await?,
+        }
+        drop(client);
 
-        // State will be updated by 'stopped' event handler when step completes
+        self.sync_state().await;
         Ok(())
     }
 
     pub async fn stack_trace(&self) -> Result<Vec<crate::dap::types::StackFrame>> {
+        self.stack_trace_page(None, None, None)
+            .await
+            .map(|page| page.stack_frames)
+    }
+
+    /// Frames `start_frame..start_frame + levels` of `thread_id`'s stack, by
+    /// default the stopped thread's
+    pub async fn stack_trace_page(
+        &self,
+        thread_id: Option<i32>,
+        start_frame: Option<i32>,
+        levels: Option<i32>,
+    ) -> Result<StackTracePage> {
+        let _requests = self.gate.read().await;
         let state = self.state.read().await;
 
         // Get thread_id from the current Stopped state, or fallback to threads list
-        let thread_id = match &state.state {
-            DebugState::Stopped { thread_id, .. } => *thread_id,
-            _ => state.threads.first().copied().unwrap_or(1),
+        let thread_id = match (thread_id, &state.state) {
+            (Some(thread_id), _) => thread_id,
+            (None, DebugState::Stopped { thread_id, .. }) => *thread_id,
+            (None, _) => state.threads.first().copied().unwrap_or(1),
         };
+        let generation = state.generation();
         drop(state);
 
-        let client_arc = self.get_debug_client().await;
+        let client_arc = self.get_thread_client(thread_id).await;
         let client = client_arc.read().await;
-        client.stack_trace(thread_id).await
+        let mut page = client
+            .stack_trace_page(thread_id, start_frame, levels)
+            .await?;
+
+        // Report sources with the MCP client's paths
+        if !self.path_mapper.is_empty() {
+            for frame in &mut page.stack_frames {
+                if let Some(path) = frame.source.as_mut().and_then(|s| s.path.as_mut()) {
+                    *path = self.path_mapper.to_local(path);
+                }
+            }
+        }
+        self.state.write().await.cache_stack(
+            generation,
+            thread_id,
+            start_frame.unwrap_or(0),
+            page.stack_frames.clone(),
+        );
+        Ok(page)
+    }
+
+    pub async fn evaluate(
+        &self,
+        expression: &str,
+        frame_id: Option<i32>,
+    ) -> Result<EvaluateResult> {
+        self.evaluate_in_context(expression, frame_id, "watch")
+            .await
     }
 
-    pub async fn evaluate(&self, expression: &str, frame_id: Option<i32>) -> Result<String> {
+    /// Reject frame ids that went stale when the program last resumed, or that
+    /// belong to another thread than `thread_id`
+    fn validate_frame(state: &SessionState, frame_id: i32, thread_id: Option<i32>) -> Result<()> {
+        match state.check_frame(frame_id, thread_id) {
+            FrameCheck::Current | FrameCheck::Unknown => Ok(()),
+            FrameCheck::Stale => Err(crate::Error::StaleFrameId(format!(
+                "frame {} is from a stack fetched before the program last resumed \
+                 (state: {}); call debugger_stack_trace again and use its frame ids",
+                frame_id,
+                state.state.name()
+            ))),
+            FrameCheck::OtherThread => Err(crate::Error::InvalidRequest(format!(
+                "frame {} is not in the current stack of thread {}",
+                frame_id,
+                thread_id.unwrap_or_default()
+            ))),
+        }
+    }
+
+    /// Id of the frame at `index` (0 is the top) of `thread_id`'s current
+    /// stack, by default the stopped thread's
+    pub async fn frame_id_at(&self, thread_id: Option<i32>, index: i32) -> Result<i32> {
+        if index < 0 {
+            return Err(crate::Error::InvalidRequest(format!(
+                "frameIndex must not be negative (got {})",
+                index
+            )));
+        }
+        let page = self
+            .stack_trace_page(thread_id, Some(index), Some(1))
+            .await?;
+        match page.stack_frames.first() {
+            Some(frame) => Ok(frame.id),
+            None => Err(crate::Error::InvalidRequest(format!(
+                "frameIndex {} is beyond the current stack ({} frames)",
+                index,
+                page.total_frames
+                    .map_or_else(|| "fewer".to_string(), |total| total.to_string())
+            ))),
+        }
+    }
+
+    /// Evaluate in an explicit DAP context ("watch", "repl", "hover", "clipboard")
+    pub async fn evaluate_in_context(
+        &self,
+        expression: &str,
+        frame_id: Option<i32>,
+        context: &str,
+    ) -> Result<EvaluateResult> {
+        self.evaluate_in_thread(expression, frame_id, None, context)
+            .await
+    }
+
+    /// Evaluate in a frame of `thread_id` (by default the stopped thread)
+    ///
+    /// A `frame_id` from a stack fetched before the program last resumed is
+    /// rejected as stale instead of being passed on to the adapter, which
+    /// would either fail with an unhelpful message or evaluate in whatever
+    /// frame now has that id.
+    pub async fn evaluate_in_thread(
+        &self,
+        expression: &str,
+        frame_id: Option<i32>,
+        thread_id: Option<i32>,
+        context: &str,
+    ) -> Result<EvaluateResult> {
+        let _requests = self.gate.read().await;
+        let state = self.state.read().await;
+        if let Some(frame_id) = frame_id {
+            Self::validate_frame(&state, frame_id, thread_id)?;
+        }
+        // Evaluate in the child that owns the thread
+        let stopped_thread = match (thread_id, &state.state) {
+            (Some(thread_id), _) => Some(thread_id),
+            (None, DebugState::Stopped { thread_id, .. }) => Some(*thread_id),
+            (None, _) => None,
+        };
+        drop(state);
+        let client_arc = match stopped_thread {
+            Some(thread_id) => self.get_thread_client(thread_id).await,
+            None => self.get_debug_client().await,
+        };
+        let client = client_arc.read().await;
+
         // If frame_id is None, auto-fetch it from stack trace using correct thread ID
         let frame_id = if let Some(id) = frame_id {
             Some(id)
         } else {
             // Get current thread ID from Stopped state
-            let state = self.state.read().await;
-            if let DebugState::Stopped { thread_id, .. } = &state.state {
-                // Get stack trace with correct thread ID
-                let client_arc = self.get_debug_client().await;
-                let client = client_arc.read().await;
-                match client.stack_trace(*thread_id).await {
+            if let Some(thread_id) = &stopped_thread {
+                // Get the top frame with correct thread ID
+                match client
+                    .stack_trace_page(*thread_id, None, Some(1))
+                    .await
+                    .map(|page| page.stack_frames)
+                {
                     Ok(frames) if !frames.is_empty() => {
                         info!(
                             "📍 Auto-fetched frame_id {} from thread {}",
@@ -900,82 +2615,2160 @@ impl DebugSession {
             }
         };
 
-        let client_arc = self.get_debug_client().await;
-        let client = client_arc.read().await;
-        client.evaluate(expression, frame_id).await
+        client
+            .evaluate_in_context(expression, frame_id, context)
+            .await
     }
 
-    pub async fn disconnect(&self) -> Result<()> {
-        let client_arc = self.get_debug_client().await;
-        let client = client_arc.read().await;
+    /// Program and breakpoint files edited on disk since the session started
+    /// using them
+    ///
+    /// The debuggee keeps running the code it loaded, so line numbers in
+    /// such files no longer match what is executed until the session is
+    /// restarted.
+    pub async fn changed_sources(&self) -> Vec<String> {
+        self.state.read().await.changed_sources()
+    }
 
-        // Use disconnect with 2s timeout (force cleanup if hangs)
-        // If timeout occurs, we still update state to Terminated
-        match client.disconnect_with_timeout().await {
-            Ok(_) => info!("✅ Disconnect completed successfully"),
-            Err(e) => {
-                warn!(
-                    "⚠️  Disconnect timeout or error: {}, proceeding with cleanup",
-                    e
-                );
-                // Continue anyway - state will be set to Terminated
+    /// Local paths of every source the adapter has loaded
+    ///
+    /// Asks the adapter when it supports the loadedSources request, and adds
+    /// its answer to the sources reported by 'loadedSource' events.
+    pub async fn loaded_sources(&self) -> Result<Vec<String>> {
+        let sources = {
+            let _requests = self.gate.read().await;
+            let client_arc = self.get_debug_client().await;
+            let client = client_arc.read().await;
+            let supported = client
+                .capabilities()
+                .and_then(|caps| caps.supports_loaded_sources_request)
+                .unwrap_or(false);
+            if supported {
+                client.loaded_sources().await?
+            } else {
+                Vec::new()
             }
-        }
+        };
 
+        // Apply 'loadedSource' events received so far
+        self.sync_state().await;
         let mut state = self.state.write().await;
-        state.set_state(DebugState::Terminated);
-
-        Ok(())
+        for path in sources.iter().filter_map(|source| source.path.as_deref()) {
+            state.loaded_sources.insert(self.path_mapper.to_local(path));
+        }
+        Ok(state.loaded_sources.iter().cloned().collect())
     }
 
-    pub async fn get_state(&self) -> DebugState {
-        let state = self.state.read().await;
-        state.state.clone()
+    /// Modules loaded by the debuggee: the adapter's answer to the modules
+    /// request when supported, otherwise those reported by 'module' events
+    pub async fn modules(&self) -> Result<Vec<Module>> {
+        {
+            let _requests = self.gate.read().await;
+            let client_arc = self.get_debug_client().await;
+            let client = client_arc.read().await;
+            let supported = client
+                .capabilities()
+                .and_then(|caps| caps.supports_modules_request)
+                .unwrap_or(false);
+            if supported {
+                return client.modules().await;
+            }
+        }
+        self.sync_state().await;
+        Ok(self.state.read().await.modules.clone())
     }
 
-    pub async fn get_full_state(&self) -> SessionState {
-        let state = self.state.read().await;
-        state.clone()
+    /// Explain why each of the unverified `breakpoints` (source path, line)
+    /// did not bind (see [`diagnose_unverified_breakpoint`])
+    ///
+    /// Refreshes the loaded sources first, so files loaded from another path
+    /// are recognized.
+    pub async fn diagnose_unverified_breakpoints(
+        &self,
+        breakpoints: &[(String, i32)],
+    ) -> Vec<BreakpointDiagnosis> {
+        if let Err(e) = self.loaded_sources().await {
+            warn!("⚠️  Failed to refresh loaded sources: {}", e);
+        }
+        // The diagnosis reads the source files, so it runs off the async workers
+        let state = self.state.read().await.clone();
+        let breakpoints = breakpoints.to_vec();
+        let diagnose = move || {
+            breakpoints
+                .iter()
+                .map(|(source_path, line)| {
+                    let message = state
+                        .get_breakpoints(source_path)
+                        .into_iter()
+                        .find(|bp| bp.line == *line)
+                        .and_then(|bp| bp.message);
+                    diagnose_unverified_breakpoint(source_path, *line, &state, message.as_deref())
+                })
+                .collect()
+        };
+        match tokio::task::spawn_blocking(diagnose).await {
+            Ok(diagnoses) => diagnoses,
+            Err(e) => {
+                warn!("⚠️  Breakpoint diagnosis failed: {}", e);
+                Vec::new()
+            }
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::dap::transport_trait::DapTransportTrait;
-    use crate::dap::types::*;
-    use crate::Error;
-    use mockall::mock;
-    use serde_json::json;
+    /// Completions for the partial expression `text` with the cursor at
+    /// `column`, in `frame_id` (the stopped thread's top frame when `None`)
+    pub async fn completions(
+        &self,
+        text: &str,
+        column: i32,
+        frame_id: Option<i32>,
+    ) -> Result<Vec<CompletionItem>> {
+        let _requests = self.gate.read().await;
+        let stopped_thread = match &self.state.read().await.state {
+            DebugState::Stopped { thread_id, .. } => *thread_id,
+            _ => {
+                return Err(crate::Error::InvalidState(
+                    "Cannot complete expressions while program is running".to_string(),
+                ))
+            }
+        };
+        let client_arc = self.get_thread_client(stopped_thread).await;
+        let client = client_arc.read().await;
+
+        let frame_id = match frame_id {
+            Some(id) => Some(id),
+            None => client
+                .stack_trace_page(stopped_thread, None, Some(1))
+                .await?
+                .stack_frames
+                .first()
+                .map(|frame| frame.id),
+        };
+        client.completions(text, column, frame_id).await
+    }
 
-    mock! {
-        pub TestTransport {}
+    /// The calls on the current line of `frame_id` (by default the stopped
+    /// thread's top frame) that [`Self::step_into`] can target
+    pub async fn step_in_targets(&self, frame_id: Option<i32>) -> Result<Vec<StepInTarget>> {
+        let _requests = self.gate.read().await;
+        let stopped_thread = match &self.state.read().await.state {
+            DebugState::Stopped { thread_id, .. } => *thread_id,
+            _ => {
+                return Err(crate::Error::InvalidState(
+                    "Cannot list step into targets while program is running".to_string(),
+                ))
+            }
+        };
+        let client_arc = self.get_thread_client(stopped_thread).await;
+        let client = client_arc.read().await;
 
-        #[async_trait::async_trait]
-        impl DapTransportTrait for TestTransport {
-            async fn read_message(&mut self) -> Result<Message>;
-            async fn write_message(&mut self, msg: &Message) -> Result<()>;
-        }
+        let frame_id = match frame_id {
+            Some(id) => id,
+            None => client
+                .stack_trace_page(stopped_thread, None, Some(1))
+                .await?
+                .stack_frames
+                .first()
+                .map(|frame| frame.id)
+                .ok_or_else(|| {
+                    crate::Error::InvalidState("The stopped thread has no stack frames".to_string())
+                })?,
+        };
+        client.step_in_targets(frame_id).await
     }
 
-    fn create_mock_with_response(response: Response) -> MockTestTransport {
-        let mut mock = MockTestTransport::new();
-        mock.expect_write_message().times(1).returning(|_| Ok(()));
-        mock.expect_read_message()
-            .times(1)
-            .return_once(move || Ok(Message::Response(response)));
-        mock.expect_read_message()
-            .returning(|| Err(Error::Dap("Connection closed".to_string())));
-        mock
+    /// Expand a structured value returned by [`Self::evaluate`] or a previous
+    /// variables request
+    pub async fn variables(&self, variables_reference: i32) -> Result<Vec<Variable>> {
+        self.variables_page(variables_reference, None, None).await
     }
 
-    fn create_empty_mock() -> MockTestTransport {
-        let mut mock = MockTestTransport::new();
-        mock.expect_read_message()
-            .returning(|| Err(Error::Dap("Connection closed".to_string())));
+    /// Like [`Self::variables`], `count` children starting at `start`
+    pub async fn variables_page(
+        &self,
+        variables_reference: i32,
+        start: Option<i32>,
+        count: Option<i32>,
+    ) -> Result<Vec<Variable>> {
+        let _requests = self.gate.read().await;
+        let client_arc = self.get_debug_client().await;
+        let client = client_arc.read().await;
+        client
+            .variables_page(variables_reference, start, count)
+            .await
+    }
+
+    /// Variable scopes of a frame of the stopped thread
+    pub async fn scopes(&self, frame_id: i32) -> Result<Vec<Scope>> {
+        let _requests = self.gate.read().await;
+        let thread_id = match &self.state.read().await.state {
+            DebugState::Stopped { thread_id, .. } => *thread_id,
+            _ => {
+                return Err(crate::Error::InvalidState(
+                    "Cannot get scopes while program is running".to_string(),
+                ))
+            }
+        };
+        let client_arc = self.get_thread_client(thread_id).await;
+        let client = client_arc.read().await;
+        client.scopes(frame_id).await
+    }
+
+    /// The "registers" scope of a frame of the stopped thread
+    ///
+    /// Only native debuggers (CodeLLDB, Delve) have one; interpreted
+    /// languages are rejected without asking the adapter.
+    pub async fn registers_scope(&self, frame_id: i32) -> Result<Scope> {
+        if matches!(
+            self.language.as_str(),
+            "python" | "ruby" | "nodejs" | "bash"
+        ) {
+            return Err(crate::Error::InvalidRequest(format!(
+                "Registers are not available for {} sessions: the program runs in an \
+                 interpreter. Registers are available for Rust, C and C++ (CodeLLDB)",
+                self.language
+            )));
+        }
+        self.scopes(frame_id)
+            .await?
+            .into_iter()
+            .find(|scope| {
+                scope.presentation_hint.as_deref() == Some("registers")
+                    || scope.name.eq_ignore_ascii_case("registers")
+            })
+            .ok_or_else(|| {
+                crate::Error::InvalidRequest(format!(
+                    "The {} debug adapter reports no registers scope for frame {}",
+                    self.language, frame_id
+                ))
+            })
+    }
+
+    /// Read memory at a `memoryReference` from an evaluate result or variable
+    pub async fn read_memory(
+        &self,
+        memory_reference: &str,
+        offset: Option<i64>,
+        count: i64,
+    ) -> Result<ReadMemoryResult> {
+        let _requests = self.gate.read().await;
+        let client_arc = self.get_debug_client().await;
+        let client = client_arc.read().await;
+        client.read_memory(memory_reference, offset, count).await
+    }
+
+    /// Disassemble instructions starting at a `memoryReference`
+    pub async fn disassemble(
+        &self,
+        memory_reference: &str,
+        instruction_count: i64,
+    ) -> Result<Vec<DisassembledInstruction>> {
+        let _requests = self.gate.read().await;
+        let client_arc = self.get_debug_client().await;
+        let client = client_arc.read().await;
+        client
+            .disassemble(memory_reference, instruction_count)
+            .await
+    }
+
+    /// Add a watch expression; returns `false` if it is already watched
+    pub async fn add_watch(&self, expression: String) -> bool {
+        self.state.write().await.add_watch(expression)
+    }
+
+    /// Remove a watch expression; returns `false` if it was not watched
+    pub async fn remove_watch(&self, expression: &str) -> bool {
+        self.state.write().await.remove_watch(expression)
+    }
+
+    /// Re-evaluate every watch in the top frame of the stopped thread
+    ///
+    /// Each evaluation is bounded by [`WATCH_EVAL_TIMEOUT`] so one slow or
+    /// hanging expression cannot hold up stop reporting. When the session is
+    /// not stopped the cached results are returned unchanged.
+    pub async fn refresh_watches(&self) -> Vec<Watch> {
+        let _requests = self.gate.read().await;
+        let (expressions, thread_id) = {
+            let state = self.state.read().await;
+            match &state.state {
+                DebugState::Stopped { thread_id, .. } if !state.watches.is_empty() => (
+                    state
+                        .watches
+                        .iter()
+                        .map(|w| w.expression.clone())
+                        .collect::<Vec<_>>(),
+                    *thread_id,
+                ),
+                _ => return state.watches.clone(),
+            }
+        };
+
+        let client_arc = self.get_thread_client(thread_id).await;
+        let client = client_arc.read().await;
+
+        let top_frame = client.stack_trace_page(thread_id, None, Some(1));
+        let frame_id = match tokio::time::timeout(WATCH_EVAL_TIMEOUT, top_frame).await {
+            Ok(Ok(page)) => page.stack_frames.first().map(|f| f.id),
+            Ok(Err(e)) => {
+                warn!("⚠️  Failed to get stack trace for watches: {}", e);
+                None
+            }
+            Err(_) => {
+                warn!("⚠️  Stack trace for watches timed out");
+                None
+            }
+        };
+
+        let mut results = Vec::with_capacity(expressions.len());
+        for expression in expressions {
+            let value = match tokio::time::timeout(
+                WATCH_EVAL_TIMEOUT,
+                client.evaluate(&expression, frame_id),
+            )
+            .await
+            {
+                Ok(Ok(value)) => WatchValue::Value(value.result),
+                Ok(Err(e)) => WatchValue::Error(e.to_string()),
+                Err(_) => WatchValue::Error(format!(
+                    "Evaluation timed out after {:?}",
+                    WATCH_EVAL_TIMEOUT
+                )),
+            };
+            results.push((expression, value));
+        }
+        drop(client);
+
+        let mut state = self.state.write().await;
+        state.record_watch_results(results);
+        state.watches.clone()
+    }
+
+    pub async fn disconnect(&self) -> Result<()> {
+        // debugpy ends the subprocesses along with the main process
+        let client_arc = match (&self.session_mode, self.main_process_child()) {
+            (SessionMode::MultiSession { parent_client, .. }, Some(_)) => parent_client.clone(),
+            _ => self.get_debug_client().await,
+        };
+        let client = client_arc.read().await;
+
+        // Bounded by the 'disconnect' request timeout; if it runs out, the
+        // session is still marked Terminated
+        match client.disconnect().await {
+            Ok(_) => info!("✅ Disconnect completed successfully"),
+            Err(e) => {
+                warn!(
+                    "⚠️  Disconnect timeout or error: {}, proceeding with cleanup",
+                    e
+                );
+                // Continue anyway - state will be set to Terminated
+            }
+        }
+
+        drop(client);
+        self.clear_session_handlers().await;
+
+        let mut state = self.state.write().await;
+        state.set_state(DebugState::Terminated);
+
+        Ok(())
+    }
+
+    /// Kill the adapter process behind this session, if the server spawned
+    /// one and it is still running (see [`DapClient::kill_adapter`]), and
+    /// the debuggee the adapter launched
+    ///
+    /// The debuggee is targeted by the PID from the 'process' event: adapters
+    /// reached over a socket have no child handle here, and a hung adapter's
+    /// debuggee does not always end with it. Attached processes are spared.
+    pub async fn kill_adapter(&self) {
+        let client = match &self.session_mode {
+            SessionMode::Single { client } => client.clone(),
+            SessionMode::MultiSession { parent_client, .. } => parent_client.clone(),
+        };
+        client.read().await.kill_adapter();
+
+        let pid = self
+            .state
+            .read()
+            .await
+            .process
+            .as_ref()
+            .and_then(|process| process.launched_pid());
+        if let Some(pid) = pid {
+            match crate::process::kill_pid(pid) {
+                Ok(()) => info!("🔪 Killed debuggee process {}", pid),
+                // Usually the debuggee already ended with its adapter
+                Err(e) => debug!("Debuggee process {} not killed: {}", pid, e),
+            }
+        }
+    }
+
+    /// End a hung session without the polite disconnect: kill the adapter
+    /// process and mark the session Failed with `reason`
+    ///
+    /// The session stays registered so its state can still be inspected.
+    pub async fn force_kill(&self, reason: &str) {
+        warn!(
+            "🔪 Force-killing adapter of session {} ({} request(s) timed out in a row)",
+            self.id,
+            self.consecutive_timeouts()
+        );
+        self.kill_adapter().await;
+        self.clear_session_handlers().await;
+
+        let mut state = self.state.write().await;
+        state.set_state(DebugState::Failed {
+            error: reason.to_string(),
+        });
+    }
+
+    /// How the session talks to its adapter: "stdio", "tcp:<address>" or
+    /// "unix:<path>"
+    pub async fn adapter_transport(&self) -> String {
+        let client = match &self.session_mode {
+            SessionMode::Single { client } => client.clone(),
+            SessionMode::MultiSession { parent_client, .. } => parent_client.clone(),
+        };
+        let transport = client.read().await.transport().to_string();
+        transport
+    }
+
+    /// Remove the event handlers and breakpoint hook this session registered
+    /// on its clients
+    ///
+    /// They hold the session state, which would otherwise stay alive as long
+    /// as the clients do.
+    pub async fn clear_session_handlers(&self) {
+        let clients = match &self.session_mode {
+            SessionMode::Single { client } => vec![client.clone()],
+            SessionMode::MultiSession {
+                parent_client,
+                multi_session_manager,
+                ..
+            } => {
+                let mut clients = vec![parent_client.clone()];
+                for child_id in multi_session_manager.get_children().await {
+                    if let Some(child) = multi_session_manager.get_child(&child_id).await {
+                        clients.push(child.client);
+                    }
+                }
+                clients
+            }
+        };
+
+        for client in clients {
+            let client = client.read().await;
+            client
+                .remove_owned_event_handlers(SESSION_HANDLER_OWNER)
+                .await;
+            client.remove_breakpoints_applied_hook().await;
+        }
+    }
+
+    pub async fn get_state(&self) -> DebugState {
+        let state = self.state.read().await;
+        state.state.clone()
+    }
+
+    pub async fn get_full_state(&self) -> SessionState {
+        let state = self.state.read().await;
+        state.clone()
+    }
+}
+
+/// Host and port of a `debugpyAttach` configuration's `connect` entry
+fn subprocess_address(config: &serde_json::Value) -> Result<(String, u16)> {
+    let connect = &config["connect"];
+    let host = connect["host"].as_str().unwrap_or("127.0.0.1").to_string();
+    let port = connect["port"]
+        .as_u64()
+        .and_then(|port| u16::try_from(port).ok())
+        .ok_or_else(|| {
+            crate::Error::Dap(format!(
+                "debugpyAttach without a valid connect port: {}",
+                config
+            ))
+        })?;
+    Ok((host, port))
+}
+
+/// Session-state record of a requested line breakpoint, not yet verified
+/// Combine the answers of several clients to the same breakpoint request
+///
+/// Position by position, the first verified breakpoint wins, otherwise the
+/// first client's answer is kept.
+fn merge_breakpoint_results(
+    results: Vec<Result<Vec<crate::dap::types::Breakpoint>>>,
+) -> Result<Vec<crate::dap::types::Breakpoint>> {
+    let mut merged: Option<Vec<crate::dap::types::Breakpoint>> = None;
+    let mut last_error = None;
+    for result in results {
+        match result {
+            Ok(bps) => match &mut merged {
+                None => merged = Some(bps),
+                Some(merged) => {
+                    for (current, other) in merged.iter_mut().zip(bps) {
+                        if !current.verified && other.verified {
+                            *current = other;
+                        }
+                    }
+                }
+            },
+            Err(e) => {
+                warn!("⚠️  Setting breakpoints failed on one session: {}", e);
+                last_error = Some(e);
+            }
+        }
+    }
+    match (merged, last_error) {
+        (Some(merged), _) => Ok(merged),
+        (None, Some(e)) => Err(e),
+        (None, None) => Ok(Vec::new()),
+    }
+}
+
+fn tracked_breakpoint(source_path: &str, bp: &SourceBreakpoint) -> Breakpoint {
+    Breakpoint {
+        source_path: source_path.to_string(),
+        line: bp.line,
+        column: bp.column,
+        id: None,
+        verified: false,
+        condition: bp.condition.clone(),
+        hit_condition: bp.hit_condition.clone(),
+        log_message: bp.log_message.clone(),
+        message: None,
+        requested_line: None,
+    }
+}
+
+/// Update a tracked breakpoint with the adapter's answer for it, including
+/// the line it was moved to (e.g. from a blank line to the next statement)
+fn apply_breakpoint_result(bp: &mut Breakpoint, result: crate::dap::types::Breakpoint) {
+    bp.id = result.id.or(bp.id);
+    bp.verified = result.verified;
+    if let Some(line) = result
+        .line
+        .filter(|line| result.verified && *line != bp.line)
+    {
+        bp.requested_line.get_or_insert(bp.line);
+        bp.line = line;
+    }
+    bp.message = if result.verified {
+        None
+    } else {
+        result.message
+    };
+}
+
+/// Reject a breakpoint `line` outside `source_path`, when the file can be
+/// read locally (remote and generated sources are left to the adapter)
+pub async fn check_breakpoint_line(source_path: &str, line: i32) -> Result<()> {
+    if line < 1 {
+        return Err(crate::Error::InvalidRequest(format!(
+            "Breakpoint line must be 1 or greater (lines are 1-indexed), got {}",
+            line
+        )));
+    }
+    let Ok(source) = tokio::fs::read_to_string(source_path).await else {
+        return Ok(());
+    };
+    let lines = source.lines().count();
+    if line as usize > lines {
+        return Err(crate::Error::InvalidRequest(format!(
+            "Line {} is past the end of {}: the file has {} line{}",
+            line,
+            source_path,
+            lines,
+            if lines == 1 { "" } else { "s" }
+        )));
+    }
+    Ok(())
+}
+
+/// Why a breakpoint did not verify, and what to do about it
+#[derive(Debug, Clone, PartialEq)]
+pub struct BreakpointDiagnosis {
+    pub hint: String,
+    /// Path the adapter loaded the file from, when the breakpoint should be
+    /// set there instead
+    pub suggested_source_path: Option<String>,
+}
+
+impl BreakpointDiagnosis {
+    fn hint(hint: String) -> Self {
+        Self {
+            hint,
+            suggested_source_path: None,
+        }
+    }
+}
+
+/// Explain why the breakpoint at `source_path:line` is not verified
+///
+/// Checks, in order: whether the adapter is still initializing, whether the
+/// file exists, whether `line` is past its end, blank or a comment, and
+/// whether the adapter loaded the file from another path (different case, a
+/// symlink, or another copy of a file with the same name). Falls back to the
+/// adapter's own `message`.
+pub fn diagnose_unverified_breakpoint(
+    source_path: &str,
+    line: i32,
+    state: &SessionState,
+    adapter_message: Option<&str>,
+) -> BreakpointDiagnosis {
+    if matches!(
+        state.state,
+        DebugState::NotStarted
+            | DebugState::Initializing
+            | DebugState::Initialized
+            | DebugState::Launching
+    ) {
+        return BreakpointDiagnosis::hint(
+            "The debug adapter is still initializing; breakpoints are verified once the program \
+             has been launched. Check again with debugger_list_breakpoints"
+                .to_string(),
+        );
+    }
+
+    let path = std::path::Path::new(source_path);
+    match std::fs::read_to_string(path) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return BreakpointDiagnosis::hint(format!(
+                "{} does not exist on disk. Check the path, or list the files the adapter knows \
+                 with debugger_loaded_sources",
+                source_path
+            ));
+        }
+        // Unreadable files (permissions, not UTF-8) skip the line checks
+        Err(_) => {}
+        Ok(content) => {
+            let lines: Vec<&str> = content.lines().collect();
+            let index = line.max(1) as usize - 1;
+            let Some(text) = lines.get(index).map(|text| text.trim()) else {
+                return BreakpointDiagnosis::hint(format!(
+                    "Line {} is past the end of {} ({} lines)",
+                    line,
+                    source_path,
+                    lines.len()
+                ));
+            };
+            let is_code = |text: &str| !text.is_empty() && !is_comment_line(path, text);
+            if !is_code(text) {
+                let what = if text.is_empty() {
+                    "blank"
+                } else {
+                    "a comment"
+                };
+                let next = lines
+                    .iter()
+                    .enumerate()
+                    .skip(index + 1)
+                    .find(|(_, text)| is_code(text.trim()))
+                    .map(|(next, _)| next + 1);
+                return BreakpointDiagnosis::hint(match next {
+                    Some(next) => format!(
+                        "Line {} is {}; breakpoints only bind on executable code. The next code line is {}",
+                        line, what, next
+                    ),
+                    None => format!("Line {} is {} and no code follows it", line, what),
+                });
+            }
+        }
+    }
+
+    if let Some(diagnosis) = diagnose_loaded_elsewhere(source_path, state) {
+        return diagnosis;
+    }
+
+    BreakpointDiagnosis::hint(match adapter_message {
+        Some(message) => format!(
+            "The debug adapter could not bind the breakpoint: {}",
+            message
+        ),
+        None => "The debug adapter has not bound this breakpoint. The code may not be loaded yet \
+                 (breakpoints in modules imported later verify once they load) or the line may \
+                 not hold executable code"
+            .to_string(),
+    })
+}
+
+/// The adapter knows the file under another path than `source_path`
+fn diagnose_loaded_elsewhere(
+    source_path: &str,
+    state: &SessionState,
+) -> Option<BreakpointDiagnosis> {
+    if state.is_loaded_source(source_path) {
+        return None;
+    }
+    let suggest = |loaded: &str, hint: String| {
+        Some(BreakpointDiagnosis {
+            hint,
+            suggested_source_path: Some(loaded.to_string()),
+        })
+    };
+
+    if let Some(loaded) = state
+        .loaded_sources
+        .iter()
+        .find(|loaded| loaded.eq_ignore_ascii_case(source_path))
+    {
+        return suggest(
+            loaded,
+            format!(
+                "The debug adapter loaded this file as {}, which differs only in case; set the breakpoint on that path",
+                loaded
+            ),
+        );
+    }
+
+    if let Ok(canonical) = std::fs::canonicalize(source_path) {
+        if let Some(loaded) = state
+            .loaded_sources
+            .iter()
+            .find(|loaded| std::fs::canonicalize(loaded).is_ok_and(|path| path == canonical))
+        {
+            return suggest(
+                loaded,
+                format!(
+                    "The debug adapter loaded this file through another path ({}, a symlink or its target); set the breakpoint on that path",
+                    loaded
+                ),
+            );
+        }
+    }
+
+    let loaded = state.closest_loaded_source(source_path)?;
+    let hint = format!(
+        "The debug adapter loaded a file of the same name from {}; if that is the file you mean, set the breakpoint on that path",
+        loaded
+    );
+    suggest(&loaded, hint)
+}
+
+/// Whether the (trimmed) line is a comment in the language of `path`
+fn is_comment_line(path: &std::path::Path, trimmed: &str) -> bool {
+    let prefixes: &[&str] = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("py" | "rb" | "sh" | "bash") => &["#"],
+        Some("js" | "mjs" | "cjs" | "ts" | "go" | "rs" | "c" | "h" | "cc" | "cpp" | "hpp") => {
+            &["//", "/*"]
+        }
+        _ => &[],
+    };
+    prefixes.iter().any(|prefix| trimmed.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dap::testing::MockTransport;
+    use crate::dap::types::*;
+    use crate::Error;
+    use serde_json::json;
+
+    fn create_mock_with_response(response: Response) -> MockTransport {
+        let mut mock = MockTransport::new();
+        mock.writer
+            .expect_write_message()
+            .times(1)
+            .returning(|_| Ok(()));
+        mock.reader
+            .expect_read_message()
+            .times(1)
+            .return_once(move || Ok(Message::Response(response)));
+        mock.reader
+            .expect_read_message()
+            .returning(|| Err(Error::Dap("Connection closed".to_string())));
+        mock
+    }
+
+    fn create_empty_mock() -> MockTransport {
+        let mut mock = MockTransport::new();
+        mock.reader
+            .expect_read_message()
+            .returning(|| Err(Error::Dap("Connection closed".to_string())));
         mock
     }
 
+    #[tokio::test]
+    async fn test_pending_breakpoint_verification_recorded_on_launch() {
+        let adapter = crate::dap::testing::ScriptedAdapter::new().rejecting_line(7);
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/main.py".to_string(), client)
+            .await
+            .unwrap();
+
+        for line in [3, 7] {
+            session
+                .add_pending_breakpoint(
+                    "/app/main.py".to_string(),
+                    SourceBreakpoint {
+                        line,
+                        column: None,
+                        condition: None,
+                        hit_condition: None,
+                        log_message: None,
+                    },
+                )
+                .await;
+        }
+
+        session
+            .initialize_and_launch(
+                "debugpy",
+                json!({"program": "/app/main.py", "stopOnEntry": false}),
+            )
+            .await
+            .unwrap();
+
+        let bps = session
+            .get_full_state()
+            .await
+            .get_breakpoints("/app/main.py");
+        assert_eq!(bps.len(), 2);
+        assert!(bps.iter().find(|b| b.line == 3).unwrap().verified);
+        assert!(!bps.iter().find(|b| b.line == 7).unwrap().verified);
+        assert!(session.pending_breakpoints.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_entry_breakpoint_reported_as_entry_and_removed_on_resume() {
+        let dir = tempfile::tempdir().unwrap();
+        let program = dir.path().join("loop.rb");
+        std::fs::write(&program, "x = 0\nloop do\n  x += 1\nend\n").unwrap();
+        let program = program.to_str().unwrap().to_string();
+
+        // rdbg stops at the entry breakpoint (ID = line) right after launch
+        let adapter = crate::dap::testing::ScriptedAdapter::new()
+            .stopping_on_resume()
+            .emitting_on_launch(
+                "stopped",
+                json!({"reason": "breakpoint", "threadId": 1, "hitBreakpointIds": [2]}),
+            );
+        let requests = adapter.requests();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("ruby".to_string(), program.clone(), client)
+            .await
+            .unwrap()
+            .with_entry_line(Some(2));
+        session
+            .add_pending_breakpoint(
+                program.clone(),
+                SourceBreakpoint {
+                    line: 3,
+                    column: None,
+                    condition: None,
+                    hit_condition: None,
+                    log_message: None,
+                },
+            )
+            .await;
+
+        session
+            .initialize_and_launch("rdbg", json!({"program": program, "stopOnEntry": true}))
+            .await
+            .unwrap();
+        session.sync_state().await;
+
+        let state = session.get_full_state().await;
+        let stop = state.last_stop.clone().unwrap();
+        assert_eq!(stop.reason, "entry");
+        assert!(stop.hit_breakpoint_ids.is_empty());
+        // Only the user's breakpoint is listed
+        let lines: Vec<i32> = state
+            .get_breakpoints(&program)
+            .iter()
+            .map(|b| b.line)
+            .collect();
+        assert_eq!(lines, vec![3]);
+
+        session.continue_execution().await.unwrap();
+        session.continue_execution().await.unwrap();
+
+        // One setBreakpoints without the entry line, before the first continue
+        let requests = requests.lock().unwrap().clone();
+        let sent: Vec<(String, Vec<i64>)> = requests
+            .iter()
+            .filter(|r| r.command == "setBreakpoints" || r.command == "continue")
+            .map(|r| {
+                let lines = r
+                    .arguments
+                    .as_ref()
+                    .and_then(|a| a["breakpoints"].as_array());
+                let lines = lines
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|bp| bp["line"].as_i64())
+                    .collect();
+                (r.command.clone(), lines)
+            })
+            .collect();
+        assert_eq!(
+            sent,
+            vec![
+                ("setBreakpoints".to_string(), vec![3]),
+                ("setBreakpoints".to_string(), vec![3, 2]),
+                ("setBreakpoints".to_string(), vec![3]),
+                ("continue".to_string(), vec![]),
+                ("continue".to_string(), vec![]),
+            ]
+        );
+        assert_eq!(
+            session.get_full_state().await.last_stop.unwrap().reason,
+            "breakpoint"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_guessed_entry_line_reported_as_diagnostic() {
+        let dir = tempfile::tempdir().unwrap();
+        let program = dir.path().join("empty.rb");
+        std::fs::write(&program, "# nothing to run\n").unwrap();
+        let program = program.to_str().unwrap().to_string();
+
+        let client = DapClient::new_with_transport(
+            Box::new(crate::dap::testing::ScriptedAdapter::new()),
+            None,
+        )
+        .await
+        .unwrap();
+        let session = DebugSession::new("ruby".to_string(), program.clone(), client)
+            .await
+            .unwrap();
+        session
+            .initialize_and_launch("rdbg", json!({"program": program, "stopOnEntry": true}))
+            .await
+            .unwrap();
+
+        let state = session.get_full_state().await;
+        assert!(state.entry_line.as_ref().unwrap().is_fallback());
+        assert!(state
+            .diagnostics()
+            .contains(&crate::debug::state::ENTRY_LINE_GUESSED.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_breakpoint_events_keep_verification_current() {
+        let adapter = crate::dap::testing::ScriptedAdapter::new()
+            .binding_late(12)
+            .rejecting_line(20);
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/main.py".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .initialize_and_launch("debugpy", json!({"program": "/app/main.py"}))
+            .await
+            .unwrap();
+        session.state.write().await.set_state(DebugState::Running);
+
+        for line in [12, 20] {
+            let verified = session
+                .set_source_breakpoint(
+                    "/app/lazy.py".to_string(),
+                    SourceBreakpoint {
+                        line,
+                        column: None,
+                        condition: None,
+                        hit_condition: None,
+                        log_message: None,
+                    },
+                )
+                .await
+                .unwrap();
+            assert!(!verified, "line {}", line);
+        }
+        session.sync_state().await;
+
+        let state = session.get_full_state().await;
+        // Bound by the 'breakpoint' event, on the next line
+        let late = state.breakpoint_by_id(12).unwrap();
+        assert!(late.verified);
+        assert_eq!(late.line, 13);
+        assert_eq!(late.message, None);
+        let rejected = state.breakpoint_by_id(20).unwrap();
+        assert!(!rejected.verified);
+        assert_eq!(
+            rejected.message.as_deref(),
+            Some("Breakpoint in file that does not exist")
+        );
+    }
+
+    #[test]
+    fn test_diagnose_unverified_breakpoint_categories() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("app.py");
+        std::fs::write(&source, "import os\n\n# setup\nx = 1\nprint(x)\n").unwrap();
+        let source = source.to_string_lossy().to_string();
+        let mut state = SessionState::new();
+        let diagnose = |path: &str, line: i32, state: &SessionState, message: Option<&str>| {
+            diagnose_unverified_breakpoint(path, line, state, message)
+        };
+
+        // Still initializing
+        assert!(diagnose(&source, 4, &state, None)
+            .hint
+            .contains("still initializing"));
+        state.set_state(DebugState::Running);
+
+        let missing = dir.path().join("missing.py").to_string_lossy().to_string();
+        assert!(diagnose(&missing, 1, &state, None)
+            .hint
+            .contains("does not exist on disk"));
+        assert!(diagnose(&source, 9, &state, None)
+            .hint
+            .contains("past the end of"));
+        assert!(diagnose(&source, 2, &state, None).hint.contains(
+            "Line 2 is blank; breakpoints only bind on executable code. The next code line is 4"
+        ));
+        assert!(diagnose(&source, 3, &state, None)
+            .hint
+            .starts_with("Line 3 is a comment"));
+
+        // Executable line the adapter did not bind
+        let fallback = diagnose(&source, 4, &state, Some("Unbound breakpoint"));
+        assert!(fallback.hint.ends_with("Unbound breakpoint"));
+        assert_eq!(fallback.suggested_source_path, None);
+    }
+
+    #[tokio::test]
+    async fn test_check_breakpoint_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("app.py");
+        std::fs::write(&source, "x = 1\n\nprint(x)").unwrap();
+        let source = source.to_string_lossy().to_string();
+
+        assert!(check_breakpoint_line(&source, 1).await.is_ok());
+        assert!(check_breakpoint_line(&source, 3).await.is_ok());
+        for line in [0, -1] {
+            let error = check_breakpoint_line(&source, line).await.unwrap_err();
+            assert!(
+                error.to_string().contains("must be 1 or greater"),
+                "{}",
+                error
+            );
+        }
+        let error = check_breakpoint_line(&source, 4).await.unwrap_err();
+        assert!(matches!(error, crate::Error::InvalidRequest(_)));
+        assert!(
+            error.to_string().contains("the file has 3 lines"),
+            "{}",
+            error
+        );
+
+        // Files that cannot be read locally are left to the adapter
+        let remote = dir.path().join("remote.py").to_string_lossy().to_string();
+        assert!(check_breakpoint_line(&remote, 500).await.is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_diagnose_unverified_breakpoint_loaded_elsewhere() {
+        let dir = tempfile::tempdir().unwrap();
+        let real = dir.path().join("real");
+        std::fs::create_dir(&real).unwrap();
+        std::fs::write(real.join("app.py"), "x = 1\n").unwrap();
+        std::fs::write(real.join("Util.py"), "y = 2\n").unwrap();
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+        let path = |p: std::path::PathBuf| p.to_string_lossy().to_string();
+
+        let mut state = SessionState::new();
+        state.set_state(DebugState::Stopped {
+            thread_id: 1,
+            reason: "breakpoint".to_string(),
+        });
+        state.record_loaded_source("new", path(real.join("app.py")));
+        state.record_loaded_source("new", path(real.join("Util.py")));
+        state.record_loaded_source("new", "/srv/app/models.py".to_string());
+
+        let symlinked = diagnose_unverified_breakpoint(&path(link.join("app.py")), 1, &state, None);
+        assert!(symlinked.hint.contains("symlink"));
+        assert_eq!(
+            symlinked.suggested_source_path,
+            Some(path(real.join("app.py")))
+        );
+
+        std::fs::write(real.join("util.py"), "y = 2\n").unwrap();
+        let cased = diagnose_unverified_breakpoint(&path(real.join("util.py")), 1, &state, None);
+        assert!(cased.hint.contains("differs only in case"));
+        assert_eq!(
+            cased.suggested_source_path,
+            Some(path(real.join("Util.py")))
+        );
+
+        std::fs::write(real.join("models.py"), "z = 3\n").unwrap();
+        let copy = diagnose_unverified_breakpoint(&path(real.join("models.py")), 1, &state, None);
+        assert!(copy.hint.contains("same name"));
+        assert_eq!(
+            copy.suggested_source_path.as_deref(),
+            Some("/srv/app/models.py")
+        );
+
+        // A loaded path has nothing to suggest
+        let loaded = diagnose_unverified_breakpoint(&path(real.join("app.py")), 1, &state, None);
+        assert_eq!(loaded.suggested_source_path, None);
+    }
+
+    #[tokio::test]
+    async fn test_post_mortem_session_cannot_resume() {
+        let adapter = crate::dap::testing::ScriptedAdapter::new();
+        let trace = adapter.trace();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("rust".to_string(), "/app/crasher".to_string(), client)
+            .await
+            .unwrap()
+            .with_core_dump(Some("/app/core".to_string()));
+        session.state.write().await.set_state(DebugState::Stopped {
+            thread_id: 1,
+            reason: "signal".to_string(),
+        });
+
+        for result in [
+            session.continue_execution().await,
+            session.step_over(1, None).await,
+            session.step_out(1, None).await,
+        ] {
+            match result {
+                Err(crate::Error::InvalidState(msg)) => {
+                    assert!(msg.contains("post-mortem"), "{}", msg)
+                }
+                other => panic!("Expected InvalidState, got {:?}", other),
+            }
+        }
+        assert!(trace.lock().unwrap().is_empty());
+        assert_eq!(session.core_dump(), Some("/app/core"));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_resumes_are_rejected() {
+        let adapter = crate::dap::testing::ScriptedAdapter::new().stopping_on_resume();
+        let trace = adapter.trace();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("ruby".to_string(), "/app/main.rb".to_string(), client)
+            .await
+            .unwrap();
+        session.state.write().await.set_state(DebugState::Stopped {
+            thread_id: 1,
+            reason: "breakpoint".to_string(),
+        });
+
+        let (first, second) =
+            tokio::join!(session.continue_execution(), session.continue_execution());
+        assert!(first.is_ok());
+        match second {
+            Err(crate::Error::InvalidState(msg)) => {
+                assert_eq!(msg, "Cannot continue: a continue is already in progress")
+            }
+            other => panic!("Expected InvalidState, got {:?}", other),
+        }
+
+        let (step, continued) =
+            tokio::join!(session.step_over(1, None), session.continue_execution());
+        assert!(step.is_ok());
+        assert!(
+            matches!(continued, Err(crate::Error::InvalidState(msg)) if msg.contains("a step is already in progress"))
+        );
+
+        // Only the accepted resumes reached the adapter, and the gate is free again
+        let resumes = trace
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|command| matches!(command.as_str(), "continue" | "next"))
+            .count();
+        assert_eq!(resumes, 2);
+        session.step_into(1, None, None).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reads_run_concurrently_and_wait_for_resume() {
+        let client = DapClient::new_with_transport(
+            Box::new(crate::dap::testing::ScriptedAdapter::new()),
+            None,
+        )
+        .await
+        .unwrap();
+        let session = DebugSession::new("ruby".to_string(), "/app/main.rb".to_string(), client)
+            .await
+            .unwrap();
+        session.state.write().await.set_state(DebugState::Stopped {
+            thread_id: 1,
+            reason: "breakpoint".to_string(),
+        });
+
+        let (frames, scopes, value) = tokio::join!(
+            session.stack_trace(),
+            session.scopes(1),
+            session.evaluate("n", Some(1))
+        );
+        assert_eq!(frames.unwrap()[0].name, "main");
+        assert_eq!(scopes.unwrap()[0].name, "Locals");
+        assert_eq!(value.unwrap().result, "n");
+
+        // A read waits for the resume holding the gate
+        let resume = session.gate.resume("continue").await.unwrap();
+        let read = session.stack_trace();
+        tokio::pin!(read);
+        assert!(tokio::time::timeout(Duration::from_millis(50), &mut read)
+            .await
+            .is_err());
+        drop(resume);
+        assert!(read.await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rapid_resume_and_stop_keeps_last_state() {
+        let adapter = crate::dap::testing::ScriptedAdapter::new().stopping_on_resume();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/main.py".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .initialize_and_launch("debugpy", json!({"program": "/app/main.py"}))
+            .await
+            .unwrap();
+
+        const ROUNDS: usize = 50;
+        for round in 0..ROUNDS {
+            if round % 2 == 0 {
+                session.continue_execution().await.unwrap();
+            } else {
+                session.step_over(1, None).await.unwrap();
+            }
+            // Never a stale state from before the resume
+            let stops = session.get_full_state().await.timing.stops();
+            assert!(stops >= round, "round {}: {} stops", round, stops);
+        }
+
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(2);
+        while session.get_full_state().await.timing.stops() < ROUNDS {
+            assert!(tokio::time::Instant::now() < deadline, "stops were lost");
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        session.sync_state().await;
+        assert_eq!(
+            session.get_state().await,
+            DebugState::Stopped {
+                thread_id: 1,
+                reason: "step".to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_event_burst_leaves_state_of_last_event() {
+        use crate::dap::testing::ScriptedAdapter;
+
+        let stopped = |reason: &str| json!({"reason": reason, "threadId": 1});
+        for (last, expected) in [
+            (
+                Some(stopped("step")),
+                DebugState::Stopped {
+                    thread_id: 1,
+                    reason: "step".to_string(),
+                },
+            ),
+            (None, DebugState::Running),
+        ] {
+            // stopped -> continued -> stopped ... read back to back
+            let mut adapter = ScriptedAdapter::new();
+            for _ in 0..20 {
+                adapter = adapter
+                    .emitting_on_launch("stopped", stopped("breakpoint"))
+                    .emitting_on_launch("continued", json!({"threadId": 1}));
+            }
+            if let Some(body) = last {
+                adapter = adapter.emitting_on_launch("stopped", body);
+            }
+            let client = DapClient::new_with_transport(Box::new(adapter), None)
+                .await
+                .unwrap();
+            let session =
+                DebugSession::new("python".to_string(), "/app/main.py".to_string(), client)
+                    .await
+                    .unwrap();
+            session
+                .initialize_and_launch("debugpy", json!({"program": "/app/main.py"}))
+                .await
+                .unwrap();
+
+            session.sync_state().await;
+            assert_eq!(session.get_state().await, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_multi_session_broadcasts_breakpoints_and_routes_by_thread() {
+        use super::super::multi_session::ChildSession;
+        use crate::dap::testing::ScriptedAdapter;
+
+        let parent = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+            .await
+            .unwrap();
+        let manager = MultiSessionManager::new("parent".to_string());
+        let session = DebugSession::new_with_mode(
+            "nodejs".to_string(),
+            "/app/parent.js".to_string(),
+            SessionMode::MultiSession {
+                parent_client: Arc::new(RwLock::new(parent)),
+                multi_session_manager: manager.clone(),
+                adapter_port: 0,
+            },
+        )
+        .await
+        .unwrap();
+        session.state.write().await.set_state(DebugState::Running);
+
+        // child-1 cannot bind line 10, child-2 can
+        let mut requests = Vec::new();
+        let mut clients = Vec::new();
+        for (id, adapter) in [
+            ("child-1", ScriptedAdapter::new().rejecting_line(10)),
+            ("child-2", ScriptedAdapter::new()),
+        ] {
+            let adapter = adapter.stopping_on_resume();
+            requests.push(adapter.requests());
+            let client = DapClient::new_with_transport(Box::new(adapter), None)
+                .await
+                .unwrap();
+            session.register_state_handlers(&client, Some(id)).await;
+            let client = Arc::new(RwLock::new(client));
+            clients.push(client.clone());
+            manager
+                .add_child(ChildSession {
+                    id: id.to_string(),
+                    client,
+                    port: 0,
+                    session_type: "pwa-node".to_string(),
+                })
+                .await;
+        }
+        let commands = |i: usize, command: &str| {
+            requests[i]
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|r| r.command == command)
+                .count()
+        };
+
+        assert!(session
+            .set_breakpoint("/app/child.js".to_string(), 10)
+            .await
+            .unwrap());
+        assert_eq!(commands(0, "setBreakpoints"), 1);
+        assert_eq!(commands(1, "setBreakpoints"), 1);
+
+        // child-2 stops on its thread 1 while child-1 is the active child
+        clients[1].read().await.continue_execution(1).await.unwrap();
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(2);
+        while !matches!(session.get_state().await, DebugState::Stopped { .. }) {
+            assert!(tokio::time::Instant::now() < deadline, "no stop");
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        let stop = session.get_full_state().await.last_stop.unwrap();
+        assert_eq!(stop.child_session.as_deref(), Some("child-2"));
+        manager
+            .set_active_child("child-1".to_string())
+            .await
+            .unwrap();
+
+        session.stack_trace().await.unwrap();
+        session.continue_execution().await.unwrap();
+        assert_eq!(commands(0, "stackTrace") + commands(0, "continue"), 0);
+        assert_eq!(commands(1, "stackTrace"), 1);
+        assert_eq!(commands(1, "continue"), 2);
+
+        let children = session.children().await;
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[1].id, "child-2");
+    }
+
+    #[tokio::test]
+    async fn test_child_termination_keeps_multi_session_alive() {
+        use super::super::multi_session::ChildSession;
+        use crate::dap::testing::ScriptedAdapter;
+
+        let parent = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+            .await
+            .unwrap();
+        let manager = MultiSessionManager::new("parent".to_string());
+        let session = DebugSession::new_with_mode(
+            "nodejs".to_string(),
+            "/app/main.js".to_string(),
+            SessionMode::MultiSession {
+                parent_client: Arc::new(RwLock::new(parent)),
+                multi_session_manager: manager.clone(),
+                adapter_port: 0,
+            },
+        )
+        .await
+        .unwrap();
+        let parent = session.get_debug_client().await;
+        session
+            .register_state_handlers(&*parent.read().await, None)
+            .await;
+        session.state.write().await.set_state(DebugState::Running);
+
+        let mut clients = Vec::new();
+        for id in ["child-main", "child-worker"] {
+            let client = DapClient::new_with_transport(
+                Box::new(ScriptedAdapter::new().stopping_on_resume()),
+                None,
+            )
+            .await
+            .unwrap();
+            session.register_state_handlers(&client, Some(id)).await;
+            let client = Arc::new(RwLock::new(client));
+            clients.push(client.clone());
+            manager
+                .add_child(ChildSession {
+                    id: id.to_string(),
+                    client,
+                    port: 0,
+                    session_type: "pwa-node".to_string(),
+                })
+                .await;
+        }
+
+        // The main child stops, then the worker ends
+        clients[0].read().await.continue_execution(1).await.unwrap();
+        clients[1]
+            .read()
+            .await
+            .send_request("terminate", None)
+            .await
+            .unwrap();
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(2);
+        while manager.child_count().await == 2 {
+            assert!(tokio::time::Instant::now() < deadline, "worker not removed");
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        session.sync_state().await;
+        assert_eq!(
+            session.get_state().await,
+            DebugState::Stopped {
+                thread_id: 1,
+                reason: "breakpoint".to_string()
+            }
+        );
+        assert_eq!(
+            manager.get_active_child_id().await.as_deref(),
+            Some("child-main")
+        );
+
+        // Last child gone, but the parent adapter is still running
+        clients[0]
+            .read()
+            .await
+            .send_request("terminate", None)
+            .await
+            .unwrap();
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(2);
+        while manager.child_count().await == 1 {
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "main child not removed"
+            );
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        session.sync_state().await;
+        assert_eq!(session.get_state().await, DebugState::Running);
+
+        parent
+            .read()
+            .await
+            .send_request("terminate", None)
+            .await
+            .unwrap();
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(2);
+        while session.get_state().await != DebugState::Terminated {
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "session not terminated"
+            );
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(manager.child_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_python_subprocess_session_ends_with_its_last_process() {
+        use crate::dap::testing::ScriptedAdapter;
+
+        let client = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+            .await
+            .unwrap();
+        let session =
+            DebugSession::new_following_subprocesses("/app/server.py".to_string(), client)
+                .await
+                .unwrap();
+        assert_eq!(session.main_process_child(), Some(PYTHON_MAIN_PROCESS));
+        let main = session.get_debug_client().await;
+        session
+            .register_state_handlers(&*main.read().await, session.main_process_child())
+            .await;
+        session.state.write().await.set_state(DebugState::Running);
+
+        let subprocess = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+            .await
+            .unwrap();
+        session
+            .register_state_handlers(&subprocess, Some("subprocess-42"))
+            .await;
+        let SessionMode::MultiSession {
+            multi_session_manager: manager,
+            ..
+        } = &session.session_mode
+        else {
+            panic!("expected a multi-session");
+        };
+        manager
+            .add_child(ChildSession {
+                id: "subprocess-42".to_string(),
+                client: Arc::new(RwLock::new(subprocess)),
+                port: 0,
+                session_type: "python".to_string(),
+            })
+            .await;
+
+        // The main process (the reloader) exits first: the server lives on
+        main.read()
+            .await
+            .send_request("terminate", None)
+            .await
+            .unwrap();
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(2);
+        while manager.child_count().await == 2 {
+            assert!(tokio::time::Instant::now() < deadline, "main not removed");
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        session.sync_state().await;
+        assert_eq!(session.get_state().await, DebugState::Running);
+
+        let subprocess = manager.get_child("subprocess-42").await.unwrap().client;
+        subprocess
+            .read()
+            .await
+            .send_request("terminate", None)
+            .await
+            .unwrap();
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(2);
+        while session.get_state().await != DebugState::Terminated {
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "session not terminated"
+            );
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }
+
+    #[test]
+    fn test_subprocess_address_from_debugpy_attach() {
+        let config = json!({
+            "request": "attach",
+            "connect": {"host": "127.0.0.1", "port": 50123},
+            "subProcessId": 4242
+        });
+        assert_eq!(
+            subprocess_address(&config).unwrap(),
+            ("127.0.0.1".to_string(), 50123)
+        );
+        assert!(subprocess_address(&json!({"connect": {"port": 70000}})).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_relaunch_registers_handlers_once() {
+        let client = DapClient::new_with_transport(
+            Box::new(crate::dap::testing::ScriptedAdapter::new().stopping_on_resume()),
+            None,
+        )
+        .await
+        .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/main.py".to_string(), client)
+            .await
+            .unwrap();
+        let client = session.get_debug_client().await;
+        let invocations = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = invocations.clone();
+        {
+            let client = client.read().await;
+            client
+                .on_event("stopped", move |_| {
+                    counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                })
+                .await;
+            session.register_state_handlers(&client, None).await;
+            session.register_state_handlers(&client, None).await;
+            assert_eq!(client.event_handler_count("stopped").await, 2);
+        }
+
+        session.continue_execution().await.unwrap();
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(2);
+        while invocations.load(std::sync::atomic::Ordering::SeqCst) == 0 {
+            assert!(tokio::time::Instant::now() < deadline);
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        session.sync_state().await;
+        assert_eq!(session.get_full_state().await.timing.stops(), 1);
+
+        // Disconnect leaves only handlers the session does not own
+        session.disconnect().await.unwrap();
+        let client = client.read().await;
+        assert_eq!(client.event_handler_count("stopped").await, 1);
+        assert_eq!(client.event_handler_count("output").await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_path_mapping_translates_breakpoints_and_frames() {
+        use crate::debug::path_mapping::PathMapping;
+
+        let adapter = crate::dap::testing::ScriptedAdapter::new().frame_source("/app/main.py");
+        let requests = adapter.requests();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let mapper = PathMapper::new(
+            vec![PathMapping {
+                local: "/home/me/project".to_string(),
+                remote: "/app".to_string(),
+            }],
+            true,
+        );
+        let session = DebugSession::new("python".to_string(), "/app/main.py".to_string(), client)
+            .await
+            .unwrap()
+            .with_path_mapper(mapper);
+
+        // Pending breakpoint, applied during the launch sequence
+        session
+            .set_breakpoint("/home/me/project/main.py".to_string(), 3)
+            .await
+            .unwrap();
+        session
+            .initialize_and_launch("debugpy", json!({"program": "/app/main.py"}))
+            .await
+            .unwrap();
+        // Breakpoint set while running
+        session.state.write().await.set_state(DebugState::Running);
+        session
+            .set_breakpoint("/home/me/project/util.py".to_string(), 9)
+            .await
+            .unwrap();
+
+        let sent: Vec<String> = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.command == "setBreakpoints")
+            .map(|r| r.arguments.as_ref().unwrap()["source"]["path"].to_string())
+            .collect();
+        assert_eq!(sent, vec!["\"/app/main.py\"", "\"/app/util.py\""]);
+
+        // State stays keyed by the client's paths
+        let state = session.get_full_state().await;
+        assert!(state.get_breakpoints("/home/me/project/main.py")[0].verified);
+        assert!(state.get_breakpoints("/home/me/project/util.py")[0].verified);
+
+        let frames = session.stack_trace().await.unwrap();
+        assert_eq!(
+            frames[0].source.as_ref().unwrap().path.as_deref(),
+            Some("/home/me/project/main.py")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_logpoint_rejected_without_adapter_support() {
+        let adapter = crate::dap::testing::ScriptedAdapter::new();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/main.py".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .initialize_and_launch("debugpy", json!({"program": "/app/main.py"}))
+            .await
+            .unwrap();
+        session.state.write().await.set_state(DebugState::Running);
+
+        let result = session
+            .set_source_breakpoint(
+                "/app/main.py".to_string(),
+                SourceBreakpoint {
+                    line: 3,
+                    column: None,
+                    condition: None,
+                    hit_condition: None,
+                    log_message: Some("n = {n}".to_string()),
+                },
+            )
+            .await;
+
+        match result {
+            Err(Error::InvalidRequest(msg)) => assert!(msg.contains("supportsLogPoints")),
+            other => panic!("Expected InvalidRequest, got {:?}", other),
+        }
+        assert!(session
+            .get_full_state()
+            .await
+            .get_breakpoints("/app/main.py")
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_set_breakpoints_bulk_merges_per_file() {
+        let adapter = crate::dap::testing::ScriptedAdapter::new().rejecting_line(9);
+        let requests = adapter.requests();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/main.py".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .initialize_and_launch("debugpy", json!({"program": "/app/main.py"}))
+            .await
+            .unwrap();
+        session.state.write().await.set_state(DebugState::Running);
+
+        let bp = |line| SourceBreakpoint {
+            line,
+            column: None,
+            condition: None,
+            hit_condition: None,
+            log_message: None,
+        };
+        session
+            .set_breakpoints_bulk(vec![("/app/main.py".to_string(), bp(2))])
+            .await
+            .unwrap();
+
+        let results = session
+            .set_breakpoints_bulk(vec![
+                ("/app/util.py".to_string(), bp(9)),
+                ("/app/main.py".to_string(), bp(5)),
+                ("/app/util.py".to_string(), bp(4)),
+            ])
+            .await
+            .unwrap();
+
+        let order: Vec<(&str, i32, bool)> = results
+            .iter()
+            .map(|bp| (bp.source_path.as_str(), bp.line, bp.verified))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("/app/util.py", 9, false),
+                ("/app/main.py", 5, true),
+                ("/app/util.py", 4, true),
+            ]
+        );
+        assert_eq!(results[1].id, Some(5));
+
+        // One request per file, and main.py keeps its earlier breakpoint
+        let sent: Vec<serde_json::Value> = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|req| req.command == "setBreakpoints")
+            .map(|req| req.arguments.clone().unwrap())
+            .collect();
+        assert_eq!(sent.len(), 3);
+        assert_eq!(sent[1]["source"]["path"], "/app/util.py");
+        assert_eq!(sent[1]["breakpoints"].as_array().unwrap().len(), 2);
+        let main_lines: Vec<i64> = sent[2]["breakpoints"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|bp| bp["line"].as_i64().unwrap())
+            .collect();
+        assert_eq!(main_lines, vec![2, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_breakpoint_locations_gated_on_capability() {
+        for supported in [false, true] {
+            let mut adapter = crate::dap::testing::ScriptedAdapter::new();
+            if supported {
+                adapter = adapter.with_capability("supportsBreakpointLocationsRequest");
+            }
+            let client = DapClient::new_with_transport(Box::new(adapter), None)
+                .await
+                .unwrap();
+            let session =
+                DebugSession::new("nodejs".to_string(), "/app/main.js".to_string(), client)
+                    .await
+                    .unwrap();
+            session
+                .initialize_and_launch("pwa-node", json!({"program": "/app/main.js"}))
+                .await
+                .unwrap();
+            session.state.write().await.set_state(DebugState::Running);
+
+            let result = session
+                .breakpoint_locations("/app/main.js".to_string(), 4, Some(5))
+                .await;
+            if supported {
+                let locations = result.unwrap();
+                assert_eq!(locations.len(), 4);
+                assert_eq!((locations[3].line, locations[3].column), (5, Some(10)));
+            } else {
+                assert!(matches!(result, Err(Error::InvalidRequest(_))));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exit_code_recorded_from_exited_event() {
+        let adapter = crate::dap::testing::ScriptedAdapter::new()
+            .emitting_on_launch(
+                "output",
+                json!({"category": "stderr", "output": "Traceback: boom\n"}),
+            )
+            .emitting_on_launch("exited", json!({"exitCode": 3}))
+            .emitting_on_launch("terminated", json!({}));
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/main.py".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .initialize_and_launch("debugpy", json!({"program": "/app/main.py"}))
+            .await
+            .unwrap();
+        session.sync_state().await;
+
+        let state = session.get_full_state().await;
+        assert_eq!(state.state, DebugState::Terminated);
+        assert_eq!(state.exit_code, Some(3));
+        assert_eq!(
+            state.exit_details()["stderrTail"],
+            json!(["Traceback: boom"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_and_capabilities_events() {
+        let adapter = crate::dap::testing::ScriptedAdapter::new()
+            .emitting_on_launch(
+                "process",
+                json!({"name": "/app/main.py", "systemProcessId": 4242, "startMethod": "launch"}),
+            )
+            .emitting_on_launch(
+                "capabilities",
+                json!({"capabilities": {"supportsBreakpointLocationsRequest": true}}),
+            );
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/main.py".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .initialize_and_launch("debugpy", json!({"program": "/app/main.py"}))
+            .await
+            .unwrap();
+        session.sync_state().await;
+
+        let process = session.state.read().await.process.clone().unwrap();
+        assert_eq!(process.name, "/app/main.py");
+        assert_eq!(process.launched_pid(), Some(4242));
+
+        // Only announced after initialize, yet breakpointLocations is allowed
+        let client = session.get_debug_client().await;
+        let capabilities = client.read().await.capabilities().unwrap();
+        assert_eq!(
+            capabilities.supports_breakpoint_locations_request,
+            Some(true)
+        );
+        assert_eq!(capabilities.supports_configuration_done_request, Some(true));
+        session.state.write().await.set_state(DebugState::Running);
+        let locations = session
+            .breakpoint_locations("/app/main.py".to_string(), 4, None)
+            .await
+            .unwrap();
+        assert_eq!(locations.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_set_breakpoints_bulk_keeps_columns_on_same_line() {
+        let adapter = crate::dap::testing::ScriptedAdapter::new();
+        let requests = adapter.requests();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("nodejs".to_string(), "/app/min.js".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .initialize_and_launch("pwa-node", json!({"program": "/app/min.js"}))
+            .await
+            .unwrap();
+        session.state.write().await.set_state(DebugState::Running);
+
+        let bp = |column| SourceBreakpoint {
+            line: 1,
+            column: Some(column),
+            condition: None,
+            hit_condition: None,
+            log_message: None,
+        };
+        let results = session
+            .set_breakpoints_bulk(vec![
+                ("/app/min.js".to_string(), bp(10)),
+                ("/app/min.js".to_string(), bp(42)),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1].column, Some(42));
+        let requests = requests.lock().unwrap();
+        let sent = requests
+            .iter()
+            .find(|req| req.command == "setBreakpoints")
+            .unwrap();
+        let args = sent.arguments.as_ref().unwrap();
+        assert_eq!(args["breakpoints"][0]["column"], 10);
+        assert_eq!(args["breakpoints"][1]["column"], 42);
+    }
+
+    #[tokio::test]
+    async fn test_set_breakpoints_bulk_while_initializing_is_pending() {
+        let client = DapClient::new_with_transport(Box::new(create_empty_mock()), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/main.py".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .state
+            .write()
+            .await
+            .set_state(DebugState::Initializing);
+
+        let bp = |line, condition: Option<&str>| SourceBreakpoint {
+            line,
+            column: None,
+            condition: condition.map(str::to_string),
+            hit_condition: None,
+            log_message: None,
+        };
+        session
+            .add_pending_breakpoint("/app/main.py".to_string(), bp(3, None))
+            .await;
+
+        let results = session
+            .set_breakpoints_bulk(vec![
+                ("/app/main.py".to_string(), bp(3, Some("n > 1"))),
+                ("/app/main.py".to_string(), bp(8, None)),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].condition.as_deref(), Some("n > 1"));
+        let pending = session.pending_breakpoints.read().await;
+        let lines: Vec<i32> = pending["/app/main.py"].iter().map(|bp| bp.line).collect();
+        assert_eq!(lines, vec![3, 8]);
+        assert_eq!(
+            session
+                .get_full_state()
+                .await
+                .get_breakpoints("/app/main.py")
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_function_breakpoints_requires_started_session() {
+        let client = DapClient::new_with_transport(Box::new(create_empty_mock()), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "test.py".to_string(), client)
+            .await
+            .unwrap();
+
+        let result = session
+            .set_function_breakpoints(vec![FunctionBreakpoint {
+                name: "fizzbuzz".to_string(),
+                condition: None,
+                hit_condition: None,
+            }])
+            .await;
+
+        assert!(matches!(result, Err(Error::InvalidState(_))));
+        assert!(session
+            .get_full_state()
+            .await
+            .function_breakpoints
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_watches_records_values_and_errors() {
+        let adapter = crate::dap::testing::ScriptedAdapter::new().failing_expression("missing");
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/main.py".to_string(), client)
+            .await
+            .unwrap();
+
+        session.add_watch("n".to_string()).await;
+        session.add_watch("missing".to_string()).await;
+
+        // Not stopped: nothing is evaluated
+        let watches = session.refresh_watches().await;
+        assert!(watches.iter().all(|w| w.last.is_none()));
+
+        session.state.write().await.set_state(DebugState::Stopped {
+            thread_id: 1,
+            reason: "breakpoint".to_string(),
+        });
+        let watches = session.refresh_watches().await;
+
+        assert_eq!(watches[0].last, Some(WatchValue::Value("n".to_string())));
+        match &watches[1].last {
+            Some(WatchValue::Error(msg)) => assert!(msg.contains("not defined")),
+            other => panic!("Expected watch error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_entry_stop_succeeds_when_stopped() {
+        let client = DapClient::new_with_transport(Box::new(create_empty_mock()), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "test.py".to_string(), client)
+            .await
+            .unwrap();
+
+        session.state.write().await.set_state(DebugState::Stopped {
+            thread_id: 1,
+            reason: "entry".to_string(),
+        });
+
+        assert!(session.verify_entry_stop(Duration::from_millis(200)).await);
+        assert!(!session.get_full_state().await.entry_stop_missed);
+    }
+
+    #[tokio::test]
+    async fn test_verify_entry_stop_flags_terminated_program() {
+        let client = DapClient::new_with_transport(Box::new(create_empty_mock()), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "test.py".to_string(), client)
+            .await
+            .unwrap();
+
+        session
+            .state
+            .write()
+            .await
+            .set_state(DebugState::Terminated);
+
+        // Terminated is conclusive, so this must not wait out the window
+        let started = std::time::Instant::now();
+        assert!(!session.verify_entry_stop(Duration::from_secs(5)).await);
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        let state = session.get_full_state().await;
+        assert!(state.entry_stop_missed);
+        assert_eq!(
+            state.diagnostics(),
+            vec![crate::debug::state::STOP_ON_ENTRY_MISSED.to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_entry_stop_waits_through_continued() {
+        // The adapter reports the program running before its entry stop
+        let adapter = crate::dap::testing::ScriptedAdapter::new()
+            .emitting_on_launch("continued", json!({"threadId": 1}))
+            .emitting_after_launch(
+                "stopped",
+                json!({"reason": "entry", "threadId": 1}),
+                Duration::from_millis(300),
+            );
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/main.py".to_string(), client)
+            .await
+            .unwrap();
+
+        session
+            .initialize_and_launch(
+                "debugpy",
+                json!({"program": "/app/main.py", "stopOnEntry": true}),
+            )
+            .await
+            .unwrap();
+
+        let state = session.get_full_state().await;
+        assert!(!state.entry_stop_missed);
+        assert!(state.diagnostics().is_empty());
+        assert!(matches!(
+            state.state,
+            DebugState::Stopped { ref reason, .. } if reason == "entry"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_verify_entry_stop_flags_other_stop_reason() {
+        let client = DapClient::new_with_transport(Box::new(create_empty_mock()), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "test.py".to_string(), client)
+            .await
+            .unwrap();
+
+        session.state.write().await.set_state(DebugState::Stopped {
+            thread_id: 1,
+            reason: "breakpoint".to_string(),
+        });
+
+        assert!(!session.verify_entry_stop(Duration::from_secs(5)).await);
+        assert!(session.get_full_state().await.entry_stop_missed);
+    }
+
+    #[tokio::test]
+    async fn test_verify_entry_stop_flags_after_window() {
+        let client = DapClient::new_with_transport(Box::new(create_empty_mock()), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "test.py".to_string(), client)
+            .await
+            .unwrap();
+
+        session
+            .state
+            .write()
+            .await
+            .set_state(DebugState::Initializing);
+
+        assert!(!session.verify_entry_stop(Duration::from_millis(100)).await);
+        assert!(session.get_full_state().await.entry_stop_missed);
+    }
+
     #[tokio::test]
     async fn test_session_new() {
         let mock_transport = create_empty_mock();
@@ -0,0 +1,139 @@
+//! Sessions recorded in a state file so a restarted server can adopt them
+//!
+//! With `--resume <file>` the manager rewrites the file whenever a session
+//! starts or ends or its breakpoints change. On startup the server reads it
+//! back and reconnects to socket-mode adapters (rdbg, CodeLLDB) that are
+//! still listening. Adapters that ran over STDIO ended with the previous
+//! server; their sessions are reported as unrecoverable.
+
+use super::breakpoint_store::{temp_path, SavedBreakpoint};
+use crate::dap::socket_helper::AdapterEndpoint;
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// What a restarted server needs to adopt a session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistedSession {
+    pub id: String,
+    pub language: String,
+    pub program: String,
+    /// Port of a socket-mode adapter listening on TCP
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adapter_port: Option<u16>,
+    /// Path of a socket-mode adapter listening on a unix socket
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adapter_socket: Option<PathBuf>,
+    /// Configuration that attaches a new connection to the running program,
+    /// for adapters that support it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attach: Option<Value>,
+    #[serde(default)]
+    pub breakpoints: Vec<SavedBreakpoint>,
+}
+
+impl PersistedSession {
+    /// Where the session's adapter listens; `None` for adapters over STDIO
+    pub fn endpoint(&self) -> Option<AdapterEndpoint> {
+        #[cfg(unix)]
+        if let Some(path) = &self.adapter_socket {
+            return Some(AdapterEndpoint::Unix(path.clone()));
+        }
+        self.adapter_port.map(AdapterEndpoint::Tcp)
+    }
+}
+
+/// A session of the previous server that could not be adopted
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnrecoverableSession {
+    pub id: String,
+    pub language: String,
+    pub program: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StateFileContent {
+    #[serde(default)]
+    sessions: Vec<PersistedSession>,
+}
+
+/// The `--resume` state file
+#[derive(Debug, Clone)]
+pub struct SessionStateFile {
+    path: PathBuf,
+}
+
+impl SessionStateFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Sessions written by a previous server (none if there is no file yet)
+    pub fn load(&self) -> Result<Vec<PersistedSession>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&self.path)?;
+        let state: StateFileContent = serde_json::from_str(&content).map_err(|e| {
+            Error::InvalidRequest(format!(
+                "Invalid session state file {}: {}",
+                self.path.display(),
+                e
+            ))
+        })?;
+        Ok(state.sessions)
+    }
+
+    /// Replace the sessions in the file
+    pub fn write(&self, sessions: Vec<PersistedSession>) -> Result<()> {
+        // Write then rename so a crash never leaves a truncated file
+        let tmp = temp_path(&self.path);
+        let content = StateFileContent { sessions };
+        std::fs::write(&tmp, serde_json::to_string_pretty(&content)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_and_load() {
+        let dir = TempDir::new().unwrap();
+        let file = SessionStateFile::new(dir.path().join("sessions.json"));
+        assert!(file.load().unwrap().is_empty());
+
+        let session = PersistedSession {
+            id: "s1".to_string(),
+            language: "ruby".to_string(),
+            program: "/app/main.rb".to_string(),
+            adapter_port: Some(40123),
+            adapter_socket: None,
+            attach: Some(json!({"request": "attach"})),
+            breakpoints: vec![SavedBreakpoint {
+                source_path: "/app/main.rb".to_string(),
+                line: 4,
+                condition: None,
+                log_message: None,
+            }],
+        };
+        file.write(vec![session.clone()]).unwrap();
+        assert_eq!(file.load().unwrap(), vec![session.clone()]);
+        assert_eq!(session.endpoint(), Some(AdapterEndpoint::Tcp(40123)));
+
+        std::fs::write(file.path(), "not json").unwrap();
+        assert!(matches!(file.load(), Err(Error::InvalidRequest(_))));
+    }
+}
@@ -1,5 +1,13 @@
+use super::path_mapping::same_source_path;
+use super::recorder::{now_ms, SessionRecorder};
+use crate::adapters::source_analysis::EntryLine;
+use crate::dap::types::{Breakpoint as DapBreakpoint, DebuggeeProcess, Module, StackFrame};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::broadcast;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum DebugState {
@@ -13,19 +21,410 @@ pub enum DebugState {
     Failed { error: String },
 }
 
+impl DebugState {
+    /// State name as reported to MCP clients
+    pub fn name(&self) -> &'static str {
+        match self {
+            DebugState::NotStarted => "NotStarted",
+            DebugState::Initializing => "Initializing",
+            DebugState::Initialized => "Initialized",
+            DebugState::Launching => "Launching",
+            DebugState::Running => "Running",
+            DebugState::Stopped { .. } => "Stopped",
+            DebugState::Terminated => "Terminated",
+            DebugState::Failed { .. } => "Failed",
+        }
+    }
+
+    /// What goes with the state name: thread and reason of a stop, the
+    /// error of a failure, nothing otherwise
+    pub fn details(&self) -> Value {
+        match self {
+            DebugState::Stopped { thread_id, reason } => serde_json::json!({
+                "threadId": thread_id,
+                "reason": reason
+            }),
+            DebugState::Failed { error } => serde_json::json!({ "error": error }),
+            _ => serde_json::json!({}),
+        }
+    }
+
+    /// `{"state": name, "details": {...}}`, the shape every tool and
+    /// resource reports a session state in
+    pub fn to_json(&self) -> Value {
+        serde_json::json!({
+            "state": self.name(),
+            "details": self.details()
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Breakpoint {
     pub source_path: String,
     pub line: i32,
+    /// Column within the line, for column breakpoints
+    #[serde(default)]
+    pub column: Option<i32>,
+    pub id: Option<i32>,
+    pub verified: bool,
+    /// Only stop when this expression is true
+    #[serde(default)]
+    pub condition: Option<String>,
+    /// Only stop once the hit count satisfies this expression
+    #[serde(default)]
+    pub hit_condition: Option<String>,
+    /// Set for logpoints, which log this message instead of stopping
+    #[serde(default)]
+    pub log_message: Option<String>,
+    /// Why the adapter could not bind the breakpoint (unverified only)
+    #[serde(default)]
+    pub message: Option<String>,
+    /// Line the breakpoint was requested on, when the adapter bound it to
+    /// another one (`line`), e.g. from a blank line to the next statement
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requested_line: Option<i32>,
+}
+
+/// Everything the adapter reported about the most recent stop
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StopContext {
+    pub thread_id: i32,
+    pub reason: String,
+    pub description: Option<String>,
+    pub text: Option<String>,
+    pub hit_breakpoint_ids: Vec<i32>,
+    pub all_threads_stopped: bool,
+    /// Child session that stopped (Node.js multi-session debugging)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub child_session: Option<String>,
+}
+
+impl StopContext {
+    /// Parse the body of a 'stopped' event (thread 1 and reason "unknown" if absent)
+    pub fn from_event_body(body: &Value) -> Self {
+        let string = |key: &str| body.get(key).and_then(|v| v.as_str()).map(str::to_string);
+        Self {
+            thread_id: body
+                .get("threadId")
+                .and_then(|v| v.as_i64())
+                .map(|v| v as i32)
+                .unwrap_or(1),
+            reason: string("reason").unwrap_or_else(|| "unknown".to_string()),
+            description: string("description"),
+            text: string("text"),
+            hit_breakpoint_ids: body
+                .get("hitBreakpointIds")
+                .and_then(|v| v.as_array())
+                .map(|ids| {
+                    ids.iter()
+                        .filter_map(|id| id.as_i64())
+                        .map(|id| id as i32)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            all_threads_stopped: body
+                .get("allThreadsStopped")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            child_session: None,
+        }
+    }
+}
+
+/// A breakpoint on entry to a named function
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionBreakpointInfo {
+    pub name: String,
+    pub condition: Option<String>,
     pub id: Option<i32>,
     pub verified: bool,
 }
 
+/// A breakpoint on a value (watchpoint): stops when it is read or written
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataBreakpointInfo {
+    pub data_id: String,
+    /// What the adapter says the dataId refers to (e.g. "4 bytes at 0x...")
+    pub description: String,
+    /// "read", "write" or "readWrite"; the adapter's default when unset
+    pub access_type: Option<String>,
+    pub condition: Option<String>,
+    pub id: Option<i32>,
+    pub verified: bool,
+}
+
+/// Latest result of evaluating a watch expression
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchValue {
+    Value(String),
+    Error(String),
+}
+
+/// An expression evaluated in the top frame whenever the session stops
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Watch {
+    pub expression: String,
+    /// `None` until the watch has been evaluated at a stop
+    pub last: Option<WatchValue>,
+}
+
+/// A chunk of output reported by the adapter through an 'output' event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputEntry {
+    pub category: String,
+    pub output: String,
+}
+
+/// Maximum number of output entries retained per session (oldest are dropped)
+pub const MAX_OUTPUT_ENTRIES: usize = 1000;
+
+/// 'breakpoint' events kept for IDs that are not tracked (yet)
+const MAX_UNMATCHED_BREAKPOINT_EVENTS: usize = 256;
+
+/// Lines of stderr reported with a failing exit code
+const EXIT_STDERR_TAIL_LINES: usize = 20;
+
+/// Diagnostic reported when a stopOnEntry launch never reached its entry stop
+pub const STOP_ON_ENTRY_MISSED: &str = "stopOnEntry requested but program did not stop at entry";
+
+/// Diagnostic reported when the entry breakpoint's line had to be guessed
+pub const ENTRY_LINE_GUESSED: &str =
+    "could not detect the first executable line; the entry breakpoint was set on line 1 (pass entryLine to debugger_start if the program does not stop at entry)";
+
+/// Content of a source file when the session started using it
+///
+/// The running program keeps executing the code it loaded, so a file edited
+/// afterwards no longer matches what the debugger reports line numbers for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceSnapshot {
+    hash: u64,
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl SourceSnapshot {
+    /// Snapshot of the file at `path`; `None` if it cannot be read
+    pub fn of(path: &str) -> Option<Self> {
+        use std::hash::{Hash, Hasher};
+
+        let metadata = std::fs::metadata(path).ok().filter(|m| m.is_file())?;
+        let content = std::fs::read(path).ok()?;
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        content.hash(&mut hasher);
+        Some(Self {
+            hash: hasher.finish(),
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+
+    /// Whether the file at `path` no longer has this content (or is gone)
+    pub fn changed(&self, path: &str) -> bool {
+        // Same size and modification time: not worth re-reading
+        if let Ok(metadata) = std::fs::metadata(path) {
+            if metadata.len() == self.len && metadata.modified().ok() == self.modified {
+                return false;
+            }
+        }
+        Self::of(path).is_none_or(|current| current.hash != self.hash)
+    }
+}
+
+/// A session moved from one state to another (or stopped again)
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateChange {
+    pub session_id: String,
+    pub old_state: DebugState,
+    pub new_state: DebugState,
+    /// Set when the change is a stop
+    pub stop: Option<StopContext>,
+    /// Set when the server itself caused the change (e.g. "restarted: file
+    /// changed") rather than the debug adapter
+    pub reason: Option<String>,
+}
+
+/// Data behind the MCP resources that changed without a state transition
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResourceChange {
+    /// A session was created or removed (`debugger://sessions`)
+    Sessions,
+    /// Breakpoints of a session were set, verified, moved or removed
+    /// (`debugger://sessions/{id}`)
+    Breakpoints(String),
+}
+
+/// Where a session publishes its [`StateChange`]s
+#[derive(Debug, Clone)]
+pub struct StateNotifier {
+    session_id: String,
+    tx: broadcast::Sender<StateChange>,
+    resources: Option<broadcast::Sender<ResourceChange>>,
+}
+
+impl StateNotifier {
+    pub fn new(session_id: String, tx: broadcast::Sender<StateChange>) -> Self {
+        Self {
+            session_id,
+            tx,
+            resources: None,
+        }
+    }
+
+    /// Also publish breakpoint changes through `resources`
+    pub fn with_resource_changes(mut self, resources: broadcast::Sender<ResourceChange>) -> Self {
+        self.resources = Some(resources);
+        self
+    }
+
+    fn notify_breakpoints(&self) {
+        if let Some(resources) = &self.resources {
+            let _ = resources.send(ResourceChange::Breakpoints(self.session_id.clone()));
+        }
+    }
+
+    fn notify(&self, old_state: DebugState, new_state: DebugState, stop: Option<StopContext>) {
+        // No receivers just means nobody is listening
+        let _ = self.tx.send(StateChange {
+            session_id: self.session_id.clone(),
+            old_state,
+            new_state,
+            stop,
+            reason: None,
+        });
+    }
+}
+
+/// Frames fetched for one thread, with the generation that reported them
+#[derive(Debug, Clone)]
+struct ThreadStack {
+    generation: u64,
+    /// The top page, as last fetched from frame 0
+    frames: Vec<StackFrame>,
+    /// Ids of every frame fetched in this generation, including later pages
+    frame_ids: BTreeSet<i32>,
+    /// Frame ids of the thread's previous generation
+    previous: BTreeSet<i32>,
+}
+
+/// Whether a frame id can still be used, see [`SessionState::check_frame`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameCheck {
+    /// Reported by a stack of the current generation
+    Current,
+    /// Only reported before the program last resumed or stopped
+    Stale,
+    /// Not in the current stack of the requested thread
+    OtherThread,
+    /// Never reported by a stack fetched through the session
+    Unknown,
+}
+
+/// How long the program has been running vs stopped, and how often it stopped
+#[derive(Debug, Clone)]
+pub struct StateTiming {
+    /// When the current state was entered
+    since: Instant,
+    stops: usize,
+    stopped: Duration,
+    running: Duration,
+}
+
+impl StateTiming {
+    fn new() -> Self {
+        Self {
+            since: Instant::now(),
+            stops: 0,
+            stopped: Duration::ZERO,
+            running: Duration::ZERO,
+        }
+    }
+
+    /// Account the time spent in `left` (the state being left)
+    fn leave(&mut self, left: &DebugState) {
+        let elapsed = self.since.elapsed();
+        match left {
+            DebugState::Running => self.running += elapsed,
+            DebugState::Stopped { .. } => self.stopped += elapsed,
+            _ => {}
+        }
+        self.since = Instant::now();
+    }
+
+    /// Stops observed so far
+    pub fn stops(&self) -> usize {
+        self.stops
+    }
+
+    /// Total time stopped, including the current stop when `current` is Stopped
+    pub fn stopped(&self, current: &DebugState) -> Duration {
+        match current {
+            DebugState::Stopped { .. } => self.stopped + self.since.elapsed(),
+            _ => self.stopped,
+        }
+    }
+
+    /// Total time running, including the current run when `current` is Running
+    pub fn running(&self, current: &DebugState) -> Duration {
+        match current {
+            DebugState::Running => self.running + self.since.elapsed(),
+            _ => self.running,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SessionState {
     pub state: DebugState,
     pub breakpoints: HashMap<String, Vec<Breakpoint>>,
+    /// Function breakpoints, tracked separately since they have no source line
+    pub function_breakpoints: Vec<FunctionBreakpointInfo>,
+    /// Data breakpoints (watchpoints), in the order they were added
+    pub data_breakpoints: Vec<DataBreakpointInfo>,
     pub threads: Vec<i32>,
+    /// Context of the most recent stop; kept after the program resumes
+    pub last_stop: Option<StopContext>,
+    /// Set when stopOnEntry was requested but the program ran or exited instead
+    pub entry_stop_missed: bool,
+    /// Line the stopOnEntry workaround set its entry breakpoint on (Ruby and Node.js)
+    pub entry_line: Option<EntryLine>,
+    /// Program and logpoint output, bounded by [`MAX_OUTPUT_ENTRIES`]
+    pub output: VecDeque<OutputEntry>,
+    /// Watch expressions, in the order they were added
+    pub watches: Vec<Watch>,
+    /// Local paths of the sources the adapter has loaded, from 'loadedSource'
+    /// events and loadedSources requests
+    pub loaded_sources: BTreeSet<String>,
+    /// Modules reported by 'module' events, in load order
+    pub modules: Vec<Module>,
+    /// Version of the debug adapter (debugpy, rdbg, ...), when known
+    pub adapter_version: Option<String>,
+    /// The debuggee, once the adapter sent its 'process' event
+    pub process: Option<DebuggeeProcess>,
+    /// Exit code from the adapter's 'exited' event
+    pub exit_code: Option<i64>,
+    /// Time spent running and stopped
+    pub timing: StateTiming,
+    /// Source files as they were when the session started using them, by path
+    source_snapshots: HashMap<String, SourceSnapshot>,
+    /// 'breakpoint' events for IDs not recorded yet: the event can overtake
+    /// the setBreakpoints result that assigns the ID
+    unmatched_breakpoint_events: HashMap<i32, DapBreakpoint>,
+    /// Publishes state changes once the session is registered with a manager
+    notifier: Option<StateNotifier>,
+    /// Transcript of a recorded session
+    recorder: Option<Arc<SessionRecorder>>,
+    /// Stacks fetched per thread, each tagged with its generation
+    stacks: HashMap<i32, ThreadStack>,
+    /// Stop generation: advances on every state change and every stop, since
+    /// adapters only keep frame ids valid while the program stays paused
+    generation: u64,
+    /// When the session was created (ms since the Unix epoch)
+    pub created_at_ms: u64,
+    /// Last tool call on the session or state change (ms since the Unix epoch)
+    pub last_activity_ms: u64,
 }
 
 impl Default for SessionState {
@@ -39,32 +438,592 @@ impl SessionState {
         Self {
             state: DebugState::NotStarted,
             breakpoints: HashMap::new(),
+            function_breakpoints: Vec::new(),
+            data_breakpoints: Vec::new(),
             threads: Vec::new(),
+            last_stop: None,
+            entry_stop_missed: false,
+            entry_line: None,
+            output: VecDeque::new(),
+            watches: Vec::new(),
+            loaded_sources: BTreeSet::new(),
+            modules: Vec::new(),
+            adapter_version: None,
+            process: None,
+            exit_code: None,
+            timing: StateTiming::new(),
+            source_snapshots: HashMap::new(),
+            unmatched_breakpoint_events: HashMap::new(),
+            notifier: None,
+            recorder: None,
+            stacks: HashMap::new(),
+            generation: 0,
+            created_at_ms: now_ms(),
+            last_activity_ms: now_ms(),
         }
     }
 
+    /// Publish every subsequent state change through `notifier`
+    pub fn attach_notifier(&mut self, notifier: StateNotifier) {
+        self.notifier = Some(notifier);
+    }
+
+    /// Write every subsequent state change to `recorder`'s transcript
+    pub fn attach_recorder(&mut self, recorder: Arc<SessionRecorder>) {
+        self.recorder = Some(recorder);
+    }
+
+    pub fn recorder(&self) -> Option<&Arc<SessionRecorder>> {
+        self.recorder.as_ref()
+    }
+
     pub fn set_state(&mut self, state: DebugState) {
-        self.state = state;
+        let old_state = std::mem::replace(&mut self.state, state);
+        if old_state != self.state {
+            // A restarted program has not exited yet
+            if old_state == DebugState::Terminated {
+                self.exit_code = None;
+            }
+            self.generation += 1;
+            self.touch();
+            self.timing.leave(&old_state);
+            if let Some(recorder) = &self.recorder {
+                recorder.record_state_change(&old_state, &self.state, None);
+            }
+            if let Some(notifier) = &self.notifier {
+                notifier.notify(old_state, self.state.clone(), None);
+            }
+        }
+    }
+
+    /// Enter the Stopped state, remembering the full stop context
+    pub fn record_stop(&mut self, stop: StopContext) {
+        if stop.reason == "entry" {
+            // A late entry stop supersedes a missed-entry diagnostic
+            self.entry_stop_missed = false;
+        }
+        let old_state = std::mem::replace(
+            &mut self.state,
+            DebugState::Stopped {
+                thread_id: stop.thread_id,
+                reason: stop.reason.clone(),
+            },
+        );
+        self.timing.leave(&old_state);
+        self.timing.stops += 1;
+        self.generation += 1;
+        self.touch();
+        // Every stop is reported, even Stopped -> Stopped on another thread
+        if let Some(recorder) = &self.recorder {
+            recorder.record_state_change(&old_state, &self.state, Some(&stop));
+        }
+        if let Some(notifier) = &self.notifier {
+            notifier.notify(old_state, self.state.clone(), Some(stop.clone()));
+        }
+        self.last_stop = Some(stop);
+    }
+
+    /// Report an earlier stop as the current one again, without counting a
+    /// new stop (another child of a multi-session debuggee resumed while
+    /// this one stayed stopped)
+    pub fn restore_stop(&mut self, stop: StopContext) {
+        self.set_state(DebugState::Stopped {
+            thread_id: stop.thread_id,
+            reason: stop.reason.clone(),
+        });
+        self.last_stop = Some(stop);
+    }
+
+    /// Find a line breakpoint by its adapter-assigned ID
+    pub fn breakpoint_by_id(&self, id: i32) -> Option<&Breakpoint> {
+        self.breakpoints
+            .values()
+            .flatten()
+            .find(|bp| bp.id == Some(id))
+    }
+
+    /// Find a function breakpoint by its adapter-assigned ID
+    pub fn function_breakpoint_by_id(&self, id: i32) -> Option<&FunctionBreakpointInfo> {
+        self.function_breakpoints
+            .iter()
+            .find(|bp| bp.id == Some(id))
+    }
+
+    /// Find a data breakpoint by its adapter-assigned ID
+    pub fn data_breakpoint_by_id(&self, id: i32) -> Option<&DataBreakpointInfo> {
+        self.data_breakpoints.iter().find(|bp| bp.id == Some(id))
+    }
+
+    /// Record activity on the session now
+    pub fn touch(&mut self) {
+        self.last_activity_ms = now_ms();
+    }
+
+    /// Start a new generation without a state change, for a goto that moves
+    /// the stopped thread: every frame fetched so far is stale
+    pub fn invalidate_frames(&mut self) {
+        self.generation += 1;
+    }
+
+    /// Current stop generation; take it before requesting a stack and pass
+    /// it to [`Self::cache_stack`]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Remember frames of `thread_id` starting at `start_frame`, fetched in
+    /// `generation`, unless the program has moved on since
+    pub fn cache_stack(
+        &mut self,
+        generation: u64,
+        thread_id: i32,
+        start_frame: i32,
+        frames: Vec<StackFrame>,
+    ) {
+        if self.generation != generation || !matches!(self.state, DebugState::Stopped { .. }) {
+            return;
+        }
+        let stack = self.stacks.entry(thread_id).or_insert_with(|| ThreadStack {
+            generation,
+            frames: Vec::new(),
+            frame_ids: BTreeSet::new(),
+            previous: BTreeSet::new(),
+        });
+        if stack.generation != generation {
+            stack.generation = generation;
+            stack.frames.clear();
+            stack.previous = std::mem::take(&mut stack.frame_ids);
+        }
+        stack.frame_ids.extend(frames.iter().map(|frame| frame.id));
+        if start_frame == 0 {
+            stack.frames = frames;
+        }
+    }
+
+    /// Top of the stopped thread's stack at the current stop, if it was fetched
+    pub fn cached_stack(&self) -> Option<&[StackFrame]> {
+        let DebugState::Stopped { thread_id, .. } = &self.state else {
+            return None;
+        };
+        self.stacks
+            .get(thread_id)
+            .filter(|stack| stack.generation == self.generation && !stack.frames.is_empty())
+            .map(|stack| stack.frames.as_slice())
+    }
+
+    /// Whether `frame_id` (of `thread_id`, when given) still belongs to a
+    /// stack of the current generation
+    pub fn check_frame(&self, frame_id: i32, thread_id: Option<i32>) -> FrameCheck {
+        let mut stale = false;
+        for (thread, stack) in &self.stacks {
+            if thread_id.is_some_and(|t| t != *thread) {
+                continue;
+            }
+            let current = stack.generation == self.generation;
+            if current && stack.frame_ids.contains(&frame_id) {
+                return FrameCheck::Current;
+            }
+            stale |= stack.previous.contains(&frame_id)
+                || (!current && stack.frame_ids.contains(&frame_id));
+        }
+        let thread_known = thread_id
+            .and_then(|t| self.stacks.get(&t))
+            .is_some_and(|stack| stack.generation == self.generation);
+        if stale {
+            FrameCheck::Stale
+        } else if thread_known {
+            FrameCheck::OtherThread
+        } else {
+            FrameCheck::Unknown
+        }
     }
 
     pub fn add_breakpoint(&mut self, source: String, line: i32) {
-        let bp = Breakpoint {
-            source_path: source.clone(),
+        self.add_logpoint(source, line, None);
+    }
+
+    /// Track a breakpoint that logs `log_message` instead of stopping (if set)
+    pub fn add_logpoint(&mut self, source: String, line: i32, log_message: Option<String>) {
+        self.add_source_breakpoint(source, line, None, log_message);
+    }
+
+    /// Track a breakpoint with its optional condition and log message
+    pub fn add_source_breakpoint(
+        &mut self,
+        source: String,
+        line: i32,
+        condition: Option<String>,
+        log_message: Option<String>,
+    ) {
+        self.add_line_breakpoint(Breakpoint {
+            source_path: source,
             line,
+            column: None,
             id: None,
             verified: false,
+            condition,
+            hit_condition: None,
+            log_message,
+            message: None,
+            requested_line: None,
+        });
+    }
+
+    /// Track a fully described line breakpoint
+    pub fn add_line_breakpoint(&mut self, bp: Breakpoint) {
+        self.breakpoints
+            .entry(bp.source_path.clone())
+            .or_default()
+            .push(bp);
+        self.breakpoints_changed();
+    }
+
+    /// Replace every line breakpoint tracked for `source`
+    pub fn set_line_breakpoints(&mut self, source: String, breakpoints: Vec<Breakpoint>) {
+        let ids: Vec<i32> = breakpoints.iter().filter_map(|bp| bp.id).collect();
+        self.breakpoints.insert(source, breakpoints);
+        self.apply_unmatched_breakpoint_events(&ids);
+        self.breakpoints_changed();
+    }
+
+    /// Replace the function breakpoints (setFunctionBreakpoints replaces them all)
+    pub fn set_function_breakpoints(&mut self, breakpoints: Vec<FunctionBreakpointInfo>) {
+        let ids: Vec<i32> = breakpoints.iter().filter_map(|bp| bp.id).collect();
+        self.function_breakpoints = breakpoints;
+        self.apply_unmatched_breakpoint_events(&ids);
+        self.breakpoints_changed();
+    }
+
+    /// Replace the data breakpoints (setDataBreakpoints replaces them all)
+    pub fn set_data_breakpoints(&mut self, breakpoints: Vec<DataBreakpointInfo>) {
+        let ids: Vec<i32> = breakpoints.iter().filter_map(|bp| bp.id).collect();
+        self.data_breakpoints = breakpoints;
+        self.apply_unmatched_breakpoint_events(&ids);
+        self.breakpoints_changed();
+    }
+
+    fn breakpoints_changed(&self) {
+        if let Some(notifier) = &self.notifier {
+            notifier.notify_breakpoints();
+        }
+    }
+
+    /// Apply a 'breakpoint' event: the adapter bound, moved or removed a
+    /// breakpoint after answering the request that set it
+    ///
+    /// Breakpoints are matched by ID; events for IDs that are not known yet
+    /// are kept and applied once a result with that ID is recorded. Returns
+    /// whether a tracked breakpoint was updated.
+    pub fn apply_breakpoint_event(&mut self, reason: &str, event: &DapBreakpoint) -> bool {
+        let updated = self.update_from_breakpoint_event(reason, event);
+        if updated {
+            self.breakpoints_changed();
+        }
+        updated
+    }
+
+    fn update_from_breakpoint_event(&mut self, reason: &str, event: &DapBreakpoint) -> bool {
+        let Some(id) = event.id else {
+            return false;
+        };
+
+        if reason == "removed" {
+            self.unmatched_breakpoint_events.remove(&id);
+            let mut removed = false;
+            for bps in self.breakpoints.values_mut() {
+                let tracked = bps.len();
+                bps.retain(|bp| bp.id != Some(id));
+                removed |= bps.len() < tracked;
+            }
+            let tracked = self.function_breakpoints.len() + self.data_breakpoints.len();
+            self.function_breakpoints.retain(|bp| bp.id != Some(id));
+            self.data_breakpoints.retain(|bp| bp.id != Some(id));
+            return removed
+                || self.function_breakpoints.len() + self.data_breakpoints.len() < tracked;
+        }
+
+        let message = (!event.verified).then(|| event.message.clone()).flatten();
+        if let Some(bp) = self
+            .breakpoints
+            .values_mut()
+            .flatten()
+            .find(|bp| bp.id == Some(id))
+        {
+            bp.verified = event.verified;
+            if let Some(line) = event.line.filter(|line| *line != bp.line) {
+                bp.requested_line.get_or_insert(bp.line);
+                bp.line = line;
+            }
+            bp.column = event.column.or(bp.column);
+            bp.message = message;
+            return true;
+        }
+        if let Some(bp) = self
+            .function_breakpoints
+            .iter_mut()
+            .find(|bp| bp.id == Some(id))
+        {
+            bp.verified = event.verified;
+            return true;
+        }
+        if let Some(bp) = self
+            .data_breakpoints
+            .iter_mut()
+            .find(|bp| bp.id == Some(id))
+        {
+            bp.verified = event.verified;
+            return true;
+        }
+
+        // Adapters only ever report a handful of breakpoints; don't let
+        // events for breakpoints set elsewhere pile up
+        if self.unmatched_breakpoint_events.len() < MAX_UNMATCHED_BREAKPOINT_EVENTS {
+            self.unmatched_breakpoint_events.insert(id, event.clone());
+        }
+        false
+    }
+
+    fn apply_unmatched_breakpoint_events(&mut self, ids: &[i32]) {
+        for id in ids {
+            if let Some(event) = self.unmatched_breakpoint_events.remove(id) {
+                self.update_from_breakpoint_event("changed", &event);
+            }
+        }
+    }
+
+    /// Add a watch expression; returns `false` if it is already watched
+    pub fn add_watch(&mut self, expression: String) -> bool {
+        if self.watches.iter().any(|w| w.expression == expression) {
+            return false;
+        }
+        self.watches.push(Watch {
+            expression,
+            last: None,
+        });
+        true
+    }
+
+    /// Remove a watch expression; returns `false` if it was not watched
+    pub fn remove_watch(&mut self, expression: &str) -> bool {
+        let before = self.watches.len();
+        self.watches.retain(|w| w.expression != expression);
+        self.watches.len() != before
+    }
+
+    /// Store fresh watch results, ignoring watches removed in the meantime
+    pub fn record_watch_results(&mut self, results: Vec<(String, WatchValue)>) {
+        for (expression, value) in results {
+            if let Some(watch) = self.watches.iter_mut().find(|w| w.expression == expression) {
+                watch.last = Some(value);
+            }
+        }
+    }
+
+    /// Append adapter output, dropping the oldest entries beyond the limit
+    pub fn push_output(&mut self, category: String, output: String) {
+        if self.output.len() == MAX_OUTPUT_ENTRIES {
+            self.output.pop_front();
+        }
+        self.output.push_back(OutputEntry { category, output });
+    }
+
+    /// Remember the content of `path` the first time the session uses it
+    /// (launching the program, setting a breakpoint in it)
+    pub fn track_source(&mut self, path: &str) {
+        if self.source_snapshots.contains_key(path) {
+            return;
+        }
+        if let Some(snapshot) = SourceSnapshot::of(path) {
+            self.source_snapshots.insert(path.to_string(), snapshot);
+        }
+    }
+
+    /// Number of source files tracked by [`SessionState::track_source`]
+    pub fn tracked_source_count(&self) -> usize {
+        self.source_snapshots.len()
+    }
+
+    /// Tracked files whose content changed on disk since they were tracked,
+    /// sorted by path
+    pub fn changed_sources(&self) -> Vec<String> {
+        let mut changed: Vec<String> = self
+            .source_snapshots
+            .iter()
+            .filter(|(path, snapshot)| snapshot.changed(path))
+            .map(|(path, _)| path.clone())
+            .collect();
+        changed.sort();
+        changed
+    }
+
+    /// Apply a 'loadedSource' event ("new", "changed" or "removed")
+    pub fn record_loaded_source(&mut self, reason: &str, path: String) {
+        if reason == "removed" {
+            self.loaded_sources.remove(&path);
+        } else {
+            self.loaded_sources.insert(path);
+        }
+    }
+
+    /// Apply a 'module' event ("new", "changed" or "removed")
+    pub fn record_module(&mut self, reason: &str, module: Module) {
+        let existing = self.modules.iter().position(|m| m.id == module.id);
+        match (reason, existing) {
+            ("removed", Some(index)) => {
+                self.modules.remove(index);
+            }
+            ("removed", None) => {}
+            (_, Some(index)) => self.modules[index] = module,
+            (_, None) => self.modules.push(module),
+        }
+    }
+
+    /// Apply a 'process' event
+    ///
+    /// Only the first one is kept: it names the program the session
+    /// launched, while later ones come from subprocesses (debugpy's
+    /// subProcess) that report their own.
+    pub fn record_process(&mut self, process: DebuggeeProcess) -> bool {
+        if self.process.is_some() {
+            return false;
+        }
+        self.process = Some(process);
+        true
+    }
+
+    /// Apply an 'exited' event's exit code
+    ///
+    /// With several processes (Node.js child sessions) a failure is kept:
+    /// the program failed if any part of it did.
+    pub fn record_exit_code(&mut self, exit_code: i64) {
+        if self.exit_code.is_none_or(|code| code == 0) {
+            self.exit_code = Some(exit_code);
+        }
+    }
+
+    /// How the program ended: `exitCode` and a `reason` saying whether it
+    /// failed, plus the last lines it wrote to stderr when it did
+    pub fn exit_details(&self) -> Value {
+        let Some(exit_code) = self.exit_code else {
+            return serde_json::json!({ "reason": "Program exited" });
         };
+        let mut details = serde_json::json!({
+            "exitCode": exit_code,
+            "reason": format!("Program exited with code {}", exit_code),
+        });
+        if exit_code != 0 {
+            let stderr = self.stderr_tail(EXIT_STDERR_TAIL_LINES);
+            if !stderr.is_empty() {
+                details["stderrTail"] = serde_json::json!(stderr);
+            }
+        }
+        details
+    }
 
-        self.breakpoints.entry(source).or_default().push(bp);
+    /// The last `lines` lines of the program's stderr output
+    pub fn stderr_tail(&self, lines: usize) -> Vec<String> {
+        let stderr: String = self
+            .output
+            .iter()
+            .filter(|entry| entry.category == "stderr")
+            .map(|entry| entry.output.as_str())
+            .collect();
+        let all: Vec<&str> = stderr.lines().collect();
+        all[all.len().saturating_sub(lines)..]
+            .iter()
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    /// Whether the adapter loaded `path` (see [`same_source_path`])
+    pub fn is_loaded_source(&self, path: &str) -> bool {
+        self.loaded_sources.contains(path)
+            || self
+                .loaded_sources
+                .iter()
+                .any(|loaded| same_source_path(loaded, path))
+    }
+
+    /// The loaded source most likely meant by `path` when `path` itself was
+    /// not loaded: a file of the same name, sharing the most trailing
+    /// directories with `path`
+    ///
+    /// An unverified breakpoint usually means the adapter loaded the file
+    /// from somewhere else (a symlink, a container mount, an installed copy).
+    pub fn closest_loaded_source(&self, path: &str) -> Option<String> {
+        if self.is_loaded_source(path) {
+            return None;
+        }
+        let requested: Vec<&str> = path.rsplit(['/', '\\']).collect();
+        self.loaded_sources
+            .iter()
+            .map(|loaded| {
+                let shared = loaded
+                    .rsplit(['/', '\\'])
+                    .zip(&requested)
+                    .take_while(|(a, b)| same_source_path(a, b))
+                    .count();
+                (shared, loaded)
+            })
+            .filter(|(shared, _)| *shared > 0)
+            // Most shared components; the first path on ties
+            .max_by(|(a, path_a), (b, path_b)| a.cmp(b).then(path_b.cmp(path_a)))
+            .map(|(_, loaded)| loaded.clone())
     }
 
     pub fn update_breakpoint(&mut self, source: &str, line: i32, id: i32, verified: bool) {
+        self.record_breakpoint_result(source, line, Some(id), verified, None);
+    }
+
+    /// Record the adapter's verdict for the breakpoint requested at `line`
+    ///
+    /// Unlike [`SessionState::update_breakpoint`] this also records results for
+    /// adapters that do not assign breakpoint IDs, and the adapter's
+    /// `message` for breakpoints it could not bind.
+    pub fn record_breakpoint_result(
+        &mut self,
+        source: &str,
+        line: i32,
+        id: Option<i32>,
+        verified: bool,
+        message: Option<String>,
+    ) {
         if let Some(bps) = self.breakpoints.get_mut(source) {
             if let Some(bp) = bps.iter_mut().find(|b| b.line == line) {
-                bp.id = Some(id);
+                bp.id = id.or(bp.id);
                 bp.verified = verified;
+                bp.message = if verified { None } else { message };
             }
         }
+        if let Some(id) = id {
+            self.apply_unmatched_breakpoint_events(&[id]);
+        }
+        self.breakpoints_changed();
+    }
+
+    /// Record that the adapter bound the breakpoint requested at `requested`
+    /// to the `actual` line
+    pub fn record_breakpoint_line(&mut self, source: &str, requested: i32, actual: i32) {
+        if requested == actual {
+            return;
+        }
+        let Some(bp) = self
+            .breakpoints
+            .get_mut(source)
+            .and_then(|bps| bps.iter_mut().find(|b| b.line == requested))
+        else {
+            return;
+        };
+        bp.requested_line.get_or_insert(requested);
+        bp.line = actual;
+        self.breakpoints_changed();
+    }
+
+    /// Line the adapter moved the breakpoint requested at `line` to, if it did
+    pub fn adjusted_line(&self, source: &str, line: i32, column: Option<i32>) -> Option<i32> {
+        self.breakpoints.get(source)?.iter().find_map(|bp| {
+            (bp.requested_line == Some(line) && bp.column == column).then_some(bp.line)
+        })
     }
 
     pub fn get_breakpoints(&self, source: &str) -> Vec<Breakpoint> {
@@ -76,6 +1035,18 @@ impl SessionState {
             self.threads.push(thread_id);
         }
     }
+
+    /// Human-readable diagnostics about how the session deviated from what was requested
+    pub fn diagnostics(&self) -> Vec<String> {
+        let mut diagnostics = Vec::new();
+        if self.entry_stop_missed {
+            diagnostics.push(STOP_ON_ENTRY_MISSED.to_string());
+        }
+        if self.entry_line.as_ref().is_some_and(EntryLine::is_fallback) {
+            diagnostics.push(ENTRY_LINE_GUESSED.to_string());
+        }
+        diagnostics
+    }
 }
 
 #[cfg(test)]
@@ -88,6 +1059,49 @@ mod tests {
         assert!(matches!(state.state, DebugState::NotStarted));
         assert!(state.breakpoints.is_empty());
         assert!(state.threads.is_empty());
+        assert!(state.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn test_entry_stop_missed_diagnostic() {
+        let mut state = SessionState::new();
+        state.entry_stop_missed = true;
+        assert_eq!(state.diagnostics(), vec![STOP_ON_ENTRY_MISSED.to_string()]);
+    }
+
+    #[test]
+    fn test_exit_details() {
+        let mut state = SessionState::new();
+        assert_eq!(state.exit_details()["reason"], "Program exited");
+        assert!(state.exit_details().get("exitCode").is_none());
+
+        state.record_exit_code(0);
+        let details = state.exit_details();
+        assert_eq!(details["exitCode"], 0);
+        assert_eq!(details["reason"], "Program exited with code 0");
+        assert!(details.get("stderrTail").is_none());
+
+        // A failing child outweighs a clean parent exit
+        for (category, output) in [("stdout", "ok\n"), ("stderr", "boom\nat line 3\n")] {
+            state.output.push_back(OutputEntry {
+                category: category.to_string(),
+                output: output.to_string(),
+            });
+        }
+        state.record_exit_code(3);
+        state.record_exit_code(0);
+        let details = state.exit_details();
+        assert_eq!(details["exitCode"], 3);
+        assert_eq!(details["reason"], "Program exited with code 3");
+        assert_eq!(
+            details["stderrTail"],
+            serde_json::json!(["boom", "at line 3"])
+        );
+
+        // Restarting clears it
+        state.set_state(DebugState::Terminated);
+        state.set_state(DebugState::Running);
+        assert_eq!(state.exit_code, None);
     }
 
     #[test]
@@ -97,6 +1111,107 @@ mod tests {
         assert!(matches!(state.state, DebugState::Running));
     }
 
+    #[test]
+    fn test_state_to_json() {
+        assert_eq!(
+            DebugState::Running.to_json(),
+            serde_json::json!({"state": "Running", "details": {}})
+        );
+        assert_eq!(
+            DebugState::Stopped {
+                thread_id: 1,
+                reason: "breakpoint".to_string()
+            }
+            .to_json(),
+            serde_json::json!({
+                "state": "Stopped",
+                "details": {"threadId": 1, "reason": "breakpoint"}
+            })
+        );
+        assert_eq!(
+            DebugState::Failed {
+                error: "boom".to_string()
+            }
+            .to_json(),
+            serde_json::json!({"state": "Failed", "details": {"error": "boom"}})
+        );
+    }
+
+    #[test]
+    fn test_state_changes_touch_session() {
+        let mut state = SessionState::new();
+        assert!(state.created_at_ms > 0);
+        state.last_activity_ms = 0;
+        state.set_state(DebugState::Running);
+        assert!(state.last_activity_ms >= state.created_at_ms);
+    }
+
+    #[test]
+    fn test_frame_ids_go_stale_on_resume() {
+        let frames = |ids: &[i32]| -> Vec<StackFrame> {
+            ids.iter()
+                .map(|&id| {
+                    serde_json::from_value(
+                        serde_json::json!({"id": id, "name": "f", "line": 1, "column": 0}),
+                    )
+                    .unwrap()
+                })
+                .collect()
+        };
+        let stop = |thread_id: i32| {
+            StopContext::from_event_body(
+                &serde_json::json!({"reason": "breakpoint", "threadId": thread_id}),
+            )
+        };
+        let mut state = SessionState::new();
+        state.record_stop(stop(1));
+        let generation = state.generation();
+        state.cache_stack(generation, 1, 0, frames(&[1, 2]));
+        state.cache_stack(generation, 2, 0, frames(&[7]));
+        assert_eq!(state.check_frame(2, None), FrameCheck::Current);
+        assert_eq!(state.check_frame(2, Some(1)), FrameCheck::Current);
+        assert_eq!(state.check_frame(7, Some(1)), FrameCheck::OtherThread);
+        assert_eq!(state.check_frame(9, None), FrameCheck::Unknown);
+        assert_eq!(state.cached_stack().map(<[_]>::len), Some(2));
+
+        // Continue, then stop again: nothing fetched before is valid
+        state.set_state(DebugState::Running);
+        assert_eq!(state.check_frame(2, None), FrameCheck::Stale);
+        assert!(state.cached_stack().is_none());
+        state.record_stop(stop(1));
+        assert_eq!(state.check_frame(7, Some(2)), FrameCheck::Stale);
+
+        // A step refetching only the top frame keeps the callers stale
+        let generation = state.generation();
+        state.cache_stack(generation, 1, 0, frames(&[1]));
+        assert_eq!(state.check_frame(1, None), FrameCheck::Current);
+        assert_eq!(state.check_frame(2, None), FrameCheck::Stale);
+
+        // Frames fetched by a request that raced a resume are not cached
+        state.set_state(DebugState::Running);
+        state.cache_stack(generation, 1, 0, frames(&[3]));
+        assert_eq!(state.check_frame(3, None), FrameCheck::Unknown);
+    }
+
+    #[test]
+    fn test_timing_tracks_stops_and_running_time() {
+        let mut state = SessionState::new();
+        state.set_state(DebugState::Running);
+        std::thread::sleep(Duration::from_millis(20));
+        state.record_stop(StopContext::from_event_body(
+            &serde_json::json!({"reason": "breakpoint", "threadId": 1}),
+        ));
+        std::thread::sleep(Duration::from_millis(20));
+        state.record_stop(StopContext::from_event_body(
+            &serde_json::json!({"reason": "step", "threadId": 1}),
+        ));
+        state.set_state(DebugState::Running);
+
+        assert_eq!(state.timing.stops(), 2);
+        assert!(state.timing.running(&state.state) >= Duration::from_millis(20));
+        assert!(state.timing.stopped(&state.state) >= Duration::from_millis(20));
+    }
+
     #[test]
     fn test_add_breakpoint() {
         let mut state = SessionState::new();
@@ -119,6 +1234,291 @@ mod tests {
         assert!(bps[0].verified);
     }
 
+    #[test]
+    fn test_add_logpoint() {
+        let mut state = SessionState::new();
+        state.add_logpoint("test.py".to_string(), 7, Some("n = {n}".to_string()));
+
+        let bps = state.get_breakpoints("test.py");
+        assert_eq!(bps[0].log_message, Some("n = {n}".to_string()));
+    }
+
+    #[test]
+    fn test_push_output_is_bounded() {
+        let mut state = SessionState::new();
+        for i in 0..MAX_OUTPUT_ENTRIES + 5 {
+            state.push_output("stdout".to_string(), format!("line {}\n", i));
+        }
+
+        assert_eq!(state.output.len(), MAX_OUTPUT_ENTRIES);
+        assert_eq!(state.output.front().unwrap().output, "line 5\n");
+    }
+
+    #[test]
+    fn test_add_and_remove_watch() {
+        let mut state = SessionState::new();
+        assert!(state.add_watch("n".to_string()));
+        assert!(!state.add_watch("n".to_string()));
+        assert!(state.add_watch("len(results)".to_string()));
+        assert_eq!(state.watches.len(), 2);
+
+        assert!(state.remove_watch("n"));
+        assert!(!state.remove_watch("n"));
+        assert_eq!(state.watches[0].expression, "len(results)");
+    }
+
+    #[test]
+    fn test_record_watch_results_skips_removed_watches() {
+        let mut state = SessionState::new();
+        state.add_watch("n".to_string());
+        state.record_watch_results(vec![
+            ("n".to_string(), WatchValue::Value("3".to_string())),
+            ("gone".to_string(), WatchValue::Error("removed".to_string())),
+        ]);
+
+        assert_eq!(state.watches.len(), 1);
+        assert_eq!(
+            state.watches[0].last,
+            Some(WatchValue::Value("3".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_stop_context_from_event_body() {
+        let stop = StopContext::from_event_body(&serde_json::json!({
+            "reason": "breakpoint",
+            "threadId": 3,
+            "description": "Paused on breakpoint",
+            "hitBreakpointIds": [2, 5],
+            "allThreadsStopped": true
+        }));
+
+        assert_eq!(stop.thread_id, 3);
+        assert_eq!(stop.reason, "breakpoint");
+        assert_eq!(stop.description.as_deref(), Some("Paused on breakpoint"));
+        assert!(stop.text.is_none());
+        assert_eq!(stop.hit_breakpoint_ids, vec![2, 5]);
+        assert!(stop.all_threads_stopped);
+
+        let minimal = StopContext::from_event_body(&serde_json::json!({}));
+        assert_eq!(minimal.thread_id, 1);
+        assert_eq!(minimal.reason, "unknown");
+        assert!(minimal.hit_breakpoint_ids.is_empty());
+    }
+
+    #[test]
+    fn test_state_changes_are_published() {
+        let (tx, mut rx) = broadcast::channel(16);
+        let mut state = SessionState::new();
+
+        // Nothing is published before a notifier is attached
+        state.set_state(DebugState::Initializing);
+        state.attach_notifier(StateNotifier::new("s1".to_string(), tx));
+
+        state.set_state(DebugState::Running);
+        state.set_state(DebugState::Running); // unchanged: not published
+        state.record_stop(StopContext::from_event_body(&serde_json::json!({
+            "reason": "breakpoint",
+            "threadId": 3,
+            "hitBreakpointIds": [1]
+        })));
+
+        let running = rx.try_recv().unwrap();
+        assert_eq!(running.session_id, "s1");
+        assert_eq!(running.old_state, DebugState::Initializing);
+        assert_eq!(running.new_state, DebugState::Running);
+        assert!(running.stop.is_none());
+
+        let stopped = rx.try_recv().unwrap();
+        assert_eq!(stopped.old_state, DebugState::Running);
+        assert!(matches!(
+            stopped.new_state,
+            DebugState::Stopped { thread_id: 3, .. }
+        ));
+        assert_eq!(stopped.stop.unwrap().hit_breakpoint_ids, vec![1]);
+
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_record_stop_and_breakpoint_lookup() {
+        let mut state = SessionState::new();
+        state.add_breakpoint("a.py".to_string(), 10);
+        state.add_breakpoint("a.py".to_string(), 11);
+        state.update_breakpoint("a.py", 10, 1, true);
+        state.update_breakpoint("a.py", 11, 2, true);
+        state.entry_stop_missed = true;
+
+        state.record_stop(StopContext::from_event_body(&serde_json::json!({
+            "reason": "entry",
+            "threadId": 1
+        })));
+        assert!(!state.entry_stop_missed);
+        assert!(matches!(state.state, DebugState::Stopped { .. }));
+
+        assert_eq!(state.breakpoint_by_id(2).unwrap().line, 11);
+        assert!(state.breakpoint_by_id(9).is_none());
+        assert!(state.function_breakpoint_by_id(1).is_none());
+    }
+
+    #[test]
+    fn test_record_breakpoint_result_without_id() {
+        let mut state = SessionState::new();
+        state.add_breakpoint("test.rb".to_string(), 5);
+        state.record_breakpoint_result("test.rb", 5, None, true, None);
+
+        let bps = state.get_breakpoints("test.rb");
+        assert_eq!(bps[0].id, None);
+        assert!(bps[0].verified);
+    }
+
+    #[test]
+    fn test_record_breakpoint_line() {
+        let mut state = SessionState::new();
+        state.add_breakpoint("app.py".to_string(), 2);
+        state.add_breakpoint("app.py".to_string(), 5);
+
+        state.record_breakpoint_line("app.py", 2, 4);
+        state.record_breakpoint_line("app.py", 5, 5);
+        let bps = state.get_breakpoints("app.py");
+        assert_eq!((bps[0].line, bps[0].requested_line), (4, Some(2)));
+        assert_eq!((bps[1].line, bps[1].requested_line), (5, None));
+        assert_eq!(state.adjusted_line("app.py", 2, None), Some(4));
+        assert_eq!(state.adjusted_line("app.py", 5, None), None);
+
+        // Moved again: still requested on line 2
+        state.record_breakpoint_line("app.py", 4, 6);
+        let bp = &state.get_breakpoints("app.py")[0];
+        assert_eq!((bp.line, bp.requested_line), (6, Some(2)));
+    }
+
+    #[test]
+    fn test_breakpoint_events_update_tracked_breakpoints() {
+        let event =
+            |id: i32, verified: bool, line: Option<i32>, message: Option<&str>| DapBreakpoint {
+                id: Some(id),
+                verified,
+                message: message.map(str::to_string),
+                source: None,
+                line,
+                column: None,
+            };
+        let mut state = SessionState::new();
+        state.add_breakpoint("lazy.js".to_string(), 10);
+        state.record_breakpoint_result(
+            "lazy.js",
+            10,
+            Some(1),
+            false,
+            Some("Unbound breakpoint".to_string()),
+        );
+        assert_eq!(
+            state.get_breakpoints("lazy.js")[0].message.as_deref(),
+            Some("Unbound breakpoint")
+        );
+
+        // Bound once the module is required, on the next statement
+        assert!(state.apply_breakpoint_event("changed", &event(1, true, Some(11), None)));
+        let bp = &state.get_breakpoints("lazy.js")[0];
+        assert!(bp.verified);
+        assert_eq!(bp.line, 11);
+        assert_eq!(bp.requested_line, Some(10));
+        assert_eq!(bp.message, None);
+        assert_eq!(state.adjusted_line("lazy.js", 10, None), Some(11));
+
+        // An event overtaking the result that assigns its ID
+        state.add_breakpoint("other.js".to_string(), 3);
+        assert!(!state.apply_breakpoint_event("changed", &event(2, true, None, None)));
+        state.record_breakpoint_result("other.js", 3, Some(2), false, None);
+        assert!(state.get_breakpoints("other.js")[0].verified);
+
+        assert!(state.apply_breakpoint_event("removed", &event(1, false, None, None)));
+        assert!(state.get_breakpoints("lazy.js").is_empty());
+
+        // A watched local goes out of scope
+        state.set_data_breakpoints(vec![DataBreakpointInfo {
+            data_id: "0x7ffe0010/4".to_string(),
+            description: "4 bytes at 0x7ffe0010".to_string(),
+            access_type: Some("write".to_string()),
+            condition: None,
+            id: Some(1000),
+            verified: true,
+        }]);
+        assert_eq!(
+            state.data_breakpoint_by_id(1000).unwrap().data_id,
+            "0x7ffe0010/4"
+        );
+        assert!(state.apply_breakpoint_event("removed", &event(1000, false, None, None)));
+        assert!(state.data_breakpoints.is_empty());
+    }
+
+    #[test]
+    fn test_changed_sources_detects_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        let program = dir.path().join("app.py");
+        let module = dir.path().join("util.py");
+        std::fs::write(&program, "print('v1')\n").unwrap();
+        std::fs::write(&module, "x = 1\n").unwrap();
+        let program = program.to_string_lossy().to_string();
+        let module = module.to_string_lossy().to_string();
+
+        let mut state = SessionState::new();
+        state.track_source(&program);
+        state.track_source(&module);
+        state.track_source("/definitely/not/here.py");
+        assert_eq!(state.tracked_source_count(), 2);
+        assert!(state.changed_sources().is_empty());
+
+        // Edited mid-session: the program still runs the old code
+        std::fs::write(&program, "print('v2, with more lines')\nprint('!')\n").unwrap();
+        assert_eq!(state.changed_sources(), vec![program.clone()]);
+        // Tracking again keeps the original snapshot
+        state.track_source(&program);
+        assert_eq!(state.changed_sources(), vec![program.clone()]);
+
+        std::fs::remove_file(&module).unwrap();
+        assert_eq!(state.changed_sources(), vec![program, module]);
+    }
+
+    #[test]
+    fn test_loaded_sources_and_closest_match() {
+        let mut state = SessionState::new();
+        state.record_loaded_source("new", "/srv/app/src/models/user.py".to_string());
+        state.record_loaded_source("new", "/srv/app/tests/user.py".to_string());
+        state.record_loaded_source("new", "/usr/lib/python3/json/decoder.py".to_string());
+        state.record_loaded_source("new", "/tmp/old.py".to_string());
+        state.record_loaded_source("removed", "/tmp/old.py".to_string());
+        assert_eq!(state.loaded_sources.len(), 3);
+
+        assert_eq!(
+            state.closest_loaded_source("/home/dev/app/src/models/user.py"),
+            Some("/srv/app/src/models/user.py".to_string())
+        );
+        // Nothing to suggest for the loaded path itself or unknown files
+        assert_eq!(state.closest_loaded_source("/srv/app/tests/user.py"), None);
+        assert_eq!(state.closest_loaded_source("/home/dev/app/main.py"), None);
+    }
+
+    #[test]
+    fn test_record_module_events() {
+        let module = |id: i64, name: &str| Module {
+            id: serde_json::json!(id),
+            name: name.to_string(),
+            path: None,
+            version: None,
+            symbol_status: None,
+            symbol_file_path: None,
+            address_range: None,
+        };
+        let mut state = SessionState::new();
+        state.record_module("new", module(1, "app"));
+        state.record_module("new", module(2, "libc.so.6"));
+        state.record_module("changed", module(1, "app (reloaded)"));
+        state.record_module("removed", module(2, "libc.so.6"));
+
+        assert_eq!(state.modules, vec![module(1, "app (reloaded)")]);
+    }
+
     #[test]
     fn test_add_thread() {
         let mut state = SessionState::new();
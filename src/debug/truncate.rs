@@ -0,0 +1,138 @@
+//! Size limits for text returned to the MCP client (`maxLength`)
+//!
+//! Evaluating a large list or a DataFrame, or reading the output of a chatty
+//! program, can produce megabytes of text that swamp the MCP client and the
+//! model's context. Tools cut such text to `maxLength` bytes (at a character
+//! boundary) and mark the result with `"truncated": true` and its
+//! `originalLength`. Structured values can still be browsed in full, page by
+//! page, with debugger_get_variables.
+
+use crate::{Error, Result};
+use serde_json::{json, Value};
+
+/// Bytes of a result returned when the call does not pass `maxLength`
+pub const DEFAULT_MAX_LENGTH: usize = 4096;
+
+/// `max_length`, or [`DEFAULT_MAX_LENGTH`] when not given
+pub fn resolve_max_length(max_length: Option<usize>) -> Result<usize> {
+    match max_length {
+        Some(0) => Err(Error::InvalidRequest(
+            "maxLength must be at least 1".to_string(),
+        )),
+        Some(max_length) => Ok(max_length),
+        None => Ok(DEFAULT_MAX_LENGTH),
+    }
+}
+
+/// The first `max_length` bytes of `text` (fewer at a character boundary);
+/// `None` when it fits
+pub fn truncate_head(text: &str, max_length: usize) -> Option<&str> {
+    if text.len() <= max_length {
+        return None;
+    }
+    let mut end = max_length;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    Some(&text[..end])
+}
+
+/// The last `max_length` bytes of `text` (fewer at a character boundary);
+/// `None` when it fits
+pub fn truncate_tail(text: &str, max_length: usize) -> Option<&str> {
+    if text.len() <= max_length {
+        return None;
+    }
+    let mut start = text.len() - max_length;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    Some(&text[start..])
+}
+
+/// Cut the string `value[field]` to `max_length` bytes, marking `value` with
+/// `truncated` and `originalLength` when it was cut
+pub fn truncate_field(value: &mut Value, field: &str, max_length: usize) {
+    let Some(text) = value.get(field).and_then(Value::as_str) else {
+        return;
+    };
+    let Some(head) = truncate_head(text, max_length) else {
+        return;
+    };
+    let original_length = text.len();
+    value[field] = json!(head);
+    value["truncated"] = json!(true);
+    value["originalLength"] = json!(original_length);
+}
+
+/// The newest of `texts` (oldest first) that fit in `max_length` bytes
+/// together, oldest first
+///
+/// Program output is read for what happened last, so older entries are
+/// dropped first and the oldest entry kept may be cut to its tail.
+pub fn newest_within<'a>(texts: &[&'a str], max_length: usize) -> Vec<&'a str> {
+    let mut budget = max_length;
+    let mut kept = Vec::new();
+    for text in texts.iter().rev() {
+        if budget == 0 {
+            break;
+        }
+        match truncate_tail(text, budget) {
+            None => {
+                budget -= text.len();
+                kept.push(*text);
+            }
+            Some(tail) => {
+                budget = 0;
+                kept.push(tail);
+            }
+        }
+    }
+    kept.reverse();
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_at_char_boundaries() {
+        assert_eq!(truncate_head("short", 10), None);
+        assert_eq!(truncate_head("abcdef", 4), Some("abcd"));
+        // 'é' is two bytes: never split it
+        assert_eq!(truncate_head("aé", 2), Some("a"));
+        assert_eq!(truncate_tail("abcdef", 4), Some("cdef"));
+        assert_eq!(truncate_tail("éa", 2), Some("a"));
+    }
+
+    #[test]
+    fn test_truncate_field_marks_value() {
+        let mut value = json!({"result": "x".repeat(10), "type": "str"});
+        truncate_field(&mut value, "result", 4);
+        assert_eq!(
+            value,
+            json!({"result": "xxxx", "type": "str", "truncated": true, "originalLength": 10})
+        );
+
+        let mut small = json!({"result": "[1, 2]"});
+        truncate_field(&mut small, "result", 4096);
+        assert_eq!(small, json!({"result": "[1, 2]"}));
+    }
+
+    #[test]
+    fn test_newest_within_keeps_the_end() {
+        let texts = ["first\n", "second\n", "third\n"];
+        assert_eq!(newest_within(&texts, 100), texts.to_vec());
+        assert_eq!(newest_within(&texts, 13), vec!["second\n", "third\n"]);
+        assert_eq!(newest_within(&texts, 9), vec!["nd\n", "third\n"]);
+        assert!(newest_within(&texts, 0).is_empty());
+    }
+
+    #[test]
+    fn test_resolve_max_length() {
+        assert_eq!(resolve_max_length(None).unwrap(), DEFAULT_MAX_LENGTH);
+        assert_eq!(resolve_max_length(Some(100)).unwrap(), 100);
+        assert!(resolve_max_length(Some(0)).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::adapters::diagnostics::CompilerDiagnostic;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -29,12 +30,34 @@ pub enum Error {
     #[error("Invalid state: {0}")]
     InvalidState(String),
 
+    /// A program, working directory or source file outside the directories
+    /// the server was allowed to debug (`--allowed-paths`)
+    #[error("Path not allowed: {0}")]
+    PathNotAllowed(String),
+
+    /// A frame id from a stack fetched before the program last resumed or
+    /// stopped; adapters invalidate frame ids on every resume
+    #[error("Stale frame id: {0}")]
+    StaleFrameId(String),
+
+    /// The adapter stopped reading its input: requests could not even be
+    /// queued for writing
+    #[error("Adapter not consuming input: {0}")]
+    AdapterStalled(String),
+
     #[error("Timeout: {0}")]
     Timeout(String),
 
     #[error("Compilation error: {0}")]
     Compilation(String),
 
+    /// Compilation failed with parsed compiler errors (see `data.diagnostics`)
+    #[error("Compilation error: {message}")]
+    CompilationFailed {
+        message: String,
+        diagnostics: Vec<CompilerDiagnostic>,
+    },
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -48,13 +71,60 @@ impl Error {
             Error::Process(_) => -32004,
             Error::InvalidState(_) => -32005,
             Error::Timeout(_) => -32006,
-            Error::Compilation(_) => -32007,
+            Error::Compilation(_) | Error::CompilationFailed { .. } => -32007,
+            Error::PathNotAllowed(_) => -32008,
+            Error::StaleFrameId(_) => -32009,
+            Error::AdapterStalled(_) => -32010,
             Error::InvalidRequest(_) => -32600,
             Error::MethodNotFound(_) => -32601,
             Error::Internal(_) => -32603,
             Error::Io(_) | Error::Json(_) => -32603,
         }
     }
+
+    /// Stable, machine-readable name for the error, as documented in the
+    /// debugger://error-handling resource
+    pub fn code_name(&self) -> &'static str {
+        match self {
+            Error::SessionNotFound(_) => "SESSION_NOT_FOUND",
+            Error::AdapterNotFound(_) => "ADAPTER_NOT_FOUND",
+            Error::Dap(_) => "DAP_ERROR",
+            Error::Process(_) => "PROCESS_ERROR",
+            Error::InvalidState(_) => "INVALID_STATE",
+            Error::Timeout(_) => "TIMEOUT",
+            Error::Compilation(_) | Error::CompilationFailed { .. } => "COMPILATION_FAILED",
+            Error::PathNotAllowed(_) => "PATH_NOT_ALLOWED",
+            Error::StaleFrameId(_) => "STALE_FRAME_ID",
+            Error::AdapterStalled(_) => "ADAPTER_NOT_CONSUMING_INPUT",
+            Error::InvalidRequest(_) => "INVALID_REQUEST",
+            Error::MethodNotFound(_) => "METHOD_NOT_FOUND",
+            Error::Json(_) => "INVALID_ARGUMENTS",
+            Error::Internal(_) | Error::Io(_) => "INTERNAL_ERROR",
+        }
+    }
+
+    /// Whether repeating the same call later can succeed without changing it
+    ///
+    /// State errors clear once the session reaches the required state and
+    /// timeouts may simply need more time; everything else needs a different
+    /// request or a new session.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Error::InvalidState(_) | Error::Timeout(_))
+    }
+
+    /// `data` payload for JSON-RPC error objects
+    ///
+    /// Compilation failures also carry the parsed compiler diagnostics.
+    pub fn data(&self) -> serde_json::Value {
+        let mut data = serde_json::json!({
+            "code": self.code_name(),
+            "retryable": self.is_retryable()
+        });
+        if let Error::CompilationFailed { diagnostics, .. } = self {
+            data["diagnostics"] = serde_json::to_value(diagnostics).unwrap_or_default();
+        }
+        data
+    }
 }
 
 #[cfg(test)]
@@ -110,6 +180,85 @@ mod tests {
         assert_eq!(err.to_string(), "Internal error: unexpected state");
     }
 
+    #[test]
+    fn test_code_names_and_retryable() {
+        let cases = [
+            (
+                Error::SessionNotFound("s".into()),
+                "SESSION_NOT_FOUND",
+                false,
+            ),
+            (Error::InvalidState("running".into()), "INVALID_STATE", true),
+            (Error::Timeout("stop".into()), "TIMEOUT", true),
+            (Error::Dap("failed".into()), "DAP_ERROR", false),
+            (
+                Error::Compilation("E0308".into()),
+                "COMPILATION_FAILED",
+                false,
+            ),
+            (
+                Error::PathNotAllowed("/etc/passwd".into()),
+                "PATH_NOT_ALLOWED",
+                false,
+            ),
+            (Error::StaleFrameId("7".into()), "STALE_FRAME_ID", false),
+            (
+                Error::AdapterStalled("evaluate".into()),
+                "ADAPTER_NOT_CONSUMING_INPUT",
+                false,
+            ),
+            (
+                Error::InvalidRequest("line".into()),
+                "INVALID_REQUEST",
+                false,
+            ),
+            (Error::Internal("bug".into()), "INTERNAL_ERROR", false),
+        ];
+
+        for (err, code, retryable) in cases {
+            assert_eq!(err.code_name(), code);
+            assert_eq!(err.is_retryable(), retryable);
+            assert_eq!(
+                err.data(),
+                serde_json::json!({"code": code, "retryable": retryable})
+            );
+        }
+    }
+
+    #[test]
+    fn test_compilation_failed_data_includes_diagnostics() {
+        let err = Error::CompilationFailed {
+            message: "Compilation failed: src/main.rs:4:18: mismatched types [E0308] (1 error)"
+                .to_string(),
+            diagnostics: vec![CompilerDiagnostic {
+                level: "error".to_string(),
+                message: "mismatched types".to_string(),
+                file: Some("src/main.rs".to_string()),
+                line: Some(4),
+                column: Some(18),
+                code: Some("E0308".to_string()),
+            }],
+        };
+
+        assert_eq!(err.error_code(), -32007);
+        assert_eq!(
+            err.to_string(),
+            "Compilation error: Compilation failed: src/main.rs:4:18: mismatched types [E0308] (1 error)"
+        );
+        let data = err.data();
+        assert_eq!(data["code"], "COMPILATION_FAILED");
+        assert_eq!(data["diagnostics"][0]["line"], 4);
+        assert_eq!(data["diagnostics"][0]["code"], "E0308");
+    }
+
+    #[test]
+    fn test_json_error_is_invalid_arguments() {
+        let json_err = serde_json::from_str::<i32>("not a number").unwrap_err();
+        let err: Error = json_err.into();
+        assert_eq!(err.code_name(), "INVALID_ARGUMENTS");
+        assert!(!err.is_retryable());
+    }
+
     #[test]
     fn test_io_error_conversion() {
         let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "file not found");
@@ -1,7 +1,11 @@
+// The debugger_start tool schema is a single large json! literal
+#![recursion_limit = "256"]
+
 pub mod adapters;
 pub mod dap;
 pub mod debug;
 pub mod error;
+pub mod logging;
 pub mod mcp;
 pub mod process;
 
@@ -10,11 +14,137 @@ pub use mcp::McpServer;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Settings for [`serve_with_options`]
+#[derive(Debug, Clone, Default)]
+pub struct ServeOptions {
+    /// Accept MCP clients over TCP on this address instead of STDIO
+    pub listen: Option<String>,
+    /// JSON file persisting saved breakpoints across server restarts
+    pub breakpoint_store: Option<std::path::PathBuf>,
+    /// Adapter connect/initialize timeout for sessions that do not set
+    /// `adapterTimeoutMs`
+    pub adapter_timeout: Option<std::time::Duration>,
+    /// Ports socket-mode adapters may listen on (any free port when unset)
+    pub dap_port_range: Option<dap::socket_helper::PortRange>,
+    /// Write a transcript of every session into this directory
+    pub record_dir: Option<std::path::PathBuf>,
+    /// Keys whose values are redacted from transcripts, besides the defaults
+    pub redact_keys: Vec<String>,
+    /// State file of sessions to adopt on startup and keep up to date, so a
+    /// restarted server can take over sessions whose adapters still run
+    pub resume: Option<std::path::PathBuf>,
+    /// Only debug programs within these directories (no restriction when
+    /// empty)
+    pub allowed_paths: Vec<std::path::PathBuf>,
+}
+
 pub async fn serve() -> Result<()> {
     let server = McpServer::new().await?;
     server.run().await
 }
 
+/// Serve MCP clients connecting over TCP on `addr` (e.g. "127.0.0.1:7000")
+pub async fn serve_tcp(addr: &str) -> Result<()> {
+    serve_with_options(ServeOptions {
+        listen: Some(addr.to_string()),
+        ..Default::default()
+    })
+    .await
+}
+
+pub async fn serve_with_options(options: ServeOptions) -> Result<()> {
+    let store = match options.breakpoint_store {
+        Some(path) => debug::BreakpointStore::open(path)?,
+        None => debug::BreakpointStore::in_memory(),
+    };
+    let mut manager = debug::SessionManager::with_breakpoint_store(store);
+    if let Some(timeout) = options.adapter_timeout {
+        manager = manager.with_adapter_timeouts(dap::socket_helper::AdapterTimeouts::new(timeout));
+    }
+    if let Some(range) = options.dap_port_range {
+        tracing::info!("🔌 Socket-mode adapters listen on ports {}", range);
+        dap::socket_helper::set_port_range(Some(range));
+    }
+    let record_all = options.record_dir.is_some();
+    let recording = debug::RecordingConfig::new(
+        options
+            .record_dir
+            .unwrap_or_else(debug::RecordingConfig::default_dir),
+    )
+    .with_redact_keys(options.redact_keys);
+    manager = manager.with_recording(recording, record_all);
+    if !options.allowed_paths.is_empty() {
+        let allowed = adapters::security::AllowedPaths::new(options.allowed_paths)?;
+        tracing::info!("🔒 Restricting sessions to {:?}", allowed.roots());
+        manager = manager.with_allowed_paths(allowed);
+    }
+    if let Some(path) = options.resume {
+        manager = manager.with_session_state(debug::SessionStateFile::new(path));
+        let adopted = manager.resume_sessions().await?;
+        let unrecoverable = manager.unrecoverable_sessions().await;
+        tracing::info!(
+            "♻️  Resumed {} session(s), {} unrecoverable",
+            adopted.len(),
+            unrecoverable.len()
+        );
+    }
+    // Debug configurations checked into the project the server runs in
+    if let Ok(dir) = std::env::current_dir() {
+        if let Err(e) = manager.load_project_configs(&dir).await {
+            tracing::warn!("⚠️  Ignoring project debug configurations: {}", e);
+        }
+    }
+    let session_manager = std::sync::Arc::new(manager);
+
+    let server = {
+        let session_manager = std::sync::Arc::clone(&session_manager);
+        async move {
+            match options.listen {
+                Some(addr) => {
+                    let listener = tokio::net::TcpListener::bind(addr).await?;
+                    McpServer::listen(listener, session_manager).await
+                }
+                None => {
+                    let transport = Box::new(mcp::transport::StdioTransport::new());
+                    McpServer::with_transport(transport, session_manager)
+                        .owning_sessions()
+                        .run()
+                        .await
+                }
+            }
+        }
+    };
+
+    // A signal ends the sessions like a client closing STDIO does
+    tokio::select! {
+        result = server => result,
+        signal = shutdown_signal() => {
+            tracing::info!("🛑 Received {}, shutting down", signal?);
+            session_manager.shutdown().await;
+            Ok(())
+        }
+    }
+}
+
+/// Wait for SIGINT or SIGTERM (Ctrl-C elsewhere) and name the signal
+async fn shutdown_signal() -> Result<&'static str> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut terminate = signal(SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result.map(|_| "SIGINT").map_err(Error::from),
+            _ = terminate.recv() => Ok("SIGTERM"),
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c().await?;
+        Ok("Ctrl-C")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
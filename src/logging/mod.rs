@@ -0,0 +1,270 @@
+//! Where the server's own log goes
+//!
+//! Under an MCP client the server's stderr is often swallowed, so besides
+//! stderr the log can go to a size-rotated file (`serve --log-file`) and
+//! always goes to an in-process ring buffer that the
+//! `debugger_server_log_tail` tool reads. With `--log-format json` every
+//! line is a JSON object whose `spans` carry the `session_id` and `tool` of
+//! the call that logged it, so a log can be filtered per session.
+
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+/// Lines kept in the ring buffer
+pub const RING_CAPACITY: usize = 2000;
+
+/// Size at which the log file is rotated, unless `--log-file-max-bytes`
+/// says otherwise
+pub const DEFAULT_MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Rotated log files kept next to the current one (`<file>.1` is the newest)
+pub const KEPT_LOG_FILES: usize = 3;
+
+/// How log lines are written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Pretty,
+    /// One JSON object per line, with the spans of the event
+    Json,
+}
+
+/// Logging configuration of the `serve` subcommand
+#[derive(Debug, Clone)]
+pub struct LogOptions {
+    /// Filter directives (e.g. "info" or "debugger_mcp=debug"); `RUST_LOG`
+    /// takes precedence
+    pub level: String,
+    pub format: LogFormat,
+    /// Also write the log to this file
+    pub file: Option<PathBuf>,
+    /// Rotate the file once it grows past this size
+    pub max_file_bytes: u64,
+}
+
+/// The last [`RING_CAPACITY`] log lines of this process
+pub struct LogRing {
+    lines: Mutex<VecDeque<String>>,
+    capacity: usize,
+}
+
+impl LogRing {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    /// Append the lines of `text`, dropping the oldest beyond the capacity
+    pub fn push(&self, text: &str) {
+        let mut lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        for line in text.lines().filter(|line| !line.is_empty()) {
+            if lines.len() == self.capacity {
+                lines.pop_front();
+            }
+            lines.push_back(line.to_string());
+        }
+    }
+
+    /// The last `count` lines, oldest first
+    pub fn tail(&self, count: usize) -> Vec<String> {
+        let lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        let skip = lines.len().saturating_sub(count);
+        lines.iter().skip(skip).cloned().collect()
+    }
+
+    /// Lines currently buffered
+    pub fn len(&self) -> usize {
+        self.lines.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+/// Ring buffer of the server log, filled once [`init`] ran
+pub fn server_log() -> &'static LogRing {
+    static RING: OnceLock<LogRing> = OnceLock::new();
+    RING.get_or_init(|| LogRing::new(RING_CAPACITY))
+}
+
+/// `io::Write` adapter feeding one formatted event at a time into a ring
+struct RingWriter(&'static LogRing);
+
+impl Write for RingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.push(&String::from_utf8_lossy(buf));
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Log file that moves itself to `<path>.1` once it grows past `max_bytes`
+///
+/// Older files shift to `<path>.2` and so on; the oldest beyond
+/// [`KEPT_LOG_FILES`] is removed.
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+}
+
+impl RotatingFile {
+    pub fn open(path: &Path, max_bytes: u64) -> std::io::Result<Self> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            written,
+            max_bytes,
+        })
+    }
+
+    /// `<path>.<index>`
+    fn rotated(&self, index: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file.flush()?;
+        let _ = std::fs::remove_file(self.rotated(KEPT_LOG_FILES));
+        for index in (1..KEPT_LOG_FILES).rev() {
+            let _ = std::fs::rename(self.rotated(index), self.rotated(index + 1));
+        }
+        std::fs::rename(&self.path, self.rotated(1))?;
+        self.file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn fmt_layer<W>(format: LogFormat, writer: W, ansi: bool) -> Box<dyn Layer<Registry> + Send + Sync>
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(writer)
+        .with_ansi(ansi);
+    match format {
+        LogFormat::Pretty => layer.boxed(),
+        LogFormat::Json => layer
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .boxed(),
+    }
+}
+
+/// Install the global subscriber: stderr, the ring buffer and, with
+/// `options.file`, the rotated log file
+///
+/// The file is written by a background thread; keep the returned guard
+/// alive until exit so its last lines are flushed.
+pub fn init(options: &LogOptions) -> crate::Result<Option<WorkerGuard>> {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&options.level));
+
+    let mut layers = vec![
+        fmt_layer(options.format, std::io::stderr, true),
+        fmt_layer(options.format, || RingWriter(server_log()), false),
+    ];
+    let guard = match &options.file {
+        Some(path) => {
+            let file = RotatingFile::open(path, options.max_file_bytes)?;
+            let (writer, guard) = tracing_appender::non_blocking(file);
+            layers.push(fmt_layer(options.format, writer, false));
+            Some(guard)
+        }
+        None => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(layers)
+        .with(filter)
+        .init();
+    Ok(guard)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_keeps_last_lines() {
+        let ring = LogRing::new(3);
+        ring.push("one\n");
+        ring.push("two\nthree\n\nfour\n");
+
+        assert_eq!(ring.len(), 3);
+        assert_eq!(ring.tail(2), vec!["three", "four"]);
+        assert_eq!(ring.tail(10), vec!["two", "three", "four"]);
+    }
+
+    #[test]
+    fn test_rotating_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs").join("server.log");
+        let mut file = RotatingFile::open(&path, 10).unwrap();
+
+        for line in [
+            "aaaaaaaa\n",
+            "bbbbbbbb\n",
+            "cccccccc\n",
+            "dddddddd\n",
+            "eeeeeeee\n",
+        ] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+        file.flush().unwrap();
+
+        let read = |path: PathBuf| std::fs::read_to_string(path).unwrap();
+        assert_eq!(read(path.clone()), "eeeeeeee\n");
+        assert_eq!(read(file.rotated(1)), "dddddddd\n");
+        assert_eq!(read(file.rotated(KEPT_LOG_FILES)), "bbbbbbbb\n");
+        assert!(!file.rotated(KEPT_LOG_FILES + 1).exists());
+    }
+}
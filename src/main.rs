@@ -1,4 +1,5 @@
 use clap::{Parser, Subcommand};
+use debugger_mcp::logging::{LogFormat, LogOptions};
 use debugger_mcp::Result;
 use tracing_subscriber::EnvFilter;
 
@@ -12,7 +13,7 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Start the MCP server listening on STDIO
+    /// Start the MCP server listening on STDIO (or TCP with --listen)
     Serve {
         /// Enable verbose logging
         #[arg(short, long)]
@@ -21,6 +22,94 @@ enum Commands {
         /// Set log level (trace, debug, info, warn, error)
         #[arg(long, default_value = "info")]
         log_level: String,
+
+        /// Also write the log to this file, rotated when it reaches
+        /// --log-file-max-bytes (the last 3 rotated files are kept)
+        #[arg(long, value_name = "FILE")]
+        log_file: Option<std::path::PathBuf>,
+
+        /// Size at which the log file is rotated
+        #[arg(
+            long,
+            value_name = "BYTES",
+            default_value_t = debugger_mcp::logging::DEFAULT_MAX_FILE_BYTES,
+            value_parser = clap::value_parser!(u64).range(1..)
+        )]
+        log_file_max_bytes: u64,
+
+        /// Log line format: human-readable, or one JSON object per line with
+        /// the session_id and tool of the call that logged it
+        #[arg(long, value_enum, default_value_t = LogFormat::Pretty)]
+        log_format: LogFormat,
+
+        /// Accept MCP connections over TCP on this address (e.g. 127.0.0.1:7000)
+        /// instead of STDIO
+        #[arg(long, value_name = "ADDR:PORT")]
+        listen: Option<String>,
+
+        /// Persist breakpoints saved on disconnect to this JSON file, so
+        /// restoreBreakpoints works across server restarts
+        #[arg(long, value_name = "FILE")]
+        breakpoint_store: Option<std::path::PathBuf>,
+
+        /// How long to wait for debug adapters to accept a connection and to
+        /// finish initializing, unless a session sets adapterTimeoutMs
+        #[arg(long, value_name = "MS", value_parser = clap::value_parser!(u64).range(1..))]
+        adapter_timeout_ms: Option<u64>,
+
+        /// Only let socket-mode debug adapters (dlv, CodeLLDB, vscode-js-debug,
+        /// rdbg over TCP) listen on ports in this range, e.g. 40000-40100
+        #[arg(long, value_name = "FIRST-LAST")]
+        dap_port_range: Option<debugger_mcp::dap::socket_helper::PortRange>,
+
+        /// Write a transcript of every session (tool calls and state changes)
+        /// into this directory, one JSON lines file per session
+        #[arg(long, value_name = "DIR")]
+        record_dir: Option<std::path::PathBuf>,
+
+        /// Redact values of keys containing this text from transcripts, besides
+        /// password, secret, token, apikey and authorization (repeatable)
+        #[arg(long, value_name = "KEY")]
+        redact_key: Vec<String>,
+
+        /// Keep this JSON file up to date with the running sessions and, on
+        /// startup, adopt the sessions it lists whose adapters still run
+        /// (rdbg, CodeLLDB); the others are reported as unrecoverable
+        #[arg(long, value_name = "FILE")]
+        resume: Option<std::path::PathBuf>,
+
+        /// Only debug programs, and set breakpoints in sources, within these
+        /// directories (comma-separated); symlinks are resolved before checking
+        #[arg(
+            long,
+            value_name = "DIR,...",
+            env = "DEBUGGER_MCP_ALLOWED_PATHS",
+            value_delimiter = ','
+        )]
+        allowed_paths: Vec<std::path::PathBuf>,
+    },
+
+    /// Re-issue the tool calls of a session transcript and report where the
+    /// outcome differs from the recording
+    Replay {
+        /// Transcript written by a recorded session
+        transcript: std::path::PathBuf,
+
+        /// Replay against the MCP server listening on this address instead of
+        /// a server in this process
+        #[arg(long, value_name = "ADDR:PORT")]
+        connect: Option<String>,
+
+        /// Set log level (trace, debug, info, warn, error)
+        #[arg(long, default_value = "warn")]
+        log_level: String,
+    },
+
+    /// Check which debug adapters are installed and print a JSON report
+    Doctor {
+        /// Only check this language
+        #[arg(long)]
+        language: Option<String>,
     },
 }
 
@@ -29,21 +118,163 @@ async fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Serve { verbose, log_level } => {
+        Commands::Serve {
+            verbose,
+            log_level,
+            log_file,
+            log_file_max_bytes,
+            log_format,
+            listen,
+            breakpoint_store,
+            adapter_timeout_ms,
+            dap_port_range,
+            record_dir,
+            redact_key,
+            resume,
+            allowed_paths,
+        } => {
             // Initialize tracing
-            let level = if verbose { "debug" } else { &log_level };
-            let filter =
-                EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(level));
+            let level = if verbose {
+                "debug".to_string()
+            } else {
+                log_level
+            };
+            let log_guard = debugger_mcp::logging::init(&LogOptions {
+                level,
+                format: log_format,
+                file: log_file,
+                max_file_bytes: log_file_max_bytes,
+            })?;
 
+            // Run the server
+            debugger_mcp::serve_with_options(debugger_mcp::ServeOptions {
+                listen,
+                breakpoint_store,
+                adapter_timeout: adapter_timeout_ms.map(std::time::Duration::from_millis),
+                dap_port_range,
+                record_dir,
+                redact_keys: redact_key,
+                resume,
+                allowed_paths,
+            })
+            .await?;
+            // Flush the log file: exit() skips destructors
+            drop(log_guard);
+            // After a signal, the runtime would wait for the blocking read
+            // on stdin before it shuts down
+            std::process::exit(0);
+        }
+        Commands::Replay {
+            transcript,
+            connect,
+            log_level,
+        } => {
             tracing_subscriber::fmt()
-                .with_env_filter(filter)
+                .with_env_filter(EnvFilter::new(log_level))
                 .with_writer(std::io::stderr)
                 .init();
 
-            // Run the server
-            debugger_mcp::serve().await?;
+            let entries = debugger_mcp::debug::recorder::read_transcript(&transcript)?;
+            let mut target = match connect {
+                Some(addr) => debugger_mcp::mcp::replay::ReplayTarget::connect(&addr).await?,
+                None => debugger_mcp::mcp::replay::ReplayTarget::in_process(),
+            };
+            let report = debugger_mcp::mcp::replay::replay(&entries, &mut target).await?;
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&report).unwrap_or_default()
+            );
+            if report.divergences > 0 {
+                std::process::exit(1);
+            }
+        }
+        Commands::Doctor { language } => {
+            let mut health = debugger_mcp::adapters::health::check_all();
+            if let Some(language) = language {
+                health.retain(|h| h.language == language);
+            }
+            let report = debugger_mcp::adapters::health::report(&health);
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&report).unwrap_or_default()
+            );
+            if health.iter().any(|h| !h.available) {
+                std::process::exit(1);
+            }
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serve_log_options() {
+        let cli = Cli::try_parse_from([
+            "debugger_mcp",
+            "serve",
+            "--log-file",
+            "/tmp/debugger.log",
+            "--log-format",
+            "json",
+            "--log-file-max-bytes",
+            "1024",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Serve {
+                log_file,
+                log_format,
+                log_file_max_bytes,
+                ..
+            } => {
+                assert_eq!(log_file, Some("/tmp/debugger.log".into()));
+                assert_eq!(log_format, LogFormat::Json);
+                assert_eq!(log_file_max_bytes, 1024);
+            }
+            _ => panic!("Expected serve"),
+        }
+
+        let cli = Cli::try_parse_from(["debugger_mcp", "serve"]).unwrap();
+        match cli.command {
+            Commands::Serve {
+                log_file,
+                log_format,
+                log_file_max_bytes,
+                ..
+            } => {
+                assert_eq!(log_file, None);
+                assert_eq!(log_format, LogFormat::Pretty);
+                assert_eq!(
+                    log_file_max_bytes,
+                    debugger_mcp::logging::DEFAULT_MAX_FILE_BYTES
+                );
+            }
+            _ => panic!("Expected serve"),
+        }
+
+        let cli = Cli::try_parse_from(["debugger_mcp", "serve", "--dap-port-range", "40000-40100"])
+            .unwrap();
+        match cli.command {
+            Commands::Serve { dap_port_range, .. } => {
+                assert_eq!(
+                    dap_port_range.map(|r| (r.first, r.last)),
+                    Some((40000, 40100))
+                );
+            }
+            _ => panic!("Expected serve"),
+        }
+
+        for bad in [
+            ["--log-format", "xml"],
+            ["--log-file-max-bytes", "0"],
+            ["--dap-port-range", "41000-40000"],
+        ] {
+            let args = ["debugger_mcp", "serve"].into_iter().chain(bad);
+            assert!(Cli::try_parse_from(args).is_err(), "{:?}", bad);
+        }
+    }
+}
@@ -1,67 +1,355 @@
+pub mod prompts;
 pub mod protocol;
+pub mod replay;
 pub mod resources;
 pub mod tools;
 pub mod transport;
 pub mod transport_trait;
 
 use crate::debug::SessionManager;
-use crate::Result;
-use protocol::ProtocolHandler;
+use crate::{Error, Result};
+use prompts::PromptsHandler;
+use protocol::{JsonRpcMessage, ProtocolHandler};
 use resources::ResourcesHandler;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::any::Any;
+use std::collections::HashMap;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, mpsc};
 use tools::ToolsHandler;
-use tracing::{error, info};
-use transport::StdioTransport;
+use tracing::{error, info, warn};
+use transport::{StdioTransport, TcpTransport};
+use transport_trait::McpTransportTrait;
 
 pub struct McpServer {
-    transport: StdioTransport,
+    transport: Box<dyn McpTransportTrait>,
     handler: ProtocolHandler,
+    session_manager: Arc<SessionManager>,
+    /// End all sessions when the client goes away or asks to shut down
+    owns_sessions: bool,
 }
 
 impl McpServer {
     pub async fn new() -> Result<Self> {
         info!("Initializing MCP server");
 
-        let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+        let session_manager = Arc::new(SessionManager::new());
 
+        Ok(
+            Self::with_transport(Box::new(StdioTransport::new()), session_manager)
+                .owning_sessions(),
+        )
+    }
+
+    /// Create a server speaking over `transport` that operates on the given
+    /// (possibly shared) session manager
+    pub fn with_transport(
+        transport: Box<dyn McpTransportTrait>,
+        session_manager: Arc<SessionManager>,
+    ) -> Self {
         // Create tools handler
         let tools_handler = Arc::new(ToolsHandler::new(Arc::clone(&session_manager)));
 
+        let mut handler = ProtocolHandler::new();
+
         // Create resources handler
-        let resources_handler = Arc::new(ResourcesHandler::new(Arc::clone(&session_manager)));
+        let resources_handler = Arc::new(
+            ResourcesHandler::new(Arc::clone(&session_manager))
+                .with_negotiation(handler.shared_negotiation()),
+        );
 
-        let mut handler = ProtocolHandler::new();
         handler.set_tools_handler(tools_handler);
         handler.set_resources_handler(resources_handler);
+        handler.set_prompts_handler(Arc::new(PromptsHandler::new()));
 
-        Ok(Self {
-            transport: StdioTransport::new(),
+        Self {
+            transport,
             handler,
-        })
+            session_manager,
+            owns_sessions: false,
+        }
+    }
+
+    /// End every session of the session manager when this client closes the
+    /// connection or sends `shutdown` (for the single STDIO client, whose
+    /// sessions would otherwise outlive the server)
+    pub fn owning_sessions(mut self) -> Self {
+        self.owns_sessions = true;
+        self
     }
 
-    pub async fn run(mut self) -> Result<()> {
+    /// Serve messages until the transport fails, reaches EOF or the client
+    /// sends the `exit` notification
+    ///
+    /// EOF and `exit` end the server normally (`Ok`). After a `shutdown`
+    /// request, which is answered once the sessions are ended, further
+    /// requests are refused. Sessions are only ended by a server that
+    /// [owns them](Self::owning_sessions).
+    ///
+    /// Every request is handled on its own task so a long-running call (such
+    /// as debugger_wait_for_stop) does not block the ones after it. Responses
+    /// go through a single writer task in completion order; JSON-RPC ids keep
+    /// them correlated. Notifications are handled inline and never answered.
+    ///
+    /// A request whose handler panics is answered with an internal error
+    /// naming the tool and the panic message; the server keeps serving.
+    ///
+    /// A cancellation notification aborts the matching in-flight request at
+    /// its next await point (wait loops sleep between polls, so this is
+    /// prompt) and answers it with a `REQUEST_CANCELLED` error instead.
+    ///
+    /// Session state changes are pushed as notifications once the client has
+    /// completed the initialize handshake, if it opted into them (see
+    /// [`Negotiation::accepts_notification`](protocol::Negotiation::accepts_notification))
+    /// or subscribed to the resources they update (`resources/subscribe`).
+    pub async fn run(self) -> Result<()> {
         info!("Starting MCP server");
 
+        let mut state_changes = self.session_manager.subscribe_state_changes();
+        let mut resource_changes = self.session_manager.subscribe_resource_changes();
+        let session_manager = self.session_manager;
+        let owns_sessions = self.owns_sessions;
+        let (mut reader, mut writer) = self.transport.split();
+        let handler = Arc::new(self.handler);
+
+        let (tx, mut rx) = mpsc::unbounded_channel::<JsonRpcMessage>();
+        let mut writer_task = tokio::spawn(async move {
+            while let Some(response) = rx.recv().await {
+                if let Err(e) = writer.write_message(&response).await {
+                    error!("Failed to write response: {}", e);
+                    return Err(e);
+                }
+            }
+            Ok(())
+        });
+
+        let notifier = {
+            let handler = Arc::clone(&handler);
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                loop {
+                    // State changes first: a removed session terminates
+                    // before it leaves the session list
+                    let notifications = tokio::select! {
+                        biased;
+                        change = state_changes.recv() => match change {
+                            Ok(change) => protocol::state_change_notifications(&change),
+                            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                                warn!("Dropped {} state change notifications", skipped);
+                                continue;
+                            }
+                            Err(broadcast::error::RecvError::Closed) => break,
+                        },
+                        change = resource_changes.recv() => match change {
+                            Ok(change) => vec![protocol::resource_change_notification(&change)],
+                            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                                warn!("Dropped {} resource change notifications", skipped);
+                                continue;
+                            }
+                            Err(broadcast::error::RecvError::Closed) => break,
+                        },
+                    };
+                    for notif in notifications {
+                        if !handler.accepts(&notif) {
+                            continue;
+                        }
+                        if tx.send(JsonRpcMessage::Notification(notif)).is_err() {
+                            return;
+                        }
+                    }
+                }
+            })
+        };
+
+        // In-flight requests by serialized JSON-RPC id. Whoever removes an
+        // entry (the finishing task or a cancellation) sends the one response.
+        let in_flight: Arc<Mutex<HashMap<String, tokio::task::AbortHandle>>> =
+            Arc::new(Mutex::new(HashMap::new()));
+        let mut shutting_down = false;
+
         loop {
-            match self.transport.read_message().await {
-                Ok(msg) => {
-                    let response = self.handler.handle_message(msg).await;
-                    if let Err(e) = self.transport.write_message(&response).await {
-                        error!("Failed to write response: {}", e);
+            tokio::select! {
+                read = reader.read_message() => match read {
+                    Ok(JsonRpcMessage::Notification(notif)) => {
+                        if let Some(id) = protocol::cancelled_request_id(&notif) {
+                            let aborted = in_flight
+                                .lock()
+                                .unwrap_or_else(|e| e.into_inner())
+                                .remove(&id.to_string());
+                            if let Some(task) = aborted {
+                                info!("🛑 Cancelling request {}", id);
+                                task.abort();
+                                let _ = tx.send(JsonRpcMessage::Response(
+                                    protocol::cancelled_response(id),
+                                ));
+                            }
+                        }
+                        if notif.method == "exit" {
+                            info!("👋 Client sent exit");
+                            break;
+                        }
+                        handler.handle_notification(notif).await;
+                    }
+                    Ok(JsonRpcMessage::Request(req)) if req.method == "shutdown" => {
+                        info!("🛑 Client requested shutdown");
+                        if !shutting_down && owns_sessions {
+                            session_manager.shutdown().await;
+                        }
+                        shutting_down = true;
+                        let _ = tx.send(JsonRpcMessage::Response(protocol::JsonRpcResponse {
+                            jsonrpc: "2.0".to_string(),
+                            id: req.id,
+                            result: Some(serde_json::Value::Null),
+                            error: None,
+                        }));
+                    }
+                    Ok(JsonRpcMessage::Request(req)) if shutting_down => {
+                        let _ = tx.send(JsonRpcMessage::Response(
+                            protocol::shutting_down_response(req.id),
+                        ));
+                    }
+                    Ok(msg) => {
+                        let (key, request) = match &msg {
+                            JsonRpcMessage::Request(req) => (
+                                Some(req.id.to_string()),
+                                Some((req.id.clone(), protocol::request_subject(req))),
+                            ),
+                            _ => (None, None),
+                        };
+                        let handler = Arc::clone(&handler);
+                        let tx = tx.clone();
+                        let registry = Arc::clone(&in_flight);
+                        let task_key = key.clone();
+
+                        // Hold the lock while spawning so the task cannot
+                        // finish before it is registered
+                        let mut in_flight = in_flight.lock().unwrap_or_else(|e| e.into_inner());
+                        let task = tokio::spawn(async move {
+                            let response = match CatchPanic::new(handler.handle_message(msg)).await {
+                                Ok(response) => response,
+                                Err(panic) => {
+                                    let panic = panic_message(panic.as_ref());
+                                    let Some((id, subject)) = request else {
+                                        error!("💥 Handling a message panicked: {}", panic);
+                                        return;
+                                    };
+                                    error!("💥 {} panicked: {}", subject, panic);
+                                    JsonRpcMessage::Response(protocol::panic_response(
+                                        id, &subject, &panic,
+                                    ))
+                                }
+                            };
+                            let still_pending = match &task_key {
+                                Some(key) => registry
+                                    .lock()
+                                    .unwrap_or_else(|e| e.into_inner())
+                                    .remove(key)
+                                    .is_some(),
+                                None => true,
+                            };
+                            if still_pending {
+                                let _ = tx.send(response);
+                            }
+                        });
+                        if let Some(key) = key {
+                            in_flight.insert(key, task.abort_handle());
+                        }
+                    }
+                    Err(e) if transport::is_eof(&e) => {
+                        info!("👋 Client closed the connection");
+                        break;
+                    }
+                    Err(e) => {
+                        error!("Failed to read message: {}", e);
+                        // Let in-flight requests finish writing their responses
+                        notifier.abort();
+                        drop(tx);
+                        let _ = writer_task.await;
                         return Err(e);
                     }
-                }
-                Err(e) => {
-                    error!("Failed to read message: {}", e);
-                    return Err(e);
+                },
+                written = &mut writer_task => {
+                    notifier.abort();
+                    return written.map_err(|e| Error::Internal(format!("Writer task failed: {}", e)))?;
                 }
             }
         }
+
+        if owns_sessions {
+            session_manager.shutdown().await;
+        }
+        // Nobody is left to read the answers to requests still in flight
+        for (_, task) in in_flight.lock().unwrap_or_else(|e| e.into_inner()).drain() {
+            task.abort();
+        }
+        notifier.abort();
+        drop(tx);
+        let _ = writer_task.await;
+        Ok(())
+    }
+
+    /// Accept MCP clients over TCP until the listener fails
+    ///
+    /// Each connection gets its own protocol handler, while all connections
+    /// share one session manager. A client disconnecting only ends its own
+    /// connection; sessions it started keep running and stay visible to the
+    /// other clients until they are disconnected explicitly.
+    pub async fn listen(listener: TcpListener, session_manager: Arc<SessionManager>) -> Result<()> {
+        info!("Starting MCP server on TCP {}", listener.local_addr()?);
+
+        loop {
+            let (stream, peer) = listener.accept().await?;
+            info!("🔌 MCP client connected from {}", peer);
+
+            let server = Self::with_transport(
+                Box::new(TcpTransport::new(stream)),
+                Arc::clone(&session_manager),
+            );
+            tokio::spawn(async move {
+                if let Err(e) = server.run().await {
+                    warn!("MCP client {} disconnected: {}", peer, e);
+                }
+            });
+        }
     }
 }
 
+/// Future catching a panic of the future it wraps, so a bug in one request
+/// handler fails that request instead of losing its response
+struct CatchPanic<F>(Pin<Box<F>>);
+
+impl<F: Future> CatchPanic<F> {
+    fn new(future: F) -> Self {
+        Self(Box::pin(future))
+    }
+}
+
+impl<F: Future> Future for CatchPanic<F> {
+    type Output = std::thread::Result<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let future = self.0.as_mut();
+        match std::panic::catch_unwind(AssertUnwindSafe(|| future.poll(cx))) {
+            Ok(Poll::Pending) => Poll::Pending,
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Err(panic) => Poll::Ready(Err(panic)),
+        }
+    }
+}
+
+/// The message a panic was raised with
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -72,4 +360,519 @@ mod tests {
         let server = McpServer::new().await;
         assert!(server.is_ok(), "Should create MCP server successfully");
     }
+
+    /// Session manager holding idle (never launched) sessions backed by
+    /// scripted adapters, plus their ids
+    async fn idle_sessions(count: usize) -> (SessionManager, Vec<String>) {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let manager = SessionManager::new();
+        let mut session_ids = Vec::new();
+        for i in 0..count {
+            let client = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+                .await
+                .unwrap();
+            let session =
+                DebugSession::new("python".to_string(), format!("program{}.py", i), client)
+                    .await
+                    .unwrap();
+            session_ids.push(manager.insert_session(Arc::new(session)).await);
+        }
+        (manager, session_ids)
+    }
+
+    /// Run a server over a loopback socket and return the client end
+    async fn connect(manager: SessionManager) -> TcpTransport {
+        connect_shared(Arc::new(manager)).await
+    }
+
+    async fn connect_shared(manager: Arc<SessionManager>) -> TcpTransport {
+        use tokio::net::TcpStream;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let accept = tokio::spawn(async move { listener.accept().await.unwrap().0 });
+        let client = TcpTransport::new(TcpStream::connect(addr).await.unwrap());
+        let server =
+            McpServer::with_transport(Box::new(TcpTransport::new(accept.await.unwrap())), manager);
+        tokio::spawn(server.run());
+        client
+    }
+
+    fn tools_call(id: i64, name: &str, arguments: serde_json::Value) -> JsonRpcMessage {
+        JsonRpcMessage::Request(protocol::JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: serde_json::json!(id),
+            method: "tools/call".to_string(),
+            params: Some(serde_json::json!({"name": name, "arguments": arguments})),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_handled_concurrently() {
+        use serde_json::json;
+        use tokio::time::{timeout, Duration};
+
+        let (manager, session_ids) = idle_sessions(2).await;
+        let mut client = connect(manager).await;
+
+        // The first session never stops, so this call polls for its full timeout
+        client
+            .write_message(&tools_call(
+                1,
+                "debugger_wait_for_stop",
+                json!({"sessionId": session_ids[0], "timeoutMs": 5000}),
+            ))
+            .await
+            .unwrap();
+        client
+            .write_message(&tools_call(
+                2,
+                "debugger_session_state",
+                json!({"sessionId": session_ids[1]}),
+            ))
+            .await
+            .unwrap();
+
+        let first = timeout(Duration::from_secs(2), client.read_message())
+            .await
+            .expect("session_state should not wait behind wait_for_stop")
+            .unwrap();
+        match first {
+            JsonRpcMessage::Response(r) => {
+                assert_eq!(r.id, json!(2));
+                assert!(r.error.is_none());
+            }
+            other => panic!("Expected Response, got: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_panicking_handler_fails_only_its_request() {
+        use serde_json::json;
+
+        let mut client = connect(SessionManager::new()).await;
+
+        client
+            .write_message(&tools_call(1, "debugger_test_panic", json!({})))
+            .await
+            .unwrap();
+        match client.read_message().await.unwrap() {
+            JsonRpcMessage::Response(r) => {
+                assert_eq!(r.id, json!(1));
+                let error = r.error.expect("panic should be an error response");
+                assert_eq!(error.code, -32603);
+                assert!(error
+                    .message
+                    .contains("debugger_test_panic panicked: injected panic"));
+                assert_eq!(error.data.unwrap()["panic"], "injected panic");
+            }
+            other => panic!("Expected Response, got: {:?}", other),
+        }
+
+        // The server keeps serving
+        client
+            .write_message(&tools_call(2, "debugger_list_configs", json!({})))
+            .await
+            .unwrap();
+        match client.read_message().await.unwrap() {
+            JsonRpcMessage::Response(r) => {
+                assert_eq!(r.id, json!(2));
+                assert!(r.error.is_none());
+            }
+            other => panic!("Expected Response, got: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_wait_for_stop() {
+        use protocol::JsonRpcNotification;
+        use serde_json::json;
+        use tokio::time::{sleep, timeout, Duration};
+
+        let (manager, session_ids) = idle_sessions(1).await;
+        let mut client = connect(manager).await;
+
+        client
+            .write_message(&tools_call(
+                1,
+                "debugger_wait_for_stop",
+                json!({"sessionId": session_ids[0], "timeoutMs": 60000}),
+            ))
+            .await
+            .unwrap();
+        sleep(Duration::from_millis(100)).await;
+        client
+            .write_message(&JsonRpcMessage::Notification(JsonRpcNotification {
+                jsonrpc: "2.0".to_string(),
+                method: "notifications/cancelled".to_string(),
+                params: Some(json!({"requestId": 1, "reason": "user gave up"})),
+            }))
+            .await
+            .unwrap();
+
+        let response = timeout(Duration::from_secs(2), client.read_message())
+            .await
+            .expect("cancellation should answer immediately")
+            .unwrap();
+        match response {
+            JsonRpcMessage::Response(r) => {
+                assert_eq!(r.id, json!(1));
+                let error = r.error.unwrap();
+                assert_eq!(error.code, protocol::REQUEST_CANCELLED);
+                assert_eq!(error.data.unwrap()["code"], "REQUEST_CANCELLED");
+            }
+            other => panic!("Expected Response, got: {:?}", other),
+        }
+
+        // Cancelling an unknown or finished request is a no-op
+        client
+            .write_message(&JsonRpcMessage::Notification(JsonRpcNotification {
+                jsonrpc: "2.0".to_string(),
+                method: "$/cancelRequest".to_string(),
+                params: Some(json!({"id": 1})),
+            }))
+            .await
+            .unwrap();
+        client
+            .write_message(&tools_call(
+                2,
+                "debugger_session_state",
+                json!({"sessionId": session_ids[0]}),
+            ))
+            .await
+            .unwrap();
+        match client.read_message().await.unwrap() {
+            JsonRpcMessage::Response(r) => {
+                assert_eq!(r.id, json!(2));
+                assert!(r.error.is_none());
+            }
+            other => panic!("Expected Response, got: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_state_changes_are_pushed_after_initialize() {
+        use crate::debug::state::DebugState;
+        use protocol::JsonRpcRequest;
+        use serde_json::json;
+        use tokio::time::{sleep, timeout, Duration};
+
+        let (manager, session_ids) = idle_sessions(1).await;
+        let session = manager.get_session(&session_ids[0]).await.unwrap();
+        let mut client = connect(manager).await;
+
+        // Not initialized yet: this change must not reach the client
+        session
+            .state
+            .write()
+            .await
+            .set_state(DebugState::Initializing);
+        sleep(Duration::from_millis(100)).await;
+
+        client
+            .write_message(&JsonRpcMessage::Request(JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                id: json!(1),
+                method: "initialize".to_string(),
+                params: Some(json!({
+                    "protocolVersion": "2024-11-05",
+                    "capabilities": {
+                        "experimental": {"stateNotifications": {}, "resourceUpdates": true}
+                    }
+                })),
+            }))
+            .await
+            .unwrap();
+        match client.read_message().await.unwrap() {
+            JsonRpcMessage::Response(r) => assert_eq!(r.id, json!(1)),
+            other => panic!("Expected initialize response, got: {:?}", other),
+        }
+
+        session.state.write().await.set_state(DebugState::Running);
+
+        let mut methods = Vec::new();
+        for _ in 0..2 {
+            match timeout(Duration::from_secs(2), client.read_message())
+                .await
+                .expect("state change should be pushed")
+                .unwrap()
+            {
+                JsonRpcMessage::Notification(n) => {
+                    if n.method == "debugger/stateChanged" {
+                        let params = n.params.clone().unwrap();
+                        assert_eq!(params["sessionId"], session_ids[0].as_str());
+                        assert_eq!(params["oldState"], "Initializing");
+                        assert_eq!(params["newState"], "Running");
+                    }
+                    methods.push(n.method);
+                }
+                other => panic!("Expected Notification, got: {:?}", other),
+            }
+        }
+        assert_eq!(
+            methods,
+            vec!["notifications/resources/updated", "debugger/stateChanged"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resource_subscriptions_notify_subscribed_uris() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+        use crate::debug::StopContext;
+        use protocol::JsonRpcRequest;
+        use serde_json::json;
+        use tokio::time::{timeout, Duration};
+
+        let manager = Arc::new(SessionManager::new());
+        let mut client = connect_shared(Arc::clone(&manager)).await;
+        let mut next_id = 0;
+        let mut request = |method: &str, params: serde_json::Value| {
+            next_id += 1;
+            JsonRpcMessage::Request(JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                id: json!(next_id),
+                method: method.to_string(),
+                params: Some(params),
+            })
+        };
+        async fn respond(
+            client: &mut TcpTransport,
+            message: JsonRpcMessage,
+        ) -> protocol::JsonRpcResponse {
+            client.write_message(&message).await.unwrap();
+            match client.read_message().await.unwrap() {
+                JsonRpcMessage::Response(r) => r,
+                other => panic!("Expected Response, got: {:?}", other),
+            }
+        }
+        async fn updated(client: &mut TcpTransport) -> String {
+            match timeout(Duration::from_secs(2), client.read_message())
+                .await
+                .expect("update should be pushed")
+                .unwrap()
+            {
+                JsonRpcMessage::Notification(n) => {
+                    assert_eq!(n.method, "notifications/resources/updated");
+                    n.params.unwrap()["uri"].as_str().unwrap().to_string()
+                }
+                other => panic!("Expected Notification, got: {:?}", other),
+            }
+        }
+
+        let init = respond(
+            &mut client,
+            request(
+                "initialize",
+                json!({"protocolVersion": "2025-06-18", "capabilities": {}}),
+            ),
+        )
+        .await;
+        assert_eq!(
+            init.result.unwrap()["capabilities"]["resources"]["subscribe"],
+            true
+        );
+        let refused = respond(
+            &mut client,
+            request(
+                "resources/subscribe",
+                json!({"uri": "debugger://workflows"}),
+            ),
+        )
+        .await;
+        assert_eq!(refused.error.unwrap().code, -32600);
+        let subscribed = respond(
+            &mut client,
+            request("resources/subscribe", json!({"uri": "debugger://sessions"})),
+        )
+        .await;
+        assert!(subscribed.error.is_none());
+
+        // Creating a session updates the session list
+        let adapter = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "app.py".to_string(), adapter)
+            .await
+            .unwrap();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        assert_eq!(updated(&mut client).await, "debugger://sessions");
+
+        let session_uri = format!("debugger://sessions/{}", session_id);
+        let stack_uri = format!("{}/stackTrace", session_uri);
+        for uri in [&session_uri, &stack_uri] {
+            let response = respond(
+                &mut client,
+                request("resources/subscribe", json!({"uri": uri})),
+            )
+            .await;
+            assert!(response.error.is_none());
+        }
+
+        // A breakpoint, then a stop at it
+        let session = manager.get_session(&session_id).await.unwrap();
+        session
+            .state
+            .write()
+            .await
+            .add_breakpoint("/app/app.py".to_string(), 3);
+        assert_eq!(updated(&mut client).await, session_uri);
+        session
+            .state
+            .write()
+            .await
+            .record_stop(StopContext::from_event_body(
+                &json!({"reason": "breakpoint", "threadId": 1}),
+            ));
+        assert_eq!(updated(&mut client).await, session_uri);
+        assert_eq!(updated(&mut client).await, stack_uri);
+
+        // After unsubscribing, stops only update the session details
+        let response = respond(
+            &mut client,
+            request("resources/unsubscribe", json!({"uri": stack_uri})),
+        )
+        .await;
+        assert!(response.error.is_none());
+        session
+            .state
+            .write()
+            .await
+            .record_stop(StopContext::from_event_body(
+                &json!({"reason": "step", "threadId": 1}),
+            ));
+        assert_eq!(updated(&mut client).await, session_uri);
+
+        manager.remove_session(&session_id).await.unwrap();
+        // Disconnecting ends the session before it leaves the list
+        assert_eq!(updated(&mut client).await, session_uri);
+        assert_eq!(updated(&mut client).await, "debugger://sessions");
+    }
+
+    #[tokio::test]
+    async fn test_state_changes_need_client_opt_in() {
+        use crate::debug::state::DebugState;
+        use protocol::JsonRpcRequest;
+        use serde_json::json;
+        use tokio::time::{sleep, Duration};
+
+        let (manager, session_ids) = idle_sessions(1).await;
+        let session = manager.get_session(&session_ids[0]).await.unwrap();
+        let mut client = connect(manager).await;
+
+        client
+            .write_message(&JsonRpcMessage::Request(JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                id: json!(1),
+                method: "initialize".to_string(),
+                params: Some(json!({
+                    "protocolVersion": "2025-06-18",
+                    "capabilities": {"experimental": {"stateNotifications": false}}
+                })),
+            }))
+            .await
+            .unwrap();
+        match client.read_message().await.unwrap() {
+            JsonRpcMessage::Response(r) => {
+                assert_eq!(r.result.unwrap()["protocolVersion"], "2025-06-18")
+            }
+            other => panic!("Expected initialize response, got: {:?}", other),
+        }
+
+        session.state.write().await.set_state(DebugState::Running);
+        sleep(Duration::from_millis(100)).await;
+        client
+            .write_message(&tools_call(
+                2,
+                "debugger_session_state",
+                json!({"sessionId": session_ids[0]}),
+            ))
+            .await
+            .unwrap();
+
+        // Nothing was pushed ahead of the response
+        match client.read_message().await.unwrap() {
+            JsonRpcMessage::Response(r) => assert_eq!(r.id, json!(2)),
+            other => panic!("Expected Response, got: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_notifications_get_no_response() {
+        use protocol::{JsonRpcNotification, JsonRpcRequest};
+        use serde_json::json;
+        use tokio::net::TcpStream;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(McpServer::listen(listener, Arc::new(SessionManager::new())));
+
+        let mut client = TcpTransport::new(TcpStream::connect(addr).await.unwrap());
+        client
+            .write_message(&JsonRpcMessage::Notification(JsonRpcNotification {
+                jsonrpc: "2.0".to_string(),
+                method: "notifications/initialized".to_string(),
+                params: None,
+            }))
+            .await
+            .unwrap();
+        client
+            .write_message(&JsonRpcMessage::Request(JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                id: json!(1),
+                method: "tools/list".to_string(),
+                params: None,
+            }))
+            .await
+            .unwrap();
+
+        // The first thing on the wire is the tools/list response
+        match client.read_message().await.unwrap() {
+            JsonRpcMessage::Response(r) => assert_eq!(r.id, json!(1)),
+            other => panic!("Expected Response, got: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tcp_clients_share_session_manager() {
+        use protocol::JsonRpcRequest;
+        use serde_json::json;
+        use tokio::net::TcpStream;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(McpServer::listen(listener, Arc::new(SessionManager::new())));
+
+        let request = |method: &str| {
+            JsonRpcMessage::Request(JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                id: json!(1),
+                method: method.to_string(),
+                params: None,
+            })
+        };
+
+        // The first client leaving must not take the listener down
+        let mut first = TcpTransport::new(TcpStream::connect(addr).await.unwrap());
+        first.write_message(&request("tools/list")).await.unwrap();
+        assert!(matches!(
+            first.read_message().await.unwrap(),
+            JsonRpcMessage::Response(r) if r.error.is_none()
+        ));
+        drop(first);
+
+        let mut second = TcpTransport::new(TcpStream::connect(addr).await.unwrap());
+        second
+            .write_message(&request("resources/list"))
+            .await
+            .unwrap();
+        match second.read_message().await.unwrap() {
+            JsonRpcMessage::Response(r) => assert!(r.result.is_some()),
+            other => panic!("Expected Response, got: {:?}", other),
+        }
+    }
 }
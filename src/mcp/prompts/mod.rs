@@ -0,0 +1,400 @@
+//! MCP prompts: canned debugging workflows (`prompts/list`, `prompts/get`)
+//!
+//! Each prompt turns a few arguments (program, language, line, ...) into a
+//! user message that walks the agent through one workflow with the actual
+//! tool names, so clients can offer "debug this" commands without the agent
+//! having to read the workflow documentation first.
+
+use crate::{Error, Result};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+/// A prompt as listed by prompts/list
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Prompt {
+    pub name: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub arguments: Vec<PromptArgument>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PromptArgument {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub required: bool,
+}
+
+const PROGRAM: PromptArgument = PromptArgument {
+    name: "program",
+    description: "Absolute path to the program (or test file) to debug",
+    required: true,
+};
+
+const LANGUAGE: PromptArgument = PromptArgument {
+    name: "language",
+    description: "python, ruby, nodejs, go, rust, c, cpp or bash; guessed from the program's extension when omitted",
+    required: false,
+};
+
+const ARGS: PromptArgument = PromptArgument {
+    name: "args",
+    description: "Command line arguments for the program, separated by spaces",
+    required: false,
+};
+
+/// Language debugger_start expects for a program, from its extension
+fn language_for(program: &str) -> Option<&'static str> {
+    let extension = std::path::Path::new(program).extension()?.to_str()?;
+    Some(match extension {
+        "py" => "python",
+        "rb" => "ruby",
+        "js" | "mjs" | "cjs" | "ts" => "nodejs",
+        "go" => "go",
+        "rs" => "rust",
+        "c" => "c",
+        "cc" | "cpp" | "cxx" => "cpp",
+        "sh" | "bash" => "bash",
+        _ => return None,
+    })
+}
+
+/// Arguments of a prompts/get call
+struct PromptArguments<'a> {
+    prompt: &'static str,
+    arguments: &'a Map<String, Value>,
+}
+
+impl PromptArguments<'_> {
+    fn optional(&self, name: &str) -> Option<&str> {
+        self.arguments
+            .get(name)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+
+    fn required(&self, name: &str) -> Result<&str> {
+        self.optional(name).ok_or_else(|| {
+            Error::InvalidRequest(format!(
+                "Prompt {} requires the '{}' argument",
+                self.prompt, name
+            ))
+        })
+    }
+
+    fn line(&self) -> Result<u32> {
+        let line = self.required("line")?;
+        match line.parse::<u32>() {
+            Ok(line) if line > 0 => Ok(line),
+            _ => Err(Error::InvalidRequest(format!(
+                "Prompt {}: line must be a positive integer, got '{}'",
+                self.prompt, line
+            ))),
+        }
+    }
+
+    fn language(&self, program: &str) -> Result<String> {
+        match self.optional("language") {
+            Some(language) => Ok(language.to_string()),
+            None => language_for(program).map(str::to_string).ok_or_else(|| {
+                Error::InvalidRequest(format!(
+                    "Prompt {}: cannot tell the language of {}; pass the 'language' argument",
+                    self.prompt, program
+                ))
+            }),
+        }
+    }
+
+    /// The program's command line arguments as a JSON array for debugger_start
+    fn args_json(&self) -> String {
+        let args: Vec<&str> = self
+            .optional("args")
+            .map(|args| args.split_whitespace().collect())
+            .unwrap_or_default();
+        json!(args).to_string()
+    }
+}
+
+/// Serves the debugging prompts
+#[derive(Debug, Default)]
+pub struct PromptsHandler;
+
+impl PromptsHandler {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn list_prompts(&self) -> Vec<Prompt> {
+        vec![
+            Prompt {
+                name: "breakpoint-investigation",
+                title: "Investigate a Line",
+                description: "Stop at a line and find out why a value there is wrong",
+                arguments: vec![
+                    PROGRAM,
+                    PromptArgument {
+                        name: "line",
+                        description: "Line (1-indexed) where the value is wrong",
+                        required: true,
+                    },
+                    PromptArgument {
+                        name: "variable",
+                        description: "Variable or expression whose value is wrong",
+                        required: false,
+                    },
+                    PromptArgument {
+                        name: "sourcePath",
+                        description: "File containing the line, when it is not the program itself",
+                        required: false,
+                    },
+                    LANGUAGE,
+                    ARGS,
+                ],
+            },
+            Prompt {
+                name: "crash-analysis",
+                title: "Analyze a Crash",
+                description:
+                    "Run a program until it crashes or exits with an error and find the cause",
+                arguments: vec![
+                    PROGRAM,
+                    LANGUAGE,
+                    ARGS,
+                    PromptArgument {
+                        name: "symptom",
+                        description: "What goes wrong, e.g. the error message",
+                        required: false,
+                    },
+                ],
+            },
+            Prompt {
+                name: "step-through-function",
+                title: "Step Through a Function",
+                description: "Stop when a function is called and follow it line by line",
+                arguments: vec![
+                    PROGRAM,
+                    PromptArgument {
+                        name: "function",
+                        description: "Name of the function to step through",
+                        required: true,
+                    },
+                    LANGUAGE,
+                    ARGS,
+                ],
+            },
+        ]
+    }
+
+    /// The messages of prompt `name` for `arguments` (prompts/get result)
+    pub fn get_prompt(&self, name: &str, arguments: &Map<String, Value>) -> Result<Value> {
+        let prompt = self
+            .list_prompts()
+            .into_iter()
+            .find(|prompt| prompt.name == name)
+            .ok_or_else(|| Error::InvalidRequest(format!("Unknown prompt: {}", name)))?;
+        let args = PromptArguments {
+            prompt: prompt.name,
+            arguments,
+        };
+
+        let text = match name {
+            "breakpoint-investigation" => Self::breakpoint_investigation(&args)?,
+            "crash-analysis" => Self::crash_analysis(&args)?,
+            _ => Self::step_through_function(&args)?,
+        };
+        Ok(json!({
+            "description": prompt.description,
+            "messages": [{
+                "role": "user",
+                "content": {"type": "text", "text": text}
+            }]
+        }))
+    }
+
+    fn breakpoint_investigation(args: &PromptArguments) -> Result<String> {
+        let program = args.required("program")?;
+        let line = args.line()?;
+        let language = args.language(program)?;
+        let source_path = args.optional("sourcePath").unwrap_or(program);
+        let subject = match args.optional("variable") {
+            Some(variable) => format!("`{}` has the wrong value", variable),
+            None => "something is wrong".to_string(),
+        };
+        let inspect = match args.optional("variable") {
+            Some(variable) => format!(
+                "Evaluate `{}` with debugger_evaluate, then the expressions it is computed from.",
+                variable
+            ),
+            None => "Read the locals in the snapshot and evaluate the expressions on the line with debugger_evaluate.".to_string(),
+        };
+
+        Ok(format!(
+            "At line {line} of {source_path}, {subject}. Find out why using the debugger tools.\n\n\
+             1. Start the program stopped at that line: call debugger_start with \
+             {{\"language\": \"{language}\", \"program\": \"{program}\", \"args\": {args_json}, \
+             \"breakpoints\": [{{\"sourcePath\": \"{source_path}\", \"line\": {line}}}]}}.\n\
+             2. Call debugger_wait_for_stop with the returned sessionId. If it reports that the \
+             program exited instead, the line was never reached: say so and check \
+             debugger_get_output.\n\
+             3. Call debugger_snapshot to see the stack, the local variables and recent output.\n\
+             4. {inspect}\n\
+             5. If the value is already wrong on arrival, go up the stack (debugger_stack_trace, \
+             then debugger_evaluate with that frameId) or restart with a breakpoint earlier in \
+             the code, and narrow down where it first goes wrong. Step with debugger_step_over \
+             and debugger_step_into to watch it change.\n\
+             6. Call debugger_disconnect when done, then explain the cause and suggest a fix.",
+            args_json = args.args_json(),
+        ))
+    }
+
+    fn crash_analysis(args: &PromptArguments) -> Result<String> {
+        let program = args.required("program")?;
+        let language = args.language(program)?;
+        let symptom = match args.optional("symptom") {
+            Some(symptom) => format!(" The symptom: {}.", symptom),
+            None => String::new(),
+        };
+
+        Ok(format!(
+            "{program} crashes or fails.{symptom} Find the cause using the debugger tools.\n\n\
+             1. Call debugger_start with {{\"language\": \"{language}\", \"program\": \
+             \"{program}\", \"args\": {args_json}}}.\n\
+             2. Call debugger_wait_for_stop with the returned sessionId. An exception or signal \
+             stops the program where it was raised.\n\
+             3. If it stopped: call debugger_snapshot for the stack, locals and output, then \
+             read the frames with debugger_stack_trace and inspect suspicious values in the \
+             failing frame and its callers with debugger_evaluate.\n\
+             4. If it exited instead: read debugger_get_output for the error and stack trace \
+             it printed, then start again with a breakpoint (the 'breakpoints' argument of \
+             debugger_start) shortly before the failing line and inspect the state there.\n\
+             5. Call debugger_disconnect when done, then explain the root cause (not just the \
+             line that failed) and suggest a fix.",
+            args_json = args.args_json(),
+        ))
+    }
+
+    fn step_through_function(args: &PromptArguments) -> Result<String> {
+        let program = args.required("program")?;
+        let function = args.required("function")?;
+        let language = args.language(program)?;
+
+        Ok(format!(
+            "Walk through the function `{function}` of {program} line by line and explain what \
+             it does with real values.\n\n\
+             1. Call debugger_start with {{\"language\": \"{language}\", \"program\": \
+             \"{program}\", \"args\": {args_json}, \"stopOnEntry\": true}} and wait with \
+             debugger_wait_for_stop.\n\
+             2. Call debugger_set_function_breakpoints with {{\"breakpoints\": [{{\"name\": \
+             \"{function}\"}}]}}. If the adapter does not support function breakpoints, find the \
+             function's first line and use debugger_set_breakpoint instead.\n\
+             3. Call debugger_continue with waitForStop: true to stop when `{function}` is called.\n\
+             4. Step with debugger_step_over (debugger_step_into for calls worth following); \
+             after each step call debugger_snapshot or debugger_evaluate to see how the \
+             variables change.\n\
+             5. When the function returns (debugger_step_out), note the return value in the \
+             caller, then call debugger_disconnect and summarize the function's behavior.",
+            args_json = args.args_json(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arguments(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    fn text(result: &Value) -> &str {
+        result["messages"][0]["content"]["text"].as_str().unwrap()
+    }
+
+    #[test]
+    fn test_prompts_substitute_arguments() {
+        let handler = PromptsHandler::new();
+        let names: Vec<&str> = handler.list_prompts().iter().map(|p| p.name).collect();
+        assert_eq!(
+            names,
+            vec![
+                "breakpoint-investigation",
+                "crash-analysis",
+                "step-through-function"
+            ]
+        );
+
+        let result = handler
+            .get_prompt(
+                "breakpoint-investigation",
+                &arguments(json!({"program": "/app/main.py", "line": "42",
+                                  "variable": "total", "args": "--fast -n 3"})),
+            )
+            .unwrap();
+        assert_eq!(result["messages"][0]["role"], "user");
+        let message = text(&result);
+        assert!(message.starts_with("At line 42 of /app/main.py, `total` has the wrong value."));
+        // Language guessed from the extension, arguments split into an array
+        assert!(message.contains(
+            "{\"language\": \"python\", \"program\": \"/app/main.py\", \"args\": [\"--fast\",\"-n\",\"3\"], \
+             \"breakpoints\": [{\"sourcePath\": \"/app/main.py\", \"line\": 42}]}"
+        ));
+        assert!(message.contains("Evaluate `total` with debugger_evaluate"));
+
+        let result = handler
+            .get_prompt(
+                "step-through-function",
+                &arguments(
+                    json!({"program": "/src/main", "language": "rust", "function": "parse"}),
+                ),
+            )
+            .unwrap();
+        assert!(text(&result).contains("[{\"name\": \"parse\"}]"));
+        assert!(text(&result).contains("\"language\": \"rust\", \"program\": \"/src/main\""));
+
+        let result = handler
+            .get_prompt(
+                "crash-analysis",
+                &arguments(json!({"program": "/app/server.rb", "symptom": "NoMethodError"})),
+            )
+            .unwrap();
+        assert!(text(&result).contains("The symptom: NoMethodError."));
+        assert!(text(&result).contains("\"language\": \"ruby\""));
+    }
+
+    #[test]
+    fn test_prompt_argument_errors() {
+        let handler = PromptsHandler::new();
+        let cases = [
+            (
+                "breakpoint-investigation",
+                json!({"program": "/app/main.py"}),
+                "'line'",
+            ),
+            (
+                "breakpoint-investigation",
+                json!({"program": "/app/main.py", "line": "0"}),
+                "positive integer",
+            ),
+            (
+                "crash-analysis",
+                json!({"program": "/app/main"}),
+                "'language'",
+            ),
+            (
+                "step-through-function",
+                json!({"program": "/app/a.go"}),
+                "'function'",
+            ),
+            ("missing", json!({}), "Unknown prompt"),
+        ];
+        for (name, args, expected) in cases {
+            match handler.get_prompt(name, &arguments(args)) {
+                Err(Error::InvalidRequest(message)) => {
+                    assert!(message.contains(expected), "{}: {}", name, message)
+                }
+                other => panic!("{}: expected an error, got {:?}", name, other),
+            }
+        }
+    }
+}
@@ -1,13 +1,13 @@
+use super::prompts::PromptsHandler;
 use super::resources::ResourcesHandler;
 use super::tools::ToolsHandler;
+use crate::debug::{ResourceChange, StateChange};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tracing::{debug, warn};
 
-#[cfg(test)]
-use tokio::sync::RwLock;
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum JsonRpcMessage {
@@ -51,10 +51,239 @@ pub struct JsonRpcError {
     pub data: Option<Value>,
 }
 
+/// JSON-RPC error code for a request the client cancelled (as in LSP)
+pub const REQUEST_CANCELLED: i32 = -32800;
+
+/// Id of the request a cancellation notification refers to
+///
+/// Accepts MCP's `notifications/cancelled` (`params.requestId`) as well as the
+/// LSP-style `$/cancelRequest` (`params.id`).
+pub fn cancelled_request_id(notif: &JsonRpcNotification) -> Option<Value> {
+    let key = match notif.method.as_str() {
+        "notifications/cancelled" => "requestId",
+        "$/cancelRequest" => "id",
+        _ => return None,
+    };
+    notif.params.as_ref()?.get(key).cloned()
+}
+
+/// MCP protocol versions the server speaks, oldest first
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2024-11-05", "2025-03-26", "2025-06-18"];
+
+/// Experimental client capability opting into `debugger/stateChanged`
+pub const STATE_NOTIFICATIONS_CAPABILITY: &str = "stateNotifications";
+
+/// Experimental client capability opting into `notifications/resources/updated`
+/// for session resources
+pub const RESOURCE_UPDATES_CAPABILITY: &str = "resourceUpdates";
+
+/// Protocol version to answer a client requesting `requested` with: the
+/// newest supported version not newer than it
+///
+/// Clients that send no version predate negotiation and get the oldest one.
+/// Versions are dates (YYYY-MM-DD), so they compare as strings.
+pub fn negotiate_version(requested: Option<&str>) -> std::result::Result<&'static str, String> {
+    let Some(requested) = requested else {
+        return Ok(SUPPORTED_PROTOCOL_VERSIONS[0]);
+    };
+    let is_date = requested.len() == 10
+        && requested.chars().enumerate().all(|(i, c)| {
+            if i == 4 || i == 7 {
+                c == '-'
+            } else {
+                c.is_ascii_digit()
+            }
+        });
+    let version = SUPPORTED_PROTOCOL_VERSIONS
+        .iter()
+        .rev()
+        .find(|version| is_date && **version <= requested);
+    version.copied().ok_or_else(|| {
+        format!(
+            "Unsupported protocol version '{}'; this server supports {}",
+            requested,
+            SUPPORTED_PROTOCOL_VERSIONS.join(", ")
+        )
+    })
+}
+
+/// Outcome of the initialize handshake
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Negotiation {
+    /// Version both sides speak
+    pub protocol_version: String,
+    /// Version the client asked for, if it sent one
+    pub requested_version: Option<String>,
+    /// The client's `clientInfo` (name and version)
+    pub client_info: Option<Value>,
+    pub client_capabilities: Value,
+}
+
+impl Negotiation {
+    /// Whether the client opted into notifications with `method`
+    ///
+    /// Our notifications are not part of the MCP core, so clients opt in
+    /// through `capabilities.experimental` (`true` or an object).
+    pub fn accepts_notification(&self, method: &str) -> bool {
+        let capability = match method {
+            "debugger/stateChanged" => STATE_NOTIFICATIONS_CAPABILITY,
+            "notifications/resources/updated" => RESOURCE_UPDATES_CAPABILITY,
+            _ => return false,
+        };
+        match &self.client_capabilities["experimental"][capability] {
+            Value::Bool(enabled) => *enabled,
+            Value::Object(_) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Negotiation shared by a connection's protocol and resources handlers;
+/// empty until the client has initialized
+pub type SharedNegotiation = Arc<std::sync::RwLock<Option<Negotiation>>>;
+
+/// Capabilities advertised in the initialize result
+pub fn server_capabilities() -> Value {
+    serde_json::json!({
+        "tools": {},
+        "resources": {"subscribe": true},
+        "prompts": {},
+        "experimental": {
+            STATE_NOTIFICATIONS_CAPABILITY: {},
+            RESOURCE_UPDATES_CAPABILITY: {},
+        },
+    })
+}
+
+/// Error response sent in place of a cancelled request's result
+pub fn cancelled_response(id: Value) -> JsonRpcResponse {
+    JsonRpcResponse {
+        jsonrpc: "2.0".to_string(),
+        id,
+        result: None,
+        error: Some(JsonRpcError {
+            code: REQUEST_CANCELLED,
+            message: "Request cancelled".to_string(),
+            data: Some(serde_json::json!({
+                "code": "REQUEST_CANCELLED",
+                "retryable": true
+            })),
+        }),
+    }
+}
+
+/// What a request asks for, for logs and errors: the tool name of a
+/// `tools/call`, otherwise the method
+pub fn request_subject(req: &JsonRpcRequest) -> String {
+    match req
+        .params
+        .as_ref()
+        .and_then(|params| params.get("name"))
+        .and_then(Value::as_str)
+    {
+        Some(tool) if req.method == "tools/call" => tool.to_string(),
+        _ => req.method.clone(),
+    }
+}
+
+/// Internal error answering a request whose handler panicked
+pub fn panic_response(id: Value, subject: &str, panic: &str) -> JsonRpcResponse {
+    let error = crate::Error::Internal(format!("{} panicked: {}", subject, panic));
+    let mut data = error.data();
+    data["panic"] = Value::from(panic);
+    JsonRpcResponse {
+        jsonrpc: "2.0".to_string(),
+        id,
+        result: None,
+        error: Some(JsonRpcError {
+            code: error.error_code(),
+            message: error.to_string(),
+            data: Some(data),
+        }),
+    }
+}
+
+/// Error for a request arriving after the client asked the server to shut down
+pub fn shutting_down_response(id: Value) -> JsonRpcResponse {
+    JsonRpcResponse {
+        jsonrpc: "2.0".to_string(),
+        id,
+        result: None,
+        error: Some(JsonRpcError {
+            code: -32600,
+            message: "Server is shutting down".to_string(),
+            data: Some(serde_json::json!({
+                "code": "SHUTTING_DOWN",
+                "retryable": false
+            })),
+        }),
+    }
+}
+
+/// `notifications/resources/updated` for the resource `uri`
+pub fn resource_updated(uri: String) -> JsonRpcNotification {
+    JsonRpcNotification {
+        jsonrpc: "2.0".to_string(),
+        method: "notifications/resources/updated".to_string(),
+        params: Some(serde_json::json!({ "uri": uri })),
+    }
+}
+
+/// Notification announcing a change to the session list or a session's
+/// breakpoints
+pub fn resource_change_notification(change: &ResourceChange) -> JsonRpcNotification {
+    match change {
+        ResourceChange::Sessions => resource_updated("debugger://sessions".to_string()),
+        ResourceChange::Breakpoints(session_id) => {
+            resource_updated(format!("debugger://sessions/{}", session_id))
+        }
+    }
+}
+
+/// Notifications announcing a session state change
+///
+/// `notifications/resources/updated` tells resource-aware clients to re-read
+/// the session resource (and, on a stop, its stack trace);
+/// `debugger/stateChanged` carries the change itself so simple clients need
+/// no extra round trip.
+pub fn state_change_notifications(change: &StateChange) -> Vec<JsonRpcNotification> {
+    let mut state_changed = serde_json::json!({
+        "sessionId": change.session_id,
+        "oldState": change.old_state.name(),
+        "newState": change.new_state.name(),
+    });
+    if let Some(stop) = &change.stop {
+        state_changed["stop"] = serde_json::to_value(stop).unwrap_or(Value::Null);
+    }
+    if let Some(reason) = &change.reason {
+        state_changed["reason"] = serde_json::json!(reason);
+    }
+
+    let mut notifications = vec![resource_updated(format!(
+        "debugger://sessions/{}",
+        change.session_id
+    ))];
+    if change.stop.is_some() {
+        notifications.push(resource_updated(format!(
+            "debugger://sessions/{}/stackTrace",
+            change.session_id
+        )));
+    }
+    notifications.push(JsonRpcNotification {
+        jsonrpc: "2.0".to_string(),
+        method: "debugger/stateChanged".to_string(),
+        params: Some(state_changed),
+    });
+    notifications
+}
+
 pub struct ProtocolHandler {
-    initialized: bool,
+    initialized: AtomicBool,
     tools_handler: Option<Arc<ToolsHandler>>,
     resources_handler: Option<Arc<ResourcesHandler>>,
+    prompts_handler: Option<Arc<PromptsHandler>>,
+    negotiation: SharedNegotiation,
 }
 
 impl Default for ProtocolHandler {
@@ -66,12 +295,56 @@ impl Default for ProtocolHandler {
 impl ProtocolHandler {
     pub fn new() -> Self {
         Self {
-            initialized: false,
+            initialized: AtomicBool::new(false),
             tools_handler: None,
             resources_handler: None,
+            prompts_handler: None,
+            negotiation: SharedNegotiation::default(),
         }
     }
 
+    /// Whether the client has completed the initialize handshake
+    pub fn is_initialized(&self) -> bool {
+        self.initialized.load(Ordering::SeqCst)
+    }
+
+    /// What was agreed on in the initialize handshake, once it completed
+    pub fn negotiation(&self) -> Option<Negotiation> {
+        self.negotiation
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Handle to the negotiation, for other handlers of the same connection
+    pub fn shared_negotiation(&self) -> SharedNegotiation {
+        Arc::clone(&self.negotiation)
+    }
+
+    /// Whether a notification with `method` may be sent to the client: only
+    /// after initialize, and only if the client opted into it
+    pub fn accepts_notification(&self, method: &str) -> bool {
+        self.negotiation()
+            .is_some_and(|negotiation| negotiation.accepts_notification(method))
+    }
+
+    /// Whether `notif` may be sent to the client: only after initialize, and
+    /// only resource updates for URIs it subscribed to and notifications it
+    /// opted into
+    pub fn accepts(&self, notif: &JsonRpcNotification) -> bool {
+        let Some(negotiation) = self.negotiation() else {
+            return false;
+        };
+        let subscribed = notif.method == "notifications/resources/updated"
+            && notif
+                .params
+                .as_ref()
+                .and_then(|params| params["uri"].as_str())
+                .zip(self.resources_handler.as_ref())
+                .is_some_and(|(uri, handler)| handler.is_subscribed(uri));
+        subscribed || negotiation.accepts_notification(&notif.method)
+    }
+
     pub fn set_tools_handler(&mut self, handler: Arc<ToolsHandler>) {
         self.tools_handler = Some(handler);
     }
@@ -80,7 +353,16 @@ impl ProtocolHandler {
         self.resources_handler = Some(handler);
     }
 
-    pub async fn handle_message(&mut self, msg: JsonRpcMessage) -> JsonRpcMessage {
+    pub fn set_prompts_handler(&mut self, handler: Arc<PromptsHandler>) {
+        self.prompts_handler = Some(handler);
+    }
+
+    /// Handle one message
+    ///
+    /// Takes `&self` so the server can run several requests concurrently
+    /// (e.g. a debugger_session_state call while a debugger_wait_for_stop is
+    /// still polling).
+    pub async fn handle_message(&self, msg: JsonRpcMessage) -> JsonRpcMessage {
         match msg {
             JsonRpcMessage::Request(req) => {
                 JsonRpcMessage::Response(self.handle_request(req).await)
@@ -116,7 +398,7 @@ impl ProtocolHandler {
         }
     }
 
-    async fn handle_request(&mut self, req: JsonRpcRequest) -> JsonRpcResponse {
+    async fn handle_request(&self, req: JsonRpcRequest) -> JsonRpcResponse {
         debug!("Handling request: {}", req.method);
 
         match req.method.as_str() {
@@ -125,6 +407,10 @@ impl ProtocolHandler {
             "tools/call" => self.handle_tools_call(req).await,
             "resources/list" => self.handle_resources_list(req).await,
             "resources/read" => self.handle_resources_read(req).await,
+            "resources/subscribe" => self.handle_resources_subscription(req, true),
+            "resources/unsubscribe" => self.handle_resources_subscription(req, false),
+            "prompts/list" => self.handle_prompts_list(req),
+            "prompts/get" => self.handle_prompts_get(req),
             _ => JsonRpcResponse {
                 jsonrpc: "2.0".to_string(),
                 id: req.id,
@@ -138,20 +424,52 @@ impl ProtocolHandler {
         }
     }
 
-    async fn handle_initialize(&mut self, req: JsonRpcRequest) -> JsonRpcResponse {
+    async fn handle_initialize(&self, req: JsonRpcRequest) -> JsonRpcResponse {
         debug!("Handling initialize request");
 
-        self.initialized = true;
+        let params = req.params.unwrap_or(Value::Null);
+        let requested = params.get("protocolVersion").and_then(Value::as_str);
+        let protocol_version = match negotiate_version(requested) {
+            Ok(version) => version,
+            Err(message) => {
+                warn!("{}", message);
+                return JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    id: req.id,
+                    result: None,
+                    error: Some(JsonRpcError {
+                        code: -32602,
+                        message,
+                        data: Some(serde_json::json!({
+                            "requested": requested,
+                            "supported": SUPPORTED_PROTOCOL_VERSIONS,
+                        })),
+                    }),
+                };
+            }
+        };
+        let negotiation = Negotiation {
+            protocol_version: protocol_version.to_string(),
+            requested_version: requested.map(str::to_string),
+            client_info: params.get("clientInfo").cloned(),
+            client_capabilities: params
+                .get("capabilities")
+                .cloned()
+                .unwrap_or_else(|| serde_json::json!({})),
+        };
+        debug!(
+            "Negotiated protocol version {} with client capabilities {}",
+            protocol_version, negotiation.client_capabilities
+        );
+        *self.negotiation.write().unwrap_or_else(|e| e.into_inner()) = Some(negotiation);
+        self.initialized.store(true, Ordering::SeqCst);
 
         let result = serde_json::json!({
-            "protocolVersion": "2024-11-05",
-            "capabilities": {
-                "tools": {},
-                "resources": {},
-            },
+            "protocolVersion": protocol_version,
+            "capabilities": server_capabilities(),
             "serverInfo": {
                 "name": "debugger_mcp",
-                "version": "0.1.0",
+                "version": env!("CARGO_PKG_VERSION"),
             },
         });
 
@@ -163,7 +481,7 @@ impl ProtocolHandler {
         }
     }
 
-    async fn handle_notification(&mut self, _notif: JsonRpcNotification) {
+    pub async fn handle_notification(&self, _notif: JsonRpcNotification) {
         // Handle notifications here
     }
 
@@ -220,6 +538,11 @@ impl ProtocolHandler {
             }
         };
 
+        let session_id = arguments
+            .get("sessionId")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+
         match handler.handle_tool(name, arguments).await {
             Ok(result) => JsonRpcResponse {
                 jsonrpc: "2.0".to_string(),
@@ -232,16 +555,25 @@ impl ProtocolHandler {
                 })),
                 error: None,
             },
-            Err(e) => JsonRpcResponse {
-                jsonrpc: "2.0".to_string(),
-                id: req.id,
-                result: None,
-                error: Some(JsonRpcError {
-                    code: e.error_code(),
-                    message: e.to_string(),
-                    data: None,
-                }),
-            },
+            Err(e) => {
+                let mut data = e.data();
+                if let Some(session_id) = session_id {
+                    if let Some(state) = handler.session_state_name(&session_id).await {
+                        data["sessionState"] = Value::from(state);
+                    }
+                }
+
+                JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    id: req.id,
+                    result: None,
+                    error: Some(JsonRpcError {
+                        code: e.error_code(),
+                        message: e.to_string(),
+                        data: Some(data),
+                    }),
+                }
+            }
         }
     }
 
@@ -280,7 +612,7 @@ impl ProtocolHandler {
                 error: Some(JsonRpcError {
                     code: e.error_code(),
                     message: e.to_string(),
-                    data: None,
+                    data: Some(e.data()),
                 }),
             },
         }
@@ -351,11 +683,151 @@ impl ProtocolHandler {
                 error: Some(JsonRpcError {
                     code: e.error_code(),
                     message: e.to_string(),
+                    data: Some(e.data()),
+                }),
+            },
+        }
+    }
+
+    /// `resources/subscribe` (`subscribe`) or `resources/unsubscribe`
+    fn handle_resources_subscription(
+        &self,
+        req: JsonRpcRequest,
+        subscribe: bool,
+    ) -> JsonRpcResponse {
+        debug!("Handling {} request", req.method);
+
+        let uri = req
+            .params
+            .as_ref()
+            .and_then(|params| params.get("uri"))
+            .and_then(Value::as_str)
+            .unwrap_or("");
+        if uri.is_empty() {
+            return JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                id: req.id,
+                result: None,
+                error: Some(JsonRpcError {
+                    code: -32602,
+                    message: "Missing 'uri' parameter".to_string(),
+                    data: None,
+                }),
+            };
+        }
+        let Some(handler) = &self.resources_handler else {
+            return JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                id: req.id,
+                result: None,
+                error: Some(JsonRpcError {
+                    code: -32603,
+                    message: "Resources handler not initialized".to_string(),
                     data: None,
                 }),
+            };
+        };
+
+        let result = if subscribe {
+            handler.subscribe(uri)
+        } else {
+            handler.unsubscribe(uri);
+            Ok(())
+        };
+        match result {
+            Ok(()) => JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                id: req.id,
+                result: Some(serde_json::json!({})),
+                error: None,
+            },
+            Err(e) => JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                id: req.id,
+                result: None,
+                error: Some(JsonRpcError {
+                    code: e.error_code(),
+                    message: e.to_string(),
+                    data: Some(e.data()),
+                }),
+            },
+        }
+    }
+
+    fn handle_prompts_list(&self, req: JsonRpcRequest) -> JsonRpcResponse {
+        debug!("Handling prompts/list request");
+
+        let Some(handler) = &self.prompts_handler else {
+            return Self::prompts_handler_missing(req.id);
+        };
+        JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            id: req.id,
+            result: Some(serde_json::json!({
+                "prompts": handler.list_prompts()
+            })),
+            error: None,
+        }
+    }
+
+    fn handle_prompts_get(&self, req: JsonRpcRequest) -> JsonRpcResponse {
+        debug!("Handling prompts/get request");
+
+        let Some(handler) = &self.prompts_handler else {
+            return Self::prompts_handler_missing(req.id);
+        };
+        let params = req.params.unwrap_or(Value::Null);
+        let name = params.get("name").and_then(|v| v.as_str()).unwrap_or("");
+        if name.is_empty() {
+            return JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                id: req.id,
+                result: None,
+                error: Some(JsonRpcError {
+                    code: -32602,
+                    message: "Missing 'name' parameter".to_string(),
+                    data: None,
+                }),
+            };
+        }
+        let arguments = params
+            .get("arguments")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+
+        match handler.get_prompt(name, &arguments) {
+            Ok(result) => JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                id: req.id,
+                result: Some(result),
+                error: None,
+            },
+            Err(e) => JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                id: req.id,
+                result: None,
+                error: Some(JsonRpcError {
+                    code: e.error_code(),
+                    message: e.to_string(),
+                    data: Some(e.data()),
+                }),
             },
         }
     }
+
+    fn prompts_handler_missing(id: Value) -> JsonRpcResponse {
+        JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(JsonRpcError {
+                code: -32603,
+                message: "Prompts handler not initialized".to_string(),
+                data: None,
+            }),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -420,16 +892,98 @@ mod tests {
         assert!(notif.params.is_some());
     }
 
+    #[test]
+    fn test_cancelled_request_id() {
+        let notif = |method: &str, params: Value| JsonRpcNotification {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params: Some(params),
+        };
+
+        assert_eq!(
+            cancelled_request_id(&notif(
+                "notifications/cancelled",
+                json!({"requestId": 7, "reason": "user"})
+            )),
+            Some(json!(7))
+        );
+        assert_eq!(
+            cancelled_request_id(&notif("$/cancelRequest", json!({"id": "abc"}))),
+            Some(json!("abc"))
+        );
+        assert_eq!(
+            cancelled_request_id(&notif("notifications/initialized", json!({}))),
+            None
+        );
+        assert_eq!(
+            cancelled_request_id(&notif("notifications/cancelled", json!({}))),
+            None
+        );
+    }
+
+    #[test]
+    fn test_state_change_notifications() {
+        use crate::debug::{DebugState, StopContext};
+
+        let change = StateChange {
+            session_id: "abc".to_string(),
+            old_state: DebugState::Running,
+            new_state: DebugState::Stopped {
+                thread_id: 1,
+                reason: "breakpoint".to_string(),
+            },
+            stop: Some(StopContext::from_event_body(
+                &json!({"reason": "breakpoint", "threadId": 1, "hitBreakpointIds": [4]}),
+            )),
+            reason: None,
+        };
+
+        let notifications = state_change_notifications(&change);
+        assert_eq!(notifications.len(), 3);
+        assert_eq!(notifications[0].method, "notifications/resources/updated");
+        assert_eq!(
+            notifications[0].params,
+            Some(json!({"uri": "debugger://sessions/abc"}))
+        );
+        // A stop also changes the stack trace
+        assert_eq!(
+            notifications[1].params,
+            Some(json!({"uri": "debugger://sessions/abc/stackTrace"}))
+        );
+
+        let params = notifications[2].params.as_ref().unwrap();
+        assert_eq!(notifications[2].method, "debugger/stateChanged");
+        assert_eq!(params["sessionId"], "abc");
+        assert_eq!(params["oldState"], "Running");
+        assert_eq!(params["newState"], "Stopped");
+        assert_eq!(params["stop"]["hitBreakpointIds"], json!([4]));
+        assert!(params.get("reason").is_none());
+
+        let restarted = StateChange {
+            session_id: "abc".to_string(),
+            old_state: DebugState::Running,
+            new_state: DebugState::Initializing,
+            stop: None,
+            reason: Some("restarted: file changed".to_string()),
+        };
+        let params = state_change_notifications(&restarted)[1]
+            .params
+            .clone()
+            .unwrap();
+        assert_eq!(params["newState"], "Initializing");
+        assert_eq!(params["reason"], "restarted: file changed");
+    }
+
     #[test]
     fn test_protocol_handler_new() {
         let handler = ProtocolHandler::new();
-        assert!(!handler.initialized);
+        assert!(!handler.initialized.load(Ordering::SeqCst));
         assert!(handler.tools_handler.is_none());
     }
 
     #[tokio::test]
     async fn test_handle_initialize() {
-        let mut handler = ProtocolHandler::new();
+        let handler = ProtocolHandler::new();
         let req = JsonRpcRequest {
             jsonrpc: "2.0".to_string(),
             id: json!(1),
@@ -438,7 +992,7 @@ mod tests {
         };
 
         let response = handler.handle_initialize(req).await;
-        assert!(handler.initialized);
+        assert!(handler.initialized.load(Ordering::SeqCst));
         assert!(response.result.is_some());
         assert!(response.error.is_none());
 
@@ -447,6 +1001,92 @@ mod tests {
         assert_eq!(result["serverInfo"]["name"], "debugger_mcp");
     }
 
+    #[test]
+    fn test_negotiate_version() {
+        assert_eq!(negotiate_version(None), Ok("2024-11-05"));
+        for version in SUPPORTED_PROTOCOL_VERSIONS {
+            assert_eq!(negotiate_version(Some(version)), Ok(*version));
+        }
+        // Unknown versions get the newest one we have that is not newer
+        assert_eq!(negotiate_version(Some("2025-01-01")), Ok("2024-11-05"));
+        assert_eq!(negotiate_version(Some("2099-12-31")), Ok("2025-06-18"));
+
+        for unsupported in ["2024-10-07", "1.0", "", "latest", "2025-06-18x"] {
+            let error = negotiate_version(Some(unsupported)).unwrap_err();
+            assert!(error.contains("Unsupported protocol version"), "{}", error);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_initialize_negotiates_with_client() {
+        let initialize = |params: Option<Value>| JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: json!(1),
+            method: "initialize".to_string(),
+            params,
+        };
+
+        // A full client payload: version echoed, capabilities recorded
+        let handler = ProtocolHandler::new();
+        assert!(!handler.accepts_notification("debugger/stateChanged"));
+        let response = handler
+            .handle_initialize(initialize(Some(json!({
+                "protocolVersion": "2025-03-26",
+                "capabilities": {
+                    "roots": {"listChanged": true},
+                    "experimental": {"stateNotifications": {}}
+                },
+                "clientInfo": {"name": "inspector", "version": "1.2"}
+            }))))
+            .await;
+        let result = response.result.unwrap();
+        assert_eq!(result["protocolVersion"], "2025-03-26");
+        assert!(result["capabilities"]["prompts"].is_object());
+        assert!(result["capabilities"]["experimental"]["stateNotifications"].is_object());
+        let negotiation = handler.negotiation().unwrap();
+        assert_eq!(negotiation.requested_version.as_deref(), Some("2025-03-26"));
+        assert_eq!(negotiation.client_info.unwrap()["name"], "inspector");
+        assert_eq!(
+            negotiation.client_capabilities["roots"]["listChanged"],
+            true
+        );
+        assert!(handler.accepts_notification("debugger/stateChanged"));
+        assert!(!handler.accepts_notification("notifications/resources/updated"));
+        assert!(!handler.accepts_notification("notifications/progress"));
+
+        // Bare capabilities: initialized, but no notifications
+        let handler = ProtocolHandler::new();
+        let response = handler
+            .handle_initialize(initialize(Some(json!({"protocolVersion": "2024-11-05"}))))
+            .await;
+        assert!(response.error.is_none());
+        assert!(handler.is_initialized());
+        assert_eq!(
+            handler.negotiation().unwrap().client_capabilities,
+            json!({})
+        );
+        assert!(!handler.accepts_notification("debugger/stateChanged"));
+
+        // Incompatible version: rejected, not initialized
+        let handler = ProtocolHandler::new();
+        let response = handler
+            .handle_initialize(initialize(Some(json!({
+                "protocolVersion": "2023-01-01",
+                "capabilities": {"experimental": {"stateNotifications": true}}
+            }))))
+            .await;
+        let error = response.error.unwrap();
+        assert_eq!(error.code, -32602);
+        assert!(error.message.contains("2023-01-01"));
+        assert_eq!(
+            error.data.unwrap()["supported"],
+            json!(SUPPORTED_PROTOCOL_VERSIONS)
+        );
+        assert!(!handler.is_initialized());
+        assert!(handler.negotiation().is_none());
+        assert!(!handler.accepts_notification("debugger/stateChanged"));
+    }
+
     #[tokio::test]
     async fn test_handle_tools_list() {
         let handler = ProtocolHandler::new();
@@ -504,7 +1144,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_handle_unknown_method() {
-        let mut handler = ProtocolHandler::new();
+        let handler = ProtocolHandler::new();
         let req = JsonRpcRequest {
             jsonrpc: "2.0".to_string(),
             id: json!(5),
@@ -520,7 +1160,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_handle_notification_message() {
-        let mut handler = ProtocolHandler::new();
+        let handler = ProtocolHandler::new();
         let notif = JsonRpcNotification {
             jsonrpc: "2.0".to_string(),
             method: "test_notification".to_string(),
@@ -545,7 +1185,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_handle_response_message() {
-        let mut handler = ProtocolHandler::new();
+        let handler = ProtocolHandler::new();
         let resp = JsonRpcResponse {
             jsonrpc: "2.0".to_string(),
             id: json!(1),
@@ -571,7 +1211,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_handle_request_message_direct() {
-        let mut handler = ProtocolHandler::new();
+        let handler = ProtocolHandler::new();
         let req = JsonRpcRequest {
             jsonrpc: "2.0".to_string(),
             id: json!(1),
@@ -599,7 +1239,7 @@ mod tests {
     #[tokio::test]
     async fn test_tools_call_without_handler_set() {
         // Test line 192 - tools handler not initialized
-        let mut handler = ProtocolHandler::new();
+        let handler = ProtocolHandler::new();
         // Don't call set_tools_handler, so it's None
 
         let req = JsonRpcRequest {
@@ -628,7 +1268,7 @@ mod tests {
         use crate::debug::SessionManager;
         use crate::mcp::tools::ToolsHandler;
 
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
+        let manager = Arc::new(SessionManager::new());
         let tools_handler = Arc::new(ToolsHandler::new(manager));
 
         let mut handler = ProtocolHandler::new();
@@ -654,13 +1294,111 @@ mod tests {
         assert!(error.code != 0); // Should have an error code
     }
 
+    fn tools_call(name: &str, arguments: Value) -> JsonRpcRequest {
+        JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: json!(1),
+            method: "tools/call".to_string(),
+            params: Some(json!({"name": name, "arguments": arguments})),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tools_call_error_data_session_not_found() {
+        use crate::debug::SessionManager;
+        use crate::mcp::tools::ToolsHandler;
+
+        let manager = Arc::new(SessionManager::new());
+        let mut handler = ProtocolHandler::new();
+        handler.set_tools_handler(Arc::new(ToolsHandler::new(manager)));
+
+        let response = handler
+            .handle_request(tools_call(
+                "debugger_stack_trace",
+                json!({"sessionId": "missing"}),
+            ))
+            .await;
+
+        let error = response.error.unwrap();
+        assert_eq!(error.code, -32001);
+        // Unknown sessions have no state to report
+        assert_eq!(
+            error.data,
+            Some(json!({"code": "SESSION_NOT_FOUND", "retryable": false}))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tools_call_error_data_invalid_state_includes_session_state() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+        use crate::debug::SessionManager;
+        use crate::mcp::tools::ToolsHandler;
+
+        let client = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "test.py".to_string(), client)
+            .await
+            .unwrap();
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+
+        let mut handler = ProtocolHandler::new();
+        handler.set_tools_handler(Arc::new(ToolsHandler::new(Arc::new(manager))));
+
+        let response = handler
+            .handle_request(tools_call(
+                "debugger_evaluate",
+                json!({"sessionId": session_id, "expression": "x"}),
+            ))
+            .await;
+
+        let error = response.error.unwrap();
+        assert_eq!(error.code, -32005);
+        assert_eq!(
+            error.data,
+            Some(json!({
+                "code": "INVALID_STATE",
+                "retryable": true,
+                "sessionState": "NotStarted"
+            }))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tools_call_error_data_invalid_arguments() {
+        use crate::debug::SessionManager;
+        use crate::mcp::tools::ToolsHandler;
+
+        let manager = Arc::new(SessionManager::new());
+        let mut handler = ProtocolHandler::new();
+        handler.set_tools_handler(Arc::new(ToolsHandler::new(manager)));
+
+        let invalid_args = handler
+            .handle_request(tools_call("debugger_start", json!({"program": "a.py"})))
+            .await;
+        assert_eq!(
+            invalid_args.error.unwrap().data.unwrap()["code"],
+            "INVALID_ARGUMENTS"
+        );
+
+        let unknown_tool = handler
+            .handle_request(tools_call("debugger_nope", json!({})))
+            .await;
+        let error = unknown_tool.error.unwrap();
+        assert_eq!(error.code, -32601);
+        assert_eq!(error.data.unwrap()["code"], "METHOD_NOT_FOUND");
+    }
+
     #[tokio::test]
     async fn test_tools_call_success_with_handler() {
         // Test line 207 - successful tool call
         use crate::debug::SessionManager;
         use crate::mcp::tools::ToolsHandler;
 
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
+        let manager = Arc::new(SessionManager::new());
         let tools_handler = Arc::new(ToolsHandler::new(manager));
 
         let mut handler = ProtocolHandler::new();
@@ -683,7 +1421,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_resources_list_without_handler() {
-        let mut handler = ProtocolHandler::new();
+        let handler = ProtocolHandler::new();
         // Don't set resources_handler
 
         let req = JsonRpcRequest {
@@ -705,7 +1443,7 @@ mod tests {
         use crate::debug::SessionManager;
         use crate::mcp::resources::ResourcesHandler;
 
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
+        let manager = Arc::new(SessionManager::new());
         let resources_handler = Arc::new(ResourcesHandler::new(manager));
 
         let mut handler = ProtocolHandler::new();
@@ -728,7 +1466,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_resources_read_without_handler() {
-        let mut handler = ProtocolHandler::new();
+        let handler = ProtocolHandler::new();
         // Don't set resources_handler
 
         let req = JsonRpcRequest {
@@ -752,7 +1490,7 @@ mod tests {
         use crate::debug::SessionManager;
         use crate::mcp::resources::ResourcesHandler;
 
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
+        let manager = Arc::new(SessionManager::new());
         let resources_handler = Arc::new(ResourcesHandler::new(manager));
 
         let mut handler = ProtocolHandler::new();
@@ -777,7 +1515,7 @@ mod tests {
         use crate::debug::SessionManager;
         use crate::mcp::resources::ResourcesHandler;
 
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
+        let manager = Arc::new(SessionManager::new());
         let resources_handler = Arc::new(ResourcesHandler::new(manager));
 
         let mut handler = ProtocolHandler::new();
@@ -802,7 +1540,7 @@ mod tests {
         use crate::debug::SessionManager;
         use crate::mcp::resources::ResourcesHandler;
 
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
+        let manager = Arc::new(SessionManager::new());
         let resources_handler = Arc::new(ResourcesHandler::new(manager));
 
         let mut handler = ProtocolHandler::new();
@@ -824,4 +1562,49 @@ mod tests {
         let result = response.result.unwrap();
         assert!(result["contents"].is_array());
     }
+
+    #[tokio::test]
+    async fn test_prompts_list_and_get() {
+        let mut handler = ProtocolHandler::new();
+        handler.set_prompts_handler(Arc::new(PromptsHandler::new()));
+
+        let response = handler
+            .handle_request(JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                id: json!(1),
+                method: "prompts/list".to_string(),
+                params: None,
+            })
+            .await;
+        let prompts = response.result.unwrap()["prompts"].clone();
+        assert_eq!(prompts[0]["name"], "breakpoint-investigation");
+        assert_eq!(prompts[0]["arguments"][0]["name"], "program");
+        assert_eq!(prompts[0]["arguments"][0]["required"], true);
+
+        let response = handler
+            .handle_request(JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                id: json!(2),
+                method: "prompts/get".to_string(),
+                params: Some(json!({
+                    "name": "crash-analysis",
+                    "arguments": {"program": "/app/main.go"}
+                })),
+            })
+            .await;
+        let result = response.result.unwrap();
+        let text = result["messages"][0]["content"]["text"].as_str().unwrap();
+        assert!(text.contains("\"language\": \"go\", \"program\": \"/app/main.go\""));
+
+        let response = handler
+            .handle_request(JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                id: json!(3),
+                method: "prompts/get".to_string(),
+                params: Some(json!({"name": "crash-analysis", "arguments": {}})),
+            })
+            .await;
+        let error = response.error.unwrap();
+        assert!(error.message.contains("'program'"));
+    }
 }
@@ -0,0 +1,294 @@
+//! Replay of a session transcript (`debugger_mcp replay <file>`)
+//!
+//! The tool calls of a transcript written by a recorded session (see
+//! [`crate::debug::recorder`]) are issued again, in order, either against a
+//! server running in this process or against one listening on TCP
+//! (`--connect`). Calls follow each other immediately: the time between the
+//! recorded calls is not reproduced, and recorded state changes are only
+//! reported for reference.
+//!
+//! Session IDs differ between runs, so the recorded `sessionId` of a
+//! debugger_start result is mapped to the new one for all later calls. A step
+//! diverges when it fails where the recorded call succeeded, or the other way
+//! round. Redacted argument values are sent as recorded (`[REDACTED]`).
+
+use super::protocol::{JsonRpcMessage, JsonRpcNotification, JsonRpcRequest};
+use super::tools::ToolsHandler;
+use super::transport::TcpTransport;
+use crate::debug::recorder::TranscriptEntry;
+use crate::debug::SessionManager;
+use crate::{Error, Result};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tracing::info;
+
+/// Where replayed tool calls go
+pub enum ReplayTarget {
+    /// A fresh session manager in this process
+    InProcess(ToolsHandler),
+    /// An MCP server over TCP
+    Remote {
+        transport: TcpTransport,
+        next_id: i64,
+    },
+}
+
+impl ReplayTarget {
+    pub fn in_process() -> Self {
+        let manager = Arc::new(SessionManager::new());
+        Self::InProcess(ToolsHandler::new(manager))
+    }
+
+    /// Connect to the server listening on `addr` and initialize the MCP session
+    pub async fn connect(addr: &str) -> Result<Self> {
+        let stream = TcpStream::connect(addr).await?;
+        let mut target = Self::Remote {
+            transport: TcpTransport::new(stream),
+            next_id: 1,
+        };
+        target
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": "2024-11-05",
+                    "capabilities": {},
+                    "clientInfo": {"name": "debugger_mcp replay", "version": env!("CARGO_PKG_VERSION")}
+                }),
+            )
+            .await?
+            .map_err(Error::InvalidState)?;
+        if let Self::Remote { transport, .. } = &mut target {
+            transport
+                .write_message(&JsonRpcMessage::Notification(JsonRpcNotification {
+                    jsonrpc: "2.0".to_string(),
+                    method: "notifications/initialized".to_string(),
+                    params: None,
+                }))
+                .await?;
+        }
+        Ok(target)
+    }
+
+    /// Call a tool; the inner error is the tool's error message, the outer
+    /// one a failure to reach the server
+    pub async fn call_tool(
+        &mut self,
+        name: &str,
+        arguments: Value,
+    ) -> Result<std::result::Result<Value, String>> {
+        match self {
+            Self::InProcess(handler) => Ok(handler
+                .handle_tool(name, arguments)
+                .await
+                .map_err(|e| e.to_string())),
+            Self::Remote { .. } => {
+                let result = self
+                    .request("tools/call", json!({"name": name, "arguments": arguments}))
+                    .await?;
+                Ok(result.map(|result| {
+                    // Tool results are JSON, pretty-printed into a text block
+                    result["content"][0]["text"]
+                        .as_str()
+                        .and_then(|text| serde_json::from_str(text).ok())
+                        .unwrap_or(result)
+                }))
+            }
+        }
+    }
+
+    /// Send a request to the remote server and wait for its response,
+    /// skipping notifications
+    async fn request(
+        &mut self,
+        method: &str,
+        params: Value,
+    ) -> Result<std::result::Result<Value, String>> {
+        let Self::Remote { transport, next_id } = self else {
+            return Err(Error::InvalidState(
+                "Not connected to a remote server".to_string(),
+            ));
+        };
+        let id = json!(*next_id);
+        *next_id += 1;
+        transport
+            .write_message(&JsonRpcMessage::Request(JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                id: id.clone(),
+                method: method.to_string(),
+                params: Some(params),
+            }))
+            .await?;
+        loop {
+            if let JsonRpcMessage::Response(response) = transport.read_message().await? {
+                if response.id != id {
+                    continue;
+                }
+                return Ok(match response.error {
+                    Some(error) => Err(error.message),
+                    None => Ok(response.result.unwrap_or(Value::Null)),
+                });
+            }
+        }
+    }
+}
+
+/// Outcome of one replayed tool call
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayStep {
+    pub tool: String,
+    pub recorded_ok: bool,
+    pub replayed_ok: bool,
+    /// Error of the replayed call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub diverged: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayReport {
+    pub steps: Vec<ReplayStep>,
+    /// State changes in the transcript (not replayed)
+    pub recorded_state_changes: usize,
+    pub divergences: usize,
+}
+
+/// Issue the tool calls of `entries` against `target`, in order
+///
+/// Sessions still open at the end of an in-process replay are disconnected.
+pub async fn replay(
+    entries: &[TranscriptEntry],
+    target: &mut ReplayTarget,
+) -> Result<ReplayReport> {
+    let mut report = ReplayReport::default();
+    // Recorded session ID -> replayed session ID
+    let mut session_ids: HashMap<String, String> = HashMap::new();
+    let mut open_sessions: Vec<String> = Vec::new();
+
+    for entry in entries {
+        let (tool, arguments, result, error) = match entry {
+            TranscriptEntry::ToolCall {
+                tool,
+                arguments,
+                result,
+                error,
+                ..
+            } => (tool, arguments, result, error),
+            TranscriptEntry::StateChange { .. } => {
+                report.recorded_state_changes += 1;
+                continue;
+            }
+        };
+
+        let mut arguments = arguments.clone();
+        if let Some(Value::String(id)) = arguments.get_mut("sessionId") {
+            if let Some(mapped) = session_ids.get(id.as_str()) {
+                *id = mapped.clone();
+            }
+        }
+
+        info!("🔁 Replaying {}", tool);
+        let replayed = target.call_tool(tool, arguments.clone()).await?;
+
+        let recorded_id = result
+            .as_ref()
+            .and_then(|result| result.get("sessionId"))
+            .and_then(Value::as_str);
+        let replayed_id = replayed
+            .as_ref()
+            .ok()
+            .and_then(|result| result.get("sessionId"))
+            .and_then(Value::as_str);
+        if let (Some(recorded_id), Some(replayed_id)) = (recorded_id, replayed_id) {
+            if !open_sessions.iter().any(|id| id == replayed_id) {
+                open_sessions.push(replayed_id.to_string());
+            }
+            session_ids.insert(recorded_id.to_string(), replayed_id.to_string());
+        }
+        if tool == "debugger_disconnect" && replayed.is_ok() {
+            if let Some(id) = arguments.get("sessionId").and_then(Value::as_str) {
+                open_sessions.retain(|open| open != id);
+            }
+        }
+
+        let recorded_ok = error.is_none();
+        let replayed_ok = replayed.is_ok();
+        let diverged = recorded_ok != replayed_ok;
+        if diverged {
+            report.divergences += 1;
+        }
+        report.steps.push(ReplayStep {
+            tool: tool.clone(),
+            recorded_ok,
+            replayed_ok,
+            error: replayed.err(),
+            diverged,
+        });
+    }
+
+    if matches!(target, ReplayTarget::InProcess(_)) {
+        for session_id in open_sessions {
+            let _ = target
+                .call_tool("debugger_disconnect", json!({"sessionId": session_id}))
+                .await?;
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool_call(tool: &str, arguments: Value, result: Option<Value>) -> TranscriptEntry {
+        let error = result.is_none().then(|| "failed".to_string());
+        TranscriptEntry::ToolCall {
+            timestamp_ms: 0,
+            tool: tool.to_string(),
+            arguments,
+            result,
+            error,
+            duration_ms: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay_reports_divergences() {
+        let entries = vec![
+            tool_call("debugger_list_configs", json!({}), Some(json!({}))),
+            TranscriptEntry::StateChange {
+                timestamp_ms: 0,
+                from: json!("Running"),
+                to: json!("Terminated"),
+                stop: None,
+            },
+            // Recorded as failing, fails again
+            tool_call("debugger_continue", json!({"sessionId": "gone"}), None),
+            // Recorded as succeeding on a session that does not exist here
+            tool_call(
+                "debugger_session_state",
+                json!({"sessionId": "gone"}),
+                Some(json!({"state": "Running"})),
+            ),
+        ];
+
+        let mut target = ReplayTarget::in_process();
+        let report = replay(&entries, &mut target).await.unwrap();
+
+        assert_eq!(report.recorded_state_changes, 1);
+        assert_eq!(report.steps.len(), 3);
+        assert!(report.steps[0].replayed_ok && !report.steps[0].diverged);
+        assert!(!report.steps[1].replayed_ok && !report.steps[1].diverged);
+        assert!(report.steps[2].diverged);
+        assert!(report.steps[2]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("Session not found"));
+        assert_eq!(report.divergences, 1);
+    }
+}
@@ -1,9 +1,10 @@
 use crate::debug::SessionManager;
+use crate::mcp::protocol::{self, SharedNegotiation};
 use crate::{Error, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 
 mod documentation;
 pub use documentation::DocumentationHandler;
@@ -30,12 +31,16 @@ pub struct ResourceContents {
 
 /// Resource handler for MCP resources
 pub struct ResourcesHandler {
-    session_manager: Arc<RwLock<SessionManager>>,
+    session_manager: Arc<SessionManager>,
     documentation_handler: DocumentationHandler,
+    /// Initialize handshake of the connection, for debugger://server
+    negotiation: Option<SharedNegotiation>,
+    /// URIs the connection subscribed to with resources/subscribe
+    subscriptions: Mutex<HashSet<String>>,
 }
 
 impl ResourcesHandler {
-    pub fn new(session_manager: Arc<RwLock<SessionManager>>) -> Self {
+    pub fn new(session_manager: Arc<SessionManager>) -> Self {
         Self {
             session_manager,
             documentation_handler: DocumentationHandler::new(
@@ -43,19 +48,71 @@ impl ResourcesHandler {
                 "debugger-mcp",
                 "main",
             ),
+            negotiation: None,
+            subscriptions: Mutex::new(HashSet::new()),
         }
     }
 
+    /// Report the connection's initialize handshake in debugger://server
+    pub fn with_negotiation(mut self, negotiation: SharedNegotiation) -> Self {
+        self.negotiation = Some(negotiation);
+        self
+    }
+
+    /// Send `notifications/resources/updated` for `uri` from now on
+    ///
+    /// Only session resources change while the server runs: the session
+    /// list, a session's details (state and breakpoints) and its stack trace.
+    pub fn subscribe(&self, uri: &str) -> Result<()> {
+        let subscribable = uri == "debugger://sessions"
+            || uri
+                .strip_prefix("debugger://sessions/")
+                .is_some_and(|rest| {
+                    matches!(
+                        rest.split('/').collect::<Vec<_>>().as_slice(),
+                        [id] | [id, "stackTrace"] if !id.is_empty()
+                    )
+                });
+        if !subscribable {
+            return Err(Error::InvalidRequest(format!(
+                "Resource {} does not support subscriptions; subscribe to debugger://sessions, \
+                 debugger://sessions/{{sessionId}} or debugger://sessions/{{sessionId}}/stackTrace",
+                uri
+            )));
+        }
+        self.subscriptions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(uri.to_string());
+        Ok(())
+    }
+
+    /// Stop sending updates for `uri`; returns whether it was subscribed
+    pub fn unsubscribe(&self, uri: &str) -> bool {
+        self.subscriptions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(uri)
+    }
+
+    /// Whether the connection subscribed to `uri`
+    pub fn is_subscribed(&self, uri: &str) -> bool {
+        self.subscriptions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(uri)
+    }
+
     /// List all available resources
     pub async fn list_resources(&self) -> Result<Vec<Resource>> {
-        let manager = self.session_manager.read().await;
+        let manager = &self.session_manager;
         let session_ids = manager.list_sessions().await;
 
         let mut resources = vec![
             Resource {
                 uri: "debugger://sessions".to_string(),
                 name: "Debug Sessions".to_string(),
-                description: Some("List of all active debugging sessions".to_string()),
+                description: Some("List of all active debugging sessions with their state, stop reason, breakpoint count, debuggee process (PID) and timestamps".to_string()),
                 mime_type: Some("application/json".to_string()),
             },
             Resource {
@@ -83,6 +140,15 @@ impl ResourcesHandler {
                 ),
                 mime_type: Some("application/json".to_string()),
             },
+            Resource {
+                uri: "debugger://server".to_string(),
+                name: "Server Connection".to_string(),
+                description: Some(
+                    "Negotiated protocol version, client capabilities and enabled notifications"
+                        .to_string(),
+                ),
+                mime_type: Some("application/json".to_string()),
+            },
         ];
 
         // Add documentation resources
@@ -90,19 +156,79 @@ impl ResourcesHandler {
 
         // Add per-session resources
         for session_id in session_ids {
+            let short_id = Self::short_id(&session_id);
             resources.push(Resource {
                 uri: format!("debugger://sessions/{}", session_id),
-                name: format!("Session {}", &session_id[..8]),
+                name: format!("Session {}", short_id),
                 description: Some(format!("Details for debug session {}", session_id)),
                 mime_type: Some("application/json".to_string()),
             });
 
             resources.push(Resource {
                 uri: format!("debugger://sessions/{}/stackTrace", session_id),
-                name: format!("Stack Trace ({})", &session_id[..8]),
+                name: format!("Stack Trace ({})", short_id),
                 description: Some(format!("Call stack for session {}", session_id)),
                 mime_type: Some("application/json".to_string()),
             });
+
+            resources.push(Resource {
+                uri: format!("debugger://sessions/{}/snapshot", session_id),
+                name: format!("Snapshot ({})", short_id),
+                description: Some(format!(
+                    "State, stack, variables, output and breakpoints of session {}",
+                    session_id
+                )),
+                mime_type: Some("application/json".to_string()),
+            });
+
+            // Compiled languages (Rust, C, C++) also expose their build result
+            let Ok(session) = manager.get_session(&session_id).await else {
+                continue;
+            };
+            let (compiled, captured) = (session.build().is_some(), session.dap_log().is_some());
+            if compiled {
+                resources.push(Resource {
+                    uri: format!("debugger://sessions/{}/compilation", session_id),
+                    name: format!("Compilation ({})", short_id),
+                    description: Some(format!(
+                        "Build result and compiler warnings for session {}",
+                        session_id
+                    )),
+                    mime_type: Some("application/json".to_string()),
+                });
+            }
+            if captured {
+                resources.push(Resource {
+                    uri: format!("debugger://sessions/{}/dapLog", session_id),
+                    name: format!("DAP Log ({})", short_id),
+                    description: Some(format!(
+                        "DAP messages exchanged with the adapter of session {}",
+                        session_id
+                    )),
+                    mime_type: Some("application/json".to_string()),
+                });
+            }
+
+            // Frames of the stack fetched at the current stop
+            let state = session.state.read().await;
+            let frames = match state.state {
+                crate::debug::state::DebugState::Stopped { .. } => state.cached_stack(),
+                _ => None,
+            };
+            for frame in frames.unwrap_or_default() {
+                resources.push(Resource {
+                    uri: format!(
+                        "debugger://sessions/{}/frames/{}/scopes",
+                        session_id, frame.id
+                    ),
+                    name: format!("Scopes of {} ({})", frame.name, short_id),
+                    description: Some(format!(
+                        "Variable scopes of frame {} of session {}",
+                        frame.id, session_id
+                    )),
+                    mime_type: Some("application/json".to_string()),
+                });
+            }
         }
 
         Ok(resources)
@@ -124,6 +250,8 @@ impl ResourcesHandler {
         }
 
         let path = &uri["debugger://".len()..];
+        // Only variables pages take a query (start, count)
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
 
         if path == "sessions" {
             // List all sessions
@@ -137,6 +265,9 @@ impl ResourcesHandler {
         } else if path == "error-handling" {
             // Error handling guide
             Self::read_error_handling()
+        } else if path == "server" {
+            // This connection's initialize handshake
+            self.read_server()
         } else if let Some(rest) = path.strip_prefix("sessions/") {
             // Parse session-specific resources
             let parts: Vec<&str> = rest.split('/').collect();
@@ -151,6 +282,29 @@ impl ResourcesHandler {
                     let session_id = parts[0];
                     self.read_session_stack_trace(session_id).await
                 }
+                2 if parts[1] == "compilation" => {
+                    // debugger://sessions/{id}/compilation
+                    let session_id = parts[0];
+                    self.read_session_compilation(session_id).await
+                }
+                2 if parts[1] == "snapshot" => {
+                    // debugger://sessions/{id}/snapshot
+                    let session_id = parts[0];
+                    self.read_session_snapshot(session_id).await
+                }
+                2 if parts[1] == "dapLog" => {
+                    // debugger://sessions/{id}/dapLog
+                    let session_id = parts[0];
+                    self.read_session_dap_log(session_id).await
+                }
+                3 if parts[1] == "variables" => {
+                    // debugger://sessions/{id}/variables/{variablesReference}
+                    self.read_session_variables(parts[0], parts[2], query).await
+                }
+                4 if parts[1] == "frames" && parts[3] == "scopes" => {
+                    // debugger://sessions/{id}/frames/{frameId}/scopes
+                    self.read_frame_scopes(parts[0], parts[2]).await
+                }
                 _ => Err(Error::InvalidRequest(format!(
                     "Unknown resource path: {}",
                     path
@@ -161,6 +315,42 @@ impl ResourcesHandler {
         }
     }
 
+    /// Read the server resource: what was negotiated with this client
+    fn read_server(&self) -> Result<ResourceContents> {
+        let negotiation = self.negotiation.as_ref().and_then(|negotiation| {
+            negotiation
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone()
+        });
+        let notifications: serde_json::Map<String, Value> =
+            ["debugger/stateChanged", "notifications/resources/updated"]
+                .into_iter()
+                .map(|method| {
+                    let enabled = negotiation
+                        .as_ref()
+                        .is_some_and(|negotiation| negotiation.accepts_notification(method));
+                    (method.to_string(), json!(enabled))
+                })
+                .collect();
+
+        let server = json!({
+            "serverInfo": {"name": "debugger_mcp", "version": env!("CARGO_PKG_VERSION")},
+            "supportedProtocolVersions": protocol::SUPPORTED_PROTOCOL_VERSIONS,
+            "serverCapabilities": protocol::server_capabilities(),
+            "initialized": negotiation.is_some(),
+            "negotiation": negotiation,
+            "notifications": notifications,
+        });
+
+        Ok(ResourceContents {
+            uri: "debugger://server".to_string(),
+            mime_type: "application/json".to_string(),
+            text: Some(serde_json::to_string_pretty(&server)?),
+            blob: None,
+        })
+    }
+
     /// Read workflows resource
     fn read_workflows() -> Result<ResourceContents> {
         let workflows = json!({
@@ -546,6 +736,37 @@ impl ResourcesHandler {
     /// Read error handling resource
     fn read_error_handling() -> Result<ResourceContents> {
         let error_handling = json!({
+            "errorData": {
+                "description": "Every failed tools/call response carries error.data with a machine-readable code. Match on data.code instead of the message text.",
+                "fields": {
+                    "code": "Stable error name (see codes below)",
+                    "retryable": "true if the same call can succeed later without changes (e.g. once the session is Stopped)",
+                    "sessionState": "Current state of the session named by sessionId, when it exists",
+                    "diagnostics": "COMPILATION_FAILED only: parsed compiler messages [{level, message, file, line, column, code}]; the error message starts with the first error's file:line:column"
+                },
+                "codes": {
+                    "SESSION_NOT_FOUND": {"jsonRpcCode": -32001, "retryable": false},
+                    "ADAPTER_NOT_FOUND": {"jsonRpcCode": -32002, "retryable": false},
+                    "DAP_ERROR": {"jsonRpcCode": -32003, "retryable": false},
+                    "PROCESS_ERROR": {"jsonRpcCode": -32004, "retryable": false},
+                    "INVALID_STATE": {"jsonRpcCode": -32005, "retryable": true},
+                    "TIMEOUT": {"jsonRpcCode": -32006, "retryable": true},
+                    "COMPILATION_FAILED": {"jsonRpcCode": -32007, "retryable": false},
+                    "PATH_NOT_ALLOWED": {"jsonRpcCode": -32008, "retryable": false, "note": "The server was started with --allowed-paths and the program, cwd or source file lies outside them"},
+                    "STALE_FRAME_ID": {"jsonRpcCode": -32009, "retryable": false, "note": "The frameId comes from a stack fetched before the program last resumed; fetch debugger_stack_trace again and use its frame ids"},
+                    "ADAPTER_NOT_CONSUMING_INPUT": {"jsonRpcCode": -32010, "retryable": false, "note": "The adapter stopped reading requests and the write queue stayed full; the session is likely hung, end it with debugger_force_kill"},
+                    "INVALID_REQUEST": {"jsonRpcCode": -32600, "retryable": false},
+                    "METHOD_NOT_FOUND": {"jsonRpcCode": -32601, "retryable": false},
+                    "INVALID_ARGUMENTS": {"jsonRpcCode": -32603, "retryable": false},
+                    "INTERNAL_ERROR": {"jsonRpcCode": -32603, "retryable": false},
+                    "REQUEST_CANCELLED": {"jsonRpcCode": -32800, "retryable": true, "note": "Sent after a notifications/cancelled (or $/cancelRequest) for an in-flight request"}
+                },
+                "example": {
+                    "code": -32005,
+                    "message": "Invalid state: Cannot evaluate expressions while program is running...",
+                    "data": {"code": "INVALID_STATE", "retryable": true, "sessionState": "Running"}
+                }
+            },
             "errors": [
                 {
                     "type": "SessionNotFound",
@@ -728,26 +949,44 @@ impl ResourcesHandler {
 
     /// Read sessions list resource
     async fn read_sessions_list(&self) -> Result<ResourceContents> {
-        let manager = self.session_manager.read().await;
+        let manager = &self.session_manager;
         let session_ids = manager.list_sessions().await;
 
         let mut sessions = Vec::new();
         for session_id in session_ids {
             if let Ok(session) = manager.get_session(&session_id).await {
-                let state = session.get_state().await;
-                sessions.push(json!({
+                let state = session.state.read().await;
+                let stop_reason = match &state.state {
+                    crate::debug::state::DebugState::Stopped { reason, .. } => Some(reason.clone()),
+                    _ => None,
+                };
+                let breakpoint_count = state.breakpoints.values().map(Vec::len).sum::<usize>()
+                    + state.function_breakpoints.len()
+                    + state.data_breakpoints.len();
+                let mut entry = json!({
                     "id": session.id,
                     "language": session.language,
                     "program": session.program,
-                    "state": state,
-                }));
+                    "stopReason": stop_reason,
+                    "breakpointCount": breakpoint_count,
+                    "createdAtMs": state.created_at_ms,
+                    "lastActivityAtMs": state.last_activity_ms,
+                    "process": state.process,
+                });
+                Self::insert_state(&mut entry, &state.state);
+                sessions.push(entry);
             }
         }
 
-        let content = json!({
+        let mut content = json!({
             "sessions": sessions,
             "total": sessions.len(),
         });
+        // Sessions of the previous server (--resume) that could not be adopted
+        let unrecoverable = manager.unrecoverable_sessions().await;
+        if !unrecoverable.is_empty() {
+            content["unrecoverable"] = json!(unrecoverable);
+        }
 
         Ok(ResourceContents {
             uri: "debugger://sessions".to_string(),
@@ -759,7 +998,7 @@ impl ResourcesHandler {
 
     /// Read session details resource
     async fn read_session_details(&self, session_id: &str) -> Result<ResourceContents> {
-        let manager = self.session_manager.read().await;
+        let manager = &self.session_manager;
         let session = manager.get_session(session_id).await?;
 
         let state = session.get_state().await;
@@ -780,15 +1019,28 @@ impl ResourcesHandler {
                 })
             })
             .collect();
+        let adapter_version = state_lock.adapter_version.clone();
+        let process = state_lock.process.clone();
         drop(state_lock);
 
-        let content = json!({
+        let mut content = json!({
             "id": session.id,
             "language": session.language,
             "program": session.program,
-            "state": state,
             "breakpoints": all_breakpoints,
+            "adapterVersion": adapter_version,
+            "adapterTransport": session.adapter_transport().await,
+            "process": process,
         });
+        Self::insert_state(&mut content, &state);
+        if let Some(build) = session.build() {
+            content["build"] = build.to_json();
+        }
+        content["metrics"] = serde_json::to_value(session.metrics().await)?;
+        let children = session.children().await;
+        if !children.is_empty() {
+            content["children"] = serde_json::to_value(children)?;
+        }
 
         Ok(ResourceContents {
             uri: format!("debugger://sessions/{}", session_id),
@@ -798,29 +1050,270 @@ impl ResourcesHandler {
         })
     }
 
+    /// Read session compilation resource (last build of a compiled program)
+    async fn read_session_compilation(&self, session_id: &str) -> Result<ResourceContents> {
+        let manager = &self.session_manager;
+        let session = manager.get_session(session_id).await?;
+        let build = session.build().ok_or_else(|| {
+            Error::InvalidRequest(format!(
+                "Session {} did not compile its program ({} is not a compiled language)",
+                session_id, session.language
+            ))
+        })?;
+
+        let mut content = build.to_json();
+        content["sessionId"] = json!(session_id);
+        content["warnings"] = json!(build
+            .diagnostics
+            .iter()
+            .filter(|d| d.level == "warning")
+            .count());
+
+        Ok(ResourceContents {
+            uri: format!("debugger://sessions/{}/compilation", session_id),
+            mime_type: "application/json".to_string(),
+            text: Some(serde_json::to_string_pretty(&content)?),
+            blob: None,
+        })
+    }
+
+    /// Read session DAP log resource (sessions started with captureDapLog)
+    async fn read_session_dap_log(&self, session_id: &str) -> Result<ResourceContents> {
+        let manager = &self.session_manager;
+        let session = manager.get_session(session_id).await?;
+        let content = session.dap_log_json(None)?;
+
+        Ok(ResourceContents {
+            uri: format!("debugger://sessions/{}/dapLog", session_id),
+            mime_type: "application/json".to_string(),
+            text: Some(serde_json::to_string_pretty(&content)?),
+            blob: None,
+        })
+    }
+
     /// Read session stack trace resource
     async fn read_session_stack_trace(&self, session_id: &str) -> Result<ResourceContents> {
-        let manager = self.session_manager.read().await;
+        let manager = &self.session_manager;
         let session = manager.get_session(session_id).await?;
 
         let state = session.get_state().await;
 
-        // Only get stack trace if stopped
-        let frames: Vec<crate::dap::types::StackFrame> = match state {
-            crate::debug::state::DebugState::Stopped { .. } => {
-                session.stack_trace().await.unwrap_or_default()
+        // Only get stack trace if stopped; the first page, like debugger_stack_trace
+        let levels = crate::mcp::tools::DEFAULT_STACK_LEVELS;
+        let page = match state {
+            crate::debug::state::DebugState::Stopped { .. } => session
+                .stack_trace_page(None, None, Some(levels))
+                .await
+                .unwrap_or_default(),
+            _ => Default::default(),
+        };
+
+        let mut content = crate::mcp::tools::stack_page_to_json(&page, 0, levels);
+        content["sessionId"] = json!(session.id);
+        Self::insert_state(&mut content, &state);
+
+        Ok(ResourceContents {
+            uri: format!("debugger://sessions/{}/stackTrace", session_id),
+            mime_type: "application/json".to_string(),
+            text: Some(serde_json::to_string_pretty(&content)?),
+            blob: None,
+        })
+    }
+
+    /// Read the scopes of a frame of a stopped session, each linking to its
+    /// variables resource
+    ///
+    /// Frame IDs are only valid at the stop they were reported for, so the
+    /// frame must be on the current stack.
+    async fn read_frame_scopes(&self, session_id: &str, frame: &str) -> Result<ResourceContents> {
+        let frame_id: i32 = frame.parse().map_err(|_| {
+            Error::InvalidRequest(format!(
+                "Invalid frame id '{}': expected a number from the stackTrace resource",
+                frame
+            ))
+        })?;
+        let manager = &self.session_manager;
+        let session = manager.get_session(session_id).await?;
+        Self::require_stopped(&session.get_state().await, "Scopes")?;
+
+        let cached = session.state.read().await.cached_stack().map(<[_]>::to_vec);
+        let frames = match cached {
+            Some(frames) => frames,
+            None => {
+                session
+                    .stack_trace_page(None, None, Some(crate::mcp::tools::DEFAULT_STACK_LEVELS))
+                    .await?
+                    .stack_frames
             }
-            _ => vec![],
+        };
+        let Some(frame) = frames.iter().find(|frame| frame.id == frame_id) else {
+            return Err(Error::InvalidRequest(format!(
+                "Frame {} is not on the current stack; frame ids change at every stop, read \
+                 debugger://sessions/{}/stackTrace for the current ones",
+                frame_id, session_id
+            )));
         };
 
+        let scopes: Vec<Value> = session
+            .scopes(frame_id)
+            .await?
+            .into_iter()
+            .map(|scope| {
+                let mut value = json!(scope);
+                if scope.variables_reference > 0 {
+                    value["uri"] =
+                        json!(Self::variables_uri(session_id, scope.variables_reference));
+                }
+                value
+            })
+            .collect();
         let content = json!({
-            "sessionId": session.id,
-            "state": state,
-            "stackFrames": frames,
+            "sessionId": session_id,
+            "frameId": frame_id,
+            "frame": frame.name,
+            "scopes": scopes,
         });
 
         Ok(ResourceContents {
-            uri: format!("debugger://sessions/{}/stackTrace", session_id),
+            uri: format!(
+                "debugger://sessions/{}/frames/{}/scopes",
+                session_id, frame_id
+            ),
+            mime_type: "application/json".to_string(),
+            text: Some(serde_json::to_string_pretty(&content)?),
+            blob: None,
+        })
+    }
+
+    /// Read a page of the children of a variables reference of a stopped
+    /// session (`?start=N&count=M`, like debugger_get_variables)
+    async fn read_session_variables(
+        &self,
+        session_id: &str,
+        reference: &str,
+        query: &str,
+    ) -> Result<ResourceContents> {
+        let variables_reference = reference
+            .parse::<i32>()
+            .ok()
+            .filter(|reference| *reference > 0)
+            .ok_or_else(|| {
+                Error::InvalidRequest(format!(
+                    "Invalid variables reference '{}': expected a number greater than 0",
+                    reference
+                ))
+            })?;
+        let mut start = 0;
+        let mut count = crate::mcp::tools::DEFAULT_VARIABLES_COUNT;
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = value.parse::<i32>().ok();
+            match (key, value) {
+                ("start", Some(value)) if value >= 0 => start = value,
+                ("count", Some(value)) if value >= 1 => count = value,
+                _ => {
+                    return Err(Error::InvalidRequest(format!(
+                        "Invalid query '{}': expected start (0 or more) and count (1 or more)",
+                        pair
+                    )))
+                }
+            }
+        }
+
+        let manager = &self.session_manager;
+        let session = manager.get_session(session_id).await?;
+        Self::require_stopped(&session.get_state().await, "Variables")?;
+
+        let variables = session
+            .variables_page(variables_reference, Some(start), Some(count))
+            .await?;
+        let returned = variables.len() as i32;
+        let max_length = crate::debug::truncate::DEFAULT_MAX_LENGTH;
+        let variables: Vec<Value> = variables
+            .into_iter()
+            .map(|variable| {
+                let mut value = json!(variable);
+                crate::debug::truncate::truncate_field(&mut value, "value", max_length);
+                if variable.variables_reference > 0 {
+                    value["uri"] = json!(Self::variables_uri(
+                        session_id,
+                        variable.variables_reference
+                    ));
+                }
+                value
+            })
+            .collect();
+        let mut content = json!({
+            "sessionId": session_id,
+            "variablesReference": variables_reference,
+            "start": start,
+            "variables": variables,
+        });
+        // A full page may be followed by more children
+        if returned == count {
+            content["next"] = json!(format!(
+                "{}?start={}&count={}",
+                Self::variables_uri(session_id, variables_reference),
+                start + count,
+                count
+            ));
+        }
+
+        let uri = Self::variables_uri(session_id, variables_reference);
+        Ok(ResourceContents {
+            uri: if query.is_empty() {
+                uri
+            } else {
+                format!("{}?{}", uri, query)
+            },
+            mime_type: "application/json".to_string(),
+            text: Some(serde_json::to_string_pretty(&content)?),
+            blob: None,
+        })
+    }
+
+    /// First characters of a session ID, for resource names; adopted
+    /// sessions keep persisted IDs that need not be generated UUIDs
+    fn short_id(session_id: &str) -> String {
+        session_id.chars().take(8).collect()
+    }
+
+    fn variables_uri(session_id: &str, variables_reference: i32) -> String {
+        format!(
+            "debugger://sessions/{}/variables/{}",
+            session_id, variables_reference
+        )
+    }
+
+    /// Add the `state` and `details` of `DebugState::to_json` to `content`
+    fn insert_state(content: &mut Value, state: &crate::debug::state::DebugState) {
+        if let (Some(content), Value::Object(state)) = (content.as_object_mut(), state.to_json()) {
+            content.extend(state);
+        }
+    }
+
+    /// Frame IDs and variables references only hold while the program stays
+    /// paused at the stop that reported them
+    fn require_stopped(state: &crate::debug::state::DebugState, what: &str) -> Result<()> {
+        match state {
+            crate::debug::state::DebugState::Stopped { .. } => Ok(()),
+            _ => Err(Error::InvalidState(format!(
+                "{} are only available while the program is stopped (state: {}); frame ids and \
+                 variables references are no longer valid once it resumes",
+                what,
+                state.name()
+            ))),
+        }
+    }
+
+    /// Read session snapshot resource (debugger_snapshot with default limits)
+    async fn read_session_snapshot(&self, session_id: &str) -> Result<ResourceContents> {
+        let manager = &self.session_manager;
+        let session = manager.get_session(session_id).await?;
+        let content = crate::mcp::tools::snapshot_to_json(&session, &Default::default()).await;
+
+        Ok(ResourceContents {
+            uri: format!("debugger://sessions/{}/snapshot", session_id),
             mime_type: "application/json".to_string(),
             text: Some(serde_json::to_string_pretty(&content)?),
             blob: None,
@@ -845,7 +1338,37 @@ impl ResourcesHandler {
             json!({
                 "uriTemplate": "debugger://sessions/{sessionId}/stackTrace",
                 "name": "Session Stack Trace",
-                "description": "Get the call stack for a stopped debug session",
+                "description": "Get the call stack for a stopped debug session: the top 50 frames with totalFrames (page through deeper stacks with debugger_stack_trace)",
+                "mimeType": "application/json"
+            }),
+            json!({
+                "uriTemplate": "debugger://sessions/{sessionId}/snapshot",
+                "name": "Session Snapshot",
+                "description": "State, stop details, stack, top frame variables, watches, recent output and breakpoints of a session in one document (debugger_snapshot with default limits)",
+                "mimeType": "application/json"
+            }),
+            json!({
+                "uriTemplate": "debugger://sessions/{sessionId}/compilation",
+                "name": "Session Compilation",
+                "description": "Build result of a compiled program (Rust, C, C++): binary, freshness, duration and compiler warnings",
+                "mimeType": "application/json"
+            }),
+            json!({
+                "uriTemplate": "debugger://sessions/{sessionId}/dapLog",
+                "name": "Session DAP Log",
+                "description": "DAP messages exchanged with the debug adapter, for sessions started with captureDapLog: true",
+                "mimeType": "application/json"
+            }),
+            json!({
+                "uriTemplate": "debugger://sessions/{sessionId}/frames/{frameId}/scopes",
+                "name": "Frame Scopes",
+                "description": "Variable scopes (locals, arguments, globals) of a frame of a stopped session, each with the URI of its variables. Frame IDs come from the stackTrace resource and change at every stop",
+                "mimeType": "application/json"
+            }),
+            json!({
+                "uriTemplate": "debugger://sessions/{sessionId}/variables/{variablesReference}",
+                "name": "Variables",
+                "description": "Children of a scope or structured value of a stopped session, 100 at a time; append ?start=N&count=M to page (the 'next' field links the following page). References are invalid once the program resumes",
                 "mimeType": "application/json"
             }),
             json!({
@@ -882,7 +1405,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_resources_handler_new() {
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
+        let manager = Arc::new(SessionManager::new());
         let handler = ResourcesHandler::new(manager);
         // Verify construction works and list_resources is callable
         let resources = handler.list_resources().await.unwrap();
@@ -891,13 +1414,13 @@ mod tests {
 
     #[tokio::test]
     async fn test_list_resources_empty() {
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
+        let manager = Arc::new(SessionManager::new());
         let handler = ResourcesHandler::new(manager);
 
         let resources = handler.list_resources().await.unwrap();
 
-        // Should have: sessions + workflows + state-machine + error-handling + 5 docs = 9
-        assert_eq!(resources.len(), 9);
+        // Should have: sessions + workflows + state-machine + error-handling + server + 5 docs = 10
+        assert_eq!(resources.len(), 10);
         assert_eq!(resources[0].uri, "debugger://sessions");
         assert_eq!(resources[0].name, "Debug Sessions");
 
@@ -921,7 +1444,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_read_sessions_list_empty() {
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
+        let manager = Arc::new(SessionManager::new());
         let handler = ResourcesHandler::new(manager);
 
         let contents = handler.read_resource("debugger://sessions").await.unwrap();
@@ -937,7 +1460,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_read_invalid_uri_scheme() {
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
+        let manager = Arc::new(SessionManager::new());
         let handler = ResourcesHandler::new(manager);
 
         let result = handler.read_resource("http://invalid").await;
@@ -953,7 +1476,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_read_unknown_resource_path() {
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
+        let manager = Arc::new(SessionManager::new());
         let handler = ResourcesHandler::new(manager);
 
         let result = handler.read_resource("debugger://unknown").await;
@@ -962,7 +1485,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_read_session_not_found() {
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
+        let manager = Arc::new(SessionManager::new());
         let handler = ResourcesHandler::new(manager);
 
         let result = handler
@@ -978,7 +1501,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_read_stack_trace_not_found() {
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
+        let manager = Arc::new(SessionManager::new());
         let handler = ResourcesHandler::new(manager);
 
         let result = handler
@@ -987,12 +1510,422 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_compilation_resource_for_compiled_sessions() {
+        use crate::adapters::diagnostics::CompilerDiagnostic;
+        use crate::adapters::rust::CompileOutcome;
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let session = |language: &'static str| async move {
+            let client = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+                .await
+                .unwrap();
+            DebugSession::new(language.to_string(), "/app/main".to_string(), client)
+                .await
+                .unwrap()
+        };
+        let build = CompileOutcome {
+            binary: "/app/target/debug/main".to_string(),
+            fresh: false,
+            duration: std::time::Duration::from_millis(1200),
+            diagnostics: vec![CompilerDiagnostic {
+                level: "warning".to_string(),
+                message: "unused variable: `x`".to_string(),
+                file: Some("/app/src/main.rs".to_string()),
+                line: Some(3),
+                column: Some(9),
+                code: Some("unused_variables".to_string()),
+            }],
+        };
+
+        let manager = SessionManager::new();
+        let rust_id = manager
+            .insert_session(Arc::new(session("rust").await.with_build(Some(build))))
+            .await;
+        let python_id = manager
+            .insert_session(Arc::new(session("python").await))
+            .await;
+        let handler = ResourcesHandler::new(Arc::new(manager));
+
+        let uris: Vec<String> = handler
+            .list_resources()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.uri)
+            .collect();
+        assert!(uris.contains(&format!("debugger://sessions/{}/compilation", rust_id)));
+        assert!(!uris.contains(&format!("debugger://sessions/{}/compilation", python_id)));
+
+        let contents = handler
+            .read_resource(&format!("debugger://sessions/{}/compilation", rust_id))
+            .await
+            .unwrap();
+        let content: Value = serde_json::from_str(&contents.text.unwrap()).unwrap();
+        assert_eq!(content["binary"], "/app/target/debug/main");
+        assert_eq!(content["durationMs"], 1200);
+        assert_eq!(content["warnings"], 1);
+        assert_eq!(content["diagnostics"][0]["line"], 3);
+
+        let details = handler
+            .read_resource(&format!("debugger://sessions/{}", rust_id))
+            .await
+            .unwrap();
+        assert!(details.text.unwrap().contains("\"fresh\": false"));
+
+        let result = handler
+            .read_resource(&format!("debugger://sessions/{}/compilation", python_id))
+            .await;
+        assert!(matches!(result, Err(Error::InvalidRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_list_resources_with_short_session_ids() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let manager = SessionManager::new();
+        for id in ["abc", "aéééé"] {
+            let client = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+                .await
+                .unwrap();
+            let session =
+                DebugSession::new("python".to_string(), "/app/main.py".to_string(), client)
+                    .await
+                    .unwrap()
+                    .with_id(Some(id.to_string()));
+            manager.insert_session(Arc::new(session)).await;
+        }
+        let handler = ResourcesHandler::new(Arc::new(manager));
+
+        let names: Vec<String> = handler
+            .list_resources()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.name)
+            .collect();
+        assert!(names.contains(&"Session abc".to_string()));
+        assert!(names.contains(&"Stack Trace (aéééé)".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_session_details_include_adapter_version() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let client = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("ruby".to_string(), "/app/main.rb".to_string(), client)
+            .await
+            .unwrap();
+        session.state.write().await.adapter_version = Some("1.9.2".to_string());
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let handler = ResourcesHandler::new(Arc::new(manager));
+
+        let details = handler
+            .read_resource(&format!("debugger://sessions/{}", session_id))
+            .await
+            .unwrap();
+        let content: Value = serde_json::from_str(&details.text.unwrap()).unwrap();
+        assert_eq!(content["adapterVersion"], "1.9.2");
+    }
+
+    #[tokio::test]
+    async fn test_server_resource_reports_negotiation() {
+        use crate::mcp::protocol::Negotiation;
+
+        let manager = Arc::new(SessionManager::new());
+        let negotiation = SharedNegotiation::default();
+        let handler = ResourcesHandler::new(manager).with_negotiation(Arc::clone(&negotiation));
+
+        let read = || async {
+            let contents = handler.read_resource("debugger://server").await.unwrap();
+            serde_json::from_str::<Value>(&contents.text.unwrap()).unwrap()
+        };
+        let before = read().await;
+        assert_eq!(before["initialized"], false);
+        assert!(before["negotiation"].is_null());
+        assert_eq!(before["supportedProtocolVersions"][0], "2024-11-05");
+
+        *negotiation.write().unwrap() = Some(Negotiation {
+            protocol_version: "2025-06-18".to_string(),
+            requested_version: Some("2025-06-18".to_string()),
+            client_info: Some(json!({"name": "inspector"})),
+            client_capabilities: json!({"experimental": {"resourceUpdates": {}}}),
+        });
+        let after = read().await;
+        assert_eq!(after["initialized"], true);
+        assert_eq!(after["negotiation"]["protocolVersion"], "2025-06-18");
+        assert_eq!(after["negotiation"]["clientInfo"]["name"], "inspector");
+        assert_eq!(
+            after["notifications"],
+            json!({"debugger/stateChanged": false, "notifications/resources/updated": true})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_resource_of_running_session() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let client = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/a.py".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .state
+            .write()
+            .await
+            .set_state(crate::debug::state::DebugState::Running);
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let handler = ResourcesHandler::new(Arc::new(manager));
+
+        let uri = format!("debugger://sessions/{}/snapshot", session_id);
+        let listed = handler.list_resources().await.unwrap();
+        assert!(listed.iter().any(|r| r.uri == uri));
+
+        let contents = handler.read_resource(&uri).await.unwrap();
+        let snapshot: Value = serde_json::from_str(&contents.text.unwrap()).unwrap();
+        assert_eq!(snapshot["sessionId"], session_id);
+        assert_eq!(snapshot["state"], "Running");
+        // Nothing to inspect while running
+        assert_eq!(snapshot["stackTrace"], Value::Null);
+        assert_eq!(snapshot["variables"], json!([]));
+        assert_eq!(snapshot["output"], json!({"lines": [], "totalLines": 0}));
+    }
+
+    #[tokio::test]
+    async fn test_sessions_list_entry_shape() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+        use crate::debug::state::StopContext;
+
+        let client = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/a.py".to_string(), client)
+            .await
+            .unwrap();
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let session = manager.get_session(&session_id).await.unwrap();
+        {
+            let mut state = session.state.write().await;
+            state.add_source_breakpoint("/app/a.py".to_string(), 3, None, None);
+            state.record_stop(StopContext::from_event_body(
+                &json!({"reason": "breakpoint", "threadId": 1}),
+            ));
+        }
+        let handler = ResourcesHandler::new(Arc::new(manager));
+
+        let contents = handler.read_resource("debugger://sessions").await.unwrap();
+        let list: Value = serde_json::from_str(&contents.text.unwrap()).unwrap();
+        assert_eq!(list["total"], 1);
+        let entry = &list["sessions"][0];
+        let mut keys: Vec<&str> = entry
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort_unstable();
+        assert_eq!(
+            keys,
+            vec![
+                "breakpointCount",
+                "createdAtMs",
+                "details",
+                "id",
+                "language",
+                "lastActivityAtMs",
+                "process",
+                "program",
+                "state",
+                "stopReason"
+            ]
+        );
+        assert_eq!(entry["id"], session_id);
+        assert_eq!(entry["state"], "Stopped");
+        assert_eq!(
+            entry["details"],
+            json!({"threadId": 1, "reason": "breakpoint"})
+        );
+        assert_eq!(entry["stopReason"], "breakpoint");
+        assert_eq!(entry["breakpointCount"], 1);
+        assert!(entry["createdAtMs"].as_u64().unwrap() > 0);
+        assert!(entry["lastActivityAtMs"].as_u64() >= entry["createdAtMs"].as_u64());
+    }
+
+    #[tokio::test]
+    async fn test_frame_scopes_and_variables_resources() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+        use crate::debug::state::{DebugState, StopContext};
+
+        let adapter = ScriptedAdapter::new().stack_depth(2);
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/a.py".to_string(), client)
+            .await
+            .unwrap();
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let session = manager.get_session(&session_id).await.unwrap();
+        session
+            .state
+            .write()
+            .await
+            .record_stop(StopContext::from_event_body(
+                &json!({"reason": "breakpoint", "threadId": 1}),
+            ));
+        let handler = ResourcesHandler::new(Arc::new(manager));
+        let base = format!("debugger://sessions/{}", session_id);
+        let read = |uri: String| {
+            let handler = &handler;
+            async move {
+                let contents = handler.read_resource(&uri).await?;
+                Ok::<Value, Error>(serde_json::from_str(&contents.text.unwrap()).unwrap())
+            }
+        };
+
+        // The stack has not been fetched yet, so no frames are listed
+        let listed = handler.list_resources().await.unwrap();
+        assert!(!listed.iter().any(|r| r.uri.contains("/frames/")));
+
+        let scopes = read(format!("{}/frames/2/scopes", base)).await.unwrap();
+        assert_eq!(scopes["frame"], "caller_1");
+        assert_eq!(scopes["scopes"][0]["name"], "Locals");
+        let locals = format!("{}/variables/100", base);
+        assert_eq!(scopes["scopes"][0]["uri"], locals.as_str());
+        // Reading the scopes fetched the stack: its frames are listed now
+        let listed = handler.list_resources().await.unwrap();
+        for frame in 1..=2 {
+            let uri = format!("{}/frames/{}/scopes", base, frame);
+            assert!(listed.iter().any(|r| r.uri == uri), "{} not listed", uri);
+        }
+
+        let variables = read(locals.clone()).await.unwrap();
+        assert_eq!(variables["variables"][0]["name"], "n");
+        assert!(variables.get("next").is_none());
+        let page = read(format!("{}?count=1", locals)).await.unwrap();
+        assert_eq!(page["next"], format!("{}?start=1&count=1", locals).as_str());
+
+        for bad in [
+            format!("{}/frames/abc/scopes", base),
+            format!("{}/frames/99/scopes", base),
+            format!("{}/frames/1/locals", base),
+            format!("{}/variables/0", base),
+            format!("{}/variables/x", base),
+            format!("{}?start=-1", locals),
+            format!("{}?limit=5", locals),
+        ] {
+            assert!(
+                matches!(read(bad.clone()).await, Err(Error::InvalidRequest(_))),
+                "{} should be rejected",
+                bad
+            );
+        }
+
+        // After resuming, the references of the last stop are stale
+        session.state.write().await.set_state(DebugState::Running);
+        assert!(matches!(read(locals).await, Err(Error::InvalidState(_))));
+        assert!(matches!(
+            read(format!("{}/frames/1/scopes", base)).await,
+            Err(Error::InvalidState(_))
+        ));
+        let listed = handler.list_resources().await.unwrap();
+        assert!(!listed.iter().any(|r| r.uri.contains("/frames/")));
+    }
+
+    #[tokio::test]
+    async fn test_dap_log_resource_for_captured_sessions() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::dap::wire_log::DapLog;
+        use crate::debug::session::DebugSession;
+
+        let captured_client = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+            .await
+            .unwrap();
+        captured_client.capture_dap_log(Arc::new(DapLog::default()));
+        captured_client.send_request("threads", None).await.unwrap();
+        let plain_client = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+            .await
+            .unwrap();
+
+        let manager = SessionManager::new();
+        let captured_id = manager
+            .insert_session(Arc::new(
+                DebugSession::new(
+                    "python".to_string(),
+                    "/app/a.py".to_string(),
+                    captured_client,
+                )
+                .await
+                .unwrap(),
+            ))
+            .await;
+        let plain_id = manager
+            .insert_session(Arc::new(
+                DebugSession::new("python".to_string(), "/app/b.py".to_string(), plain_client)
+                    .await
+                    .unwrap(),
+            ))
+            .await;
+        let handler = ResourcesHandler::new(Arc::new(manager));
+
+        let uris: Vec<String> = handler
+            .list_resources()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.uri)
+            .collect();
+        assert!(uris.contains(&format!("debugger://sessions/{}/dapLog", captured_id)));
+        assert!(!uris.contains(&format!("debugger://sessions/{}/dapLog", plain_id)));
+
+        let contents = handler
+            .read_resource(&format!("debugger://sessions/{}/dapLog", captured_id))
+            .await
+            .unwrap();
+        let content: Value = serde_json::from_str(&contents.text.unwrap()).unwrap();
+        assert_eq!(content["sessionId"], captured_id.as_str());
+        let entries = content["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["direction"], "sent");
+        assert_eq!(entries[0]["name"], "threads");
+        assert_eq!(entries[1]["direction"], "received");
+        assert_eq!(entries[1]["type"], "response");
+        assert_eq!(entries[1]["requestSeq"], entries[0]["seq"]);
+
+        let result = handler
+            .read_resource(&format!("debugger://sessions/{}/dapLog", plain_id))
+            .await;
+        assert!(
+            matches!(result, Err(Error::InvalidRequest(msg)) if msg.contains("captureDapLog: true"))
+        );
+    }
+
     #[tokio::test]
     async fn test_list_resource_templates() {
         let templates = ResourcesHandler::list_resource_templates();
 
-        // Should have: 3 session templates + 3 workflow templates + 4 docs templates = 10
-        assert_eq!(templates.len(), 10);
+        // 8 session templates + 3 workflow templates + 4 docs templates
+        assert_eq!(templates.len(), 15);
 
         // Check first template (sessions)
         assert!(templates[0]["uriTemplate"]
@@ -1015,7 +1948,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_resource_uri_parsing() {
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
+        let manager = Arc::new(SessionManager::new());
         let handler = ResourcesHandler::new(manager);
 
         // Test various invalid URIs
@@ -1,21 +1,173 @@
-use crate::adapters::security;
-use crate::debug::SessionManager;
+use crate::adapters::health;
+use crate::adapters::nodejs::{self, NodeLaunchOptions};
+use crate::adapters::python::{self, PythonLaunchOptions, WebFramework};
+use crate::adapters::ruby::{self, RubyLaunchOptions};
+use crate::adapters::rust::{CargoTargetType, LldbExpressions, LldbLaunchOptions, LldbTerminal};
+use crate::adapters::security::{self, AllowedPaths};
+use crate::dap::socket_helper::AdapterTimeouts;
+use crate::dap::types::{
+    DataBreakpoint, FunctionBreakpoint, SourceBreakpoint, StackFrame, DATA_BREAKPOINT_ACCESS_TYPES,
+    EVALUATE_CONTEXTS, STEPPING_GRANULARITIES,
+};
+use crate::debug::config_store::{self, ConfigSource, LAST_CONFIG};
+use crate::debug::session::{check_breakpoint_line, BreakpointDiagnosis};
+use crate::debug::truncate;
+use crate::debug::{
+    BreakpointStore, DataBreakpointInfo, DebugSession, ExistingSession, InitialBreakpoint,
+    OutputEntry, PathMapper, PathMapping, SessionManager, SessionOptions, SessionRecorder,
+    SessionState, Watch,
+};
 use crate::{Error, Result};
 use serde::Deserialize;
-use serde_json::{json, Value};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Instant;
+use tracing::Instrument;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DebuggerStartArgs {
     pub language: String,
+    /// Required unless `module` is given
+    #[serde(default)]
     pub program: String,
     #[serde(default)]
     pub args: Vec<String>,
     pub cwd: Option<String>,
     #[serde(default)]
     pub stop_on_entry: bool,
+    /// Default timeout for individual DAP requests (milliseconds)
+    pub request_timeout_ms: Option<u64>,
+    /// How long to wait for the adapter to accept connections and to finish
+    /// initializing (milliseconds)
+    pub adapter_timeout_ms: Option<u64>,
+    /// Line for the stopOnEntry entry breakpoint, overriding detection
+    pub entry_line: Option<usize>,
+    /// Breakpoints to apply before the program starts running
+    #[serde(default)]
+    pub breakpoints: Vec<InitialBreakpointArgs>,
+    /// Register the breakpoints saved for this program as initial breakpoints
+    #[serde(default)]
+    pub restore_breakpoints: bool,
+    /// Rust only: "binary", "test" or "example:<name>"
+    pub cargo_target: Option<String>,
+    /// Rust only: run just this test (implies cargoTarget "test")
+    pub test_filter: Option<String>,
+    /// Rust only: compile even if the previous build is up to date
+    #[serde(default)]
+    pub force_rebuild: bool,
+    /// local/remote directory pairs for programs that see other paths
+    /// (e.g. inside a container)
+    #[serde(default)]
+    pub path_mappings: Vec<PathMapping>,
+    /// Whether path mapping prefixes are compared case-sensitively
+    #[serde(default = "default_true")]
+    pub path_mappings_case_sensitive: bool,
+    /// Pass program and cwd to the adapter unchecked (for adapters that
+    /// resolve paths themselves)
+    #[serde(default)]
+    pub skip_validation: bool,
+    /// Record the session's DAP messages for debugger_dap_log
+    #[serde(default)]
+    pub capture_dap_log: bool,
+    /// Write a transcript of the session's tool calls and state changes
+    #[serde(default)]
+    pub record: bool,
+    /// Restart the session when the program or a `watch_paths` file changes
+    #[serde(default)]
+    pub watch_mode: bool,
+    /// Files watched besides the program (watch mode)
+    #[serde(default)]
+    pub watch_paths: Vec<String>,
+    /// Fail when a session is already debugging the program
+    #[serde(default)]
+    pub exclusive: bool,
+    /// Return the session already debugging the program instead of
+    /// starting another
+    #[serde(default)]
+    pub reuse_existing: bool,
+    /// Node.js only: map compiled code back to its sources
+    pub source_maps: Option<bool>,
+    /// Node.js only: globs of the compiled JavaScript
+    #[serde(default)]
+    pub out_files: Vec<String>,
+    /// Node.js only: globs of the locations allowed to provide source maps
+    #[serde(default)]
+    pub resolve_source_map_locations: Vec<String>,
+    /// Node.js only: arguments for the runtime (e.g. ts-node or tsx loaders)
+    #[serde(default)]
+    pub runtime_args: Vec<String>,
+    /// Node.js only: runtime to start instead of node (e.g. "npm")
+    pub runtime_executable: Option<String>,
+    /// Node.js only: package.json script to run with `npm run`
+    pub npm_script: Option<String>,
+    /// Python only: module to run instead of a program (e.g. "pytest")
+    pub module: Option<String>,
+    /// Python only: debug subprocesses too (e.g. an autoreloader's server)
+    #[serde(default)]
+    pub sub_process: bool,
+    /// Python only: "flask" or "django" dev server launch
+    pub web_framework: Option<String>,
+    /// Ruby only: start rdbg with `bundle exec` in the Gemfile's directory
+    #[serde(default)]
+    pub use_bundler: bool,
+    /// Ruby only: command that runs the program (rdbg -c), e.g. ["bundle", "exec", "rspec"]
+    #[serde(default)]
+    pub command: Vec<String>,
+    /// Rust, C and C++ only: CodeLLDB evaluator, "simple", "python" or "native"
+    pub expressions: Option<String>,
+    /// Rust, C and C++ only: LLDB commands run before the target is created
+    #[serde(default)]
+    pub init_commands: Vec<String>,
+    /// Rust, C and C++ only: LLDB commands run just before launch
+    #[serde(default)]
+    pub pre_run_commands: Vec<String>,
+    /// Rust, C and C++ only: environment variables added for the program
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Rust, C and C++ only: files for stdin, stdout and stderr (null keeps the default)
+    #[serde(default)]
+    pub stdio: Vec<Option<String>>,
+    /// Rust, C and C++ only: "console", "integrated" or "external"
+    pub terminal: Option<String>,
+    /// Rust, C and C++ only: core dump to inspect; `program` is the crashed binary
+    pub core_dump: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebuggerAttachArgs {
+    pub language: String,
+    #[serde(default = "default_attach_host")]
+    pub host: String,
+    pub port: u16,
+    /// localRoot/remoteRoot pairs for programs running in a container or on
+    /// another machine
+    #[serde(default)]
+    pub path_mappings: Vec<python::PathMapping>,
+    /// Default timeout for individual DAP requests (milliseconds)
+    pub request_timeout_ms: Option<u64>,
+    /// Breakpoints to apply before configurationDone
+    #[serde(default)]
+    pub breakpoints: Vec<InitialBreakpointArgs>,
+}
+
+fn default_attach_host() -> String {
+    "127.0.0.1".to_string()
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitialBreakpointArgs {
+    pub source_path: String,
+    pub line: i32,
+    pub condition: Option<String>,
+    pub log_message: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -24,1036 +176,8246 @@ pub struct SetBreakpointArgs {
     pub session_id: String,
     pub source_path: String,
     pub line: i32,
+    /// Column within the line, for dense or minified code
+    pub column: Option<i32>,
+    /// Turns the breakpoint into a logpoint that logs instead of stopping
+    pub log_message: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct ContinueArgs {
+pub struct BreakpointLocationsArgs {
     pub session_id: String,
+    pub source_path: String,
+    pub line: i32,
+    /// Last line of the range (inclusive); only `line` when omitted
+    pub end_line: Option<i32>,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct StackTraceArgs {
+pub struct SetBreakpointsArgs {
     pub session_id: String,
+    pub breakpoints: Vec<BreakpointEntryArgs>,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct EvaluateArgs {
+pub struct BreakpointEntryArgs {
+    pub source_path: String,
+    pub line: i32,
+    pub column: Option<i32>,
+    pub condition: Option<String>,
+    pub hit_condition: Option<String>,
+    pub log_message: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetFunctionBreakpointsArgs {
     pub session_id: String,
-    pub expression: String,
-    pub frame_id: Option<i32>,
+    pub breakpoints: Vec<FunctionBreakpointArgs>,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct DisconnectArgs {
+pub struct FunctionBreakpointArgs {
+    pub name: String,
+    pub condition: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetDataBreakpointArgs {
     pub session_id: String,
+    /// Container of `name`, from debugger_get_variables or debugger_evaluate
+    pub variables_reference: Option<i32>,
+    pub name: Option<String>,
+    /// Expression evaluated in `frame_id` (default: top frame)
+    pub expression: Option<String>,
+    pub frame_id: Option<i32>,
+    /// dataId from an earlier lookup
+    pub data_id: Option<String>,
+    /// "read", "write" or "readWrite"
+    pub access_type: Option<String>,
+    pub condition: Option<String>,
+    /// Remove every data breakpoint instead
+    #[serde(default)]
+    pub clear: bool,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct SessionStateArgs {
+pub struct WatchArgs {
     pub session_id: String,
+    pub expression: String,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct WaitForStopArgs {
+pub struct ListWatchesArgs {
     pub session_id: String,
-    #[serde(default = "default_timeout")]
-    pub timeout_ms: u64,
 }
 
-fn default_timeout() -> u64 {
-    5000
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetOutputArgs {
+    pub session_id: String,
+    /// Only return output with this category (e.g. 'stdout', 'stderr', 'console')
+    pub category: Option<String>,
+    /// Bytes of output to return, newest first (default 4096)
+    pub max_length: Option<usize>,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct ListBreakpointsArgs {
+pub struct ContinueArgs {
     pub session_id: String,
+    /// Return only once the program stops again, with the new location
+    #[serde(default)]
+    pub wait_for_stop: bool,
+    /// With `wait_for_stop`: how long to wait for the stop
+    #[serde(default = "default_timeout")]
+    pub timeout_ms: u64,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct StepArgs {
+pub struct StackTraceArgs {
     pub session_id: String,
+    /// Index of the first frame to return (0 is the top frame)
+    #[serde(default)]
+    pub start_frame: i32,
+    /// Frames to return (default [`DEFAULT_STACK_LEVELS`])
+    pub levels: Option<i32>,
+    /// Thread whose stack to return (default: the stopped thread)
     pub thread_id: Option<i32>,
+    /// Leave out frames the adapter marks as "label" or "subtle"
+    #[serde(default)]
+    pub skip_subtle_frames: bool,
 }
 
-pub struct ToolsHandler {
-    session_manager: Arc<RwLock<SessionManager>>,
-}
+/// Frames returned by debugger_stack_trace and the stackTrace resource per
+/// page; deep recursion would otherwise produce thousands of frames
+pub const DEFAULT_STACK_LEVELS: i32 = 50;
 
-impl ToolsHandler {
-    pub fn new(session_manager: Arc<RwLock<SessionManager>>) -> Self {
-        Self { session_manager }
-    }
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvaluateArgs {
+    pub session_id: String,
+    pub expression: String,
+    pub frame_id: Option<i32>,
+    /// Position of the frame in the current stack (0 is the top frame),
+    /// resolved to a frame id at call time; `frame_id` wins when both are set
+    pub frame_index: Option<i32>,
+    /// Thread `frame_id` belongs to (default: the stopped thread)
+    pub thread_id: Option<i32>,
+    /// DAP evaluate context: 'watch' (default), 'repl', 'hover' or 'clipboard'
+    pub context: Option<String>,
+    /// `false` restricts evaluation to side-effect free contexts; `true` without
+    /// an explicit context selects 'repl'
+    pub allow_side_effects: Option<bool>,
+    /// Bytes of the result to return (default 4096)
+    pub max_length: Option<usize>,
+}
 
-    pub async fn handle_tool(&self, name: &str, arguments: Value) -> Result<Value> {
-        match name {
-            "debugger_start" => self.debugger_start(arguments).await,
-            "debugger_session_state" => self.debugger_session_state(arguments).await,
-            "debugger_set_breakpoint" => self.debugger_set_breakpoint(arguments).await,
-            "debugger_continue" => self.debugger_continue(arguments).await,
-            "debugger_stack_trace" => self.debugger_stack_trace(arguments).await,
-            "debugger_evaluate" => self.debugger_evaluate(arguments).await,
-            "debugger_disconnect" => self.debugger_disconnect(arguments).await,
-            "debugger_wait_for_stop" => self.debugger_wait_for_stop(arguments).await,
-            "debugger_list_breakpoints" => self.debugger_list_breakpoints(arguments).await,
-            "debugger_step_over" => self.debugger_step_over(arguments).await,
-            "debugger_step_into" => self.debugger_step_into(arguments).await,
-            "debugger_step_out" => self.debugger_step_out(arguments).await,
-            _ => Err(Error::MethodNotFound(name.to_string())),
+impl EvaluateArgs {
+    /// Pick the DAP context from `context` and `allowSideEffects`
+    fn resolve_context(&self) -> Result<&str> {
+        if let Some(context) = self.context.as_deref() {
+            if !EVALUATE_CONTEXTS.contains(&context) {
+                return Err(Error::InvalidRequest(format!(
+                    "Invalid evaluate context '{}'. Allowed values: {}",
+                    context,
+                    EVALUATE_CONTEXTS.join(", ")
+                )));
+            }
+            if context == "repl" && self.allow_side_effects == Some(false) {
+                return Err(Error::InvalidRequest(
+                    "context 'repl' may execute statements; it cannot be combined with allowSideEffects: false".to_string(),
+                ));
+            }
+            return Ok(context);
         }
-    }
-
-    async fn debugger_start(&self, arguments: Value) -> Result<Value> {
-        let args: DebuggerStartArgs = serde_json::from_value(arguments)?;
-
-        // Validate program path to prevent path traversal attacks
-        // For Rust, validate with .rs extension; for others, allow any file
-        let extension = match args.language.as_str() {
-            "rust" => Some("rs"),
-            "python" => Some("py"),
-            "ruby" => Some("rb"),
-            "javascript" | "nodejs" => Some("js"),
-            "go" => Some("go"),
-            _ => None,
-        };
-
-        let validated_program = security::validate_source_path(&args.program, extension)?;
-        let program = validated_program
-            .to_str()
-            .ok_or_else(|| Error::Internal("Non-UTF8 program path (invalid encoding)".to_string()))?
-            .to_string();
 
-        // Validate cwd if provided
-        let validated_cwd = if let Some(cwd_path) = &args.cwd {
-            let validated = security::validate_directory_path(cwd_path)?;
-            Some(
-                validated
-                    .to_str()
-                    .ok_or_else(|| {
-                        Error::Internal("Non-UTF8 cwd path (invalid encoding)".to_string())
-                    })?
-                    .to_string(),
-            )
-        } else {
-            None
-        };
+        Ok(match self.allow_side_effects {
+            Some(true) => "repl",
+            _ => "watch",
+        })
+    }
+}
 
-        let manager = self.session_manager.read().await;
-        let session_id = manager
-            .create_session(
-                &args.language,
-                program,
-                args.args,
-                validated_cwd,
-                args.stop_on_entry,
-            )
-            .await?;
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetVariablesArgs {
+    pub session_id: String,
+    /// `variablesReference` from debugger_evaluate or a previous expansion
+    pub variables_reference: i32,
+    /// Index of the first child to return
+    #[serde(default)]
+    pub start: i32,
+    /// Children to return (default [`DEFAULT_VARIABLES_COUNT`])
+    pub count: Option<i32>,
+    /// Bytes of each child's value to return (default 4096)
+    pub max_length: Option<usize>,
+}
 
-        Ok(json!({
-            "sessionId": session_id,
-            "status": "started"
-        }))
-    }
+/// Children returned by debugger_get_variables per page
+pub(crate) const DEFAULT_VARIABLES_COUNT: i32 = 100;
 
-    async fn debugger_session_state(&self, arguments: Value) -> Result<Value> {
-        let args: SessionStateArgs = serde_json::from_value(arguments)?;
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadMemoryArgs {
+    pub session_id: String,
+    /// `memoryReference` from debugger_evaluate or debugger_get_variables
+    pub memory_reference: String,
+    /// Byte offset from the reference (may be negative)
+    pub offset: Option<i64>,
+    pub count: i64,
+}
 
-        let manager = self.session_manager.read().await;
-        let state = manager.get_session_state(&args.session_id).await?;
-
-        // Convert DebugState to JSON-friendly format
-        let (state_str, details) = match state {
-            crate::debug::state::DebugState::NotStarted => ("NotStarted", json!({})),
-            crate::debug::state::DebugState::Initializing => ("Initializing", json!({})),
-            crate::debug::state::DebugState::Initialized => ("Initialized", json!({})),
-            crate::debug::state::DebugState::Launching => ("Launching", json!({})),
-            crate::debug::state::DebugState::Running => ("Running", json!({})),
-            crate::debug::state::DebugState::Stopped { thread_id, reason } => (
-                "Stopped",
-                json!({
-                    "threadId": thread_id,
-                    "reason": reason
-                }),
-            ),
-            crate::debug::state::DebugState::Terminated => ("Terminated", json!({})),
-            crate::debug::state::DebugState::Failed { error } => (
-                "Failed",
-                json!({
-                    "error": error
-                }),
-            ),
-        };
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisassembleArgs {
+    pub session_id: String,
+    pub memory_reference: String,
+    #[serde(default = "default_instruction_count")]
+    pub instruction_count: i64,
+}
 
-        Ok(json!({
-            "sessionId": args.session_id,
-            "state": state_str,
-            "details": details
-        }))
+impl StepArgs {
+    fn validate_granularity(&self) -> Result<()> {
+        match self.granularity.as_deref() {
+            Some(granularity) if !STEPPING_GRANULARITIES.contains(&granularity) => {
+                Err(Error::InvalidRequest(format!(
+                    "Invalid granularity '{}'. Allowed values: {}",
+                    granularity,
+                    STEPPING_GRANULARITIES.join(", ")
+                )))
+            }
+            _ => Ok(()),
+        }
     }
+}
 
-    async fn debugger_set_breakpoint(&self, arguments: Value) -> Result<Value> {
-        let args: SetBreakpointArgs = serde_json::from_value(arguments)?;
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistersArgs {
+    pub session_id: String,
+    /// Frame whose registers to read (default: top frame of the stopped thread)
+    pub frame_id: Option<i32>,
+    /// Register group to return, matched case-insensitively against the
+    /// start of the group name (default: the first group)
+    pub group: Option<String>,
+}
 
-        // Validate source path to prevent path traversal
-        // Note: We validate without extension requirement since breakpoints
-        // can be set in any source file regardless of language
-        let validated_source = security::validate_source_path(&args.source_path, None)?;
-        let source_path = validated_source
-            .to_str()
-            .ok_or_else(|| Error::Internal("Non-UTF8 source path (invalid encoding)".to_string()))?
-            .to_string();
+fn default_instruction_count() -> i64 {
+    16
+}
 
-        let manager = self.session_manager.read().await;
-        let session = manager.get_session(&args.session_id).await?;
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletionsArgs {
+    pub session_id: String,
+    /// Partial expression, e.g. "fi" or "self.ba"
+    pub text: String,
+    /// Cursor position in `text` (1-based); the end of `text` when omitted
+    pub column: Option<i32>,
+    /// Frame whose scope is completed; the top frame when omitted
+    pub frame_id: Option<i32>,
+}
 
-        let verified = session
-            .set_breakpoint(source_path.clone(), args.line)
-            .await?;
+/// Largest instructionCount accepted by debugger_disassemble
+const MAX_DISASSEMBLE_INSTRUCTIONS: i64 = 1000;
 
-        Ok(json!({
-            "verified": verified,
-            "sourcePath": source_path,
-            "line": args.line
-        }))
-    }
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedSourcesArgs {
+    pub session_id: String,
+    /// Only paths containing this text
+    pub filter: Option<String>,
+}
 
-    async fn debugger_continue(&self, arguments: Value) -> Result<Value> {
-        let args: ContinueArgs = serde_json::from_value(arguments)?;
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModulesArgs {
+    pub session_id: String,
+}
 
-        let manager = self.session_manager.read().await;
-        let session = manager.get_session(&args.session_id).await?;
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckSourcesArgs {
+    pub session_id: String,
+}
 
-        session.continue_execution().await?;
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchStatusArgs {
+    pub session_id: String,
+    /// Turn watch mode off
+    #[serde(default)]
+    pub disable: bool,
+}
 
-        Ok(json!({
-            "status": "continued"
-        }))
-    }
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveConfigArgs {
+    pub name: String,
+    /// debugger_start arguments
+    pub config: Map<String, Value>,
+}
 
-    async fn debugger_stack_trace(&self, arguments: Value) -> Result<Value> {
-        let args: StackTraceArgs = serde_json::from_value(arguments)?;
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedBreakpointsArgs {
+    /// "list" (default) or "clear"
+    #[serde(default = "default_saved_breakpoints_action")]
+    pub action: String,
+    /// Only this program; all programs when omitted
+    pub program: Option<String>,
+}
 
-        let manager = self.session_manager.read().await;
-        let session = manager.get_session(&args.session_id).await?;
+fn default_saved_breakpoints_action() -> String {
+    "list".to_string()
+}
 
-        // Validate we're in a stopped state
-        let state = session.get_state().await;
-        if !matches!(state, crate::debug::state::DebugState::Stopped { .. }) {
-            return Err(Error::InvalidState(
-                "Cannot get stack trace while program is running. The program must be stopped at a breakpoint, entry point, or step. Use debugger_wait_for_stop() to wait for the program to stop.".to_string()
-            ));
-        }
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisconnectArgs {
+    pub session_id: String,
+}
 
-        let frames = session.stack_trace().await?;
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionStateArgs {
+    pub session_id: String,
+}
 
-        Ok(json!({
-            "stackFrames": frames
-        }))
-    }
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForceKillArgs {
+    pub session_id: String,
+    pub reason: Option<String>,
+}
 
-    async fn debugger_evaluate(&self, arguments: Value) -> Result<Value> {
-        let args: EvaluateArgs = serde_json::from_value(arguments)?;
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaitForStopArgs {
+    pub session_id: String,
+    #[serde(default = "default_timeout")]
+    pub timeout_ms: u64,
+    /// Embed the stopped thread's stack trace in the response
+    #[serde(default)]
+    pub include_stack_trace: bool,
+    /// With `include_stack_trace`: frames to include (default 20)
+    pub levels: Option<usize>,
+    /// Embed the variables of the top frame's scopes in the response
+    #[serde(default)]
+    pub include_top_frame_variables: bool,
+}
 
-        let manager = self.session_manager.read().await;
-        let session = manager.get_session(&args.session_id).await?;
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotArgs {
+    pub session_id: String,
+    /// Stack frames to include (default 20)
+    pub levels: Option<i32>,
+    /// Frames, from the top, whose variables are included (default 1)
+    pub variable_frames: Option<usize>,
+    /// Last lines of program output to include (default 20)
+    pub output_lines: Option<usize>,
+    /// Bytes of each variable value and of the output (default 4096)
+    pub max_length: Option<usize>,
+}
 
-        // Validate we're in a stopped state
-        let state = session.get_state().await;
-        if !matches!(state, crate::debug::state::DebugState::Stopped { .. }) {
-            return Err(Error::InvalidState(
-                "Cannot evaluate expressions while program is running. The program must be stopped at a breakpoint, entry point, or step. Use debugger_wait_for_stop() to wait for the program to stop.".to_string()
-            ));
+impl SnapshotArgs {
+    fn options(&self) -> Result<SnapshotOptions> {
+        let defaults = SnapshotOptions::default();
+        let levels = self.levels.unwrap_or(defaults.levels);
+        if !(1..=DEFAULT_STACK_LEVELS).contains(&levels) {
+            return Err(Error::InvalidRequest(format!(
+                "levels must be between 1 and {} (got {})",
+                DEFAULT_STACK_LEVELS, levels
+            )));
         }
+        let variable_frames = self.variable_frames.unwrap_or(defaults.variable_frames);
+        if variable_frames > MAX_SNAPSHOT_VARIABLE_FRAMES {
+            return Err(Error::InvalidRequest(format!(
+                "variableFrames must be at most {} (got {}); use debugger_get_variables for deeper frames",
+                MAX_SNAPSHOT_VARIABLE_FRAMES, variable_frames
+            )));
+        }
+        let output_lines = self.output_lines.unwrap_or(defaults.output_lines);
+        if output_lines > MAX_SNAPSHOT_OUTPUT_LINES {
+            return Err(Error::InvalidRequest(format!(
+                "outputLines must be at most {} (got {}); use debugger_get_output for more",
+                MAX_SNAPSHOT_OUTPUT_LINES, output_lines
+            )));
+        }
+        Ok(SnapshotOptions {
+            levels,
+            variable_frames,
+            output_lines,
+            max_length: truncate::resolve_max_length(self.max_length)?,
+        })
+    }
+}
 
-        let result = session.evaluate(&args.expression, args.frame_id).await?;
+/// Frames embedded by wait_for_stop's `includeStackTrace` unless `levels` is given
+const DEFAULT_STOP_STACK_LEVELS: usize = 20;
 
-        Ok(json!({
-            "result": result
-        }))
-    }
+fn default_timeout() -> u64 {
+    5000
+}
 
-    async fn debugger_wait_for_stop(&self, arguments: Value) -> Result<Value> {
-        let args: WaitForStopArgs = serde_json::from_value(arguments)?;
+/// debugger_run_until_exit's limit for the whole run unless `timeoutMs` is given
+const DEFAULT_RUN_TIMEOUT_MS: u64 = 60_000;
 
-        let manager = self.session_manager.read().await;
-        let session = manager.get_session(&args.session_id).await?;
+/// Stops debugger_run_until_exit records in detail unless `maxStops` is given
+const DEFAULT_RUN_MAX_STOPS: usize = 50;
 
-        let timeout = tokio::time::Duration::from_millis(args.timeout_ms);
-        let start = tokio::time::Instant::now();
+/// debugger_run_until_exit's own arguments; the rest go to debugger_start
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunUntilExitArgs {
+    #[serde(default = "default_run_timeout")]
+    pub timeout_ms: u64,
+    /// Record the top frame's variables at every stop
+    #[serde(default)]
+    pub include_locals: bool,
+    #[serde(default = "default_run_max_stops")]
+    pub max_stops: usize,
+}
 
-        loop {
-            let state = session.get_state().await;
+fn default_run_timeout() -> u64 {
+    DEFAULT_RUN_TIMEOUT_MS
+}
 
-            // Check if we're stopped
-            if let crate::debug::state::DebugState::Stopped { thread_id, reason } = state {
-                return Ok(json!({
-                    "state": "Stopped",
-                    "threadId": thread_id,
-                    "reason": reason
-                }));
-            }
+fn default_run_max_stops() -> usize {
+    DEFAULT_RUN_MAX_STOPS
+}
 
-            // Check if program terminated
-            if matches!(state, crate::debug::state::DebugState::Terminated) {
-                return Ok(json!({
-                    "state": "Terminated",
-                    "reason": "Program exited"
-                }));
-            }
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListBreakpointsArgs {
+    pub session_id: String,
+}
 
-            // Check if program failed
-            if let crate::debug::state::DebugState::Failed { error } = state {
-                return Err(Error::Dap(format!("Session failed: {}", error)));
-            }
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepArgs {
+    pub session_id: String,
+    pub thread_id: Option<i32>,
+    /// "statement", "line" or "instruction" (adapters with
+    /// supportsSteppingGranularity)
+    pub granularity: Option<String>,
+    /// Return only once the step completed, with the new location
+    #[serde(default)]
+    pub wait_for_stop: bool,
+    /// With `wait_for_stop`: how long to wait for the stop
+    #[serde(default = "default_timeout")]
+    pub timeout_ms: u64,
+    /// debugger_step_into only: the call to enter, from
+    /// debugger_step_in_targets
+    pub target_id: Option<i32>,
+}
 
-            // Check timeout
-            if start.elapsed() > timeout {
-                return Err(Error::InvalidState(format!(
-                    "Timeout waiting for program to stop ({}ms). Current state: {:?}",
-                    args.timeout_ms, state
-                )));
-            }
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestartFrameArgs {
+    pub session_id: String,
+    pub frame_id: Option<i32>,
+    /// Position of the frame in the current stack; used without `frame_id`
+    pub frame_index: Option<i32>,
+    #[serde(default)]
+    pub wait_for_stop: bool,
+    #[serde(default = "default_timeout")]
+    pub timeout_ms: u64,
+}
 
-            // Sleep briefly before checking again
-            tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
-        }
-    }
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JumpToLineArgs {
+    pub session_id: String,
+    pub source_path: String,
+    pub line: i32,
+    pub thread_id: Option<i32>,
+}
 
-    async fn debugger_list_breakpoints(&self, arguments: Value) -> Result<Value> {
-        let args: ListBreakpointsArgs = serde_json::from_value(arguments)?;
+/// How long debugger_jump_to_line waits for the adapter's 'stopped' event
+/// (reason "goto") before reading the new top frame anyway
+const GOTO_STOP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
 
-        let manager = self.session_manager.read().await;
-        let session = manager.get_session(&args.session_id).await?;
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepInTargetsArgs {
+    pub session_id: String,
+    /// Frame whose current line is listed; the top frame when omitted
+    pub frame_id: Option<i32>,
+}
 
-        let full_state = session.get_full_state().await;
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoctorArgs {
+    /// Only check this language
+    pub language: Option<String>,
+}
 
-        // Collect all breakpoints from all source files
-        let mut all_breakpoints = Vec::new();
-        for (source_path, breakpoints) in full_state.breakpoints.iter() {
-            for bp in breakpoints {
-                all_breakpoints.push(json!({
-                    "id": bp.id,
-                    "verified": bp.verified,
-                    "line": bp.line,
-                    "sourcePath": source_path
-                }));
-            }
-        }
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionMetricsArgs {
+    pub session_id: String,
+}
 
-        Ok(json!({
-            "breakpoints": all_breakpoints
-        }))
-    }
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingPathArgs {
+    pub session_id: String,
+}
 
-    async fn debugger_step_over(&self, arguments: Value) -> Result<Value> {
-        let args: StepArgs = serde_json::from_value(arguments)?;
+/// Entries debugger_dap_log returns without `tail`
+const DEFAULT_DAP_LOG_TAIL: usize = 100;
 
-        let manager = self.session_manager.read().await;
-        let session = manager.get_session(&args.session_id).await?;
+/// Server log lines returned by debugger_server_log_tail by default
+const DEFAULT_SERVER_LOG_LINES: usize = 100;
 
-        // Validate we're in a stopped state
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DapLogArgs {
+    pub session_id: String,
+    /// Number of most recent messages to return
+    pub tail: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerLogTailArgs {
+    /// Number of most recent lines to return
+    pub lines: Option<usize>,
+}
+
+pub struct ToolsHandler {
+    /// Shared without a lock of its own: [`SessionManager`]'s methods take
+    /// `&self` and lock their own maps briefly, so a slow start or DAP
+    /// request never holds up tools working on other sessions
+    session_manager: Arc<SessionManager>,
+}
+
+impl ToolsHandler {
+    pub fn new(session_manager: Arc<SessionManager>) -> Self {
+        Self { session_manager }
+    }
+
+    /// Look up a session
+    ///
+    /// The returned `Arc` keeps the session alive by itself, even if it is
+    /// removed while a DAP request made with it is still running.
+    async fn session(&self, session_id: &str) -> Result<Arc<DebugSession>> {
+        self.session_manager.get_session(session_id).await
+    }
+
+    /// Current state name of a session, if it exists
+    pub async fn session_state_name(&self, session_id: &str) -> Option<&'static str> {
+        let session = self.session(session_id).await.ok()?;
         let state = session.get_state().await;
-        let thread_id = if let crate::debug::state::DebugState::Stopped { thread_id, .. } = state {
-            thread_id
-        } else {
-            return Err(Error::InvalidState(
-                "Cannot step while program is running. The program must be stopped first."
-                    .to_string(),
-            ));
+        Some(state.name())
+    }
+
+    pub async fn handle_tool(&self, name: &str, arguments: Value) -> Result<Value> {
+        // Tool calls on a session log under that session's span
+        let session_id = arguments
+            .get("sessionId")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let span = match &session_id {
+            Some(session_id) => tracing::info_span!("tool", tool = name, session_id = %session_id),
+            None => tracing::info_span!("tool", tool = name),
         };
 
-        let thread_id = args.thread_id.unwrap_or(thread_id);
-        session.step_over(thread_id).await?;
+        // Looked up before the call, since debugger_disconnect removes the session
+        let recorder = match &session_id {
+            Some(session_id) => self.session_recorder(session_id).await,
+            None => None,
+        };
+        let started = Instant::now();
+        let result = self
+            .dispatch_tool(name, arguments.clone())
+            .instrument(span)
+            .await;
 
-        Ok(json!({
-            "status": "stepping",
-            "threadId": thread_id
-        }))
+        // debugger_start only knows its session once it returns
+        let recorder = match recorder {
+            Some(recorder) => Some(recorder),
+            None => match result
+                .as_ref()
+                .ok()
+                .and_then(|value| value.get("sessionId"))
+                .and_then(Value::as_str)
+            {
+                Some(session_id) => self.session_recorder(session_id).await,
+                None => None,
+            },
+        };
+        if let Some(recorder) = recorder {
+            recorder.record_tool_call(name, &arguments, &result, started.elapsed());
+        }
+        if let Some(session_id) = &session_id {
+            self.session_manager.touch_session(session_id).await;
+        }
+        // Keep the --resume state file's breakpoints current
+        if result.is_ok() && matches!(name, "debugger_set_breakpoint" | "debugger_set_breakpoints")
+        {
+            self.session_manager.persist_sessions().await;
+        }
+        result
     }
 
-    async fn debugger_step_into(&self, arguments: Value) -> Result<Value> {
-        let args: StepArgs = serde_json::from_value(arguments)?;
+    /// Transcript recorder of a session, if it exists and is recorded
+    async fn session_recorder(&self, session_id: &str) -> Option<Arc<SessionRecorder>> {
+        let session = self.session(session_id).await.ok()?;
+        session.recorder().await
+    }
 
-        let manager = self.session_manager.read().await;
-        let session = manager.get_session(&args.session_id).await?;
+    async fn dispatch_tool(&self, name: &str, arguments: Value) -> Result<Value> {
+        match name {
+            "debugger_start" => self.debugger_start(arguments).await,
+            "debugger_attach" => self.debugger_attach(arguments).await,
+            "debugger_session_state" => self.debugger_session_state(arguments).await,
+            "debugger_snapshot" => self.debugger_snapshot(arguments).await,
+            "debugger_set_breakpoint" => self.debugger_set_breakpoint(arguments).await,
+            "debugger_set_breakpoints" => self.debugger_set_breakpoints(arguments).await,
+            "debugger_breakpoint_locations" => self.debugger_breakpoint_locations(arguments).await,
+            "debugger_continue" => self.debugger_continue(arguments).await,
+            "debugger_stack_trace" => self.debugger_stack_trace(arguments).await,
+            "debugger_evaluate" => self.debugger_evaluate(arguments).await,
+            "debugger_get_variables" => self.debugger_get_variables(arguments).await,
+            "debugger_read_memory" => self.debugger_read_memory(arguments).await,
+            "debugger_disassemble" => self.debugger_disassemble(arguments).await,
+            "debugger_registers" => self.debugger_registers(arguments).await,
+            "debugger_completions" => self.debugger_completions(arguments).await,
+            "debugger_loaded_sources" => self.debugger_loaded_sources(arguments).await,
+            "debugger_modules" => self.debugger_modules(arguments).await,
+            "debugger_check_sources" => self.debugger_check_sources(arguments).await,
+            "debugger_watch_status" => self.debugger_watch_status(arguments).await,
+            "debugger_disconnect" => self.debugger_disconnect(arguments).await,
+            "debugger_force_kill" => self.debugger_force_kill(arguments).await,
+            "debugger_wait_for_stop" => self.debugger_wait_for_stop(arguments).await,
+            "debugger_run_until_exit" => self.debugger_run_until_exit(arguments).await,
+            "debugger_list_breakpoints" => self.debugger_list_breakpoints(arguments).await,
+            "debugger_step_over" => self.debugger_step_over(arguments).await,
+            "debugger_step_into" => self.debugger_step_into(arguments).await,
+            "debugger_step_in_targets" => self.debugger_step_in_targets(arguments).await,
+            "debugger_step_out" => self.debugger_step_out(arguments).await,
+            "debugger_restart_frame" => self.debugger_restart_frame(arguments).await,
+            "debugger_jump_to_line" => self.debugger_jump_to_line(arguments).await,
+            "debugger_get_output" => self.debugger_get_output(arguments).await,
+            "debugger_set_function_breakpoints" => {
+                self.debugger_set_function_breakpoints(arguments).await
+            }
+            "debugger_set_data_breakpoint" => self.debugger_set_data_breakpoint(arguments).await,
+            "debugger_add_watch" => self.debugger_add_watch(arguments).await,
+            "debugger_remove_watch" => self.debugger_remove_watch(arguments).await,
+            "debugger_list_watches" => self.debugger_list_watches(arguments).await,
+            "debugger_saved_breakpoints" => self.debugger_saved_breakpoints(arguments).await,
+            "debugger_save_config" => self.debugger_save_config(arguments).await,
+            "debugger_list_configs" => self.debugger_list_configs(arguments).await,
+            "debugger_doctor" => self.debugger_doctor(arguments).await,
+            "debugger_dap_log" => self.debugger_dap_log(arguments).await,
+            "debugger_session_metrics" => self.debugger_session_metrics(arguments).await,
+            "debugger_server_log_tail" => self.debugger_server_log_tail(arguments).await,
+            "debugger_recording_path" => self.debugger_recording_path(arguments).await,
+            // Stands in for a bug in a handler (see McpServer::run)
+            #[cfg(test)]
+            "debugger_test_panic" => panic!("injected panic"),
+            _ => Err(Error::MethodNotFound(name.to_string())),
+        }
+    }
 
-        // Validate we're in a stopped state
-        let state = session.get_state().await;
-        let thread_id = if let crate::debug::state::DebugState::Stopped { thread_id, .. } = state {
-            thread_id
-        } else {
-            return Err(Error::InvalidState(
-                "Cannot step while program is running. The program must be stopped first."
-                    .to_string(),
+    async fn debugger_start(&self, arguments: Value) -> Result<Value> {
+        let (arguments, config_name) = self.resolve_start_config(arguments).await?;
+        let args: DebuggerStartArgs = serde_json::from_value(Value::Object(arguments.clone()))?;
+
+        let node_launch = Self::node_launch_options(&args)?;
+        let python_module = Self::python_module(&args)?;
+        let python_launch = Self::python_launch_options(&args)?;
+        let ruby_launch = Self::ruby_launch_options(&args)?;
+        let lldb_launch = Self::lldb_launch_options(&args)?;
+        let core_dump = Self::core_dump(&args)?;
+
+        let (program, validated_cwd) = if let Some(module) = &python_module {
+            // The module is resolved by Python; the session is named after it
+            let cwd = match &args.cwd {
+                Some(cwd) if !args.skip_validation => Some(
+                    security::validate_directory_path(cwd)?
+                        .to_str()
+                        .ok_or_else(|| {
+                            Error::Internal("Non-UTF8 cwd path (invalid encoding)".to_string())
+                        })?
+                        .to_string(),
+                ),
+                cwd => cwd.clone(),
+            };
+            (module.clone(), cwd)
+        } else if args.program.is_empty() {
+            return Err(Error::InvalidRequest(
+                "program is required (or module, for Python)".to_string(),
             ));
+        } else if args.skip_validation || core_dump.is_some() {
+            (args.program.clone(), args.cwd.clone())
+        } else if node_launch.uses_package_manager() {
+            Self::validate_package_target(&args.program, args.cwd.as_deref())?
+        } else {
+            Self::validate_launch_target(&args.language, &args.program, args.cwd.as_deref())?
         };
 
-        let thread_id = args.thread_id.unwrap_or(thread_id);
-        session.step_into(thread_id).await?;
+        // Bundled programs run from the Gemfile's directory; fail early without one
+        let validated_cwd = match validated_cwd {
+            None if ruby_launch.use_bundler && !args.skip_validation => {
+                let root = ruby::gemfile_root(&program, None)?;
+                Some(root.to_str().map(str::to_string).ok_or_else(|| {
+                    Error::Internal("Non-UTF8 cwd path (invalid encoding)".to_string())
+                })?)
+            }
+            Some(cwd) if ruby_launch.use_bundler && !args.skip_validation => {
+                ruby::gemfile_root(&program, Some(&cwd))?;
+                Some(cwd)
+            }
+            cwd => cwd,
+        };
 
-        Ok(json!({
-            "status": "stepping",
-            "threadId": thread_id
-        }))
-    }
+        if args.request_timeout_ms == Some(0) {
+            return Err(Error::InvalidRequest(
+                "requestTimeoutMs must be greater than 0".to_string(),
+            ));
+        }
 
-    async fn debugger_step_out(&self, arguments: Value) -> Result<Value> {
-        let args: StepArgs = serde_json::from_value(arguments)?;
+        if args.adapter_timeout_ms == Some(0) {
+            return Err(Error::InvalidRequest(
+                "adapterTimeoutMs must be greater than 0".to_string(),
+            ));
+        }
 
-        let manager = self.session_manager.read().await;
-        let session = manager.get_session(&args.session_id).await?;
+        if args.entry_line == Some(0) {
+            return Err(Error::InvalidRequest(
+                "entryLine must be 1 or greater (lines are 1-indexed)".to_string(),
+            ));
+        }
 
-        // Validate we're in a stopped state
-        let state = session.get_state().await;
-        let thread_id = if let crate::debug::state::DebugState::Stopped { thread_id, .. } = state {
-            thread_id
+        let (cargo_target, test_filter) = Self::cargo_target_options(&args)?;
+
+        // Relative breakpoint paths resolve against the program's working
+        // directory, as the manager defaults it
+        let breakpoint_base = validated_cwd.clone().or_else(|| {
+            let program = std::path::Path::new(&program);
+            let dir = if program.is_dir() {
+                Some(program)
+            } else {
+                program.parent()
+            };
+            dir.and_then(|dir| dir.to_str()).map(str::to_string)
+        });
+        let breakpoints =
+            Self::initial_breakpoints(&args.breakpoints, breakpoint_base.as_deref()).await?;
+        let initial_breakpoints = breakpoints.len();
+
+        let manager = &self.session_manager;
+        // Checked before anything is started: a rejected path must not leave
+        // a session behind
+        let watch_paths = Self::watch_paths(&args, &program, manager.allowed_paths())?;
+        let mut options = SessionOptions {
+            request_timeout: args
+                .request_timeout_ms
+                .map(std::time::Duration::from_millis),
+            entry_line: args.entry_line,
+            breakpoints,
+            cargo_target,
+            test_filter,
+            force_rebuild: args.force_rebuild,
+            path_mapper: Self::path_mapper(&args)?,
+            adapter_timeouts: args
+                .adapter_timeout_ms
+                .map(|ms| AdapterTimeouts::new(std::time::Duration::from_millis(ms))),
+            capture_dap_log: args.capture_dap_log,
+            record: args.record,
+            node_launch,
+            python_module,
+            python_launch,
+            ruby_launch,
+            lldb_launch,
+            core_dump,
+            session_id: None,
+            existing_session: match (args.reuse_existing, args.exclusive) {
+                (true, _) => ExistingSession::Reuse,
+                (false, true) => ExistingSession::Fail,
+                (false, false) => ExistingSession::Start,
+            },
+        };
+        let restored_breakpoints = if args.restore_breakpoints {
+            options.restore_breakpoints(manager.saved_breakpoints(&program).await)
         } else {
-            return Err(Error::InvalidState(
-                "Cannot step while program is running. The program must be stopped first."
-                    .to_string(),
-            ));
+            0
         };
 
-        let thread_id = args.thread_id.unwrap_or(thread_id);
-        session.step_out(thread_id).await?;
-
-        Ok(json!({
-            "status": "stepping",
-            "threadId": thread_id
-        }))
-    }
+        let started = manager
+            .start_session(
+                &args.language,
+                program,
+                args.args,
+                validated_cwd,
+                args.stop_on_entry,
+                options,
+            )
+            .await?;
+        let session_id = started.session_id;
+        if started.reused {
+            // Left as it is: no breakpoints, watch or saved configuration
+            let session = manager.get_session(&session_id).await?;
+            let state = session.get_state().await;
+            return Ok(json!({
+                "sessionId": session_id,
+                "status": "reused",
+                "state": state.name()
+            }));
+        }
 
-    async fn debugger_disconnect(&self, arguments: Value) -> Result<Value> {
-        let args: DisconnectArgs = serde_json::from_value(arguments)?;
+        let mut result = json!({
+            "sessionId": session_id,
+            "status": "started",
+            "initialBreakpoints": initial_breakpoints,
+            "restoredBreakpoints": restored_breakpoints
+        });
+        // Resolved language, adapter, program, cwd and build
+        if let (Some(info), Value::Object(result)) = (started.info, &mut result) {
+            if let Value::Object(info) = serde_json::to_value(info)? {
+                result.extend(info);
+            }
+        }
+        // Compiled languages report whether a rebuild happened
+        if let Some(build) = manager.get_session(&session_id).await?.build() {
+            result["build"] = build.to_json();
+        }
+        if let Some(name) = config_name {
+            result["config"] = json!(name);
+        }
+        if let Some(paths) = watch_paths {
+            manager.watch_session(&session_id, paths.clone()).await?;
+            result["watchPaths"] = json!(paths);
+        }
+        manager
+            .save_config(LAST_CONFIG, arguments, ConfigSource::LastStart)
+            .await;
 
-        let manager = self.session_manager.write().await;
-        manager.remove_session(&args.session_id).await?;
+        Ok(result)
+    }
 
-        Ok(json!({
-            "status": "disconnected"
-        }))
+    /// Files to watch with `watchMode`: the program (when it is a file) and
+    /// `watchPaths`, each within `allowed`; `None` without watch mode
+    fn watch_paths(
+        args: &DebuggerStartArgs,
+        program: &str,
+        allowed: &AllowedPaths,
+    ) -> Result<Option<Vec<String>>> {
+        if !args.watch_mode {
+            if !args.watch_paths.is_empty() {
+                return Err(Error::InvalidRequest(
+                    "watchPaths requires watchMode: true".to_string(),
+                ));
+            }
+            return Ok(None);
+        }
+        let mut paths = Vec::new();
+        if std::path::Path::new(program).is_file() {
+            paths.push(program.to_string());
+        }
+        for path in &args.watch_paths {
+            let validated = security::validate_source_path(path, None)?;
+            let path = validated
+                .to_str()
+                .ok_or_else(|| {
+                    Error::Internal("Non-UTF8 watch path (invalid encoding)".to_string())
+                })?
+                .to_string();
+            allowed.check(&path)?;
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        if paths.is_empty() {
+            return Err(Error::InvalidRequest(
+                "watchMode needs a program file or watchPaths to watch".to_string(),
+            ));
+        }
+        Ok(Some(paths))
     }
 
-    pub fn list_tools() -> Vec<Value> {
-        vec![
-            json!({
-                "name": "debugger_start",
-                "title": "Start Debugging Session",
-                "description": "Starts a new debugging session for a program. RETURNS IMMEDIATELY with a sessionId while initialization happens asynchronously in the background.\n\nIMPORTANT WORKFLOW:\n1. Call this tool first to create a session\n2. Use debugger_wait_for_stop to wait for entry point (if stopOnEntry: true)\n3. Once stopped, set breakpoints with debugger_set_breakpoint\n4. Control execution with debugger_continue\n\nTIMING: Returns in <100ms. Background initialization takes 200-500ms.\n\n⭐ CRITICAL: stopOnEntry Parameter\n=================================\nFor reliable breakpoint debugging, ALWAYS use stopOnEntry: true:\n\n✅ RECOMMENDED (with stopOnEntry: true):\n  - Program pauses at first executable line\n  - Gives you time to set breakpoints before execution\n  - Prevents program from completing before breakpoints are set\n  - Required for debugging programs that execute quickly\n\n❌ NOT RECOMMENDED (stopOnEntry: false or omitted):\n  - Program runs immediately upon start\n  - May complete before breakpoints can be set\n  - Breakpoints might be missed\n  - Only use if you don't need breakpoints\n\nEXAMPLE WORKFLOW:\n  debugger_start({program: \"app.py\", stopOnEntry: true})\n  debugger_wait_for_stop()  // Wait for entry point\n  debugger_set_breakpoint({line: 20})  // Set while paused ✓\n  debugger_continue()  // Now resume to breakpoint\n\nSEE ALSO: debugger_wait_for_stop (efficient waiting), debugger_session_state (state checking), debugger://workflows (complete examples)",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "language": {
-                            "type": "string",
-                            "description": "Programming language (e.g., 'python', 'ruby', 'javascript', 'rust', 'go')"
-                        },
-                        "program": {
-                            "type": "string",
-                            "description": "Absolute or relative path to the program file to debug"
-                        },
-                        "args": {
-                            "type": "array",
-                            "items": { "type": "string" },
-                            "description": "Command-line arguments passed to the program (optional, defaults to empty array)"
-                        },
-                        "cwd": {
-                            "type": "string",
-                            "description": "Working directory for the program execution (optional, defaults to program's directory)"
-                        },
-                        "stopOnEntry": {
-                            "type": "boolean",
-                            "description": "If true, pauses execution at the program's first line (recommended for setting early breakpoints)"
-                        }
-                    },
-                    "required": ["language", "program"]
-                },
-                "annotations": {
-                    "async": true,
-                    "returnsTiming": "< 100ms",
-                    "completionTiming": "200-500ms (background)",
-                    "workflow": "initialization",
-                    "requiredFollowUp": ["debugger_session_state"],
-                    "category": "session-management",
-                    "priority": 1.0
-                }
-            }),
-            json!({
-                "name": "debugger_session_state",
-                "title": "Check Session State",
-                "description": "Retrieves the current state of a debugging session. Essential for tracking async initialization progress.\n\nWORKFLOW USAGE:\n- After debugger_start: Poll this until state is 'Running' or 'Stopped' (not 'Initializing')\n- Before setting breakpoints: Verify state is 'Stopped' (with stopOnEntry) or 'Running'\n- After operations: Check state to verify success or detect failures\n\nSTATES:\n- NotStarted: Session created but not yet initialized\n- Initializing: DAP adapter starting (wait for this to complete)\n- Launching: Program starting\n- Running: Program executing (can set breakpoints)\n- Stopped: Hit breakpoint or paused (details.reason shows why)\n- Terminated: Program exited normally\n- Failed: Error occurred (details.error shows message)\n\nTIMING: Returns immediately (<10ms)\n\nTIP: When state is 'Stopped', check details.reason to understand why (e.g., 'entry', 'breakpoint', 'step')\n\nSEE ALSO: debugger://state-machine (complete state diagram), debugger-docs://guide/async-initialization",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "sessionId": {
-                            "type": "string",
-                            "description": "Session ID returned from debugger_start"
-                        }
-                    },
-                    "required": ["sessionId"]
-                },
-                "annotations": {
-                    "async": false,
-                    "returnsTiming": "< 10ms",
-                    "workflow": "state-checking",
-                    "category": "session-management",
-                    "pollable": true,
-                    "priority": 0.9
-                }
-            }),
-            json!({
-                "name": "debugger_set_breakpoint",
-                "title": "Set Breakpoint",
-                "description": "Sets a breakpoint at a specific line in a source file. The debugger will pause execution when this line is about to execute.\n\nWORKFLOW:\n1. Ensure session state is 'Stopped' (recommended) or 'Running'\n2. Call this tool with the source file path and line number\n3. Check the 'verified' field in response (true = breakpoint accepted)\n4. Use debugger_continue to resume execution until breakpoint is hit\n\nTIMING: Returns in 5-20ms\n\nIMPORTANT: Use stopOnEntry: true when starting the session to pause before code execution, giving you time to set breakpoints.\n\nTIP: The sourcePath must match the path used by the debugger. For best results, use absolute paths.\n\nRETURNS:\n- verified: true if breakpoint was successfully set and recognized by the debugger\n- sourcePath: echo of the source file path\n- line: echo of the line number\n\nSEE ALSO: debugger_continue (to hit the breakpoint), debugger://workflows (breakpoint examples)",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "sessionId": {
-                            "type": "string",
-                            "description": "Session ID from debugger_start"
-                        },
-                        "sourcePath": {
-                            "type": "string",
-                            "description": "Absolute or relative path to the source file (must match debugger's path resolution)"
-                        },
-                        "line": {
-                            "type": "integer",
-                            "description": "Line number where breakpoint should be set (1-indexed, i.e., first line is 1)"
-                        }
-                    },
-                    "required": ["sessionId", "sourcePath", "line"]
-                },
-                "annotations": {
-                    "async": false,
-                    "returnsTiming": "5-20ms",
-                    "workflow": "breakpoint-management",
-                    "category": "debugging",
-                    "requiresState": ["Running", "Stopped"],
-                    "priority": 0.8
+    /// Expand debugger_start's `config`: the named configuration with the
+    /// call's other arguments overriding its fields
+    async fn resolve_start_config(
+        &self,
+        arguments: Value,
+    ) -> Result<(Map<String, Value>, Option<String>)> {
+        let Value::Object(mut overrides) = arguments else {
+            return Err(Error::InvalidRequest(
+                "debugger_start arguments must be an object".to_string(),
+            ));
+        };
+        let name = match overrides.remove("config") {
+            None => return Ok((overrides, None)),
+            Some(Value::String(name)) => name,
+            Some(other) => {
+                return Err(Error::InvalidRequest(format!(
+                    "config must be the name of a saved configuration, got {}",
+                    other
+                )))
+            }
+        };
+
+        let manager = &self.session_manager;
+        if manager.config(&name).await.is_none() {
+            // A program can bring its own .debugger-mcp.json
+            let dir = overrides
+                .get("program")
+                .and_then(Value::as_str)
+                .and_then(|program| std::path::Path::new(program).parent());
+            if let Some(dir) = dir {
+                manager.load_project_configs(dir).await?;
+            }
+        }
+        let Some(config) = manager.config(&name).await else {
+            let names: Vec<String> = manager
+                .list_configs()
+                .await
+                .into_iter()
+                .map(|config| config.name)
+                .collect();
+            return Err(Error::InvalidRequest(format!(
+                "Unknown configuration \"{}\" (available: {})",
+                name,
+                if names.is_empty() {
+                    "none; save one with debugger_save_config".to_string()
+                } else {
+                    names.join(", ")
                 }
-            }),
-            json!({
-                "name": "debugger_continue",
-                "title": "Continue Execution",
-                "description": "Resumes program execution after being paused (e.g., at a breakpoint or entry point). Execution continues until the next breakpoint, exception, or program termination.\n\nWORKFLOW:\n1. Session must be in 'Stopped' state (verify with debugger_session_state)\n2. Call this tool to resume execution\n3. Poll debugger_session_state to detect when execution stops again\n4. When state returns to 'Stopped', check details.reason:\n   - 'breakpoint': Hit a breakpoint (use debugger_stack_trace to inspect)\n   - 'exception': Uncaught exception occurred\n   - 'pause': Manual pause requested\n   - 'step': Completed a step operation\n\nTIMING: Returns in <10ms (but program continues running asynchronously)\n\nTIP: After calling continue, immediately poll debugger_session_state in a loop to detect when the program stops again.\n\nRETURNS: {\"status\": \"continued\"}\n\nSEE ALSO: debugger_stack_trace (inspect state when stopped), debugger://workflows (execution control patterns)",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "sessionId": {
-                            "type": "string",
-                            "description": "Session ID from debugger_start"
-                        }
-                    },
-                    "required": ["sessionId"]
-                },
-                "annotations": {
-                    "async": true,
-                    "returnsTiming": "< 10ms",
-                    "completionTiming": "unknown (until next stop)",
-                    "workflow": "execution-control",
-                    "category": "debugging",
-                    "requiresState": ["Stopped"],
-                    "priority": 0.7
+            )));
+        };
+
+        let merged = config_store::merge(&config.config, &overrides);
+        Self::validate_start_config(&merged).map_err(|e| match e {
+            Error::InvalidRequest(msg) => {
+                Error::InvalidRequest(format!("configuration \"{}\": {}", name, msg))
+            }
+            other => other,
+        })?;
+        Ok((merged, Some(name)))
+    }
+
+    /// Check debugger_start arguments field by field, so errors name the
+    /// offending field instead of failing the whole payload
+    fn validate_start_config(config: &Map<String, Value>) -> Result<()> {
+        let schema = Self::list_tools()
+            .into_iter()
+            .find(|tool| tool["name"] == "debugger_start")
+            .map(|tool| tool["inputSchema"]["properties"].clone())
+            .unwrap_or_default();
+
+        for (field, value) in config {
+            if field == "config" {
+                return Err(Error::InvalidRequest(
+                    "field \"config\": configurations cannot refer to other configurations"
+                        .to_string(),
+                ));
+            }
+            if schema.get(field).is_none() {
+                return Err(Error::InvalidRequest(format!(
+                    "unknown field \"{}\" (not a debugger_start argument)",
+                    field
+                )));
+            }
+            // Deserialize the field on its own to see whether its value fits
+            let mut probe = Map::new();
+            probe.insert("language".to_string(), json!("python"));
+            probe.insert(field.clone(), value.clone());
+            if let Err(e) = serde_json::from_value::<DebuggerStartArgs>(Value::Object(probe)) {
+                return Err(Error::InvalidRequest(format!("field \"{}\": {}", field, e)));
+            }
+        }
+        if !config.contains_key("language") {
+            return Err(Error::InvalidRequest(
+                "field \"language\" is required".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check program and cwd before any adapter is spawned
+    ///
+    /// A missing program otherwise only shows up as a timeout waiting for
+    /// the adapter's 'initialized' event. A relative program is resolved
+    /// against `cwd` when one is given. Returns the canonical program and
+    /// cwd paths.
+    fn validate_launch_target(
+        language: &str,
+        program: &str,
+        cwd: Option<&str>,
+    ) -> Result<(String, Option<String>)> {
+        let in_cwd;
+        let program = match cwd {
+            Some(cwd) if std::path::Path::new(program).is_relative() => {
+                in_cwd = std::path::Path::new(cwd).join(program);
+                in_cwd.to_str().ok_or_else(|| {
+                    Error::Internal("Non-UTF8 program path (invalid encoding)".to_string())
+                })?
+            }
+            _ => program,
+        };
+        let program_path = std::path::Path::new(program);
+        let metadata = std::fs::metadata(program_path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                Error::InvalidRequest(format!("program not found: {}", program))
+            }
+            _ => Error::InvalidRequest(format!("program is not accessible: {}: {}", program, e)),
+        })?;
+
+        // Shell scripts often have no extension, C++ has several (checked when
+        // compiling), and Delve also takes package directories and binaries
+        let extensions: Option<&[&str]> = match language {
+            "rust" => Some(&["rs"]),
+            "python" => Some(&["py"]),
+            "ruby" => Some(&["rb"]),
+            "javascript" | "nodejs" => Some(nodejs::PROGRAM_EXTENSIONS),
+            "c" => Some(&["c"]),
+            _ => None,
+        };
+        let is_go = matches!(language, "go" | "golang");
+        if metadata.is_dir() && !is_go {
+            return Err(Error::InvalidRequest(format!(
+                "program is a directory, not a file: {}",
+                program
+            )));
+        }
+        if metadata.is_file() {
+            std::fs::File::open(program_path).map_err(|e| {
+                Error::InvalidRequest(format!("program is not readable: {}: {}", program, e))
+            })?;
+        }
+        let actual = program_path.extension().and_then(|e| e.to_str());
+        let extension = match extensions {
+            Some(expected) if !actual.is_some_and(|actual| expected.contains(&actual)) => {
+                let expected: Vec<String> = expected.iter().map(|e| format!(".{}", e)).collect();
+                return Err(Error::InvalidRequest(format!(
+                    "program must be a {} file for {}: {}",
+                    expected.join(" or "),
+                    language,
+                    program
+                )));
+            }
+            Some(_) => actual,
+            None => None,
+        };
+
+        if let Some(cwd) = cwd {
+            match std::fs::metadata(cwd) {
+                Ok(metadata) if metadata.is_dir() => {}
+                Ok(_) => {
+                    return Err(Error::InvalidRequest(format!(
+                        "cwd is not a directory: {}",
+                        cwd
+                    )))
                 }
-            }),
-            json!({
-                "name": "debugger_stack_trace",
-                "title": "Get Stack Trace",
-                "description": "Retrieves the current call stack when execution is paused. Shows the sequence of function calls that led to the current execution point.\n\n⭐ PRIMARY PURPOSE: Get Frame IDs for debugger_evaluate\n======================================================\nThe 'id' field in each frame is CRITICAL - use it with debugger_evaluate to access variables:\n\nRETURNS: Array of stack frames, each containing:\n- id: Frame identifier → USE THIS as frameId in debugger_evaluate ⭐\n- name: Function/method name\n- source: {path: \"file path\", name: \"filename\"}\n- line: Current line number in this frame\n- column: Column number (if available)\n\n⚠️ Frame IDs Change Between Stops!\n================================\nFrame IDs are NOT stable across different stop events:\n- After EACH stop (breakpoint, step, continue), frame IDs change\n- ALWAYS call debugger_stack_trace fresh after each stop\n- NEVER reuse frame IDs from previous stops\n\nEXAMPLE PATTERN:\n  // Stop 1: Hit breakpoint\n  debugger_wait_for_stop()\n  stack1 = debugger_stack_trace()\n  frameId1 = stack1.stackFrames[0].id  // e.g., id = 5\n  debugger_evaluate({expression: \"x\", frameId: frameId1})  ✓\n  \n  // Stop 2: After continue and hit another breakpoint\n  debugger_continue()\n  debugger_wait_for_stop()\n  stack2 = debugger_stack_trace()  // GET FRESH TRACE!\n  frameId2 = stack2.stackFrames[0].id  // e.g., id = 8 (DIFFERENT!)\n  \n  // Using old frameId1 here would FAIL ❌\n  debugger_evaluate({expression: \"x\", frameId: frameId2})  ✓ Correct\n\nWORKFLOW:\n1. Session must be in 'Stopped' state (e.g., at a breakpoint)\n2. Call this tool to get current stack frames\n3. Extract the 'id' field from desired frame\n4. Pass that 'id' as frameId to debugger_evaluate\n5. Repeat steps 2-4 after each new stop event\n\nTIMING: Returns in 10-50ms depending on stack depth\n\nTIP: The first frame (index 0) is the current execution point. Higher indices are caller frames.\n\nCOMMON USE CASES:\n- Get frame IDs for debugger_evaluate (primary use)\n- Inspect where a breakpoint was hit\n- Understand call hierarchy\n- Diagnose unexpected execution paths\n\nSEE ALSO: debugger_evaluate (requires frame IDs from this tool), debugger://patterns (frame ID usage examples)",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "sessionId": {
-                            "type": "string",
-                            "description": "Session ID from debugger_start"
-                        }
-                    },
-                    "required": ["sessionId"]
-                },
-                "annotations": {
-                    "async": false,
-                    "returnsTiming": "10-50ms",
-                    "workflow": "inspection",
-                    "category": "debugging",
-                    "requiresState": ["Stopped"],
-                    "priority": 0.6
+                Err(_) => {
+                    return Err(Error::InvalidRequest(format!("cwd not found: {}", cwd)));
                 }
-            }),
-            json!({
-                "name": "debugger_evaluate",
-                "title": "Evaluate Expression",
-                "description": "Evaluates an expression in the context of the paused program. Can access variables, call functions, and perform computations using the program's current state.\n\n⚠️ CRITICAL: frameId Requirement\n================================\nWhile technically optional, frameId is REQUIRED in practice for accessing local variables:\n\n❌ WITHOUT frameId:\n  debugger_evaluate({expression: \"local_var\"})\n  → Result: NameError: name 'local_var' is not defined\n  \n  Why: Evaluates in global/default context where local variables don't exist\n\n✅ WITH frameId (REQUIRED WORKFLOW):\n  1. Get stack trace: stack = debugger_stack_trace()\n  2. Extract frame ID: frameId = stack.stackFrames[0].id\n  3. Evaluate with frameId:\n     debugger_evaluate({expression: \"local_var\", frameId: frameId})\n  → Result: Successfully accesses local variable ✓\n\n⚠️ Frame IDs Change Between Stops!\n  - Frame IDs are NOT stable across different stop events\n  - ALWAYS get a fresh stack trace after each stop\n  - NEVER reuse frame IDs from previous stops\n\nEXAMPLE PATTERN (Correct Way):\n  // After hitting breakpoint:\n  const stack = debugger_stack_trace()\n  const frameId = stack.stackFrames[0].id  // Current frame\n  const value = debugger_evaluate({expression: \"n\", frameId: frameId})\n  \n  // After next stop, get NEW frame ID:\n  const stack2 = debugger_stack_trace()  // Fresh trace!\n  const frameId2 = stack2.stackFrames[0].id  // New frame ID\n  const value2 = debugger_evaluate({expression: \"n\", frameId: frameId2})\n\nWORKFLOW:\n1. Session must be in 'Stopped' state\n2. Call debugger_stack_trace to get current stack frames\n3. Extract frame ID from desired frame (usually frame[0] for current location)\n4. Call this tool with expression AND frameId\n5. Examine the result value\n\nTIMING: Returns in 20-200ms depending on expression complexity\n\nEXPRESSION EXAMPLES:\n- Variable access: \"x\", \"obj.property\", \"array[0]\"\n- Arithmetic: \"x + y\", \"count * 2\"\n- Comparisons: \"x > 10\", \"status == 'ready'\"\n- Function calls: \"len(array)\", \"obj.method()\"\n- Complex: \"[item for item in list if item > 0]\" (Python)\n\nRETURNS: {\"result\": \"string representation of evaluation result\"}\n\nCOMMON ERROR:\n  \"NameError: name 'variable' is not defined\"\n  → Solution: Add frameId parameter from debugger_stack_trace\n\nSEE ALSO: debugger_stack_trace (get frame IDs), debugger://patterns (cookbook examples)",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "sessionId": {
-                            "type": "string",
-                            "description": "Session ID from debugger_start"
-                        },
-                        "expression": {
-                            "type": "string",
-                            "description": "Expression to evaluate (syntax depends on programming language being debugged)"
-                        },
-                        "frameId": {
-                            "type": "integer",
-                            "description": "Stack frame ID from debugger_stack_trace (optional, defaults to current frame)"
-                        }
-                    },
-                    "required": ["sessionId", "expression"]
-                },
-                "annotations": {
-                    "async": false,
-                    "returnsTiming": "20-200ms",
-                    "workflow": "inspection",
-                    "category": "debugging",
-                    "requiresState": ["Stopped"],
-                    "priority": 0.5
+            }
+        }
+
+        // Prevent path traversal and enforce WORKSPACE_ROOT
+        let validated_program = security::validate_source_path(program, extension)?;
+        let program = validated_program
+            .to_str()
+            .ok_or_else(|| Error::Internal("Non-UTF8 program path (invalid encoding)".to_string()))?
+            .to_string();
+        let cwd = match cwd {
+            Some(cwd) => Some(
+                security::validate_directory_path(cwd)?
+                    .to_str()
+                    .ok_or_else(|| {
+                        Error::Internal("Non-UTF8 cwd path (invalid encoding)".to_string())
+                    })?
+                    .to_string(),
+            ),
+            None => None,
+        };
+        Ok((program, cwd))
+    }
+
+    /// Validate a package started through npm/yarn/pnpm
+    ///
+    /// `program` is the package directory, its package.json or a file inside
+    /// the package; the working directory defaults to the package root (the
+    /// nearest directory with a package.json).
+    fn validate_package_target(
+        program: &str,
+        cwd: Option<&str>,
+    ) -> Result<(String, Option<String>)> {
+        let program_path = std::path::Path::new(program);
+        if !program_path.exists() {
+            return Err(Error::InvalidRequest(format!(
+                "program not found: {}",
+                program
+            )));
+        }
+        let root = nodejs::package_root(program_path).ok_or_else(|| {
+            Error::InvalidRequest(format!(
+                "no package.json found in or above {}; npmScript and package manager \
+                 runtimes need the package directory",
+                program
+            ))
+        })?;
+
+        let to_string = |path: std::path::PathBuf| {
+            path.to_str()
+                .map(str::to_string)
+                .ok_or_else(|| Error::Internal("Non-UTF8 path (invalid encoding)".to_string()))
+        };
+        // Prevent path traversal and enforce WORKSPACE_ROOT
+        let program = if program_path.is_dir() {
+            security::validate_directory_path(program)?
+        } else {
+            security::validate_source_path(program, None)?
+        };
+        let cwd = match cwd {
+            Some(cwd) => security::validate_directory_path(cwd)?,
+            None => security::validate_directory_path(&to_string(root)?)?,
+        };
+        Ok((to_string(program)?, Some(to_string(cwd)?)))
+    }
+
+    /// debugger_start's `module`, checked: Python only, a dotted module name,
+    /// and not combined with a program
+    fn python_module(args: &DebuggerStartArgs) -> Result<Option<String>> {
+        let Some(module) = &args.module else {
+            return Ok(None);
+        };
+        if args.language != "python" {
+            return Err(Error::InvalidRequest(format!(
+                "module is only supported for language \"python\", not \"{}\"",
+                args.language
+            )));
+        }
+        let valid = !module.is_empty()
+            && module.split('.').all(|part| {
+                part.chars()
+                    .next()
+                    .is_some_and(|c| c.is_alphabetic() || c == '_')
+                    && part.chars().all(|c| c.is_alphanumeric() || c == '_')
+            });
+        if !valid {
+            return Err(Error::InvalidRequest(format!(
+                "module must be a Python module name like \"pytest\" or \"package.tool\", got \"{}\"",
+                module
+            )));
+        }
+        if !args.program.is_empty() {
+            return Err(Error::InvalidRequest(
+                "program and module cannot be combined: pass the test file or other \
+                 targets in args"
+                    .to_string(),
+            ));
+        }
+        Ok(Some(module.clone()))
+    }
+
+    /// debugger_start's `subProcess` and `webFramework` (Python only)
+    fn python_launch_options(args: &DebuggerStartArgs) -> Result<PythonLaunchOptions> {
+        let web_framework = match &args.web_framework {
+            Some(name) => Some(WebFramework::from_name(name).ok_or_else(|| {
+                Error::InvalidRequest(format!(
+                    "unknown webFramework \"{}\" (expected one of: {})",
+                    name,
+                    WebFramework::NAMES.join(", ")
+                ))
+            })?),
+            None => None,
+        };
+        let options = PythonLaunchOptions {
+            sub_process: args.sub_process,
+            web_framework,
+        };
+        if options != PythonLaunchOptions::default() && args.language != "python" {
+            return Err(Error::InvalidRequest(format!(
+                "subProcess and webFramework are only supported for language \"python\", not \"{}\"",
+                args.language
+            )));
+        }
+        if web_framework.is_some() && args.module.is_some() {
+            return Err(Error::InvalidRequest(
+                "webFramework and module cannot be combined: webFramework chooses how the \
+                 server is started"
+                    .to_string(),
+            ));
+        }
+        Ok(options)
+    }
+
+    /// debugger_start's `useBundler` and `command` (Ruby only)
+    fn ruby_launch_options(args: &DebuggerStartArgs) -> Result<RubyLaunchOptions> {
+        let options = RubyLaunchOptions {
+            use_bundler: args.use_bundler,
+            command: args.command.clone(),
+        };
+        if options != RubyLaunchOptions::default() && args.language != "ruby" {
+            return Err(Error::InvalidRequest(format!(
+                "useBundler and command are only supported for language \"ruby\", not \"{}\"",
+                args.language
+            )));
+        }
+        if options.command.iter().any(|part| part.trim().is_empty()) {
+            return Err(Error::InvalidRequest(
+                "command must not contain empty entries (each entry is one argument)".to_string(),
+            ));
+        }
+        Ok(options)
+    }
+
+    /// debugger_start's `expressions`, `initCommands`, `preRunCommands`,
+    /// `env`, `stdio` and `terminal` (CodeLLDB languages only)
+    fn lldb_launch_options(args: &DebuggerStartArgs) -> Result<LldbLaunchOptions> {
+        let expressions = match &args.expressions {
+            Some(name) => Some(LldbExpressions::from_name(name).ok_or_else(|| {
+                Error::InvalidRequest(format!(
+                    "unknown expressions \"{}\" (expected one of: {})",
+                    name,
+                    LldbExpressions::NAMES.join(", ")
+                ))
+            })?),
+            None => None,
+        };
+        let terminal = match &args.terminal {
+            Some(name) => Some(LldbTerminal::from_name(name).ok_or_else(|| {
+                Error::InvalidRequest(format!(
+                    "unknown terminal \"{}\" (expected one of: {})",
+                    name,
+                    LldbTerminal::NAMES.join(", ")
+                ))
+            })?),
+            None => None,
+        };
+        if args.stdio.len() > 3 {
+            return Err(Error::InvalidRequest(format!(
+                "stdio has {} entries; expected at most 3 (stdin, stdout, stderr)",
+                args.stdio.len()
+            )));
+        }
+        let options = LldbLaunchOptions {
+            expressions,
+            init_commands: args.init_commands.clone(),
+            pre_run_commands: args.pre_run_commands.clone(),
+            env: args.env.clone(),
+            stdio: args.stdio.clone(),
+            terminal,
+        };
+        if options != LldbLaunchOptions::default()
+            && !matches!(args.language.as_str(), "rust" | "c" | "cpp")
+        {
+            return Err(Error::InvalidRequest(format!(
+                "expressions, initCommands, preRunCommands, env, stdio and terminal are \
+                 only supported for languages \"rust\", \"c\" and \"cpp\", not \"{}\"",
+                args.language
+            )));
+        }
+        Ok(options)
+    }
+
+    /// debugger_start's `coreDump` (CodeLLDB languages only)
+    ///
+    /// The program is the binary that crashed: it is loaded as is, so source
+    /// files (which would be rebuilt) and Cargo targets are rejected.
+    fn core_dump(args: &DebuggerStartArgs) -> Result<Option<String>> {
+        let Some(core_dump) = &args.core_dump else {
+            return Ok(None);
+        };
+        if !matches!(args.language.as_str(), "rust" | "c" | "cpp") {
+            return Err(Error::InvalidRequest(format!(
+                "coreDump is only supported for languages \"rust\", \"c\" and \"cpp\", not \"{}\"",
+                args.language
+            )));
+        }
+        if args.cargo_target.is_some() || args.test_filter.is_some() {
+            return Err(Error::InvalidRequest(
+                "coreDump cannot be combined with cargoTarget or testFilter: pass the \
+                 binary that crashed as program"
+                    .to_string(),
+            ));
+        }
+        let extension = std::path::Path::new(&args.program)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("");
+        if matches!(extension, "rs" | "c" | "cpp" | "cc" | "cxx" | "c++") {
+            return Err(Error::InvalidRequest(format!(
+                "with coreDump, program must be the binary that crashed, not a source file: {}",
+                args.program
+            )));
+        }
+        if !args.skip_validation {
+            for (name, path) in [("program", &args.program), ("coreDump", core_dump)] {
+                if !std::path::Path::new(path).is_file() {
+                    return Err(Error::InvalidRequest(format!(
+                        "{} not found: {}",
+                        name, path
+                    )));
                 }
-            }),
-            json!({
-                "name": "debugger_disconnect",
-                "title": "Disconnect Session",
-                "description": "Terminates a debugging session and cleans up all associated resources. The debugged program will be stopped if still running.\n\nWORKFLOW:\n1. Call this when debugging is complete\n2. Session and all breakpoints are removed\n3. Debugged program is terminated gracefully\n\nTIMING: Returns in 50-200ms (includes cleanup time)\n\nIMPORTANT: Always disconnect when finished to free resources. The session cannot be resumed after disconnection.\n\nRETURNS: {\"status\": \"disconnected\"}\n\nTIP: If the program is still running, it will be terminated. If you want to let the program finish naturally, you can skip calling this tool, but resources will not be cleaned up immediately.\n\nSEE ALSO: debugger://workflows (complete debugging workflows showing disconnect)",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "sessionId": {
-                            "type": "string",
-                            "description": "Session ID from debugger_start"
-                        }
-                    },
-                    "required": ["sessionId"]
+            }
+        }
+        Ok(Some(core_dump.clone()))
+    }
+
+    /// Path mapper for debugger_start's pathMappings
+    fn path_mapper(args: &DebuggerStartArgs) -> Result<PathMapper> {
+        if let Some(mapping) = args
+            .path_mappings
+            .iter()
+            .find(|m| m.local.is_empty() || m.remote.is_empty())
+        {
+            return Err(Error::InvalidRequest(format!(
+                "pathMappings entries need both 'local' and 'remote' (got {:?})",
+                mapping
+            )));
+        }
+        Ok(PathMapper::new(
+            args.path_mappings.clone(),
+            args.path_mappings_case_sensitive,
+        ))
+    }
+
+    /// Canonical path of a source file named by the client
+    ///
+    /// Relative paths are resolved against `base` (a session's working
+    /// directory) when given. Canonical paths keep symlinked and real paths
+    /// of one file from becoming two breakpoint sources.
+    fn resolve_source_path(source_path: &str, base: Option<&str>) -> Result<String> {
+        let path = match base {
+            Some(base) if std::path::Path::new(source_path).is_relative() => {
+                std::path::Path::new(base).join(source_path)
+            }
+            _ => std::path::PathBuf::from(source_path),
+        };
+        let path = path.to_str().ok_or_else(|| {
+            Error::Internal("Non-UTF8 source path (invalid encoding)".to_string())
+        })?;
+        let validated_source = security::validate_source_path(path, None)?;
+        Ok(validated_source
+            .to_str()
+            .ok_or_else(|| Error::Internal("Non-UTF8 source path (invalid encoding)".to_string()))?
+            .to_string())
+    }
+
+    /// Working directory of a session
+    async fn session_cwd(&self, session_id: &str) -> Result<Option<String>> {
+        let session = self.session(session_id).await?;
+        Ok(session.cwd().map(str::to_string))
+    }
+
+    /// [`Self::resolve_source_path`] against the working directory of a
+    /// session, checked against the allowed paths
+    async fn session_source_path(&self, session_id: &str, source_path: &str) -> Result<String> {
+        let base = if std::path::Path::new(source_path).is_relative() {
+            self.session_cwd(session_id).await?
+        } else {
+            None
+        };
+        let source_path = Self::resolve_source_path(source_path, base.as_deref())?;
+        self.session_manager.allowed_paths().check(&source_path)?;
+        Ok(source_path)
+    }
+
+    /// Validate breakpoints passed when starting or attaching a session;
+    /// relative source paths are resolved against `base`
+    async fn initial_breakpoints(
+        args: &[InitialBreakpointArgs],
+        base: Option<&str>,
+    ) -> Result<Vec<InitialBreakpoint>> {
+        let mut breakpoints = Vec::with_capacity(args.len());
+        for bp in args {
+            if bp.line < 1 {
+                return Err(Error::InvalidRequest(format!(
+                    "Breakpoint line for {} must be 1 or greater (lines are 1-indexed)",
+                    bp.source_path
+                )));
+            }
+            let source_path = Self::resolve_source_path(&bp.source_path, base)?;
+            check_breakpoint_line(&source_path, bp.line).await?;
+            breakpoints.push(InitialBreakpoint {
+                source_path,
+                line: bp.line,
+                condition: bp.condition.clone(),
+                log_message: bp.log_message.clone(),
+            });
+        }
+        Ok(breakpoints)
+    }
+
+    async fn debugger_attach(&self, arguments: Value) -> Result<Value> {
+        let args: DebuggerAttachArgs = serde_json::from_value(arguments)?;
+
+        if args.port == 0 {
+            return Err(Error::InvalidRequest(
+                "port must be between 1 and 65535".to_string(),
+            ));
+        }
+        if args.request_timeout_ms == Some(0) {
+            return Err(Error::InvalidRequest(
+                "requestTimeoutMs must be greater than 0".to_string(),
+            ));
+        }
+
+        let breakpoints = Self::initial_breakpoints(&args.breakpoints, None).await?;
+        let initial_breakpoints = breakpoints.len();
+        let options = SessionOptions {
+            request_timeout: args
+                .request_timeout_ms
+                .map(std::time::Duration::from_millis),
+            breakpoints,
+            ..SessionOptions::default()
+        };
+
+        let manager = &self.session_manager;
+        let session_id = manager
+            .attach_session(
+                &args.language,
+                &args.host,
+                args.port,
+                args.path_mappings,
+                options,
+            )
+            .await?;
+
+        Ok(json!({
+            "sessionId": session_id,
+            "status": "started",
+            "initialBreakpoints": initial_breakpoints
+        }))
+    }
+
+    /// Validate `cargoTarget` and `testFilter`; a filter alone selects the test target
+    fn cargo_target_options(
+        args: &DebuggerStartArgs,
+    ) -> Result<(Option<CargoTargetType>, Option<String>)> {
+        if args.cargo_target.is_none() && args.test_filter.is_none() {
+            return Ok((None, None));
+        }
+        if args.language != "rust" {
+            return Err(Error::InvalidRequest(format!(
+                "cargoTarget and testFilter are only supported for language \"rust\", not \"{}\"",
+                args.language
+            )));
+        }
+
+        let target = match &args.cargo_target {
+            Some(value) => CargoTargetType::parse(value)?,
+            None => CargoTargetType::Test,
+        };
+        if let Some(filter) = &args.test_filter {
+            if filter.trim().is_empty() {
+                return Err(Error::InvalidRequest(
+                    "testFilter must not be empty".to_string(),
+                ));
+            }
+            if target != CargoTargetType::Test {
+                return Err(Error::InvalidRequest(
+                    "testFilter requires cargoTarget \"test\"".to_string(),
+                ));
+            }
+        }
+
+        Ok((Some(target), args.test_filter.clone()))
+    }
+
+    /// Source map and runtime options, which only the Node.js adapter understands
+    fn node_launch_options(args: &DebuggerStartArgs) -> Result<NodeLaunchOptions> {
+        let options = NodeLaunchOptions {
+            source_maps: args.source_maps,
+            out_files: args.out_files.clone(),
+            resolve_source_map_locations: args.resolve_source_map_locations.clone(),
+            runtime_args: args.runtime_args.clone(),
+            runtime_executable: args.runtime_executable.clone(),
+            npm_script: args.npm_script.clone(),
+        };
+        if options != NodeLaunchOptions::default()
+            && !matches!(args.language.as_str(), "nodejs" | "javascript")
+        {
+            return Err(Error::InvalidRequest(format!(
+                "sourceMaps, outFiles, resolveSourceMapLocations, runtimeArgs, \
+                 runtimeExecutable and npmScript are only supported for language \"nodejs\", \
+                 not \"{}\"",
+                args.language
+            )));
+        }
+        if let Some(script) = &options.npm_script {
+            if script.trim().is_empty() {
+                return Err(Error::InvalidRequest(
+                    "npmScript must not be empty".to_string(),
+                ));
+            }
+            if options.runtime_executable.is_some() {
+                return Err(Error::InvalidRequest(
+                    "npmScript and runtimeExecutable cannot be combined (npmScript runs npm)"
+                        .to_string(),
+                ));
+            }
+        }
+        Ok(options)
+    }
+
+    async fn debugger_session_state(&self, arguments: Value) -> Result<Value> {
+        let args: SessionStateArgs = serde_json::from_value(arguments)?;
+
+        let manager = &self.session_manager;
+        let session = match manager.get_session(&args.session_id).await {
+            Ok(session) => session,
+            Err(e) => {
+                // A session of the previous server (--resume) that was lost
+                let unrecoverable = manager.unrecoverable_sessions().await;
+                return match unrecoverable.iter().find(|s| s.id == args.session_id) {
+                    Some(lost) => Ok(json!({
+                        "sessionId": lost.id,
+                        "state": "Unrecoverable",
+                        "details": {"reason": lost.reason},
+                        "language": lost.language,
+                        "program": lost.program,
+                    })),
+                    None => Err(e),
+                };
+            }
+        };
+        let full_state = session.get_full_state().await;
+
+        let mut breakpoints = Vec::new();
+        for (source_path, bps) in full_state.breakpoints.iter() {
+            for bp in bps {
+                breakpoints.push(json!({
+                    "id": bp.id,
+                    "verified": bp.verified,
+                    "line": bp.line,
+                    "sourcePath": source_path
+                }));
+            }
+        }
+
+        let mut details = full_state.state.details();
+        if full_state.state == crate::debug::state::DebugState::Terminated {
+            details = full_state.exit_details();
+        }
+        let mut response = json!({
+            "sessionId": args.session_id,
+            "state": full_state.state.name(),
+            "details": details,
+            "breakpoints": breakpoints,
+            "threads": full_state.threads,
+            "process": full_state.process,
+            "lastStop": stop_context_to_json(&full_state),
+            "diagnostics": full_state.diagnostics(),
+            "healthy": !session.is_unhealthy(),
+            "consecutiveTimeouts": session.consecutive_timeouts()
+        });
+        // Post-mortem sessions cannot be resumed
+        if let Some(core_dump) = session.core_dump() {
+            response["coreDump"] = json!(core_dump);
+        }
+        Ok(response)
+    }
+
+    async fn debugger_set_breakpoint(&self, arguments: Value) -> Result<Value> {
+        let args: SetBreakpointArgs = serde_json::from_value(arguments)?;
+
+        if args.line < 1 {
+            return Err(Error::InvalidRequest(format!(
+                "line must be 1 or greater (lines are 1-indexed), got {}",
+                args.line
+            )));
+        }
+
+        // Validate source path to prevent path traversal
+        // Note: We validate without extension requirement since breakpoints
+        // can be set in any source file regardless of language
+        let source_path = self
+            .session_source_path(&args.session_id, &args.source_path)
+            .await?;
+
+        let session = self.session(&args.session_id).await?;
+
+        if args.column.is_some_and(|column| column < 1) {
+            return Err(Error::InvalidRequest(
+                "column must be 1 or greater (columns are 1-indexed)".to_string(),
+            ));
+        }
+
+        let breakpoint = SourceBreakpoint {
+            line: args.line,
+            column: args.column,
+            condition: None,
+            hit_condition: None,
+            log_message: args.log_message.clone(),
+        };
+        let verified = session
+            .set_source_breakpoint(source_path.clone(), breakpoint)
+            .await?;
+
+        let mut response = json!({
+            "verified": verified,
+            "sourcePath": source_path,
+            "line": args.line
+        });
+        // The adapter bound it elsewhere, e.g. to the next statement
+        let adjusted_line =
+            session
+                .state
+                .read()
+                .await
+                .adjusted_line(&source_path, args.line, args.column);
+        if let Some(adjusted_line) = adjusted_line {
+            response["adjustedLine"] = json!(adjusted_line);
+        }
+        if let Some(column) = args.column {
+            response["column"] = json!(column);
+        }
+        if let Some(log_message) = args.log_message {
+            response["logMessage"] = json!(log_message);
+        }
+        if !verified {
+            let diagnoses = session
+                .diagnose_unverified_breakpoints(&[(source_path, args.line)])
+                .await;
+            if let Some(diagnosis) = diagnoses.into_iter().next() {
+                add_diagnosis(&mut response, diagnosis);
+            }
+        }
+        add_source_changes(&mut response, session.changed_sources().await);
+        Ok(response)
+    }
+
+    async fn debugger_breakpoint_locations(&self, arguments: Value) -> Result<Value> {
+        let args: BreakpointLocationsArgs = serde_json::from_value(arguments)?;
+
+        if args.line < 1 {
+            return Err(Error::InvalidRequest(
+                "line must be 1 or greater (lines are 1-indexed)".to_string(),
+            ));
+        }
+        if args.end_line.is_some_and(|end_line| end_line < args.line) {
+            return Err(Error::InvalidRequest(format!(
+                "endLine must not be before line {}",
+                args.line
+            )));
+        }
+
+        let source_path = self
+            .session_source_path(&args.session_id, &args.source_path)
+            .await?;
+
+        let session = self.session(&args.session_id).await?;
+
+        let locations = session
+            .breakpoint_locations(source_path.clone(), args.line, args.end_line)
+            .await?;
+
+        Ok(json!({
+            "sourcePath": source_path,
+            "locations": locations
+        }))
+    }
+
+    async fn debugger_set_breakpoints(&self, arguments: Value) -> Result<Value> {
+        let args: SetBreakpointsArgs = serde_json::from_value(arguments)?;
+
+        if args.breakpoints.is_empty() {
+            return Err(Error::InvalidRequest(
+                "breakpoints must contain at least one entry".to_string(),
+            ));
+        }
+
+        let base = if args
+            .breakpoints
+            .iter()
+            .any(|bp| std::path::Path::new(&bp.source_path).is_relative())
+        {
+            self.session_cwd(&args.session_id).await?
+        } else {
+            None
+        };
+        let allowed = self.session_manager.allowed_paths().clone();
+        let mut requested = Vec::with_capacity(args.breakpoints.len());
+        for bp in args.breakpoints {
+            if bp.line < 1 {
+                return Err(Error::InvalidRequest(format!(
+                    "Breakpoint line for {} must be 1 or greater (lines are 1-indexed)",
+                    bp.source_path
+                )));
+            }
+            let source_path = Self::resolve_source_path(&bp.source_path, base.as_deref())?;
+            allowed.check(&source_path)?;
+            requested.push((
+                source_path,
+                SourceBreakpoint {
+                    line: bp.line,
+                    column: bp.column,
+                    condition: bp.condition,
+                    hit_condition: bp.hit_condition,
+                    log_message: bp.log_message,
                 },
-                "annotations": {
-                    "async": false,
-                    "returnsTiming": "50-200ms",
-                    "workflow": "cleanup",
-                    "category": "session-management",
-                    "destructive": true,
-                    "priority": 0.4
+            ));
+        }
+
+        let session = self.session(&args.session_id).await?;
+
+        let pending = matches!(
+            session.get_state().await,
+            crate::debug::state::DebugState::NotStarted
+                | crate::debug::state::DebugState::Initializing
+        );
+        let results = session.set_breakpoints_bulk(requested).await?;
+
+        let unverified: Vec<(String, i32)> = results
+            .iter()
+            .filter(|bp| !bp.verified)
+            .map(|bp| (bp.source_path.clone(), bp.line))
+            .collect();
+        let mut diagnoses = session
+            .diagnose_unverified_breakpoints(&unverified)
+            .await
+            .into_iter();
+
+        let breakpoints: Vec<Value> = results
+            .iter()
+            .map(|bp| {
+                let mut entry = json!({
+                    "sourcePath": bp.source_path,
+                    "line": bp.requested_line.unwrap_or(bp.line),
+                    "id": bp.id,
+                    "verified": bp.verified
+                });
+                if bp.requested_line.is_some() {
+                    entry["adjustedLine"] = json!(bp.line);
                 }
-            }),
-            json!({
-                "name": "debugger_wait_for_stop",
-                "title": "Wait For Program To Stop",
-                "description": "Blocks until the debugger stops (at breakpoint, step, or entry point), or times out. More efficient than polling debugger_session_state.\n\n⭐ EFFICIENT ALTERNATIVE TO POLLING\n==================================\nReplaces old pattern of repeated sleep + state check with single blocking call:\n\n❌ OLD PATTERN (slow, inefficient):\n  debugger_continue()\n  sleep(200ms)  // Arbitrary delay\n  state = debugger_session_state()\n  if state != \"Stopped\":\n    sleep(500ms)  // More waiting\n    state = debugger_session_state()  // Still might be Running\n  // Takes 500-3000ms with multiple polls\n\n✅ NEW PATTERN (fast, efficient):\n  debugger_continue()\n  debugger_wait_for_stop({timeoutMs: 5000})\n  // Returns immediately when stopped (typically <100ms)\n  // No wasted polling cycles!\n\n⭐ TIMING BEHAVIOR\n=================\n- If ALREADY stopped: Returns immediately (<10ms)\n- If running: Blocks until stop event or timeout\n- If program terminated: Returns with state \"Terminated\"\n- If timeout expires: Returns error\n\nTypical return times:\n- Entry point (stopOnEntry): <100ms\n- Breakpoint hit: <100ms  \n- Step completion: <50ms\n\nCOMMON PATTERNS:\n\n1. Wait for entry after start:\n   debugger_start({stopOnEntry: true})\n   debugger_wait_for_stop()  // Immediate return when at entry\n\n2. Wait for breakpoint:\n   debugger_continue()\n   debugger_wait_for_stop()  // Blocks until breakpoint hit\n\n3. Wait for step completion:\n   debugger_step_over()\n   debugger_wait_for_stop()  // Blocks until step completes\n\n4. Loop through multiple stops:\n   for (i = 0; i < 5; i++):\n     debugger_continue()\n     result = debugger_wait_for_stop()\n     // Process each stop...\n\nWORKFLOW:\n1. Call debugger_continue(), debugger_step_*, or debugger_start()\n2. Call this tool to wait for the next stop event\n3. Returns immediately when program stops\n4. Check result.reason to understand why it stopped\n\nRETURNS:\n{\n  \"state\": \"Stopped\",\n  \"threadId\": 1,\n  \"reason\": \"breakpoint\"  // or \"entry\", \"step\", \"pause\", etc.\n}\n\nPERFORMANCE:\n~5x faster than polling approach\nNo wasted CPU cycles\nImmediate notification of state changes\n\nSEE ALSO: debugger_session_state (check current state), debugger_continue (resume execution)",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "sessionId": {
-                            "type": "string",
-                            "description": "Session ID from debugger_start"
-                        },
-                        "timeoutMs": {
-                            "type": "integer",
-                            "default": 5000,
-                            "description": "Maximum time to wait in milliseconds (default: 5000)"
-                        }
-                    },
-                    "required": ["sessionId"]
+                if !bp.verified {
+                    if let Some(diagnosis) = diagnoses.next() {
+                        add_diagnosis(&mut entry, diagnosis);
+                    }
                 }
-            }),
-            json!({
-                "name": "debugger_list_breakpoints",
-                "title": "List All Breakpoints",
-                "description": "Lists all breakpoints currently set across all source files.\n\nUSEFUL FOR:\n- Verifying which breakpoints are active\n- Checking breakpoint verification status\n- Debugging why a breakpoint might not be hit\n\nTIMING: Returns immediately (<10ms)\n\nRETURNS: Array of breakpoints with id, verified status, line, and sourcePath",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "sessionId": {
-                            "type": "string",
-                            "description": "Session ID from debugger_start"
-                        }
-                    },
-                    "required": ["sessionId"]
+                if let Some(column) = bp.column {
+                    entry["column"] = json!(column);
                 }
-            }),
-            json!({
-                "name": "debugger_step_over",
-                "title": "Step Over (Next Line)",
-                "description": "Executes the current line and stops at the next line. Does NOT step into function calls.\n\nREQUIRES: Program must be stopped (at breakpoint, entry, or previous step)\n\nWORKFLOW:\n1. Ensure program is stopped\n2. Call this tool to execute one line\n3. Use debugger_wait_for_stop to wait for the step to complete\n4. Inspect state with debugger_stack_trace and debugger_evaluate\n\nTIMING: Returns quickly; use debugger_wait_for_stop to detect completion\n\nSEE ALSO: debugger_step_into (to step into functions), debugger_step_out (to step out)",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "sessionId": {
-                            "type": "string",
-                            "description": "Session ID from debugger_start"
-                        },
-                        "threadId": {
-                            "type": "integer",
-                            "description": "Thread ID (optional, uses stopped thread if not specified)"
-                        }
-                    },
-                    "required": ["sessionId"]
+                if let Some(condition) = &bp.condition {
+                    entry["condition"] = json!(condition);
                 }
-            }),
-            json!({
-                "name": "debugger_step_into",
-                "title": "Step Into (Enter Function)",
-                "description": "Steps into function calls on the current line. If no function call, behaves like step_over.\n\nREQUIRES: Program must be stopped\n\nUSEFUL FOR: Debugging function implementations line by line\n\nWORKFLOW: Same as debugger_step_over\n\nSEE ALSO: debugger_step_over (to skip functions), debugger_step_out (to exit function)",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "sessionId": {
-                            "type": "string",
-                            "description": "Session ID from debugger_start"
-                        },
-                        "threadId": {
-                            "type": "integer",
-                            "description": "Thread ID (optional)"
-                        }
-                    },
-                    "required": ["sessionId"]
+                if let Some(hit_condition) = &bp.hit_condition {
+                    entry["hitCondition"] = json!(hit_condition);
                 }
-            }),
-            json!({
-                "name": "debugger_step_out",
-                "title": "Step Out (Exit Function)",
-                "description": "Continues execution until the current function returns, then stops at the caller.\n\nREQUIRES: Program must be stopped inside a function\n\nUSEFUL FOR: Quickly exiting from deep call stacks\n\nWORKFLOW: Same as debugger_step_over\n\nSEE ALSO: debugger_step_into (to enter function), debugger_step_over (to skip line)",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "sessionId": {
-                            "type": "string",
-                            "description": "Session ID from debugger_start"
-                        },
-                        "threadId": {
-                            "type": "integer",
-                            "description": "Thread ID (optional)"
-                        }
-                    },
-                    "required": ["sessionId"]
+                if let Some(log_message) = &bp.log_message {
+                    entry["logMessage"] = json!(log_message);
                 }
-            }),
-        ]
+                entry
+            })
+            .collect();
+
+        let mut response = json!({
+            "breakpoints": breakpoints,
+            "pending": pending
+        });
+        add_source_changes(&mut response, session.changed_sources().await);
+        Ok(response)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::debug::SessionManager;
+    async fn debugger_set_function_breakpoints(&self, arguments: Value) -> Result<Value> {
+        let args: SetFunctionBreakpointsArgs = serde_json::from_value(arguments)?;
+
+        if let Some(bp) = args.breakpoints.iter().find(|bp| bp.name.trim().is_empty()) {
+            return Err(Error::InvalidRequest(format!(
+                "Function breakpoint name must not be empty (got {:?})",
+                bp.name
+            )));
+        }
+
+        let session = self.session(&args.session_id).await?;
+
+        let breakpoints = args
+            .breakpoints
+            .into_iter()
+            .map(|bp| FunctionBreakpoint {
+                name: bp.name,
+                condition: bp.condition,
+                hit_condition: None,
+            })
+            .collect();
+        let tracked = session.set_function_breakpoints(breakpoints).await?;
+
+        let breakpoints: Vec<Value> = tracked
+            .iter()
+            .map(|bp| {
+                json!({
+                    "type": "function",
+                    "name": bp.name,
+                    "condition": bp.condition,
+                    "id": bp.id,
+                    "verified": bp.verified
+                })
+            })
+            .collect();
+
+        Ok(json!({
+            "breakpoints": breakpoints
+        }))
+    }
+
+    async fn debugger_set_data_breakpoint(&self, arguments: Value) -> Result<Value> {
+        let args: SetDataBreakpointArgs = serde_json::from_value(arguments)?;
+
+        let session = self.session(&args.session_id).await?;
+
+        if args.clear {
+            let cleared = session.clear_data_breakpoints().await?;
+            return Ok(json!({ "cleared": cleared }));
+        }
+
+        if let Some(access_type) = &args.access_type {
+            if !DATA_BREAKPOINT_ACCESS_TYPES.contains(&access_type.as_str()) {
+                return Err(Error::InvalidRequest(format!(
+                    "accessType must be one of {} (got \"{}\")",
+                    DATA_BREAKPOINT_ACCESS_TYPES.join(", "),
+                    access_type
+                )));
+            }
+        }
+
+        let (data_id, description) = match (
+            args.variables_reference,
+            &args.name,
+            &args.expression,
+            &args.data_id,
+        ) {
+            (Some(reference), Some(name), None, None) => {
+                Self::data_breakpoint_target(&session, Some(reference), name, None, &args).await?
+            }
+            (None, None, Some(expression), None) => {
+                Self::data_breakpoint_target(&session, None, expression, args.frame_id, &args)
+                    .await?
+            }
+            (None, None, None, Some(data_id)) => (data_id.clone(), data_id.clone()),
+            _ => {
+                return Err(Error::InvalidRequest(
+                    "Pass exactly one target: variablesReference and name, expression, or dataId"
+                        .to_string(),
+                ))
+            }
+        };
+
+        let bp = session
+            .add_data_breakpoint(
+                DataBreakpoint {
+                    data_id,
+                    access_type: args.access_type,
+                    condition: args.condition,
+                    hit_condition: None,
+                },
+                description,
+            )
+            .await?;
+        Ok(data_breakpoint_to_json(&bp))
+    }
+
+    /// dataId and description of a variable or expression, checking that the
+    /// adapter can watch it with the requested access type
+    async fn data_breakpoint_target(
+        session: &crate::debug::DebugSession,
+        variables_reference: Option<i32>,
+        name: &str,
+        frame_id: Option<i32>,
+        args: &SetDataBreakpointArgs,
+    ) -> Result<(String, String)> {
+        let info = session
+            .data_breakpoint_info(variables_reference, name, frame_id)
+            .await?;
+        let Some(data_id) = info.data_id else {
+            return Err(Error::InvalidRequest(format!(
+                "Cannot set a data breakpoint on {}: {}",
+                name, info.description
+            )));
+        };
+        if let (Some(access_type), Some(supported)) = (&args.access_type, &info.access_types) {
+            if !supported.contains(access_type) {
+                return Err(Error::InvalidRequest(format!(
+                    "{} cannot be watched for accessType \"{}\" (supported: {})",
+                    name,
+                    access_type,
+                    supported.join(", ")
+                )));
+            }
+        }
+        Ok((data_id, info.description))
+    }
+
+    async fn debugger_add_watch(&self, arguments: Value) -> Result<Value> {
+        let args: WatchArgs = serde_json::from_value(arguments)?;
+
+        if args.expression.trim().is_empty() {
+            return Err(Error::InvalidRequest(
+                "Watch expression must not be empty".to_string(),
+            ));
+        }
+
+        let session = self.session(&args.session_id).await?;
+
+        let added = session.add_watch(args.expression.clone()).await;
+        // Evaluate straight away if the program is already paused
+        let watches = session.refresh_watches().await;
+
+        Ok(json!({
+            "added": added,
+            "expression": args.expression,
+            "watches": watches_to_json(&watches)
+        }))
+    }
+
+    async fn debugger_remove_watch(&self, arguments: Value) -> Result<Value> {
+        let args: WatchArgs = serde_json::from_value(arguments)?;
+
+        let session = self.session(&args.session_id).await?;
+
+        let removed = session.remove_watch(&args.expression).await;
+
+        Ok(json!({
+            "removed": removed,
+            "expression": args.expression
+        }))
+    }
+
+    async fn debugger_list_watches(&self, arguments: Value) -> Result<Value> {
+        let args: ListWatchesArgs = serde_json::from_value(arguments)?;
+
+        let session = self.session(&args.session_id).await?;
+
+        let watches = session.get_full_state().await.watches;
+
+        Ok(json!({
+            "watches": watches_to_json(&watches)
+        }))
+    }
+
+    async fn debugger_save_config(&self, arguments: Value) -> Result<Value> {
+        let args: SaveConfigArgs = serde_json::from_value(arguments)?;
+        config_store::validate_name(&args.name)?;
+        Self::validate_start_config(&args.config)?;
+
+        let manager = &self.session_manager;
+        let replaced = manager
+            .save_config(&args.name, args.config, ConfigSource::Saved)
+            .await;
+
+        Ok(json!({
+            "name": args.name,
+            "saved": true,
+            "replaced": replaced.is_some()
+        }))
+    }
+
+    async fn debugger_list_configs(&self, _arguments: Value) -> Result<Value> {
+        let manager = &self.session_manager;
+        let configs = manager.list_configs().await;
+
+        Ok(json!({
+            "count": configs.len(),
+            "configs": configs
+        }))
+    }
+
+    async fn debugger_saved_breakpoints(&self, arguments: Value) -> Result<Value> {
+        let args: SavedBreakpointsArgs = serde_json::from_value(arguments)?;
+
+        let manager = &self.session_manager;
+        match args.action.as_str() {
+            "list" => {
+                let programs = match &args.program {
+                    Some(program) => {
+                        let saved = manager.saved_breakpoints(program).await;
+                        let mut programs = std::collections::BTreeMap::new();
+                        if !saved.is_empty() {
+                            programs.insert(BreakpointStore::program_key(program), saved);
+                        }
+                        programs
+                    }
+                    None => manager.all_saved_breakpoints().await,
+                };
+                Ok(json!({ "programs": programs }))
+            }
+            "clear" => {
+                let cleared = manager
+                    .clear_saved_breakpoints(args.program.as_deref())
+                    .await?;
+                Ok(json!({ "cleared": cleared }))
+            }
+            other => Err(Error::InvalidRequest(format!(
+                "Unknown action '{}': expected 'list' or 'clear'",
+                other
+            ))),
+        }
+    }
+
+    async fn debugger_doctor(&self, arguments: Value) -> Result<Value> {
+        let args: DoctorArgs = serde_json::from_value(arguments)?;
+        // Accept the same aliases as debugger_start
+        let language = args.language.map(|language| match language.as_str() {
+            "golang" => "go".to_string(),
+            "javascript" => "nodejs".to_string(),
+            "sh" => "bash".to_string(),
+            _ => language,
+        });
+
+        // The checks run external commands
+        let mut health = tokio::task::spawn_blocking(health::check_all)
+            .await
+            .map_err(|e| Error::Internal(format!("Health check failed: {}", e)))?;
+        if let Some(language) = &language {
+            health.retain(|h| &h.language == language);
+            if health.is_empty() {
+                return Err(Error::AdapterNotFound(language.clone()));
+            }
+        }
+
+        Ok(health::report(&health))
+    }
+
+    async fn debugger_dap_log(&self, arguments: Value) -> Result<Value> {
+        let args: DapLogArgs = serde_json::from_value(arguments)?;
+        if args.tail == Some(0) {
+            return Err(Error::InvalidRequest(
+                "tail must be greater than 0".to_string(),
+            ));
+        }
+
+        let session = self.session(&args.session_id).await?;
+        session.dap_log_json(Some(args.tail.unwrap_or(DEFAULT_DAP_LOG_TAIL)))
+    }
+
+    async fn debugger_session_metrics(&self, arguments: Value) -> Result<Value> {
+        let args: SessionMetricsArgs = serde_json::from_value(arguments)?;
+
+        let session = self.session(&args.session_id).await?;
+        let mut metrics = serde_json::to_value(session.metrics().await)?;
+        metrics["sessionId"] = json!(args.session_id);
+        Ok(metrics)
+    }
+
+    async fn debugger_server_log_tail(&self, arguments: Value) -> Result<Value> {
+        let args: ServerLogTailArgs = serde_json::from_value(arguments)?;
+        if args.lines == Some(0) {
+            return Err(Error::InvalidRequest(
+                "lines must be greater than 0".to_string(),
+            ));
+        }
+
+        let log = crate::logging::server_log();
+        let lines = log.tail(args.lines.unwrap_or(DEFAULT_SERVER_LOG_LINES));
+        Ok(json!({
+            "count": lines.len(),
+            "buffered": log.len(),
+            "capacity": log.capacity(),
+            "lines": lines
+        }))
+    }
+
+    async fn debugger_recording_path(&self, arguments: Value) -> Result<Value> {
+        let args: RecordingPathArgs = serde_json::from_value(arguments)?;
+
+        let session = self.session(&args.session_id).await?;
+        let path = session
+            .recorder()
+            .await
+            .map(|recorder| recorder.path().to_string_lossy().to_string());
+        Ok(json!({
+            "sessionId": args.session_id,
+            "recording": path.is_some(),
+            "path": path,
+        }))
+    }
+
+    async fn debugger_get_output(&self, arguments: Value) -> Result<Value> {
+        let args: GetOutputArgs = serde_json::from_value(arguments)?;
+        let max_length = truncate::resolve_max_length(args.max_length)?;
+
+        let session = self.session(&args.session_id).await?;
+
+        let full_state = session.get_full_state().await;
+        let entries: Vec<&OutputEntry> = full_state
+            .output
+            .iter()
+            .filter(|entry| {
+                args.category
+                    .as_ref()
+                    .is_none_or(|category| &entry.category == category)
+            })
+            .collect();
+
+        let texts: Vec<&str> = entries.iter().map(|entry| entry.output.as_str()).collect();
+        let kept = truncate::newest_within(&texts, max_length);
+        let omitted = entries.len() - kept.len();
+        let output: Vec<Value> = entries[omitted..]
+            .iter()
+            .zip(&kept)
+            .map(|(entry, output)| {
+                json!({
+                    "category": entry.category,
+                    "output": output
+                })
+            })
+            .collect();
+
+        let mut result = json!({
+            "count": output.len(),
+            "output": output
+        });
+        let original_length: usize = texts.iter().map(|text| text.len()).sum();
+        if kept.iter().map(|text| text.len()).sum::<usize>() < original_length {
+            result["truncated"] = json!(true);
+            result["originalLength"] = json!(original_length);
+            result["omittedEntries"] = json!(omitted);
+        }
+        Ok(result)
+    }
+
+    async fn debugger_continue(&self, arguments: Value) -> Result<Value> {
+        let args: ContinueArgs = serde_json::from_value(arguments)?;
+
+        let session = self.session(&args.session_id).await?;
+
+        // Subscribed before resuming, so a stop reported right away is not missed
+        let changes = if args.wait_for_stop {
+            Some(self.session_manager.subscribe_state_changes())
+        } else {
+            None
+        };
+        session.continue_execution().await?;
+
+        if let Some(changes) = changes {
+            return Self::await_stop(&args.session_id, &session, changes, args.timeout_ms).await;
+        }
+        Ok(json!({
+            "status": "continued"
+        }))
+    }
+
+    async fn debugger_stack_trace(&self, arguments: Value) -> Result<Value> {
+        let args: StackTraceArgs = serde_json::from_value(arguments)?;
+
+        if args.start_frame < 0 {
+            return Err(Error::InvalidRequest(format!(
+                "startFrame must not be negative (got {})",
+                args.start_frame
+            )));
+        }
+        let levels = args.levels.unwrap_or(DEFAULT_STACK_LEVELS);
+        if levels < 1 {
+            return Err(Error::InvalidRequest(format!(
+                "levels must be at least 1 (got {})",
+                levels
+            )));
+        }
+
+        let session = self.session(&args.session_id).await?;
+
+        // Validate we're in a stopped state
+        let state = session.get_state().await;
+        if !matches!(state, crate::debug::state::DebugState::Stopped { .. }) {
+            return Err(Error::InvalidState(
+                "Cannot get stack trace while program is running. The program must be stopped at a breakpoint, entry point, or step. Use debugger_wait_for_stop() to wait for the program to stop.".to_string()
+            ));
+        }
+
+        let page = session
+            .stack_trace_page(args.thread_id, Some(args.start_frame), Some(levels))
+            .await?;
+
+        // Paging and frameIndex count every frame; only the response is filtered
+        let mut response = stack_page_to_json(&page, args.start_frame, levels);
+        if args.skip_subtle_frames {
+            let frames: Vec<&StackFrame> = page
+                .stack_frames
+                .iter()
+                .filter(|frame| !frame.is_subtle())
+                .collect();
+            response["hiddenFrames"] = json!(page.stack_frames.len() - frames.len());
+            response["stackFrames"] = json!(frames);
+        }
+        add_source_changes(&mut response, session.changed_sources().await);
+        Ok(response)
+    }
+
+    async fn debugger_evaluate(&self, arguments: Value) -> Result<Value> {
+        let args: EvaluateArgs = serde_json::from_value(arguments)?;
+
+        let context = args.resolve_context()?;
+        let max_length = truncate::resolve_max_length(args.max_length)?;
+
+        let session = self.session(&args.session_id).await?;
+
+        // Validate we're in a stopped state
+        let state = session.get_state().await;
+        if !matches!(state, crate::debug::state::DebugState::Stopped { .. }) {
+            return Err(Error::InvalidState(
+                "Cannot evaluate expressions while program is running. The program must be stopped at a breakpoint, entry point, or step. Use debugger_wait_for_stop() to wait for the program to stop.".to_string()
+            ));
+        }
+
+        let frame_id = match (args.frame_id, args.frame_index) {
+            (None, Some(index)) => Some(session.frame_id_at(args.thread_id, index).await?),
+            (frame_id, _) => frame_id,
+        };
+        let result = session
+            .evaluate_in_thread(&args.expression, frame_id, args.thread_id, context)
+            .await?;
+
+        let mut response = json!({
+            "result": result.result,
+            "type": result.type_,
+            "variablesReference": result.variables_reference,
+            "namedVariables": result.named_variables,
+            "indexedVariables": result.indexed_variables,
+            "presentationHint": result.presentation_hint,
+            "memoryReference": result.memory_reference
+        });
+        truncate::truncate_field(&mut response, "result", max_length);
+        // A cut-off container is better browsed through the adapter's paging
+        if response.get("truncated").is_some() && result.variables_reference > 0 {
+            let children =
+                result.indexed_variables.unwrap_or(0) + result.named_variables.unwrap_or(0);
+            response["hint"] = json!(format!(
+                "The result is structured: browse its {} children page by page with debugger_get_variables({{variablesReference: {}, start: 0, count: {}}})",
+                if children > 0 { children.to_string() } else { "children".to_string() },
+                result.variables_reference,
+                DEFAULT_VARIABLES_COUNT
+            ));
+        }
+        Ok(response)
+    }
+
+    async fn debugger_get_variables(&self, arguments: Value) -> Result<Value> {
+        let args: GetVariablesArgs = serde_json::from_value(arguments)?;
+
+        if args.variables_reference <= 0 {
+            return Err(Error::InvalidRequest(format!(
+                "variablesReference must be greater than 0 (got {}); scalar values have no children",
+                args.variables_reference
+            )));
+        }
+        let count = args.count.unwrap_or(DEFAULT_VARIABLES_COUNT);
+        if args.start < 0 || count < 1 {
+            return Err(Error::InvalidRequest(format!(
+                "start must not be negative and count must be at least 1 (got start {}, count {})",
+                args.start, count
+            )));
+        }
+        let max_length = truncate::resolve_max_length(args.max_length)?;
+
+        let session = self.session(&args.session_id).await?;
+
+        // References are only valid while the program stays paused
+        let state = session.get_state().await;
+        if !matches!(state, crate::debug::state::DebugState::Stopped { .. }) {
+            return Err(Error::InvalidState(
+                "Cannot expand variables while program is running. Variable references are only valid until the program resumes.".to_string()
+            ));
+        }
+
+        let variables = session
+            .variables_page(args.variables_reference, Some(args.start), Some(count))
+            .await?;
+
+        let returned = variables.len() as i32;
+        let variables: Vec<Value> = variables
+            .iter()
+            .map(|variable| {
+                let mut value = json!(variable);
+                truncate::truncate_field(&mut value, "value", max_length);
+                value
+            })
+            .collect();
+        let mut result = json!({
+            "variables": variables,
+            "start": args.start
+        });
+        if returned == count {
+            result["note"] = json!(format!(
+                "Showing up to {} children from index {}. Call debugger_get_variables with start: {} for more",
+                count,
+                args.start,
+                args.start + count
+            ));
+        }
+        Ok(result)
+    }
+
+    async fn debugger_read_memory(&self, arguments: Value) -> Result<Value> {
+        let args: ReadMemoryArgs = serde_json::from_value(arguments)?;
+
+        if args.count <= 0 || args.count > crate::debug::memory::MAX_READ_BYTES {
+            return Err(Error::InvalidRequest(format!(
+                "count must be between 1 and {} (got {})",
+                crate::debug::memory::MAX_READ_BYTES,
+                args.count
+            )));
+        }
+
+        let session = self.session(&args.session_id).await?;
+        Self::require_stopped(&session, "read memory").await?;
+
+        let result = session
+            .read_memory(&args.memory_reference, args.offset, args.count)
+            .await?;
+        crate::debug::memory::memory_view(&result)
+    }
+
+    async fn debugger_disassemble(&self, arguments: Value) -> Result<Value> {
+        let args: DisassembleArgs = serde_json::from_value(arguments)?;
+
+        if args.instruction_count <= 0 || args.instruction_count > MAX_DISASSEMBLE_INSTRUCTIONS {
+            return Err(Error::InvalidRequest(format!(
+                "instructionCount must be between 1 and {} (got {})",
+                MAX_DISASSEMBLE_INSTRUCTIONS, args.instruction_count
+            )));
+        }
+
+        let session = self.session(&args.session_id).await?;
+        Self::require_stopped(&session, "disassemble").await?;
+
+        let instructions = session
+            .disassemble(&args.memory_reference, args.instruction_count)
+            .await?;
+        let instructions: Vec<Value> = instructions
+            .into_iter()
+            .map(|i| {
+                json!({
+                    "address": i.address,
+                    "instruction": i.instruction,
+                    "bytes": i.instruction_bytes,
+                    "symbol": i.symbol,
+                    "line": i.line,
+                })
+            })
+            .collect();
+
+        Ok(json!({ "instructions": instructions }))
+    }
+
+    async fn debugger_registers(&self, arguments: Value) -> Result<Value> {
+        let args: RegistersArgs = serde_json::from_value(arguments)?;
+
+        let session = self.session(&args.session_id).await?;
+        if !matches!(
+            session.get_state().await,
+            crate::debug::state::DebugState::Stopped { .. }
+        ) {
+            return Err(Error::InvalidState(
+                "Cannot read registers while program is running. The program must be stopped first."
+                    .to_string(),
+            ));
+        }
+
+        let (frame_id, instruction_pointer) = match args.frame_id {
+            Some(frame_id) => (frame_id, None),
+            None => {
+                let page = session.stack_trace_page(None, None, Some(1)).await?;
+                let frame = page.stack_frames.into_iter().next().ok_or_else(|| {
+                    Error::InvalidState("The stopped thread has no stack frames".to_string())
+                })?;
+                (frame.id, frame.instruction_pointer_reference)
+            }
+        };
+
+        // CodeLLDB groups registers ("General Purpose Registers", ...);
+        // adapters listing them directly get a single unnamed group
+        let scope = session.registers_scope(frame_id).await?;
+        let children = session.variables(scope.variables_reference).await?;
+        let groups: Vec<&crate::dap::types::Variable> = children
+            .iter()
+            .filter(|child| child.variables_reference > 0)
+            .collect();
+        let (group, registers) = if groups.is_empty() {
+            (None, children.clone())
+        } else {
+            let selected = match &args.group {
+                Some(wanted) => groups
+                    .iter()
+                    .find(|g| g.name.to_lowercase().starts_with(&wanted.to_lowercase()))
+                    .ok_or_else(|| {
+                        let names: Vec<&str> = groups.iter().map(|g| g.name.as_str()).collect();
+                        Error::InvalidRequest(format!(
+                            "No register group matches \"{}\". Groups: {}",
+                            wanted,
+                            names.join(", ")
+                        ))
+                    })?,
+                None => &groups[0],
+            };
+            (
+                Some(selected.name.clone()),
+                session.variables(selected.variables_reference).await?,
+            )
+        };
+
+        let registers: Vec<Value> = registers
+            .iter()
+            .map(|register| json!({"name": register.name, "value": register.value}))
+            .collect();
+        Ok(json!({
+            "frameId": frame_id,
+            "instructionPointerReference": instruction_pointer,
+            "group": group,
+            "groups": groups.iter().map(|g| g.name.as_str()).collect::<Vec<_>>(),
+            "registers": registers
+        }))
+    }
+
+    async fn debugger_completions(&self, arguments: Value) -> Result<Value> {
+        let args: CompletionsArgs = serde_json::from_value(arguments)?;
+
+        // DAP columns count UTF-16 code units
+        let end = args.text.encode_utf16().count() as i32 + 1;
+        let column = args.column.unwrap_or(end);
+        if column < 1 || column > end {
+            return Err(Error::InvalidRequest(format!(
+                "column must be between 1 and {} (the end of text), got {}",
+                end, column
+            )));
+        }
+
+        let session = self.session(&args.session_id).await?;
+
+        let targets: Vec<Value> = session
+            .completions(&args.text, column, args.frame_id)
+            .await?
+            .into_iter()
+            .map(|item| {
+                json!({
+                    "text": item.text.as_deref().unwrap_or(&item.label),
+                    "label": item.label,
+                    "type": item.type_,
+                })
+            })
+            .collect();
+
+        Ok(json!({ "targets": targets }))
+    }
+
+    async fn debugger_loaded_sources(&self, arguments: Value) -> Result<Value> {
+        let args: LoadedSourcesArgs = serde_json::from_value(arguments)?;
+
+        let session = self.session(&args.session_id).await?;
+
+        let mut sources = session.loaded_sources().await?;
+        if let Some(filter) = &args.filter {
+            sources.retain(|path| path.contains(filter.as_str()));
+        }
+        Ok(json!({
+            "count": sources.len(),
+            "sources": sources,
+        }))
+    }
+
+    async fn debugger_modules(&self, arguments: Value) -> Result<Value> {
+        let args: ModulesArgs = serde_json::from_value(arguments)?;
+
+        let session = self.session(&args.session_id).await?;
+
+        let modules = session.modules().await?;
+        Ok(json!({
+            "count": modules.len(),
+            "modules": modules,
+        }))
+    }
+
+    async fn debugger_check_sources(&self, arguments: Value) -> Result<Value> {
+        let args: CheckSourcesArgs = serde_json::from_value(arguments)?;
+
+        let session = self.session(&args.session_id).await?;
+
+        let changed = session.changed_sources().await;
+        let tracked = session.state.read().await.tracked_source_count();
+        let mut response = json!({
+            "changed": !changed.is_empty(),
+            "changedSources": changed,
+            "trackedSources": tracked,
+        });
+        if !changed.is_empty() {
+            response["warning"] = json!(SOURCE_CHANGED_WARNING);
+        }
+        Ok(response)
+    }
+
+    async fn debugger_watch_status(&self, arguments: Value) -> Result<Value> {
+        let args: WatchStatusArgs = serde_json::from_value(arguments)?;
+
+        let manager = &self.session_manager;
+        if args.disable {
+            manager.unwatch_session(&args.session_id).await;
+        }
+        let status = manager.watch_status(&args.session_id).await?;
+        Ok(serde_json::to_value(status)?)
+    }
+
+    /// Memory references, like variable references, are only valid while paused
+    async fn require_stopped(session: &crate::debug::DebugSession, action: &str) -> Result<()> {
+        let state = session.get_state().await;
+        if !matches!(state, crate::debug::state::DebugState::Stopped { .. }) {
+            return Err(Error::InvalidState(format!(
+                "Cannot {} while program is running. Memory references are only valid until the program resumes.",
+                action
+            )));
+        }
+        Ok(())
+    }
+
+    async fn debugger_wait_for_stop(&self, arguments: Value) -> Result<Value> {
+        let args: WaitForStopArgs = serde_json::from_value(arguments)?;
+
+        if args.levels == Some(0) {
+            return Err(Error::InvalidRequest(
+                "levels must be at least 1".to_string(),
+            ));
+        }
+
+        let session = self.session(&args.session_id).await?;
+
+        let timeout = tokio::time::Duration::from_millis(args.timeout_ms);
+        let start = tokio::time::Instant::now();
+
+        loop {
+            let state = session.get_state().await;
+            if let Some(response) = Self::stop_response(&session, &state).await {
+                let mut response = response?;
+                if matches!(state, crate::debug::state::DebugState::Stopped { .. }) {
+                    Self::add_stop_details(&session, &args, &mut response).await;
+                }
+                return Ok(response);
+            }
+
+            // Check timeout
+            if start.elapsed() > timeout {
+                return Err(Self::stop_timeout(&session, args.timeout_ms, &state).await);
+            }
+
+            // Sleep briefly before checking again
+            tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        }
+    }
+
+    /// Start a session, continue past every stop until the program exits
+    /// (or the timeout passes), then remove the session and summarize
+    async fn debugger_run_until_exit(&self, arguments: Value) -> Result<Value> {
+        let args: RunUntilExitArgs = serde_json::from_value(arguments.clone())?;
+        if args.timeout_ms == 0 {
+            return Err(Error::InvalidRequest(
+                "timeoutMs must be greater than 0".to_string(),
+            ));
+        }
+        let Value::Object(mut start_args) = arguments else {
+            return Err(Error::InvalidRequest(
+                "Arguments must be an object".to_string(),
+            ));
+        };
+        for own in ["timeoutMs", "includeLocals", "maxStops"] {
+            start_args.remove(own);
+        }
+        for unsupported in ["reuseExisting", "watchMode"] {
+            if start_args.get(unsupported) == Some(&Value::Bool(true)) {
+                return Err(Error::InvalidRequest(format!(
+                    "{} cannot be used with debugger_run_until_exit, which removes its session once the program exits",
+                    unsupported
+                )));
+            }
+        }
+
+        let started = Instant::now();
+        let deadline =
+            tokio::time::Instant::now() + tokio::time::Duration::from_millis(args.timeout_ms);
+        let start = self.debugger_start(Value::Object(start_args)).await?;
+        let session_id = start["sessionId"].as_str().unwrap_or_default().to_string();
+        let session = self.session_manager.get_session(&session_id).await?;
+
+        let mut stops = Vec::new();
+        let mut stop_count = 0;
+        let mut error = None;
+        let mut timed_out = false;
+        loop {
+            if tokio::time::Instant::now() >= deadline {
+                timed_out = true;
+                break;
+            }
+            match session.get_state().await {
+                crate::debug::state::DebugState::Stopped { .. } => {
+                    stop_count += 1;
+                    if stops.len() < args.max_stops {
+                        stops.push(Self::run_stop_snapshot(&session, &args, stop_count).await);
+                    }
+                    if let Err(e) = session.continue_execution().await {
+                        error = Some(format!(
+                            "Failed to continue after stop {}: {}",
+                            stop_count, e
+                        ));
+                        break;
+                    }
+                    // Continuing may have stopped again right away
+                    continue;
+                }
+                crate::debug::state::DebugState::Terminated => break,
+                crate::debug::state::DebugState::Failed { error: e } => {
+                    error = Some(e);
+                    break;
+                }
+                _ => {}
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        }
+
+        let full_state = session.get_full_state().await;
+        let mut result = match &full_state.state {
+            crate::debug::state::DebugState::Terminated => full_state.exit_details(),
+            _ => json!({}),
+        };
+        result["sessionId"] = json!(session_id);
+        result["state"] = json!(full_state.state.name());
+        if timed_out {
+            result["reason"] = json!(format!(
+                "Timed out after {}ms with the program {}",
+                args.timeout_ms,
+                full_state.state.name()
+            ));
+        }
+        if let Some(error) = error {
+            result["reason"] = json!(error);
+            result["error"] = json!(error);
+        }
+        result["stops"] = json!(stops);
+        result["stopCount"] = json!(stop_count);
+        result["stopsTruncated"] = json!(stop_count > stops.len());
+        result["timedOut"] = json!(timed_out);
+        drop(full_state);
+
+        match self
+            .debugger_get_output(json!({ "sessionId": session_id }))
+            .await
+        {
+            Ok(output) => {
+                result["output"] = output["output"].clone();
+                if output.get("truncated").is_some() {
+                    result["outputTruncated"] = json!(true);
+                }
+            }
+            Err(e) => tracing::warn!("⚠️  Failed to collect the program output: {}", e),
+        }
+
+        // Ends the program too when it is still running (timeout)
+        drop(session);
+        let removed = self.session_manager.remove_session(&session_id).await;
+        if let Err(e) = removed {
+            tracing::warn!("⚠️  Failed to remove session {}: {}", session_id, e);
+        }
+        result["durationMs"] = json!(started.elapsed().as_millis() as u64);
+        Ok(result)
+    }
+
+    /// One entry of debugger_run_until_exit's `stops`
+    async fn run_stop_snapshot(
+        session: &crate::debug::DebugSession,
+        args: &RunUntilExitArgs,
+        index: usize,
+    ) -> Value {
+        let mut snapshot = stop_context_to_json(&session.get_full_state().await);
+        snapshot["index"] = json!(index);
+        snapshot["topFrame"] = match session.stack_trace_page(None, None, Some(1)).await {
+            Ok(page) => page
+                .stack_frames
+                .first()
+                .map_or(Value::Null, top_frame_to_json),
+            Err(e) => {
+                snapshot["warnings"] = json!([format!("Failed to get the top frame: {}", e)]);
+                Value::Null
+            }
+        };
+        if args.include_locals {
+            let details = WaitForStopArgs {
+                session_id: String::new(),
+                timeout_ms: 0,
+                include_stack_trace: false,
+                levels: None,
+                include_top_frame_variables: true,
+            };
+            Self::add_stop_details(session, &details, &mut snapshot).await;
+        }
+        snapshot
+    }
+
+    /// debugger_wait_for_stop's response for a stopped, terminated or failed
+    /// session; `None` while it is still running
+    async fn stop_response(
+        session: &crate::debug::DebugSession,
+        state: &crate::debug::state::DebugState,
+    ) -> Option<Result<Value>> {
+        match state {
+            crate::debug::state::DebugState::Stopped { thread_id, reason } => {
+                // The stop is fully registered now, so watches see the new frame
+                let watches = session.refresh_watches().await;
+                let full_state = session.get_full_state().await;
+                Some(Ok(json!({
+                    "state": "Stopped",
+                    "threadId": thread_id,
+                    "reason": reason,
+                    "stop": stop_context_to_json(&full_state),
+                    "watches": watches_to_json(&watches)
+                })))
+            }
+            crate::debug::state::DebugState::Terminated => {
+                let full_state = session.get_full_state().await;
+                let mut response = full_state.exit_details();
+                response["state"] = json!("Terminated");
+                let diagnostics = full_state.diagnostics();
+                if !diagnostics.is_empty() {
+                    response["reason"] = json!(diagnostics.join("; "));
+                    response["diagnostics"] = json!(diagnostics);
+                }
+                Some(Ok(response))
+            }
+            crate::debug::state::DebugState::Failed { error } => {
+                Some(Err(Error::Dap(format!("Session failed: {}", error))))
+            }
+            _ => None,
+        }
+    }
+
+    /// Embed what `includeStackTrace`/`includeTopFrameVariables` asked for
+    ///
+    /// The program did stop, so failures are reported in a `warnings` array
+    /// instead of failing the whole call.
+    async fn add_stop_details(
+        session: &crate::debug::DebugSession,
+        args: &WaitForStopArgs,
+        response: &mut Value,
+    ) {
+        if !args.include_stack_trace && !args.include_top_frame_variables {
+            return;
+        }
+
+        let mut warnings = Vec::new();
+        // Only the top frame is needed for its variables
+        let levels = if args.include_stack_trace {
+            args.levels.unwrap_or(DEFAULT_STOP_STACK_LEVELS)
+        } else {
+            1
+        };
+        let frames = match session
+            .stack_trace_page(None, None, Some(levels as i32))
+            .await
+        {
+            Ok(page) => {
+                if args.include_stack_trace {
+                    response["totalFrames"] = json!(page.total_frames);
+                }
+                page.stack_frames
+            }
+            Err(e) => {
+                warnings.push(format!("Failed to get the stack trace: {}", e));
+                Vec::new()
+            }
+        };
+
+        if args.include_stack_trace {
+            response["stackTrace"] = json!(frames);
+        }
+
+        if args.include_top_frame_variables {
+            if let Some(top) = frames.first() {
+                match session.scopes(top.id).await {
+                    Ok(scopes) => {
+                        let mut variables = Vec::new();
+                        for scope in scopes {
+                            // Globals and registers can be huge; expand them with debugger_get_variables
+                            if scope.expensive {
+                                variables.push(json!({
+                                    "scope": scope.name,
+                                    "expensive": true,
+                                    "variablesReference": scope.variables_reference
+                                }));
+                                continue;
+                            }
+                            match session.variables(scope.variables_reference).await {
+                                Ok(vars) => variables.push(json!({
+                                    "scope": scope.name,
+                                    "variables": vars
+                                })),
+                                Err(e) => warnings.push(format!(
+                                    "Failed to get the variables of scope '{}': {}",
+                                    scope.name, e
+                                )),
+                            }
+                        }
+                        response["topFrameVariables"] = json!(variables);
+                    }
+                    Err(e) => warnings.push(format!("Failed to get the top frame's scopes: {}", e)),
+                }
+            } else if warnings.is_empty() {
+                warnings.push("No stack frames to read variables from".to_string());
+            }
+        }
+
+        if !warnings.is_empty() {
+            response["warnings"] = json!(warnings);
+        }
+    }
+
+    async fn stop_timeout(
+        session: &crate::debug::DebugSession,
+        timeout_ms: u64,
+        state: &crate::debug::state::DebugState,
+    ) -> Error {
+        let diagnostics = session.get_full_state().await.diagnostics();
+        if !diagnostics.is_empty() {
+            return Error::InvalidState(format!(
+                "{} (waited {}ms). Current state: {:?}",
+                diagnostics.join("; "),
+                timeout_ms,
+                state
+            ));
+        }
+        Error::InvalidState(format!(
+            "Timeout waiting for program to stop ({}ms). Current state: {:?}",
+            timeout_ms, state
+        ))
+    }
+
+    /// Wait for the stop following a continue or step (`waitForStop`)
+    ///
+    /// `changes` must be subscribed before resuming. Returns the response of
+    /// debugger_wait_for_stop plus the new top frame, so stepping through
+    /// code takes one call per line instead of step + wait + stack trace.
+    async fn await_stop(
+        session_id: &str,
+        session: &crate::debug::DebugSession,
+        mut changes: tokio::sync::broadcast::Receiver<crate::debug::state::StateChange>,
+        timeout_ms: u64,
+    ) -> Result<Value> {
+        use tokio::sync::broadcast::error::RecvError;
+
+        let deadline = tokio::time::Instant::now() + tokio::time::Duration::from_millis(timeout_ms);
+        loop {
+            match tokio::time::timeout_at(deadline, changes.recv()).await {
+                Ok(Ok(change)) if change.session_id != session_id => continue,
+                Ok(Ok(change))
+                    if matches!(change.new_state, crate::debug::state::DebugState::Running) =>
+                {
+                    continue
+                }
+                // Lagged: some changes were dropped, so check the state itself
+                Ok(Ok(_)) | Ok(Err(RecvError::Lagged(_))) => {}
+                Ok(Err(RecvError::Closed)) => {
+                    return Err(Error::InvalidState(
+                        "Session manager shut down while waiting for the program to stop"
+                            .to_string(),
+                    ))
+                }
+                Err(_) => {
+                    let state = session.get_state().await;
+                    return Err(Self::stop_timeout(session, timeout_ms, &state).await);
+                }
+            }
+
+            let state = session.get_state().await;
+            let Some(response) = Self::stop_response(session, &state).await else {
+                continue;
+            };
+            let mut response = response?;
+            if matches!(state, crate::debug::state::DebugState::Stopped { .. }) {
+                response["topFrame"] = match session.stack_trace_page(None, None, Some(1)).await {
+                    Ok(page) => page
+                        .stack_frames
+                        .first()
+                        .map_or(Value::Null, top_frame_to_json),
+                    Err(e) => {
+                        tracing::warn!("⚠️  Failed to get the top frame after stopping: {}", e);
+                        Value::Null
+                    }
+                };
+            }
+            return Ok(response);
+        }
+    }
+
+    async fn debugger_list_breakpoints(&self, arguments: Value) -> Result<Value> {
+        let args: ListBreakpointsArgs = serde_json::from_value(arguments)?;
+
+        let session = self.session(&args.session_id).await?;
+
+        let unverified: Vec<(String, i32)> = session
+            .get_full_state()
+            .await
+            .breakpoints
+            .values()
+            .flatten()
+            .filter(|bp| !bp.verified)
+            .map(|bp| (bp.source_path.clone(), bp.line))
+            .collect();
+        // Stale unverified breakpoints stay explained
+        let diagnoses: std::collections::HashMap<(String, i32), BreakpointDiagnosis> =
+            if unverified.is_empty() {
+                Default::default()
+            } else {
+                let diagnoses = session.diagnose_unverified_breakpoints(&unverified).await;
+                unverified.into_iter().zip(diagnoses).collect()
+            };
+
+        let full_state = session.get_full_state().await;
+        Ok(json!({
+            "breakpoints": breakpoints_to_json(&full_state, &diagnoses)
+        }))
+    }
+
+    async fn debugger_snapshot(&self, arguments: Value) -> Result<Value> {
+        let args: SnapshotArgs = serde_json::from_value(arguments)?;
+        let options = args.options()?;
+
+        let session = self.session(&args.session_id).await?;
+        Ok(snapshot_to_json(&session, &options).await)
+    }
+
+    async fn debugger_step_over(&self, arguments: Value) -> Result<Value> {
+        let args: StepArgs = serde_json::from_value(arguments)?;
+        args.validate_granularity()?;
+
+        let session = self.session(&args.session_id).await?;
+
+        // Validate we're in a stopped state
+        let state = session.get_state().await;
+        let thread_id = if let crate::debug::state::DebugState::Stopped { thread_id, .. } = state {
+            thread_id
+        } else {
+            return Err(Error::InvalidState(
+                "Cannot step while program is running. The program must be stopped first."
+                    .to_string(),
+            ));
+        };
+
+        let thread_id = args.thread_id.unwrap_or(thread_id);
+        let changes = if args.wait_for_stop {
+            Some(self.session_manager.subscribe_state_changes())
+        } else {
+            None
+        };
+        session
+            .step_over(thread_id, args.granularity.as_deref())
+            .await?;
+
+        if let Some(changes) = changes {
+            return Self::await_stop(&args.session_id, &session, changes, args.timeout_ms).await;
+        }
+        Ok(json!({
+            "status": "stepping",
+            "threadId": thread_id
+        }))
+    }
+
+    async fn debugger_step_into(&self, arguments: Value) -> Result<Value> {
+        let args: StepArgs = serde_json::from_value(arguments)?;
+        args.validate_granularity()?;
+
+        let session = self.session(&args.session_id).await?;
+
+        // Validate we're in a stopped state
+        let state = session.get_state().await;
+        let thread_id = if let crate::debug::state::DebugState::Stopped { thread_id, .. } = state {
+            thread_id
+        } else {
+            return Err(Error::InvalidState(
+                "Cannot step while program is running. The program must be stopped first."
+                    .to_string(),
+            ));
+        };
+
+        let thread_id = args.thread_id.unwrap_or(thread_id);
+        let changes = if args.wait_for_stop {
+            Some(self.session_manager.subscribe_state_changes())
+        } else {
+            None
+        };
+        session
+            .step_into(thread_id, args.granularity.as_deref(), args.target_id)
+            .await?;
+
+        if let Some(changes) = changes {
+            return Self::await_stop(&args.session_id, &session, changes, args.timeout_ms).await;
+        }
+        Ok(json!({
+            "status": "stepping",
+            "threadId": thread_id
+        }))
+    }
+
+    async fn debugger_step_in_targets(&self, arguments: Value) -> Result<Value> {
+        let args: StepInTargetsArgs = serde_json::from_value(arguments)?;
+
+        let session = self.session(&args.session_id).await?;
+
+        let targets = session.step_in_targets(args.frame_id).await?;
+        Ok(json!({ "targets": targets }))
+    }
+
+    async fn debugger_step_out(&self, arguments: Value) -> Result<Value> {
+        let args: StepArgs = serde_json::from_value(arguments)?;
+        args.validate_granularity()?;
+
+        let session = self.session(&args.session_id).await?;
+
+        // Validate we're in a stopped state
+        let state = session.get_state().await;
+        let thread_id = if let crate::debug::state::DebugState::Stopped { thread_id, .. } = state {
+            thread_id
+        } else {
+            return Err(Error::InvalidState(
+                "Cannot step while program is running. The program must be stopped first."
+                    .to_string(),
+            ));
+        };
+
+        let thread_id = args.thread_id.unwrap_or(thread_id);
+        let changes = if args.wait_for_stop {
+            Some(self.session_manager.subscribe_state_changes())
+        } else {
+            None
+        };
+        session
+            .step_out(thread_id, args.granularity.as_deref())
+            .await?;
+
+        if let Some(changes) = changes {
+            return Self::await_stop(&args.session_id, &session, changes, args.timeout_ms).await;
+        }
+        Ok(json!({
+            "status": "stepping",
+            "threadId": thread_id
+        }))
+    }
+
+    async fn debugger_restart_frame(&self, arguments: Value) -> Result<Value> {
+        let args: RestartFrameArgs = serde_json::from_value(arguments)?;
+
+        let session = self.session(&args.session_id).await?;
+
+        let state = session.get_state().await;
+        let thread_id = if let crate::debug::state::DebugState::Stopped { thread_id, .. } = state {
+            thread_id
+        } else {
+            return Err(Error::InvalidState(
+                "Cannot restart a frame while program is running. The program must be stopped first."
+                    .to_string(),
+            ));
+        };
+
+        let frame_id = match args.frame_id {
+            Some(frame_id) => frame_id,
+            None => {
+                session
+                    .frame_id_at(Some(thread_id), args.frame_index.unwrap_or(0))
+                    .await?
+            }
+        };
+        let changes = if args.wait_for_stop {
+            Some(self.session_manager.subscribe_state_changes())
+        } else {
+            None
+        };
+        session.restart_frame(thread_id, frame_id).await?;
+
+        if let Some(changes) = changes {
+            return Self::await_stop(&args.session_id, &session, changes, args.timeout_ms).await;
+        }
+        Ok(json!({
+            "status": "restarting",
+            "frameId": frame_id,
+            "threadId": thread_id
+        }))
+    }
+
+    async fn debugger_jump_to_line(&self, arguments: Value) -> Result<Value> {
+        use tokio::sync::broadcast::error::RecvError;
+
+        let args: JumpToLineArgs = serde_json::from_value(arguments)?;
+        if args.line < 1 {
+            return Err(Error::InvalidRequest(
+                "line must be 1 or greater (lines are 1-indexed)".to_string(),
+            ));
+        }
+        let source_path = self
+            .session_source_path(&args.session_id, &args.source_path)
+            .await?;
+
+        let session = self.session(&args.session_id).await?;
+
+        let state = session.get_state().await;
+        let thread_id = if let crate::debug::state::DebugState::Stopped { thread_id, .. } = state {
+            args.thread_id.unwrap_or(thread_id)
+        } else {
+            return Err(Error::InvalidState(
+                "Cannot jump while program is running. The program must be stopped first."
+                    .to_string(),
+            ));
+        };
+
+        let mut changes = self.session_manager.subscribe_state_changes();
+        let target = session
+            .jump_to_line(thread_id, &source_path, args.line)
+            .await?;
+
+        // Frames read before the adapter's 'stopped' event would be stale
+        // as soon as it arrives
+        let _ = tokio::time::timeout(GOTO_STOP_TIMEOUT, async {
+            loop {
+                match changes.recv().await {
+                    Ok(change)
+                        if change.session_id == args.session_id
+                            && matches!(
+                                change.new_state,
+                                crate::debug::state::DebugState::Stopped { .. }
+                            ) =>
+                    {
+                        break
+                    }
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        })
+        .await;
+
+        let top_frame = session
+            .stack_trace_page(Some(thread_id), None, Some(1))
+            .await?
+            .stack_frames
+            .first()
+            .map_or(Value::Null, top_frame_to_json);
+        Ok(json!({
+            "state": session.get_state().await.name(),
+            "threadId": thread_id,
+            "target": target,
+            "topFrame": top_frame
+        }))
+    }
+
+    async fn debugger_disconnect(&self, arguments: Value) -> Result<Value> {
+        let args: DisconnectArgs = serde_json::from_value(arguments)?;
+
+        self.session_manager
+            .remove_session(&args.session_id)
+            .await?;
+
+        Ok(json!({
+            "status": "disconnected"
+        }))
+    }
+
+    async fn debugger_force_kill(&self, arguments: Value) -> Result<Value> {
+        let args: ForceKillArgs = serde_json::from_value(arguments)?;
+
+        let session = self.session(&args.session_id).await?;
+        let timeouts = session.consecutive_timeouts();
+        let reason = match args.reason {
+            Some(reason) => format!("Force-killed: {}", reason),
+            None => format!(
+                "Force-killed: adapter stopped responding ({} request(s) timed out in a row)",
+                timeouts
+            ),
+        };
+        session.force_kill(&reason).await;
+
+        let state = session.get_state().await;
+        Ok(json!({
+            "sessionId": args.session_id,
+            "state": state.name(),
+            "details": state.details()
+        }))
+    }
+
+    pub fn list_tools() -> Vec<Value> {
+        let mut tools = vec![
+            json!({
+                "name": "debugger_start",
+                "title": "Start Debugging Session",
+                "description": "Starts a new debugging session for a program. RETURNS IMMEDIATELY with a sessionId while initialization happens asynchronously in the background.\n\nIMPORTANT WORKFLOW:\n1. Call this tool first to create a session\n2. Use debugger_wait_for_stop to wait for entry point (if stopOnEntry: true)\n3. Once stopped, set breakpoints with debugger_set_breakpoint\n4. Control execution with debugger_continue\n\nTIMING: Returns in <100ms. Background initialization takes 200-500ms.\n\nRETURNS: {sessionId, status, language, program, cwd, stopOnEntry, adapter, adapterCommand, adapterVersion, compiled, rustProject: {kind: singleFile|cargo|binary, target} (Rust), build (Rust, C, C++), initialBreakpoints, restoredBreakpoints}. program and cwd are the resolved paths the program is launched with. compiled: true means the program was built from source and the build has already finished (its result is in build); compilation is never pending when this returns\n\n⭐ CRITICAL: stopOnEntry Parameter\n=================================\nFor reliable breakpoint debugging, ALWAYS use stopOnEntry: true:\n\n✅ RECOMMENDED (with stopOnEntry: true):\n  - Program pauses at first executable line\n  - Gives you time to set breakpoints before execution\n  - Prevents program from completing before breakpoints are set\n  - Required for debugging programs that execute quickly\n\n❌ NOT RECOMMENDED (stopOnEntry: false or omitted):\n  - Program runs immediately upon start\n  - May complete before breakpoints can be set\n  - Breakpoints might be missed\n  - Only use if you don't need breakpoints\n\nEXAMPLE WORKFLOW:\n  debugger_start({program: \"app.py\", stopOnEntry: true})\n  debugger_wait_for_stop()  // Wait for entry point\n  debugger_set_breakpoint({line: 20})  // Set while paused ✓\n  debugger_continue()  // Now resume to breakpoint\n\nSHORTCUT: To just 'break at line N and run', pass breakpoints: [{sourcePath, line}] instead. They are applied before the program starts, so stopOnEntry is not needed.\n\nCONFIGURATIONS: save arguments you reuse with debugger_save_config, then start with {config: \"name\"} plus any fields to override, e.g. {config: \"tests\", args: [\"-k\", \"test_login\"]}. Projects can check configurations into a .debugger-mcp.json file next to the program or in the server's working directory.\n\nSEE ALSO: debugger_wait_for_stop (efficient waiting), debugger_session_state (state checking), debugger_list_configs (saved configurations), debugger://workflows (complete examples)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "language": {
+                            "type": "string",
+                            "description": "Programming language (e.g., 'python', 'ruby', 'javascript', 'rust', 'go' (or 'golang'), 'c', 'cpp', 'bash'). Required unless 'config' provides it"
+                        },
+                        "program": {
+                            "type": "string",
+                            "description": "Absolute or relative path to the program file to debug. For Go this may also be a package directory or a prebuilt binary (debugged with Delve's exec mode). Required unless 'module' is given"
+                        },
+                        "subProcess": {
+                            "type": "boolean",
+                            "description": "Python only: also debug the subprocesses the program starts (multiprocessing, an autoreloader's server process). Each subprocess becomes a child of the session; the session ends once every process has"
+                        },
+                        "webFramework": {
+                            "type": "string",
+                            "enum": ["flask", "django"],
+                            "description": "Python only: launch a dev server so breakpoints in views are hit. flask: 'program' is the app file, started with `flask run`; django: 'program' is manage.py, args default to [\"runserver\"]. The autoreloader is turned off unless subProcess is true, in which case the reloaded server process is followed"
+                        },
+                        "useBundler": {
+                            "type": "boolean",
+                            "description": "Ruby only: start rdbg with `bundle exec` so the Gemfile's gems are available. cwd defaults to the directory of the nearest Gemfile above the program, which must exist"
+                        },
+                        "command": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Ruby only: command that runs the program instead of ruby (rdbg -c), one argument per entry, e.g. [\"bundle\", \"exec\", \"rspec\"] with program \"spec/foo_spec.rb\", or [\"bin/rails\", \"runner\"]. 'args' follow the program. No shell is involved"
+                        },
+                        "expressions": {
+                            "type": "string",
+                            "enum": ["simple", "python", "native"],
+                            "description": "Rust, C and C++ only: CodeLLDB expression evaluator. \"simple\" (default) and \"python\" show Rust values through the Rust formatters: a Vec<i32> evaluates to its elements and a String to its text. \"native\" uses LLDB's own expression parser, which can call functions and cast, but shows Rust collections as raw structs with pointers"
+                        },
+                        "coreDump": {
+                            "type": "string",
+                            "description": "Rust, C and C++ only: post-mortem mode. Path of a core dump to load, with 'program' set to the binary that crashed (not its source). The session stops at the crashed thread without running anything: use debugger_wait_for_stop, debugger_stack_trace and debugger_evaluate to inspect it. Continue and step return an error"
+                        },
+                        "initCommands": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Rust, C and C++ only: LLDB commands run before the program is loaded, e.g. [\"command script import /path/to/formatters.py\"] for custom pretty printers"
+                        },
+                        "preRunCommands": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Rust, C and C++ only: LLDB commands run just before the program is launched, e.g. [\"settings set target.max-children-count 1000\"]"
+                        },
+                        "env": {
+                            "type": "object",
+                            "additionalProperties": { "type": "string" },
+                            "description": "Rust, C and C++ only: environment variables set for the program in addition to the server's own, e.g. {\"RUST_LOG\": \"debug\"}"
+                        },
+                        "stdio": {
+                            "type": "array",
+                            "items": { "type": ["string", "null"] },
+                            "maxItems": 3,
+                            "description": "Rust, C and C++ only: files for the program's stdin, stdout and stderr, in that order; null or a missing entry keeps the default. Without a stdin file a program that reads stdin waits for input that never comes, e.g. [\"/tmp/input.txt\"] feeds that file to stdin"
+                        },
+                        "terminal": {
+                            "type": "string",
+                            "enum": ["console", "integrated", "external"],
+                            "description": "Rust, C and C++ only: where the program runs. \"console\" (default) runs it under CodeLLDB without a terminal. \"integrated\" and \"external\" have this server start it as a separate process (runInTerminal), with its output on the server's stderr"
+                        },
+                        "module": {
+                            "type": "string",
+                            "description": "Python only: run a module instead of a program, like `python -m <module>`. For a failing test: module \"pytest\" with args [\"-x\", \"tests/test_foo.py::test_bar\"] and cwd set to the project root. Pass breakpoints in the test file rather than stopOnEntry, which stops in pytest's own main"
+                        },
+                        "args": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Command-line arguments passed to the program (optional, defaults to empty array)"
+                        },
+                        "cwd": {
+                            "type": "string",
+                            "description": "Working directory for the program execution (optional, defaults to program's directory). A relative program is resolved against it"
+                        },
+                        "stopOnEntry": {
+                            "type": "boolean",
+                            "description": "If true, pauses execution at the program's first line (recommended for setting early breakpoints)"
+                        },
+                        "requestTimeoutMs": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "description": "Timeout for each DAP request in milliseconds (optional, defaults to 10000). Raise this for slow environments such as debugging inside containers"
+                        },
+                        "adapterTimeoutMs": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "description": "How long to wait for the debug adapter to accept a connection and to finish initializing, in milliseconds (optional, defaults to the server's --adapter-timeout-ms, or 3000 to connect and 7000 to initialize, 15000 for Rust and Node.js). Raise this on slow CI machines"
+                        },
+                        "entryLine": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "description": "Line to stop at when stopOnEntry is emulated with an entry breakpoint (Ruby, Node.js). Optional: by default the first executable line is detected from the source"
+                        },
+                        "breakpoints": {
+                            "type": "array",
+                            "description": "Breakpoints applied before the program starts running (after 'initialized', before configurationDone). The simplest way to 'break at line N and run' without stopOnEntry. Check debugger_session_state to see which ones verified",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "sourcePath": {
+                                        "type": "string",
+                                        "description": "Path to the source file, absolute or relative to the session's working directory (cwd, which defaults to the program's directory)"
+                                    },
+                                    "line": {
+                                        "type": "integer",
+                                        "minimum": 1,
+                                        "description": "Line number (1-indexed)"
+                                    },
+                                    "condition": {
+                                        "type": "string",
+                                        "description": "Optional expression; the breakpoint only stops when it evaluates to true"
+                                    },
+                                    "logMessage": {
+                                        "type": "string",
+                                        "description": "Optional logpoint message logged instead of stopping, e.g. 'n = {n}'"
+                                    }
+                                },
+                                "required": ["sourcePath", "line"]
+                            }
+                        },
+                        "restoreBreakpoints": {
+                            "type": "boolean",
+                            "description": "Also apply the breakpoints saved when an earlier session of this program disconnected (see debugger_saved_breakpoints). They are registered like 'breakpoints' and verified the same way. Default: false"
+                        },
+                        "cargoTarget": {
+                            "type": "string",
+                            "description": "Rust only: which Cargo target to build when 'program' is a source file inside a Cargo project: \"binary\" (default), \"test\" (cargo test --no-run) or \"example:<name>\""
+                        },
+                        "testFilter": {
+                            "type": "string",
+                            "description": "Rust only: name of the #[test] function to debug, e.g. 'test_serialization' or 'tests::test_serialization'. The matching test binary runs just that test (--exact --nocapture). Implies cargoTarget \"test\""
+                        },
+                        "forceRebuild": {
+                            "type": "boolean",
+                            "description": "Rust only: compile even when the previous build is up to date. By default single files are not recompiled while target/debug holds a newer binary, and Cargo projects use cargo's cache. The result's 'build' field reports freshness and compile time. Default: false"
+                        },
+                        "pathMappings": {
+                            "type": "array",
+                            "description": "For programs that see their sources under other paths than you do (e.g. running inside Docker). Breakpoint paths are translated from 'local' to 'remote' before they reach the debugger, and stack trace paths are translated back, so you always use your own paths. The longest matching prefix wins",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "local": {
+                                        "type": "string",
+                                        "description": "Directory as you see it, e.g. /home/me/project"
+                                    },
+                                    "remote": {
+                                        "type": "string",
+                                        "description": "Same directory as the program sees it, e.g. /app"
+                                    }
+                                },
+                                "required": ["local", "remote"]
+                            }
+                        },
+                        "pathMappingsCaseSensitive": {
+                            "type": "boolean",
+                            "description": "Compare pathMappings prefixes case-sensitively. Set to false for Windows or macOS file systems. Default: true"
+                        },
+                        "skipValidation": {
+                            "type": "boolean",
+                            "description": "Pass program and cwd to the debug adapter without checking that they exist, are readable and have the language's extension. Only for adapters that resolve paths themselves. Default: false"
+                        },
+                        "captureDapLog": {
+                            "type": "boolean",
+                            "description": "Record every DAP message exchanged with the debug adapter, readable with debugger_dap_log or the debugger://sessions/{sessionId}/dapLog resource. For troubleshooting a misbehaving session. Default: false"
+                        },
+                        "record": {
+                            "type": "boolean",
+                            "description": "Write a transcript of the session: every tool call (arguments and result) and every state change, one timestamped JSON line each. Values of keys like password or token are redacted. Find the file with debugger_recording_path and re-run it with 'debugger_mcp replay <file>'. Always on when the server runs with --record-dir. Default: false"
+                        },
+                        "watchMode": {
+                            "type": "boolean",
+                            "description": "Restart the session whenever the program file (or a watchPaths file) is saved while the program runs or is paused: the program is terminated, rebuilt for Rust, C and C++, and launched again with the current breakpoints under the same sessionId. Clients that opted into state notifications (capabilities.experimental.stateNotifications) receive a debugger/stateChanged notification with reason \"restarted: file changed\". Inspect or turn off with debugger_watch_status. Default: false"
+                        },
+                        "watchPaths": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "Watch mode only: more files whose changes restart the session, e.g. modules the program imports"
+                        },
+                        "exclusive": {
+                            "type": "boolean",
+                            "description": "Fail instead of starting a second session when a session that has not terminated already debugs the same program (compared as canonical paths). The error names the existing sessionId, so it can be used instead. Default: false"
+                        },
+                        "reuseExisting": {
+                            "type": "boolean",
+                            "description": "When a session that has not terminated already debugs the same program, return its sessionId with status \"reused\" (and its state) instead of starting another; the other start arguments are ignored then. Implies exclusive. Default: false"
+                        },
+                        "sourceMaps": {
+                            "type": "boolean",
+                            "description": "Node.js only: map compiled JavaScript back to its sources, so breakpoints can be set on .ts files and stack frames report them. Default: true for .ts programs, otherwise the adapter's default"
+                        },
+                        "outFiles": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "Node.js only: globs of the compiled JavaScript whose source maps bind breakpoints in .ts files, e.g. [\"/app/dist/**/*.js\"]. Default for .ts programs: all JavaScript under cwd (or the program's directory) except node_modules"
+                        },
+                        "resolveSourceMapLocations": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "Node.js only: globs of the locations allowed to provide source maps, e.g. [\"/app/**\", \"!**/node_modules/**\"]"
+                        },
+                        "runtimeArgs": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "Node.js only: arguments for the Node.js runtime, e.g. [\"-r\", \"ts-node/register\"] or [\"--import\", \"tsx\"] to run a .ts program without a build step. With runtimeExecutable \"npm\": the npm arguments, e.g. [\"run\", \"dev\"]"
+                        },
+                        "runtimeExecutable": {
+                            "type": "string",
+                            "description": "Node.js only: runtime to start instead of node. With a package manager (npm, yarn, pnpm) no program is launched: 'program' is then the package directory, its package.json or a file inside it, and cwd defaults to the package root"
+                        },
+                        "npmScript": {
+                            "type": "string",
+                            "description": "Node.js only: package.json script to run, e.g. \"dev\" for `npm run dev`. Shorthand for runtimeExecutable \"npm\" with runtimeArgs [\"run\", <script>]; 'args' are passed to the script. Breakpoints bind in the node processes the script starts"
+                        },
+                        "config": {
+                            "type": "string",
+                            "description": "Name of a configuration from debugger_list_configs to start from. Every other argument given here overrides the configuration's value for that field. \"last\" repeats the previous successful debugger_start"
+                        }
+                    },
+                    "required": []
+                },
+                "annotations": {
+                    "async": true,
+                    "returnsTiming": "< 100ms",
+                    "completionTiming": "200-500ms (background)",
+                    "workflow": "initialization",
+                    "requiredFollowUp": ["debugger_session_state"],
+                    "category": "session-management",
+                    "priority": 1.0
+                }
+            }),
+            json!({
+                "name": "debugger_attach",
+                "title": "Attach to Running Program",
+                "description": "Attaches to a program that is already running under a debug server, instead of launching it. RETURNS IMMEDIATELY with a sessionId; the attach handshake happens in the background like debugger_start.\n\nSUPPORTED: Python only. Start the program with:\n  python -m debugpy --listen 0.0.0.0:5678 --wait-for-client app.py\n\nREMOTE / CONTAINER PROGRAMS: pass pathMappings so breakpoints set on local files map to the paths the program runs from, e.g. [{\"localRoot\": \"/home/me/app\", \"remoteRoot\": \"/app\"}].\n\nDisconnecting detaches: the program keeps running.\n\nSEE ALSO: debugger_start (launch mode), debugger_session_state",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "language": {
+                            "type": "string",
+                            "enum": ["python"],
+                            "description": "Programming language of the running program"
+                        },
+                        "host": {
+                            "type": "string",
+                            "description": "Host of the debug server (optional, defaults to 127.0.0.1)"
+                        },
+                        "port": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "maximum": 65535,
+                            "description": "Port the debug server listens on (debugpy --listen)"
+                        },
+                        "pathMappings": {
+                            "type": "array",
+                            "description": "Optional local/remote directory pairs for programs running in a container or on another machine",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "localRoot": {
+                                        "type": "string",
+                                        "description": "Directory as seen by this machine"
+                                    },
+                                    "remoteRoot": {
+                                        "type": "string",
+                                        "description": "Same directory as seen by the debugged program"
+                                    }
+                                },
+                                "required": ["localRoot", "remoteRoot"]
+                            }
+                        },
+                        "requestTimeoutMs": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "description": "Timeout for each DAP request in milliseconds (optional, defaults to 10000)"
+                        },
+                        "breakpoints": {
+                            "type": "array",
+                            "description": "Breakpoints applied before configurationDone (same format as debugger_start)",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "sourcePath": { "type": "string" },
+                                    "line": { "type": "integer", "minimum": 1 },
+                                    "condition": { "type": "string" },
+                                    "logMessage": { "type": "string" }
+                                },
+                                "required": ["sourcePath", "line"]
+                            }
+                        }
+                    },
+                    "required": ["language", "port"]
+                },
+                "annotations": {
+                    "async": true,
+                    "returnsTiming": "< 100ms",
+                    "workflow": "initialization",
+                    "requiredFollowUp": ["debugger_session_state"],
+                    "category": "session-management",
+                    "priority": 0.8
+                }
+            }),
+            json!({
+                "name": "debugger_session_state",
+                "title": "Check Session State",
+                "description": "Retrieves the current state of a debugging session. Essential for tracking async initialization progress.\n\nWORKFLOW USAGE:\n- After debugger_start: Poll this until state is 'Running' or 'Stopped' (not 'Initializing')\n- Before setting breakpoints: Verify state is 'Stopped' (with stopOnEntry) or 'Running'\n- After operations: Check state to verify success or detect failures\n\nSTATES:\n- NotStarted: Session created but not yet initialized\n- Initializing: DAP adapter starting (wait for this to complete)\n- Launching: Program starting\n- Running: Program executing (can set breakpoints)\n- Stopped: Hit breakpoint or paused (details.reason shows why)\n- Terminated: Program exited (details.exitCode and details.reason say how; a non-zero exit adds details.stderrTail)\n- Failed: Error occurred (details.error shows message)\n\nHEALTH: healthy is false once 3 DAP requests in a row timed out (consecutiveTimeouts); the adapter is probably hung. End the session with debugger_force_kill.\n\nTIMING: Returns immediately (<10ms)\n\nTIP: When state is 'Stopped', check details.reason to understand why (e.g., 'entry', 'breakpoint', 'step')\n\nSEE ALSO: debugger://state-machine (complete state diagram), debugger-docs://guide/async-initialization",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID returned from debugger_start"
+                        }
+                    },
+                    "required": ["sessionId"]
+                },
+                "annotations": {
+                    "async": false,
+                    "returnsTiming": "< 10ms",
+                    "workflow": "state-checking",
+                    "category": "session-management",
+                    "pollable": true,
+                    "priority": 0.9
+                }
+            }),
+            json!({
+                "name": "debugger_snapshot",
+                "title": "Snapshot Session",
+                "description": "Everything needed to reason about a stop, in one call: state and stop details, stack frames, variables of the top frames, watch values, the last lines of program output and the breakpoint list. Replaces debugger_session_state + debugger_stack_trace + debugger_get_variables + debugger_get_output + debugger_list_breakpoints.\n\nWhile the program runs, stackTrace is null and variables is empty; the other sections are still filled.\n\nRETURNS: {\"sessionId\", \"language\", \"program\", \"state\": \"Stopped\", \"details\": {\"threadId\": 1, \"reason\": \"breakpoint\"}, \"stop\": {...as in debugger_wait_for_stop}, \"stackTrace\": {\"stackFrames\": [...], \"totalFrames\": 12}, \"variables\": [{\"frameId\": 1, \"name\": \"main\", \"scopes\": [{\"scope\": \"Locals\", \"variables\": [...]}]}], \"watches\": {...}, \"output\": {\"lines\": [...], \"totalLines\": 240}, \"breakpoints\": [...], \"diagnostics\": [...]}\n- A section that could not be fetched holds {\"error\": \"...\"} (per frame or scope for variables) and the document has partial: true\n- Expensive scopes (globals, registers) are listed with their variablesReference, not expanded; scopes with more than 50 variables list the first 50 and the count of the rest under more\n- Long values and output are cut to maxLength with truncated: true\n\nALSO AVAILABLE as the resource debugger://sessions/{sessionId}/snapshot (default limits)\n\nSEE ALSO: debugger_wait_for_stop (includeStackTrace, includeTopFrameVariables)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "levels": {
+                            "type": "integer",
+                            "default": 20,
+                            "description": "Stack frames to include (1-50)"
+                        },
+                        "variableFrames": {
+                            "type": "integer",
+                            "default": 1,
+                            "description": "Frames, from the top, whose variables are included (0-5)"
+                        },
+                        "outputLines": {
+                            "type": "integer",
+                            "default": 20,
+                            "description": "Last lines of program output to include (0-500)"
+                        },
+                        "maxLength": {
+                            "type": "integer",
+                            "default": 4096,
+                            "description": "Bytes of each variable value, and of the output lines together"
+                        }
+                    },
+                    "required": ["sessionId"]
+                }
+            }),
+            json!({
+                "name": "debugger_set_breakpoint",
+                "title": "Set Breakpoint",
+                "description": "Sets a breakpoint at a specific line in a source file. The debugger will pause execution when this line is about to execute.\n\nWORKFLOW:\n1. Ensure session state is 'Stopped' (recommended) or 'Running'\n2. Call this tool with the source file path and line number\n3. Check the 'verified' field in response (true = breakpoint accepted)\n4. Use debugger_continue to resume execution until breakpoint is hit\n\nTIMING: Returns in 5-20ms\n\nIMPORTANT: Use stopOnEntry: true when starting the session to pause before code execution, giving you time to set breakpoints.\n\nTIP: Relative sourcePaths resolve against the session's working directory (cwd, by default the program's directory), not the server's.\n\nRETURNS:\n- verified: true if breakpoint was successfully set and recognized by the debugger\n- sourcePath: canonical path of the source file\n- line: echo of the line number\n- hint: only when verified is false; why the breakpoint did not bind (file missing, line past the end, blank or comment line, adapter still initializing, file loaded from another path) and what to do\n- suggestedSourcePath: when the adapter loaded the file from another path (symlink, different case, another copy)\n\nSEE ALSO: debugger_continue (to hit the breakpoint), debugger_loaded_sources, debugger://workflows (breakpoint examples)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "sourcePath": {
+                            "type": "string",
+                            "description": "Path to the source file, absolute or relative to the session's working directory (cwd, which defaults to the program's directory)"
+                        },
+                        "line": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "description": "Line number where breakpoint should be set (1-indexed, i.e., first line is 1). Must be within the file; when the adapter moves the breakpoint (e.g. from a blank line to the next statement) the response has 'adjustedLine'"
+                        },
+                        "column": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "description": "Optional column (1-indexed) for lines with several statements, e.g. minified JavaScript. Use debugger_breakpoint_locations to find valid columns"
+                        },
+                        "logMessage": {
+                            "type": "string",
+                            "description": "Optional. Makes this a logpoint: instead of stopping, the adapter logs this message each time the line runs. Expressions in braces are interpolated, e.g. 'n = {n}'. Read the messages with debugger_get_output. Requires adapter support (supportsLogPoints)"
+                        }
+                    },
+                    "required": ["sessionId", "sourcePath", "line"]
+                },
+                "annotations": {
+                    "async": false,
+                    "returnsTiming": "5-20ms",
+                    "workflow": "breakpoint-management",
+                    "category": "debugging",
+                    "requiresState": ["Running", "Stopped"],
+                    "priority": 0.8
+                }
+            }),
+            json!({
+                "name": "debugger_set_breakpoints",
+                "title": "Set Multiple Breakpoints",
+                "description": "Sets several line breakpoints in one call, across one or more files. Sends one setBreakpoints request per file, so it is faster than repeated debugger_set_breakpoint calls.\n\nBreakpoints already set in a file are kept; a new entry on a line that already has a breakpoint replaces it. While the session is still initializing, the breakpoints are queued and applied before the program runs (pending: true, verified: false until then).\n\nRETURNS: {\"pending\": false, \"breakpoints\": [{\"sourcePath\": \"...\", \"line\": 12, \"id\": 3, \"verified\": true}]} in the same order as the request. Unverified entries carry a hint (and suggestedSourcePath) as in debugger_set_breakpoint\n\nSEE ALSO: debugger_set_breakpoint (single breakpoint), debugger_list_breakpoints",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "breakpoints": {
+                            "type": "array",
+                            "minItems": 1,
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "sourcePath": {
+                                        "type": "string",
+                                        "description": "Path to the source file, absolute or relative to the session's working directory (cwd, which defaults to the program's directory)"
+                                    },
+                                    "line": {
+                                        "type": "integer",
+                                        "minimum": 1,
+                                        "description": "Line number (1-indexed)"
+                                    },
+                                    "column": {
+                                        "type": "integer",
+                                        "minimum": 1,
+                                        "description": "Optional column (1-indexed) within the line"
+                                    },
+                                    "condition": {
+                                        "type": "string",
+                                        "description": "Optional expression; the breakpoint only stops when it evaluates to true"
+                                    },
+                                    "hitCondition": {
+                                        "type": "string",
+                                        "description": "Optional hit count expression, e.g. '>= 3' (syntax depends on the adapter)"
+                                    },
+                                    "logMessage": {
+                                        "type": "string",
+                                        "description": "Optional logpoint message logged instead of stopping, e.g. 'n = {n}'"
+                                    }
+                                },
+                                "required": ["sourcePath", "line"]
+                            }
+                        }
+                    },
+                    "required": ["sessionId", "breakpoints"]
+                },
+                "annotations": {
+                    "async": false,
+                    "returnsTiming": "5-50ms",
+                    "workflow": "breakpoint-management",
+                    "category": "debugging",
+                    "priority": 0.8
+                }
+            }),
+            json!({
+                "name": "debugger_breakpoint_locations",
+                "title": "Find Breakpoint Locations",
+                "description": "Lists the positions in a line range where the debug adapter can actually bind a breakpoint. Use it before setting breakpoints in minified or dense code, or when a breakpoint comes back unverified, to pick a line and column that will work.\n\nREQUIRES: A started session and adapter support (supportsBreakpointLocationsRequest; vscode-js-debug supports it)\n\nRETURNS: {\"sourcePath\": \"...\", \"locations\": [{\"line\": 3, \"column\": 17, \"endLine\": null, \"endColumn\": null}]}\n\nSEE ALSO: debugger_set_breakpoint (column parameter)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "sourcePath": {
+                            "type": "string",
+                            "description": "Path to the source file, absolute or relative to the session's working directory (cwd, which defaults to the program's directory)"
+                        },
+                        "line": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "description": "First line of the range (1-indexed)"
+                        },
+                        "endLine": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "description": "Optional last line of the range (inclusive). Defaults to line"
+                        }
+                    },
+                    "required": ["sessionId", "sourcePath", "line"]
+                },
+                "annotations": {
+                    "async": false,
+                    "returnsTiming": "5-50ms",
+                    "workflow": "breakpoint-management",
+                    "category": "debugging",
+                    "requiresState": ["Running", "Stopped"],
+                    "priority": 0.6
+                }
+            }),
+            json!({
+                "name": "debugger_continue",
+                "title": "Continue Execution",
+                "description": "Resumes program execution after being paused (e.g., at a breakpoint or entry point). Execution continues until the next breakpoint, exception, or program termination.\n\nWORKFLOW:\n1. Session must be in 'Stopped' state (verify with debugger_session_state)\n2. Call this tool to resume execution\n3. Poll debugger_session_state to detect when execution stops again\n4. When state returns to 'Stopped', check details.reason:\n   - 'breakpoint': Hit a breakpoint (use debugger_stack_trace to inspect)\n   - 'exception': Uncaught exception occurred\n   - 'pause': Manual pause requested\n   - 'step': Completed a step operation\n\nTIMING: Returns in <10ms (but program continues running asynchronously)\n\nTIP: Pass waitForStop: true to get the next stop in the same call instead of polling.\n\nRETURNS: {\"status\": \"continued\"}, or with waitForStop the debugger_wait_for_stop response plus \"topFrame\": {\"name\", \"source\", \"line\"}\n\nSEE ALSO: debugger_stack_trace (inspect state when stopped), debugger://workflows (execution control patterns)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "waitForStop": {
+                            "type": "boolean",
+                            "default": false,
+                            "description": "Return only once the program stops again (or terminates), with the stop reason and the new top frame: the response of debugger_wait_for_stop plus topFrame {name, source, line}"
+                        },
+                        "timeoutMs": {
+                            "type": "integer",
+                            "default": 5000,
+                            "description": "With waitForStop: maximum time to wait in milliseconds (default: 5000)"
+                        }
+                    },
+                    "required": ["sessionId"]
+                },
+                "annotations": {
+                    "async": true,
+                    "returnsTiming": "< 10ms",
+                    "completionTiming": "unknown (until next stop)",
+                    "workflow": "execution-control",
+                    "category": "debugging",
+                    "requiresState": ["Stopped"],
+                    "priority": 0.7
+                }
+            }),
+            json!({
+                "name": "debugger_stack_trace",
+                "title": "Get Stack Trace",
+                "description": "Retrieves the current call stack when execution is paused. Shows the sequence of function calls that led to the current execution point.\n\n⭐ PRIMARY PURPOSE: Get Frame IDs for debugger_evaluate\n======================================================\nThe 'id' field in each frame is CRITICAL - use it with debugger_evaluate to access variables:\n\nRETURNS: Array of stack frames, each containing:\n- id: Frame identifier → USE THIS as frameId in debugger_evaluate ⭐\n- name: Function/method name\n- source: {path: \"file path\", name: \"filename\"}\n- line: Current line number in this frame\n- column: Column number (if available)\n- presentationHint: \"label\" or \"subtle\" for separators and library frames, when the adapter marks them (debugpy with justMyCode, vscode-js-debug); source.origin says where such code comes from\n\n⚠️ Frame IDs Change Between Stops!\n================================\nFrame IDs are NOT stable across different stop events:\n- After EACH stop (breakpoint, step, continue), frame IDs change\n- ALWAYS call debugger_stack_trace fresh after each stop\n- NEVER reuse frame IDs from previous stops\n\nEXAMPLE PATTERN:\n  // Stop 1: Hit breakpoint\n  debugger_wait_for_stop()\n  stack1 = debugger_stack_trace()\n  frameId1 = stack1.stackFrames[0].id  // e.g., id = 5\n  debugger_evaluate({expression: \"x\", frameId: frameId1})  ✓\n  \n  // Stop 2: After continue and hit another breakpoint\n  debugger_continue()\n  debugger_wait_for_stop()\n  stack2 = debugger_stack_trace()  // GET FRESH TRACE!\n  frameId2 = stack2.stackFrames[0].id  // e.g., id = 8 (DIFFERENT!)\n  \n  // Using old frameId1 here fails with STALE_FRAME_ID ❌\n  debugger_evaluate({expression: \"x\", frameId: frameId2})  ✓ Correct\n\nWORKFLOW:\n1. Session must be in 'Stopped' state (e.g., at a breakpoint)\n2. Call this tool to get current stack frames\n3. Extract the 'id' field from desired frame\n4. Pass that 'id' as frameId to debugger_evaluate\n5. Repeat steps 2-4 after each new stop event\n\nTIMING: Returns in 10-50ms depending on stack depth\n\nTIP: The first frame (index 0) is the current execution point. Higher indices are caller frames.\n\nPAGING: At most 50 frames are returned by default. 'totalFrames' is the depth of the whole stack (null if the adapter does not report it); when more frames exist a 'note' gives the startFrame of the next page. For runaway recursion, page with {startFrame: 50, levels: 50} or jump to the bottom of the stack with startFrame: totalFrames - 10.\n\nTHREADS: by default the stack of the thread that stopped. Pass threadId (see 'threads' in debugger_session_state) to inspect another thread; pass the same threadId to debugger_evaluate along with a frameId from that stack.\n\nLIBRARY NOISE: skipSubtleFrames: true leaves out label and subtle frames and reports how many in 'hiddenFrames'. startFrame, totalFrames and frameIndex (debugger_evaluate) still count the hidden frames.\n\nCOMMON USE CASES:\n- Get frame IDs for debugger_evaluate (primary use)\n- Inspect where a breakpoint was hit\n- Understand call hierarchy\n- Diagnose unexpected execution paths\n\nSEE ALSO: debugger_evaluate (requires frame IDs from this tool), debugger://patterns (frame ID usage examples)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "startFrame": {
+                            "type": "integer",
+                            "minimum": 0,
+                            "default": 0,
+                            "description": "Index of the first frame to return (0 is the current execution point)"
+                        },
+                        "levels": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "default": 50,
+                            "description": "Maximum number of frames to return (default: 50)"
+                        },
+                        "threadId": {
+                            "type": "integer",
+                            "description": "Thread whose stack to return (default: the thread that stopped)"
+                        },
+                        "skipSubtleFrames": {
+                            "type": "boolean",
+                            "default": false,
+                            "description": "Leave out frames the adapter marks as label or subtle (library code, async separators); the response counts them in hiddenFrames"
+                        }
+                    },
+                    "required": ["sessionId"]
+                },
+                "annotations": {
+                    "async": false,
+                    "returnsTiming": "10-50ms",
+                    "workflow": "inspection",
+                    "category": "debugging",
+                    "requiresState": ["Stopped"],
+                    "priority": 0.6
+                }
+            }),
+            json!({
+                "name": "debugger_evaluate",
+                "title": "Evaluate Expression",
+                "description": "Evaluates an expression in the context of the paused program. Can access variables, call functions, and perform computations using the program's current state.\n\n⚠️ CRITICAL: frameId Requirement\n================================\nWhile technically optional, frameId is REQUIRED in practice for accessing local variables:\n\n❌ WITHOUT frameId:\n  debugger_evaluate({expression: \"local_var\"})\n  → Result: NameError: name 'local_var' is not defined\n  \n  Why: Evaluates in global/default context where local variables don't exist\n\n✅ WITH frameId (REQUIRED WORKFLOW):\n  1. Get stack trace: stack = debugger_stack_trace()\n  2. Extract frame ID: frameId = stack.stackFrames[0].id\n  3. Evaluate with frameId:\n     debugger_evaluate({expression: \"local_var\", frameId: frameId})\n  → Result: Successfully accesses local variable ✓\n\n⚠️ Frame IDs Change Between Stops!\n  - Frame IDs are NOT stable across different stop events\n  - ALWAYS get a fresh stack trace after each stop\n  - NEVER reuse frame IDs from previous stops: they are rejected with STALE_FRAME_ID\n  - Or skip frame IDs entirely: frameIndex (0 = current frame, 1 = caller) is resolved fresh at every call\n\nEXAMPLE PATTERN (Correct Way):\n  // After hitting breakpoint:\n  const stack = debugger_stack_trace()\n  const frameId = stack.stackFrames[0].id  // Current frame\n  const value = debugger_evaluate({expression: \"n\", frameId: frameId})\n  \n  // After next stop, get NEW frame ID:\n  const stack2 = debugger_stack_trace()  // Fresh trace!\n  const frameId2 = stack2.stackFrames[0].id  // New frame ID\n  const value2 = debugger_evaluate({expression: \"n\", frameId: frameId2})\n\nWORKFLOW:\n1. Session must be in 'Stopped' state\n2. Call debugger_stack_trace to get current stack frames\n3. Extract frame ID from desired frame (usually frame[0] for current location)\n4. Call this tool with expression AND frameId\n5. Examine the result value\n\nTIMING: Returns in 20-200ms depending on expression complexity\n\nEXPRESSION EXAMPLES:\n- Variable access: \"x\", \"obj.property\", \"array[0]\"\n- Arithmetic: \"x + y\", \"count * 2\"\n- Comparisons: \"x > 10\", \"status == 'ready'\"\n- Function calls: \"len(array)\", \"obj.method()\"\n- Complex: \"[item for item in list if item > 0]\" (Python)\n\nRETURNS: {\"result\": \"string representation\", \"type\": \"dict\", \"variablesReference\": 7, \"namedVariables\": 2, \"indexedVariables\": null, \"presentationHint\": null, \"memoryReference\": null}\n- type and the counts are null when the adapter does not report them\n- variablesReference > 0 means the value is structured (object, list, dict): pass it to debugger_get_variables to drill into its children\n\nCONTEXT:\n- 'watch' (default): evaluate an expression for inspection\n- 'hover': like watch, for quick value previews\n- 'repl': debug console input. debugpy executes statements here (\"x = 5\" assigns), CodeLLDB runs LLDB commands\n- 'clipboard': value formatted for copying\n- allowSideEffects: true selects 'repl' when no context is given; false refuses 'repl'\n\nRUST VALUES: with the default evaluator (debugger_start expressions \"simple\") Rust collections are pretty-printed: a Vec<i32> shows as \"(3) vec![1, 2, 3]\" and its elements can be expanded with debugger_get_variables. With expressions \"native\" the same value shows its raw buf/ptr/len fields\n\nLARGE RESULTS: results longer than maxLength (default 4096 bytes) are cut and marked {\"truncated\": true, \"originalLength\": n}. For a big list or DataFrame, browse the children with debugger_get_variables (start/count) instead of raising maxLength; the response's 'hint' says how\n\nCOMMON ERROR:\n  \"NameError: name 'variable' is not defined\"\n  → Solution: Add frameId parameter from debugger_stack_trace\n\nSEE ALSO: debugger_stack_trace (get frame IDs), debugger_get_variables (expand structured results), debugger://patterns (cookbook examples)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "expression": {
+                            "type": "string",
+                            "description": "Expression to evaluate (syntax depends on programming language being debugged)"
+                        },
+                        "frameId": {
+                            "type": "integer",
+                            "description": "Stack frame ID from debugger_stack_trace (optional, defaults to current frame)"
+                        },
+                        "frameIndex": {
+                            "type": "integer",
+                            "minimum": 0,
+                            "description": "Position of the frame in the current stack instead of a frameId (0 = current frame, 1 = its caller, ...). Resolved against a fresh stack trace, so it never goes stale. frameId wins when both are given"
+                        },
+                        "threadId": {
+                            "type": "integer",
+                            "description": "Thread the frame belongs to (default: the thread that stopped). Without frameId, evaluates in this thread's top frame"
+                        },
+                        "context": {
+                            "type": "string",
+                            "enum": ["watch", "repl", "hover", "clipboard"],
+                            "description": "DAP evaluate context (default 'watch'). 'repl' behaves like typing into the debug console and may execute statements"
+                        },
+                        "allowSideEffects": {
+                            "type": "boolean",
+                            "description": "false rejects 'repl'; true without a context selects 'repl'"
+                        },
+                        "maxLength": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "default": 4096,
+                            "description": "Maximum bytes of 'result' to return (default: 4096). Longer results are cut and marked with truncated: true and originalLength"
+                        }
+                    },
+                    "required": ["sessionId", "expression"]
+                },
+                "annotations": {
+                    "async": false,
+                    "returnsTiming": "20-200ms",
+                    "workflow": "inspection",
+                    "category": "debugging",
+                    "requiresState": ["Stopped"],
+                    "priority": 0.5
+                }
+            }),
+            json!({
+                "name": "debugger_get_variables",
+                "title": "Expand Variables",
+                "description": "Lists the children of a structured value (object fields, list items, dict entries) using the variablesReference returned by debugger_evaluate.\n\nWORKFLOW:\n1. debugger_evaluate({expression: \"config\", frameId}) → {\"variablesReference\": 7, ...}\n2. debugger_get_variables({variablesReference: 7}) → children of config\n3. Any child with variablesReference > 0 can be expanded the same way\n\n⚠️ References are only valid while the program stays stopped; they become invalid after continue or step.\n\nPAGING: at most 'count' (default 100) children are returned, starting at 'start'. A list with 100000 items is read page by page: {start: 0, count: 100}, {start: 100, count: 100}, ... A 'note' is included when more children may follow.\n\nRETURNS: {\"variables\": [{\"name\": \"...\", \"value\": \"...\", \"type\": \"...\", \"variablesReference\": 0}], \"start\": 0}\n\nSEE ALSO: debugger_evaluate",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "variablesReference": {
+                            "type": "integer",
+                            "description": "variablesReference (> 0) from debugger_evaluate or a previous debugger_get_variables call"
+                        },
+                        "start": {
+                            "type": "integer",
+                            "minimum": 0,
+                            "default": 0,
+                            "description": "Index of the first child to return"
+                        },
+                        "count": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "default": 100,
+                            "description": "Maximum number of children to return (default: 100)"
+                        },
+                        "maxLength": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "default": 4096,
+                            "description": "Maximum bytes of each child's value (default: 4096); longer values are marked truncated: true with their originalLength"
+                        }
+                    },
+                    "required": ["sessionId", "variablesReference"]
+                },
+                "annotations": {
+                    "async": false,
+                    "returnsTiming": "10-100ms",
+                    "workflow": "inspection",
+                    "category": "debugging",
+                    "requiresState": ["Stopped"],
+                    "priority": 0.5
+                }
+            }),
+            json!({
+                "name": "debugger_read_memory",
+                "title": "Read Memory",
+                "description": "Reads raw memory at a memoryReference (an address) returned by debugger_evaluate or debugger_get_variables. Useful for unsafe code and FFI in Rust, C and C++ (CodeLLDB).\n\nWORKFLOW:\n1. debugger_evaluate({expression: \"&BUFFER\", frameId}) → {\"memoryReference\": \"0x5555555a1040\", ...}\n2. debugger_read_memory({memoryReference: \"0x5555555a1040\", count: 32})\n\n⚠️ Only adapters with supportsReadMemoryRequest (CodeLLDB) support this. The session must be stopped.\n\nRETURNS: {\"address\": \"0x5555555a1040\", \"data\": \"<base64>\", \"bytesRead\": 32, \"unreadableBytes\": 0, \"rows\": [{\"address\": \"0x5555555a1040\", \"hex\": \"68 65 6c 6c 6f ...\", \"ascii\": \"hello...\"}]}\n- rows: 16 bytes each; non-printable bytes show as '.' in ascii\n\nSEE ALSO: debugger_disassemble, debugger_evaluate",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "memoryReference": {
+                            "type": "string",
+                            "description": "memoryReference from debugger_evaluate or debugger_get_variables"
+                        },
+                        "offset": {
+                            "type": "integer",
+                            "description": "Byte offset from memoryReference (may be negative)"
+                        },
+                        "count": {
+                            "type": "integer",
+                            "description": "Number of bytes to read (1-65536)"
+                        }
+                    },
+                    "required": ["sessionId", "memoryReference", "count"]
+                },
+                "annotations": {
+                    "async": false,
+                    "returnsTiming": "10-100ms",
+                    "workflow": "inspection",
+                    "category": "debugging",
+                    "requiresState": ["Stopped"],
+                    "priority": 0.3
+                }
+            }),
+            json!({
+                "name": "debugger_disassemble",
+                "title": "Disassemble",
+                "description": "Disassembles machine instructions starting at a memoryReference returned by debugger_evaluate or debugger_get_variables (e.g. a function pointer).\n\n⚠️ Only adapters with supportsDisassembleRequest (CodeLLDB) support this. The session must be stopped.\n\nRETURNS: {\"instructions\": [{\"address\": \"0x555555559a20\", \"instruction\": \"push rbp\", \"bytes\": \"55\", \"symbol\": \"main\", \"line\": 4}]}\n- bytes, symbol and line are null when the adapter does not report them\n\nSEE ALSO: debugger_read_memory",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "memoryReference": {
+                            "type": "string",
+                            "description": "memoryReference from debugger_evaluate or debugger_get_variables"
+                        },
+                        "instructionCount": {
+                            "type": "integer",
+                            "description": "Number of instructions (1-1000, default 16)"
+                        }
+                    },
+                    "required": ["sessionId", "memoryReference"]
+                },
+                "annotations": {
+                    "async": false,
+                    "returnsTiming": "10-100ms",
+                    "workflow": "inspection",
+                    "category": "debugging",
+                    "requiresState": ["Stopped"],
+                    "priority": 0.3
+                }
+            }),
+            json!({
+                "name": "debugger_registers",
+                "title": "Read Registers",
+                "description": "Returns the CPU registers of a stack frame as name/value pairs, from the adapter's registers scope. Useful at a crash or in unsafe code, together with instruction stepping:\n  debugger_step_over({sessionId, granularity: \"instruction\", waitForStop: true})\n  debugger_registers({sessionId})  // rip/pc moved by one instruction\n\n⚠️ Only native debuggers have registers: Rust, C and C++ (CodeLLDB). Python, Ruby and Node.js sessions are rejected. The session must be stopped.\n\nRETURNS: {\"frameId\": 1000, \"instructionPointerReference\": \"0x555555559a24\", \"group\": \"General Purpose Registers\", \"groups\": [\"General Purpose Registers\", \"Floating Point Registers\", ...], \"registers\": [{\"name\": \"rip\", \"value\": \"0x0000555555559a24\"}, ...]}\n- instructionPointerReference: only when frameId is omitted (top frame)\n- group/groups: null/[] when the adapter lists registers without groups\n\nSEE ALSO: debugger_disassemble (instructionPointerReference), debugger_step_over (granularity)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "frameId": {
+                            "type": "integer",
+                            "description": "Frame ID from debugger_stack_trace (default: top frame)"
+                        },
+                        "group": {
+                            "type": "string",
+                            "description": "Register group, matched against the start of its name, e.g. \"float\" (default: the first group, general purpose registers for CodeLLDB)"
+                        }
+                    },
+                    "required": ["sessionId"]
+                },
+                "annotations": {
+                    "async": false,
+                    "returnsTiming": "10-100ms",
+                    "workflow": "inspection",
+                    "category": "debugging",
+                    "requiresState": ["Stopped"],
+                    "priority": 0.3
+                }
+            }),
+            json!({
+                "name": "debugger_completions",
+                "title": "Complete Expression",
+                "description": "Suggests completions for a partial expression in the scope of a stack frame, like a debug console's tab completion. Use it to discover variable, attribute and function names before calling debugger_evaluate.\n\nEXAMPLE:\ndebugger_completions({sessionId, text: \"fi\"}) → {\"targets\": [{\"label\": \"fizzbuzz\", \"type\": \"function\", \"text\": \"fizzbuzz\"}]}\n\n⚠️ Only adapters with supportsCompletionsRequest (debugpy, CodeLLDB) support this. The session must be stopped.\n\nRETURNS: {\"targets\": [{\"label\", \"type\", \"text\"}]}\n- text: what to insert in place of the word before the cursor\n- type: \"function\", \"variable\", \"module\", ... or null\n\nSEE ALSO: debugger_evaluate, debugger_stack_trace (frame IDs)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "text": {
+                            "type": "string",
+                            "description": "Partial expression to complete, e.g. \"fi\" or \"self.na\""
+                        },
+                        "column": {
+                            "type": "integer",
+                            "description": "Cursor position in text, 1-based (default: end of text)"
+                        },
+                        "frameId": {
+                            "type": "integer",
+                            "description": "Frame whose scope is used (default: top frame of the stopped thread)"
+                        }
+                    },
+                    "required": ["sessionId", "text"]
+                },
+                "annotations": {
+                    "async": false,
+                    "returnsTiming": "10-200ms",
+                    "workflow": "inspection",
+                    "category": "debugging",
+                    "requiresState": ["Stopped"],
+                    "priority": 0.4
+                }
+            }),
+            json!({
+                "name": "debugger_loaded_sources",
+                "title": "List Loaded Sources",
+                "description": "Lists the source files the debug adapter has loaded, by path. Use it when a breakpoint comes back unverified: the usual cause is that the program runs the file from a different path (a symlink, a container mount, an installed copy of a package).\n\nThe list grows as the program imports code, so call it after the program has run past the imports.\n\nRETURNS: {\"sources\": [\"/app/main.py\", \"/usr/lib/python3.12/json/__init__.py\", ...], \"count\": 42}\n\nSEE ALSO: debugger_set_breakpoint (reports suggestedSourcePath for unverified breakpoints), debugger_modules",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "filter": {
+                            "type": "string",
+                            "description": "Only return paths containing this text (e.g. a file name)"
+                        }
+                    },
+                    "required": ["sessionId"]
+                },
+                "annotations": {
+                    "async": false,
+                    "returnsTiming": "10-200ms",
+                    "workflow": "inspection",
+                    "category": "debugging",
+                    "priority": 0.3
+                }
+            }),
+            json!({
+                "name": "debugger_check_sources",
+                "title": "Check Source Changes",
+                "description": "Checks whether the program file or any file with breakpoints was edited since the session started using it. The running program keeps executing the code it loaded, so after an edit its line numbers, breakpoints and stack traces no longer match the file on disk.\n\ndebugger_set_breakpoint, debugger_set_breakpoints and debugger_stack_trace add the same sourceChanged warning on their own.\n\nRETURNS: {\"changed\": true, \"changedSources\": [\"/app/main.py\"], \"trackedSources\": 2, \"warning\": \"...restart the session...\"}\n- warning is only present when changed is true\n\nSEE ALSO: debugger_disconnect + debugger_start (restart to pick up the edits)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        }
+                    },
+                    "required": ["sessionId"]
+                },
+                "annotations": {
+                    "async": false,
+                    "returnsTiming": "< 10ms",
+                    "workflow": "inspection",
+                    "category": "debugging",
+                    "priority": 0.3
+                }
+            }),
+            json!({
+                "name": "debugger_watch_status",
+                "title": "Watch Mode Status",
+                "description": "Reports the watch mode of a session started with watchMode: true, or turns it off with disable: true. In watch mode a saved change to a watched file restarts the session under the same sessionId.\n\nRETURNS: {\"enabled\": true, \"paths\": [\"/app/main.py\"], \"restarts\": 2, \"restarting\": false, \"lastRestartMs\": 1700000000000, \"lastError\": null}\n- lastError: why the last restart failed (e.g. a build error); the next change tries again\n- enabled is false for sessions without watch mode\n\nSEE ALSO: debugger_start (watchMode, watchPaths), debugger_check_sources",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "disable": {
+                            "type": "boolean",
+                            "description": "Stop watching the session's files. Default: false"
+                        }
+                    },
+                    "required": ["sessionId"]
+                },
+                "annotations": {
+                    "async": false,
+                    "returnsTiming": "< 10ms",
+                    "workflow": "inspection",
+                    "category": "debugging",
+                    "priority": 0.2
+                }
+            }),
+            json!({
+                "name": "debugger_modules",
+                "title": "List Modules",
+                "description": "Lists the modules loaded by the debugged program: shared libraries and executables for CodeLLDB, imported modules for debugpy. Check symbolStatus when breakpoints in a library do not bind.\n\nRETURNS: {\"modules\": [{\"id\": 1, \"name\": \"libfoo.so\", \"path\": \"/usr/lib/libfoo.so\", \"version\": null, \"symbolStatus\": \"Symbols loaded.\", \"addressRange\": \"0x7ffff7fc3000\"}], \"count\": 1}\n- Fields other than id and name are omitted when the adapter does not report them\n\nSEE ALSO: debugger_loaded_sources",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        }
+                    },
+                    "required": ["sessionId"]
+                },
+                "annotations": {
+                    "async": false,
+                    "returnsTiming": "10-200ms",
+                    "workflow": "inspection",
+                    "category": "debugging",
+                    "priority": 0.2
+                }
+            }),
+            json!({
+                "name": "debugger_disconnect",
+                "title": "Disconnect Session",
+                "description": "Terminates a debugging session and cleans up all associated resources. The debugged program will be stopped if still running.\n\nWORKFLOW:\n1. Call this when debugging is complete\n2. Session and all breakpoints are removed\n3. Debugged program is terminated gracefully\n\nTIMING: Returns in 50-200ms (includes cleanup time)\n\nIMPORTANT: Always disconnect when finished to free resources. The session cannot be resumed after disconnection.\n\nRETURNS: {\"status\": \"disconnected\"}\n\nTIP: If the program is still running, it will be terminated. If you want to let the program finish naturally, you can skip calling this tool, but resources will not be cleaned up immediately.\n\nSEE ALSO: debugger://workflows (complete debugging workflows showing disconnect)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        }
+                    },
+                    "required": ["sessionId"]
+                },
+                "annotations": {
+                    "async": false,
+                    "returnsTiming": "50-200ms",
+                    "workflow": "cleanup",
+                    "category": "session-management",
+                    "destructive": true,
+                    "priority": 0.4
+                }
+            }),
+            json!({
+                "name": "debugger_force_kill",
+                "title": "Force-Kill Hung Session",
+                "description": "Kills the debug adapter of a session that stopped responding, WITHOUT the polite disconnect (which would only time out again). The session moves to state 'Failed' with the reason in details.error and stays listed until debugger_disconnect.\n\nWHEN TO USE: debugger_session_state reports healthy: false (3 DAP requests in a row timed out), or every tool call on the session times out while the adapter process is still alive.\n\nWORKFLOW:\n1. debugger_session_state → healthy: false\n2. debugger_force_kill({sessionId})\n3. debugger_disconnect({sessionId}) to remove it, then debugger_start to relaunch\n\nRETURNS: {\"sessionId\": \"...\", \"state\": \"Failed\", \"details\": {\"error\": \"Force-killed: adapter stopped responding (3 request(s) timed out in a row)\"}}\n\nSEE ALSO: debugger_disconnect (normal cleanup), debugger_session_state",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "reason": {
+                            "type": "string",
+                            "description": "Why the session is killed, kept in details.error (default: the number of timed-out requests)"
+                        }
+                    },
+                    "required": ["sessionId"]
+                },
+                "annotations": {
+                    "async": false,
+                    "workflow": "cleanup",
+                    "category": "session-management",
+                    "destructive": true,
+                    "priority": 0.2
+                }
+            }),
+            json!({
+                "name": "debugger_wait_for_stop",
+                "title": "Wait For Program To Stop",
+                "description": "Blocks until the debugger stops (at breakpoint, step, or entry point), or times out. More efficient than polling debugger_session_state.\n\n⭐ EFFICIENT ALTERNATIVE TO POLLING\n==================================\nReplaces old pattern of repeated sleep + state check with single blocking call:\n\n❌ OLD PATTERN (slow, inefficient):\n  debugger_continue()\n  sleep(200ms)  // Arbitrary delay\n  state = debugger_session_state()\n  if state != \"Stopped\":\n    sleep(500ms)  // More waiting\n    state = debugger_session_state()  // Still might be Running\n  // Takes 500-3000ms with multiple polls\n\n✅ NEW PATTERN (fast, efficient):\n  debugger_continue()\n  debugger_wait_for_stop({timeoutMs: 5000})\n  // Returns immediately when stopped (typically <100ms)\n  // No wasted polling cycles!\n\n⭐ TIMING BEHAVIOR\n=================\n- If ALREADY stopped: Returns immediately (<10ms)\n- If running: Blocks until stop event or timeout\n- If program terminated: Returns with state \"Terminated\", its exitCode and, for a non-zero exit, stderrTail (last stderr lines)\n- If timeout expires: Returns error\n\nTypical return times:\n- Entry point (stopOnEntry): <100ms\n- Breakpoint hit: <100ms  \n- Step completion: <50ms\n\nCOMMON PATTERNS:\n\n1. Wait for entry after start:\n   debugger_start({stopOnEntry: true})\n   debugger_wait_for_stop()  // Immediate return when at entry\n\n2. Wait for breakpoint:\n   debugger_continue()\n   debugger_wait_for_stop()  // Blocks until breakpoint hit\n\n3. Wait for step completion:\n   debugger_step_over()\n   debugger_wait_for_stop()  // Blocks until step completes\n\n4. Loop through multiple stops:\n   for (i = 0; i < 5; i++):\n     debugger_continue()\n     result = debugger_wait_for_stop()\n     // Process each stop...\n\nWORKFLOW:\n1. Call debugger_continue(), debugger_step_*, or debugger_start()\n2. Call this tool to wait for the next stop event\n3. Returns immediately when program stops\n4. Check result.reason to understand why it stopped\n\nRETURNS:\n{\n  \"state\": \"Stopped\",\n  \"threadId\": 1,\n  \"reason\": \"breakpoint\",  // or \"entry\", \"step\", \"pause\", etc.\n  \"stop\": {\"description\": ..., \"hitBreakpoints\": [{\"type\": \"line\", \"id\": 2, \"sourcePath\": \"...\", \"line\": 11}], ...},\n  \"watches\": {\"n\": {\"value\": \"15\"}}  // see debugger_add_watch\n}\n\nINSPECT IN ONE CALL: pass includeStackTrace: true and/or includeTopFrameVariables: true to also get 'stackTrace' and 'topFrameVariables' without calling debugger_stack_trace and debugger_get_variables. If fetching them fails the stop is still returned, with the errors in 'warnings'.\n\nPERFORMANCE:\n~5x faster than polling approach\nNo wasted CPU cycles\nImmediate notification of state changes\n\nSEE ALSO: debugger_session_state (check current state), debugger_continue (resume execution)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "timeoutMs": {
+                            "type": "integer",
+                            "default": 5000,
+                            "description": "Maximum time to wait in milliseconds (default: 5000)"
+                        },
+                        "includeStackTrace": {
+                            "type": "boolean",
+                            "default": false,
+                            "description": "On a stop, also return the stopped thread's frames as 'stackTrace' (same format as debugger_stack_trace)"
+                        },
+                        "levels": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "default": 20,
+                            "description": "With includeStackTrace: number of frames to return, top first (default: 20)"
+                        },
+                        "includeTopFrameVariables": {
+                            "type": "boolean",
+                            "default": false,
+                            "description": "On a stop, also return the variables of the top frame as 'topFrameVariables': [{\"scope\": \"Locals\", \"variables\": [...]}]. Expensive scopes (e.g. globals) are listed with their variablesReference for debugger_get_variables instead of being expanded"
+                        }
+                    },
+                    "required": ["sessionId"]
+                }
+            }),
+            json!({
+                "name": "debugger_list_breakpoints",
+                "title": "List All Breakpoints",
+                "description": "Lists all breakpoints currently set across all source files.\n\nUSEFUL FOR:\n- Verifying which breakpoints are active\n- Checking breakpoint verification status\n- Debugging why a breakpoint might not be hit\n\nTIMING: Returns immediately (<10ms)\n\nRETURNS: Array of breakpoints. Line breakpoints have type 'line' with id, verified status, line, and sourcePath, plus the adapter's message (when given) and a hint explaining why an unverified breakpoint did not bind. Verification is updated when the adapter binds a breakpoint later, e.g. once a lazily loaded module is imported; function breakpoints have type 'function' with id, verified status, name, and condition",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        }
+                    },
+                    "required": ["sessionId"]
+                }
+            }),
+            json!({
+                "name": "debugger_step_over",
+                "title": "Step Over (Next Line)",
+                "description": "Executes the current line and stops at the next line. Does NOT step into function calls.\n\nREQUIRES: Program must be stopped (at breakpoint, entry, or previous step)\n\nWORKFLOW:\n1. Ensure program is stopped\n2. Call this tool to execute one line\n3. Use debugger_wait_for_stop to wait for the step to complete\n4. Inspect state with debugger_stack_trace and debugger_evaluate\n\nFASTER: With waitForStop: true this tool waits for the step itself and returns the new location, replacing steps 3 and 4 with one call:\n  debugger_step_over({sessionId, waitForStop: true})\n  // {\"state\": \"Stopped\", \"reason\": \"step\", \"topFrame\": {\"name\": \"main\", \"source\": \"/app/main.py\", \"line\": 12}, ...}\n\nTIMING: Returns quickly; use debugger_wait_for_stop to detect completion (or waitForStop)\n\nSEE ALSO: debugger_step_into (to step into functions), debugger_step_out (to step out)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "threadId": {
+                            "type": "integer",
+                            "description": "Thread ID (optional, uses stopped thread if not specified)"
+                        },
+                        "granularity": {
+                            "type": "string",
+                            "enum": ["statement", "line", "instruction"],
+                            "description": "Step by statement, line or single machine instruction (default: the adapter's, usually statement). Needs supportsSteppingGranularity: CodeLLDB for Rust, C and C++"
+                        },
+                        "waitForStop": {
+                            "type": "boolean",
+                            "default": false,
+                            "description": "Return only once the program stops again (or terminates), with the stop reason and the new top frame: the response of debugger_wait_for_stop plus topFrame {name, source, line}"
+                        },
+                        "timeoutMs": {
+                            "type": "integer",
+                            "default": 5000,
+                            "description": "With waitForStop: maximum time to wait in milliseconds (default: 5000)"
+                        }
+                    },
+                    "required": ["sessionId"]
+                }
+            }),
+            json!({
+                "name": "debugger_step_into",
+                "title": "Step Into (Enter Function)",
+                "description": "Steps into function calls on the current line. If no function call, behaves like step_over.\n\nREQUIRES: Program must be stopped\n\nUSEFUL FOR: Debugging function implementations line by line\n\nWORKFLOW: Same as debugger_step_over\n\nSEVERAL CALLS ON A LINE: in foo(bar(), baz()) a plain step enters bar() first. To enter baz() directly, list the calls with debugger_step_in_targets and pass the chosen target's id as targetId\n\nSEE ALSO: debugger_step_over (to skip functions), debugger_step_out (to exit function), debugger_step_in_targets",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "threadId": {
+                            "type": "integer",
+                            "description": "Thread ID (optional)"
+                        },
+                        "granularity": {
+                            "type": "string",
+                            "enum": ["statement", "line", "instruction"],
+                            "description": "Step by statement, line or single machine instruction, as for debugger_step_over"
+                        },
+                        "targetId": {
+                            "type": "integer",
+                            "description": "Call to step into, from debugger_step_in_targets (default: the first call on the line)"
+                        },
+                        "waitForStop": {
+                            "type": "boolean",
+                            "default": false,
+                            "description": "Return only once the program stops again (or terminates), with the stop reason and the new top frame: the response of debugger_wait_for_stop plus topFrame {name, source, line}"
+                        },
+                        "timeoutMs": {
+                            "type": "integer",
+                            "default": 5000,
+                            "description": "With waitForStop: maximum time to wait in milliseconds (default: 5000)"
+                        }
+                    },
+                    "required": ["sessionId"]
+                }
+            }),
+            json!({
+                "name": "debugger_step_in_targets",
+                "title": "List Step Into Targets",
+                "description": "Lists the calls on the current line that debugger_step_into can enter. On a line like foo(bar(), baz()) a plain step_into always enters bar() first; pick baz() here instead.\n\nEXAMPLE:\ndebugger_step_in_targets({sessionId}) → {\"targets\": [{\"id\": 1, \"label\": \"bar\"}, {\"id\": 2, \"label\": \"baz\"}]}\ndebugger_step_into({sessionId, targetId: 2, waitForStop: true})\n\n⚠️ Only adapters with supportsStepInTargetsRequest (debugpy, vscode-js-debug) support this. The session must be stopped; target ids are only valid until the program resumes.\n\nRETURNS: {\"targets\": [{\"id\", \"label\", \"line\", \"column\", \"endLine\", \"endColumn\"}]} (positions only when the adapter reports them)\n\nSEE ALSO: debugger_step_into",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "frameId": {
+                            "type": "integer",
+                            "description": "Frame whose current line is listed (default: top frame of the stopped thread)"
+                        }
+                    },
+                    "required": ["sessionId"]
+                },
+                "annotations": {
+                    "async": false,
+                    "returnsTiming": "10-200ms",
+                    "workflow": "execution-control",
+                    "category": "debugging",
+                    "requiresState": ["Stopped"],
+                    "priority": 0.4
+                }
+            }),
+            json!({
+                "name": "debugger_step_out",
+                "title": "Step Out (Exit Function)",
+                "description": "Continues execution until the current function returns, then stops at the caller.\n\nREQUIRES: Program must be stopped inside a function\n\nUSEFUL FOR: Quickly exiting from deep call stacks\n\nWORKFLOW: Same as debugger_step_over\n\nSEE ALSO: debugger_step_into (to enter function), debugger_step_over (to skip line)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "threadId": {
+                            "type": "integer",
+                            "description": "Thread ID (optional)"
+                        },
+                        "granularity": {
+                            "type": "string",
+                            "enum": ["statement", "line", "instruction"],
+                            "description": "Step by statement, line or single machine instruction, as for debugger_step_over"
+                        },
+                        "waitForStop": {
+                            "type": "boolean",
+                            "default": false,
+                            "description": "Return only once the program stops again (or terminates), with the stop reason and the new top frame: the response of debugger_wait_for_stop plus topFrame {name, source, line}"
+                        },
+                        "timeoutMs": {
+                            "type": "integer",
+                            "default": 5000,
+                            "description": "With waitForStop: maximum time to wait in milliseconds (default: 5000)"
+                        }
+                    },
+                    "required": ["sessionId"]
+                }
+            }),
+            json!({
+                "name": "debugger_restart_frame",
+                "title": "Restart Frame",
+                "description": "Re-runs a function from its first line, dropping the frames above it. Useful to retry a function after changing a variable with debugger_evaluate (context 'repl'), without restarting the whole program.\n\nREQUIRES: Program must be stopped, and adapter support (supportsRestartFrame: debugpy and CodeLLDB; not Ruby)\n\nFRAME: frameIndex (0 = current function, 1 = its caller, ...) or a frameId from debugger_stack_trace; the current function when neither is given. Side effects the function already had (output, writes to globals) are not undone.\n\nRETURNS: {\"status\": \"restarting\", \"frameId\": 3, \"threadId\": 1}, or with waitForStop the new stop (reason \"restart\") and topFrame\n\nSEE ALSO: debugger_stack_trace, debugger_step_into",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "frameId": {
+                            "type": "integer",
+                            "description": "Frame to restart, from debugger_stack_trace"
+                        },
+                        "frameIndex": {
+                            "type": "integer",
+                            "minimum": 0,
+                            "description": "Position of the frame to restart in the current stack (0 = current function); used when frameId is omitted"
+                        },
+                        "waitForStop": {
+                            "type": "boolean",
+                            "default": false,
+                            "description": "Return only once the program stops at the start of the function, with the stop reason and the new top frame"
+                        },
+                        "timeoutMs": {
+                            "type": "integer",
+                            "default": 5000,
+                            "description": "With waitForStop: maximum time to wait in milliseconds (default: 5000)"
+                        }
+                    },
+                    "required": ["sessionId"]
+                },
+                "annotations": {
+                    "async": false,
+                    "workflow": "execution-control",
+                    "category": "debugging",
+                    "requiresState": ["Stopped"],
+                    "priority": 0.3
+                }
+            }),
+            json!({
+                "name": "debugger_jump_to_line",
+                "title": "Jump To Line (Set Next Statement)",
+                "description": "Moves the stopped program to another line of the current function WITHOUT executing the code in between. Use it to skip a faulty block, or to run a few lines again after changing a variable.\n\nREQUIRES: Program must be stopped, and adapter support (supportsGotoTargetsRequest: debugpy)\n\nWORKFLOW:\n1. Stopped before a line that would raise, e.g. line 12\n2. debugger_jump_to_line({sessionId, sourcePath, line: 13})\n3. Continue or step from line 13; line 12 never ran\n\n⚠️ Skipped code has no effects: variables it would assign keep their old values. Jumping out of the current function, or into a loop or try block, is usually refused by the adapter. Frame ids fetched before the jump are stale afterwards.\n\nRETURNS: {\"state\": \"Stopped\", \"threadId\": 1, \"target\": {\"id\", \"label\", \"line\"}, \"topFrame\": {\"name\", \"source\", \"line\"}}\n\nSEE ALSO: debugger_restart_frame (run the whole function again), debugger_step_over",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "sourcePath": {
+                            "type": "string",
+                            "description": "File of the line to jump to (normally the file of the current frame)"
+                        },
+                        "line": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "description": "Line to continue from (1-indexed)"
+                        },
+                        "threadId": {
+                            "type": "integer",
+                            "description": "Thread to move (default: the thread that stopped)"
+                        }
+                    },
+                    "required": ["sessionId", "sourcePath", "line"]
+                },
+                "annotations": {
+                    "async": false,
+                    "workflow": "execution-control",
+                    "category": "debugging",
+                    "requiresState": ["Stopped"],
+                    "priority": 0.3
+                }
+            }),
+            json!({
+                "name": "debugger_set_function_breakpoints",
+                "title": "Set Function Breakpoints",
+                "description": "Breaks on entry to functions by name, so you don't need to know (or keep up with) exact line numbers.\n\nREPLACES all previously set function breakpoints: pass the full list each time, or an empty list to clear them. Line breakpoints are not affected.\n\nREQUIRES: A started session (use stopOnEntry: true and debugger_wait_for_stop first) and adapter support (supportsFunctionBreakpoints; debugpy and CodeLLDB support it)\n\nNAMES: Use the adapter's naming, e.g. 'fizzbuzz' for Python, 'my_crate::fizzbuzz' or 'fizzbuzz' for Rust\n\nRETURNS: {\"breakpoints\": [{\"type\": \"function\", \"name\": \"fizzbuzz\", \"id\": 1, \"verified\": true}]}\n\nSEE ALSO: debugger_set_breakpoint (line breakpoints), debugger_list_breakpoints",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "breakpoints": {
+                            "type": "array",
+                            "description": "Function breakpoints to set (replaces the current set)",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "name": {
+                                        "type": "string",
+                                        "description": "Function name"
+                                    },
+                                    "condition": {
+                                        "type": "string",
+                                        "description": "Optional expression; only stop when it evaluates to true"
+                                    }
+                                },
+                                "required": ["name"]
+                            }
+                        }
+                    },
+                    "required": ["sessionId", "breakpoints"]
+                }
+            }),
+            json!({
+                "name": "debugger_set_data_breakpoint",
+                "title": "Set Data Breakpoint",
+                "description": "Stops the program when a value is written (or read): the way to find out who mutates a field. The stop reports reason \"data breakpoint\", with the adapter's description of the access and the breakpoint under stop.hitBreakpoints.\n\nTARGET (exactly one):\n- variablesReference + name: a variable or field listed by debugger_get_variables, e.g. {variablesReference: 1001, name: \"count\"}\n- expression: evaluated in frameId (default: top frame), e.g. \"self.balance\" or \"counter.hits\"\n- dataId: an ID returned by an earlier call\n\nAdds to the data breakpoints already set (one on the same value is replaced); clear: true removes them all. A data breakpoint on a local is removed by the adapter when the local goes out of scope.\n\nREQUIRES: A stopped program (variable references are only valid while paused) and adapter support (supportsDataBreakpoints: CodeLLDB for Rust, C and C++). Hardware watchpoints are limited, typically 4 values of up to 8 bytes.\n\nRETURNS: {\"type\": \"data\", \"id\": 1, \"verified\": true, \"dataId\": \"0x7ffe0010/4\", \"description\": \"4 bytes at 0x7ffe0010\", \"accessType\": \"write\", \"condition\": null}\n\nSEE ALSO: debugger_get_variables (variablesReference), debugger_list_breakpoints, debugger_wait_for_stop",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "variablesReference": {
+                            "type": "integer",
+                            "description": "Container of the variable (from debugger_get_variables or debugger_evaluate); use with name"
+                        },
+                        "name": {
+                            "type": "string",
+                            "description": "Name of the variable or field inside variablesReference"
+                        },
+                        "expression": {
+                            "type": "string",
+                            "description": "Expression for the value to watch, instead of variablesReference + name"
+                        },
+                        "frameId": {
+                            "type": "integer",
+                            "description": "Frame the expression is evaluated in (default: top frame of the stopped thread)"
+                        },
+                        "dataId": {
+                            "type": "string",
+                            "description": "dataId returned by an earlier call, instead of a variable or expression"
+                        },
+                        "accessType": {
+                            "type": "string",
+                            "enum": ["read", "write", "readWrite"],
+                            "description": "Which accesses stop the program. Default: the adapter's (write for CodeLLDB)"
+                        },
+                        "condition": {
+                            "type": "string",
+                            "description": "Only stop when this expression is true"
+                        },
+                        "clear": {
+                            "type": "boolean",
+                            "description": "Remove every data breakpoint of the session (other arguments are ignored)"
+                        }
+                    },
+                    "required": ["sessionId"]
+                }
+            }),
+            json!({
+                "name": "debugger_add_watch",
+                "title": "Add Watch Expression",
+                "description": "Adds an expression that is evaluated automatically in the top frame every time the program stops, so you don't have to repeat debugger_evaluate after each step.\n\nResults appear in the 'watches' object of every debugger_wait_for_stop response: {\"expr\": {\"value\": \"...\"}} or {\"expr\": {\"error\": \"...\"}} (null until first evaluated). If the program is already stopped, the new watch is evaluated immediately.\n\nNOTE: Each watch evaluation is limited to 2 seconds; avoid expressions with side effects.\n\nSEE ALSO: debugger_remove_watch, debugger_list_watches, debugger_wait_for_stop",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "expression": {
+                            "type": "string",
+                            "description": "Expression to evaluate at every stop, e.g. 'n' or 'len(results)'"
+                        }
+                    },
+                    "required": ["sessionId", "expression"]
+                }
+            }),
+            json!({
+                "name": "debugger_remove_watch",
+                "title": "Remove Watch Expression",
+                "description": "Removes a watch expression added with debugger_add_watch.\n\nRETURNS: {\"removed\": true|false, \"expression\": \"...\"}",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "expression": {
+                            "type": "string",
+                            "description": "The exact expression text that was added"
+                        }
+                    },
+                    "required": ["sessionId", "expression"]
+                }
+            }),
+            json!({
+                "name": "debugger_list_watches",
+                "title": "List Watch Expressions",
+                "description": "Lists the session's watch expressions with their most recent values (from the last stop).\n\nRETURNS: {\"watches\": {\"expr\": {\"value\": \"...\"} | {\"error\": \"...\"} | null}}",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        }
+                    },
+                    "required": ["sessionId"]
+                }
+            }),
+            json!({
+                "name": "debugger_get_output",
+                "title": "Get Program Output",
+                "description": "Returns output the debug adapter reported for the session: the program's stdout/stderr and messages from logpoints (breakpoints set with logMessage).\n\nUSEFUL FOR: Tracing values with logpoints without pausing the program, and reading what the program printed\n\nNOTE: Only the most recent 1000 output entries are kept per session, and at most maxLength bytes (default 4096) of the newest output are returned. Older output is dropped first: the response then has truncated: true, originalLength and omittedEntries.\n\nRETURNS: {\"count\": n, \"output\": [{\"category\": \"stdout\", \"output\": \"...\"}]}\n\nSEE ALSO: debugger_set_breakpoint (logMessage parameter)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "category": {
+                            "type": "string",
+                            "description": "Optional. Only return output of this category: 'stdout', 'stderr', 'console' (logpoints and adapter messages) or 'important'"
+                        },
+                        "maxLength": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "default": 4096,
+                            "description": "Maximum bytes of output to return, newest first (default: 4096)"
+                        }
+                    },
+                    "required": ["sessionId"]
+                }
+            }),
+            json!({
+                "name": "debugger_session_metrics",
+                "title": "Session Metrics",
+                "description": "Returns performance counters of a debug session: DAP requests sent and events received, how often and how long the program was stopped vs running, how long the adapter took to start and the program to compile, and the latency of every DAP command. Use it to find out why a session feels slow.\n\nRETURNS: {\"sessionId\", \"requestsSent\", \"eventsReceived\", \"stops\", \"timeStoppedMs\", \"timeRunningMs\", \"adapterSpawnMs\": ms|null, \"compileMs\": ms|null, \"commands\": [{\"command\", \"count\", \"failures\", \"meanMs\", \"p95Ms\", \"maxMs\"}], \"writeQueueDepth\"}\n\nwriteQueueDepth counts requests waiting to be written to the adapter. It stays high when the adapter stops reading its input; requests then fail with ADAPTER_NOT_CONSUMING_INPUT.\n\nSEE ALSO: debugger://sessions/{sessionId} resource (includes the same metrics)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        }
+                    },
+                    "required": ["sessionId"]
+                }
+            }),
+            json!({
+                "name": "debugger_server_log_tail",
+                "title": "Server Log Tail",
+                "description": "Returns the most recent lines of the debugger server's own log, kept in memory (the last 2000 lines). Use it to find out why a session failed to start or an adapter misbehaves when the server's stderr is not visible to you.\n\nLines use the server's --log-format: human-readable by default, one JSON object per line with --log-format json (filter those by spans[].session_id). The level follows --log-level; run the server with --log-level debug for adapter traffic.\n\nRETURNS: {\"count\": 2, \"buffered\": 350, \"capacity\": 2000, \"lines\": [\"... INFO debugger_mcp::debug::manager: ...\"]}\n\nSEE ALSO: debugger_dap_log (raw DAP messages of one session), debugger_session_state",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "lines": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "description": "Number of most recent lines to return (default 100)"
+                        }
+                    }
+                }
+            }),
+            json!({
+                "name": "debugger_recording_path",
+                "title": "Session Transcript Path",
+                "description": "Returns where the transcript of a recorded session is written (sessions started with record: true, or every session when the server runs with --record-dir). The transcript holds one JSON line per tool call ({\"type\": \"toolCall\", \"timestampMs\", \"tool\", \"arguments\", \"result\"|\"error\", \"durationMs\"}) and per state change ({\"type\": \"stateChange\", \"timestampMs\", \"from\", \"to\", \"stop\"}). Attach it to bug reports, or re-run it with 'debugger_mcp replay <file>'.\n\nRETURNS: {\"sessionId\", \"recording\": bool, \"path\": string|null}",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        }
+                    },
+                    "required": ["sessionId"]
+                }
+            }),
+            json!({
+                "name": "debugger_dap_log",
+                "title": "DAP Protocol Log",
+                "description": "Returns the Debug Adapter Protocol messages exchanged with the adapter of a session started with captureDapLog: true, oldest first. Use it to troubleshoot a misbehaving session: see exactly which requests were sent, how the adapter answered and which events it reported.\n\nNOTE: The last 1000 messages are kept per session. Bodies over 2 KB are cut off and end with a '[truncated: ...]' note (truncated: true).\n\nRETURNS: {\"sessionId\", \"recorded\": n, \"capacity\": 1000, \"returned\": n, \"entries\": [{\"direction\": \"sent\"|\"received\", \"timestampMs\", \"elapsedMs\", \"type\": \"request\"|\"response\"|\"event\", \"seq\", \"name\", \"requestSeq\", \"success\", \"body\", \"truncated\"}]}\n\nSEE ALSO: debugger://sessions/{sessionId}/dapLog resource (whole log)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sessionId": {
+                            "type": "string",
+                            "description": "Session ID from debugger_start"
+                        },
+                        "tail": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "description": "Optional. Number of most recent messages to return (default 100)"
+                        }
+                    },
+                    "required": ["sessionId"]
+                }
+            }),
+            json!({
+                "name": "debugger_doctor",
+                "title": "Check Debugger Installation",
+                "description": "Checks which languages can be debugged on this machine: for each language it looks for the debug adapter and the tools it needs (debugpy, rdbg, node + vscode-js-debug, dlv, codelldb, rustc/cargo, gcc/clang, bashdb), reports their versions, and gives install hints for anything missing.\n\nCall this before debugger_start when unsure whether a language is set up, or after a session ends up in 'Failed' during initialization.\n\nRETURNS: {\"adapters\": [{\"language\", \"adapter\", \"available\", \"version\", \"checks\": [{\"name\", \"command\", \"ok\", \"detail\"}], \"installHint\"}], \"available\": [languages], \"missing\": [languages]}\n\nTIMING: Runs each tool's --version, typically < 2s",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "language": {
+                            "type": "string",
+                            "description": "Optional. Only check this language (same names as debugger_start)"
+                        }
+                    }
+                }
+            }),
+            json!({
+                "name": "debugger_saved_breakpoints",
+                "title": "Saved Breakpoints",
+                "description": "Lists or clears the breakpoints remembered per program. When a session disconnects, its line breakpoints are saved under the absolute program path; debugger_start with restoreBreakpoints: true applies them again before the program runs.\n\nACTIONS:\n- list (default): {\"programs\": {\"/abs/app.py\": [{\"sourcePath\": \"...\", \"line\": 12}]}}\n- clear: {\"cleared\": n}\n\nNOTE: Saved breakpoints only survive a server restart when the server runs with --breakpoint-store <FILE>.\n\nSEE ALSO: debugger_start (restoreBreakpoints)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "action": {
+                            "type": "string",
+                            "enum": ["list", "clear"],
+                            "description": "'list' (default) or 'clear'"
+                        },
+                        "program": {
+                            "type": "string",
+                            "description": "Optional. Only this program; all programs when omitted"
+                        }
+                    }
+                }
+            }),
+            json!({
+                "name": "debugger_save_config",
+                "title": "Save Start Configuration",
+                "description": "Saves debugger_start arguments under a name so later sessions start with {config: \"<name>\"} instead of repeating them. Saving an existing name replaces it.\n\nEXAMPLE:\n  debugger_save_config({name: \"tests\", config: {language: \"python\", module: \"pytest\", args: [\"-x\", \"tests\"], cwd: \"/workspace\"}})\n  debugger_start({config: \"tests\", args: [\"-x\", \"tests/test_api.py\"]})  // args overridden\n\nEach field is checked like a debugger_start argument; errors name the offending field. Configurations live until the server exits.\n\nRETURNS: {\"name\": \"tests\", \"saved\": true, \"replaced\": false}\n\nSEE ALSO: debugger_list_configs, debugger_start (config)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "name": {
+                            "type": "string",
+                            "description": "Configuration name: letters, digits, '-', '_' and '.'"
+                        },
+                        "config": {
+                            "type": "object",
+                            "description": "debugger_start arguments (language, program, args, cwd, breakpoints, ...)"
+                        }
+                    },
+                    "required": ["name", "config"]
+                }
+            }),
+            json!({
+                "name": "debugger_list_configs",
+                "title": "List Start Configurations",
+                "description": "Lists the named debugger_start configurations: saved with debugger_save_config, read from a project's .debugger-mcp.json, and \"last\" (the previous successful debugger_start).\n\nPROJECT FILE (.debugger-mcp.json, relative program/cwd paths are resolved against its directory):\n  {\"configurations\": {\"tests\": {\"language\": \"python\", \"module\": \"pytest\", \"args\": [\"tests\"]}}}\n\nRETURNS: {\"count\": 1, \"configs\": [{\"name\": \"tests\", \"source\": {\"kind\": \"saved\"}, \"config\": {...}}]}\n\nSEE ALSO: debugger_save_config, debugger_start (config)",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {}
+                }
+            }),
+        ];
+        let run_until_exit = Self::run_until_exit_tool(&tools[0]);
+        tools.push(run_until_exit);
+        tools
+    }
+
+    /// debugger_run_until_exit takes debugger_start's arguments (`start`'s
+    /// schema) plus its own
+    fn run_until_exit_tool(start: &Value) -> Value {
+        let mut schema = start["inputSchema"].clone();
+        if let Some(properties) = schema["properties"].as_object_mut() {
+            for unsupported in ["reuseExisting", "watchMode", "watchPaths"] {
+                properties.remove(unsupported);
+            }
+            properties.insert(
+                "timeoutMs".to_string(),
+                json!({
+                    "type": "integer",
+                    "minimum": 1,
+                    "default": DEFAULT_RUN_TIMEOUT_MS,
+                    "description": "Limit for the whole run, from starting the session to the program's exit (default: 60000). On timeout the program is stopped and what was collected so far is returned with timedOut: true"
+                }),
+            );
+            properties.insert(
+                "includeLocals".to_string(),
+                json!({
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Also record the top frame's variables at every stop, as 'topFrameVariables'"
+                }),
+            );
+            properties.insert(
+                "maxStops".to_string(),
+                json!({
+                    "type": "integer",
+                    "minimum": 0,
+                    "default": DEFAULT_RUN_MAX_STOPS,
+                    "description": "Stops recorded in detail (default: 50). Later stops are still continued and counted in stopCount, and stopsTruncated is true"
+                }),
+            );
+        }
+        json!({
+            "name": "debugger_run_until_exit",
+            "title": "Run Program To Exit",
+            "description": "Runs a program under the debugger from start to exit and returns a summary, for 'run it with these breakpoints; if it crashes, tell me where'. Takes the arguments of debugger_start (breakpoints, args, cwd, ...). Every stop (breakpoint, exception, entry) is recorded with its reason, hit breakpoints and top frame, then the program is continued. When the program exits or timeoutMs passes, the session is ended and removed.\n\nRETURNS: {sessionId, state, exitCode, reason, stderrTail (non-zero exit), stops: [{index, reason, threadId, hitBreakpoints, topFrame, topFrameVariables?}], stopCount, stopsTruncated, output: [{category, output}], timedOut, durationMs}\n\nEXAMPLE:\n  debugger_run_until_exit({language: \"python\", program: \"app.py\", breakpoints: [{sourcePath: \"app.py\", line: 42}], includeLocals: true})\n\nTIP: A breakpoint in a hot loop is hit many times; only the first maxStops stops are recorded. An exception stop is usually the last entry in 'stops'.\n\nSEE ALSO: debugger_start and debugger_wait_for_stop (to stay at a stop and explore)",
+            "inputSchema": schema
+        })
+    }
+}
+
+/// Render the last stop, resolving hit breakpoint IDs to the breakpoints we set
+fn stop_context_to_json(state: &SessionState) -> Value {
+    let Some(stop) = &state.last_stop else {
+        return Value::Null;
+    };
+
+    let hit_breakpoints: Vec<Value> = stop
+        .hit_breakpoint_ids
+        .iter()
+        .map(|&id| {
+            if let Some(bp) = state.breakpoint_by_id(id) {
+                json!({
+                    "type": "line",
+                    "id": id,
+                    "sourcePath": bp.source_path,
+                    "line": bp.line
+                })
+            } else if let Some(bp) = state.function_breakpoint_by_id(id) {
+                json!({
+                    "type": "function",
+                    "id": id,
+                    "name": bp.name
+                })
+            } else if let Some(bp) = state.data_breakpoint_by_id(id) {
+                json!({
+                    "type": "data",
+                    "id": id,
+                    "dataId": bp.data_id,
+                    "description": bp.description
+                })
+            } else {
+                // Not one of ours (e.g. the stopOnEntry workaround breakpoint)
+                json!({ "id": id })
+            }
+        })
+        .collect();
+
+    let mut context = json!({
+        "threadId": stop.thread_id,
+        "reason": stop.reason,
+        "description": stop.description,
+        "text": stop.text,
+        "allThreadsStopped": stop.all_threads_stopped,
+        "hitBreakpointIds": stop.hit_breakpoint_ids,
+        "hitBreakpoints": hit_breakpoints
+    });
+    if let Some(child) = &stop.child_session {
+        context["childSession"] = json!(child);
+    }
+    context
+}
+
+/// Every breakpoint of the session, marking those that caused the current
+/// stop; unverified line breakpoints carry their entry of `diagnoses`
+fn breakpoints_to_json(
+    full_state: &SessionState,
+    diagnoses: &std::collections::HashMap<(String, i32), BreakpointDiagnosis>,
+) -> Vec<Value> {
+    // Breakpoints that caused the current stop, if the program is stopped
+    let hit_ids: &[i32] = match (&full_state.state, &full_state.last_stop) {
+        (crate::debug::state::DebugState::Stopped { .. }, Some(stop)) => &stop.hit_breakpoint_ids,
+        _ => &[],
+    };
+    let is_hit = |id: Option<i32>| id.is_some_and(|id| hit_ids.contains(&id));
+
+    // Collect all breakpoints from all source files
+    let mut all_breakpoints = Vec::new();
+    for (source_path, breakpoints) in full_state.breakpoints.iter() {
+        for bp in breakpoints {
+            let mut entry = json!({
+                "type": "line",
+                "id": bp.id,
+                "verified": bp.verified,
+                "line": bp.requested_line.unwrap_or(bp.line),
+                "sourcePath": source_path,
+                "hit": is_hit(bp.id)
+            });
+            if bp.requested_line.is_some() {
+                entry["adjustedLine"] = json!(bp.line);
+            }
+            if let Some(log_message) = &bp.log_message {
+                entry["logMessage"] = json!(log_message);
+            }
+            if let Some(message) = &bp.message {
+                entry["message"] = json!(message);
+            }
+            if !bp.verified {
+                if let Some(diagnosis) = diagnoses.get(&(bp.source_path.clone(), bp.line)) {
+                    add_diagnosis(&mut entry, diagnosis.clone());
+                }
+            }
+            all_breakpoints.push(entry);
+        }
+    }
+    for bp in &full_state.function_breakpoints {
+        all_breakpoints.push(json!({
+            "type": "function",
+            "id": bp.id,
+            "verified": bp.verified,
+            "name": bp.name,
+            "condition": bp.condition,
+            "hit": is_hit(bp.id)
+        }));
+    }
+    for bp in &full_state.data_breakpoints {
+        let mut entry = data_breakpoint_to_json(bp);
+        entry["hit"] = json!(is_hit(bp.id));
+        all_breakpoints.push(entry);
+    }
+
+    all_breakpoints
+}
+
+pub(crate) fn stack_page_to_json(
+    page: &crate::dap::types::StackTracePage,
+    start_frame: i32,
+    levels: i32,
+) -> Value {
+    let end = start_frame + page.stack_frames.len() as i32;
+    let more = match page.total_frames {
+        Some(total) => end < total,
+        // Unknown depth: a full page may have more after it
+        None => page.stack_frames.len() as i32 == levels,
+    };
+
+    let mut result = json!({
+        "stackFrames": page.stack_frames,
+        "startFrame": start_frame,
+        "totalFrames": page.total_frames
+    });
+    if more {
+        let total = page
+            .total_frames
+            .map_or_else(|| "more".to_string(), |total| total.to_string());
+        result["note"] = json!(format!(
+            "Showing frames {}-{} of {}. Call debugger_stack_trace with startFrame: {} for the next frames",
+            start_frame,
+            end - 1,
+            total,
+            end
+        ));
+    }
+    result
+}
+
+/// Limits of a debugger_snapshot document
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SnapshotOptions {
+    /// Stack frames to include
+    pub levels: i32,
+    /// Frames, from the top, whose variables are included
+    pub variable_frames: usize,
+    /// Last lines of program output to include
+    pub output_lines: usize,
+    /// Bytes of each variable value, and of the output lines together
+    pub max_length: usize,
+}
+
+impl Default for SnapshotOptions {
+    fn default() -> Self {
+        Self {
+            levels: DEFAULT_STOP_STACK_LEVELS as i32,
+            variable_frames: 1,
+            output_lines: 20,
+            max_length: truncate::DEFAULT_MAX_LENGTH,
+        }
+    }
+}
+
+/// Frames whose variables a snapshot may include
+const MAX_SNAPSHOT_VARIABLE_FRAMES: usize = 5;
+
+/// Output lines a snapshot may include
+const MAX_SNAPSHOT_OUTPUT_LINES: usize = 500;
+
+/// Variables listed per scope in a snapshot; the rest are counted in `more`
+const SNAPSHOT_VARIABLES_PER_SCOPE: usize = 50;
+
+/// State, stop, stack, variables, watches, output and breakpoints of a
+/// session in one document (debugger_snapshot and the snapshot resource)
+///
+/// The pieces are fetched independently: a piece that fails is reported as
+/// `{"error": ...}` in its section and the document is marked `partial`.
+pub(crate) async fn snapshot_to_json(
+    session: &crate::debug::DebugSession,
+    options: &SnapshotOptions,
+) -> Value {
+    let stopped = matches!(
+        session.get_state().await,
+        crate::debug::state::DebugState::Stopped { .. }
+    );
+    let stack = async {
+        match stopped {
+            true => Some(
+                session
+                    .stack_trace_page(None, None, Some(options.levels))
+                    .await,
+            ),
+            false => None,
+        }
+    };
+    let (page, watches) = tokio::join!(stack, session.refresh_watches());
+    let full_state = session.get_full_state().await;
+    let (state, details) = (full_state.state.name(), full_state.state.details());
+    let mut partial = false;
+
+    let (stack_trace, variables) = match page {
+        None => (Value::Null, json!([])),
+        Some(Err(e)) => {
+            partial = true;
+            (json!({ "error": e.to_string() }), json!([]))
+        }
+        Some(Ok(page)) => {
+            let mut frames = Vec::new();
+            for frame in page.stack_frames.iter().take(options.variable_frames) {
+                let (entry, failed) = snapshot_frame_variables(session, frame, options).await;
+                partial |= failed;
+                frames.push(entry);
+            }
+            (
+                json!({
+                    "stackFrames": page.stack_frames,
+                    "totalFrames": page.total_frames
+                }),
+                json!(frames),
+            )
+        }
+    };
+
+    let mut document = json!({
+        "sessionId": session.id,
+        "language": session.language,
+        "program": session.program,
+        "state": state,
+        "details": details,
+        "stop": if stopped { stop_context_to_json(&full_state) } else { Value::Null },
+        "stackTrace": stack_trace,
+        "variables": variables,
+        "watches": watches_to_json(&watches),
+        "output": snapshot_output(&full_state.output, options),
+        "breakpoints": breakpoints_to_json(&full_state, &Default::default()),
+        "diagnostics": full_state.diagnostics()
+    });
+    if partial {
+        document["partial"] = json!(true);
+    }
+    document
+}
+
+/// Variables of the cheap scopes of `frame`; true when a request failed
+async fn snapshot_frame_variables(
+    session: &crate::debug::DebugSession,
+    frame: &crate::dap::types::StackFrame,
+    options: &SnapshotOptions,
+) -> (Value, bool) {
+    let mut entry = json!({ "frameId": frame.id, "name": frame.name });
+    let scopes = match session.scopes(frame.id).await {
+        Ok(scopes) => scopes,
+        Err(e) => {
+            entry["error"] = json!(e.to_string());
+            return (entry, true);
+        }
+    };
+
+    let mut failed = false;
+    let mut rendered = Vec::new();
+    for scope in scopes {
+        // Globals and registers can be huge; expand them with debugger_get_variables
+        if scope.expensive {
+            rendered.push(json!({
+                "scope": scope.name,
+                "expensive": true,
+                "variablesReference": scope.variables_reference
+            }));
+            continue;
+        }
+        match session.variables(scope.variables_reference).await {
+            Ok(vars) => {
+                let listed: Vec<Value> = vars
+                    .iter()
+                    .take(SNAPSHOT_VARIABLES_PER_SCOPE)
+                    .map(|var| {
+                        let mut value = json!(var);
+                        truncate::truncate_field(&mut value, "value", options.max_length);
+                        value
+                    })
+                    .collect();
+                let mut scope_entry = json!({ "scope": scope.name, "variables": listed });
+                if vars.len() > SNAPSHOT_VARIABLES_PER_SCOPE {
+                    scope_entry["more"] = json!(vars.len() - SNAPSHOT_VARIABLES_PER_SCOPE);
+                    scope_entry["variablesReference"] = json!(scope.variables_reference);
+                }
+                rendered.push(scope_entry);
+            }
+            Err(e) => {
+                failed = true;
+                rendered.push(json!({ "scope": scope.name, "error": e.to_string() }));
+            }
+        }
+    }
+    entry["scopes"] = json!(rendered);
+    (entry, failed)
+}
+
+/// The last `output_lines` lines of program output, within `max_length` bytes
+fn snapshot_output(
+    output: &std::collections::VecDeque<OutputEntry>,
+    options: &SnapshotOptions,
+) -> Value {
+    let text: String = output.iter().map(|entry| entry.output.as_str()).collect();
+    let lines: Vec<&str> = text.lines().collect();
+    let recent = &lines[lines.len().saturating_sub(options.output_lines)..];
+    let kept = truncate::newest_within(recent, options.max_length);
+
+    let mut result = json!({ "lines": kept, "totalLines": lines.len() });
+    let length = |texts: &[&str]| texts.iter().map(|text| text.len()).sum::<usize>();
+    if length(&kept) < length(recent) {
+        result["truncated"] = json!(true);
+    }
+    result
+}
+
+/// Where a `waitForStop` continue or step stopped
+fn top_frame_to_json(frame: &crate::dap::types::StackFrame) -> Value {
+    let source = frame
+        .source
+        .as_ref()
+        .and_then(|source| source.path.as_ref().or(source.name.as_ref()));
+    json!({
+        "name": frame.name,
+        "source": source,
+        "line": frame.line
+    })
+}
+
+fn data_breakpoint_to_json(bp: &DataBreakpointInfo) -> Value {
+    json!({
+        "type": "data",
+        "id": bp.id,
+        "verified": bp.verified,
+        "dataId": bp.data_id,
+        "description": bp.description,
+        "accessType": bp.access_type,
+        "condition": bp.condition
+    })
+}
+
+/// Render watches as `{expression: {"value": ...} | {"error": ...} | null}`
+fn watches_to_json(watches: &[Watch]) -> Value {
+    let map: serde_json::Map<String, Value> = watches
+        .iter()
+        .map(|w| (w.expression.clone(), json!(w.last)))
+        .collect();
+    Value::Object(map)
+}
+
+const SOURCE_CHANGED_WARNING: &str = "Source files changed since the session started: the program still runs the old code, so lines and breakpoints may not match the files. Restart the session (debugger_disconnect, then debugger_start) to debug the current code.";
+
+/// Flag a response with `sourceChanged` when files edited since the session
+/// started are listed in `changed`
+fn add_source_changes(response: &mut Value, changed: Vec<String>) {
+    if changed.is_empty() {
+        return;
+    }
+    response["sourceChanged"] = json!(true);
+    response["changedSources"] = json!(changed);
+    response["warning"] = json!(SOURCE_CHANGED_WARNING);
+}
+
+/// Explain an unverified breakpoint with `hint` (and `suggestedSourcePath`)
+fn add_diagnosis(entry: &mut Value, diagnosis: BreakpointDiagnosis) {
+    entry["hint"] = json!(diagnosis.hint);
+    if let Some(path) = diagnosis.suggested_source_path {
+        entry["suggestedSourcePath"] = json!(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::debug::SessionManager;
+
+    #[test]
+    fn test_debugger_start_args_deserialization() {
+        let json = json!({
+            "language": "python",
+            "program": "/path/to/script.py",
+            "args": ["arg1", "arg2"],
+            "cwd": "/working/dir"
+        });
+
+        let args: DebuggerStartArgs = serde_json::from_value(json).unwrap();
+        assert_eq!(args.language, "python");
+        assert_eq!(args.program, "/path/to/script.py");
+        assert_eq!(args.args.len(), 2);
+        assert_eq!(args.cwd, Some("/working/dir".to_string()));
+    }
+
+    #[test]
+    fn test_debugger_start_args_without_cwd() {
+        let json = json!({
+            "language": "python",
+            "program": "test.py",
+            "args": []
+        });
+
+        let args: DebuggerStartArgs = serde_json::from_value(json).unwrap();
+        assert!(args.cwd.is_none());
+        assert!(args.args.is_empty());
+        assert!(args.request_timeout_ms.is_none());
+    }
+
+    #[test]
+    fn test_debugger_start_args_with_request_timeout() {
+        let json = json!({
+            "language": "python",
+            "program": "test.py",
+            "requestTimeoutMs": 30000
+        });
+
+        let args: DebuggerStartArgs = serde_json::from_value(json).unwrap();
+        assert_eq!(args.request_timeout_ms, Some(30000));
+    }
+
+    #[test]
+    fn test_debugger_start_args_with_entry_line() {
+        let json = json!({
+            "language": "ruby",
+            "program": "app.rb",
+            "stopOnEntry": true,
+            "entryLine": 12
+        });
+
+        let args: DebuggerStartArgs = serde_json::from_value(json).unwrap();
+        assert_eq!(args.entry_line, Some(12));
+        assert!(args.breakpoints.is_empty());
+    }
+
+    #[test]
+    fn test_debugger_start_args_with_breakpoints() {
+        let json = json!({
+            "language": "python",
+            "program": "app.py",
+            "breakpoints": [
+                {"sourcePath": "/app/app.py", "line": 10},
+                {"sourcePath": "/app/lib.py", "line": 3, "condition": "n > 5"}
+            ]
+        });
+
+        let args: DebuggerStartArgs = serde_json::from_value(json).unwrap();
+        assert_eq!(args.breakpoints.len(), 2);
+        assert_eq!(args.breakpoints[0].source_path, "/app/app.py");
+        assert_eq!(args.breakpoints[0].line, 10);
+        assert!(args.breakpoints[0].condition.is_none());
+        assert_eq!(args.breakpoints[1].condition, Some("n > 5".to_string()));
+    }
+
+    #[test]
+    fn test_cargo_target_options() {
+        let parse = |value: Value| {
+            let args: DebuggerStartArgs = serde_json::from_value(value).unwrap();
+            ToolsHandler::cargo_target_options(&args)
+        };
+
+        let (target, filter) = parse(json!({
+            "language": "rust",
+            "program": "/app/tests/describe.rs",
+            "testFilter": "test_describe"
+        }))
+        .unwrap();
+        assert_eq!(target, Some(CargoTargetType::Test));
+        assert_eq!(filter.as_deref(), Some("test_describe"));
+
+        let (target, _) = parse(json!({
+            "language": "rust",
+            "program": "/app/src/main.rs",
+            "cargoTarget": "example:demo"
+        }))
+        .unwrap();
+        assert_eq!(target, Some(CargoTargetType::Example("demo".to_string())));
+
+        for invalid in [
+            json!({"language": "python", "program": "app.py", "cargoTarget": "test"}),
+            json!({"language": "rust", "program": "main.rs", "cargoTarget": "bench"}),
+            json!({"language": "rust", "program": "main.rs", "cargoTarget": "binary", "testFilter": "t"}),
+            json!({"language": "rust", "program": "main.rs", "testFilter": " "}),
+        ] {
+            assert!(
+                matches!(parse(invalid.clone()), Err(Error::InvalidRequest(_))),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_set_breakpoint_args_deserialization() {
+        let json = json!({
+            "sessionId": "session-123",
+            "sourcePath": "/path/to/file.py",
+            "line": 42
+        });
+
+        let args: SetBreakpointArgs = serde_json::from_value(json).unwrap();
+        assert_eq!(args.session_id, "session-123");
+        assert_eq!(args.source_path, "/path/to/file.py");
+        assert_eq!(args.line, 42);
+        assert!(args.column.is_none());
+        assert!(args.log_message.is_none());
+    }
+
+    #[test]
+    fn test_set_breakpoint_args_with_column() {
+        let json = json!({
+            "sessionId": "session-123",
+            "sourcePath": "/path/to/bundle.min.js",
+            "line": 1,
+            "column": 2048
+        });
+
+        let args: SetBreakpointArgs = serde_json::from_value(json).unwrap();
+        assert_eq!(args.column, Some(2048));
+    }
+
+    #[test]
+    fn test_set_breakpoint_args_with_log_message() {
+        let json = json!({
+            "sessionId": "session-123",
+            "sourcePath": "/path/to/fizzbuzz.py",
+            "line": 8,
+            "logMessage": "n = {n}"
+        });
+
+        let args: SetBreakpointArgs = serde_json::from_value(json).unwrap();
+        assert_eq!(args.log_message, Some("n = {n}".to_string()));
+    }
+
+    #[test]
+    fn test_continue_args_deserialization() {
+        let json = json!({"sessionId": "test-session"});
+        let args: ContinueArgs = serde_json::from_value(json).unwrap();
+        assert_eq!(args.session_id, "test-session");
+    }
+
+    #[test]
+    fn test_stack_trace_args_deserialization() {
+        let json = json!({"sessionId": "trace-session"});
+        let args: StackTraceArgs = serde_json::from_value(json).unwrap();
+        assert_eq!(args.session_id, "trace-session");
+    }
+
+    #[test]
+    fn test_evaluate_args_deserialization() {
+        let json = json!({
+            "sessionId": "eval-session",
+            "expression": "x + y",
+            "frameId": 5
+        });
+
+        let args: EvaluateArgs = serde_json::from_value(json).unwrap();
+        assert_eq!(args.session_id, "eval-session");
+        assert_eq!(args.expression, "x + y");
+        assert_eq!(args.frame_id, Some(5));
+    }
+
+    #[test]
+    fn test_evaluate_args_without_frame_id() {
+        let json = json!({
+            "sessionId": "eval-session",
+            "expression": "x + y"
+        });
+
+        let args: EvaluateArgs = serde_json::from_value(json).unwrap();
+        assert!(args.frame_id.is_none());
+    }
+
+    #[test]
+    fn test_disconnect_args_deserialization() {
+        let json = json!({"sessionId": "disconnect-session"});
+        let args: DisconnectArgs = serde_json::from_value(json).unwrap();
+        assert_eq!(args.session_id, "disconnect-session");
+    }
+
+    #[test]
+    fn test_list_tools() {
+        let tools = ToolsHandler::list_tools();
+        assert_eq!(tools.len(), 44);
+
+        // Verify tool names
+        let tool_names: Vec<&str> = tools.iter().filter_map(|t| t["name"].as_str()).collect();
+
+        // Original tools
+        assert!(tool_names.contains(&"debugger_start"));
+        assert!(tool_names.contains(&"debugger_session_state"));
+        assert!(tool_names.contains(&"debugger_snapshot"));
+        assert!(tool_names.contains(&"debugger_set_breakpoint"));
+        assert!(tool_names.contains(&"debugger_continue"));
+        assert!(tool_names.contains(&"debugger_stack_trace"));
+        assert!(tool_names.contains(&"debugger_evaluate"));
+        assert!(tool_names.contains(&"debugger_get_variables"));
+        assert!(tool_names.contains(&"debugger_saved_breakpoints"));
+        assert!(tool_names.contains(&"debugger_attach"));
+        assert!(tool_names.contains(&"debugger_doctor"));
+        assert!(tool_names.contains(&"debugger_dap_log"));
+        assert!(tool_names.contains(&"debugger_session_metrics"));
+        assert!(tool_names.contains(&"debugger_server_log_tail"));
+        assert!(tool_names.contains(&"debugger_recording_path"));
+        assert!(tool_names.contains(&"debugger_set_breakpoints"));
+        assert!(tool_names.contains(&"debugger_breakpoint_locations"));
+        assert!(tool_names.contains(&"debugger_disconnect"));
+        assert!(tool_names.contains(&"debugger_force_kill"));
+
+        // New tools
+        assert!(tool_names.contains(&"debugger_wait_for_stop"));
+        assert!(tool_names.contains(&"debugger_list_breakpoints"));
+        assert!(tool_names.contains(&"debugger_step_over"));
+        assert!(tool_names.contains(&"debugger_step_into"));
+        assert!(tool_names.contains(&"debugger_step_in_targets"));
+        assert!(tool_names.contains(&"debugger_restart_frame"));
+        assert!(tool_names.contains(&"debugger_jump_to_line"));
+        assert!(tool_names.contains(&"debugger_step_out"));
+        assert!(tool_names.contains(&"debugger_get_output"));
+        assert!(tool_names.contains(&"debugger_set_function_breakpoints"));
+        assert!(tool_names.contains(&"debugger_set_data_breakpoint"));
+        assert!(tool_names.contains(&"debugger_add_watch"));
+        assert!(tool_names.contains(&"debugger_remove_watch"));
+        assert!(tool_names.contains(&"debugger_list_watches"));
+        assert!(tool_names.contains(&"debugger_read_memory"));
+        assert!(tool_names.contains(&"debugger_registers"));
+        assert!(tool_names.contains(&"debugger_disassemble"));
+        assert!(tool_names.contains(&"debugger_completions"));
+        assert!(tool_names.contains(&"debugger_loaded_sources"));
+        assert!(tool_names.contains(&"debugger_modules"));
+        assert!(tool_names.contains(&"debugger_check_sources"));
+        assert!(tool_names.contains(&"debugger_watch_status"));
+        assert!(tool_names.contains(&"debugger_save_config"));
+        assert!(tool_names.contains(&"debugger_list_configs"));
+    }
+
+    #[test]
+    fn test_stop_context_to_json_resolves_hit_breakpoints() {
+        use crate::debug::StopContext;
+
+        let mut state = SessionState::new();
+        assert_eq!(stop_context_to_json(&state), Value::Null);
+
+        state.add_breakpoint("/app/a.py".to_string(), 10);
+        state.add_breakpoint("/app/a.py".to_string(), 11);
+        state.update_breakpoint("/app/a.py", 10, 1, true);
+        state.update_breakpoint("/app/a.py", 11, 2, true);
+        state.record_stop(StopContext::from_event_body(&json!({
+            "reason": "breakpoint",
+            "threadId": 1,
+            "hitBreakpointIds": [2, 42]
+        })));
+
+        let stop = stop_context_to_json(&state);
+        assert_eq!(stop["reason"], "breakpoint");
+        assert_eq!(stop["hitBreakpointIds"], json!([2, 42]));
+        assert_eq!(
+            stop["hitBreakpoints"],
+            json!([
+                {"type": "line", "id": 2, "sourcePath": "/app/a.py", "line": 11},
+                {"id": 42}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_watches_to_json() {
+        use crate::debug::WatchValue;
+
+        let watches = vec![
+            Watch {
+                expression: "n".to_string(),
+                last: Some(WatchValue::Value("15".to_string())),
+            },
+            Watch {
+                expression: "bogus".to_string(),
+                last: Some(WatchValue::Error("NameError".to_string())),
+            },
+            Watch {
+                expression: "fresh".to_string(),
+                last: None,
+            },
+        ];
+
+        assert_eq!(
+            watches_to_json(&watches),
+            json!({
+                "n": {"value": "15"},
+                "bogus": {"error": "NameError"},
+                "fresh": null
+            })
+        );
+    }
+
+    #[test]
+    fn test_set_function_breakpoints_args_deserialization() {
+        let json = json!({
+            "sessionId": "session-123",
+            "breakpoints": [
+                {"name": "fizzbuzz"},
+                {"name": "main", "condition": "len(sys.argv) > 1"}
+            ]
+        });
+
+        let args: SetFunctionBreakpointsArgs = serde_json::from_value(json).unwrap();
+        assert_eq!(args.breakpoints.len(), 2);
+        assert_eq!(args.breakpoints[0].name, "fizzbuzz");
+        assert!(args.breakpoints[0].condition.is_none());
+        assert_eq!(
+            args.breakpoints[1].condition,
+            Some("len(sys.argv) > 1".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_function_breakpoints_rejects_empty_name() {
+        let manager = Arc::new(SessionManager::new());
+        let handler = ToolsHandler::new(manager);
+
+        let result = handler
+            .handle_tool(
+                "debugger_set_function_breakpoints",
+                json!({"sessionId": "any", "breakpoints": [{"name": " "}]}),
+            )
+            .await;
+        assert!(matches!(result, Err(Error::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_list_tools_schema_validation() {
+        let tools = ToolsHandler::list_tools();
+
+        // Check first tool structure
+        let start_tool = &tools[0];
+        assert_eq!(start_tool["name"], "debugger_start");
+        assert!(start_tool["description"].is_string());
+        assert!(start_tool["inputSchema"]["type"].is_string());
+        assert!(start_tool["inputSchema"]["properties"].is_object());
+        assert!(start_tool["inputSchema"]["required"].is_array());
+    }
+
+    #[tokio::test]
+    async fn test_tools_handler_new() {
+        let manager = Arc::new(SessionManager::new());
+        let _handler = ToolsHandler::new(manager);
+        // Verify list_tools returns expected tools
+        let tools = ToolsHandler::list_tools();
+        assert!(tools.iter().any(|t| t["name"] == "debugger_start"));
+    }
+
+    #[tokio::test]
+    async fn test_handle_tool_unknown_method() {
+        let manager = Arc::new(SessionManager::new());
+        let handler = ToolsHandler::new(manager);
+
+        let result = handler.handle_tool("unknown_tool", json!({})).await;
+        assert!(result.is_err());
+
+        match result {
+            Err(Error::MethodNotFound(name)) => {
+                assert_eq!(name, "unknown_tool");
+            }
+            _ => panic!("Expected MethodNotFound error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handle_tool_invalid_arguments() {
+        let manager = Arc::new(SessionManager::new());
+        let handler = ToolsHandler::new(manager);
+
+        // Invalid JSON for debugger_start
+        let result = handler
+            .handle_tool("debugger_start", json!({"invalid": "data"}))
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_variables_rejects_scalar_reference() {
+        let manager = Arc::new(SessionManager::new());
+        let handler = ToolsHandler::new(manager);
+
+        let result = handler
+            .handle_tool(
+                "debugger_get_variables",
+                json!({"sessionId": "any", "variablesReference": 0}),
+            )
+            .await;
+
+        assert!(matches!(result, Err(Error::InvalidRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_memory_tools_reject_out_of_range_counts() {
+        let manager = Arc::new(SessionManager::new());
+        let handler = ToolsHandler::new(manager);
+
+        for count in [0, crate::debug::memory::MAX_READ_BYTES + 1] {
+            let result = handler
+                .handle_tool(
+                    "debugger_read_memory",
+                    json!({"sessionId": "any", "memoryReference": "0x1000", "count": count}),
+                )
+                .await;
+            assert!(matches!(result, Err(Error::InvalidRequest(_))));
+        }
+
+        let result = handler
+            .handle_tool(
+                "debugger_disassemble",
+                json!({"sessionId": "any", "memoryReference": "0x1000", "instructionCount": 0}),
+            )
+            .await;
+        assert!(matches!(result, Err(Error::InvalidRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_set_breakpoints_rejects_empty_and_invalid_lines() {
+        let manager = Arc::new(SessionManager::new());
+        let handler = ToolsHandler::new(manager);
+
+        let empty = handler
+            .handle_tool(
+                "debugger_set_breakpoints",
+                json!({"sessionId": "any", "breakpoints": []}),
+            )
+            .await;
+        assert!(matches!(empty, Err(Error::InvalidRequest(_))));
+
+        let zero = handler
+            .handle_tool(
+                "debugger_set_breakpoints",
+                json!({"sessionId": "any", "breakpoints": [{"sourcePath": "/tmp/a.py", "line": 0}]}),
+            )
+            .await;
+        assert!(matches!(zero, Err(Error::InvalidRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_paths_outside_allowed_paths_are_rejected() {
+        use crate::adapters::security::AllowedPaths;
+
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let source = outside.path().join("app.py");
+        std::fs::write(&source, "print(1)\n").unwrap();
+        let source = source.to_string_lossy().to_string();
+        let manager = SessionManager::new()
+            .with_allowed_paths(AllowedPaths::new([root.path().to_path_buf()]).unwrap());
+        let handler = ToolsHandler::new(Arc::new(manager));
+
+        let calls = [
+            (
+                "debugger_start",
+                json!({"language": "python", "program": source}),
+            ),
+            (
+                "debugger_set_breakpoint",
+                json!({"sessionId": "any", "sourcePath": source, "line": 1}),
+            ),
+            (
+                "debugger_set_breakpoints",
+                json!({"sessionId": "any", "breakpoints": [{"sourcePath": source, "line": 1}]}),
+            ),
+        ];
+        for (tool, arguments) in calls {
+            let result = handler.handle_tool(tool, arguments).await;
+            assert!(
+                matches!(result, Err(Error::PathNotAllowed(_))),
+                "{}: {:?}",
+                tool,
+                result
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_watch_path_outside_allowed_paths_starts_nothing() {
+        use crate::adapters::security::AllowedPaths;
+
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let program = root.path().join("app.py");
+        std::fs::write(&program, "print(1)\n").unwrap();
+        let watched = outside.path().join("config.toml");
+        std::fs::write(&watched, "").unwrap();
+        let manager = Arc::new(
+            SessionManager::new()
+                .with_allowed_paths(AllowedPaths::new([root.path().to_path_buf()]).unwrap()),
+        );
+        let handler = ToolsHandler::new(manager.clone());
+
+        let result = handler
+            .handle_tool(
+                "debugger_start",
+                json!({
+                    "language": "python",
+                    "program": program.to_string_lossy(),
+                    "watchMode": true,
+                    "watchPaths": [watched.to_string_lossy()]
+                }),
+            )
+            .await;
+
+        assert!(
+            matches!(result, Err(Error::PathNotAllowed(_))),
+            "{:?}",
+            result
+        );
+        assert!(manager.list_sessions().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_breakpoint_locations_rejects_inverted_range() {
+        let manager = Arc::new(SessionManager::new());
+        let handler = ToolsHandler::new(manager);
+
+        let result = handler
+            .handle_tool(
+                "debugger_breakpoint_locations",
+                json!({"sessionId": "any", "sourcePath": "/tmp/a.js", "line": 5, "endLine": 2}),
+            )
+            .await;
+
+        assert!(matches!(result, Err(Error::InvalidRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_saved_breakpoints_list_and_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let program = dir.path().join("app.py");
+        std::fs::write(&program, "x = 1\n").unwrap();
+        let program = program.to_str().unwrap().to_string();
+
+        let mut store = BreakpointStore::in_memory();
+        store
+            .save(
+                &program,
+                vec![crate::debug::SavedBreakpoint {
+                    source_path: program.clone(),
+                    line: 1,
+                    condition: None,
+                    log_message: None,
+                }],
+            )
+            .unwrap();
+        let manager = Arc::new(SessionManager::with_breakpoint_store(store));
+        let handler = ToolsHandler::new(manager);
+
+        let listed = handler
+            .handle_tool("debugger_saved_breakpoints", json!({"program": program}))
+            .await
+            .unwrap();
+        let key = BreakpointStore::program_key(&program);
+        assert_eq!(listed["programs"][&key][0]["line"], 1);
+
+        let cleared = handler
+            .handle_tool("debugger_saved_breakpoints", json!({"action": "clear"}))
+            .await
+            .unwrap();
+        assert_eq!(cleared["cleared"], 1);
+
+        let listed = handler
+            .handle_tool("debugger_saved_breakpoints", json!({}))
+            .await
+            .unwrap();
+        assert_eq!(listed["programs"], json!({}));
+
+        let bad = handler
+            .handle_tool("debugger_saved_breakpoints", json!({"action": "purge"}))
+            .await;
+        assert!(matches!(bad, Err(Error::InvalidRequest(_))));
+    }
+
+    // Phase 6: Error path tests for missing required fields and invalid types
+
+    #[test]
+    fn test_debugger_start_missing_language() {
+        let json = json!({
+            "program": "/path/to/script.py"
+        });
+
+        let result = serde_json::from_value::<DebuggerStartArgs>(json);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_debugger_start_missing_program() {
+        let json = json!({
+            "language": "python"
+        });
+
+        // Optional for module mode; debugger_start rejects it otherwise
+        let args = serde_json::from_value::<DebuggerStartArgs>(json).unwrap();
+        assert!(args.program.is_empty());
+    }
+
+    #[test]
+    fn test_debugger_start_invalid_args_type() {
+        let json = json!({
+            "language": "python",
+            "program": "test.py",
+            "args": "not an array"  // Should be array, not string
+        });
+
+        let result = serde_json::from_value::<DebuggerStartArgs>(json);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_set_breakpoint_missing_session_id() {
+        let json = json!({
+            "sourcePath": "/path/to/file.py",
+            "line": 42
+        });
+
+        let result = serde_json::from_value::<SetBreakpointArgs>(json);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_set_breakpoint_missing_source_path() {
+        let json = json!({
+            "sessionId": "session-123",
+            "line": 42
+        });
+
+        let result = serde_json::from_value::<SetBreakpointArgs>(json);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_set_breakpoint_missing_line() {
+        let json = json!({
+            "sessionId": "session-123",
+            "sourcePath": "/path/to/file.py"
+        });
+
+        let result = serde_json::from_value::<SetBreakpointArgs>(json);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_set_breakpoint_invalid_line_type() {
+        let json = json!({
+            "sessionId": "session-123",
+            "sourcePath": "/path/to/file.py",
+            "line": "not a number"  // Should be integer
+        });
+
+        let result = serde_json::from_value::<SetBreakpointArgs>(json);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_continue_args_missing_session_id() {
+        let json = json!({});
+
+        let result = serde_json::from_value::<ContinueArgs>(json);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_stack_trace_args_missing_session_id() {
+        let json = json!({});
+
+        let result = serde_json::from_value::<StackTraceArgs>(json);
+        assert!(result.is_err());
+    }
 
     #[test]
-    fn test_debugger_start_args_deserialization() {
+    fn test_evaluate_missing_session_id() {
         let json = json!({
-            "language": "python",
-            "program": "/path/to/script.py",
-            "args": ["arg1", "arg2"],
-            "cwd": "/working/dir"
+            "expression": "x + y"
         });
 
-        let args: DebuggerStartArgs = serde_json::from_value(json).unwrap();
-        assert_eq!(args.language, "python");
-        assert_eq!(args.program, "/path/to/script.py");
-        assert_eq!(args.args.len(), 2);
-        assert_eq!(args.cwd, Some("/working/dir".to_string()));
+        let result = serde_json::from_value::<EvaluateArgs>(json);
+        assert!(result.is_err());
     }
 
     #[test]
-    fn test_debugger_start_args_without_cwd() {
+    fn test_evaluate_missing_expression() {
         let json = json!({
-            "language": "python",
-            "program": "test.py",
-            "args": []
+            "sessionId": "eval-session"
         });
 
-        let args: DebuggerStartArgs = serde_json::from_value(json).unwrap();
-        assert!(args.cwd.is_none());
-        assert!(args.args.is_empty());
+        let result = serde_json::from_value::<EvaluateArgs>(json);
+        assert!(result.is_err());
     }
 
     #[test]
-    fn test_set_breakpoint_args_deserialization() {
+    fn test_evaluate_context_resolution() {
+        let args = |extra: Value| {
+            let mut json = json!({"sessionId": "s", "expression": "x"});
+            json.as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().unwrap().clone());
+            serde_json::from_value::<EvaluateArgs>(json).unwrap()
+        };
+
+        assert_eq!(args(json!({})).resolve_context().unwrap(), "watch");
+        assert_eq!(
+            args(json!({"context": "hover"})).resolve_context().unwrap(),
+            "hover"
+        );
+        assert_eq!(
+            args(json!({"allowSideEffects": true}))
+                .resolve_context()
+                .unwrap(),
+            "repl"
+        );
+        assert_eq!(
+            args(json!({"allowSideEffects": false}))
+                .resolve_context()
+                .unwrap(),
+            "watch"
+        );
+        assert!(matches!(
+            args(json!({"context": "console"})).resolve_context(),
+            Err(Error::InvalidRequest(_))
+        ));
+        assert!(matches!(
+            args(json!({"context": "repl", "allowSideEffects": false})).resolve_context(),
+            Err(Error::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_evaluate_invalid_frame_id_type() {
         let json = json!({
-            "sessionId": "session-123",
-            "sourcePath": "/path/to/file.py",
-            "line": 42
+            "sessionId": "eval-session",
+            "expression": "x + y",
+            "frameId": "not a number"  // Should be integer
         });
 
-        let args: SetBreakpointArgs = serde_json::from_value(json).unwrap();
-        assert_eq!(args.session_id, "session-123");
-        assert_eq!(args.source_path, "/path/to/file.py");
-        assert_eq!(args.line, 42);
+        let result = serde_json::from_value::<EvaluateArgs>(json);
+        assert!(result.is_err());
     }
 
     #[test]
-    fn test_continue_args_deserialization() {
-        let json = json!({"sessionId": "test-session"});
-        let args: ContinueArgs = serde_json::from_value(json).unwrap();
-        assert_eq!(args.session_id, "test-session");
+    fn test_disconnect_missing_session_id() {
+        let json = json!({});
+
+        let result = serde_json::from_value::<DisconnectArgs>(json);
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_handle_tool_debugger_start_invalid_json() {
+        let manager = Arc::new(SessionManager::new());
+        let handler = ToolsHandler::new(manager);
+
+        // Missing required fields
+        let result = handler
+            .handle_tool("debugger_start", json!({"language": "python"}))
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_debugger_start_validates_program_and_cwd() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("app.py");
+        std::fs::write(&script, "print('hi')\n").unwrap();
+        let script = script.to_str().unwrap().to_string();
+        let missing = dir.path().join("ap.py").to_str().unwrap().to_string();
+        let dir_path = dir.path().to_str().unwrap().to_string();
+
+        let manager = Arc::new(SessionManager::new());
+        let handler = ToolsHandler::new(manager);
+
+        let cases = [
+            (
+                json!({"language": "python", "program": missing}),
+                format!("program not found: {}", missing),
+            ),
+            (
+                json!({"language": "python", "program": dir_path}),
+                format!("program is a directory, not a file: {}", dir_path),
+            ),
+            (
+                json!({"language": "ruby", "program": script}),
+                format!("program must be a .rb file for ruby: {}", script),
+            ),
+            (
+                json!({"language": "rust", "program": missing.replace(".py", ".rs")}),
+                format!("program not found: {}", missing.replace(".py", ".rs")),
+            ),
+            (
+                json!({"language": "python", "program": script, "cwd": format!("{}/nope", dir_path)}),
+                format!("cwd not found: {}/nope", dir_path),
+            ),
+            (
+                json!({"language": "python", "program": script, "cwd": script}),
+                format!("cwd is not a directory: {}", script),
+            ),
+            (
+                json!({"language": "python", "program": script, "adapterTimeoutMs": 0}),
+                "adapterTimeoutMs must be greater than 0".to_string(),
+            ),
+        ];
+
+        for (arguments, expected) in cases {
+            match handler.handle_tool("debugger_start", arguments).await {
+                Err(Error::InvalidRequest(msg)) => assert_eq!(msg, expected),
+                other => panic!("Expected InvalidRequest({}), got {:?}", expected, other),
+            }
+        }
     }
 
     #[test]
-    fn test_stack_trace_args_deserialization() {
-        let json = json!({"sessionId": "trace-session"});
-        let args: StackTraceArgs = serde_json::from_value(json).unwrap();
-        assert_eq!(args.session_id, "trace-session");
+    fn test_validate_launch_target_accepts_go_package_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().to_str().unwrap();
+
+        let (program, cwd) =
+            ToolsHandler::validate_launch_target("go", dir_path, Some(dir_path)).unwrap();
+
+        let canonical = dir.path().canonicalize().unwrap();
+        assert_eq!(program, canonical.to_str().unwrap());
+        assert_eq!(cwd.as_deref(), canonical.to_str());
     }
 
     #[test]
-    fn test_evaluate_args_deserialization() {
-        let json = json!({
-            "sessionId": "eval-session",
-            "expression": "x + y",
-            "frameId": 5
-        });
+    fn test_validate_launch_target_resolves_program_against_cwd() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("app.py"), "print(1)\n").unwrap();
 
-        let args: EvaluateArgs = serde_json::from_value(json).unwrap();
-        assert_eq!(args.session_id, "eval-session");
-        assert_eq!(args.expression, "x + y");
-        assert_eq!(args.frame_id, Some(5));
+        let (program, cwd) =
+            ToolsHandler::validate_launch_target("python", "app.py", dir.path().to_str()).unwrap();
+
+        let canonical = dir.path().canonicalize().unwrap();
+        assert_eq!(program, canonical.join("app.py").to_str().unwrap());
+        assert_eq!(cwd.as_deref(), canonical.to_str());
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_evaluate_args_without_frame_id() {
-        let json = json!({
-            "sessionId": "eval-session",
-            "expression": "x + y"
-        });
+    fn test_resolve_source_path_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let real = dir.path().join("real");
+        std::fs::create_dir(&real).unwrap();
+        std::fs::write(real.join("app.py"), "print(1)\n").unwrap();
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+        let canonical = real.canonicalize().unwrap().join("app.py");
+        let canonical = canonical.to_str().unwrap();
+
+        // Relative paths resolve against the base, symlinks to the real file
+        assert_eq!(
+            ToolsHandler::resolve_source_path("app.py", link.to_str()).unwrap(),
+            canonical
+        );
+        let absolute = link.join("app.py");
+        assert_eq!(
+            ToolsHandler::resolve_source_path(absolute.to_str().unwrap(), None).unwrap(),
+            canonical
+        );
+        // An absolute path ignores the base
+        assert_eq!(
+            ToolsHandler::resolve_source_path(absolute.to_str().unwrap(), Some("/nonexistent"))
+                .unwrap(),
+            canonical
+        );
+        assert!(ToolsHandler::resolve_source_path("missing.py", link.to_str()).is_err());
+    }
 
-        let args: EvaluateArgs = serde_json::from_value(json).unwrap();
-        assert!(args.frame_id.is_none());
+    #[test]
+    fn test_validate_launch_target_accepts_typescript_for_nodejs() {
+        let dir = tempfile::tempdir().unwrap();
+        let program = dir.path().join("main.ts");
+        std::fs::write(&program, "console.log(1);\n").unwrap();
+        let program = program.to_str().unwrap();
+
+        assert!(ToolsHandler::validate_launch_target("nodejs", program, None).is_ok());
+        match ToolsHandler::validate_launch_target("python", program, None) {
+            Err(Error::InvalidRequest(msg)) => {
+                assert!(msg.contains("must be a .py file"), "{}", msg)
+            }
+            other => panic!("Expected InvalidRequest, got {:?}", other),
+        }
     }
 
     #[test]
-    fn test_disconnect_args_deserialization() {
-        let json = json!({"sessionId": "disconnect-session"});
-        let args: DisconnectArgs = serde_json::from_value(json).unwrap();
-        assert_eq!(args.session_id, "disconnect-session");
+    fn test_node_launch_options_only_for_nodejs() {
+        let parse = |value: Value| {
+            let args: DebuggerStartArgs = serde_json::from_value(value).unwrap();
+            ToolsHandler::node_launch_options(&args)
+        };
+
+        let options = parse(json!({
+            "language": "nodejs",
+            "program": "/app/main.ts",
+            "outFiles": ["/app/dist/**/*.js"],
+            "runtimeArgs": ["--import", "tsx"]
+        }))
+        .unwrap();
+        assert_eq!(options.out_files, vec!["/app/dist/**/*.js"]);
+        assert_eq!(options.runtime_args, vec!["--import", "tsx"]);
+        assert_eq!(options.source_maps, None);
+
+        assert!(matches!(
+            parse(json!({"language": "python", "program": "a.py", "sourceMaps": true})),
+            Err(Error::InvalidRequest(_))
+        ));
     }
 
     #[test]
-    fn test_list_tools() {
-        let tools = ToolsHandler::list_tools();
-        assert_eq!(tools.len(), 12); // Updated from 7 to 12
+    fn test_python_module_mode() {
+        let parse = |value: Value| {
+            let args: DebuggerStartArgs = serde_json::from_value(value).unwrap();
+            ToolsHandler::python_module(&args)
+        };
 
-        // Verify tool names
-        let tool_names: Vec<&str> = tools.iter().filter_map(|t| t["name"].as_str()).collect();
+        assert_eq!(
+            parse(json!({"language": "python", "module": "pytest"})).unwrap(),
+            Some("pytest".to_string())
+        );
+        assert_eq!(
+            parse(json!({"language": "python", "program": "a.py"})).unwrap(),
+            None
+        );
+        for invalid in [
+            json!({"language": "ruby", "module": "pytest"}),
+            json!({"language": "python", "module": "pytest; rm"}),
+            json!({"language": "python", "module": "1pkg"}),
+            json!({"language": "python", "module": "pytest", "program": "a.py"}),
+        ] {
+            assert!(matches!(parse(invalid), Err(Error::InvalidRequest(_))));
+        }
+    }
 
-        // Original tools
-        assert!(tool_names.contains(&"debugger_start"));
-        assert!(tool_names.contains(&"debugger_session_state"));
-        assert!(tool_names.contains(&"debugger_set_breakpoint"));
-        assert!(tool_names.contains(&"debugger_continue"));
-        assert!(tool_names.contains(&"debugger_stack_trace"));
-        assert!(tool_names.contains(&"debugger_evaluate"));
-        assert!(tool_names.contains(&"debugger_disconnect"));
+    #[test]
+    fn test_python_launch_options() {
+        let parse = |value: Value| {
+            let args: DebuggerStartArgs = serde_json::from_value(value).unwrap();
+            ToolsHandler::python_launch_options(&args)
+        };
 
-        // New tools
-        assert!(tool_names.contains(&"debugger_wait_for_stop"));
-        assert!(tool_names.contains(&"debugger_list_breakpoints"));
-        assert!(tool_names.contains(&"debugger_step_over"));
-        assert!(tool_names.contains(&"debugger_step_into"));
-        assert!(tool_names.contains(&"debugger_step_out"));
+        let options = parse(json!({
+            "language": "python",
+            "program": "/app/app.py",
+            "subProcess": true,
+            "webFramework": "flask"
+        }))
+        .unwrap();
+        assert!(options.sub_process);
+        assert_eq!(options.web_framework, Some(WebFramework::Flask));
+
+        for invalid in [
+            json!({"language": "python", "program": "a.py", "webFramework": "rails"}),
+            json!({"language": "ruby", "program": "a.rb", "subProcess": true}),
+            json!({"language": "python", "module": "flask", "webFramework": "flask"}),
+        ] {
+            assert!(matches!(parse(invalid), Err(Error::InvalidRequest(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ruby_bundler_and_command_options() {
+        let parse = |value: Value| {
+            let args: DebuggerStartArgs = serde_json::from_value(value).unwrap();
+            ToolsHandler::ruby_launch_options(&args)
+        };
+        let options = parse(json!({
+            "language": "ruby",
+            "program": "spec/foo_spec.rb",
+            "useBundler": true,
+            "command": ["rspec"]
+        }))
+        .unwrap();
+        assert!(options.use_bundler);
+        assert_eq!(options.command, vec!["rspec"]);
+        for invalid in [
+            json!({"language": "python", "program": "a.py", "useBundler": true}),
+            json!({"language": "ruby", "program": "a.rb", "command": ["bundle", ""]}),
+        ] {
+            assert!(matches!(parse(invalid), Err(Error::InvalidRequest(_))));
+        }
+
+        // No Gemfile: rejected before rdbg is spawned
+        let dir = tempfile::tempdir().unwrap();
+        let program = dir.path().join("app.rb");
+        std::fs::write(&program, "puts 1\n").unwrap();
+        let manager = Arc::new(SessionManager::new());
+        let handler = ToolsHandler::new(manager);
+        match handler
+            .handle_tool(
+                "debugger_start",
+                json!({"language": "ruby", "program": program, "useBundler": true}),
+            )
+            .await
+        {
+            Err(Error::InvalidRequest(msg)) => assert!(msg.contains("Gemfile"), "{}", msg),
+            other => panic!("Expected InvalidRequest, got {:?}", other),
+        }
     }
 
     #[test]
-    fn test_list_tools_schema_validation() {
-        let tools = ToolsHandler::list_tools();
+    fn test_core_dump_option() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("crasher");
+        let core = dir.path().join("core");
+        std::fs::write(&binary, b"\x7fELF").unwrap();
+        std::fs::write(&core, b"\x7fELF").unwrap();
+        let parse = |value: Value| {
+            let args: DebuggerStartArgs = serde_json::from_value(value).unwrap();
+            ToolsHandler::core_dump(&args)
+        };
 
-        // Check first tool structure
-        let start_tool = &tools[0];
-        assert_eq!(start_tool["name"], "debugger_start");
-        assert!(start_tool["description"].is_string());
-        assert!(start_tool["inputSchema"]["type"].is_string());
-        assert!(start_tool["inputSchema"]["properties"].is_object());
-        assert!(start_tool["inputSchema"]["required"].is_array());
+        let core_dump = parse(json!({"language": "rust", "program": binary, "coreDump": core}));
+        assert_eq!(core_dump.unwrap().as_deref(), core.to_str());
+        assert_eq!(
+            parse(json!({"language": "rust", "program": "main.rs"})).unwrap(),
+            None
+        );
+        for invalid in [
+            json!({"language": "python", "program": binary, "coreDump": core}),
+            json!({"language": "rust", "program": "src/main.rs", "coreDump": core}),
+            json!({"language": "rust", "program": binary, "coreDump": core, "cargoTarget": "test"}),
+            json!({"language": "c", "program": binary, "coreDump": dir.path().join("missing")}),
+        ] {
+            assert!(matches!(parse(invalid), Err(Error::InvalidRequest(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_start_config_overrides_and_validation() {
+        let manager = Arc::new(SessionManager::new());
+        let handler = ToolsHandler::new(manager);
+
+        let saved = handler
+            .handle_tool(
+                "debugger_save_config",
+                json!({"name": "app", "config": {
+                    "language": "python",
+                    "program": "/app/main.py",
+                    "args": ["--port", "80"],
+                    "stopOnEntry": true
+                }}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(saved["replaced"], false);
+
+        // Explicit arguments override the configuration, field by field
+        let (merged, name) = handler
+            .resolve_start_config(json!({"config": "app", "args": ["--port", "8080"]}))
+            .await
+            .unwrap();
+        assert_eq!(name.as_deref(), Some("app"));
+        assert_eq!(merged["args"], json!(["--port", "8080"]));
+        assert_eq!(merged["stopOnEntry"], true);
+        assert_eq!(merged["program"], "/app/main.py");
+        assert!(merged.get("config").is_none());
+
+        let listed = handler
+            .handle_tool("debugger_list_configs", json!({}))
+            .await
+            .unwrap();
+        assert_eq!(listed["configs"][0]["name"], "app");
+
+        let error = |result: Result<Value>| match result {
+            Err(Error::InvalidRequest(msg)) => msg,
+            other => panic!("Expected InvalidRequest, got {:?}", other),
+        };
+        let msg = error(
+            handler
+                .handle_tool(
+                    "debugger_save_config",
+                    json!({"name": "bad", "config": {"language": "python", "stopOnEntry": "yes"}}),
+                )
+                .await,
+        );
+        assert!(msg.contains("field \"stopOnEntry\""), "{}", msg);
+        let msg = error(
+            handler
+                .handle_tool(
+                    "debugger_save_config",
+                    json!({"name": "bad", "config": {"language": "python", "stopOnEntyr": true}}),
+                )
+                .await,
+        );
+        assert!(msg.contains("unknown field \"stopOnEntyr\""), "{}", msg);
+        let msg = error(
+            handler
+                .handle_tool(
+                    "debugger_start",
+                    json!({"config": "app", "entryLine": "first"}),
+                )
+                .await,
+        );
+        assert!(
+            msg.contains("configuration \"app\": field \"entryLine\""),
+            "{}",
+            msg
+        );
+        let msg = error(
+            handler
+                .handle_tool("debugger_start", json!({"config": "nope"}))
+                .await,
+        );
+        assert!(msg.contains("Unknown configuration \"nope\""), "{}", msg);
+    }
+
+    #[test]
+    fn test_lldb_launch_options() {
+        let parse = |value: Value| {
+            let args: DebuggerStartArgs = serde_json::from_value(value).unwrap();
+            ToolsHandler::lldb_launch_options(&args)
+        };
+        let options = parse(json!({
+            "language": "rust",
+            "program": "main.rs",
+            "expressions": "python",
+            "initCommands": ["command script import fmt.py"]
+        }))
+        .unwrap();
+        assert_eq!(options.expressions, Some(LldbExpressions::Python));
+        assert_eq!(options.init_commands, vec!["command script import fmt.py"]);
+        assert!(
+            parse(json!({"language": "cpp", "program": "a.cpp", "preRunCommands": ["x"]})).is_ok()
+        );
+        let options = parse(json!({
+            "language": "rust",
+            "program": "main.rs",
+            "env": {"RUST_LOG": "debug"},
+            "stdio": ["input.txt", null],
+            "terminal": "external"
+        }))
+        .unwrap();
+        assert_eq!(options.env["RUST_LOG"], "debug");
+        assert_eq!(options.stdio, vec![Some("input.txt".to_string()), None]);
+        assert_eq!(options.terminal, Some(LldbTerminal::External));
+        for invalid in [
+            json!({"language": "rust", "program": "main.rs", "expressions": "lua"}),
+            json!({"language": "python", "program": "a.py", "expressions": "native"}),
+            json!({"language": "rust", "program": "main.rs", "terminal": "tty"}),
+            json!({"language": "rust", "program": "main.rs", "stdio": ["a", "b", "c", "d"]}),
+            json!({"language": "nodejs", "program": "a.js", "env": {"A": "1"}}),
+        ] {
+            assert!(matches!(parse(invalid), Err(Error::InvalidRequest(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_debugger_start_requires_program_or_module() {
+        let manager = Arc::new(SessionManager::new());
+        let handler = ToolsHandler::new(manager);
+
+        match handler
+            .handle_tool("debugger_start", json!({"language": "python"}))
+            .await
+        {
+            Err(Error::InvalidRequest(msg)) => {
+                assert!(msg.contains("program is required"), "{}", msg)
+            }
+            other => panic!("Expected InvalidRequest, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_npm_script_options_and_package_target() {
+        let parse = |value: Value| {
+            let args: DebuggerStartArgs = serde_json::from_value(value).unwrap();
+            ToolsHandler::node_launch_options(&args)
+        };
+        let options =
+            parse(json!({"language": "nodejs", "program": "/app", "npmScript": "dev"})).unwrap();
+        assert!(options.uses_package_manager());
+        for invalid in [
+            json!({"language": "nodejs", "program": "/app", "npmScript": " "}),
+            json!({"language": "nodejs", "program": "/app", "npmScript": "dev",
+                   "runtimeExecutable": "yarn"}),
+            json!({"language": "python", "program": "/app", "npmScript": "dev"}),
+        ] {
+            assert!(matches!(parse(invalid), Err(Error::InvalidRequest(_))));
+        }
+
+        // cwd defaults to the package root, found from a file inside the package
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), "{}").unwrap();
+        std::fs::create_dir(root.join("src")).unwrap();
+        std::fs::write(root.join("src").join("server.js"), "").unwrap();
+        let server = root.join("src").join("server.js");
+
+        let (program, cwd) =
+            ToolsHandler::validate_package_target(server.to_str().unwrap(), None).unwrap();
+        assert_eq!(program, server.to_str().unwrap());
+        assert_eq!(cwd.as_deref(), root.to_str());
+
+        let outside = tempfile::tempdir().unwrap();
+        match ToolsHandler::validate_package_target(outside.path().to_str().unwrap(), None) {
+            Err(Error::InvalidRequest(msg)) => {
+                assert!(msg.contains("no package.json found"), "{}", msg)
+            }
+            other => panic!("Expected InvalidRequest, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_debugger_dap_log_returns_tail() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::dap::wire_log::DapLog;
+        use crate::debug::session::DebugSession;
+
+        let client = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+            .await
+            .unwrap();
+        client.capture_dap_log(Arc::new(DapLog::default()));
+        client.send_request("threads", None).await.unwrap();
+        client.send_request("pause", None).await.unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/a.py".to_string(), client)
+            .await
+            .unwrap();
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let handler = ToolsHandler::new(Arc::new(manager));
+
+        let log = handler
+            .handle_tool(
+                "debugger_dap_log",
+                json!({"sessionId": session_id, "tail": 2}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(log["recorded"], 4);
+        assert_eq!(log["returned"], 2);
+        assert_eq!(log["entries"][0]["name"], "pause");
+        assert_eq!(log["entries"][0]["direction"], "sent");
+        assert_eq!(log["entries"][1]["direction"], "received");
+
+        let result = handler
+            .handle_tool(
+                "debugger_dap_log",
+                json!({"sessionId": session_id, "tail": 0}),
+            )
+            .await;
+        assert!(matches!(result, Err(Error::InvalidRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_resume_wait_for_stop_returns_new_location() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let manager = SessionManager::new();
+        let mut session_ids = Vec::new();
+        for adapter in [
+            ScriptedAdapter::new()
+                .stopping_on_resume()
+                .frame_source("/app/main.py"),
+            ScriptedAdapter::new(),
+        ] {
+            let client = DapClient::new_with_transport(Box::new(adapter), None)
+                .await
+                .unwrap();
+            let session =
+                DebugSession::new("python".to_string(), "/app/main.py".to_string(), client)
+                    .await
+                    .unwrap();
+            session
+                .initialize_and_launch("debugpy", json!({"program": "/app/main.py"}))
+                .await
+                .unwrap();
+            session_ids.push(manager.insert_session(Arc::new(session)).await);
+        }
+        let handler = ToolsHandler::new(Arc::new(manager));
+        let (stopping, running) = (&session_ids[0], &session_ids[1]);
+
+        let stop = handler
+            .handle_tool(
+                "debugger_continue",
+                json!({"sessionId": stopping, "waitForStop": true}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(stop["state"], "Stopped");
+        assert_eq!(stop["reason"], "breakpoint");
+        assert_eq!(
+            stop["topFrame"],
+            json!({"name": "main", "source": "/app/main.py", "line": 1})
+        );
+
+        for step in [
+            "debugger_step_over",
+            "debugger_step_into",
+            "debugger_step_out",
+        ] {
+            let stop = handler
+                .handle_tool(
+                    step,
+                    json!({"sessionId": stopping, "waitForStop": true, "timeoutMs": 2000}),
+                )
+                .await
+                .unwrap();
+            assert_eq!(stop["reason"], "step", "{}", step);
+            assert_eq!(stop["threadId"], 1);
+            assert_eq!(stop["topFrame"]["name"], "main");
+        }
+
+        // Waiting is opt-in
+        let stepping = handler
+            .handle_tool("debugger_step_over", json!({"sessionId": stopping}))
+            .await
+            .unwrap();
+        assert_eq!(stepping["status"], "stepping");
+        assert!(stepping.get("topFrame").is_none());
+
+        let result = handler
+            .handle_tool(
+                "debugger_continue",
+                json!({"sessionId": running, "waitForStop": true, "timeoutMs": 100}),
+            )
+            .await;
+        match result {
+            Err(Error::InvalidState(msg)) => assert!(msg.contains("100ms"), "{}", msg),
+            other => panic!("Expected InvalidState, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_stop_includes_stack_and_variables() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let manager = SessionManager::new();
+        let mut session_ids = Vec::new();
+        for adapter in [
+            ScriptedAdapter::new().frame_source("/app/main.py"),
+            ScriptedAdapter::new().failing_command("scopes"),
+        ] {
+            let client = DapClient::new_with_transport(Box::new(adapter), None)
+                .await
+                .unwrap();
+            let session =
+                DebugSession::new("python".to_string(), "/app/main.py".to_string(), client)
+                    .await
+                    .unwrap();
+            session
+                .state
+                .write()
+                .await
+                .set_state(crate::debug::state::DebugState::Stopped {
+                    thread_id: 1,
+                    reason: "breakpoint".to_string(),
+                });
+            session_ids.push(manager.insert_session(Arc::new(session)).await);
+        }
+        let handler = ToolsHandler::new(Arc::new(manager));
+
+        let plain = handler
+            .handle_tool(
+                "debugger_wait_for_stop",
+                json!({"sessionId": session_ids[0]}),
+            )
+            .await
+            .unwrap();
+        assert!(plain.get("stackTrace").is_none());
+        assert!(plain.get("topFrameVariables").is_none());
+
+        let stop = handler
+            .handle_tool(
+                "debugger_wait_for_stop",
+                json!({
+                    "sessionId": session_ids[0],
+                    "includeStackTrace": true,
+                    "levels": 1,
+                    "includeTopFrameVariables": true
+                }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(stop["state"], "Stopped");
+        assert_eq!(stop["stackTrace"][0]["name"], "main");
+        assert_eq!(stop["stackTrace"][0]["source"]["path"], "/app/main.py");
+        assert_eq!(stop["topFrameVariables"][0]["scope"], "Locals");
+        assert_eq!(stop["topFrameVariables"][0]["variables"][0]["name"], "n");
+        assert_eq!(stop["topFrameVariables"][0]["variables"][0]["value"], "15");
+        // Expensive scopes are only referenced
+        assert_eq!(stop["topFrameVariables"][1]["expensive"], true);
+        assert!(stop["topFrameVariables"][1].get("variables").is_none());
+        assert!(stop.get("warnings").is_none());
+
+        // A failed extra does not fail the stop
+        let stop = handler
+            .handle_tool(
+                "debugger_wait_for_stop",
+                json!({
+                    "sessionId": session_ids[1],
+                    "includeStackTrace": true,
+                    "includeTopFrameVariables": true
+                }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(stop["reason"], "breakpoint");
+        assert_eq!(stop["stackTrace"][0]["name"], "main");
+        assert!(stop.get("topFrameVariables").is_none());
+        let warning = stop["warnings"][0].as_str().unwrap();
+        assert!(warning.contains("scopes is not available"), "{}", warning);
+
+        let result = handler
+            .handle_tool(
+                "debugger_wait_for_stop",
+                json!({"sessionId": session_ids[0], "includeStackTrace": true, "levels": 0}),
+            )
+            .await;
+        assert!(matches!(result, Err(Error::InvalidRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_stack_trace_pages_deep_stacks() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+        use crate::mcp::resources::ResourcesHandler;
+
+        let client =
+            DapClient::new_with_transport(Box::new(ScriptedAdapter::new().stack_depth(120)), None)
+                .await
+                .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/a.py".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .state
+            .write()
+            .await
+            .set_state(crate::debug::state::DebugState::Stopped {
+                thread_id: 1,
+                reason: "breakpoint".to_string(),
+            });
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let manager = Arc::new(manager);
+        let handler = ToolsHandler::new(Arc::clone(&manager));
+
+        let first = handler
+            .handle_tool("debugger_stack_trace", json!({"sessionId": session_id}))
+            .await
+            .unwrap();
+        assert_eq!(
+            first["stackFrames"].as_array().unwrap().len(),
+            DEFAULT_STACK_LEVELS as usize
+        );
+        assert_eq!(first["stackFrames"][0]["name"], "main");
+        assert_eq!(first["totalFrames"], 120);
+        let note = first["note"].as_str().unwrap();
+        assert!(note.contains("startFrame: 50"), "{}", note);
+
+        let last = handler
+            .handle_tool(
+                "debugger_stack_trace",
+                json!({"sessionId": session_id, "startFrame": 100, "levels": 50}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(last["stackFrames"].as_array().unwrap().len(), 20);
+        assert_eq!(last["stackFrames"][0]["name"], "caller_100");
+        assert_eq!(last["startFrame"], 100);
+        assert!(last.get("note").is_none());
+
+        for invalid in [json!({"levels": 0}), json!({"startFrame": -1})] {
+            let mut args = invalid.clone();
+            args["sessionId"] = json!(session_id);
+            let result = handler.handle_tool("debugger_stack_trace", args).await;
+            assert!(
+                matches!(result, Err(Error::InvalidRequest(_))),
+                "{}",
+                invalid
+            );
+        }
+
+        // The resource returns the same first page
+        let resource = ResourcesHandler::new(manager)
+            .read_resource(&format!("debugger://sessions/{}/stackTrace", session_id))
+            .await
+            .unwrap();
+        let content: Value = serde_json::from_str(resource.text.as_deref().unwrap()).unwrap();
+        assert_eq!(content["stackFrames"].as_array().unwrap().len(), 50);
+        assert_eq!(content["totalFrames"], 120);
+        assert_eq!(content["sessionId"], json!(session_id));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_disconnect_not_blocked_by_slow_request() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let manager = SessionManager::new();
+        let mut ids = Vec::new();
+        for adapter in [
+            ScriptedAdapter::new().ignoring("stackTrace"),
+            ScriptedAdapter::new(),
+        ] {
+            let client = DapClient::new_with_transport(Box::new(adapter), None)
+                .await
+                .unwrap();
+            let session = DebugSession::new("python".to_string(), "/app/a.py".to_string(), client)
+                .await
+                .unwrap();
+            session
+                .state
+                .write()
+                .await
+                .set_state(crate::debug::state::DebugState::Stopped {
+                    thread_id: 1,
+                    reason: "breakpoint".to_string(),
+                });
+            ids.push(manager.insert_session(Arc::new(session)).await);
+        }
+        let handler = Arc::new(ToolsHandler::new(Arc::new(manager)));
+
+        // Waits for the request timeout: the adapter never answers
+        let slow = tokio::spawn({
+            let handler = Arc::clone(&handler);
+            let session_id = ids[0].clone();
+            async move {
+                handler
+                    .handle_tool("debugger_stack_trace", json!({"sessionId": session_id}))
+                    .await
+            }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let disconnected = tokio::time::timeout(
+            std::time::Duration::from_secs(2),
+            handler.handle_tool("debugger_disconnect", json!({"sessionId": ids[1]})),
+        )
+        .await
+        .expect("disconnect must not wait for another session's request");
+        assert_eq!(disconnected.unwrap()["status"], "disconnected");
+        assert!(!slow.is_finished());
+        slow.abort();
+    }
+
+    #[tokio::test]
+    async fn test_large_results_are_truncated() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let adapter = ScriptedAdapter::new();
+        let requests = adapter.requests();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/a.py".to_string(), client)
+            .await
+            .unwrap();
+        {
+            let mut state = session.state.write().await;
+            state.set_state(crate::debug::state::DebugState::Stopped {
+                thread_id: 1,
+                reason: "breakpoint".to_string(),
+            });
+            for line in ["old line\n", "middle line\n", "newest line\n"] {
+                state.push_output("stdout".to_string(), line.to_string());
+            }
+        }
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let handler = ToolsHandler::new(Arc::new(manager));
+
+        // The scripted adapter echoes the expression as the result
+        let expression = "x".repeat(truncate::DEFAULT_MAX_LENGTH + 100);
+        let evaluated = handler
+            .handle_tool(
+                "debugger_evaluate",
+                json!({"sessionId": session_id, "expression": expression, "frameId": 1}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            evaluated["result"].as_str().unwrap().len(),
+            truncate::DEFAULT_MAX_LENGTH
+        );
+        assert_eq!(evaluated["truncated"], true);
+        assert_eq!(evaluated["originalLength"], expression.len());
+
+        let small = handler
+            .handle_tool(
+                "debugger_evaluate",
+                json!({"sessionId": session_id, "expression": "n", "frameId": 1, "maxLength": 10}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(small["result"], "n");
+        assert!(small.get("truncated").is_none());
+
+        let variables = handler
+            .handle_tool(
+                "debugger_get_variables",
+                json!({"sessionId": session_id, "variablesReference": 100, "start": 20, "maxLength": 1}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(variables["variables"][0]["value"], "1");
+        assert_eq!(variables["variables"][0]["truncated"], true);
+        assert_eq!(variables["variables"][0]["originalLength"], 2);
+        assert_eq!(variables["start"], 20);
+        let sent = requests.lock().unwrap().last().unwrap().clone();
+        assert_eq!(sent.command, "variables");
+        assert_eq!(sent.arguments.unwrap()["count"], DEFAULT_VARIABLES_COUNT);
+
+        let output = handler
+            .handle_tool(
+                "debugger_get_output",
+                json!({"sessionId": session_id, "maxLength": 20}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(output["count"], 2);
+        assert_eq!(output["output"][0]["output"], "le line\n");
+        assert_eq!(output["output"][1]["output"], "newest line\n");
+        assert_eq!(output["truncated"], true);
+        assert_eq!(output["originalLength"], 33);
+        assert_eq!(output["omittedEntries"], 1);
+
+        let result = handler
+            .handle_tool(
+                "debugger_get_variables",
+                json!({"sessionId": session_id, "variablesReference": 100, "count": 0}),
+            )
+            .await;
+        assert!(matches!(result, Err(Error::InvalidRequest(_))));
     }
 
     #[tokio::test]
-    async fn test_tools_handler_new() {
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
-        let _handler = ToolsHandler::new(manager);
-        // Verify list_tools returns expected tools
-        let tools = ToolsHandler::list_tools();
-        assert!(tools.iter().any(|t| t["name"] == "debugger_start"));
+    async fn test_completions_in_top_frame() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let adapter = ScriptedAdapter::new().with_capability("supportsCompletionsRequest");
+        let requests = adapter.requests();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        client.initialize("debugpy").await.unwrap();
+        let session = DebugSession::new("python".into(), "/app/a.py".into(), client)
+            .await
+            .unwrap();
+        session
+            .state
+            .write()
+            .await
+            .set_state(crate::debug::state::DebugState::Stopped {
+                thread_id: 1,
+                reason: "breakpoint".into(),
+            });
+        let session_id = session.id.clone();
+        let manager = SessionManager::new();
+        manager.insert_session(Arc::new(session)).await;
+        let handler = ToolsHandler::new(Arc::new(manager));
+
+        let result = handler
+            .handle_tool(
+                "debugger_completions",
+                json!({"sessionId": session_id, "text": "fi"}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            result["targets"],
+            json!([
+                {"label": "fizzbuzz", "type": "function", "text": "fizzbuzz"},
+                {"label": "filter", "type": "class", "text": "filter"}
+            ])
+        );
+        let completions = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|req| req.command == "completions")
+            .and_then(|req| req.arguments.clone())
+            .unwrap();
+        // The cursor defaults to the end of the text, in the top frame
+        assert_eq!(
+            completions,
+            json!({"frameId": 1, "text": "fi", "column": 3})
+        );
+
+        let err = handler
+            .handle_tool(
+                "debugger_completions",
+                json!({"sessionId": session_id, "text": "fi", "column": 4}),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidRequest(_)));
     }
 
     #[tokio::test]
-    async fn test_handle_tool_unknown_method() {
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
-        let handler = ToolsHandler::new(manager);
-
-        let result = handler.handle_tool("unknown_tool", json!({})).await;
-        assert!(result.is_err());
+    async fn test_unverified_breakpoint_suggests_loaded_source() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("main.py");
+        std::fs::write(&source, "print('hello')\n".repeat(10)).unwrap();
+        let source = source.to_string_lossy().to_string();
+
+        let adapter = ScriptedAdapter::new()
+            .rejecting_line(7)
+            .loading_source("/srv/app/main.py")
+            .loading_source("/srv/app/util.py");
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/main.py".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .initialize_and_launch("debugpy", json!({"program": "/app/main.py"}))
+            .await
+            .unwrap();
+        // Launched sessions are Running once the adapter says so
+        session
+            .state
+            .write()
+            .await
+            .set_state(crate::debug::state::DebugState::Running);
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let handler = ToolsHandler::new(Arc::new(manager));
+
+        let sources = handler
+            .handle_tool(
+                "debugger_loaded_sources",
+                json!({"sessionId": session_id, "filter": "util"}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            sources,
+            json!({"sources": ["/srv/app/util.py"], "count": 1})
+        );
+
+        let unverified = handler
+            .handle_tool(
+                "debugger_set_breakpoint",
+                json!({"sessionId": session_id, "sourcePath": source, "line": 7}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(unverified["verified"], false);
+        assert_eq!(unverified["suggestedSourcePath"], "/srv/app/main.py");
+        assert!(unverified["hint"]
+            .as_str()
+            .unwrap()
+            .contains("/srv/app/main.py"));
+
+        // The explanation stays on the listed breakpoint
+        let listed = handler
+            .handle_tool(
+                "debugger_list_breakpoints",
+                json!({"sessionId": session_id}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            listed["breakpoints"][0]["suggestedSourcePath"],
+            "/srv/app/main.py"
+        );
+
+        let verified = handler
+            .handle_tool(
+                "debugger_set_breakpoint",
+                json!({"sessionId": session_id, "sourcePath": source, "line": 8}),
+            )
+            .await
+            .unwrap();
+        assert!(verified.get("hint").is_none());
+
+        // No modules request support and no 'module' events
+        let modules = handler
+            .handle_tool("debugger_modules", json!({"sessionId": session_id}))
+            .await
+            .unwrap();
+        assert_eq!(modules, json!({"modules": [], "count": 0}));
+    }
 
-        match result {
-            Err(Error::MethodNotFound(name)) => {
-                assert_eq!(name, "unknown_tool");
-            }
-            _ => panic!("Expected MethodNotFound error"),
+    #[tokio::test]
+    async fn test_breakpoint_lines_validated_and_adjusted() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("main.py");
+        std::fs::write(&source, "x = 1\n\ny = 2\n\n\nz = 3\n").unwrap();
+        let source = source.to_string_lossy().to_string();
+
+        let adapter = ScriptedAdapter::new().moving_line(2, 3).moving_line(4, 6);
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), source.clone(), client)
+            .await
+            .unwrap();
+        session
+            .state
+            .write()
+            .await
+            .set_state(crate::debug::state::DebugState::Running);
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let handler = ToolsHandler::new(Arc::new(manager));
+
+        for (line, expected) in [
+            (0, "line must be 1 or greater (lines are 1-indexed), got 0"),
+            (-3, "got -3"),
+            (7, "Line 7 is past the end"),
+        ] {
+            let error = handler
+                .handle_tool(
+                    "debugger_set_breakpoint",
+                    json!({"sessionId": session_id, "sourcePath": source, "line": line}),
+                )
+                .await
+                .unwrap_err();
+            assert!(matches!(error, Error::InvalidRequest(_)), "{}", error);
+            assert!(error.to_string().contains(expected), "{}", error);
         }
+        let error = handler
+            .handle_tool(
+                "debugger_set_breakpoints",
+                json!({"sessionId": session_id, "breakpoints": [{"sourcePath": source, "line": 9}]}),
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("the file has 6 lines"),
+            "{}",
+            error
+        );
+
+        // The blank line 2 binds to the next statement
+        let moved = handler
+            .handle_tool(
+                "debugger_set_breakpoint",
+                json!({"sessionId": session_id, "sourcePath": source, "line": 2}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(moved["verified"], true);
+        assert_eq!(moved["line"], 2);
+        assert_eq!(moved["adjustedLine"], 3);
+
+        let exact = handler
+            .handle_tool(
+                "debugger_set_breakpoints",
+                json!({"sessionId": session_id, "breakpoints": [
+                    {"sourcePath": source, "line": 1},
+                    {"sourcePath": source, "line": 4}
+                ]}),
+            )
+            .await
+            .unwrap();
+        let breakpoints = exact["breakpoints"].as_array().unwrap();
+        assert_eq!(breakpoints.len(), 2);
+        assert!(breakpoints[0].get("adjustedLine").is_none());
+        assert_eq!(breakpoints[1]["line"], 4);
+        assert_eq!(breakpoints[1]["adjustedLine"], 6);
+
+        let listed = handler
+            .handle_tool(
+                "debugger_list_breakpoints",
+                json!({"sessionId": session_id}),
+            )
+            .await
+            .unwrap();
+        let adjusted: Vec<(Value, Value)> = listed["breakpoints"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|bp| (bp["line"].clone(), bp["adjustedLine"].clone()))
+            .collect();
+        assert_eq!(
+            adjusted,
+            vec![
+                (json!(2), json!(3)),
+                (json!(1), Value::Null),
+                (json!(4), json!(6))
+            ]
+        );
     }
 
     #[tokio::test]
-    async fn test_handle_tool_invalid_arguments() {
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
-        let handler = ToolsHandler::new(manager);
+    async fn test_edited_sources_are_flagged() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let dir = tempfile::tempdir().unwrap();
+        let program = dir.path().join("app.py");
+        let module = dir.path().join("util.py");
+        std::fs::write(&program, "import util\n").unwrap();
+        std::fs::write(&module, "def f():\n    return 1\n").unwrap();
+        let program = program.to_string_lossy().to_string();
+        let module = module.to_string_lossy().to_string();
+
+        let client = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), program.clone(), client)
+            .await
+            .unwrap();
+        session
+            .initialize_and_launch("debugpy", json!({"program": program}))
+            .await
+            .unwrap();
+        session
+            .state
+            .write()
+            .await
+            .set_state(crate::debug::state::DebugState::Running);
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let handler = ToolsHandler::new(Arc::new(manager));
+
+        let first = handler
+            .handle_tool(
+                "debugger_set_breakpoint",
+                json!({"sessionId": session_id, "sourcePath": module, "line": 2}),
+            )
+            .await
+            .unwrap();
+        assert!(first.get("sourceChanged").is_none());
+        let unchanged = handler
+            .handle_tool("debugger_check_sources", json!({"sessionId": session_id}))
+            .await
+            .unwrap();
+        assert_eq!(
+            unchanged,
+            json!({"changed": false, "changedSources": [], "trackedSources": 2})
+        );
+
+        // Edited mid-session: the program still runs the old definition
+        std::fs::write(&module, "def f():\n    x = 2\n    return x\n").unwrap();
+
+        let second = handler
+            .handle_tool(
+                "debugger_set_breakpoint",
+                json!({"sessionId": session_id, "sourcePath": module, "line": 3}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(second["sourceChanged"], true);
+        assert_eq!(second["changedSources"], json!([module]));
+        assert!(second["warning"].as_str().unwrap().contains("Restart"));
+
+        let changed = handler
+            .handle_tool("debugger_check_sources", json!({"sessionId": session_id}))
+            .await
+            .unwrap();
+        assert_eq!(changed["changed"], true);
+        assert_eq!(changed["changedSources"], json!([module]));
+    }
 
-        // Invalid JSON for debugger_start
-        let result = handler
-            .handle_tool("debugger_start", json!({"invalid": "data"}))
+    #[tokio::test]
+    async fn test_evaluate_rejects_frame_ids_of_earlier_stops() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let adapter = ScriptedAdapter::new().stack_depth(3).stopping_on_resume();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/a.py".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .initialize_and_launch("debugpy", json!({"program": "/app/a.py"}))
+            .await
+            .unwrap();
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let handler = ToolsHandler::new(Arc::new(manager));
+        let evaluate = |frame_id: i32| {
+            handler.handle_tool(
+                "debugger_evaluate",
+                json!({"sessionId": session_id, "expression": "n", "frameId": frame_id}),
+            )
+        };
+
+        handler
+            .handle_tool(
+                "debugger_continue",
+                json!({"sessionId": session_id, "waitForStop": true}),
+            )
+            .await
+            .unwrap();
+        let stack = handler
+            .handle_tool(
+                "debugger_stack_trace",
+                json!({"sessionId": session_id, "threadId": 1}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(stack["stackFrames"][2]["id"], 3);
+        evaluate(3).await.unwrap();
+        let in_thread = handler
+            .handle_tool(
+                "debugger_evaluate",
+                json!({"sessionId": session_id, "expression": "n", "frameId": 3, "threadId": 1}),
+            )
             .await;
-        assert!(result.is_err());
+        assert!(in_thread.is_ok());
+
+        // Stepping moves on to a new stop; only the refetched top frame is valid
+        handler
+            .handle_tool(
+                "debugger_step_over",
+                json!({"sessionId": session_id, "waitForStop": true}),
+            )
+            .await
+            .unwrap();
+        let stale = evaluate(3).await.unwrap_err();
+        assert!(matches!(stale, Error::StaleFrameId(_)), "{:?}", stale);
+        assert_eq!(stale.code_name(), "STALE_FRAME_ID");
+        evaluate(1).await.unwrap();
+
+        handler
+            .handle_tool("debugger_stack_trace", json!({"sessionId": session_id}))
+            .await
+            .unwrap();
+        evaluate(3).await.unwrap();
     }
 
-    // Phase 6: Error path tests for missing required fields and invalid types
+    #[tokio::test]
+    async fn test_evaluate_with_frame_index_across_stops() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let adapter = ScriptedAdapter::new().stack_depth(3).stopping_on_resume();
+        let requests = adapter.requests();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/a.py".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .initialize_and_launch("debugpy", json!({"program": "/app/a.py"}))
+            .await
+            .unwrap();
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let handler = ToolsHandler::new(Arc::new(manager));
+        let evaluate = |arguments: Value| {
+            let mut arguments = arguments;
+            arguments["sessionId"] = json!(session_id);
+            arguments["expression"] = json!("n");
+            handler.handle_tool("debugger_evaluate", arguments)
+        };
+        let evaluated_frame = || {
+            let requests = requests.lock().unwrap();
+            let sent = requests
+                .iter()
+                .rev()
+                .find(|r| r.command == "evaluate")
+                .unwrap();
+            sent.arguments.as_ref().unwrap()["frameId"].clone()
+        };
 
-    #[test]
-    fn test_debugger_start_missing_language() {
-        let json = json!({
-            "program": "/path/to/script.py"
-        });
+        for step in ["debugger_continue", "debugger_step_over"] {
+            handler
+                .handle_tool(step, json!({"sessionId": session_id, "waitForStop": true}))
+                .await
+                .unwrap();
+            evaluate(json!({"frameIndex": 1})).await.unwrap();
+            assert_eq!(evaluated_frame(), 2);
+            evaluate(json!({"frameIndex": 2})).await.unwrap();
+            assert_eq!(evaluated_frame(), 3);
+        }
 
-        let result = serde_json::from_value::<DebuggerStartArgs>(json);
-        assert!(result.is_err());
+        let beyond = evaluate(json!({"frameIndex": 3})).await;
+        assert!(matches!(beyond, Err(Error::InvalidRequest(_))));
+        // frameId wins over frameIndex, and is still checked for staleness
+        handler
+            .handle_tool(
+                "debugger_step_over",
+                json!({"sessionId": session_id, "waitForStop": true}),
+            )
+            .await
+            .unwrap();
+        let stale = evaluate(json!({"frameId": 3, "frameIndex": 0})).await;
+        assert!(matches!(stale, Err(Error::StaleFrameId(_))));
     }
 
-    #[test]
-    fn test_debugger_start_missing_program() {
-        let json = json!({
-            "language": "python"
-        });
-
-        let result = serde_json::from_value::<DebuggerStartArgs>(json);
-        assert!(result.is_err());
+    #[tokio::test]
+    async fn test_stack_trace_skips_subtle_frames() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let adapter = ScriptedAdapter::new()
+            .stack_depth(5)
+            .frame_hint(1, "subtle")
+            .frame_hint(2, "label")
+            .stopping_on_resume();
+        let requests = adapter.requests();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/a.py".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .initialize_and_launch("debugpy", json!({"program": "/app/a.py"}))
+            .await
+            .unwrap();
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let handler = ToolsHandler::new(Arc::new(manager));
+        handler
+            .handle_tool(
+                "debugger_continue",
+                json!({"sessionId": session_id, "waitForStop": true}),
+            )
+            .await
+            .unwrap();
+
+        let all = handler
+            .handle_tool("debugger_stack_trace", json!({"sessionId": session_id}))
+            .await
+            .unwrap();
+        assert_eq!(all["stackFrames"].as_array().unwrap().len(), 5);
+        assert_eq!(all["stackFrames"][1]["presentationHint"], "subtle");
+        assert_eq!(all["stackFrames"][1]["source"]["origin"], "library");
+        assert_eq!(all["stackFrames"][0].get("presentationHint"), None);
+        assert_eq!(all.get("hiddenFrames"), None);
+
+        let filtered = handler
+            .handle_tool(
+                "debugger_stack_trace",
+                json!({"sessionId": session_id, "skipSubtleFrames": true, "levels": 4}),
+            )
+            .await
+            .unwrap();
+        let ids: Vec<&Value> = filtered["stackFrames"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|frame| &frame["id"])
+            .collect();
+        assert_eq!(ids, vec![1, 4]);
+        assert_eq!(filtered["hiddenFrames"], 2);
+        assert_eq!(filtered["totalFrames"], 5);
+        // The next page starts after the hidden frames too
+        assert!(filtered["note"].as_str().unwrap().contains("startFrame: 4"));
+
+        // frameIndex counts the hidden frames
+        handler
+            .handle_tool(
+                "debugger_evaluate",
+                json!({"sessionId": session_id, "expression": "n", "frameIndex": 3}),
+            )
+            .await
+            .unwrap();
+        let requests = requests.lock().unwrap();
+        let evaluate = requests
+            .iter()
+            .rev()
+            .find(|r| r.command == "evaluate")
+            .unwrap();
+        assert_eq!(evaluate.arguments.as_ref().unwrap()["frameId"], 4);
     }
 
-    #[test]
-    fn test_debugger_start_invalid_args_type() {
-        let json = json!({
-            "language": "python",
-            "program": "test.py",
-            "args": "not an array"  // Should be array, not string
-        });
+    #[tokio::test]
+    async fn test_step_into_chosen_target() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let adapter = ScriptedAdapter::new()
+            .with_capability("supportsStepInTargetsRequest")
+            .stopping_on_resume();
+        let requests = adapter.requests();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("nodejs".to_string(), "/app/a.js".to_string(), client)
+            .await
+            .unwrap();
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let handler = ToolsHandler::new(Arc::new(manager));
+
+        let running = handler
+            .handle_tool("debugger_step_in_targets", json!({"sessionId": session_id}))
+            .await;
+        assert!(matches!(running, Err(Error::InvalidState(_))));
+
+        let session = handler
+            .session_manager
+            .get_session(&session_id)
+            .await
+            .unwrap();
+        session
+            .initialize_and_launch("pwa-node", json!({"program": "/app/a.js"}))
+            .await
+            .unwrap();
+        handler
+            .handle_tool(
+                "debugger_continue",
+                json!({"sessionId": session_id, "waitForStop": true}),
+            )
+            .await
+            .unwrap();
+
+        let targets = handler
+            .handle_tool("debugger_step_in_targets", json!({"sessionId": session_id}))
+            .await
+            .unwrap();
+        assert_eq!(
+            targets["targets"][1],
+            json!({"id": 2, "label": "baz", "line": 1, "column": 12})
+        );
+        let sent = requests.lock().unwrap().last().unwrap().clone();
+        assert_eq!(sent.command, "stepInTargets");
+        assert_eq!(sent.arguments.unwrap()["frameId"], 1);
+
+        let stop = handler
+            .handle_tool(
+                "debugger_step_into",
+                json!({"sessionId": session_id, "targetId": 2, "waitForStop": true}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(stop["reason"], "step");
+        let requests = requests.lock().unwrap();
+        let step = requests
+            .iter()
+            .rev()
+            .find(|r| r.command == "stepIn")
+            .unwrap();
+        assert_eq!(step.arguments.as_ref().unwrap()["targetId"], 2);
+    }
 
-        let result = serde_json::from_value::<DebuggerStartArgs>(json);
-        assert!(result.is_err());
+    #[tokio::test]
+    async fn test_step_into_target_needs_adapter_support() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let client = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("ruby".to_string(), "/app/a.rb".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .initialize_and_launch("rdbg", json!({"program": "/app/a.rb"}))
+            .await
+            .unwrap();
+        session
+            .state
+            .write()
+            .await
+            .set_state(crate::debug::state::DebugState::Stopped {
+                thread_id: 1,
+                reason: "breakpoint".to_string(),
+            });
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let handler = ToolsHandler::new(Arc::new(manager));
+
+        for (tool, arguments) in [
+            ("debugger_step_in_targets", json!({"sessionId": session_id})),
+            (
+                "debugger_step_into",
+                json!({"sessionId": session_id, "targetId": 1}),
+            ),
+        ] {
+            let result = handler.handle_tool(tool, arguments).await;
+            assert!(
+                matches!(result, Err(Error::InvalidRequest(_))),
+                "{}: {:?}",
+                tool,
+                result
+            );
+        }
     }
 
-    #[test]
-    fn test_set_breakpoint_missing_session_id() {
-        let json = json!({
-            "sourcePath": "/path/to/file.py",
-            "line": 42
-        });
+    #[tokio::test]
+    async fn test_restart_frame() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let adapter = ScriptedAdapter::new()
+            .with_capability("supportsRestartFrame")
+            .stack_depth(2)
+            .stopping_on_resume();
+        let requests = adapter.requests();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/a.py".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .initialize_and_launch("debugpy", json!({"program": "/app/a.py"}))
+            .await
+            .unwrap();
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let handler = ToolsHandler::new(Arc::new(manager));
+
+        handler
+            .handle_tool(
+                "debugger_continue",
+                json!({"sessionId": session_id, "waitForStop": true}),
+            )
+            .await
+            .unwrap();
+        let stop = handler
+            .handle_tool(
+                "debugger_restart_frame",
+                json!({"sessionId": session_id, "frameIndex": 1, "waitForStop": true}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(stop["state"], "Stopped");
+        assert_eq!(stop["reason"], "restart");
+        let sent = {
+            let requests = requests.lock().unwrap();
+            requests
+                .iter()
+                .rev()
+                .find(|r| r.command == "restartFrame")
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(sent.arguments.unwrap(), json!({"frameId": 2}));
 
-        let result = serde_json::from_value::<SetBreakpointArgs>(json);
-        assert!(result.is_err());
+        // The stack of the stop before the restart is gone
+        let stale = handler
+            .handle_tool(
+                "debugger_restart_frame",
+                json!({"sessionId": session_id, "frameId": 2}),
+            )
+            .await;
+        assert!(matches!(stale, Err(Error::StaleFrameId(_))), "{:?}", stale);
     }
 
-    #[test]
-    fn test_set_breakpoint_missing_source_path() {
-        let json = json!({
-            "sessionId": "session-123",
-            "line": 42
-        });
+    #[tokio::test]
+    async fn test_restart_frame_needs_adapter_support() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let client = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("ruby".to_string(), "/app/a.rb".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .initialize_and_launch("rdbg", json!({"program": "/app/a.rb"}))
+            .await
+            .unwrap();
+        session
+            .state
+            .write()
+            .await
+            .set_state(crate::debug::state::DebugState::Stopped {
+                thread_id: 1,
+                reason: "breakpoint".to_string(),
+            });
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let handler = ToolsHandler::new(Arc::new(manager));
 
-        let result = serde_json::from_value::<SetBreakpointArgs>(json);
-        assert!(result.is_err());
+        let result = handler
+            .handle_tool("debugger_restart_frame", json!({"sessionId": session_id}))
+            .await;
+        assert!(
+            matches!(result, Err(Error::InvalidRequest(_))),
+            "{:?}",
+            result
+        );
+        // Nothing was resumed
+        assert_eq!(
+            handler.session_state_name(&session_id).await,
+            Some("Stopped")
+        );
     }
 
-    #[test]
-    fn test_set_breakpoint_missing_line() {
-        let json = json!({
-            "sessionId": "session-123",
-            "sourcePath": "/path/to/file.py"
-        });
+    #[tokio::test]
+    async fn test_jump_to_line() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let adapter = ScriptedAdapter::new()
+            .with_capability("supportsGotoTargetsRequest")
+            .stack_depth(2)
+            .stopping_on_resume();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/a.py".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .initialize_and_launch("debugpy", json!({"program": "/app/a.py"}))
+            .await
+            .unwrap();
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let handler = ToolsHandler::new(Arc::new(manager));
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("a.py");
+        std::fs::write(&source, "n = 1\n").unwrap();
+        let source = source.to_string_lossy().to_string();
+        let jump = json!({"sessionId": session_id, "sourcePath": source, "line": 5});
+
+        let running = handler
+            .handle_tool("debugger_jump_to_line", jump.clone())
+            .await;
+        assert!(
+            matches!(running, Err(Error::InvalidState(_))),
+            "{:?}",
+            running
+        );
+
+        handler
+            .handle_tool(
+                "debugger_continue",
+                json!({"sessionId": session_id, "waitForStop": true}),
+            )
+            .await
+            .unwrap();
+        handler
+            .handle_tool("debugger_stack_trace", json!({"sessionId": session_id}))
+            .await
+            .unwrap();
+
+        let jumped = handler
+            .handle_tool("debugger_jump_to_line", jump)
+            .await
+            .unwrap();
+        assert_eq!(jumped["state"], "Stopped");
+        assert_eq!(
+            jumped["target"],
+            json!({"id": 5, "label": "line 5", "line": 5})
+        );
+        assert_eq!(jumped["topFrame"]["line"], 5);
+        assert_eq!(
+            handler.session_state_name(&session_id).await,
+            Some("Stopped")
+        );
+
+        // The caller frame fetched before the jump is stale
+        let stale = handler
+            .handle_tool(
+                "debugger_evaluate",
+                json!({"sessionId": session_id, "expression": "n", "frameId": 2}),
+            )
+            .await;
+        assert!(matches!(stale, Err(Error::StaleFrameId(_))), "{:?}", stale);
 
-        let result = serde_json::from_value::<SetBreakpointArgs>(json);
-        assert!(result.is_err());
+        let bad_line = handler
+            .handle_tool(
+                "debugger_jump_to_line",
+                json!({"sessionId": session_id, "sourcePath": source, "line": 0}),
+            )
+            .await;
+        assert!(matches!(bad_line, Err(Error::InvalidRequest(_))));
     }
 
-    #[test]
-    fn test_set_breakpoint_invalid_line_type() {
-        let json = json!({
-            "sessionId": "session-123",
-            "sourcePath": "/path/to/file.py",
-            "line": "not a number"  // Should be integer
-        });
+    #[tokio::test]
+    async fn test_data_breakpoint_stops_on_write() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let adapter = ScriptedAdapter::new()
+            .with_capability("supportsDataBreakpoints")
+            .stopping_on_resume();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("rust".to_string(), "/app/main".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .initialize_and_launch("codelldb", json!({"program": "/app/main"}))
+            .await
+            .unwrap();
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let handler = ToolsHandler::new(Arc::new(manager));
+
+        handler
+            .handle_tool(
+                "debugger_continue",
+                json!({"sessionId": session_id, "waitForStop": true}),
+            )
+            .await
+            .unwrap();
+
+        let bad_access = handler
+            .handle_tool(
+                "debugger_set_data_breakpoint",
+                json!({"sessionId": session_id, "variablesReference": 100, "name": "n",
+                       "accessType": "modify"}),
+            )
+            .await;
+        assert!(matches!(bad_access, Err(Error::InvalidRequest(_))));
+        let no_target = handler
+            .handle_tool(
+                "debugger_set_data_breakpoint",
+                json!({"sessionId": session_id, "name": "n"}),
+            )
+            .await;
+        assert!(matches!(no_target, Err(Error::InvalidRequest(_))));
 
-        let result = serde_json::from_value::<SetBreakpointArgs>(json);
-        assert!(result.is_err());
+        let bp = handler
+            .handle_tool(
+                "debugger_set_data_breakpoint",
+                json!({"sessionId": session_id, "variablesReference": 100, "name": "n",
+                       "accessType": "write"}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            bp,
+            json!({"type": "data", "id": 1000, "verified": true, "dataId": "100/n",
+                   "description": "4 bytes of n", "accessType": "write", "condition": null})
+        );
+
+        let stop = handler
+            .handle_tool(
+                "debugger_continue",
+                json!({"sessionId": session_id, "waitForStop": true}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(stop["reason"], "data breakpoint");
+        let state = handler
+            .handle_tool("debugger_session_state", json!({"sessionId": session_id}))
+            .await
+            .unwrap();
+        assert_eq!(
+            state["lastStop"]["hitBreakpoints"],
+            json!([{"type": "data", "id": 1000, "dataId": "100/n", "description": "4 bytes of n"}])
+        );
+
+        let listed = handler
+            .handle_tool(
+                "debugger_list_breakpoints",
+                json!({"sessionId": session_id}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(listed["breakpoints"][0]["type"], "data");
+        assert_eq!(listed["breakpoints"][0]["hit"], true);
+
+        let cleared = handler
+            .handle_tool(
+                "debugger_set_data_breakpoint",
+                json!({"sessionId": session_id, "clear": true}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(cleared, json!({"cleared": 1}));
     }
 
-    #[test]
-    fn test_continue_args_missing_session_id() {
-        let json = json!({});
+    #[tokio::test]
+    async fn test_registers_follow_instruction_steps() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let manager = SessionManager::new();
+        let mut session_ids = Vec::new();
+        for (language, adapter) in [
+            (
+                "rust",
+                ScriptedAdapter::new()
+                    .with_registers()
+                    .with_capability("supportsSteppingGranularity")
+                    .stopping_on_resume(),
+            ),
+            ("python", ScriptedAdapter::new().stopping_on_resume()),
+        ] {
+            let client = DapClient::new_with_transport(Box::new(adapter), None)
+                .await
+                .unwrap();
+            let session = DebugSession::new(language.to_string(), "/app/main".to_string(), client)
+                .await
+                .unwrap();
+            session
+                .initialize_and_launch("test-adapter", json!({"program": "/app/main"}))
+                .await
+                .unwrap();
+            session_ids.push(manager.insert_session(Arc::new(session)).await);
+        }
+        let handler = ToolsHandler::new(Arc::new(manager));
+        let (native, python) = (&session_ids[0], &session_ids[1]);
+        for session_id in [native, python] {
+            handler
+                .handle_tool(
+                    "debugger_continue",
+                    json!({"sessionId": session_id, "waitForStop": true}),
+                )
+                .await
+                .unwrap();
+        }
 
-        let result = serde_json::from_value::<ContinueArgs>(json);
-        assert!(result.is_err());
+        let before = handler
+            .handle_tool("debugger_registers", json!({"sessionId": native}))
+            .await
+            .unwrap();
+        assert_eq!(before["group"], "General Purpose Registers");
+        assert_eq!(
+            before["groups"],
+            json!(["General Purpose Registers", "Floating Point Registers"])
+        );
+        assert_eq!(
+            before["registers"][0],
+            json!({"name": "rip", "value": "0x0000000000401000"})
+        );
+
+        let stop = handler
+            .handle_tool(
+                "debugger_step_over",
+                json!({"sessionId": native, "granularity": "instruction", "waitForStop": true}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(stop["reason"], "step");
+        let after = handler
+            .handle_tool("debugger_registers", json!({"sessionId": native}))
+            .await
+            .unwrap();
+        assert_eq!(after["registers"][0]["value"], "0x0000000000401004");
+
+        let float = handler
+            .handle_tool(
+                "debugger_registers",
+                json!({"sessionId": native, "group": "float"}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(float["registers"][0]["name"], "xmm0");
+
+        let invalid = handler
+            .handle_tool(
+                "debugger_step_into",
+                json!({"sessionId": native, "granularity": "opcode"}),
+            )
+            .await;
+        assert!(matches!(invalid, Err(Error::InvalidRequest(_))));
+
+        // Interpreted languages have no registers, debugpy no instruction steps
+        match handler
+            .handle_tool("debugger_registers", json!({"sessionId": python}))
+            .await
+        {
+            Err(Error::InvalidRequest(msg)) => assert!(msg.contains("python"), "{}", msg),
+            other => panic!("Expected InvalidRequest, got {:?}", other),
+        }
+        match handler
+            .handle_tool(
+                "debugger_step_over",
+                json!({"sessionId": python, "granularity": "instruction"}),
+            )
+            .await
+        {
+            Err(Error::InvalidRequest(msg)) => {
+                assert!(msg.contains("supportsSteppingGranularity"), "{}", msg)
+            }
+            other => panic!("Expected InvalidRequest, got {:?}", other),
+        }
     }
 
-    #[test]
-    fn test_stack_trace_args_missing_session_id() {
-        let json = json!({});
+    #[tokio::test]
+    async fn test_snapshot_degrades_per_section() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let manager = SessionManager::new();
+        let mut session_ids = Vec::new();
+        for adapter in [
+            ScriptedAdapter::new().stopping_on_resume(),
+            ScriptedAdapter::new()
+                .stopping_on_resume()
+                .failing_command("scopes"),
+            ScriptedAdapter::new()
+                .stopping_on_resume()
+                .failing_command("stackTrace"),
+        ] {
+            let client = DapClient::new_with_transport(Box::new(adapter), None)
+                .await
+                .unwrap();
+            let session =
+                DebugSession::new("python".to_string(), "/app/main.py".to_string(), client)
+                    .await
+                    .unwrap();
+            session
+                .initialize_and_launch("debugpy", json!({"program": "/app/main.py"}))
+                .await
+                .unwrap();
+            {
+                let mut state = session.state.write().await;
+                for i in 1..=30 {
+                    state.push_output("stdout".to_string(), format!("line {}\n", i));
+                }
+            }
+            session_ids.push(manager.insert_session(Arc::new(session)).await);
+        }
+        let handler = ToolsHandler::new(Arc::new(manager));
+        for session_id in &session_ids {
+            handler
+                .handle_tool(
+                    "debugger_continue",
+                    json!({"sessionId": session_id, "waitForStop": true}),
+                )
+                .await
+                .unwrap();
+        }
+        let (healthy, no_scopes, no_stack) = (&session_ids[0], &session_ids[1], &session_ids[2]);
 
-        let result = serde_json::from_value::<StackTraceArgs>(json);
-        assert!(result.is_err());
-    }
+        let snapshot = handler
+            .handle_tool(
+                "debugger_snapshot",
+                json!({"sessionId": healthy, "outputLines": 3}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(snapshot["state"], "Stopped");
+        assert_eq!(snapshot["details"]["reason"], "breakpoint");
+        assert_eq!(snapshot["stop"]["reason"], "breakpoint");
+        assert_eq!(snapshot["stackTrace"]["stackFrames"][0]["name"], "main");
+        let scopes = &snapshot["variables"][0]["scopes"];
+        assert_eq!(scopes[0]["variables"][0]["name"], "n");
+        assert_eq!(scopes[0]["variables"][0]["value"], "15");
+        assert_eq!(scopes[1]["expensive"], true);
+        assert_eq!(
+            snapshot["output"],
+            json!({"lines": ["line 28", "line 29", "line 30"], "totalLines": 30})
+        );
+        assert_eq!(snapshot["breakpoints"], json!([]));
+        assert!(snapshot.get("partial").is_none());
+
+        // Output is cut from the oldest line to fit maxLength
+        let small = handler
+            .handle_tool(
+                "debugger_snapshot",
+                json!({"sessionId": healthy, "outputLines": 3, "maxLength": 10}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(small["output"]["lines"], json!([" 29", "line 30"]));
+        assert_eq!(small["output"]["truncated"], true);
+
+        let snapshot = handler
+            .handle_tool("debugger_snapshot", json!({"sessionId": no_scopes}))
+            .await
+            .unwrap();
+        assert_eq!(snapshot["partial"], true);
+        assert_eq!(snapshot["stackTrace"]["stackFrames"][0]["name"], "main");
+        assert!(snapshot["variables"][0]["error"]
+            .as_str()
+            .unwrap()
+            .contains("scopes"));
+        assert_eq!(snapshot["output"]["lines"].as_array().unwrap().len(), 20);
+
+        let snapshot = handler
+            .handle_tool("debugger_snapshot", json!({"sessionId": no_stack}))
+            .await
+            .unwrap();
+        assert_eq!(snapshot["partial"], true);
+        assert!(snapshot["stackTrace"]["error"].is_string());
+        assert_eq!(snapshot["variables"], json!([]));
+        assert_eq!(snapshot["state"], "Stopped");
 
-    #[test]
-    fn test_evaluate_missing_session_id() {
-        let json = json!({
-            "expression": "x + y"
-        });
+        let result = handler
+            .handle_tool(
+                "debugger_snapshot",
+                json!({"sessionId": healthy, "variableFrames": 50}),
+            )
+            .await;
+        assert!(matches!(result, Err(Error::InvalidRequest(_))));
+    }
 
-        let result = serde_json::from_value::<EvaluateArgs>(json);
-        assert!(result.is_err());
+    #[tokio::test]
+    async fn test_recorded_session_transcript() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::recorder::{read_transcript, TranscriptEntry, REDACTED};
+        use crate::debug::session::DebugSession;
+        use crate::debug::RecordingConfig;
+
+        let dir = tempfile::tempdir().unwrap();
+        let manager = SessionManager::new().with_recording(
+            RecordingConfig::new(dir.path()).with_redact_keys(["expression".to_string()]),
+            true,
+        );
+        let client = DapClient::new_with_transport(
+            Box::new(ScriptedAdapter::new().stopping_on_resume()),
+            None,
+        )
+        .await
+        .unwrap();
+        let session = DebugSession::new("python".to_string(), "/app/main.py".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .initialize_and_launch("debugpy", json!({"program": "/app/main.py"}))
+            .await
+            .unwrap();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let handler = ToolsHandler::new(Arc::new(manager));
+
+        handler
+            .handle_tool(
+                "debugger_continue",
+                json!({"sessionId": session_id, "waitForStop": true}),
+            )
+            .await
+            .unwrap();
+        let _ = handler
+            .handle_tool(
+                "debugger_evaluate",
+                json!({"sessionId": session_id, "expression": "secret_key"}),
+            )
+            .await;
+        let recording = handler
+            .handle_tool("debugger_recording_path", json!({"sessionId": session_id}))
+            .await
+            .unwrap();
+        assert_eq!(recording["recording"], true);
+        let path = dir.path().join(format!("{}.jsonl", session_id));
+        assert_eq!(recording["path"], path.to_string_lossy().as_ref());
+        handler
+            .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+            .await
+            .unwrap();
+        // Calls without a recorded session are not written anywhere
+        handler
+            .handle_tool("debugger_list_configs", json!({}))
+            .await
+            .unwrap();
+
+        let entries = read_transcript(&path).unwrap();
+        let tools: Vec<&str> = entries
+            .iter()
+            .filter_map(|entry| match entry {
+                TranscriptEntry::ToolCall { tool, .. } => Some(tool.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            tools,
+            vec![
+                "debugger_continue",
+                "debugger_evaluate",
+                "debugger_recording_path",
+                "debugger_disconnect"
+            ]
+        );
+        assert!(entries.iter().any(|entry| matches!(
+            entry,
+            TranscriptEntry::StateChange { to, stop: Some(stop), .. }
+                if to["Stopped"]["reason"] == "breakpoint" && stop["reason"] == "breakpoint"
+        )));
+        assert!(entries.iter().any(|entry| matches!(
+            entry,
+            TranscriptEntry::ToolCall { tool, arguments, .. }
+                if tool == "debugger_evaluate" && arguments["expression"] == REDACTED
+        )));
     }
 
-    #[test]
-    fn test_evaluate_missing_expression() {
-        let json = json!({
-            "sessionId": "eval-session"
-        });
+    #[tokio::test]
+    async fn test_set_breakpoint_resolves_relative_path_against_session_cwd() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("app.py"), "x = 1\ny = 2\n").unwrap();
+        let cwd = dir.path().to_str().unwrap().to_string();
+
+        let client = DapClient::new_with_transport(Box::new(ScriptedAdapter::new()), None)
+            .await
+            .unwrap();
+        let session = DebugSession::new("python".to_string(), format!("{}/app.py", cwd), client)
+            .await
+            .unwrap()
+            .with_cwd(Some(cwd));
+        let manager = Arc::new(SessionManager::new());
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let handler = ToolsHandler::new(manager);
 
-        let result = serde_json::from_value::<EvaluateArgs>(json);
-        assert!(result.is_err());
+        let result = handler
+            .handle_tool(
+                "debugger_set_breakpoint",
+                json!({"sessionId": session_id, "sourcePath": "app.py", "line": 2}),
+            )
+            .await
+            .unwrap();
+        let expected = dir.path().canonicalize().unwrap().join("app.py");
+        assert_eq!(result["sourcePath"], expected.to_str().unwrap());
     }
 
-    #[test]
-    fn test_evaluate_invalid_frame_id_type() {
-        let json = json!({
-            "sessionId": "eval-session",
-            "expression": "x + y",
-            "frameId": "not a number"  // Should be integer
-        });
+    #[tokio::test]
+    async fn test_debugger_server_log_tail() {
+        let handler = ToolsHandler::new(Arc::new(SessionManager::new()));
+        crate::logging::server_log().push("first test line\nsecond test line\n");
+
+        let tail = handler
+            .handle_tool("debugger_server_log_tail", json!({"lines": 2}))
+            .await
+            .unwrap();
+        assert_eq!(tail["count"], 2);
+        assert_eq!(
+            tail["lines"],
+            json!(["first test line", "second test line"])
+        );
+        assert_eq!(tail["capacity"], crate::logging::RING_CAPACITY);
+
+        let error = handler
+            .handle_tool("debugger_server_log_tail", json!({"lines": 0}))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::InvalidRequest(_)));
+    }
 
-        let result = serde_json::from_value::<EvaluateArgs>(json);
-        assert!(result.is_err());
+    #[tokio::test]
+    async fn test_debugger_session_metrics_counts_requests_and_stops() {
+        use crate::dap::client::DapClient;
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+        use crate::debug::state::StopContext;
+
+        let client = DapClient::new_with_transport(
+            Box::new(ScriptedAdapter::new().failing_expression("boom")),
+            None,
+        )
+        .await
+        .unwrap();
+        // 'launch' makes the adapter send the 'initialized' event, which is
+        // read before the configurationDone response
+        client.send_request_nowait("launch", None).await.unwrap();
+        client
+            .send_request("configurationDone", None)
+            .await
+            .unwrap();
+        client.send_request("threads", None).await.unwrap();
+        client.send_request("threads", None).await.unwrap();
+        assert!(client.evaluate("boom", None).await.is_err());
+        let session = DebugSession::new("python".to_string(), "/app/a.py".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .state
+            .write()
+            .await
+            .record_stop(StopContext::from_event_body(
+                &json!({"reason": "breakpoint", "threadId": 1}),
+            ));
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let handler = ToolsHandler::new(Arc::new(manager));
+
+        let metrics = handler
+            .handle_tool("debugger_session_metrics", json!({"sessionId": session_id}))
+            .await
+            .unwrap();
+
+        assert_eq!(metrics["sessionId"], json!(session_id));
+        assert_eq!(metrics["requestsSent"], 5);
+        assert!(metrics["eventsReceived"].as_u64().unwrap() >= 1);
+        assert_eq!(metrics["stops"], 1);
+        assert_eq!(metrics["adapterSpawnMs"], Value::Null);
+        assert_eq!(metrics["writeQueueDepth"], 0);
+        let commands = metrics["commands"].as_array().unwrap();
+        let names: Vec<&str> = commands
+            .iter()
+            .map(|c| c["command"].as_str().unwrap())
+            .collect();
+        // evaluate without a frame looks up the top frame first
+        assert_eq!(
+            names,
+            vec!["configurationDone", "evaluate", "stackTrace", "threads"]
+        );
+        assert_eq!(commands[1]["failures"], 1);
+        assert_eq!(commands[3]["count"], 2);
     }
 
-    #[test]
-    fn test_disconnect_missing_session_id() {
-        let json = json!({});
+    #[tokio::test]
+    async fn test_hung_adapter_is_unhealthy_and_force_killed() {
+        use crate::dap::client::{DapClient, RequestTimeouts};
+        use crate::dap::testing::ScriptedAdapter;
+        use crate::debug::session::DebugSession;
+        use crate::debug::state::StopContext;
+        use std::sync::atomic::Ordering;
+
+        let adapter = ScriptedAdapter::new();
+        let silence = adapter.silence_switch();
+        let client = DapClient::new_with_transport(Box::new(adapter), None)
+            .await
+            .unwrap();
+        client.set_request_timeouts(RequestTimeouts::new(std::time::Duration::from_millis(20)));
+        client.send_request("threads", None).await.unwrap();
+        let session = DebugSession::new("ruby".to_string(), "/app/a.rb".to_string(), client)
+            .await
+            .unwrap();
+        session
+            .state
+            .write()
+            .await
+            .record_stop(StopContext::from_event_body(
+                &json!({"reason": "breakpoint", "threadId": 1}),
+            ));
+        let manager = SessionManager::new();
+        let session_id = manager.insert_session(Arc::new(session)).await;
+        let handler = ToolsHandler::new(Arc::new(manager));
+
+        let state = handler
+            .handle_tool("debugger_session_state", json!({"sessionId": session_id}))
+            .await
+            .unwrap();
+        assert_eq!(state["healthy"], true);
+
+        // The adapter process lives on but answers nothing
+        silence.store(true, Ordering::SeqCst);
+        for _ in 0..3 {
+            let result = handler
+                .handle_tool(
+                    "debugger_evaluate",
+                    json!({"sessionId": session_id, "expression": "x", "frameId": 1}),
+                )
+                .await;
+            assert!(result.unwrap_err().to_string().contains("timed out"));
+        }
+        let state = handler
+            .handle_tool("debugger_session_state", json!({"sessionId": session_id}))
+            .await
+            .unwrap();
+        assert_eq!(state["healthy"], false);
+        assert_eq!(state["consecutiveTimeouts"], 3);
+
+        let killed = handler
+            .handle_tool(
+                "debugger_force_kill",
+                json!({"sessionId": session_id, "reason": "rdbg hung"}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(killed["state"], "Failed");
+        assert_eq!(killed["details"]["error"], "Force-killed: rdbg hung");
+        assert_eq!(
+            handler.session_state_name(&session_id).await,
+            Some("Failed")
+        );
+    }
 
-        let result = serde_json::from_value::<DisconnectArgs>(json);
-        assert!(result.is_err());
+    #[tokio::test]
+    async fn test_debugger_doctor_filters_by_language() {
+        let manager = Arc::new(SessionManager::new());
+        let handler = ToolsHandler::new(manager);
+
+        let report = handler
+            .handle_tool("debugger_doctor", json!({"language": "golang"}))
+            .await
+            .unwrap();
+        let adapters = report["adapters"].as_array().unwrap();
+        assert_eq!(adapters.len(), 1);
+        assert_eq!(adapters[0]["language"], "go");
+        assert_eq!(adapters[0]["adapter"], "delve");
+
+        let result = handler
+            .handle_tool("debugger_doctor", json!({"language": "cobol"}))
+            .await;
+        assert!(matches!(result, Err(Error::AdapterNotFound(_))));
     }
 
     #[tokio::test]
-    async fn test_handle_tool_debugger_start_invalid_json() {
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
+    async fn test_debugger_attach_validation() {
+        let manager = Arc::new(SessionManager::new());
         let handler = ToolsHandler::new(manager);
 
-        // Missing required fields
         let result = handler
-            .handle_tool("debugger_start", json!({"language": "python"}))
+            .handle_tool("debugger_attach", json!({"language": "ruby", "port": 5678}))
             .await;
-        assert!(result.is_err());
+        match result {
+            Err(Error::InvalidRequest(msg)) => assert!(msg.contains("only supported for Python")),
+            other => panic!("Expected InvalidRequest, got {:?}", other),
+        }
+
+        let result = handler
+            .handle_tool("debugger_attach", json!({"language": "python", "port": 0}))
+            .await;
+        assert!(matches!(result, Err(Error::InvalidRequest(_))));
     }
 
     #[tokio::test]
     async fn test_handle_tool_set_breakpoint_invalid_json() {
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
+        let manager = Arc::new(SessionManager::new());
         let handler = ToolsHandler::new(manager);
 
         // Missing required fields
@@ -1065,7 +8427,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_handle_tool_continue_invalid_json() {
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
+        let manager = Arc::new(SessionManager::new());
         let handler = ToolsHandler::new(manager);
 
         // Missing required fields
@@ -1075,7 +8437,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_handle_tool_stack_trace_invalid_json() {
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
+        let manager = Arc::new(SessionManager::new());
         let handler = ToolsHandler::new(manager);
 
         // Missing required fields
@@ -1085,7 +8447,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_handle_tool_evaluate_invalid_json() {
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
+        let manager = Arc::new(SessionManager::new());
         let handler = ToolsHandler::new(manager);
 
         // Missing required fields
@@ -1095,9 +8457,20 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_handle_tool_get_output_unknown_session() {
+        let manager = Arc::new(SessionManager::new());
+        let handler = ToolsHandler::new(manager);
+
+        let result = handler
+            .handle_tool("debugger_get_output", json!({"sessionId": "missing"}))
+            .await;
+        assert!(matches!(result, Err(Error::SessionNotFound(_))));
+    }
+
     #[tokio::test]
     async fn test_handle_tool_disconnect_invalid_json() {
-        let manager = Arc::new(RwLock::new(SessionManager::new()));
+        let manager = Arc::new(SessionManager::new());
         let handler = ToolsHandler::new(manager);
 
         // Missing required fields
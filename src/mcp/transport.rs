@@ -1,8 +1,10 @@
 use super::protocol::JsonRpcMessage;
-use super::transport_trait::McpTransportTrait;
+use super::transport_trait::{McpReader, McpTransportTrait, McpWriter};
 use crate::{Error, Result};
 use async_trait::async_trait;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::TcpStream;
 use tracing::{debug, trace};
 
 pub struct StdioTransport {
@@ -25,46 +27,116 @@ impl StdioTransport {
     }
 
     pub async fn read_message(&mut self) -> Result<JsonRpcMessage> {
-        // MCP uses line-based JSON-RPC transport (not LSP's Content-Length headers)
-        // Each message is a single line terminated by \n
-        // See: https://spec.modelcontextprotocol.io/specification/basic/transports/#stdio
+        read_line_message(&mut self.stdin).await
+    }
 
-        let mut line = String::new();
-        let bytes_read = self.stdin.read_line(&mut line).await?;
+    pub async fn write_message(&mut self, msg: &JsonRpcMessage) -> Result<()> {
+        write_line_message(&mut self.stdout, msg).await
+    }
+}
 
-        if bytes_read == 0 {
-            return Err(Error::InvalidRequest("EOF reached".to_string()));
-        }
+/// TCP transport for MCP clients connecting over a socket
+///
+/// Uses the same newline-delimited JSON-RPC framing as [`StdioTransport`].
+pub struct TcpTransport {
+    reader: BufReader<OwnedReadHalf>,
+    writer: OwnedWriteHalf,
+}
 
-        let trimmed = line.trim();
-        if trimmed.is_empty() {
-            return Err(Error::InvalidRequest("Empty message line".to_string()));
+impl TcpTransport {
+    pub fn new(stream: TcpStream) -> Self {
+        let (read_half, write_half) = stream.into_split();
+        Self {
+            reader: BufReader::new(read_half),
+            writer: write_half,
         }
+    }
 
-        trace!("Reading MCP message: {} bytes", trimmed.len());
-        debug!("Received message: {}", trimmed);
-
-        let msg: JsonRpcMessage = serde_json::from_str(trimmed)?;
-        Ok(msg)
+    pub async fn read_message(&mut self) -> Result<JsonRpcMessage> {
+        read_line_message(&mut self.reader).await
     }
 
     pub async fn write_message(&mut self, msg: &JsonRpcMessage) -> Result<()> {
-        // MCP uses line-based JSON-RPC transport (not LSP's Content-Length headers)
-        // Each message is a single line terminated by \n
-        // See: https://spec.modelcontextprotocol.io/specification/basic/transports/#stdio
+        write_line_message(&mut self.writer, msg).await
+    }
+}
+
+/// Whether a read failed because the peer closed the connection
+pub fn is_eof(error: &Error) -> bool {
+    matches!(error, Error::InvalidRequest(msg) if msg == EOF_MESSAGE)
+}
+
+const EOF_MESSAGE: &str = "EOF reached";
 
-        let content = serde_json::to_string(msg)?;
-        debug!("Sending message: {}", content);
+/// Read one line-delimited JSON-RPC message
+async fn read_line_message<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<JsonRpcMessage> {
+    // MCP uses line-based JSON-RPC transport (not LSP's Content-Length headers)
+    // Each message is a single line terminated by \n
+    // See: https://spec.modelcontextprotocol.io/specification/basic/transports/#stdio
 
-        self.stdout.write_all(content.as_bytes()).await?;
-        self.stdout.write_all(b"\n").await?;
-        self.stdout.flush().await?;
+    let mut line = String::new();
+    let bytes_read = reader.read_line(&mut line).await?;
+
+    if bytes_read == 0 {
+        return Err(Error::InvalidRequest(EOF_MESSAGE.to_string()));
+    }
+
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+        return Err(Error::InvalidRequest("Empty message line".to_string()));
+    }
 
-        Ok(())
+    trace!("Reading MCP message: {} bytes", trimmed.len());
+    debug!("Received message: {}", trimmed);
+
+    let msg: JsonRpcMessage = serde_json::from_str(trimmed)?;
+    Ok(msg)
+}
+
+/// Serialize and write one line-delimited JSON-RPC message
+async fn write_line_message<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    msg: &JsonRpcMessage,
+) -> Result<()> {
+    // MCP uses line-based JSON-RPC transport (not LSP's Content-Length headers)
+    // Each message is a single line terminated by \n
+    // See: https://spec.modelcontextprotocol.io/specification/basic/transports/#stdio
+
+    let content = serde_json::to_string(msg)?;
+    debug!("Sending message: {}", content);
+
+    writer.write_all(content.as_bytes()).await?;
+    writer.write_all(b"\n").await?;
+    writer.flush().await?;
+
+    Ok(())
+}
+
+/// Read half of a split MCP transport
+pub struct McpReadHalf<R> {
+    reader: R,
+}
+
+/// Write half of a split MCP transport
+pub struct McpWriteHalf<W> {
+    writer: W,
+}
+
+#[async_trait]
+impl<R: AsyncBufRead + Unpin + Send> McpReader for McpReadHalf<R> {
+    async fn read_message(&mut self) -> Result<JsonRpcMessage> {
+        read_line_message(&mut self.reader).await
+    }
+}
+
+#[async_trait]
+impl<W: AsyncWrite + Unpin + Send> McpWriter for McpWriteHalf<W> {
+    async fn write_message(&mut self, msg: &JsonRpcMessage) -> Result<()> {
+        write_line_message(&mut self.writer, msg).await
     }
 }
 
-// Implement the trait for the concrete transport
+// Implement the trait for the concrete transports
 #[async_trait]
 impl McpTransportTrait for StdioTransport {
     async fn read_message(&mut self) -> Result<JsonRpcMessage> {
@@ -76,6 +148,37 @@ impl McpTransportTrait for StdioTransport {
         // Delegate to existing implementation
         self.write_message(msg).await
     }
+
+    fn split(self: Box<Self>) -> (Box<dyn McpReader>, Box<dyn McpWriter>) {
+        (
+            Box::new(McpReadHalf { reader: self.stdin }),
+            Box::new(McpWriteHalf {
+                writer: self.stdout,
+            }),
+        )
+    }
+}
+
+#[async_trait]
+impl McpTransportTrait for TcpTransport {
+    async fn read_message(&mut self) -> Result<JsonRpcMessage> {
+        self.read_message().await
+    }
+
+    async fn write_message(&mut self, msg: &JsonRpcMessage) -> Result<()> {
+        self.write_message(msg).await
+    }
+
+    fn split(self: Box<Self>) -> (Box<dyn McpReader>, Box<dyn McpWriter>) {
+        (
+            Box::new(McpReadHalf {
+                reader: self.reader,
+            }),
+            Box::new(McpWriteHalf {
+                writer: self.writer,
+            }),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -355,7 +458,7 @@ mod tests {
     // Phase 5: Real transport implementation tests (not just mocks)
     // These tests exercise the actual read_message() and write_message() code
 
-    // Helper struct for testing that mirrors StdioTransport but accepts generic readers/writers
+    // Helper struct for testing that wraps the shared framing helpers with in-memory readers/writers
     struct TestTransport<R, W> {
         stdin: BufReader<R>,
         stdout: W,
@@ -367,28 +470,11 @@ mod tests {
         W: tokio::io::AsyncWrite + Unpin,
     {
         async fn read_message(&mut self) -> Result<JsonRpcMessage> {
-            let mut line = String::new();
-            let bytes_read = self.stdin.read_line(&mut line).await?;
-
-            if bytes_read == 0 {
-                return Err(Error::InvalidRequest("EOF reached".to_string()));
-            }
-
-            let trimmed = line.trim();
-            if trimmed.is_empty() {
-                return Err(Error::InvalidRequest("Empty message line".to_string()));
-            }
-
-            let msg: JsonRpcMessage = serde_json::from_str(trimmed)?;
-            Ok(msg)
+            read_line_message(&mut self.stdin).await
         }
 
         async fn write_message(&mut self, msg: &JsonRpcMessage) -> Result<()> {
-            let content = serde_json::to_string(msg)?;
-            self.stdout.write_all(content.as_bytes()).await?;
-            self.stdout.write_all(b"\n").await?;
-            self.stdout.flush().await?;
-            Ok(())
+            write_line_message(&mut self.stdout, msg).await
         }
     }
 
@@ -584,4 +670,39 @@ mod tests {
         let transport = StdioTransport::default();
         drop(transport);
     }
+
+    #[tokio::test]
+    async fn test_tcp_transport_round_trip() {
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let accept = tokio::spawn(async move { listener.accept().await.unwrap().0 });
+
+        let mut client = TcpTransport::new(TcpStream::connect(addr).await.unwrap());
+        let mut server = TcpTransport::new(accept.await.unwrap());
+
+        let request = JsonRpcMessage::Request(JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: json!(7),
+            method: "tools/list".to_string(),
+            params: None,
+        });
+        client.write_message(&request).await.unwrap();
+
+        match server.read_message().await.unwrap() {
+            JsonRpcMessage::Request(req) => {
+                assert_eq!(req.id, json!(7));
+                assert_eq!(req.method, "tools/list");
+            }
+            other => panic!("Expected Request, got: {:?}", other),
+        }
+
+        // Closing the client surfaces as EOF on the server side
+        drop(client);
+        assert!(matches!(
+            server.read_message().await,
+            Err(Error::InvalidRequest(msg)) if msg == "EOF reached"
+        ));
+    }
 }
@@ -1,6 +1,28 @@
 use super::protocol::JsonRpcMessage;
 use crate::Result;
 use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Read half of an MCP transport
+///
+/// Owned exclusively by the server's read loop, so a pending read never
+/// holds up responses being written.
+#[async_trait]
+pub trait McpReader: Send {
+    /// Read the next JSON-RPC message
+    async fn read_message(&mut self) -> Result<JsonRpcMessage>;
+}
+
+/// Write half of an MCP transport
+///
+/// Owned exclusively by the server's writer task, which serializes writes so
+/// concurrent responses never interleave mid-line.
+#[async_trait]
+pub trait McpWriter: Send {
+    /// Write a JSON-RPC message
+    async fn write_message(&mut self, msg: &JsonRpcMessage) -> Result<()>;
+}
 
 /// Trait for MCP transport layer to enable testing with mocks
 #[async_trait]
@@ -10,4 +32,38 @@ pub trait McpTransportTrait: Send + Sync {
 
     /// Write a JSON-RPC message to the transport
     async fn write_message(&mut self, msg: &JsonRpcMessage) -> Result<()>;
+
+    /// Split the transport into independently owned read and write halves
+    ///
+    /// The default implementation shares the transport behind a lock, which is
+    /// only suitable for transports whose reads return promptly (e.g. test
+    /// doubles). Real transports override this with a true split so a pending
+    /// read never blocks a write.
+    fn split(self: Box<Self>) -> (Box<dyn McpReader>, Box<dyn McpWriter>)
+    where
+        Self: 'static,
+    {
+        let shared = Arc::new(Mutex::new(self));
+        (
+            Box::new(SharedHalf(shared.clone())),
+            Box::new(SharedHalf(shared)),
+        )
+    }
+}
+
+/// Half of a transport that could not be split natively
+struct SharedHalf<T: ?Sized>(Arc<Mutex<Box<T>>>);
+
+#[async_trait]
+impl<T: McpTransportTrait + ?Sized> McpReader for SharedHalf<T> {
+    async fn read_message(&mut self) -> Result<JsonRpcMessage> {
+        self.0.lock().await.read_message().await
+    }
+}
+
+#[async_trait]
+impl<T: McpTransportTrait + ?Sized> McpWriter for SharedHalf<T> {
+    async fn write_message(&mut self, msg: &JsonRpcMessage) -> Result<()> {
+        self.0.lock().await.write_message(msg).await
+    }
 }
@@ -1 +1,135 @@
-// Process management will be implemented here
+//! Platform differences in finding and ending adapter processes
+//!
+//! On Windows, executables carry an extension (`codelldb.exe`, `rdbg.bat`)
+//! that `Command::new` only adds by itself for `.exe`, and killing a process
+//! does not end the processes it started; `taskkill /T` ends the whole tree.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use tokio::process::Child;
+use tracing::warn;
+
+/// File names under which the executable `name` can be installed
+///
+/// Windows tries the extensions of `PATHEXT` (`.exe`, `.bat`, `.cmd`, ...)
+/// before the bare name; other platforms only the bare name.
+pub fn executable_names(name: &str) -> Vec<String> {
+    if !cfg!(windows) || Path::new(name).extension().is_some() {
+        return vec![name.to_string()];
+    }
+    let pathext = std::env::var("PATHEXT").unwrap_or_else(|_| ".EXE;.BAT;.CMD".to_string());
+    pathext
+        .split(';')
+        .filter(|ext| !ext.is_empty())
+        .map(|ext| format!("{}{}", name, ext.to_lowercase()))
+        .chain(std::iter::once(name.to_string()))
+        .collect()
+}
+
+/// The first file named like the executable `name` in the directories of
+/// `path` (a `PATH`-style list)
+pub fn find_in_path(name: &str, path: Option<OsString>) -> Option<PathBuf> {
+    let dirs: Vec<PathBuf> = path
+        .map(|p| std::env::split_paths(&p).collect())
+        .unwrap_or_default();
+    find_in_dirs(name, dirs)
+}
+
+/// The first file named like the executable `name` in `dirs`
+pub fn find_in_dirs(name: &str, dirs: impl IntoIterator<Item = PathBuf>) -> Option<PathBuf> {
+    let names = executable_names(name);
+    dirs.into_iter()
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Find the executable `name` on `PATH`
+pub fn which(name: &str) -> Option<PathBuf> {
+    find_in_path(name, std::env::var_os("PATH"))
+}
+
+/// Start killing `child`, on Windows together with the processes it started
+///
+/// Adapters such as CodeLLDB and vscode-js-debug run the debuggee as their
+/// own child, which Windows does not end along with its parent.
+pub fn kill_tree(child: &mut Child) -> std::io::Result<()> {
+    if cfg!(windows) {
+        if let Some(pid) = child.id() {
+            let taskkill = std::process::Command::new("taskkill")
+                .args(["/T", "/F", "/PID", &pid.to_string()])
+                .output();
+            if let Err(e) = taskkill {
+                warn!("⚠️  taskkill failed for process {}: {}", pid, e);
+            }
+        }
+    }
+    child.start_kill()
+}
+
+/// Kill the process `pid` (on Windows with the processes it started)
+///
+/// For processes the server did not spawn itself, such as the debuggee an
+/// adapter launched, which the server only knows by the PID the adapter
+/// reported.
+pub fn kill_pid(pid: u32) -> std::io::Result<()> {
+    let pid = pid.to_string();
+    let output = if cfg!(windows) {
+        std::process::Command::new("taskkill")
+            .args(["/T", "/F", "/PID", &pid])
+            .output()?
+    } else {
+        std::process::Command::new("kill")
+            .args(["-KILL", &pid])
+            .output()?
+    };
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_in_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_name = if cfg!(windows) { "tool.exe" } else { "tool" };
+        std::fs::write(dir.path().join(file_name), "").unwrap();
+        let path = std::env::join_paths([Path::new("/nonexistent"), dir.path()]).unwrap();
+
+        assert_eq!(
+            find_in_path("tool", Some(path.clone())),
+            Some(dir.path().join(file_name))
+        );
+        assert_eq!(find_in_path("missing", Some(path)), None);
+        assert_eq!(find_in_path("tool", None), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_kill_pid() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        kill_pid(child.id()).unwrap();
+        assert!(!child.wait().unwrap().success());
+    }
+
+    #[test]
+    fn test_executable_names() {
+        assert_eq!(executable_names("rdbg.bat"), vec!["rdbg.bat"]);
+        let names = executable_names("rdbg");
+        assert_eq!(names.last().map(String::as_str), Some("rdbg"));
+        if cfg!(windows) {
+            assert!(names.contains(&"rdbg.bat".to_string()));
+        } else {
+            assert_eq!(names.len(), 1);
+        }
+    }
+}
@@ -1,13 +1,35 @@
-/// Fake DAP Adapter for Integration Testing.
+/// Fake DAP adapter for deterministic integration tests
 ///
-/// This is a minimal DAP adapter implementation that responds to DAP protocol
-/// requests for testing purposes. It doesn't actually debug anything, but
-/// simulates the protocol correctly.
+/// Speaks DAP over STDIO like debugpy, but debugs nothing: the program given
+/// in the launch request is a scenario file (JSON) listing, in execution
+/// order, the lines the "program" runs and its stack at each of them:
+///
+/// ```json
+/// {
+///   "steps": [
+///     {"line": 3, "frames": [{"name": "main", "locals": {"total": "0"}}]},
+///     {"line": 8, "frames": [
+///       {"name": "add", "locals": {"a": "1", "b": "2"}},
+///       {"name": "main", "line": 4, "locals": {"total": "0"}}
+///     ]}
+///   ]
+/// }
+/// ```
+///
+/// The top frame is at the step's line; callers give their own `line`.
+/// After the last step the program writes the optional `stderr` lines and
+/// exits with `exitCode` (default 0).
+/// `continue` runs to the next step on a breakpoint line (or exits after the
+/// last step), `next`/`stepIn` move one step, and `stepOut` moves to the next
+/// step with a shallower stack. Frame ids change with every stop, and
+/// `evaluate` looks the expression up in the locals of the given frame (the
+/// top frame without one).
+use serde::Deserialize;
 use serde_json::{json, Value};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashSet};
 use std::io::{self, BufRead, Write};
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Deserialize)]
 struct Message {
     seq: Option<i32>,
     #[serde(rename = "type")]
@@ -16,16 +38,59 @@ struct Message {
     arguments: Option<Value>,
 }
 
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Scenario {
+    steps: Vec<Step>,
+    #[serde(default)]
+    exit_code: i64,
+    #[serde(default)]
+    stderr: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Step {
+    line: i64,
+    frames: Vec<Frame>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Frame {
+    name: String,
+    /// Line of a caller frame; the top frame is at the step's line
+    line: Option<i64>,
+    #[serde(default)]
+    locals: BTreeMap<String, String>,
+}
+
+/// Frame ids of one stop are `stop * FRAME_ID_STRIDE + index + 1`
+const FRAME_ID_STRIDE: i64 = 1000;
+
 struct FakeDapAdapter {
     seq: i32,
-    breakpoints: HashMap<String, Vec<i32>>,
+    program: String,
+    scenario: Scenario,
+    breakpoints: HashSet<i64>,
+    /// Index of the current step, once the program started
+    position: Option<usize>,
+    /// Stops so far; frame ids are only valid for the latest
+    stops: i64,
+    /// Deferred launch request: answered after configurationDone
+    launch_seq: Option<i32>,
+    stop_on_entry: bool,
 }
 
 impl FakeDapAdapter {
     fn new() -> Self {
         Self {
             seq: 1,
-            breakpoints: HashMap::new(),
+            program: String::new(),
+            scenario: Scenario::default(),
+            breakpoints: HashSet::new(),
+            position: None,
+            stops: 0,
+            launch_seq: None,
+            stop_on_entry: false,
         }
     }
 
@@ -49,6 +114,20 @@ impl FakeDapAdapter {
         self.write_message(&response);
     }
 
+    fn send_error(&mut self, request_seq: i32, command: &str, message: &str) {
+        let response = json!({
+            "seq": self.seq,
+            "type": "response",
+            "request_seq": request_seq,
+            "command": command,
+            "success": false,
+            "message": message
+        });
+
+        self.seq += 1;
+        self.write_message(&response);
+    }
+
     fn send_event(&mut self, event: &str, body: Option<Value>) {
         let event_msg = json!({
             "seq": self.seq,
@@ -72,44 +151,88 @@ impl FakeDapAdapter {
     fn handle_initialize(&mut self, request_seq: i32) {
         let capabilities = json!({
             "supportsConfigurationDoneRequest": true,
-            "supportsFunctionBreakpoints": false,
-            "supportsConditionalBreakpoints": true,
-            "supportsHitConditionalBreakpoints": false,
+            "supportsConditionalBreakpoints": false,
             "supportsEvaluateForHovers": true,
-            "supportsStepBack": false,
-            "supportsSetVariable": true,
-            "supportsRestartFrame": false,
-            "supportsGotoTargetsRequest": false,
-            "supportsStepInTargetsRequest": false,
-            "supportsCompletionsRequest": true,
-            "supportsModulesRequest": false,
-            "supportsRestartRequest": false,
-            "supportsExceptionOptions": false,
-            "supportsValueFormattingOptions": true,
-            "supportsExceptionInfoRequest": true,
-            "supportTerminateDebuggee": true,
             "supportsDelayedStackTraceLoading": true,
-            "supportsLoadedSourcesRequest": false,
-            "supportsLogPoints": true,
-            "supportsTerminateThreadsRequest": false,
-            "supportsSetExpression": true,
-            "supportsTerminateRequest": true,
-            "supportsDataBreakpoints": false,
-            "supportsReadMemoryRequest": false,
-            "supportsDisassembleRequest": false,
-            "supportsCancelRequest": false,
-            "supportsBreakpointLocationsRequest": false,
-            "supportsClipboardContext": false
+            "supportTerminateDebuggee": true
         });
 
         self.send_response(request_seq, "initialize", true, Some(capabilities));
-        self.send_event("initialized", None);
     }
 
-    fn handle_launch(&mut self, request_seq: i32, _args: Option<Value>) {
-        self.send_response(request_seq, "launch", true, None);
+    /// Load the scenario; like debugpy, 'initialized' follows the launch
+    /// request and the launch response waits for configurationDone
+    fn handle_launch(&mut self, request_seq: i32, args: Option<Value>) {
+        let args = args.unwrap_or_default();
+        self.program = args["program"].as_str().unwrap_or_default().to_string();
+        self.stop_on_entry = args["stopOnEntry"].as_bool().unwrap_or(false);
+
+        let scenario = std::fs::read_to_string(&self.program)
+            .map_err(|e| e.to_string())
+            .and_then(|text| serde_json::from_str::<Scenario>(&text).map_err(|e| e.to_string()));
+        match scenario {
+            Ok(scenario) if !scenario.steps.is_empty() => {
+                self.scenario = scenario;
+                self.launch_seq = Some(request_seq);
+                self.send_event("initialized", None);
+            }
+            Ok(_) => self.send_error(request_seq, "launch", "Scenario has no steps"),
+            Err(e) => {
+                let message = format!("Cannot load scenario {}: {}", self.program, e);
+                self.send_error(request_seq, "launch", &message);
+            }
+        }
+    }
+
+    fn handle_set_breakpoints(&mut self, request_seq: i32, args: Option<Value>) {
+        let requested: Vec<i64> = args
+            .as_ref()
+            .and_then(|args| args.get("breakpoints"))
+            .and_then(|bps| bps.as_array())
+            .map(|bps| bps.iter().filter_map(|bp| bp["line"].as_i64()).collect())
+            .unwrap_or_default();
+
+        // One source: the scenario's lines
+        self.breakpoints = requested.iter().copied().collect();
+        let breakpoints: Vec<Value> = requested
+            .iter()
+            .enumerate()
+            .map(|(index, &line)| {
+                if self.scenario.steps.iter().any(|step| step.line == line) {
+                    json!({"id": index + 1, "verified": true, "line": line})
+                } else {
+                    json!({
+                        "id": index + 1,
+                        "verified": false,
+                        "line": line,
+                        "message": "No code at this line"
+                    })
+                }
+            })
+            .collect();
+
+        self.send_response(
+            request_seq,
+            "setBreakpoints",
+            true,
+            Some(json!({ "breakpoints": breakpoints })),
+        );
+    }
+
+    fn handle_configuration_done(&mut self, request_seq: i32) {
+        self.send_response(request_seq, "configurationDone", true, None);
+        if let Some(launch_seq) = self.launch_seq.take() {
+            self.send_response(launch_seq, "launch", true, None);
+        }
 
-        // Send a thread event to indicate the process started
+        self.send_event(
+            "process",
+            Some(json!({
+                "name": self.program,
+                "isLocalProcess": true,
+                "startMethod": "launch"
+            })),
+        );
         self.send_event(
             "thread",
             Some(json!({
@@ -117,102 +240,145 @@ impl FakeDapAdapter {
                 "threadId": 1
             })),
         );
+
+        if self.stop_on_entry {
+            self.stop_at(0, "entry");
+        } else {
+            self.run_from(0);
+        }
     }
 
-    fn handle_set_breakpoints(&mut self, request_seq: i32, args: Option<Value>) {
-        if let Some(args) = args {
-            let source_path = args
-                .get("source")
-                .and_then(|s| s.get("path"))
-                .and_then(|p| p.as_str())
-                .unwrap_or("unknown");
-
-            let breakpoints = args
-                .get("breakpoints")
-                .and_then(|b| b.as_array())
-                .map(|arr| {
-                    arr.iter()
-                        .enumerate()
-                        .map(|(id, bp)| {
-                            let line = bp.get("line").and_then(|l| l.as_i64()).unwrap_or(0) as i32;
-
-                            // Store breakpoint
-                            self.breakpoints
-                                .entry(source_path.to_string())
-                                .or_default()
-                                .push(line);
-
-                            json!({
-                                "id": id + 1,
-                                "verified": true,
-                                "line": line
-                            })
-                        })
-                        .collect::<Vec<_>>()
-                })
-                .unwrap_or_default();
+    /// Stop at the first step from `start` on a breakpoint line, or exit
+    fn run_from(&mut self, start: usize) {
+        let hit = (start..self.scenario.steps.len())
+            .find(|&index| self.breakpoints.contains(&self.scenario.steps[index].line));
+        match hit {
+            Some(index) => self.stop_at(index, "breakpoint"),
+            None => self.exit(),
+        }
+    }
 
-            self.send_response(
-                request_seq,
-                "setBreakpoints",
-                true,
-                Some(json!({
-                    "breakpoints": breakpoints
-                })),
+    fn stop_at(&mut self, index: usize, reason: &str) {
+        self.position = Some(index);
+        self.stops += 1;
+        self.send_event(
+            "stopped",
+            Some(json!({
+                "reason": reason,
+                "threadId": 1,
+                "allThreadsStopped": true
+            })),
+        );
+    }
+
+    fn exit(&mut self) {
+        self.position = None;
+        for line in self.scenario.stderr.clone() {
+            self.send_event(
+                "output",
+                Some(json!({ "category": "stderr", "output": format!("{}\n", line) })),
             );
+        }
+        let exit_code = self.scenario.exit_code;
+        self.send_event("exited", Some(json!({ "exitCode": exit_code })));
+        self.send_event("terminated", None);
+    }
+
+    /// Move to step `index` with `reason`, exiting past the last step
+    fn step_to(&mut self, index: usize, reason: &str) {
+        if index < self.scenario.steps.len() {
+            self.stop_at(index, reason);
         } else {
-            self.send_response(request_seq, "setBreakpoints", false, None);
+            self.exit();
         }
     }
 
-    fn handle_configuration_done(&mut self, request_seq: i32) {
-        self.send_response(request_seq, "configurationDone", true, None);
+    fn handle_resume(&mut self, request_seq: i32, command: &str) {
+        let Some(position) = self.position else {
+            self.send_error(request_seq, command, "Program is not stopped");
+            return;
+        };
+
+        let body = (command == "continue").then(|| json!({ "allThreadsContinued": true }));
+        self.send_response(request_seq, command, true, body);
+        self.send_event(
+            "continued",
+            Some(json!({"threadId": 1, "allThreadsContinued": true})),
+        );
+
+        match command {
+            "continue" => self.run_from(position + 1),
+            "stepOut" => {
+                let depth = self.scenario.steps[position].frames.len();
+                let caller = (position + 1..self.scenario.steps.len())
+                    .find(|&index| self.scenario.steps[index].frames.len() < depth)
+                    .unwrap_or(self.scenario.steps.len());
+                self.step_to(caller, "step");
+            }
+            _ => self.step_to(position + 1, "step"),
+        }
+    }
+
+    fn handle_pause(&mut self, request_seq: i32) {
+        self.send_response(request_seq, "pause", true, None);
+        if let Some(position) = self.position {
+            self.stop_at(position, "pause");
+        }
     }
 
-    fn handle_continue(&mut self, request_seq: i32, _args: Option<Value>) {
+    fn handle_threads(&mut self, request_seq: i32) {
         self.send_response(
             request_seq,
-            "continue",
+            "threads",
             true,
-            Some(json!({
-                "allThreadsContinued": true
-            })),
+            Some(json!({"threads": [{"id": 1, "name": "MainThread"}]})),
         );
+    }
 
-        // Simulate hitting a breakpoint
-        self.send_event(
-            "stopped",
-            Some(json!({
-                "reason": "breakpoint",
-                "threadId": 1,
-                "allThreadsStopped": true
-            })),
-        );
+    /// Frame `frame_id` of the current stop
+    fn frame(&self, frame_id: i64) -> Option<&Frame> {
+        let step = &self.scenario.steps[self.position?];
+        let index = frame_id - self.stops * FRAME_ID_STRIDE - 1;
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| step.frames.get(index))
     }
 
-    fn handle_stack_trace(&mut self, request_seq: i32, _args: Option<Value>) {
-        let stack_frames = vec![
-            json!({
-                "id": 1,
-                "name": "main",
-                "source": {
-                    "name": "test.py",
-                    "path": "/test/test.py"
-                },
-                "line": 10,
-                "column": 1
-            }),
-            json!({
-                "id": 2,
-                "name": "<module>",
-                "source": {
-                    "name": "test.py",
-                    "path": "/test/test.py"
-                },
-                "line": 1,
-                "column": 1
-            }),
-        ];
+    fn handle_stack_trace(&mut self, request_seq: i32, args: Option<Value>) {
+        let Some(position) = self.position else {
+            self.send_error(request_seq, "stackTrace", "Program is not stopped");
+            return;
+        };
+        let args = args.unwrap_or_default();
+        let start = args["startFrame"].as_u64().unwrap_or(0) as usize;
+        let levels = match args["levels"].as_u64() {
+            Some(levels) if levels > 0 => levels as usize,
+            _ => usize::MAX,
+        };
+
+        let step = &self.scenario.steps[position];
+        let stack_frames: Vec<Value> = step
+            .frames
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(levels)
+            .map(|(index, frame)| {
+                let line = if index == 0 {
+                    step.line
+                } else {
+                    frame.line.unwrap_or(1)
+                };
+                json!({
+                    "id": self.stops * FRAME_ID_STRIDE + index as i64 + 1,
+                    "name": frame.name,
+                    "source": {"name": "scenario", "path": self.program},
+                    "line": line,
+                    "column": 1
+                })
+            })
+            .collect();
+        let total_frames = step.frames.len();
 
         self.send_response(
             request_seq,
@@ -220,52 +386,89 @@ impl FakeDapAdapter {
             true,
             Some(json!({
                 "stackFrames": stack_frames,
-                "totalFrames": 2
+                "totalFrames": total_frames
+            })),
+        );
+    }
+
+    fn handle_scopes(&mut self, request_seq: i32, args: Option<Value>) {
+        let frame_id = args.unwrap_or_default()["frameId"].as_i64().unwrap_or(0);
+        if self.frame(frame_id).is_none() {
+            self.send_error(request_seq, "scopes", "Invalid frame id");
+            return;
+        }
+        self.send_response(
+            request_seq,
+            "scopes",
+            true,
+            Some(json!({
+                "scopes": [
+                    {"name": "Locals", "variablesReference": frame_id, "expensive": false}
+                ]
             })),
         );
     }
 
+    fn handle_variables(&mut self, request_seq: i32, args: Option<Value>) {
+        let reference = args.unwrap_or_default()["variablesReference"]
+            .as_i64()
+            .unwrap_or(0);
+        let variables: Vec<Value> = self
+            .frame(reference)
+            .map(|frame| {
+                frame
+                    .locals
+                    .iter()
+                    .map(|(name, value)| {
+                        json!({"name": name, "value": value, "variablesReference": 0})
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.send_response(
+            request_seq,
+            "variables",
+            true,
+            Some(json!({ "variables": variables })),
+        );
+    }
+
     fn handle_evaluate(&mut self, request_seq: i32, args: Option<Value>) {
-        if let Some(args) = args {
-            let expression = args
-                .get("expression")
-                .and_then(|e| e.as_str())
-                .unwrap_or("");
-
-            // Simple evaluation - just return the expression with a fake result
-            let result = match expression {
-                "x" => "42",
-                "y" => "10",
-                _ => "None",
-            };
-
-            self.send_response(
+        let args = args.unwrap_or_default();
+        let expression = args["expression"].as_str().unwrap_or_default();
+        let frame_id = args["frameId"]
+            .as_i64()
+            .unwrap_or(self.stops * FRAME_ID_STRIDE + 1);
+
+        let Some(frame) = self.frame(frame_id) else {
+            self.send_error(request_seq, "evaluate", "Invalid frame id");
+            return;
+        };
+        match frame.locals.get(expression).cloned() {
+            Some(value) => self.send_response(
                 request_seq,
                 "evaluate",
                 true,
                 Some(json!({
-                    "result": result,
-                    "type": "int",
+                    "result": value,
                     "variablesReference": 0
                 })),
-            );
-        } else {
-            self.send_response(request_seq, "evaluate", false, None);
+            ),
+            None => {
+                let message = format!("NameError: name '{}' is not defined", expression);
+                self.send_error(request_seq, "evaluate", &message);
+            }
         }
     }
 
-    fn handle_disconnect(&mut self, request_seq: i32, _args: Option<Value>) {
-        self.send_response(request_seq, "disconnect", true, None);
+    /// Answer, end the "program" and stop reading
+    fn handle_disconnect(&mut self, request_seq: i32, command: &str) {
+        self.send_response(request_seq, command, true, None);
         self.send_event("terminated", None);
-        self.send_event(
-            "exited",
-            Some(json!({
-                "exitCode": 0
-            })),
-        );
     }
 
-    fn handle_request(&mut self, msg: Message) {
+    /// Returns false once the session is over
+    fn handle_request(&mut self, msg: Message) -> bool {
         let request_seq = msg.seq.unwrap_or(0);
         let command = msg.command.as_deref().unwrap_or("");
 
@@ -273,16 +476,31 @@ impl FakeDapAdapter {
             "initialize" => self.handle_initialize(request_seq),
             "launch" => self.handle_launch(request_seq, msg.arguments),
             "setBreakpoints" => self.handle_set_breakpoints(request_seq, msg.arguments),
+            "setExceptionBreakpoints" | "setFunctionBreakpoints" => self.send_response(
+                request_seq,
+                command,
+                true,
+                Some(json!({ "breakpoints": [] })),
+            ),
             "configurationDone" => self.handle_configuration_done(request_seq),
-            "continue" => self.handle_continue(request_seq, msg.arguments),
+            "continue" | "next" | "stepIn" | "stepOut" => self.handle_resume(request_seq, command),
+            "pause" => self.handle_pause(request_seq),
+            "threads" => self.handle_threads(request_seq),
             "stackTrace" => self.handle_stack_trace(request_seq, msg.arguments),
+            "scopes" => self.handle_scopes(request_seq, msg.arguments),
+            "variables" => self.handle_variables(request_seq, msg.arguments),
             "evaluate" => self.handle_evaluate(request_seq, msg.arguments),
-            "disconnect" | "terminate" => self.handle_disconnect(request_seq, msg.arguments),
+            "disconnect" | "terminate" => {
+                self.handle_disconnect(request_seq, command);
+                return command == "terminate";
+            }
             _ => {
                 eprintln!("Unknown command: {}", command);
-                self.send_response(request_seq, command, false, None);
+                let message = format!("Unsupported command: {}", command);
+                self.send_error(request_seq, command, &message);
             }
         }
+        true
     }
 
     fn run(&mut self) {
@@ -294,7 +512,7 @@ impl FakeDapAdapter {
             let mut headers = String::new();
             loop {
                 let mut line = String::new();
-                if reader.read_line(&mut line).unwrap() == 0 {
+                if reader.read_line(&mut line).unwrap_or(0) == 0 {
                     return; // EOF
                 }
 
@@ -319,14 +537,14 @@ impl FakeDapAdapter {
 
             // Read content
             let mut buffer = vec![0u8; content_length];
-            io::Read::read_exact(&mut reader, &mut buffer).unwrap();
-
-            let content = String::from_utf8(buffer).unwrap();
+            if io::Read::read_exact(&mut reader, &mut buffer).is_err() {
+                return;
+            }
 
             // Parse message
-            if let Ok(msg) = serde_json::from_str::<Message>(&content) {
-                if msg.msg_type == "request" {
-                    self.handle_request(msg);
+            if let Ok(msg) = serde_json::from_slice::<Message>(&buffer) {
+                if msg.msg_type == "request" && !self.handle_request(msg) {
+                    return;
                 }
             }
         }
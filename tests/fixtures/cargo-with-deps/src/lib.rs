@@ -0,0 +1,8 @@
+// Library half of the fixture: code under test for tests/describe.rs
+
+/// Describe a person as "Name (age, group)"
+pub fn describe(name: &str, age: u32) -> String {
+    // BUG: 18-year-olds should count as adults (should be >= 18)
+    let group = if age > 18 { "adult" } else { "minor" };
+    format!("{} ({}, {})", name, age, group)
+}
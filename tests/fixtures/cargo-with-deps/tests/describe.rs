@@ -0,0 +1,8 @@
+// Integration test that fails because of the bug in describe()
+use cargo_with_deps::describe;
+
+#[test]
+fn test_describe_adult() {
+    let description = describe("Carol", 18);
+    assert_eq!(description, "Carol (18, adult)");
+}
@@ -0,0 +1,6 @@
+// Workspace member "alpha"
+
+fn main() {
+    let total: u32 = (1..=3).sum();
+    println!("alpha: {}", total);
+}
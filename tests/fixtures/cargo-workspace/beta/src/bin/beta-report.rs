@@ -0,0 +1,5 @@
+// Second binary of the "beta" package
+
+fn main() {
+    println!("beta-report");
+}
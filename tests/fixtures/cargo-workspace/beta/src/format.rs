@@ -0,0 +1,5 @@
+// Module shared by beta's main binary
+
+pub fn banner(name: &str) -> String {
+    format!("== {} ==", name)
+}
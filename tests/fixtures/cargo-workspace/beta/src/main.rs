@@ -0,0 +1,7 @@
+// Workspace member "beta" (also has a second binary in src/bin/)
+mod format;
+
+fn main() {
+    let message = format::banner("beta");
+    println!("{}", message);
+}
@@ -0,0 +1,14 @@
+/* Fixture for core dump analysis: dereferences NULL in crash() */
+#include <stdio.h>
+
+static int crash(int *value, int depth) {
+    int local = depth * 2;
+    return *value + local; /* line 6: segfaults */
+}
+
+int main(void) {
+    int *missing = NULL;
+    printf("about to crash\n");
+    fflush(stdout);
+    return crash(missing, 21);
+}
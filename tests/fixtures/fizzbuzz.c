@@ -0,0 +1,23 @@
+// FizzBuzz implementation with a deliberate bug for testing
+// Bug: Line 10 checks n % 4 instead of n % 5
+#include <stdio.h>
+
+const char *fizzbuzz(int n, char *buf, size_t len) {
+    if (n % 15 == 0) {
+        return "FizzBuzz";
+    } else if (n % 3 == 0) {
+        return "Fizz";
+    } else if (n % 4 == 0) {  // BUG: Should be n % 5
+        return "Buzz";
+    }
+    snprintf(buf, len, "%d", n);
+    return buf;
+}
+
+int main(void) {
+    char buf[16];
+    for (int i = 1; i <= 100; i++) {
+        printf("%d: %s\n", i, fizzbuzz(i, buf, sizeof buf));
+    }
+    return 0;
+}
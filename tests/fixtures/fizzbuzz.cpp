@@ -0,0 +1,22 @@
+// FizzBuzz implementation with a deliberate bug for testing
+// Bug: Line 11 checks n % 4 instead of n % 5
+#include <iostream>
+#include <string>
+
+std::string fizzbuzz(int n) {
+    if (n % 15 == 0) {
+        return "FizzBuzz";
+    } else if (n % 3 == 0) {
+        return "Fizz";
+    } else if (n % 4 == 0) {  // BUG: Should be n % 5
+        return "Buzz";
+    }
+    return std::to_string(n);
+}
+
+int main() {
+    for (int i = 1; i <= 100; i++) {
+        std::cout << i << ": " << fizzbuzz(i) << std::endl;
+    }
+    return 0;
+}
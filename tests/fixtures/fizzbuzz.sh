@@ -0,0 +1,21 @@
+#!/usr/bin/env bash
+# FizzBuzz implementation with a deliberate bug for testing
+# Bug: Line 11 checks n % 4 instead of n % 5
+
+fizzbuzz() {
+    local n=$1
+    if (( n % 15 == 0 )); then
+        result="FizzBuzz"
+    elif (( n % 3 == 0 )); then
+        result="Fizz"
+    elif (( n % 4 == 0 )); then  # BUG: Should be n % 5
+        result="Buzz"
+    else
+        result="$n"
+    fi
+}
+
+for i in $(seq 1 "${1:-100}"); do
+    fizzbuzz "$i"
+    echo "$i: $result"
+done
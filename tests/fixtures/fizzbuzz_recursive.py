@@ -0,0 +1,34 @@
+#!/usr/bin/env python3
+"""
+Recursive FizzBuzz for testing stack trace paging.
+
+fizzbuzz_to(n) recurses once per number, so the stack is n + 3 frames deep
+(n + 1 calls, main and the module) when the base case is reached.
+"""
+import sys
+
+
+def fizzbuzz_to(n, results):
+    if n == 0:
+        return results  # Breakpoint target: line 13 (deepest frame)
+    fizzbuzz_to(n - 1, results)
+    if n % 15 == 0:
+        results.append("FizzBuzz")
+    elif n % 3 == 0:
+        results.append("Fizz")
+    elif n % 5 == 0:
+        results.append("Buzz")
+    else:
+        results.append(str(n))
+    return results
+
+
+def main():
+    sys.setrecursionlimit(5000)
+    depth = int(sys.argv[1]) if len(sys.argv) > 1 else 1000
+    results = fizzbuzz_to(depth, [])
+    print(len(results))
+
+
+if __name__ == "__main__":
+    main()
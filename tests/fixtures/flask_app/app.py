@@ -0,0 +1,17 @@
+"""Minimal Flask app served with the autoreloader (for subProcess debugging)"""
+import sys
+
+from flask import Flask
+
+app = Flask(__name__)
+
+
+@app.route("/greet/<name>")
+def greet(name):
+    message = f"Hello, {name}!"
+    return message
+
+
+if __name__ == "__main__":
+    port = int(sys.argv[1]) if len(sys.argv) > 1 else 5000
+    app.run(port=port, debug=True, use_reloader=True)
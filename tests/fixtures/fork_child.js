@@ -0,0 +1,5 @@
+// Sums the numbers sent by fork_parent.js
+process.on("message", (message) => {
+    const total = message.numbers.reduce((a, b) => a + b, 0);
+    process.send({ total });
+});
@@ -0,0 +1,11 @@
+// Forks fork_child.js and waits for its answer (multi-process debugging)
+const { fork } = require("child_process");
+const path = require("path");
+
+const child = fork(path.join(__dirname, "fork_child.js"));
+child.on("message", (reply) => {
+    const total = reply.total;
+    console.log(`parent received ${total}`);
+    child.disconnect();
+});
+child.send({ numbers: [1, 2, 3] });
@@ -0,0 +1,14 @@
+#!/usr/bin/env python3
+"""Raises unless the debugger jumps over the raise (line 8)."""
+
+
+def risky():
+    value = 1
+    if value:
+        raise RuntimeError("this line should have been skipped")
+    value = 2
+    return value
+
+
+if __name__ == "__main__":
+    print(f"result = {risky()}")
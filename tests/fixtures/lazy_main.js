@@ -0,0 +1,6 @@
+// Requires lazy_module.js only after a delay, so breakpoints set in it
+// before then can only bind once the module is loaded
+setTimeout(() => {
+    const { double } = require("./lazy_module");
+    console.log(`double(21) = ${double(21)}`);
+}, 1000);
@@ -0,0 +1,7 @@
+// Loaded lazily by lazy_main.js
+function double(n) {
+    const result = n * 2; // Breakpoint target: line 3
+    return result;
+}
+
+module.exports = { double };
@@ -0,0 +1,12 @@
+// Fixture for debugger_read_memory: a static with known bytes
+
+static MAGIC: [u8; 8] = *b"DEBUGMCP";
+
+fn checksum(bytes: &[u8]) -> u32 {
+    bytes.iter().map(|&b| b as u32).sum()
+}
+
+fn main() {
+    let sum = checksum(&MAGIC);
+    println!("checksum: {}", sum); // breakpoint line 11
+}
@@ -0,0 +1,11 @@
+// Started through `npm start` (npm → node server.js)
+
+function handle(request) {
+  const body = `hello ${request}`;
+  return body;
+}
+
+const requests = ["a", "b"];
+for (const request of requests) {
+  console.log(handle(request));
+}
@@ -0,0 +1,12 @@
+// Fixture for pretty-printed Rust values in evaluate/variables output
+
+fn total(values: &[i32]) -> i32 {
+    values.iter().sum()
+}
+
+fn main() {
+    let numbers: Vec<i32> = vec![10, 20, 30];
+    let label = String::from("numbers");
+    let sum = total(&numbers);
+    println!("{}: {}", label, sum); // breakpoint line 11
+}
@@ -0,0 +1,11 @@
+from calculator import average
+
+
+def test_average():
+    values = [2, 4, 9]
+    result = average(values)
+    assert result == 5
+
+
+def test_average_single():
+    assert average([3]) == 3
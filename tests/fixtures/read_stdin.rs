@@ -0,0 +1,10 @@
+// Fixture for stdin redirection: reads a line before doing anything else
+
+use std::io::BufRead;
+
+fn main() {
+    let mut line = String::new();
+    std::io::stdin().lock().read_line(&mut line).unwrap();
+    let name = line.trim();
+    println!("Hello, {}!", name); // breakpoint line 9
+}
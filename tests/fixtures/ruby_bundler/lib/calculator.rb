@@ -0,0 +1,6 @@
+class Calculator
+  def average(values)
+    total = values.sum
+    total / values.size.to_f
+  end
+end
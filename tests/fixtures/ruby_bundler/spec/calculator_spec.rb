@@ -0,0 +1,7 @@
+require_relative "../lib/calculator"
+
+RSpec.describe Calculator do
+  it "averages values" do
+    expect(Calculator.new.average([2, 4, 9])).to eq(5.0)
+  end
+end
@@ -0,0 +1,5 @@
+# stopOnEntry fixture: the file loads itself, so its first executable line
+# runs three times. A leftover entry breakpoint would stop there again.
+$runs = ($runs || 0) + 1
+load __FILE__ if $runs < 3
+puts "run #{$runs}"
@@ -0,0 +1,15 @@
+// Two calls on one line, for stepping into the second one directly
+function bar() {
+    return 1;
+}
+
+function baz() {
+    return 2;
+}
+
+function foo(a, b) {
+    return a + b;
+}
+
+const total = foo(bar(), baz());
+console.log(`total = ${total}`);
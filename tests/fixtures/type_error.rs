@@ -0,0 +1,10 @@
+// Deliberate type error for compiler diagnostics tests
+
+fn square(n: i32) -> i32 {
+    n * n
+}
+
+fn main() {
+    let result: String = square(4);
+    println!("{}", result);
+}
@@ -0,0 +1,20 @@
+// Greets a few people; `tsc -p .` compiles it to dist/ with source maps
+interface Person {
+    name: string;
+    age: number;
+}
+
+type Greeting = `Hello, ${string}`;
+
+function greet(person: Person): Greeting {
+    const greeting: Greeting = `Hello, ${person.name}`;
+    return greeting;
+}
+
+const people: Person[] = [
+    { name: "Ada", age: 36 },
+    { name: "Alan", age: 41 },
+];
+for (const person of people) {
+    console.log(greet(person));
+}
@@ -0,0 +1,18 @@
+// Fixture for debugger_set_data_breakpoint: a struct field mutated in a loop
+
+struct Counter {
+    hits: u32,
+}
+
+fn bump(counter: &mut Counter) {
+    counter.hits += 1; // the write stops here
+}
+
+fn main() {
+    let mut counter = Counter { hits: 0 };
+    println!("start: {}", counter.hits); // breakpoint line 13
+    for _ in 0..3 {
+        bump(&mut counter);
+    }
+    println!("hits: {}", counter.hits);
+}
@@ -0,0 +1,14 @@
+// Starts a short-lived worker thread and keeps running after it has exited
+const { Worker } = require("worker_threads");
+
+const worker = new Worker(
+    "require('worker_threads').parentPort.postMessage(21 * 2);",
+    { eval: true }
+);
+worker.on("message", (answer) => console.log(`worker answered ${answer}`));
+worker.on("exit", () => {
+    setTimeout(() => {
+        const status = "main thread still running";
+        console.log(status);
+    }, 500);
+});
@@ -0,0 +1,447 @@
+/// Core debugging workflow against the fake DAP adapter
+///
+/// The "fake" language runs `fake_dap_adapter` (tests/bin/fake_dap_adapter.rs), which
+/// plays back the scenario in tests/fixtures/fake/workflow.json instead of
+/// running a program. No debugger needs to be installed, so unlike the
+/// language tests these run in every `cargo test`.
+///
+/// The scenario "program":
+///
+/// ```text
+/// 1  <module>
+/// 2  total = 0
+/// 3  total = add(1, 2)        -> 6  add(a, b)
+/// 4  print(total)                7  result = a + b
+/// ```
+use debugger_mcp::debug::SessionManager;
+use debugger_mcp::mcp::tools::ToolsHandler;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+fn scenario_path() -> String {
+    fixture("workflow.json")
+}
+
+/// Path of a scenario in tests/fixtures/fake
+fn fixture(name: &str) -> String {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("fake")
+        .join(name)
+        .to_string_lossy()
+        .to_string()
+}
+
+fn tools_handler() -> ToolsHandler {
+    std::env::set_var(
+        debugger_mcp::adapters::fake::ADAPTER_PATH_ENV,
+        env!("CARGO_BIN_EXE_fake_dap_adapter"),
+    );
+    ToolsHandler::new(Arc::new(SessionManager::new()))
+}
+
+/// Start a fake session on the scenario and return its id
+async fn start(tools: &ToolsHandler, extra: Value) -> String {
+    let mut args = json!({
+        "language": "fake",
+        "program": scenario_path()
+    });
+    args.as_object_mut()
+        .unwrap()
+        .extend(extra.as_object().unwrap().clone());
+
+    let response = tools.handle_tool("debugger_start", args).await.unwrap();
+    response["sessionId"].as_str().unwrap().to_string()
+}
+
+async fn wait_for_stop(tools: &ToolsHandler, session_id: &str) -> Value {
+    tools
+        .handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 5000}),
+        )
+        .await
+        .unwrap()
+}
+
+async fn stack_frames(tools: &ToolsHandler, session_id: &str) -> Vec<Value> {
+    let response = tools
+        .handle_tool("debugger_stack_trace", json!({"sessionId": session_id}))
+        .await
+        .unwrap();
+    response["stackFrames"].as_array().unwrap().clone()
+}
+
+async fn step(tools: &ToolsHandler, tool: &str, session_id: &str) -> Value {
+    tools
+        .handle_tool(
+            tool,
+            json!({"sessionId": session_id, "waitForStop": true, "timeoutMs": 5000}),
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_stop_on_entry() {
+    let tools = tools_handler();
+    let session_id = start(&tools, json!({"stopOnEntry": true})).await;
+
+    let stop = wait_for_stop(&tools, &session_id).await;
+    assert_eq!(stop["state"], "Stopped");
+    assert_eq!(stop["reason"], "entry");
+
+    let frames = stack_frames(&tools, &session_id).await;
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0]["line"], 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_breakpoint_hit() {
+    let tools = tools_handler();
+    let session_id = start(
+        &tools,
+        json!({"breakpoints": [{"sourcePath": scenario_path(), "line": 6}]}),
+    )
+    .await;
+
+    let stop = wait_for_stop(&tools, &session_id).await;
+    assert_eq!(stop["reason"], "breakpoint");
+
+    let frames = stack_frames(&tools, &session_id).await;
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0]["name"], "add");
+    assert_eq!(frames[0]["line"], 6);
+    assert_eq!(frames[1]["line"], 3);
+
+    // No breakpoint after line 6: the program runs to the end
+    let response = step(&tools, "debugger_continue", &session_id).await;
+    assert_eq!(response["state"], "Terminated");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_evaluate_with_frame_id() {
+    let tools = tools_handler();
+    let session_id = start(
+        &tools,
+        json!({"breakpoints": [{"sourcePath": scenario_path(), "line": 6}]}),
+    )
+    .await;
+    wait_for_stop(&tools, &session_id).await;
+    let frames = stack_frames(&tools, &session_id).await;
+
+    // Without a frame: the top frame
+    let response = tools
+        .handle_tool(
+            "debugger_evaluate",
+            json!({"sessionId": session_id, "expression": "a"}),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response["result"], "1");
+
+    // The caller's local, only visible in the caller's frame
+    let response = tools
+        .handle_tool(
+            "debugger_evaluate",
+            json!({"sessionId": session_id, "expression": "total", "frameId": frames[1]["id"]}),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response["result"], "0");
+
+    let error = tools
+        .handle_tool(
+            "debugger_evaluate",
+            json!({"sessionId": session_id, "expression": "total", "frameId": frames[0]["id"]}),
+        )
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("NameError"), "{}", error);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_step_sequence() {
+    let tools = tools_handler();
+    let session_id = start(&tools, json!({"stopOnEntry": true})).await;
+    wait_for_stop(&tools, &session_id).await;
+
+    let lines = [
+        ("debugger_step_over", 2),
+        ("debugger_step_into", 6),
+        ("debugger_step_over", 7),
+        ("debugger_step_out", 3),
+        ("debugger_step_over", 4),
+    ];
+    for (tool, line) in lines {
+        let response = step(&tools, tool, &session_id).await;
+        assert_eq!(response["reason"], "step", "{}: {}", tool, response);
+        assert_eq!(response["topFrame"]["line"], line, "{}: {}", tool, response);
+    }
+
+    let response = step(&tools, "debugger_step_over", &session_id).await;
+    assert_eq!(response["state"], "Terminated");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_disconnect() {
+    let tools = tools_handler();
+    let session_id = start(&tools, json!({"stopOnEntry": true})).await;
+    wait_for_stop(&tools, &session_id).await;
+
+    let response = tools
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await
+        .unwrap();
+    assert_eq!(response["status"], "disconnected");
+
+    let error = tools
+        .handle_tool("debugger_session_state", json!({"sessionId": session_id}))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("not found"), "{}", error);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_exclusive_start_names_existing_session() {
+    let tools = tools_handler();
+    let session_id = start(&tools, json!({"stopOnEntry": true})).await;
+    wait_for_stop(&tools, &session_id).await;
+
+    let error = tools
+        .handle_tool(
+            "debugger_start",
+            json!({"language": "fake", "program": scenario_path(), "exclusive": true}),
+        )
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains(&session_id), "{}", error);
+
+    // A terminated session does not count
+    step(&tools, "debugger_continue", &session_id).await;
+    let second = start(&tools, json!({"exclusive": true})).await;
+    assert_ne!(second, session_id);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reuse_existing_session() {
+    let tools = tools_handler();
+    let session_id = start(&tools, json!({"stopOnEntry": true})).await;
+    wait_for_stop(&tools, &session_id).await;
+
+    let response = tools
+        .handle_tool(
+            "debugger_start",
+            json!({"language": "fake", "program": scenario_path(), "reuseExisting": true}),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response["sessionId"], session_id.as_str());
+    assert_eq!(response["status"], "reused");
+    assert_eq!(response["state"], "Stopped");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_simultaneous_exclusive_starts_share_one_session() {
+    let tools = Arc::new(tools_handler());
+    let starts = (0..2).map(|_| {
+        let tools = Arc::clone(&tools);
+        tokio::spawn(async move {
+            tools
+                .handle_tool(
+                    "debugger_start",
+                    json!({"language": "fake", "program": scenario_path(), "reuseExisting": true}),
+                )
+                .await
+                .unwrap()
+        })
+    });
+    let mut responses = Vec::new();
+    for start in starts.collect::<Vec<_>>() {
+        responses.push(start.await.unwrap());
+    }
+
+    assert_eq!(responses[0]["sessionId"], responses[1]["sessionId"]);
+    let mut statuses: Vec<_> = responses
+        .iter()
+        .map(|r| r["status"].as_str().unwrap().to_string())
+        .collect();
+    statuses.sort();
+    assert_eq!(statuses, vec!["reused", "started"]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_exit_codes_reported_on_termination() {
+    let tools = tools_handler();
+
+    let session_id = start(&tools, json!({"program": fixture("exit_0.json")})).await;
+    let end = wait_for_stop(&tools, &session_id).await;
+    assert_eq!(end["state"], "Terminated");
+    assert_eq!(end["exitCode"], 0);
+    assert_eq!(end["reason"], "Program exited with code 0");
+    assert!(end.get("stderrTail").is_none());
+
+    let session_id = start(&tools, json!({"program": fixture("exit_3.json")})).await;
+    let end = wait_for_stop(&tools, &session_id).await;
+    assert_eq!(end["state"], "Terminated");
+    assert_eq!(end["exitCode"], 3);
+    assert_eq!(end["reason"], "Program exited with code 3");
+    assert_eq!(
+        end["stderrTail"],
+        json!([
+            "Traceback (most recent call last):",
+            "ValueError: bad input"
+        ])
+    );
+
+    let state = tools
+        .handle_tool("debugger_session_state", json!({"sessionId": session_id}))
+        .await
+        .unwrap();
+    assert_eq!(state["state"], "Terminated");
+    assert_eq!(state["details"]["exitCode"], 3);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_until_exit() {
+    let tools = tools_handler();
+
+    let summary = tools
+        .handle_tool(
+            "debugger_run_until_exit",
+            json!({
+                "language": "fake",
+                "program": scenario_path(),
+                "breakpoints": [
+                    {"sourcePath": scenario_path(), "line": 2},
+                    {"sourcePath": scenario_path(), "line": 7}
+                ],
+                "includeLocals": true
+            }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(summary["state"], "Terminated");
+    assert_eq!(summary["timedOut"], false);
+    assert_eq!(summary["stopCount"], 2);
+    assert_eq!(summary["stopsTruncated"], false);
+    let stops = summary["stops"].as_array().unwrap();
+    assert_eq!(stops[0]["reason"], "breakpoint");
+    assert_eq!(stops[0]["topFrame"]["line"], 2);
+    assert_eq!(stops[1]["topFrame"]["name"], "add");
+    assert_eq!(stops[1]["topFrame"]["line"], 7);
+    assert!(stops[1]["topFrameVariables"].to_string().contains("result"));
+
+    // The session is removed once the program exits
+    let error = tools
+        .handle_tool(
+            "debugger_session_state",
+            json!({"sessionId": summary["sessionId"]}),
+        )
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("not found"), "{}", error);
+
+    let summary = tools
+        .handle_tool(
+            "debugger_run_until_exit",
+            json!({
+                "language": "fake",
+                "program": fixture("exit_3.json"),
+                "breakpoints": [
+                    {"sourcePath": fixture("exit_3.json"), "line": 1},
+                    {"sourcePath": fixture("exit_3.json"), "line": 2}
+                ],
+                "maxStops": 1
+            }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(summary["exitCode"], 3);
+    assert_eq!(summary["stopCount"], 2);
+    assert_eq!(summary["stops"].as_array().unwrap().len(), 1);
+    assert_eq!(summary["stopsTruncated"], true);
+    assert!(summary["stops"][0].get("topFrameVariables").is_none());
+    assert!(summary["stderrTail"]
+        .to_string()
+        .contains("ValueError: bad input"));
+    assert!(summary["output"].to_string().contains("ValueError"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_start_reports_launch_details() {
+    let tools = tools_handler();
+    let response = tools
+        .handle_tool(
+            "debugger_start",
+            json!({"language": "fake", "program": scenario_path(), "stopOnEntry": true}),
+        )
+        .await
+        .unwrap();
+
+    let program = std::fs::canonicalize(scenario_path()).unwrap();
+    assert_eq!(response["status"], "started");
+    assert_eq!(response["language"], "fake");
+    assert_eq!(response["program"], program.to_string_lossy().as_ref());
+    assert_eq!(
+        response["cwd"],
+        program.parent().unwrap().to_string_lossy().as_ref()
+    );
+    assert_eq!(response["stopOnEntry"], true);
+    assert_eq!(response["adapter"], "fake");
+    assert_eq!(
+        response["adapterCommand"],
+        env!("CARGO_BIN_EXE_fake_dap_adapter")
+    );
+    assert_eq!(response["compiled"], false);
+    assert!(response.get("rustProject").is_none());
+
+    let session_id = response["sessionId"].as_str().unwrap();
+    let stop = wait_for_stop(&tools, session_id).await;
+    assert_eq!(stop["reason"], "entry");
+}
+
+/// Session lookups keep working while other sessions come and go
+#[tokio::test(flavor = "multi_thread")]
+async fn test_session_state_while_sessions_start_and_stop() {
+    let tools = Arc::new(tools_handler());
+    let session_id = start(&tools, json!({"stopOnEntry": true})).await;
+    wait_for_stop(&tools, &session_id).await;
+
+    let churn = tokio::spawn({
+        let tools = Arc::clone(&tools);
+        async move {
+            for _ in 0..5 {
+                let other = start(&tools, json!({"stopOnEntry": true})).await;
+                wait_for_stop(&tools, &other).await;
+                tools
+                    .handle_tool("debugger_disconnect", json!({"sessionId": other}))
+                    .await
+                    .unwrap();
+            }
+        }
+    });
+
+    let mut lookups = 0;
+    while !churn.is_finished() {
+        let state = tokio::time::timeout(
+            std::time::Duration::from_secs(1),
+            tools.handle_tool("debugger_session_state", json!({"sessionId": session_id})),
+        )
+        .await
+        .expect("session_state must not wait for other sessions")
+        .unwrap();
+        assert_eq!(state["state"], "Stopped");
+        lookups += 1;
+    }
+    churn.await.unwrap();
+    assert!(lookups > 0);
+
+    let state = tools
+        .handle_tool("debugger_session_state", json!({"sessionId": session_id}))
+        .await
+        .unwrap();
+    assert_eq!(state["state"], "Stopped");
+}
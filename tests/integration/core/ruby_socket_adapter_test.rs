@@ -3,7 +3,7 @@
 /// This test suite verifies that the Ruby adapter correctly:
 /// 1. Finds free ports
 /// 2. Spawns rdbg with --open flag
-/// 3. Connects to the TCP socket
+/// 3. Connects to the socket (a unix domain socket where available, TCP otherwise)
 /// 4. Communicates via DAP protocol
 /// 5. Handles timeouts appropriately
 use debugger_mcp::adapters::ruby::RubyAdapter;
@@ -226,11 +226,13 @@ async fn test_ruby_adapter_spawn_real_rdbg() {
 
     let session = result.unwrap();
 
-    // Verify port is in valid range
-    assert!(session.port > 1024);
-
-    // Verify socket is connected
-    assert!(session.socket.peer_addr().is_ok());
+    // rdbg listens on a unix socket where there are any, otherwise on a port
+    if cfg!(unix) {
+        assert!(session.endpoint.port().is_none(), "{}", session.endpoint);
+        assert!(session.endpoint.to_string().starts_with("unix:"));
+    } else {
+        assert!(session.endpoint.port().unwrap() > 1024);
+    }
 
     // Clean up
     std::fs::remove_file(test_script).ok();
@@ -269,7 +271,7 @@ async fn test_ruby_e2e_dap_communication() {
         .expect("Failed to spawn rdbg");
 
     // 2. Create DAP client from socket
-    let client = DapClient::from_socket(session.socket)
+    let client = DapClient::from_stream(session.socket)
         .await
         .expect("Failed to create DAP client");
 
@@ -332,7 +334,7 @@ async fn test_ruby_adapter_spawn_with_args() {
         .await
         .expect("Failed to spawn with args");
 
-    assert!(session.socket.peer_addr().is_ok());
+    assert!(session.process.id().is_some());
 
     // Clean up
     std::fs::remove_file(test_script).ok();
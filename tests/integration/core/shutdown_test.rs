@@ -0,0 +1,236 @@
+/// Integration tests for shutting the STDIO server down
+///
+/// These run the real binary with piped STDIO, the way an MCP host does, and
+/// check that closing stdin, the `shutdown`/`exit` pair and SIGTERM all end
+/// the server with status 0 without leaving adapter processes behind.
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::process::{ExitStatus, Stdio};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::time::{sleep, timeout, Duration, Instant};
+
+/// Helper to check if debugpy is available
+fn is_debugpy_available() -> bool {
+    std::process::Command::new("python3")
+        .args(["-c", "import debugpy"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+struct StdioServer {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    stdout: BufReader<ChildStdout>,
+    next_id: i64,
+}
+
+impl StdioServer {
+    fn spawn() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_debugger_mcp"))
+            .arg("serve")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .expect("failed to spawn debugger_mcp");
+        let stdin = child.stdin.take();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        Self {
+            child,
+            stdin,
+            stdout,
+            next_id: 1,
+        }
+    }
+
+    async fn send(&mut self, message: Value) {
+        let mut line = serde_json::to_string(&message).unwrap();
+        line.push('\n');
+        let stdin = self.stdin.as_mut().expect("stdin already closed");
+        stdin.write_all(line.as_bytes()).await.unwrap();
+        stdin.flush().await.unwrap();
+    }
+
+    async fn request(&mut self, method: &str, params: Value) -> Value {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}))
+            .await;
+
+        loop {
+            let mut line = String::new();
+            timeout(Duration::from_secs(30), self.stdout.read_line(&mut line))
+                .await
+                .expect("response timed out")
+                .unwrap();
+            let message: Value = serde_json::from_str(&line).unwrap();
+            if message["id"] == id {
+                return message;
+            }
+        }
+    }
+
+    async fn initialize(&mut self) {
+        let init = self
+            .request(
+                "initialize",
+                json!({"protocolVersion": "2024-11-05", "capabilities": {}}),
+            )
+            .await;
+        assert!(init["error"].is_null(), "initialize failed: {}", init);
+        self.send(json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+            .await;
+    }
+
+    async fn call_tool(&mut self, name: &str, arguments: Value) -> Value {
+        let response = self
+            .request("tools/call", json!({"name": name, "arguments": arguments}))
+            .await;
+        assert!(
+            response["error"].is_null(),
+            "{} failed: {}",
+            name,
+            response["error"]
+        );
+        let text = response["result"]["content"][0]["text"].as_str().unwrap();
+        serde_json::from_str(text).unwrap()
+    }
+
+    fn close_stdin(&mut self) {
+        self.stdin = None;
+    }
+
+    async fn wait_for_exit(&mut self) -> ExitStatus {
+        timeout(Duration::from_secs(10), self.child.wait())
+            .await
+            .expect("server did not exit")
+            .unwrap()
+    }
+}
+
+/// Live (non-zombie) direct children of `pid`, from /proc
+#[cfg(target_os = "linux")]
+fn child_pids(pid: u32) -> Vec<u32> {
+    let mut children = Vec::new();
+    for entry in std::fs::read_dir("/proc").unwrap().flatten() {
+        let Ok(child) = entry.file_name().to_string_lossy().parse::<u32>() else {
+            continue;
+        };
+        let Ok(stat) = std::fs::read_to_string(format!("/proc/{}/stat", child)) else {
+            continue;
+        };
+        // Fields after the parenthesized command: state, ppid, ...
+        let fields: Vec<&str> = stat
+            .rsplit_once(')')
+            .map(|(_, rest)| rest.split_whitespace().collect())
+            .unwrap_or_default();
+        if fields.len() > 1 && fields[0] != "Z" && fields[1] == pid.to_string() {
+            children.push(child);
+        }
+    }
+    children
+}
+
+#[cfg(target_os = "linux")]
+fn is_alive(pid: u32) -> bool {
+    std::fs::read_to_string(format!("/proc/{}/stat", pid))
+        .map(|stat| !stat.contains(") Z "))
+        .unwrap_or(false)
+}
+
+#[tokio::test]
+async fn test_closing_stdin_exits_cleanly() {
+    let mut server = StdioServer::spawn();
+    server.initialize().await;
+
+    server.close_stdin();
+    assert!(server.wait_for_exit().await.success());
+}
+
+#[tokio::test]
+async fn test_shutdown_request_then_exit() {
+    let mut server = StdioServer::spawn();
+    server.initialize().await;
+
+    let shutdown = server.request("shutdown", Value::Null).await;
+    assert!(shutdown["error"].is_null(), "shutdown failed: {}", shutdown);
+
+    // Nothing is accepted after shutdown
+    let refused = server.request("tools/list", json!({})).await;
+    assert_eq!(refused["error"]["data"]["code"], "SHUTTING_DOWN");
+
+    server
+        .send(json!({"jsonrpc": "2.0", "method": "exit"}))
+        .await;
+    assert!(server.wait_for_exit().await.success());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_sigterm_exits_cleanly() {
+    let mut server = StdioServer::spawn();
+    server.initialize().await;
+
+    let pid = server.child.id().unwrap();
+    let status = std::process::Command::new("kill")
+        .args(["-TERM", &pid.to_string()])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert!(server.wait_for_exit().await.success());
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn test_closing_stdin_ends_adapter_processes() {
+    if !is_debugpy_available() {
+        println!("⚠️  Skipping: debugpy not installed");
+        return;
+    }
+
+    let fizzbuzz_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("fizzbuzz.py")
+        .to_string_lossy()
+        .to_string();
+
+    let mut server = StdioServer::spawn();
+    server.initialize().await;
+    let start = server
+        .call_tool(
+            "debugger_start",
+            json!({
+                "language": "python",
+                "program": fizzbuzz_path,
+                "breakpoints": [{"sourcePath": fizzbuzz_path, "line": 18}]
+            }),
+        )
+        .await;
+    let session_id = start["sessionId"].as_str().unwrap().to_string();
+    server
+        .call_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 10000}),
+        )
+        .await;
+
+    let adapters = child_pids(server.child.id().unwrap());
+    assert!(!adapters.is_empty(), "no adapter process found");
+
+    server.close_stdin();
+    assert!(server.wait_for_exit().await.success());
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while adapters.iter().any(|&pid| is_alive(pid)) {
+        assert!(
+            Instant::now() < deadline,
+            "adapter processes still running: {:?}",
+            adapters
+        );
+        sleep(Duration::from_millis(100)).await;
+    }
+}
@@ -9,7 +9,6 @@ use debugger_mcp::mcp::tools::ToolsHandler;
 use serde_json::json;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::RwLock;
 use tokio::time::{timeout, Duration};
 
 /// Helper to get path to fizzbuzz test fixture
@@ -50,7 +49,7 @@ async fn test_stopOnEntry_sets_stopped_state() {
         return;
     }
 
-    let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+    let session_manager = Arc::new(SessionManager::new());
     let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
 
     // Start session with stopOnEntry
@@ -164,7 +163,7 @@ async fn test_wait_for_stop_detects_stopOnEntry() {
         return;
     }
 
-    let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+    let session_manager = Arc::new(SessionManager::new());
     let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
 
     // Start session with stopOnEntry
@@ -258,7 +257,7 @@ async fn test_breakpoint_works_with_stopOnEntry() {
         return;
     }
 
-    let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+    let session_manager = Arc::new(SessionManager::new());
     let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
     let fizzbuzz_path = get_fizzbuzz_path();
 
@@ -425,7 +424,7 @@ async fn test_state_transitions_are_accurate() {
         return;
     }
 
-    let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+    let session_manager = Arc::new(SessionManager::new());
     let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
 
     // Start with stopOnEntry
@@ -519,3 +518,87 @@ async fn test_state_transitions_are_accurate() {
         .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
         .await;
 }
+
+/// TEST: stopOnEntry on a fast-exiting one-line script
+///
+/// A script that finishes immediately must either stop at entry or surface
+/// the "did not stop at entry" diagnostic - never a generic timeout.
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+#[allow(non_snake_case)]
+async fn test_stopOnEntry_one_line_script_is_verified() {
+    if !is_debugpy_available() {
+        println!("⚠️  Skipping: debugpy not installed");
+        return;
+    }
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let script = temp_dir.path().join("one_line.py");
+    std::fs::write(&script, "print('done')\n").unwrap();
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let start_args = json!({
+        "language": "python",
+        "program": script.to_string_lossy(),
+        "args": [],
+        "stopOnEntry": true
+    });
+
+    let start_response = match timeout(
+        Duration::from_secs(10),
+        tools_handler.handle_tool("debugger_start", start_args),
+    )
+    .await
+    {
+        Ok(Ok(response)) => response,
+        Ok(Err(e)) => {
+            println!("⚠️  Skipping: debugger_start failed: {}", e);
+            return;
+        }
+        Err(_) => {
+            println!("⚠️  Skipping: debugger_start timed out");
+            return;
+        }
+    };
+
+    let session_id = start_response["sessionId"].as_str().unwrap().to_string();
+
+    let wait_result = tools_handler
+        .handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 10000}),
+        )
+        .await;
+
+    println!("📊 wait_for_stop result: {:?}", wait_result);
+
+    match wait_result {
+        Ok(response) if response["state"] == "Stopped" => {
+            assert_eq!(response["reason"], "entry");
+        }
+        Ok(response) => {
+            assert_eq!(response["state"], "Terminated");
+            assert!(
+                response["reason"]
+                    .as_str()
+                    .unwrap()
+                    .contains("did not stop at entry"),
+                "Terminated without stopOnEntry diagnostic: {}",
+                response
+            );
+        }
+        Err(e) => {
+            assert!(
+                e.to_string().contains("did not stop at entry"),
+                "Expected stopOnEntry diagnostic, got: {}",
+                e
+            );
+        }
+    }
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
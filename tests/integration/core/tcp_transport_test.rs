@@ -0,0 +1,248 @@
+/// Integration tests for the MCP server's TCP transport (`serve --listen`)
+///
+/// These run the real binary, connect over a socket and speak
+/// newline-delimited JSON-RPC, exactly as a host-side MCP client would.
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::TcpStream;
+use tokio::process::{Child, Command};
+use tokio::time::{sleep, timeout, Duration};
+
+/// Helper to check if debugpy is available
+fn is_debugpy_available() -> bool {
+    std::process::Command::new("python3")
+        .args(["-c", "import debugpy"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/// Start `debugger_mcp serve --listen` on a free local port
+async fn spawn_tcp_server() -> (Child, String) {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let addr = format!("127.0.0.1:{}", port);
+
+    let child = Command::new(env!("CARGO_BIN_EXE_debugger_mcp"))
+        .args(["serve", "--listen", &addr])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .expect("failed to spawn debugger_mcp");
+
+    (child, addr)
+}
+
+struct McpClient {
+    reader: BufReader<OwnedReadHalf>,
+    writer: OwnedWriteHalf,
+    next_id: i64,
+    /// Server notifications received while waiting for responses
+    notifications: Vec<Value>,
+}
+
+impl McpClient {
+    async fn connect(addr: &str) -> Self {
+        for _ in 0..50 {
+            if let Ok(stream) = TcpStream::connect(addr).await {
+                let (read_half, write_half) = stream.into_split();
+                return Self {
+                    reader: BufReader::new(read_half),
+                    writer: write_half,
+                    next_id: 1,
+                    notifications: Vec::new(),
+                };
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+        panic!("MCP server did not start listening on {}", addr);
+    }
+
+    async fn request(&mut self, method: &str, params: Value) -> Value {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let request = json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params});
+        let mut line = serde_json::to_string(&request).unwrap();
+        line.push('\n');
+        self.writer.write_all(line.as_bytes()).await.unwrap();
+
+        loop {
+            let message = self.read().await;
+            if message.get("id").is_none() {
+                self.notifications.push(message);
+                continue;
+            }
+            assert_eq!(message["id"], id);
+            return message;
+        }
+    }
+
+    async fn read(&mut self) -> Value {
+        let mut line = String::new();
+        timeout(Duration::from_secs(30), self.reader.read_line(&mut line))
+            .await
+            .expect("message timed out")
+            .unwrap();
+        serde_json::from_str(&line).unwrap()
+    }
+
+    /// Wait for a `debugger/stateChanged` notification entering `state`
+    async fn wait_for_state_notification(&mut self, state: &str) -> Value {
+        let is_match =
+            |n: &Value| n["method"] == "debugger/stateChanged" && n["params"]["newState"] == state;
+        if let Some(n) = self.notifications.iter().find(|n| is_match(n)) {
+            return n.clone();
+        }
+        loop {
+            let message = self.read().await;
+            if is_match(&message) {
+                return message;
+            }
+            self.notifications.push(message);
+        }
+    }
+
+    async fn call_tool(&mut self, name: &str, arguments: Value) -> Value {
+        let response = self
+            .request("tools/call", json!({"name": name, "arguments": arguments}))
+            .await;
+        assert!(
+            response["error"].is_null(),
+            "{} failed: {}",
+            name,
+            response["error"]
+        );
+        let text = response["result"]["content"][0]["text"].as_str().unwrap();
+        serde_json::from_str(text).unwrap()
+    }
+}
+
+#[tokio::test]
+async fn test_tcp_client_lists_tools() {
+    let (_server, addr) = spawn_tcp_server().await;
+    let mut client = McpClient::connect(&addr).await;
+
+    let init = client
+        .request(
+            "initialize",
+            json!({
+                "protocolVersion": "2024-11-05",
+                "capabilities": {},
+                "clientInfo": {"name": "tcp-test", "version": "1.0"}
+            }),
+        )
+        .await;
+    assert_eq!(init["result"]["serverInfo"]["name"], "debugger_mcp");
+
+    let tools = client.request("tools/list", json!({})).await;
+    let names: Vec<&str> = tools["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["name"].as_str().unwrap())
+        .collect();
+    assert!(names.contains(&"debugger_start"));
+    assert!(names.contains(&"debugger_disconnect"));
+}
+
+#[tokio::test]
+async fn test_tcp_session_survives_other_client_disconnecting() {
+    if !is_debugpy_available() {
+        println!("⚠️  Skipping: debugpy not installed");
+        return;
+    }
+
+    let (_server, addr) = spawn_tcp_server().await;
+    let fizzbuzz_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("fizzbuzz.py")
+        .to_string_lossy()
+        .to_string();
+
+    let mut first = McpClient::connect(&addr).await;
+    let start = first
+        .call_tool(
+            "debugger_start",
+            json!({"language": "python", "program": fizzbuzz_path, "stopOnEntry": true}),
+        )
+        .await;
+    let session_id = start["sessionId"].as_str().unwrap().to_string();
+
+    // Dropping the first connection must leave the session in place
+    drop(first);
+
+    let mut second = McpClient::connect(&addr).await;
+    let state = second
+        .call_tool("debugger_session_state", json!({"sessionId": session_id}))
+        .await;
+    assert_eq!(state["sessionId"], session_id);
+
+    let disconnect = second
+        .call_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+    assert_eq!(disconnect["status"], "disconnected");
+}
+
+#[tokio::test]
+async fn test_tcp_client_receives_state_notifications() {
+    if !is_debugpy_available() {
+        println!("⚠️  Skipping: debugpy not installed");
+        return;
+    }
+
+    let (_server, addr) = spawn_tcp_server().await;
+    let fizzbuzz_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("fizzbuzz.py")
+        .to_string_lossy()
+        .to_string();
+
+    let mut client = McpClient::connect(&addr).await;
+    client
+        .request(
+            "initialize",
+            json!({
+                "protocolVersion": "2024-11-05",
+                "capabilities": {
+                    "experimental": {"stateNotifications": {}, "resourceUpdates": {}}
+                }
+            }),
+        )
+        .await;
+
+    let start = client
+        .call_tool(
+            "debugger_start",
+            json!({
+                "language": "python",
+                "program": fizzbuzz_path,
+                "breakpoints": [{"sourcePath": fizzbuzz_path, "line": 18}]
+            }),
+        )
+        .await;
+    let session_id = start["sessionId"].as_str().unwrap().to_string();
+
+    // The stop arrives as a push notification; no polling needed
+    let stopped = client.wait_for_state_notification("Stopped").await;
+    assert_eq!(stopped["params"]["sessionId"], session_id);
+    assert_eq!(stopped["params"]["stop"]["reason"], "breakpoint");
+    assert!(client.notifications.iter().any(|n| {
+        n["method"] == "notifications/resources/updated"
+            && n["params"]["uri"] == format!("debugger://sessions/{}", session_id)
+    }));
+
+    client
+        .call_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
@@ -10,7 +10,6 @@ use debugger_mcp::mcp::tools::ToolsHandler;
 use serde_json::json;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::RwLock;
 use tokio::time::{timeout, Duration};
 
 /// Helper to get path to fizzbuzz test fixture
@@ -137,7 +136,7 @@ async fn test_frameid_required_for_local_variables() {
         return;
     }
 
-    let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+    let session_manager = Arc::new(SessionManager::new());
     let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
 
     println!("🧪 TEST: frameId requirement for local variable access");
@@ -245,7 +244,7 @@ async fn test_frame_ids_change_between_stops() {
         return;
     }
 
-    let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+    let session_manager = Arc::new(SessionManager::new());
     let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
     let fizzbuzz_path = get_fizzbuzz_path();
 
@@ -428,7 +427,7 @@ async fn test_list_breakpoints_shows_all_breakpoints() {
         return;
     }
 
-    let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+    let session_manager = Arc::new(SessionManager::new());
     let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
     let fizzbuzz_path = get_fizzbuzz_path();
 
@@ -590,7 +589,7 @@ async fn test_pattern_inspect_variable_at_breakpoint() {
         return;
     }
 
-    let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+    let session_manager = Arc::new(SessionManager::new());
     let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
     let fizzbuzz_path = get_fizzbuzz_path();
 
@@ -741,7 +740,7 @@ async fn test_step_commands_comprehensive() {
         return;
     }
 
-    let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+    let session_manager = Arc::new(SessionManager::new());
     let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
     let fizzbuzz_path = get_fizzbuzz_path();
 
@@ -969,7 +968,7 @@ async fn test_wait_for_stop_timing_behavior() {
         return;
     }
 
-    let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+    let session_manager = Arc::new(SessionManager::new());
     let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
     let fizzbuzz_path = get_fizzbuzz_path();
 
@@ -0,0 +1,100 @@
+use debugger_mcp::adapters::bash::BashAdapter;
+use debugger_mcp::debug::SessionManager;
+use debugger_mcp::mcp::tools::ToolsHandler;
+use serde_json::json;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Arc;
+
+/// Break inside fizzbuzz() of the Bash fixture, read a shell variable, and see
+/// the script's output arrive through the adapter's output events
+#[tokio::test]
+#[ignore]
+async fn test_bash_breakpoint_in_function_and_evaluate() {
+    use tokio::time::{timeout, Duration};
+
+    let node_check = Command::new("node").arg("--version").output();
+    if node_check.is_err() || !node_check.unwrap().status.success() {
+        println!("⚠️  Skipping test: node not installed");
+        return;
+    }
+    if !BashAdapter::adapter_script().exists() {
+        println!("⚠️  Skipping test: bash-debug-adapter not found (set BASH_DEBUG_ADAPTER_PATH)");
+        return;
+    }
+
+    let fizzbuzz_path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("fizzbuzz.sh");
+    let fizzbuzz_str = fizzbuzz_path.to_string_lossy().to_string();
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    // Line 7 is the first statement inside fizzbuzz()
+    let start = timeout(
+        Duration::from_secs(30),
+        tools_handler.handle_tool(
+            "debugger_start",
+            json!({
+                "language": "bash",
+                "program": fizzbuzz_str,
+                "args": ["3"],
+                "breakpoints": [{"sourcePath": fizzbuzz_str, "line": 7}]
+            }),
+        ),
+    )
+    .await
+    .expect("debugger_start timed out")
+    .expect("debugger_start failed");
+    let session_id = start["sessionId"].as_str().unwrap().to_string();
+
+    let wait_for_stop = || async {
+        timeout(
+            Duration::from_secs(20),
+            tools_handler.handle_tool(
+                "debugger_wait_for_stop",
+                json!({"sessionId": session_id, "timeoutMs": 15000}),
+            ),
+        )
+        .await
+        .expect("wait_for_stop timed out")
+        .expect("breakpoint not hit")
+    };
+
+    let stop = wait_for_stop().await;
+    assert_eq!(stop["reason"], "breakpoint", "unexpected stop: {}", stop);
+
+    let value = tools_handler
+        .handle_tool(
+            "debugger_evaluate",
+            json!({"sessionId": session_id, "expression": "$n"}),
+        )
+        .await
+        .expect("evaluate failed");
+    assert_eq!(value["result"].as_str().unwrap().trim(), "1");
+
+    // The second call prints the first line before stopping again
+    tools_handler
+        .handle_tool("debugger_continue", json!({"sessionId": session_id}))
+        .await
+        .expect("continue failed");
+    wait_for_stop().await;
+
+    let output = tools_handler
+        .handle_tool("debugger_get_output", json!({"sessionId": session_id}))
+        .await
+        .unwrap();
+    let text: String = output["output"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|entry| entry["output"].as_str())
+        .collect();
+    assert!(text.contains("1: 1"), "script output missing: {:?}", text);
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
@@ -0,0 +1,244 @@
+use debugger_mcp::adapters::rust::RustAdapter;
+use debugger_mcp::debug::SessionManager;
+use debugger_mcp::mcp::tools::ToolsHandler;
+use debugger_mcp::Error;
+use serde_json::json;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+fn tool_available(command: &str, version_flag: &str) -> bool {
+    Command::new(command)
+        .arg(version_flag)
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/// Copy a fixture into a temp dir, since compiling writes target/debug next to it
+fn copy_fixture(name: &str) -> (TempDir, String) {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let fixture = PathBuf::from(manifest_dir)
+        .join("tests/fixtures")
+        .join(name);
+    let dir = TempDir::new().unwrap();
+    let source = dir.path().join(name);
+    fs::copy(&fixture, &source).unwrap();
+    let source = source.to_string_lossy().to_string();
+    (dir, source)
+}
+
+/// Compiler errors reach the caller as Error::CompilationFailed with parsed diagnostics,
+/// before any debug adapter is spawned
+#[tokio::test]
+async fn test_c_compilation_error_is_reported() {
+    if !tool_available("gcc", "--version") && !tool_available("clang", "--version") {
+        println!("⚠️  Skipping test: gcc/clang not installed");
+        return;
+    }
+
+    let dir = TempDir::new().unwrap();
+    let source = dir.path().join("broken.c");
+    fs::write(&source, "int main(void) { return missing_symbol; }\n").unwrap();
+
+    let manager = SessionManager::new();
+    let result = manager
+        .create_session(
+            "c",
+            source.to_string_lossy().to_string(),
+            vec![],
+            None,
+            false,
+        )
+        .await;
+
+    match result {
+        Err(Error::CompilationFailed {
+            message,
+            diagnostics,
+        }) => {
+            assert!(message.contains("missing_symbol"), "{}", message);
+            assert_eq!(diagnostics[0].line, Some(1));
+        }
+        other => panic!("Expected compilation error, got {:?}", other),
+    }
+}
+
+/// Break in fizzbuzz() of the C and C++ fixtures and read `n`
+#[tokio::test]
+#[ignore]
+async fn test_c_and_cpp_breakpoint_debugging() {
+    use tokio::time::{timeout, Duration};
+
+    if !tool_available(&RustAdapter::command(), "--version") {
+        println!("⚠️  Skipping test: codelldb not installed");
+        println!("   Install CodeLLDB from: https://github.com/vadimcn/codelldb/releases");
+        return;
+    }
+
+    // (language, fixture, compiler, line inside fizzbuzz())
+    let cases = [
+        ("c", "fizzbuzz.c", "gcc", 6),
+        ("cpp", "fizzbuzz.cpp", "g++", 7),
+    ];
+
+    for (language, fixture, compiler, line) in cases {
+        if !tool_available(compiler, "--version") {
+            println!("⚠️  Skipping {}: {} not installed", language, compiler);
+            continue;
+        }
+
+        let (_dir, source) = copy_fixture(fixture);
+        let session_manager = Arc::new(SessionManager::new());
+        let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+        let start = timeout(
+            Duration::from_secs(60),
+            tools_handler.handle_tool(
+                "debugger_start",
+                json!({
+                    "language": language,
+                    "program": source,
+                    "breakpoints": [{"sourcePath": source, "line": line}]
+                }),
+            ),
+        )
+        .await
+        .expect("debugger_start timed out")
+        .expect("debugger_start failed");
+        let session_id = start["sessionId"].as_str().unwrap().to_string();
+
+        let stop = timeout(
+            Duration::from_secs(30),
+            tools_handler.handle_tool(
+                "debugger_wait_for_stop",
+                json!({"sessionId": session_id, "timeoutMs": 20000}),
+            ),
+        )
+        .await
+        .expect("wait_for_stop timed out")
+        .expect("breakpoint not hit");
+        assert_eq!(stop["reason"], "breakpoint", "{}: {}", language, stop);
+
+        let value = tools_handler
+            .handle_tool(
+                "debugger_evaluate",
+                json!({"sessionId": session_id, "expression": "n"}),
+            )
+            .await
+            .expect("evaluate failed");
+        assert_eq!(value["result"], "1", "{}: {}", language, value);
+
+        let _ = tools_handler
+            .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+            .await;
+        println!("✅ {} breakpoint hit and evaluated", language);
+    }
+}
+
+/// Run a compiled binary with core dumps enabled; the core's path when the
+/// kernel wrote one into `dir` (core_pattern may send it elsewhere)
+fn produce_core_dump(binary: &str, dir: &std::path::Path) -> Option<PathBuf> {
+    let _ = Command::new("sh")
+        .arg("-c")
+        .arg(format!("ulimit -c unlimited && exec '{}'", binary))
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .find(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name == "core" || name.starts_with("core."))
+        })
+}
+
+/// Load a core dump of a segfaulted C program: the session stops at the
+/// crashed frame, locals can be read and continue is refused
+///
+/// Needs core dumps written to the working directory
+/// (`/proc/sys/kernel/core_pattern` = `core`); skipped otherwise.
+#[tokio::test]
+#[ignore]
+async fn test_c_core_dump_post_mortem() {
+    use tokio::time::{timeout, Duration};
+
+    if !tool_available(&RustAdapter::command(), "--version") || !tool_available("gcc", "--version")
+    {
+        println!("⚠️  Skipping test: codelldb or gcc not installed");
+        return;
+    }
+
+    let (dir, source) = copy_fixture("crasher.c");
+    let binary = debugger_mcp::adapters::cpp::CppAdapter::compile(
+        &source,
+        debugger_mcp::adapters::cpp::CLanguage::C,
+    )
+    .await
+    .expect("compilation failed");
+    let Some(core) = produce_core_dump(&binary, dir.path()) else {
+        println!("⚠️  Skipping test: no core dump written (check ulimit -c and core_pattern)");
+        return;
+    };
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let start = timeout(
+        Duration::from_secs(60),
+        tools_handler.handle_tool(
+            "debugger_start",
+            json!({
+                "language": "c",
+                "program": binary,
+                "coreDump": core.to_string_lossy()
+            }),
+        ),
+    )
+    .await
+    .expect("debugger_start timed out")
+    .expect("debugger_start failed");
+    let session_id = start["sessionId"].as_str().unwrap().to_string();
+
+    tools_handler
+        .handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 20000}),
+        )
+        .await
+        .expect("core dump did not stop at the crash");
+
+    let stack = tools_handler
+        .handle_tool("debugger_stack_trace", json!({"sessionId": session_id}))
+        .await
+        .expect("stack_trace failed");
+    let top = &stack["stackFrames"][0];
+    assert!(top["name"].as_str().unwrap().contains("crash"), "{}", stack);
+    assert_eq!(top["line"], 6, "{}", stack);
+
+    let depth = tools_handler
+        .handle_tool(
+            "debugger_evaluate",
+            json!({"sessionId": session_id, "expression": "depth", "frameId": top["id"]}),
+        )
+        .await
+        .expect("evaluate failed");
+    assert_eq!(depth["result"], "21", "{}", depth);
+
+    let resumed = tools_handler
+        .handle_tool("debugger_continue", json!({"sessionId": session_id}))
+        .await;
+    assert!(
+        matches!(resumed, Err(Error::InvalidState(_))),
+        "{:?}",
+        resumed
+    );
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
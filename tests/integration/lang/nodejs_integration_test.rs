@@ -7,14 +7,13 @@ use std::path::PathBuf;
 use std::process::Command;
 use std::sync::Arc;
 use tempfile::TempDir;
-use tokio::sync::RwLock;
 
 /// Test Node.js language detection
 #[tokio::test]
 #[ignore]
 async fn test_nodejs_language_detection() {
-    let manager = Arc::new(RwLock::new(SessionManager::new()));
-    let session_manager = manager.read().await;
+    let manager = Arc::new(SessionManager::new());
+    let session_manager = manager;
 
     // Try to create a Node.js debug session
     let result = session_manager
@@ -38,8 +37,8 @@ async fn test_nodejs_language_detection() {
 #[tokio::test]
 #[ignore]
 async fn test_nodejs_adapter_spawning() {
-    let manager = Arc::new(RwLock::new(SessionManager::new()));
-    let session_manager = manager.read().await;
+    let manager = Arc::new(SessionManager::new());
+    let session_manager = manager;
 
     // Create a Node.js debug session
     let session_id = session_manager
@@ -75,7 +74,7 @@ async fn test_nodejs_fizzbuzz_debugging_integration() {
     // Wrap entire test in timeout
     let test_result = timeout(Duration::from_secs(30), async {
         // Setup
-        let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+        let session_manager = Arc::new(SessionManager::new());
         let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
 
         // Get absolute path to fizzbuzz.js
@@ -291,7 +290,7 @@ async fn test_nodejs_fizzbuzz_debugging_integration() {
             println!("⚠️  Disconnect may have issues or timed out");
         }
 
-        let manager = session_manager.read().await;
+        let manager = session_manager;
         let sessions = manager.list_sessions().await;
 
         if !sessions.contains(&session_id) {
@@ -317,6 +316,453 @@ async fn test_nodejs_fizzbuzz_debugging_integration() {
     }
 }
 
+/// vscode-js-debug reports column positions through breakpointLocations, and a
+/// column breakpoint at one of them verifies
+#[tokio::test]
+#[ignore]
+async fn test_nodejs_breakpoint_locations_and_column_breakpoint() {
+    use tokio::time::{timeout, Duration};
+
+    let node_check = Command::new("node").arg("--version").output();
+    if node_check.is_err() || !node_check.unwrap().status.success() {
+        println!("⚠️  Skipping breakpoint locations test: node not installed");
+        return;
+    }
+    if !PathBuf::from("/tmp/js-debug/src/dapDebugServer.js").exists() {
+        println!("⚠️  Skipping breakpoint locations test: js-debug not installed at /tmp/js-debug");
+        return;
+    }
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let fizzbuzz_path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("fizzbuzz.js");
+    let fizzbuzz_str = fizzbuzz_path.to_string_lossy().to_string();
+
+    let start_response = timeout(
+        Duration::from_secs(30),
+        tools_handler.handle_tool(
+            "debugger_start",
+            json!({"language": "nodejs", "program": fizzbuzz_str, "stopOnEntry": true}),
+        ),
+    )
+    .await
+    .expect("debugger_start timed out")
+    .expect("debugger_start failed");
+    let session_id = start_response["sessionId"].as_str().unwrap().to_string();
+
+    timeout(
+        Duration::from_secs(15),
+        tools_handler.handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 10000}),
+        ),
+    )
+    .await
+    .expect("wait_for_stop timed out")
+    .expect("entry stop not reached");
+
+    let locations = tools_handler
+        .handle_tool(
+            "debugger_breakpoint_locations",
+            json!({"sessionId": session_id, "sourcePath": fizzbuzz_str, "line": 1, "endLine": 10}),
+        )
+        .await
+        .expect("breakpoint_locations failed");
+    let locations = locations["locations"].as_array().unwrap().clone();
+    assert!(!locations.is_empty(), "js-debug reported no locations");
+
+    let target = &locations[0];
+    let bp = tools_handler
+        .handle_tool(
+            "debugger_set_breakpoint",
+            json!({
+                "sessionId": session_id,
+                "sourcePath": fizzbuzz_str,
+                "line": target["line"],
+                "column": target["column"].as_i64().unwrap_or(1)
+            }),
+        )
+        .await
+        .expect("column breakpoint failed");
+    assert_eq!(
+        bp["verified"], true,
+        "column breakpoint not verified: {}",
+        bp
+    );
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
+
+/// Breakpoints reach processes forked by the program: both the parent's and
+/// the child's breakpoint are hit, each reported by its own child session
+#[tokio::test]
+#[ignore]
+async fn test_nodejs_breakpoints_in_forked_child_process() {
+    use tokio::time::{timeout, Duration};
+
+    let node_check = Command::new("node").arg("--version").output();
+    if node_check.is_err() || !node_check.unwrap().status.success() {
+        println!("⚠️  Skipping forked child test: node not installed");
+        return;
+    }
+    if !PathBuf::from("/tmp/js-debug/src/dapDebugServer.js").exists() {
+        println!("⚠️  Skipping forked child test: js-debug not installed at /tmp/js-debug");
+        return;
+    }
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let fixtures = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures");
+    let parent_str = fixtures
+        .join("fork_parent.js")
+        .to_string_lossy()
+        .to_string();
+    let child_str = fixtures.join("fork_child.js").to_string_lossy().to_string();
+
+    let start_response = timeout(
+        Duration::from_secs(30),
+        tools_handler.handle_tool(
+            "debugger_start",
+            json!({"language": "nodejs", "program": parent_str, "stopOnEntry": true}),
+        ),
+    )
+    .await
+    .expect("debugger_start timed out")
+    .expect("debugger_start failed");
+    let session_id = start_response["sessionId"].as_str().unwrap().to_string();
+
+    let wait_for_stop = || async {
+        timeout(
+            Duration::from_secs(15),
+            tools_handler.handle_tool(
+                "debugger_wait_for_stop",
+                json!({"sessionId": session_id, "timeoutMs": 10000}),
+            ),
+        )
+        .await
+        .expect("wait_for_stop timed out")
+    };
+    wait_for_stop().await.expect("entry stop not reached");
+
+    for (source, line) in [(&parent_str, 7), (&child_str, 3)] {
+        tools_handler
+            .handle_tool(
+                "debugger_set_breakpoint",
+                json!({"sessionId": session_id, "sourcePath": source, "line": line}),
+            )
+            .await
+            .expect("set_breakpoint failed");
+    }
+
+    // The child's breakpoint is hit first, then the parent's
+    let mut hit = Vec::new();
+    for _ in 0..4 {
+        tools_handler
+            .handle_tool("debugger_continue", json!({"sessionId": session_id}))
+            .await
+            .expect("continue failed");
+        if wait_for_stop().await.is_err() {
+            break;
+        }
+        let stack = tools_handler
+            .handle_tool("debugger_stack_trace", json!({"sessionId": session_id}))
+            .await
+            .expect("stack trace failed");
+        let top = &stack["stackFrames"][0];
+        println!("   Stopped at {}", top);
+        hit.push((
+            top["source"]["path"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            top["line"].as_i64().unwrap_or_default(),
+        ));
+        if hit.iter().any(|(path, _)| path == &parent_str)
+            && hit.iter().any(|(path, _)| path == &child_str)
+        {
+            break;
+        }
+    }
+
+    assert!(
+        hit.contains(&(child_str.clone(), 3)),
+        "child breakpoint not hit: {:?}",
+        hit
+    );
+    assert!(
+        hit.contains(&(parent_str.clone(), 7)),
+        "parent breakpoint not hit: {:?}",
+        hit
+    );
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
+
+/// A worker thread ending does not end the session: the main thread's
+/// breakpoint, hit after the worker exited, is still reported
+#[tokio::test]
+#[ignore]
+async fn test_nodejs_worker_exit_keeps_session_alive() {
+    use tokio::time::{timeout, Duration};
+
+    let node_check = Command::new("node").arg("--version").output();
+    if node_check.is_err() || !node_check.unwrap().status.success() {
+        println!("⚠️  Skipping worker exit test: node not installed");
+        return;
+    }
+    if !PathBuf::from("/tmp/js-debug/src/dapDebugServer.js").exists() {
+        println!("⚠️  Skipping worker exit test: js-debug not installed at /tmp/js-debug");
+        return;
+    }
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let program = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("worker_main.js");
+    let program_str = program.to_string_lossy().to_string();
+
+    let start_response = timeout(
+        Duration::from_secs(30),
+        tools_handler.handle_tool(
+            "debugger_start",
+            json!({"language": "nodejs", "program": program_str, "stopOnEntry": true}),
+        ),
+    )
+    .await
+    .expect("debugger_start timed out")
+    .expect("debugger_start failed");
+    let session_id = start_response["sessionId"].as_str().unwrap().to_string();
+
+    let wait_for_stop = || async {
+        timeout(
+            Duration::from_secs(15),
+            tools_handler.handle_tool(
+                "debugger_wait_for_stop",
+                json!({"sessionId": session_id, "timeoutMs": 10000}),
+            ),
+        )
+        .await
+        .expect("wait_for_stop timed out")
+    };
+    wait_for_stop().await.expect("entry stop not reached");
+
+    tools_handler
+        .handle_tool(
+            "debugger_set_breakpoint",
+            json!({"sessionId": session_id, "sourcePath": program_str, "line": 11}),
+        )
+        .await
+        .expect("set_breakpoint failed");
+    tools_handler
+        .handle_tool("debugger_continue", json!({"sessionId": session_id}))
+        .await
+        .expect("continue failed");
+
+    // The worker exits 500ms before the breakpoint is reached
+    wait_for_stop()
+        .await
+        .expect("session ended with the worker instead of stopping");
+    let stack = tools_handler
+        .handle_tool("debugger_stack_trace", json!({"sessionId": session_id}))
+        .await
+        .expect("stack trace failed");
+    assert_eq!(stack["stackFrames"][0]["line"], 11, "{}", stack);
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
+
+/// A breakpoint on a TypeScript source binds through the compiled output's
+/// source maps, and the stack frame reports the .ts file
+#[tokio::test]
+#[ignore]
+async fn test_typescript_breakpoint_with_source_maps() {
+    use tokio::time::{timeout, Duration};
+
+    let node_check = Command::new("node").arg("--version").output();
+    if node_check.is_err() || !node_check.unwrap().status.success() {
+        println!("⚠️  Skipping TypeScript test: node not installed");
+        return;
+    }
+    if !PathBuf::from("/tmp/js-debug/src/dapDebugServer.js").exists() {
+        println!("⚠️  Skipping TypeScript test: js-debug not installed at /tmp/js-debug");
+        return;
+    }
+    let tsc_check = Command::new("tsc").arg("--version").output();
+    if tsc_check.is_err() || !tsc_check.unwrap().status.success() {
+        println!("⚠️  Skipping TypeScript test: tsc not installed (npm install -g typescript)");
+        return;
+    }
+
+    // Build a copy of the fixture project: src/greeter.ts → dist/greeter.js(.map)
+    let fixture = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("typescript");
+    let project = tempfile::tempdir().unwrap();
+    std::fs::create_dir(project.path().join("src")).unwrap();
+    for file in ["tsconfig.json", "src/greeter.ts"] {
+        std::fs::copy(fixture.join(file), project.path().join(file)).unwrap();
+    }
+    let build = Command::new("tsc")
+        .arg("-p")
+        .arg(project.path())
+        .output()
+        .unwrap();
+    assert!(
+        build.status.success(),
+        "tsc failed: {}",
+        String::from_utf8_lossy(&build.stdout)
+    );
+
+    let root = project.path().canonicalize().unwrap();
+    let source = root
+        .join("src")
+        .join("greeter.ts")
+        .to_string_lossy()
+        .to_string();
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let start_response = timeout(
+        Duration::from_secs(30),
+        tools_handler.handle_tool(
+            "debugger_start",
+            json!({
+                "language": "nodejs",
+                "program": source,
+                "cwd": root.to_string_lossy(),
+                "stopOnEntry": true,
+                "breakpoints": [{"sourcePath": source, "line": 10}]
+            }),
+        ),
+    )
+    .await
+    .expect("debugger_start timed out")
+    .expect("debugger_start failed");
+    let session_id = start_response["sessionId"].as_str().unwrap().to_string();
+
+    let wait_for_stop = || async {
+        timeout(
+            Duration::from_secs(15),
+            tools_handler.handle_tool(
+                "debugger_wait_for_stop",
+                json!({"sessionId": session_id, "timeoutMs": 10000}),
+            ),
+        )
+        .await
+        .expect("wait_for_stop timed out")
+    };
+    wait_for_stop().await.expect("entry stop not reached");
+    tools_handler
+        .handle_tool("debugger_continue", json!({"sessionId": session_id}))
+        .await
+        .expect("continue failed");
+    wait_for_stop()
+        .await
+        .expect("TypeScript breakpoint not hit");
+
+    let stack = tools_handler
+        .handle_tool("debugger_stack_trace", json!({"sessionId": session_id}))
+        .await
+        .expect("stack trace failed");
+    let top = &stack["stackFrames"][0];
+    assert_eq!(top["source"]["path"], source, "{}", top);
+    assert_eq!(top["line"], 10, "{}", top);
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
+
+/// `npmScript` runs the package's script through npm, and breakpoints bind in
+/// the node process the script starts (a grandchild: npm → node)
+#[tokio::test]
+#[ignore]
+async fn test_npm_script_breakpoint_in_started_process() {
+    use tokio::time::{timeout, Duration};
+
+    let node_check = Command::new("node").arg("--version").output();
+    if node_check.is_err() || !node_check.unwrap().status.success() {
+        println!("⚠️  Skipping npm script test: node not installed");
+        return;
+    }
+    let npm_check = Command::new("npm").arg("--version").output();
+    if npm_check.is_err() || !npm_check.unwrap().status.success() {
+        println!("⚠️  Skipping npm script test: npm not installed");
+        return;
+    }
+    if !PathBuf::from("/tmp/js-debug/src/dapDebugServer.js").exists() {
+        println!("⚠️  Skipping npm script test: js-debug not installed at /tmp/js-debug");
+        return;
+    }
+
+    let package = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("npm_app");
+    let server = package.join("server.js").to_string_lossy().to_string();
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let start_response = timeout(
+        Duration::from_secs(30),
+        tools_handler.handle_tool(
+            "debugger_start",
+            json!({
+                "language": "nodejs",
+                "program": package.join("package.json").to_string_lossy(),
+                "npmScript": "start",
+                "breakpoints": [{"sourcePath": server, "line": 5}]
+            }),
+        ),
+    )
+    .await
+    .expect("debugger_start timed out")
+    .expect("debugger_start failed");
+    let session_id = start_response["sessionId"].as_str().unwrap().to_string();
+
+    timeout(
+        Duration::from_secs(30),
+        tools_handler.handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 25000}),
+        ),
+    )
+    .await
+    .expect("wait_for_stop timed out")
+    .expect("breakpoint in the npm script's process not hit");
+
+    let stack = tools_handler
+        .handle_tool("debugger_stack_trace", json!({"sessionId": session_id}))
+        .await
+        .expect("stack trace failed");
+    let top = &stack["stackFrames"][0];
+    assert_eq!(top["source"]["path"], server, "{}", top);
+    assert_eq!(top["line"], 5, "{}", top);
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
+
 /// Test that validates Node.js MCP server works with Claude Code CLI
 #[tokio::test]
 #[ignore]
@@ -436,3 +882,188 @@ Create mcp_protocol_log.md documenting all interactions."#,
 
     println!("\n🎉 Node.js Claude Code integration test completed!");
 }
+
+/// A breakpoint in a module that is required later binds once it is loaded,
+/// and debugger_list_breakpoints reports it as verified from then on
+#[tokio::test]
+#[ignore]
+async fn test_nodejs_breakpoint_in_lazily_required_module_becomes_verified() {
+    use tokio::time::{timeout, Duration};
+
+    let node_check = Command::new("node").arg("--version").output();
+    if node_check.is_err() || !node_check.unwrap().status.success() {
+        println!("⚠️  Skipping lazy module test: node not installed");
+        return;
+    }
+    if !PathBuf::from("/tmp/js-debug/src/dapDebugServer.js").exists() {
+        println!("⚠️  Skipping lazy module test: js-debug not installed at /tmp/js-debug");
+        return;
+    }
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let fixtures = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures");
+    let main_str = fixtures.join("lazy_main.js").to_string_lossy().to_string();
+    let module_str = fixtures
+        .join("lazy_module.js")
+        .to_string_lossy()
+        .to_string();
+
+    let start_response = timeout(
+        Duration::from_secs(30),
+        tools_handler.handle_tool(
+            "debugger_start",
+            json!({"language": "nodejs", "program": main_str, "stopOnEntry": true}),
+        ),
+    )
+    .await
+    .expect("debugger_start timed out")
+    .expect("debugger_start failed");
+    let session_id = start_response["sessionId"].as_str().unwrap().to_string();
+
+    timeout(
+        Duration::from_secs(15),
+        tools_handler.handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 10000}),
+        ),
+    )
+    .await
+    .expect("wait_for_stop timed out")
+    .expect("entry stop not reached");
+
+    // lazy_module.js is not loaded yet
+    let bp = tools_handler
+        .handle_tool(
+            "debugger_set_breakpoint",
+            json!({"sessionId": session_id, "sourcePath": module_str, "line": 3}),
+        )
+        .await
+        .expect("set_breakpoint failed");
+    println!("Breakpoint before the module is loaded: {}", bp);
+
+    let stop = tools_handler
+        .handle_tool(
+            "debugger_continue",
+            json!({"sessionId": session_id, "waitForStop": true, "timeoutMs": 10000}),
+        )
+        .await
+        .expect("breakpoint in the lazily required module was not hit");
+    assert_eq!(stop["state"], "Stopped");
+    assert_eq!(stop["topFrame"]["line"], 3, "stopped at {}", stop);
+
+    let listed = tools_handler
+        .handle_tool(
+            "debugger_list_breakpoints",
+            json!({"sessionId": session_id}),
+        )
+        .await
+        .unwrap();
+    let module_bp = listed["breakpoints"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|bp| bp["sourcePath"] == module_str.as_str())
+        .expect("module breakpoint is listed");
+    assert_eq!(module_bp["verified"], true, "{}", module_bp);
+    assert!(module_bp.get("message").is_none(), "{}", module_bp);
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
+
+/// On a line with several calls, debugger_step_in_targets lists them and
+/// debugger_step_into with a targetId enters the chosen one (the second)
+#[tokio::test]
+#[ignore]
+async fn test_nodejs_step_into_second_call_on_line() {
+    use tokio::time::{timeout, Duration};
+
+    let node_check = Command::new("node").arg("--version").output();
+    if node_check.is_err() || !node_check.unwrap().status.success() {
+        println!("⚠️  Skipping step in targets test: node not installed");
+        return;
+    }
+    if !PathBuf::from("/tmp/js-debug/src/dapDebugServer.js").exists() {
+        println!("⚠️  Skipping step in targets test: js-debug not installed at /tmp/js-debug");
+        return;
+    }
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let program = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("step_targets.js")
+        .to_string_lossy()
+        .to_string();
+
+    let start_response = timeout(
+        Duration::from_secs(30),
+        tools_handler.handle_tool(
+            "debugger_start",
+            json!({"language": "nodejs", "program": program, "stopOnEntry": true}),
+        ),
+    )
+    .await
+    .expect("debugger_start timed out")
+    .expect("debugger_start failed");
+    let session_id = start_response["sessionId"].as_str().unwrap().to_string();
+
+    timeout(
+        Duration::from_secs(15),
+        tools_handler.handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 10000}),
+        ),
+    )
+    .await
+    .expect("wait_for_stop timed out")
+    .expect("entry stop not reached");
+
+    tools_handler
+        .handle_tool(
+            "debugger_set_breakpoint",
+            json!({"sessionId": session_id, "sourcePath": program, "line": 14}),
+        )
+        .await
+        .expect("set_breakpoint failed");
+    let stop = tools_handler
+        .handle_tool(
+            "debugger_continue",
+            json!({"sessionId": session_id, "waitForStop": true, "timeoutMs": 10000}),
+        )
+        .await
+        .expect("breakpoint on the call line was not hit");
+    assert_eq!(stop["topFrame"]["line"], 14, "stopped at {}", stop);
+
+    let targets = tools_handler
+        .handle_tool("debugger_step_in_targets", json!({"sessionId": session_id}))
+        .await
+        .expect("step_in_targets failed");
+    let baz = targets["targets"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|target| target["label"].as_str().unwrap_or("").contains("baz"))
+        .unwrap_or_else(|| panic!("baz() is not a target: {}", targets))
+        .clone();
+
+    let stepped = tools_handler
+        .handle_tool(
+            "debugger_step_into",
+            json!({"sessionId": session_id, "targetId": baz["id"], "waitForStop": true}),
+        )
+        .await
+        .expect("step_into failed");
+    assert_eq!(stepped["topFrame"]["name"], "baz", "stepped to {}", stepped);
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
@@ -8,7 +8,6 @@ use std::path::PathBuf;
 use std::process::Command;
 use std::sync::Arc;
 use tempfile::TempDir;
-use tokio::sync::RwLock;
 
 #[path = "../../helpers/mod.rs"]
 mod helpers;
@@ -63,7 +62,7 @@ async fn test_fizzbuzz_debugging_integration() {
     // Wrap entire test in timeout
     let test_result = timeout(Duration::from_secs(30), async {
         // Setup
-        let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+        let session_manager = Arc::new(SessionManager::new());
         let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
 
         // Get absolute path to fizzbuzz.py
@@ -304,7 +303,7 @@ async fn test_fizzbuzz_debugging_integration() {
         }
 
         // Verify session is removed
-        let manager = session_manager.read().await;
+        let manager = session_manager;
         let sessions = manager.list_sessions().await;
 
         if !sessions.contains(&session_id) {
@@ -389,7 +388,7 @@ async fn test_fizzbuzz_debugging_integration() {
 /// Test resource queries without active sessions
 #[tokio::test]
 async fn test_resources_empty_state() {
-    let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+    let session_manager = Arc::new(SessionManager::new());
     let resources_handler = ResourcesHandler::new(session_manager);
 
     // List resources
@@ -414,13 +413,13 @@ async fn test_resources_empty_state() {
 /// Test tools/list functionality
 #[tokio::test]
 async fn test_tools_list() {
-    let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+    let session_manager = Arc::new(SessionManager::new());
     let _tools_handler = ToolsHandler::new(session_manager);
 
     // This calls the static method directly
     let tools = ToolsHandler::list_tools();
 
-    assert_eq!(tools.len(), 12);
+    assert_eq!(tools.len(), 44);
 
     // Verify all tools are present
     let tool_names: Vec<&str> = tools.iter().filter_map(|t| t["name"].as_str()).collect();
@@ -552,3 +551,1477 @@ Create mcp_protocol_log.md documenting all interactions."#,
 
     println!("\n🎉 Python Claude Code integration test completed!");
 }
+
+/// Logpoints trace values through the fizzbuzz loop without ever stopping
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn test_python_logpoint_logs_without_stopping() {
+    use tokio::time::{timeout, Duration};
+
+    let debugpy_available = Command::new("python3")
+        .args(["-c", "import debugpy"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if !debugpy_available {
+        println!("⚠️  Skipping: debugpy not installed");
+        return;
+    }
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let fizzbuzz_path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("fizzbuzz.py")
+        .to_string_lossy()
+        .to_string();
+
+    // The logpoint goes in before the program starts, so no stop is needed
+    let start = tools_handler
+        .handle_tool(
+            "debugger_start",
+            json!({
+                "language": "python",
+                "program": fizzbuzz_path,
+                "breakpoints": [
+                    {"sourcePath": fizzbuzz_path, "line": 18, "logMessage": "n={n}"}
+                ]
+            }),
+        )
+        .await
+        .expect("debugger_start should succeed");
+    let session_id = start["sessionId"].as_str().unwrap().to_string();
+
+    // The program must run to completion without stopping at the logpoint
+    let wait = timeout(
+        Duration::from_secs(20),
+        tools_handler.handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 15000}),
+        ),
+    )
+    .await
+    .expect("wait_for_stop should not hang")
+    .expect("wait_for_stop should succeed");
+    assert_eq!(wait["state"], "Terminated", "Logpoint must not stop");
+
+    let output = tools_handler
+        .handle_tool("debugger_get_output", json!({"sessionId": session_id}))
+        .await
+        .unwrap();
+    let text: String = output["output"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|entry| entry["output"].as_str())
+        .collect();
+    assert!(text.contains("n=1"), "Missing logpoint output: {}", text);
+    assert!(text.contains("n=100"), "Missing logpoint output: {}", text);
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
+
+/// Function breakpoints stop on entry to a function given only its name
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn test_python_function_breakpoint_stops_in_fizzbuzz() {
+    let debugpy_available = Command::new("python3")
+        .args(["-c", "import debugpy"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if !debugpy_available {
+        println!("⚠️  Skipping: debugpy not installed");
+        return;
+    }
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let fizzbuzz_path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("fizzbuzz.py")
+        .to_string_lossy()
+        .to_string();
+
+    let start = tools_handler
+        .handle_tool(
+            "debugger_start",
+            json!({"language": "python", "program": fizzbuzz_path, "stopOnEntry": true}),
+        )
+        .await
+        .expect("debugger_start should succeed");
+    let session_id = start["sessionId"].as_str().unwrap().to_string();
+
+    tools_handler
+        .handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 10000}),
+        )
+        .await
+        .expect("should stop at entry");
+
+    let set = tools_handler
+        .handle_tool(
+            "debugger_set_function_breakpoints",
+            json!({"sessionId": session_id, "breakpoints": [{"name": "fizzbuzz"}]}),
+        )
+        .await
+        .expect("debugger_set_function_breakpoints should succeed");
+    assert_eq!(set["breakpoints"][0]["verified"], true);
+
+    let listed = tools_handler
+        .handle_tool(
+            "debugger_list_breakpoints",
+            json!({"sessionId": session_id}),
+        )
+        .await
+        .unwrap();
+    assert!(listed["breakpoints"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|bp| bp["type"] == "function" && bp["name"] == "fizzbuzz"));
+
+    tools_handler
+        .handle_tool("debugger_continue", json!({"sessionId": session_id}))
+        .await
+        .unwrap();
+    let stop = tools_handler
+        .handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 10000}),
+        )
+        .await
+        .expect("should stop in fizzbuzz");
+    assert_eq!(stop["state"], "Stopped");
+
+    let trace = tools_handler
+        .handle_tool("debugger_stack_trace", json!({"sessionId": session_id}))
+        .await
+        .unwrap();
+    assert_eq!(trace["stackFrames"][0]["name"], "fizzbuzz");
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
+
+/// Assignments in the 'watch' context must not change program state
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn test_python_watch_context_does_not_mutate_state() {
+    let debugpy_available = Command::new("python3")
+        .args(["-c", "import debugpy"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if !debugpy_available {
+        println!("⚠️  Skipping: debugpy not installed");
+        return;
+    }
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let fizzbuzz_path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("fizzbuzz.py")
+        .to_string_lossy()
+        .to_string();
+
+    let start = tools_handler
+        .handle_tool(
+            "debugger_start",
+            json!({
+                "language": "python",
+                "program": fizzbuzz_path,
+                "breakpoints": [{"sourcePath": fizzbuzz_path, "line": 18}]
+            }),
+        )
+        .await
+        .expect("debugger_start should succeed");
+    let session_id = start["sessionId"].as_str().unwrap().to_string();
+
+    tools_handler
+        .handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 10000}),
+        )
+        .await
+        .expect("should stop in fizzbuzz");
+
+    let before = tools_handler
+        .handle_tool(
+            "debugger_evaluate",
+            json!({"sessionId": session_id, "expression": "n"}),
+        )
+        .await
+        .unwrap();
+
+    // debugpy rejects statements outside the repl context
+    let _ = tools_handler
+        .handle_tool(
+            "debugger_evaluate",
+            json!({"sessionId": session_id, "expression": "n = 5", "context": "watch"}),
+        )
+        .await;
+
+    let after = tools_handler
+        .handle_tool(
+            "debugger_evaluate",
+            json!({"sessionId": session_id, "expression": "n"}),
+        )
+        .await
+        .unwrap();
+    assert_eq!(before["result"], after["result"]);
+    assert_ne!(after["result"], "5");
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
+
+/// Attach to a program started with `python -m debugpy --listen` and hit a breakpoint
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn test_python_attach_to_debugpy_listener() {
+    let debugpy_available = Command::new("python3")
+        .args(["-c", "import debugpy"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if !debugpy_available {
+        println!("⚠️  Skipping: debugpy not installed");
+        return;
+    }
+
+    let fizzbuzz_path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("fizzbuzz.py")
+        .to_string_lossy()
+        .to_string();
+
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let mut program = Command::new("python3")
+        .args([
+            "-m",
+            "debugpy",
+            "--listen",
+            &format!("127.0.0.1:{}", port),
+            "--wait-for-client",
+            &fizzbuzz_path,
+        ])
+        .spawn()
+        .expect("failed to start debugpy listener");
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let attach = tools_handler
+        .handle_tool(
+            "debugger_attach",
+            json!({
+                "language": "python",
+                "port": port,
+                "pathMappings": [{
+                    "localRoot": PathBuf::from(&fizzbuzz_path).parent().unwrap(),
+                    "remoteRoot": PathBuf::from(&fizzbuzz_path).parent().unwrap()
+                }],
+                "breakpoints": [{"sourcePath": fizzbuzz_path, "line": 18}]
+            }),
+        )
+        .await
+        .expect("debugger_attach should succeed");
+    let session_id = attach["sessionId"].as_str().unwrap().to_string();
+
+    let stop = tools_handler
+        .handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 10000}),
+        )
+        .await
+        .expect("should stop in fizzbuzz");
+    assert_eq!(stop["reason"], "breakpoint");
+
+    let trace = tools_handler
+        .handle_tool("debugger_stack_trace", json!({"sessionId": session_id}))
+        .await
+        .unwrap();
+    assert_eq!(trace["stackFrames"][0]["name"], "fizzbuzz");
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+    let _ = program.kill();
+    let _ = program.wait();
+}
+
+/// Breakpoints and stack frames are translated with pathMappings
+///
+/// A symlinked directory stands in for a container: the program runs from
+/// the link ("remote" root) while breakpoints use the real directory ("local").
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn test_python_path_mappings_with_symlinked_root() {
+    use debugger_mcp::debug::{InitialBreakpoint, PathMapper, PathMapping, SessionOptions};
+
+    let debugpy_available = Command::new("python3")
+        .args(["-c", "import debugpy"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if !debugpy_available {
+        println!("⚠️  Skipping: debugpy not installed");
+        return;
+    }
+
+    let dir = TempDir::new().unwrap();
+    let local_root = dir.path().canonicalize().unwrap().join("project");
+    let remote_root = dir.path().canonicalize().unwrap().join("container-app");
+    fs::create_dir(&local_root).unwrap();
+    std::os::unix::fs::symlink(&local_root, &remote_root).unwrap();
+    let fixture = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests/fixtures/fizzbuzz.py");
+    fs::copy(fixture, local_root.join("fizzbuzz.py")).unwrap();
+
+    let local_source = local_root.join("fizzbuzz.py").to_string_lossy().to_string();
+    let remote_program = remote_root
+        .join("fizzbuzz.py")
+        .to_string_lossy()
+        .to_string();
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let options = SessionOptions {
+        breakpoints: vec![InitialBreakpoint {
+            source_path: local_source.clone(),
+            line: 18,
+            condition: None,
+            log_message: None,
+        }],
+        path_mapper: PathMapper::new(
+            vec![PathMapping {
+                local: local_root.to_string_lossy().to_string(),
+                remote: remote_root.to_string_lossy().to_string(),
+            }],
+            true,
+        ),
+        ..SessionOptions::default()
+    };
+    let session_id = session_manager
+        .create_session_with_options("python", remote_program, vec![], None, false, options)
+        .await
+        .expect("session should start");
+
+    let stop = tools_handler
+        .handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 10000}),
+        )
+        .await
+        .expect("should stop at the mapped breakpoint");
+    assert_eq!(stop["reason"], "breakpoint");
+
+    let trace = tools_handler
+        .handle_tool("debugger_stack_trace", json!({"sessionId": session_id}))
+        .await
+        .unwrap();
+    assert_eq!(trace["stackFrames"][0]["source"]["path"], local_source);
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
+
+/// `module: "pytest"` runs a single test under debugpy; a breakpoint inside
+/// the test function stops there with the test's locals available
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn test_python_pytest_module_breakpoint_in_test() {
+    let available = Command::new("python3")
+        .args(["-c", "import debugpy, pytest"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if !available {
+        println!("⚠️  Skipping: debugpy or pytest not installed");
+        return;
+    }
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let project = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("pytest_project");
+    let test_file = project
+        .join("tests")
+        .join("test_calculator.py")
+        .to_string_lossy()
+        .to_string();
+
+    let start = tools_handler
+        .handle_tool(
+            "debugger_start",
+            json!({
+                "language": "python",
+                "module": "pytest",
+                "args": ["-x", "tests/test_calculator.py::test_average"],
+                "cwd": project.to_string_lossy(),
+                "breakpoints": [{"sourcePath": test_file, "line": 7}]
+            }),
+        )
+        .await
+        .expect("debugger_start should succeed");
+    let session_id = start["sessionId"].as_str().unwrap().to_string();
+
+    let stop = tools_handler
+        .handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 20000}),
+        )
+        .await
+        .expect("should stop in the test function");
+    assert_eq!(stop["state"], "Stopped", "{}", stop);
+
+    let stack = tools_handler
+        .handle_tool("debugger_stack_trace", json!({"sessionId": session_id}))
+        .await
+        .unwrap();
+    let top = &stack["stackFrames"][0];
+    assert_eq!(top["name"], "test_average", "{}", top);
+    assert_eq!(top["line"], 7, "{}", top);
+
+    let result = tools_handler
+        .handle_tool(
+            "debugger_evaluate",
+            json!({"sessionId": session_id, "expression": "result"}),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result["result"], "5.0");
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
+
+/// A Flask dev server with the autoreloader serves requests from a forked
+/// process; with `subProcess` the breakpoint in the view is hit there
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn test_python_flask_reloader_subprocess_breakpoint() {
+    use std::io::{Read, Write};
+    use tokio::time::{timeout, Duration};
+
+    let available = Command::new("python3")
+        .args(["-c", "import debugpy, flask"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if !available {
+        println!("⚠️  Skipping: debugpy or flask not installed");
+        return;
+    }
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let app = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("flask_app")
+        .join("app.py")
+        .to_string_lossy()
+        .to_string();
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let start = tools_handler
+        .handle_tool(
+            "debugger_start",
+            json!({
+                "language": "python",
+                "program": app,
+                "args": [port.to_string()],
+                "subProcess": true,
+                "breakpoints": [{"sourcePath": app, "line": 12}]
+            }),
+        )
+        .await
+        .expect("debugger_start should succeed");
+    let session_id = start["sessionId"].as_str().unwrap().to_string();
+
+    // The request blocks until the view returns, so it runs on its own thread
+    let request = std::thread::spawn(move || {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(20);
+        loop {
+            match std::net::TcpStream::connect(("127.0.0.1", port)) {
+                Ok(mut stream) => {
+                    stream
+                        .write_all(b"GET /greet/mcp HTTP/1.0\r\nHost: localhost\r\n\r\n")
+                        .unwrap();
+                    let mut response = String::new();
+                    let _ = stream.read_to_string(&mut response);
+                    return response;
+                }
+                Err(_) if std::time::Instant::now() < deadline => {
+                    std::thread::sleep(std::time::Duration::from_millis(200))
+                }
+                Err(e) => panic!("server did not start: {}", e),
+            }
+        }
+    });
+
+    let stop = timeout(
+        Duration::from_secs(30),
+        tools_handler.handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 25000}),
+        ),
+    )
+    .await
+    .expect("wait_for_stop timed out")
+    .expect("breakpoint in the view not hit");
+    assert_eq!(stop["state"], "Stopped", "{}", stop);
+
+    let stack = tools_handler
+        .handle_tool("debugger_stack_trace", json!({"sessionId": session_id}))
+        .await
+        .unwrap();
+    let top = &stack["stackFrames"][0];
+    assert_eq!(top["name"], "greet", "{}", top);
+    assert_eq!(top["line"], 12, "{}", top);
+
+    let message = tools_handler
+        .handle_tool(
+            "debugger_evaluate",
+            json!({"sessionId": session_id, "expression": "message"}),
+        )
+        .await
+        .unwrap();
+    assert_eq!(message["result"], "'Hello, mcp!'");
+
+    tools_handler
+        .handle_tool("debugger_continue", json!({"sessionId": session_id}))
+        .await
+        .expect("continue failed");
+    let response = request.join().unwrap();
+    assert!(response.contains("Hello, mcp!"), "{}", response);
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
+
+/// Ten steps through main's loop with waitForStop (one call per step) vs
+/// step + wait_for_stop + stack_trace (three calls per step)
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn test_python_step_loop_with_wait_for_stop() {
+    let debugpy_available = Command::new("python3")
+        .args(["-c", "import debugpy"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if !debugpy_available {
+        println!("⚠️  Skipping: debugpy not installed");
+        return;
+    }
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let fizzbuzz_path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("fizzbuzz.py")
+        .to_string_lossy()
+        .to_string();
+
+    let start = tools_handler
+        .handle_tool(
+            "debugger_start",
+            json!({
+                "language": "python",
+                "program": fizzbuzz_path,
+                "breakpoints": [{"sourcePath": fizzbuzz_path, "line": 32}]
+            }),
+        )
+        .await
+        .expect("debugger_start should succeed");
+    let session_id = start["sessionId"].as_str().unwrap().to_string();
+
+    let stop = tools_handler
+        .handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 10000}),
+        )
+        .await
+        .expect("should stop at the breakpoint");
+    assert_eq!(stop["reason"], "breakpoint");
+
+    const STEPS: usize = 10;
+
+    let started = std::time::Instant::now();
+    for _ in 0..STEPS {
+        tools_handler
+            .handle_tool("debugger_step_over", json!({"sessionId": session_id}))
+            .await
+            .unwrap();
+        tools_handler
+            .handle_tool(
+                "debugger_wait_for_stop",
+                json!({"sessionId": session_id, "timeoutMs": 5000}),
+            )
+            .await
+            .unwrap();
+        let trace = tools_handler
+            .handle_tool("debugger_stack_trace", json!({"sessionId": session_id}))
+            .await
+            .unwrap();
+        assert_eq!(trace["stackFrames"][0]["name"], "main");
+    }
+    let three_calls = started.elapsed();
+
+    let started = std::time::Instant::now();
+    for _ in 0..STEPS {
+        let stop = tools_handler
+            .handle_tool(
+                "debugger_step_over",
+                json!({"sessionId": session_id, "waitForStop": true}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(stop["state"], "Stopped");
+        assert_eq!(stop["reason"], "step");
+        let top = &stop["topFrame"];
+        assert_eq!(top["name"], "main");
+        assert!(top["source"].as_str().unwrap().ends_with("fizzbuzz.py"));
+        let line = top["line"].as_i64().unwrap();
+        assert!((31..=34).contains(&line), "stepped to line {}", line);
+    }
+    let one_call = started.elapsed();
+
+    println!(
+        "⏱️  {} steps: {:?} with step + wait + stack trace, {:?} with waitForStop",
+        STEPS, three_calls, one_call
+    );
+    assert!(
+        one_call < three_calls,
+        "waitForStop should be faster ({:?} vs {:?})",
+        one_call,
+        three_calls
+    );
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
+
+/// A 1000-level recursion is returned 50 frames at a time
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn test_python_stack_trace_pages_through_deep_recursion() {
+    let debugpy_available = Command::new("python3")
+        .args(["-c", "import debugpy"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if !debugpy_available {
+        println!("⚠️  Skipping: debugpy not installed");
+        return;
+    }
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let program = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("fizzbuzz_recursive.py")
+        .to_string_lossy()
+        .to_string();
+
+    let start = tools_handler
+        .handle_tool(
+            "debugger_start",
+            json!({
+                "language": "python",
+                "program": program,
+                "args": ["1000"],
+                "breakpoints": [{"sourcePath": program, "line": 13}]
+            }),
+        )
+        .await
+        .expect("debugger_start should succeed");
+    let session_id = start["sessionId"].as_str().unwrap().to_string();
+
+    tools_handler
+        .handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 20000}),
+        )
+        .await
+        .expect("should stop at the base case");
+
+    let first = tools_handler
+        .handle_tool("debugger_stack_trace", json!({"sessionId": session_id}))
+        .await
+        .unwrap();
+    assert_eq!(first["stackFrames"].as_array().unwrap().len(), 50);
+    assert_eq!(first["stackFrames"][0]["line"], 13);
+    assert!(first["note"].as_str().unwrap().contains("startFrame: 50"));
+    let total = first["totalFrames"]
+        .as_i64()
+        .expect("debugpy reports totalFrames");
+    // fizzbuzz_to(1000)..fizzbuzz_to(0), main and the module
+    assert!(total >= 1003, "totalFrames {}", total);
+
+    let mut start_frame = 0;
+    let mut seen = Vec::new();
+    loop {
+        let page = tools_handler
+            .handle_tool(
+                "debugger_stack_trace",
+                json!({"sessionId": session_id, "startFrame": start_frame, "levels": 50}),
+            )
+            .await
+            .unwrap();
+        let frames = page["stackFrames"].as_array().unwrap();
+        assert!(frames.len() <= 50);
+        seen.extend(frames.iter().map(|frame| frame["name"].clone()));
+        if page.get("note").is_none() {
+            break;
+        }
+        start_frame += frames.len() as i64;
+    }
+    assert_eq!(seen.len() as i64, total);
+    assert_eq!(
+        seen.iter().filter(|name| *name == "fizzbuzz_to").count(),
+        1001
+    );
+    assert!(seen.contains(&json!("main")));
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
+
+/// A 100000-item list is cut to maxLength and browsed through variable paging
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn test_python_huge_evaluate_result_is_truncated() {
+    let debugpy_available = Command::new("python3")
+        .args(["-c", "import debugpy"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if !debugpy_available {
+        println!("⚠️  Skipping: debugpy not installed");
+        return;
+    }
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let fizzbuzz_path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("fizzbuzz.py")
+        .to_string_lossy()
+        .to_string();
+
+    let start = tools_handler
+        .handle_tool(
+            "debugger_start",
+            json!({
+                "language": "python",
+                "program": fizzbuzz_path,
+                "breakpoints": [{"sourcePath": fizzbuzz_path, "line": 18}]
+            }),
+        )
+        .await
+        .expect("debugger_start should succeed");
+    let session_id = start["sessionId"].as_str().unwrap().to_string();
+
+    let stop = tools_handler
+        .handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 10000, "includeStackTrace": true, "levels": 1}),
+        )
+        .await
+        .expect("should stop at the breakpoint");
+    let frame_id = stop["stackTrace"][0]["id"].clone();
+
+    let evaluated = tools_handler
+        .handle_tool(
+            "debugger_evaluate",
+            json!({"sessionId": session_id, "expression": "list(range(100000))", "frameId": frame_id}),
+        )
+        .await
+        .unwrap();
+    assert_eq!(evaluated["truncated"], true);
+    assert!(evaluated["result"].as_str().unwrap().len() <= 4096);
+    assert!(evaluated["result"]
+        .as_str()
+        .unwrap()
+        .starts_with("[0, 1, 2"));
+    assert!(evaluated["originalLength"].as_u64().unwrap() > 4096);
+    let reference = evaluated["variablesReference"].as_i64().unwrap();
+    assert!(reference > 0);
+    assert!(evaluated["hint"]
+        .as_str()
+        .unwrap()
+        .contains("debugger_get_variables"));
+
+    let page = tools_handler
+        .handle_tool(
+            "debugger_get_variables",
+            json!({"sessionId": session_id, "variablesReference": reference, "start": 500, "count": 10}),
+        )
+        .await
+        .unwrap();
+    let children = page["variables"].as_array().unwrap();
+    assert_eq!(children.len(), 10);
+    assert!(page["note"].as_str().unwrap().contains("start: 510"));
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
+
+/// Completing "fi" inside main() offers the module-level fizzbuzz function
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn test_python_completions_in_main() {
+    let debugpy_available = Command::new("python3")
+        .args(["-c", "import debugpy"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if !debugpy_available {
+        println!("⚠️  Skipping: debugpy not installed");
+        return;
+    }
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let fizzbuzz_path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("fizzbuzz.py")
+        .to_string_lossy()
+        .to_string();
+
+    let start = tools_handler
+        .handle_tool(
+            "debugger_start",
+            json!({
+                "language": "python",
+                "program": fizzbuzz_path,
+                "breakpoints": [{"sourcePath": fizzbuzz_path, "line": 32}]
+            }),
+        )
+        .await
+        .expect("debugger_start should succeed");
+    let session_id = start["sessionId"].as_str().unwrap().to_string();
+
+    let stop = tools_handler
+        .handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 10000, "includeStackTrace": true, "levels": 1}),
+        )
+        .await
+        .expect("should stop at the breakpoint");
+    assert_eq!(stop["stackTrace"][0]["name"], "main");
+    let frame_id = stop["stackTrace"][0]["id"].clone();
+
+    let completions = tools_handler
+        .handle_tool(
+            "debugger_completions",
+            json!({"sessionId": session_id, "text": "fi", "frameId": frame_id}),
+        )
+        .await
+        .expect("debugpy supports completions");
+    let labels: Vec<&str> = completions["targets"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|target| target["label"].as_str())
+        .collect();
+    assert!(
+        labels.contains(&"fizzbuzz"),
+        "expected fizzbuzz among {:?}",
+        labels
+    );
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
+
+/// The main script is among the sources debugpy reports as loaded
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn test_python_loaded_sources_include_main_script() {
+    let debugpy_available = Command::new("python3")
+        .args(["-c", "import debugpy"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if !debugpy_available {
+        println!("⚠️  Skipping: debugpy not installed");
+        return;
+    }
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let fizzbuzz_path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("fizzbuzz.py")
+        .to_string_lossy()
+        .to_string();
+
+    let start = tools_handler
+        .handle_tool(
+            "debugger_start",
+            json!({
+                "language": "python",
+                "program": fizzbuzz_path,
+                "breakpoints": [{"sourcePath": fizzbuzz_path, "line": 18}]
+            }),
+        )
+        .await
+        .expect("debugger_start should succeed");
+    let session_id = start["sessionId"].as_str().unwrap().to_string();
+
+    tools_handler
+        .handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 10000}),
+        )
+        .await
+        .expect("should stop at the breakpoint");
+
+    let loaded = tools_handler
+        .handle_tool(
+            "debugger_loaded_sources",
+            json!({"sessionId": session_id, "filter": "fizzbuzz.py"}),
+        )
+        .await
+        .expect("debugger_loaded_sources should succeed");
+    let sources: Vec<&str> = loaded["sources"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|source| source.as_str())
+        .collect();
+    assert!(
+        sources.contains(&fizzbuzz_path.as_str()),
+        "expected {} among {:?}",
+        fizzbuzz_path,
+        sources
+    );
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
+
+/// In watch mode, saving the program restarts the session under the same ID
+/// with its breakpoints
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn test_python_watch_mode_restarts_on_save() {
+    let debugpy_available = Command::new("python3")
+        .args(["-c", "import debugpy"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if !debugpy_available {
+        println!("⚠️  Skipping: debugpy not installed");
+        return;
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let program = dir.path().join("fizzbuzz.py");
+    std::fs::copy(
+        PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+            .join("tests")
+            .join("fixtures")
+            .join("fizzbuzz.py"),
+        &program,
+    )
+    .unwrap();
+    let program = program.to_string_lossy().to_string();
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+    let mut changes = session_manager.subscribe_state_changes();
+
+    let start = tools_handler
+        .handle_tool(
+            "debugger_start",
+            json!({
+                "language": "python",
+                "program": program,
+                "breakpoints": [{"sourcePath": program, "line": 18}],
+                "watchMode": true
+            }),
+        )
+        .await
+        .expect("debugger_start should succeed");
+    let session_id = start["sessionId"].as_str().unwrap().to_string();
+    assert_eq!(start["watchPaths"], json!([program]));
+
+    tools_handler
+        .handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 10000}),
+        )
+        .await
+        .expect("should stop at the breakpoint");
+
+    // Save an edit (appending keeps line 18 where it is)
+    let mut content = std::fs::read_to_string(&program).unwrap();
+    content.push_str("\n# edited\n");
+    std::fs::write(&program, content).unwrap();
+
+    let restarted = tokio::time::timeout(std::time::Duration::from_secs(20), async {
+        loop {
+            let change = changes.recv().await.unwrap();
+            if change.session_id == session_id && change.reason.is_some() {
+                return change;
+            }
+        }
+    })
+    .await
+    .expect("the session should restart");
+    assert_eq!(restarted.reason.as_deref(), Some("restarted: file changed"));
+
+    // The breakpoint is applied again in the new run
+    let stop = tools_handler
+        .handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 10000}),
+        )
+        .await
+        .expect("should stop at the breakpoint after the restart");
+    assert_eq!(stop["topFrame"]["line"], 18);
+
+    let status = tools_handler
+        .handle_tool("debugger_watch_status", json!({"sessionId": session_id}))
+        .await
+        .unwrap();
+    assert_eq!(status["restarts"], 1);
+    assert_eq!(status["enabled"], true);
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
+
+/// Relative cwd, program and breakpoint paths resolve against each other, and
+/// responses carry the canonical paths
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn test_python_relative_paths_throughout() {
+    let debugpy_available = Command::new("python3")
+        .args(["-c", "import debugpy"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if !debugpy_available {
+        println!("⚠️  Skipping: debugpy not installed");
+        return;
+    }
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    // cargo runs tests from the package root
+    let fizzbuzz_path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("fizzbuzz.py")
+        .canonicalize()
+        .unwrap()
+        .to_string_lossy()
+        .to_string();
+
+    let start = tools_handler
+        .handle_tool(
+            "debugger_start",
+            json!({
+                "language": "python",
+                "program": "fizzbuzz.py",
+                "cwd": "tests/fixtures",
+                "breakpoints": [{"sourcePath": "fizzbuzz.py", "line": 18}]
+            }),
+        )
+        .await
+        .expect("debugger_start should succeed");
+    let session_id = start["sessionId"].as_str().unwrap().to_string();
+
+    let wait = tools_handler
+        .handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 15000}),
+        )
+        .await
+        .expect("wait_for_stop should succeed");
+    assert_eq!(wait["state"], "Stopped");
+
+    let stack = tools_handler
+        .handle_tool("debugger_stack_trace", json!({"sessionId": session_id}))
+        .await
+        .unwrap();
+    assert_eq!(stack["stackFrames"][0]["source"]["path"], fizzbuzz_path);
+
+    let breakpoint = tools_handler
+        .handle_tool(
+            "debugger_set_breakpoint",
+            json!({"sessionId": session_id, "sourcePath": "fizzbuzz.py", "line": 20}),
+        )
+        .await
+        .unwrap();
+    assert_eq!(breakpoint["sourcePath"], fizzbuzz_path);
+    assert_eq!(breakpoint["verified"], true);
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
+
+/// After changing a local, debugger_restart_frame runs fizzbuzz() again from
+/// its first line
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn test_python_restart_frame_reenters_function() {
+    let debugpy_available = Command::new("python3")
+        .args(["-c", "import debugpy"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if !debugpy_available {
+        println!("⚠️  Skipping: debugpy not installed");
+        return;
+    }
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let fizzbuzz_path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("fizzbuzz.py")
+        .to_string_lossy()
+        .to_string();
+
+    let start = tools_handler
+        .handle_tool(
+            "debugger_start",
+            json!({
+                "language": "python",
+                "program": fizzbuzz_path,
+                "breakpoints": [{"sourcePath": fizzbuzz_path, "line": 18}]
+            }),
+        )
+        .await
+        .expect("debugger_start should succeed");
+    let session_id = start["sessionId"].as_str().unwrap().to_string();
+
+    tools_handler
+        .handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 10000}),
+        )
+        .await
+        .expect("should stop at the breakpoint");
+
+    let evaluate = |expression: &str, context: &str| {
+        tools_handler.handle_tool(
+            "debugger_evaluate",
+            json!({"sessionId": session_id, "expression": expression, "frameIndex": 0, "context": context}),
+        )
+    };
+    evaluate("n = 15", "repl").await.expect("assignment failed");
+    let changed = evaluate("n", "watch").await.unwrap();
+    assert_eq!(changed["result"], "15");
+
+    let restarted = tools_handler
+        .handle_tool(
+            "debugger_restart_frame",
+            json!({"sessionId": session_id, "frameIndex": 0, "waitForStop": true, "timeoutMs": 10000}),
+        )
+        .await
+        .expect("debugpy supports restartFrame");
+    assert_eq!(restarted["state"], "Stopped", "{}", restarted);
+    assert_eq!(restarted["topFrame"]["name"], "fizzbuzz", "{}", restarted);
+    let line = restarted["topFrame"]["line"].as_i64().unwrap();
+    assert!(
+        line <= 18,
+        "expected the top of fizzbuzz(), stopped at line {}",
+        line
+    );
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
+
+/// Jumping over the line that raises lets risky() return normally
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn test_python_jump_over_raising_line() {
+    let debugpy_available = Command::new("python3")
+        .args(["-c", "import debugpy"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if !debugpy_available {
+        println!("⚠️  Skipping: debugpy not installed");
+        return;
+    }
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let program = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("jump_over.py")
+        .to_string_lossy()
+        .to_string();
+
+    let start = tools_handler
+        .handle_tool(
+            "debugger_start",
+            json!({
+                "language": "python",
+                "program": program,
+                "breakpoints": [{"sourcePath": program, "line": 8}]
+            }),
+        )
+        .await
+        .expect("debugger_start should succeed");
+    let session_id = start["sessionId"].as_str().unwrap().to_string();
+
+    tools_handler
+        .handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 10000}),
+        )
+        .await
+        .expect("should stop before the raise");
+
+    let jumped = tools_handler
+        .handle_tool(
+            "debugger_jump_to_line",
+            json!({"sessionId": session_id, "sourcePath": program, "line": 9}),
+        )
+        .await
+        .expect("debugpy supports goto");
+    assert_eq!(jumped["topFrame"]["line"], 9, "{}", jumped);
+
+    let wait = tools_handler
+        .handle_tool(
+            "debugger_continue",
+            json!({"sessionId": session_id, "waitForStop": true, "timeoutMs": 10000}),
+        )
+        .await
+        .expect("program should run to the end");
+    assert_eq!(wait["state"], "Terminated", "{}", wait);
+
+    let output = tools_handler
+        .handle_tool("debugger_get_output", json!({"sessionId": session_id}))
+        .await
+        .unwrap();
+    let text: String = output["output"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|entry| entry["output"].as_str())
+        .collect();
+    assert!(text.contains("result = 2"), "unexpected output: {}", text);
+    assert!(!text.contains("RuntimeError"), "the raise ran: {}", text);
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
+
+/// debugger_run_until_exit runs fizzbuzz through all 100 breakpoint hits,
+/// recording only the first few
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn test_python_run_until_exit_summarizes_fizzbuzz() {
+    let debugpy_available = Command::new("python3")
+        .args(["-c", "import debugpy"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if !debugpy_available {
+        println!("⚠️  Skipping: debugpy not installed");
+        return;
+    }
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let fizzbuzz_path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("fizzbuzz.py")
+        .to_string_lossy()
+        .to_string();
+
+    let summary = tools_handler
+        .handle_tool(
+            "debugger_run_until_exit",
+            json!({
+                "language": "python",
+                "program": fizzbuzz_path,
+                "breakpoints": [{"sourcePath": fizzbuzz_path, "line": 18}],
+                "includeLocals": true,
+                "maxStops": 3,
+                "timeoutMs": 120000
+            }),
+        )
+        .await
+        .expect("debugger_run_until_exit should succeed");
+
+    assert_eq!(summary["state"], "Terminated", "{}", summary);
+    assert_eq!(summary["timedOut"], false);
+    assert_eq!(summary["exitCode"], 0);
+    assert_eq!(summary["stopCount"], 100);
+    assert_eq!(summary["stopsTruncated"], true);
+
+    let stops = summary["stops"].as_array().unwrap();
+    assert_eq!(stops.len(), 3);
+    assert_eq!(stops[0]["topFrame"]["name"], "fizzbuzz");
+    assert_eq!(stops[0]["topFrame"]["line"], 18);
+    assert!(
+        stops[2]["topFrameVariables"].to_string().contains('n'),
+        "Missing locals: {}",
+        stops[2]
+    );
+
+    let text: String = summary["output"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|entry| entry["output"].as_str())
+        .collect();
+    assert!(text.contains("FizzBuzz"), "Missing output: {}", text);
+
+    assert!(session_manager.list_sessions().await.is_empty());
+}
+
+/// debugpy binds a breakpoint on a blank line to the next statement, and the
+/// response says where
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn test_python_breakpoint_on_blank_line_is_adjusted() {
+    let debugpy_available = Command::new("python3")
+        .args(["-c", "import debugpy"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if !debugpy_available {
+        println!("⚠️  Skipping: debugpy not installed");
+        return;
+    }
+
+    let session_manager = Arc::new(SessionManager::new());
+    let tools_handler = ToolsHandler::new(Arc::clone(&session_manager));
+
+    let fizzbuzz_path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("fixtures")
+        .join("fizzbuzz.py")
+        .to_string_lossy()
+        .to_string();
+
+    let start = tools_handler
+        .handle_tool(
+            "debugger_start",
+            json!({"language": "python", "program": fizzbuzz_path, "stopOnEntry": true}),
+        )
+        .await
+        .expect("debugger_start should succeed");
+    let session_id = start["sessionId"].as_str().unwrap().to_string();
+    let entry = tools_handler
+        .handle_tool(
+            "debugger_wait_for_stop",
+            json!({"sessionId": session_id, "timeoutMs": 15000}),
+        )
+        .await
+        .unwrap();
+    assert_eq!(entry["state"], "Stopped", "{}", entry);
+
+    // Line 35 is the blank line before `return results`
+    let breakpoint = tools_handler
+        .handle_tool(
+            "debugger_set_breakpoint",
+            json!({"sessionId": session_id, "sourcePath": fizzbuzz_path, "line": 35}),
+        )
+        .await
+        .unwrap();
+    assert_eq!(breakpoint["verified"], true, "{}", breakpoint);
+    assert_eq!(breakpoint["line"], 35);
+    assert_eq!(breakpoint["adjustedLine"], 36, "{}", breakpoint);
+
+    let past_end = tools_handler
+        .handle_tool(
+            "debugger_set_breakpoint",
+            json!({"sessionId": session_id, "sourcePath": fizzbuzz_path, "line": 500}),
+        )
+        .await
+        .unwrap_err();
+    assert!(
+        past_end.to_string().contains("past the end"),
+        "{}",
+        past_end
+    );
+
+    let stop = tools_handler
+        .handle_tool(
+            "debugger_continue",
+            json!({"sessionId": session_id, "waitForStop": true, "timeoutMs": 15000}),
+        )
+        .await
+        .unwrap();
+    assert_eq!(stop["state"], "Stopped", "{}", stop);
+    let frames = tools_handler
+        .handle_tool("debugger_stack_trace", json!({"sessionId": session_id}))
+        .await
+        .unwrap();
+    assert_eq!(frames["stackFrames"][0]["line"], 36);
+
+    let _ = tools_handler
+        .handle_tool("debugger_disconnect", json!({"sessionId": session_id}))
+        .await;
+}
@@ -15,7 +15,6 @@ use debugger_mcp::debug::manager::SessionManager;
 use debugger_mcp::mcp::tools::ToolsHandler;
 use serde_json::json;
 use std::sync::Arc;
-use tokio::sync::RwLock;
 
 /// Test that Rust adapter command points to CodeLLDB
 #[test]
@@ -132,7 +131,7 @@ async fn test_rust_compilation_error() {
 #[tokio::test]
 #[ignore] // Requires Docker with rustc and CodeLLDB installed
 async fn test_rust_session_creation() {
-    let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+    let session_manager = Arc::new(SessionManager::new());
     let tools_handler = ToolsHandler::new(session_manager);
 
     let args = json!({
@@ -162,7 +161,7 @@ async fn test_rust_session_creation() {
 #[tokio::test]
 #[ignore] // Requires Docker with rustc and CodeLLDB
 async fn test_rust_session_with_program_args() {
-    let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+    let session_manager = Arc::new(SessionManager::new());
     let tools_handler = ToolsHandler::new(session_manager);
 
     let args = json!({
@@ -193,7 +192,7 @@ async fn test_rust_session_with_program_args() {
 #[tokio::test]
 #[ignore] // Requires Docker with full debugging environment
 async fn test_rust_stack_trace_uses_correct_thread_id() {
-    let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+    let session_manager = Arc::new(SessionManager::new());
     let tools_handler = ToolsHandler::new(session_manager.clone());
 
     // Start debug session
@@ -336,7 +335,7 @@ async fn test_rust_stack_trace_uses_correct_thread_id() {
 #[tokio::test]
 #[ignore] // Requires Docker with full debugging environment
 async fn test_rust_evaluate_uses_watch_context() {
-    let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+    let session_manager = Arc::new(SessionManager::new());
     let tools_handler = ToolsHandler::new(session_manager.clone());
 
     // Start debug session
@@ -522,7 +521,7 @@ async fn test_rust_evaluate_uses_watch_context() {
 #[tokio::test]
 #[ignore] // Requires Docker with full debugging environment
 async fn test_rust_fizzbuzz_debugging_workflow() {
-    let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+    let session_manager = Arc::new(SessionManager::new());
     let tools_handler = ToolsHandler::new(session_manager.clone());
 
     // Step 1: Start debug session with stopOnEntry
@@ -870,6 +869,137 @@ async fn test_cargo_compile_example() {
     );
 }
 
+/// In a workspace, each member's main.rs builds and debugs its own binary
+#[tokio::test]
+async fn test_cargo_workspace_builds_owning_binary() {
+    use debugger_mcp::adapters::rust::RustAdapter;
+
+    let workspace =
+        std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/cargo-workspace");
+    let source = |relative: &str| workspace.join(relative).to_string_lossy().to_string();
+
+    for (main_file, binary_name) in [
+        ("alpha/src/main.rs", "alpha"),
+        ("beta/src/main.rs", "beta"),
+        ("beta/src/bin/beta-report.rs", "beta-report"),
+    ] {
+        let binary = RustAdapter::compile(&source(main_file), false)
+            .await
+            .unwrap_or_else(|e| panic!("{} failed to compile: {}", main_file, e));
+        assert!(
+            binary.ends_with(&format!("target/debug/{}", binary_name)),
+            "{} built {}",
+            main_file,
+            binary
+        );
+    }
+
+    // beta has two binaries, so one of its modules can't pick one
+    let result = RustAdapter::compile(&source("beta/src/format.rs"), false).await;
+    assert!(
+        matches!(result, Err(debugger_mcp::Error::InvalidRequest(ref msg)) if msg.contains("beta-report")),
+        "{:?}",
+        result
+    );
+}
+
+/// A second compile of an unchanged single file skips rustc
+#[tokio::test]
+async fn test_single_file_second_compile_is_fresh() {
+    use debugger_mcp::adapters::rust::RustAdapter;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let source = dir.path().join("hello.rs");
+    std::fs::write(&source, "fn main() { println!(\"hello\"); }\n").unwrap();
+    let source = source.to_string_lossy().to_string();
+
+    let first = RustAdapter::compile_with_options(&source, false, false)
+        .await
+        .expect("first compile failed");
+    assert!(!first.fresh);
+
+    let second = RustAdapter::compile_with_options(&source, false, false)
+        .await
+        .unwrap();
+    assert!(second.fresh);
+    assert_eq!(second.binary, first.binary);
+    assert!(
+        second.duration < std::time::Duration::from_millis(100),
+        "fresh check took {:?}",
+        second.duration
+    );
+
+    // forceRebuild always runs rustc
+    let forced = RustAdapter::compile_with_options(&source, false, true)
+        .await
+        .unwrap();
+    assert!(!forced.fresh);
+}
+
+/// Cargo reports an unchanged binary as fresh on the second build
+#[tokio::test]
+async fn test_cargo_second_compile_is_fresh() {
+    use debugger_mcp::adapters::rust::RustAdapter;
+
+    let main_rs = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/cargo-simple/src/main.rs")
+        .to_string_lossy()
+        .to_string();
+
+    let first = RustAdapter::compile_with_options(&main_rs, false, false)
+        .await
+        .expect("cargo build failed");
+    let second = RustAdapter::compile_with_options(&main_rs, false, false)
+        .await
+        .unwrap();
+
+    assert!(second.fresh, "second build rebuilt {}", second.binary);
+    assert_eq!(second.binary, first.binary);
+    assert!(second.to_json()["durationMs"].is_u64());
+}
+
+/// rustc errors come back as structured diagnostics through debugger_start
+#[tokio::test]
+async fn test_type_error_reported_as_diagnostics() {
+    use debugger_mcp::debug::SessionManager;
+    use debugger_mcp::mcp::tools::ToolsHandler;
+    use std::sync::Arc;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let source = dir.path().join("type_error.rs");
+    std::fs::copy(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/type_error.rs"),
+        &source,
+    )
+    .unwrap();
+    let source = source.canonicalize().unwrap().to_string_lossy().to_string();
+
+    let tools_handler = ToolsHandler::new(Arc::new(SessionManager::new()));
+    let error = tools_handler
+        .handle_tool(
+            "debugger_start",
+            serde_json::json!({"language": "rust", "program": source}),
+        )
+        .await
+        .expect_err("type error should fail compilation");
+
+    // The message leads with the first error's location
+    let message = error.to_string();
+    assert!(
+        message.contains(&format!("{}:8:26: mismatched types [E0308]", source)),
+        "{}",
+        message
+    );
+
+    let data = error.data();
+    assert_eq!(data["code"], "COMPILATION_FAILED");
+    let first = &data["diagnostics"][0];
+    assert_eq!(first["level"], "error");
+    assert_eq!(first["file"], source.as_str());
+    assert_eq!(first["line"], 8);
+    assert_eq!(first["code"], "E0308");
+}
+
 /// Test backward compatibility: single-file compilation still works
 #[tokio::test]
 #[ignore] // Requires Docker
@@ -30,7 +30,12 @@ fn test_ruby_adapter_logging_metadata() {
     // Verify metadata
     assert_eq!(adapter.language_name(), "Ruby");
     assert_eq!(adapter.language_emoji(), "💎");
-    assert_eq!(adapter.transport_type(), "TCP Socket");
+    let transport = if cfg!(unix) {
+        "Unix Socket"
+    } else {
+        "TCP Socket"
+    };
+    assert_eq!(adapter.transport_type(), transport);
     assert_eq!(adapter.adapter_id(), "rdbg");
     assert!(adapter.requires_workaround());
     assert_eq!(
@@ -42,7 +47,7 @@ fn test_ruby_adapter_logging_metadata() {
     let cmd = adapter.command_line();
     assert!(cmd.contains("rdbg"));
     assert!(cmd.contains("--open"));
-    assert!(cmd.contains("--port"));
+    assert!(cmd.contains(if cfg!(unix) { "--sock-path" } else { "--port" }));
 }
 
 #[test]
@@ -165,10 +170,10 @@ fn test_error_logging_methods_dont_panic() {
 /// ✅ [PYTHON] Adapter connected and ready
 ///
 /// 💎 [RUBY] Adapter selected: rdbg
-///    Transport: TCP Socket
-///    Command: rdbg --open --port <PORT> [--stop-at-load|--nonstop] <program> [args...]
+///    Transport: Unix Socket
+///    Command: rdbg --open --sock-path=<PATH> [--stop-at-load|--nonstop] <program> [args...]
 ///    Workaround: rdbg socket mode doesn't honor --stop-at-load flag
-/// 📡 [RUBY] Initializing TCP Socket transport
+/// 📡 [RUBY] Initializing Unix Socket transport
 /// 🚀 [RUBY] Spawning adapter process
 /// ✅ [RUBY] Connected to rdbg on port 12345
 /// 🔧 [RUBY] Applying workaround: rdbg socket mode doesn't honor --stop-at-load flag